@@ -0,0 +1,97 @@
+//! Base station stack as a library, so it can be embedded and driven by external code
+//! such as tests or user interfaces. The tetra-bluestation binary is a thin wrapper around it.
+
+use std::error::Error;
+
+use tetra_config::{PhyBackend, SharedConfig};
+use tetra_core::TdmaTime;
+use tetra_entities::{cmce::cmce_bs::CmceBs, llc::llc_bs_ms::Llc, lmac::lmac_bs::LmacBs, mle::mle_bs_ms::Mle, mm::mm_bs::MmBs, phy::{components::{loopback_dev::RxTxDevLoopback, recording_dev::RxTxDevRecording, soapy_dev::RxTxDevSoapySdr}, phy_bs::PhyBs}, sndcp::sndcp_bs::Sndcp, umac::umac_bs::UmacBs, uplane::uplane_bs::UplaneBs};
+use tetra_entities::MessageRouter;
+use tetra_pdus::phy::traits::rxtx_dev::RxTxDev;
+
+
+/// Registers the PHY on top of `rxdev`, recording its IQ samples if configured
+fn register_phy<D: RxTxDev + Send + 'static>(router: &mut MessageRouter, cfg: &SharedConfig, rxdev: D) -> Result<(), Box<dyn Error>> {
+    let recording = cfg.config().phy_io.recording.clone();
+    match recording {
+        Some(rec_cfg) => {
+            let rxdev = RxTxDevRecording::new(rxdev, &rec_cfg, cfg.config().phy_io.sample_format)
+                .map_err(|e| format!("Failed to set up recording to {}: {}", rec_cfg.file, e))?;
+            router.register_entity(Box::new(PhyBs::new(cfg.clone(), rxdev)));
+        }
+        None => router.register_entity(Box::new(PhyBs::new(cfg.clone(), rxdev))),
+    }
+    Ok(())
+}
+
+/// A complete base station stack, from the PHY up to MM, CMCE and SNDCP
+pub struct BsStack {
+    router: MessageRouter,
+}
+
+impl BsStack {
+    /// Builds all base station entities for `cfg`, using the PHY backend it selects.
+    /// Fails if the backend is not supported in BS mode, or the sample recording can't be set up.
+    pub fn from_config(cfg: SharedConfig) -> Result<Self, Box<dyn Error>> {
+
+        let mut router = MessageRouter::new(cfg.clone());
+
+        // Add suitable Phy component based on PhyIo type
+        let backend = cfg.config().phy_io.backend;
+        match backend {
+            PhyBackend::SoapySdr => {
+                let rxdev = RxTxDevSoapySdr::new(&cfg);
+                register_phy(&mut router, &cfg, rxdev)?;
+            }
+            PhyBackend::Loopback => {
+                let rxdev = RxTxDevLoopback::new(&cfg);
+                register_phy(&mut router, &cfg, rxdev)?;
+            }
+            _ => {
+                return Err(format!("Unsupported PhyIo type for BS mode: {:?}", backend).into());
+            }
+        }
+
+        // Add remaining components
+        let lmac = LmacBs::new(cfg.clone());
+        let umac = UmacBs::new(cfg.clone());
+        let llc = Llc::new(cfg.clone());
+        let mle = Mle::new(cfg.clone());
+        let mm = MmBs::new(cfg.clone());
+        let sndcp = Sndcp::new(cfg.clone());
+        let cmce = CmceBs::new(cfg.clone());
+        let uplane = UplaneBs::new(cfg.clone());
+        router.register_entity(Box::new(lmac));
+        router.register_entity(Box::new(umac));
+        router.register_entity(Box::new(llc));
+        router.register_entity(Box::new(mle));
+        router.register_entity(Box::new(mm));
+        router.register_entity(Box::new(sndcp));
+        router.register_entity(Box::new(cmce));
+        router.register_entity(Box::new(uplane));
+
+        // Init network time
+        router.set_dl_time(TdmaTime::default());
+
+        Ok(Self { router })
+    }
+
+    /// Runs the stack for a single timeslot
+    pub fn tick(&mut self) {
+        self.router.tick();
+    }
+
+    /// Runs the stack for `num_ticks` timeslots, or forever if None
+    pub fn run(&mut self, num_ticks: Option<usize>) {
+        self.router.run_stack(num_ticks);
+    }
+
+    pub fn router(&self) -> &MessageRouter {
+        &self.router
+    }
+
+    /// Gives access to the router, e.g. to register PDU observers or open control queries
+    pub fn router_mut(&mut self) -> &mut MessageRouter {
+        &mut self.router
+    }
+}
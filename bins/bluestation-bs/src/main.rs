@@ -1,9 +1,9 @@
 use clap::Parser;
 
-use tetra_config::{PhyBackend, SharedConfig, StackMode, toml_config};
-use tetra_core::{TdmaTime, debug};
-use tetra_entities::{cmce::cmce_bs::CmceBs, llc::llc_bs_ms::Llc, lmac::lmac_bs::LmacBs, mle::mle_bs_ms::Mle, mm::mm_bs::MmBs, phy::{components::soapy_dev::RxTxDevSoapySdr, phy_bs::PhyBs}, sndcp::sndcp_bs::Sndcp, umac::umac_bs::UmacBs};
-use tetra_entities::MessageRouter;
+use bluestation_bs::BsStack;
+use tetra_config::{SharedConfig, StackMode, example_config, toml_config};
+use tetra_core::debug;
+use tetra_pdus::selftest;
 
 
 /// Load configuration file
@@ -17,43 +17,18 @@ fn load_config_from_toml(cfg_path: &str) -> SharedConfig {
     }
 }
 
-/// Start base station stack
-fn build_bs_stack(cfg: &mut SharedConfig) -> MessageRouter {
-
-    let mut router = MessageRouter::new(cfg.clone());
-
-    // Add suitable Phy component based on PhyIo type
-    match cfg.config().phy_io.backend {
-        PhyBackend::SoapySdr => {
-            let rxdev = RxTxDevSoapySdr::new(cfg);
-            let phy = PhyBs::new(cfg.clone(), rxdev);
-            router.register_entity(Box::new(phy));
-        } 
-        _ => {
-            panic!("Unsupported PhyIo type: {:?}", cfg.config().phy_io.backend);
+/// Round-trips all supported PDUs and prints the results. Returns true if all passed.
+fn run_selftest() -> bool {
+    let results = selftest::run_selftest();
+    for r in &results {
+        match &r.result {
+            Ok(()) => println!("    pass  {}", r.pdu),
+            Err(e) => println!("    FAIL  {}", e),
         }
     }
-    
-    // Add remaining components
-    let lmac = LmacBs::new(cfg.clone());
-    let umac = UmacBs::new(cfg.clone());
-    let llc = Llc::new(cfg.clone());
-    let mle = Mle::new(cfg.clone());
-    let mm = MmBs::new(cfg.clone());
-    let sndcp = Sndcp::new(cfg.clone());
-    let cmce = CmceBs::new(cfg.clone());
-    router.register_entity(Box::new(lmac));
-    router.register_entity(Box::new(umac));
-    router.register_entity(Box::new(llc));
-    router.register_entity(Box::new(mle));
-    router.register_entity(Box::new(mm));
-    router.register_entity(Box::new(sndcp));
-    router.register_entity(Box::new(cmce));
-    
-    // Init network time
-    router.set_dl_time(TdmaTime::default());
-
-    router
+    let failed = results.iter().filter(|r| r.result.is_err()).count();
+    println!("Self-test: {} of {} PDUs passed", results.len() - failed, results.len());
+    failed == 0
 }
 
 
@@ -67,14 +42,50 @@ fn build_bs_stack(cfg: &mut SharedConfig) -> MessageRouter {
 
 
 struct Args {
-    /// Config file (required)
+    /// Config file (required unless running the self-test or generating a config)
     #[arg(
+        required_unless_present_any = ["selftest", "generate_config"],
         help = "TOML config with network/cell parameters",
     )]
-    config: String,
+    config: Option<String>,
+
+    /// Self-test mode
+    #[arg(
+        long,
+        help = "Check encoding and decoding of all supported PDUs, report the results and exit",
+    )]
+    selftest: bool,
+
+    /// Config generation mode
+    #[arg(
+        long,
+        help = "Print an example config listing every field with its bit width and default, and exit",
+    )]
+    generate_config: bool,
+
+    /// Control socket path (optional)
+    #[cfg(feature = "control-socket")]
+    #[arg(
+        long,
+        help = "Path of a Unix-domain socket accepting control commands, e.g. `set cell_load_ca 2` or `list clients`",
+    )]
+    control_socket: Option<String>,
+
+    /// Metrics endpoint address (optional)
+    #[cfg(feature = "metrics-http")]
+    #[arg(
+        long,
+        help = "Address to serve Prometheus metrics on at /metrics, e.g. `127.0.0.1:9150`",
+    )]
+    metrics_listen: Option<std::net::SocketAddr>,
 }
 
 fn main() {
+    let args = Args::parse();
+    if args.generate_config {
+        print!("{}", example_config::generate_example_toml());
+        return;
+    }
 
     eprintln!("░▀█▀░█▀▀░▀█▀░█▀▄░█▀█░░░░░█▀▄░█░░░█░█░█▀▀░█▀▀░▀█▀░█▀█░▀█▀░▀█▀░█▀█░█▀█");
     eprintln!("░░█░░█▀▀░░█░░█▀▄░█▀█░▄▄▄░█▀▄░█░░░█░█░█▀▀░▀▀█░░█░░█▀█░░█░░░█░░█░█░█░█");
@@ -83,11 +94,13 @@ fn main() {
     eprintln!(" -> https://github.com/MidnightBlueLabs/tetra-bluestation");
     eprintln!(" -> https://midnightblue.nl\n");
 
-    let args = Args::parse();
-    let mut cfg = load_config_from_toml(&args.config);
+    if args.selftest {
+        std::process::exit(if run_selftest() { 0 } else { 1 });
+    }
+    let cfg = load_config_from_toml(args.config.as_deref().expect("config is required without --selftest"));
     let _log_guard = debug::setup_logging_default(cfg.config().debug_log.clone());
     
-    let mut router = match cfg.config().stack_mode {
+    let mut stack = match cfg.config().stack_mode {
         StackMode::Mon => {
             unimplemented!("Monitor mode is not implemented");
         },
@@ -95,9 +108,40 @@ fn main() {
             unimplemented!("MS mode is not implemented");
         },
         StackMode::Bs => {
-            build_bs_stack(&mut cfg)
+            match BsStack::from_config(cfg.clone()) {
+                Ok(stack) => stack,
+                Err(e) => {
+                    println!("Failed to start the stack: {}", e);
+                    std::process::exit(1);
+                }
+            }
         }
     };
 
-    router.run_stack(None);
+    #[cfg(feature = "control-socket")]
+    let _control = args.control_socket.as_ref().map(|path| {
+        let queries = stack.router_mut().open_control_queries();
+        match tetra_entities::control::socket::spawn_control_socket(cfg.clone(), queries, std::path::Path::new(path)) {
+            Ok(handle) => handle,
+            Err(e) => {
+                println!("Failed to open control socket {}: {}", path, e);
+                std::process::exit(1);
+            }
+        }
+    });
+
+    #[cfg(feature = "metrics-http")]
+    let _metrics = args.metrics_listen.map(|addr| {
+        let queries = stack.router_mut().open_control_queries();
+        match tetra_entities::control::metrics_http::spawn_metrics_endpoint(queries, addr) {
+            Ok(handle) => handle,
+            Err(e) => {
+                println!("Failed to open metrics endpoint {}: {}", addr, e);
+                std::process::exit(1);
+            }
+        }
+    });
+
+    stack.run(None);
+    stack.router_mut().log_summary();
 }
@@ -0,0 +1,28 @@
+use bluestation_bs::BsStack;
+use tetra_config::{PhyBackend, SharedConfig, StackConfig, StackMode};
+use tetra_core::debug;
+
+#[test]
+fn test_bs_stack_runs_ticks() {
+    debug::setup_logging_verbose();
+    let mut cfg = StackConfig::new(StackMode::Bs, 204, 1337);
+    cfg.phy_io.backend = PhyBackend::Loopback;
+    let mut stack = BsStack::from_config(SharedConfig::from_config(cfg)).unwrap();
+
+    stack.tick();
+    assert_eq!(stack.router().stats().ticks, 1);
+
+    // A full multiframe, each tick sends a downlink slot to the PHY
+    stack.run(Some(72));
+    let stats = stack.router().stats();
+    assert_eq!(stats.ticks, 73);
+    assert!(stats.primitives.get("TpUnitdataReq").copied().unwrap_or(0) >= 72, "{:?}", stats.primitives);
+}
+
+#[test]
+fn test_bs_stack_rejects_unsupported_backend() {
+    let mut cfg = StackConfig::new(StackMode::Bs, 204, 1337);
+    cfg.phy_io.backend = PhyBackend::None;
+    let err = BsStack::from_config(SharedConfig::from_config(cfg)).err().expect("Stack built without a PHY");
+    assert!(err.to_string().contains("Unsupported PhyIo type"), "{}", err);
+}
@@ -24,6 +24,7 @@ use tetra_pdus::umac::{
         access_define::AccessDefine,
     },
 };
+use tetra_pdus::parse_timing::timed_parse;
 
 /// Result of length_ind interpretation
 #[derive(Debug)]
@@ -210,7 +211,7 @@ impl UmacParser {
 
     fn parse_mac_data(pdu: &mut BitBuffer) {
         println!("--- Parsing MAC-DATA ---");
-        match MacData::from_bitbuf(pdu) {
+        match timed_parse("MacData", pdu, MacData::from_bitbuf) {
             Ok(mac_data) => {
                 println!("{:#?}", mac_data);
                 
@@ -233,7 +234,7 @@ impl UmacParser {
 
     fn parse_mac_access(pdu: &mut BitBuffer) {
         println!("--- Parsing MAC-ACCESS ---");
-        match MacAccess::from_bitbuf(pdu) {
+        match timed_parse("MacAccess", pdu, MacAccess::from_bitbuf) {
             Ok(mac_access) => {
                 println!("{:#?}", mac_access);
                 
@@ -256,7 +257,7 @@ impl UmacParser {
 
     fn parse_mac_frag_ul(pdu: &mut BitBuffer) {
         println!("--- Parsing MAC-FRAG (UL) ---");
-        match MacFragUl::from_bitbuf(pdu) {
+        match timed_parse("MacFragUl", pdu, MacFragUl::from_bitbuf) {
             Ok(mac_frag) => {
                 println!("{:#?}", mac_frag);
                 let remaining = pdu.get_len_remaining();
@@ -274,7 +275,7 @@ impl UmacParser {
 
     fn parse_mac_end_ul(pdu: &mut BitBuffer) {
         println!("--- Parsing MAC-END (UL) ---");
-        match MacEndUl::from_bitbuf(pdu) {
+        match timed_parse("MacEndUl", pdu, MacEndUl::from_bitbuf) {
             Ok(mac_end) => {
                 println!("{:#?}", mac_end);
                 
@@ -297,7 +298,7 @@ impl UmacParser {
 
     fn parse_mac_end_hu(pdu: &mut BitBuffer) {
         println!("--- Parsing MAC-END-HU ---");
-        match MacEndHu::from_bitbuf(pdu) {
+        match timed_parse("MacEndHu", pdu, MacEndHu::from_bitbuf) {
             Ok(mac_end) => {
                 println!("{:#?}", mac_end);
                 
@@ -320,7 +321,7 @@ impl UmacParser {
 
     fn parse_mac_u_blck(pdu: &mut BitBuffer) {
         println!("--- Parsing MAC-U-BLCK ---");
-        match MacUBlck::from_bitbuf(pdu) {
+        match timed_parse("MacUBlck", pdu, MacUBlck::from_bitbuf) {
             Ok(mac_u_blck) => {
                 println!("{:#?}", mac_u_blck);
                 let remaining = pdu.get_len_remaining();
@@ -333,7 +334,7 @@ impl UmacParser {
 
     fn parse_mac_u_signal(pdu: &mut BitBuffer) {
         println!("--- Parsing MAC-U-SIGNAL ---");
-        match MacUSignal::from_bitbuf(pdu) {
+        match timed_parse("MacUSignal", pdu, MacUSignal::from_bitbuf) {
             Ok(mac_u_signal) => {
                 println!("{:#?}", mac_u_signal);
                 println!("BitBuffer: {}", pdu.dump_bin());
@@ -348,7 +349,7 @@ impl UmacParser {
 
     fn parse_mac_resource(pdu: &mut BitBuffer) {
         println!("--- Parsing MAC-RESOURCE ---");
-        match MacResource::from_bitbuf(pdu) {
+        match timed_parse("MacResource", pdu, MacResource::from_bitbuf) {
             Ok(mac_res) => {
                 println!("{:#?}", mac_res);
                 
@@ -370,7 +371,7 @@ impl UmacParser {
 
     fn parse_mac_frag_dl(pdu: &mut BitBuffer) {
         println!("--- Parsing MAC-FRAG (DL) ---");
-        match MacFragDl::from_bitbuf(pdu) {
+        match timed_parse("MacFragDl", pdu, MacFragDl::from_bitbuf) {
             Ok(mac_frag) => {
                 println!("{:#?}", mac_frag);
                 let remaining = pdu.get_len_remaining();
@@ -383,7 +384,7 @@ impl UmacParser {
 
     fn parse_mac_end_dl(pdu: &mut BitBuffer) {
         println!("--- Parsing MAC-END (DL) ---");
-        match MacEndDl::from_bitbuf(pdu) {
+        match timed_parse("MacEndDl", pdu, MacEndDl::from_bitbuf) {
             Ok(mac_end) => {
                 println!("{:#?}", mac_end);
                 
@@ -404,7 +405,7 @@ impl UmacParser {
 
     fn parse_mac_d_blck(pdu: &mut BitBuffer) {
         println!("--- Parsing MAC-D-BLCK ---");
-        match MacDBlck::from_bitbuf(pdu) {
+        match timed_parse("MacDBlck", pdu, MacDBlck::from_bitbuf) {
             Ok(mac_d_blck) => {
                 println!("{:#?}", mac_d_blck);
                 let remaining = pdu.get_len_remaining();
@@ -443,7 +444,7 @@ impl UmacParser {
 
     fn parse_mac_sync(pdu: &mut BitBuffer) {
         println!("--- Parsing MAC-SYNC (BSCH) ---");
-        match MacSync::from_bitbuf(pdu) {
+        match timed_parse("MacSync", pdu, MacSync::from_bitbuf) {
             Ok(mac_sync) => {
                 println!("{:#?}", mac_sync);
                 println!("BitBuffer: {}", pdu.dump_bin());
@@ -454,7 +455,7 @@ impl UmacParser {
 
     fn parse_mac_sysinfo(pdu: &mut BitBuffer) {
         println!("--- Parsing MAC-SYSINFO ---");
-        match MacSysinfo::from_bitbuf(pdu) {
+        match timed_parse("MacSysinfo", pdu, MacSysinfo::from_bitbuf) {
             Ok(mac_sysinfo) => {
                 println!("{:#?}", mac_sysinfo);
                 let remaining = pdu.get_len_remaining();
@@ -469,7 +470,7 @@ impl UmacParser {
 
     fn parse_access_define(pdu: &mut BitBuffer) {
         println!("--- Parsing ACCESS-DEFINE ---");
-        match AccessDefine::from_bitbuf(pdu) {
+        match timed_parse("AccessDefine", pdu, AccessDefine::from_bitbuf) {
             Ok(access_def) => {
                 println!("{:#?}", access_def);
                 println!("BitBuffer: {}", pdu.dump_bin());
@@ -0,0 +1,353 @@
+//! Generation of a commented example TOML configuration
+//!
+//! Every field is listed with its description and default. Required fields are set,
+//! optional fields are commented out at their default. Descriptions are the doc comments
+//! of the `Cfg*` structures, read from their source, and defaults are taken from their
+//! `Default` implementations, so both stay in sync with the parser.
+
+use std::fmt::{Display, Write};
+
+use super::stack_config::{CfgAccessControl, CfgAgc, CfgCallControl, CfgCellInfo, CfgLoopback, CfgPowerControl, CfgSds};
+
+/// Sources of the structures whose doc comments describe the configuration fields
+const SOURCES: [&str; 4] = [
+    include_str!("stack_config.rs"),
+    include_str!("stack_config_soapy.rs"),
+    include_str!("subscriber_db.rs"),
+    include_str!("toml_config.rs"),
+];
+
+/// TOML keys named differently from the structure field they are parsed into, by structure
+const RENAMED: [(&str, &str, &str); 4] = [
+    ("CfgCellInfo", "freq_offset", "freq_offset_hz"),
+    ("CfgCellInfo", "duplex_spacing", "duplex_spacing_id"),
+    ("CfgSoapySdr", "rx_freq", "ul_freq"),
+    ("CfgSoapySdr", "tx_freq", "dl_freq"),
+];
+
+/// Whether `line` declares the structure `ty`
+fn declares_struct(line: &str, ty: &str) -> bool {
+    line.strip_prefix("pub ").unwrap_or(line)
+        .strip_prefix("struct ")
+        .and_then(|rest| rest.strip_suffix('{'))
+        .is_some_and(|name| name.trim() == ty)
+}
+
+/// Doc comment lines of field `field` of structure `ty`. Panics if the field is undocumented.
+fn field_doc(ty: &str, field: &str) -> Vec<&'static str> {
+    for source in SOURCES {
+        let mut lines = source.lines().map(str::trim).skip_while(|line| !declares_struct(line, ty));
+        if lines.next().is_none() {
+            continue;
+        }
+        let mut doc = Vec::new();
+        for line in lines.take_while(|line| *line != "}") {
+            if let Some(text) = line.strip_prefix("///") {
+                doc.push(text.trim());
+            } else if line.starts_with("#[") {
+                continue;
+            } else if line.strip_prefix("pub ").unwrap_or(line).strip_prefix(field).is_some_and(|rest| rest.starts_with(':')) {
+                assert!(!doc.is_empty(), "{}::{} has no doc comment", ty, field);
+                return doc;
+            } else {
+                doc.clear();
+            }
+        }
+    }
+    panic!("{}::{} not found", ty, field);
+}
+
+struct Example {
+    out: String,
+    /// Structure whose fields are listed in the current section
+    ty: &'static str,
+}
+
+impl Example {
+    fn describe(&mut self, doc: &[&str], suffix: &str) {
+        for (i, line) in doc.iter().enumerate() {
+            let suffix = if i + 1 == doc.len() { suffix } else { "" };
+            let _ = writeln!(self.out, "# {}{}", line.replace('`', ""), suffix);
+        }
+    }
+
+    /// Lists the fields of `ty` from here on, described by the doc comments of its fields
+    fn fields_of(&mut self, ty: &'static str) {
+        self.ty = ty;
+    }
+
+    /// Section holding the fields of `ty`, described by the doc comment of field `field` of `parent`
+    fn section(&mut self, header: &str, parent: &str, field: &str, ty: &'static str) {
+        self.out.push('\n');
+        self.describe(&field_doc(parent, field), "");
+        let _ = writeln!(self.out, "{}", header);
+        self.fields_of(ty);
+    }
+
+    /// Section that is left out unless uncommented
+    fn optional_section(&mut self, header: &str, parent: &str, field: &str, ty: &'static str) {
+        self.section(&format!("# {}", header), parent, field, ty);
+    }
+
+    fn doc(&self, name: &str) -> Vec<&'static str> {
+        let field = RENAMED.iter()
+            .find(|(ty, key, _)| *ty == self.ty && *key == name)
+            .map_or(name, |(_, _, field)| field);
+        field_doc(self.ty, field)
+    }
+
+    /// Field that must be set
+    fn required(&mut self, name: &str, value: impl Display) {
+        self.describe(&self.doc(name), "");
+        let _ = writeln!(self.out, "{} = {}", name, value);
+    }
+
+    /// Field commented out at its default, or at an example value if its section is optional
+    fn optional(&mut self, name: &str, default: impl Display) {
+        self.describe(&self.doc(name), "");
+        let _ = writeln!(self.out, "# {} = {}", name, default);
+    }
+
+    /// Field that is not set by default, commented out at an example value
+    fn unset(&mut self, name: &str, example: impl Display) {
+        let doc = self.doc(name);
+        let text = doc.join(" ").to_lowercase();
+        let suffix = if text.contains("if set") || text.contains("if not set") {
+            ""
+        } else if text.ends_with('.') {
+            " Not set by default."
+        } else {
+            ". Not set by default."
+        };
+        self.describe(&doc, suffix);
+        let _ = writeln!(self.out, "# {} = {}", name, example);
+    }
+}
+
+/// Formats an enum value as a TOML string, the variant names are used as is
+fn quoted(value: impl std::fmt::Debug) -> String {
+    format!("\"{:?}\"", value)
+}
+
+/// Returns an example configuration with every field, which parses as is.
+/// The network and cell identities are placeholders to be replaced.
+pub fn generate_example_toml() -> String {
+    let mut ex = Example { out: String::new(), ty: "TomlConfigRoot" };
+    let _ = writeln!(ex.out, "# TETRA BlueStation example configuration, listing every field");
+    let _ = writeln!(ex.out, "# Fields that are commented out are optional and shown at their default");
+    ex.out.push('\n');
+    ex.required("config_version", "\"0.5\"");
+    ex.fields_of("StackConfig");
+    ex.required("stack_mode", quoted(crate::StackMode::Bs));
+    ex.unset("debug_log", "\"bluestation.log\"");
+
+    ex.section("[phy_io]", "StackConfig", "phy_io", "CfgPhyIo");
+    ex.required("backend", quoted(crate::PhyBackend::None));
+    ex.optional("rssi_offset_db", "0.0");
+    ex.optional("sample_format", "\"cf32\"");
+    ex.unset("dl_tx_file", "\"dl_output.bin\"");
+    ex.unset("ul_rx_file", "\"ul_output.bin\"");
+    ex.unset("ul_input_file", "\"ul_input.bin\"");
+    ex.unset("dl_input_file", "\"dl_input.bin\"");
+
+    ex.optional_section("[phy_io.soapysdr]", "CfgPhyIo", "soapysdr", "CfgSoapySdr");
+    ex.optional("tx_freq", 438025000);
+    ex.optional("rx_freq", 433025000);
+    ex.unset("ppm_err", "0.0");
+    ex.unset("rx_stream_mtu", 4096);
+    ex.unset("rx_stream_buffers", 16);
+    ex.unset("tx_stream_mtu", 4096);
+    ex.unset("tx_stream_buffers", 16);
+
+    ex.optional_section("[phy_io.soapysdr.iocfg_usrpb2xx]", "SoapySdrIoCfg", "iocfg_usrpb2xx", "UsrpB2xxCfg");
+    ex.optional("rx_ant", "\"TX/RX\"");
+    ex.optional("tx_ant", "\"TX/RX\"");
+    ex.optional("rx_gain_pga", "50.0");
+    ex.optional("tx_gain_pga", "35.0");
+
+    ex.optional_section("[phy_io.soapysdr.iocfg_limesdr]", "SoapySdrIoCfg", "iocfg_limesdr", "LimeSdrCfg");
+    ex.optional("rx_ant", "\"LNAL\"");
+    ex.optional("tx_ant", "\"BAND1\"");
+    ex.optional("rx_gain_lna", "18.0");
+    ex.optional("rx_gain_tia", "6.0");
+    ex.optional("rx_gain_pga", "10.0");
+    ex.optional("tx_gain_pad", "18.0");
+    ex.optional("tx_gain_iamp", "3.0");
+
+    ex.optional_section("[phy_io.soapysdr.iocfg_sxceiver]", "SoapySdrIoCfg", "iocfg_sxceiver", "SXceiverCfg");
+    ex.optional("rx_ant", "\"RX\"");
+    ex.optional("tx_ant", "\"TX\"");
+    ex.optional("rx_gain_lna", "42.0");
+    ex.optional("rx_gain_pga", "16.0");
+    ex.optional("tx_gain_dac", "9.0");
+    ex.optional("tx_gain_mixer", "30.0");
+
+    let lb = CfgLoopback::default();
+    ex.optional_section("[phy_io.loopback]", "CfgPhyIo", "loopback", "CfgLoopback");
+    ex.unset("noise_snr_db", "20.0");
+    ex.optional("delay_samples", lb.delay_samples);
+    ex.optional("freq_offset_hz", format!("{:?}", lb.freq_offset_hz));
+    ex.optional("clock_ppm", format!("{:?}", lb.clock_ppm));
+
+    let agc = CfgAgc::default();
+    ex.optional_section("[phy_io.agc]", "CfgPhyIo", "agc", "CfgAgc");
+    ex.optional("target_rms", format!("{:?}", agc.target_rms));
+    ex.optional("attack_ms", format!("{:?}", agc.attack_ms));
+    ex.optional("decay_ms", format!("{:?}", agc.decay_ms));
+    ex.optional("max_gain_db", format!("{:?}", agc.max_gain_db));
+
+    ex.optional_section("[phy_io.recording]", "CfgPhyIo", "recording", "CfgRecording");
+    ex.optional("file", "\"recording.bin\"");
+    ex.unset("tx_file", "\"recording_tx.bin\"");
+
+    ex.section("[net_info]", "StackConfig", "net", "CfgNetInfo");
+    ex.required("mcc", 204);
+    ex.required("mnc", 1337);
+
+    let cell = CfgCellInfo::default();
+    ex.section("[cell_info]", "StackConfig", "cell", "CfgCellInfo");
+    ex.required("main_carrier", cell.main_carrier);
+    ex.required("freq_band", cell.freq_band);
+    ex.required("freq_offset", cell.freq_offset_hz);
+    ex.required("duplex_spacing", 4);
+    ex.required("reverse_operation", cell.reverse_operation);
+    ex.required("location_area", 2);
+    ex.unset("custom_duplex_spacing", 10000000);
+    ex.optional("neighbor_cell_broadcast", cell.neighbor_cell_broadcast);
+    ex.optional("cell_load_ca", cell.cell_load_ca);
+    ex.optional("late_entry_supported", cell.late_entry_supported);
+    ex.optional("subscriber_class", cell.subscriber_class);
+    ex.unset("ms_subscriber_class", "0xFFFF");
+    ex.optional("registration", cell.registration);
+    ex.optional("deregistration", cell.deregistration);
+    ex.optional("priority_cell", cell.priority_cell);
+    ex.optional("no_minimum_mode", cell.no_minimum_mode);
+    ex.optional("migration", cell.migration);
+    ex.optional("protocol_edition", quoted(cell.protocol_edition));
+    ex.optional("system_wide_services", cell.system_wide_services);
+    ex.optional("voice_service", cell.voice_service);
+    ex.optional("circuit_mode_data_service", cell.circuit_mode_data_service);
+    ex.optional("sndcp_service", cell.sndcp_service);
+    ex.optional("aie_service", cell.aie_service);
+    ex.optional("advanced_link", cell.advanced_link);
+    ex.optional("data_priority_service", cell.data_priority_service);
+    ex.optional("extended_advanced_links", cell.extended_advanced_links);
+    ex.optional("qos_negotiation_service", cell.qos_negotiation_service);
+    ex.optional("security_class", quoted(cell.security_class));
+    ex.unset("sck_number", 1);
+    ex.unset("cck_id", 1);
+    ex.optional("system_code", cell.system_code);
+    ex.optional("colour_code", cell.colour_code);
+    ex.optional("sharing_mode", cell.sharing_mode);
+    ex.optional("ts_reserved_frames", cell.ts_reserved_frames);
+    ex.optional("u_plane_dtx", cell.u_plane_dtx);
+    ex.optional("frame_18_ext", cell.frame_18_ext);
+    ex.optional("mcch_timeslot", cell.mcch_timeslot);
+    ex.optional("secondary_control_channels", cell.secondary_control_channels);
+    ex.unset("cell_radius_km", "10.0");
+    ex.optional("sync_period_multiframes", cell.sync_period_multiframes);
+    ex.optional("sysinfo_period_multiframes", cell.sysinfo_period_multiframes);
+    ex.optional("tx_off_frames", "[]");
+    ex.optional("max_tm_sdu_bits", cell.max_tm_sdu_bits);
+
+    let ac = CfgAccessControl::default();
+    ex.optional_section("[access_control]", "StackConfig", "access_control", "CfgAccessControl");
+    ex.optional("allow", "[]");
+    ex.optional("deny", "[]");
+    ex.optional("allow_disable", ac.allow_disable);
+    ex.unset("max_registered_ms", 100);
+    ex.unset("inactivity_timeout_s", 3600);
+    ex.optional("require_authentication", ac.require_authentication);
+    ex.unset("detach_ack_status", 32);
+    ex.unset("vassi_range", "[0xFF0000, 0xFFFFFE]");
+
+    let pc = CfgPowerControl::default();
+    ex.optional_section("[power_control]", "StackConfig", "power_control", "CfgPowerControl");
+    ex.optional("rssi_min_dbm", format!("{:?}", pc.rssi_min_dbm));
+    ex.optional("rssi_max_dbm", format!("{:?}", pc.rssi_max_dbm));
+
+    ex.optional_section("[uplane]", "StackConfig", "uplane", "CfgUplane");
+    ex.unset("frame_dir", "\"frames\"");
+    ex.unset("udp_target", "\"127.0.0.1:5000\"");
+
+    let sds = CfgSds::default();
+    ex.optional_section("[sds]", "StackConfig", "sds", "CfgSds");
+    ex.optional("text_coding", quoted(sds.text_coding));
+    ex.optional("sender_ssi", sds.sender_ssi);
+
+    let cc = CfgCallControl::default();
+    ex.optional_section("[call_control]", "StackConfig", "call_control", "CfgCallControl");
+    ex.optional("preemption", cc.preemption);
+    ex.optional("emergency_priority", cc.emergency_priority);
+    ex.optional("t301_s", cc.t301_s);
+    ex.optional("t302_s", cc.t302_s);
+    ex.optional("t310_s", cc.t310_s);
+    ex.unset("t310_reset_interval_s", 5);
+
+    ex.optional_section("[temporary_addresses]", "StackConfig", "temporary_addresses", "CfgTemporaryAddresses");
+    ex.optional("first", 9000000);
+    ex.optional("last", 9000099);
+
+    ex.optional_section("[[neighbor_cell]]", "StackConfig", "neighbor_cells", "CfgNeighborCell");
+    ex.optional("cell_id", 1);
+    ex.optional("main_carrier", 1522);
+    ex.unset("location_area", 3);
+    ex.optional("reselection_types", 0);
+    ex.optional("synchronized", false);
+
+    ex.optional_section("[[subscriber]]", "StackConfig", "subscribers", "CfgSubscriber");
+    ex.optional("issi", 2040001);
+    ex.unset("subscriber_class", "0xFFFF");
+    ex.optional("services", "[\"IndividualCall\", \"GroupCall\", \"Sds\"]");
+    ex.optional("groups", "[]");
+
+    ex.optional_section("[[sds_autoresponder.rules]]", "CfgSdsAutoresponder", "rules", "CfgSdsRule");
+    ex.optional("match_status", 32768);
+    ex.unset("reply_status", 32769);
+    ex.unset("reply_text", "\"ok\"");
+
+    ex.optional_section("[stack_state]", "TomlConfigRoot", "stack_state", "StackState");
+    ex.optional("cell_load_ca", 0);
+    ex.out
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fields of each DTO structure in the TOML parser, along with whether they hold a table
+    fn dto_fields() -> Vec<(&'static str, &'static str, bool)> {
+        let mut fields = Vec::new();
+        let mut dto = None;
+        for line in include_str!("toml_config.rs").lines().map(str::trim) {
+            if let Some(name) = line.strip_prefix("struct ").and_then(|rest| rest.strip_suffix(" {")) {
+                dto = Some(name);
+            } else if line == "}" {
+                dto = None;
+            } else if let Some(dto) = dto
+                && !line.starts_with("//") && !line.starts_with("#[")
+                && let Some((field, ty)) = line.strip_prefix("pub ").unwrap_or(line).split_once(':')
+                && field != "extra" {
+                fields.push((dto, field, ty.contains("Dto") || ty.contains("Patch")));
+            }
+        }
+        fields
+    }
+
+    #[test]
+    fn test_every_dto_field_listed() {
+        let toml = generate_example_toml();
+        let lines: Vec<&str> = toml.lines().map(|line| line.trim_start_matches("# ")).collect();
+        let fields = dto_fields();
+        assert!(fields.len() > 100, "{:?}", fields);
+        for (dto, field, is_table) in fields {
+            let listed = if is_table {
+                lines.iter().any(|line| line.starts_with('[') && line.trim_matches(['[', ']']).split('.').any(|part| part == field))
+            } else {
+                lines.iter().any(|line| line.starts_with(&format!("{} = ", field)))
+            };
+            assert!(listed, "{}.{} missing from the example config", dto, field);
+        }
+    }
+}
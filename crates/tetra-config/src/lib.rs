@@ -4,10 +4,15 @@
 //! - TOML configuration file parsing
 //! - Stack configuration structures
 //! - SoapySDR-specific configuration
+//! - Static subscriber database
+//! - Example configuration generation
 
+pub mod example_config;
 pub mod stack_config;
 pub mod stack_config_soapy;
+pub mod subscriber_db;
 pub mod toml_config;
 
 pub use stack_config::*;
+pub use subscriber_db::*;
 pub use toml_config::*;
@@ -1,8 +1,11 @@
+use core::fmt;
 use std::sync::{Arc, RwLock};
 use serde::Deserialize;
 use tetra_core::freqs::FreqInfo;
+use tetra_core::{DEFAULT_MAX_PATH_DELAY_SYMBOLS, path_delay_symbols};
 
 use super::stack_config_soapy::CfgSoapySdr;
+use super::subscriber_db::SubscriberDb;
 
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
@@ -19,22 +22,81 @@ pub enum StackMode {
 pub enum PhyBackend {
     Undefined,
     None,
-    SoapySdr
+    SoapySdr,
+    /// Internal TX to RX loopback, for testing without RF hardware
+    Loopback,
+}
+
+/// Storage format of IQ samples in files, interleaved I and Q, little-endian
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SampleFormat {
+    /// Complex 32-bit float, full scale at 1.0
+    #[default]
+    Cf32,
+    /// Complex 16-bit signed integer
+    Cs16,
+    /// Complex 8-bit signed integer
+    Cs8,
+}
+
+/// Air interface encryption security class of the cell, EN 300 392-7 clause 6.2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum SecurityClass {
+    /// Class 1, no air interface encryption
+    #[default]
+    Clear,
+    /// Class 2, encryption with a static cipher key
+    Sck,
+    /// Class 3, encryption with derived and common cipher keys
+    Cck,
+}
+
+/// Edition of EN 300 392-2 the MSs served by the cell implement. Selects the values of
+/// elements that later editions no longer use, but edition 1 MSs still interpret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum ProtocolEdition {
+    Edition1,
+    #[default]
+    Edition2,
 }
 
 /// PHY layer I/O configuration
 #[derive(Debug, Clone, Deserialize)]
 pub struct CfgPhyIo {
-    /// Backend type: Soapysdr, File, or None
+    /// SoapySdr, Loopback or None. SoapySdr requires a [phy_io.soapysdr] section
     pub backend: PhyBackend,
     
+    /// Debugging: file to write the transmitted downlink bursts to
     pub dl_tx_file: Option<String>,
+    /// Debugging: file to write the received uplink bursts to
     pub ul_rx_file: Option<String>,
+    /// Testing mode: file to read uplink bursts from instead of the device. May be a
+    /// FIFO, streamed into in real time by another process, e.g. a recorder
     pub ul_input_file: Option<String>,
+    /// Testing mode: file to read downlink bursts from, restarting at its end. May be a
+    /// FIFO, streamed into in real time by another process
     pub dl_input_file: Option<String>,
 
-    /// For Soapysdr backend: SoapySDR configuration
+    /// SoapySDR device, required for the SoapySdr backend
     pub soapysdr: Option<CfgSoapySdr>,
+
+    /// Channel impairments of the Loopback backend. No impairments if not set.
+    pub loopback: Option<CfgLoopback>,
+
+    /// Automatic gain control on received signals. Disabled if not set.
+    pub agc: Option<CfgAgc>,
+
+    /// Recording of the IQ samples passing through the RX/TX device. Disabled if not set.
+    pub recording: Option<CfgRecording>,
+
+    /// Format of IQ sample files: cf32, cs16 or cs8. Applies to recordings and sample input files
+    pub sample_format: SampleFormat,
+
+    /// Added to received signal strength in dBFS to report it in dBm
+    pub rssi_offset_db: f32,
 }
 
 impl Default for CfgPhyIo {
@@ -46,34 +108,331 @@ impl Default for CfgPhyIo {
             ul_input_file: None,
             dl_input_file: None,
             soapysdr: None,
+            loopback: None,
+            agc: None,
+            recording: None,
+            sample_format: SampleFormat::default(),
+            rssi_offset_db: 0.0,
         }
     }
 }
 
+/// Automatic gain control applied to each received channel before demodulation
 #[derive(Debug, Clone, Deserialize)]
+pub struct CfgAgc {
+    /// RMS amplitude the AGC scales the signal towards
+    pub target_rms: f32,
+    /// Time constant in milliseconds for following an increase in signal power
+    pub attack_ms: f32,
+    /// Time constant in milliseconds for following a decrease in signal power
+    pub decay_ms: f32,
+    /// Maximum gain in dB, limiting amplification of noise in the absence of a signal
+    pub max_gain_db: f32,
+}
+
+impl Default for CfgAgc {
+    fn default() -> Self {
+        Self {
+            target_rms: 0.25,
+            attack_ms: 1.0,
+            decay_ms: 50.0,
+            max_gain_db: 60.0,
+        }
+    }
+}
+
+/// Recording of the IQ samples received from, and optionally sent to, the RX/TX device
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CfgRecording {
+    /// File the received samples are written to, truncated at startup
+    pub file: String,
+    /// File the samples sent to the device for transmission are written to. Not recorded if not set.
+    pub tx_file: Option<String>,
+}
+
+/// Channel impairments applied by the Loopback backend between TX and RX
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CfgLoopback {
+    /// If set, complex white Gaussian noise is added to the looped-back signal at this
+    /// signal-to-noise ratio in dB, measured over the full 72 kHz modem sample rate bandwidth
+    pub noise_snr_db: Option<f32>,
+    /// Delay of the looped-back signal in samples at the 72 kHz modem sample rate
+    pub delay_samples: usize,
+    /// Carrier frequency offset of the looped-back signal in Hz
+    pub freq_offset_hz: f32,
+    /// Sample clock offset of the receiver relative to the transmitter in ppm.
+    /// Positive values mean the receiver samples faster.
+    pub clock_ppm: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct CfgNetInfo {
-    /// 10 bits, from 18.4.2.1 D-MLE-SYNC
+    /// 10 bits, mobile country code, from 18.4.2.1 D-MLE-SYNC
     pub mcc: u16,
-    /// 14 bits, from 18.4.2.1 D-MLE-SYNC
+    /// 14 bits, mobile network code, from 18.4.2.1 D-MLE-SYNC
     pub mnc: u16,
 }
 
+/// ISSI based access control, consulted upon registration
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CfgAccessControl {
+    /// If non-empty, only these ISSIs may register
+    #[serde(default)]
+    pub allow: Vec<u32>,
+    /// These ISSIs may never register. Takes precedence over `allow`
+    #[serde(default)]
+    pub deny: Vec<u32>,
+    /// Permits the BS to disable (stun) and re-enable MSs with D-DISABLE / D-ENABLE.
+    /// Off by default, since a permanently disabled MS can not be recovered over the air
+    #[serde(default)]
+    pub allow_disable: bool,
+    /// Maximum number of simultaneously registered MSs. Registrations of new MSs
+    /// beyond this are rejected; already registered MSs may still re-register
+    #[serde(default)]
+    pub max_registered_ms: Option<usize>,
+    /// Registered MSs not heard from for this many seconds are deregistered.
+    /// MSs are kept registered indefinitely if not set
+    #[serde(default)]
+    pub inactivity_timeout_s: Option<u32>,
+    /// MSs must authenticate before their registration is accepted. Requires an
+    /// authenticator to be installed in MM; without one, all registrations are rejected
+    #[serde(default)]
+    pub require_authentication: bool,
+    /// If set, U-ITSI DETACH is acknowledged with a D-MM STATUS carrying this 6-bit
+    /// status downlink value. No standard value exists for this, so a network or user
+    /// specific value (32-63) is to be used
+    #[serde(default)]
+    pub detach_ack_status: Option<u8>,
+    /// First and last SSI assigned as (V)ASSI to MSs migrating from other networks.
+    /// Local MSs registering with an ISSI in this range are rejected.
+    /// 0xFF0000-0xFFFFFE if not set
+    #[serde(default)]
+    pub vassi_range: Option<(u32, u32)>,
+}
+
+/// Range of SSIs used for (V)ASSIs if none is configured
+pub const DEFAULT_VASSI_RANGE: (u32, u32) = (0xFF0000, 0xFFFFFE);
+
+/// Uplink power control: MSs received outside the target RSSI window are
+/// commanded to adjust their transmit power. MSs whose bursts arrive from beyond
+/// the cell radius are told so through the same power control element,
+/// see CfgCellInfo::max_path_delay_symbols.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CfgPowerControl {
+    /// Lower bound of the target window for received signal strength, in dBm
+    pub rssi_min_dbm: f32,
+    /// Upper bound of the target window for received signal strength, in dBm
+    pub rssi_max_dbm: f32,
+}
+
+impl Default for CfgPowerControl {
+    fn default() -> Self {
+        Self {
+            rssi_min_dbm: -95.0,
+            rssi_max_dbm: -75.0,
+        }
+    }
+}
+
+/// Destination of U-plane (speech) frames of active calls. Frames are passed
+/// through as received; no codec is applied. Exactly one destination must be set.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CfgUplane {
+    /// Directory to write one file of raw traffic frames per call into
+    #[serde(default)]
+    pub frame_dir: Option<String>,
+    /// host:port to send traffic frames to as UDP datagrams, prefixed with the call id
+    #[serde(default)]
+    pub udp_target: Option<String>,
+}
+
+/// Text coding scheme of SDS-TL simple text messages, EN 300 392-2 clause 29.5.4.1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum SdsTextCoding {
+    /// 7-bit alphabet of ETSI TS 100 900, packed
+    Gsm7,
+    /// ISO/IEC 8859-1, one byte per character
+    #[default]
+    Latin1,
+}
+
+/// Text messages originated by the BS
+#[derive(Debug, Clone, Deserialize)]
+pub struct CfgSds {
+    /// Coding scheme of text messages sent by the BS
+    #[serde(default)]
+    pub text_coding: SdsTextCoding,
+    /// SSI that text messages sent by the BS appear to come from
+    #[serde(default = "default_sds_sender_ssi")]
+    pub sender_ssi: u32,
+}
+
+impl Default for CfgSds {
+    fn default() -> Self {
+        Self {
+            text_coding: SdsTextCoding::default(),
+            sender_ssi: default_sds_sender_ssi(),
+        }
+    }
+}
+
+#[inline]
+fn default_sds_sender_ssi() -> u32 {
+    1
+}
+
+/// Call control behaviour of the BS
 #[derive(Debug, Clone, Deserialize)]
+pub struct CfgCallControl {
+    /// If set, an emergency call (see `emergency_priority`) that finds all traffic
+    /// timeslots in use releases the active call with the lowest, lower priority
+    #[serde(default)]
+    pub preemption: bool,
+    /// Call priority (1-15) from which a call requested in U-SETUP is an emergency call.
+    /// Emergency calls are set up ahead of any queued call.
+    #[serde(default = "default_emergency_priority")]
+    pub emergency_priority: u8,
+    /// T301 in seconds: a call set up to an individual MS is released if the MS
+    /// neither alerts nor connects within this time after the D-SETUP
+    #[serde(default = "default_t301_s")]
+    pub t301_s: u32,
+    /// T302 in seconds: an alerting call is released if the called MS does not
+    /// connect within this time
+    #[serde(default = "default_t302_s")]
+    pub t302_s: u32,
+    /// T310 in seconds: maximum call length. An active call is released when it expires.
+    #[serde(default = "default_t310_s")]
+    pub t310_s: u32,
+    /// If set, active calls are sent a D-INFO resetting T310 at this interval in seconds,
+    /// which keeps them alive beyond T310. Must be shorter than T310.
+    #[serde(default)]
+    pub t310_reset_interval_s: Option<u32>,
+}
+
+impl Default for CfgCallControl {
+    fn default() -> Self {
+        Self {
+            preemption: false,
+            emergency_priority: default_emergency_priority(),
+            t301_s: default_t301_s(),
+            t302_s: default_t302_s(),
+            t310_s: default_t310_s(),
+            t310_reset_interval_s: None,
+        }
+    }
+}
+
+#[inline]
+fn default_emergency_priority() -> u8 {
+    15
+}
+
+#[inline]
+fn default_t301_s() -> u32 {
+    30
+}
+
+#[inline]
+fn default_t302_s() -> u32 {
+    60
+}
+
+#[inline]
+fn default_t310_s() -> u32 {
+    10
+}
+
+/// Pool of temporary addresses assigned to calls set up by the BS, and carried
+/// in their D-SETUP and D-CONNECT. Keep it apart from the ISSI and (V)ASSI ranges.
+#[derive(Debug, Clone, Deserialize)]
+pub struct CfgTemporaryAddresses {
+    /// First SSI of the pool
+    pub first: u32,
+    /// Last SSI of the pool
+    pub last: u32,
+}
+
+/// Longest auto-responder reply text, in characters. Keeps the reply within the
+/// 2047 bits of user defined data 4.
+pub const MAX_SDS_REPLY_TEXT_LEN: usize = 200;
+
+/// Canned replies to incoming SDS, for testing MS behaviour. An U-SDS-DATA carrying
+/// a 16-bit status (user defined data 1) matching a rule is answered with a D-SDS-DATA
+/// from the address it was sent to.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CfgSdsAutoresponder {
+    /// SDS auto-responder rules. Incoming SDS is not answered if none are defined
+    #[serde(default)]
+    pub rules: Vec<CfgSdsRule>,
+}
+
+/// A single auto-responder rule. Exactly one of `reply_status` and `reply_text` must be set.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct CfgSdsRule {
+    /// Status value of the incoming SDS
+    pub match_status: u16,
+    /// Reply with this 16-bit status
+    #[serde(default)]
+    pub reply_status: Option<u16>,
+    /// Reply with this text, as a simple text message
+    #[serde(default)]
+    pub reply_text: Option<String>,
+}
+
+impl CfgSdsAutoresponder {
+    /// First rule matching an incoming status, if any
+    pub fn rule_for(&self, status: u16) -> Option<&CfgSdsRule> {
+        self.rules.iter().find(|r| r.match_status == status)
+    }
+}
+
+impl CfgAccessControl {
+    /// Returns whether the given ISSI may register. Deny wins over allow; 
+    /// without an allow list, any ISSI that is not explicitly denied may register.
+    pub fn is_issi_allowed(&self, issi: u32) -> bool {
+        if self.deny.contains(&issi) {
+            return false;
+        }
+        self.allow.is_empty() || self.allow.contains(&issi)
+    }
+}
+
+/// A CA neighbour cell, broadcast in D-NWRK-BROADCAST (18.5.17 Neighbour cell information for CA)
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CfgNeighborCell {
+    /// 5 bits, cell identifier by which MSs refer to this neighbour cell
+    pub cell_id: u8,
+    /// 12 bits, main carrier number of the neighbour cell
+    pub main_carrier: u16,
+    /// 14 bits, location area of the neighbour cell. If not set, MSs assume it equals our own
+    #[serde(default)]
+    pub location_area: Option<u16>,
+    /// 2 bits, cell reselection types supported by the neighbour cell
+    #[serde(default)]
+    pub reselection_types: u8,
+    /// Whether the neighbour cell is synchronized to this cell
+    #[serde(default)]
+    pub synchronized: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 pub struct CfgCellInfo {
-    // 2 bits, from 18.4.2.1 D-MLE-SYNC
+    /// 2 bits, neighbour cell broadcast, from 18.4.2.1 D-MLE-SYNC
     #[serde(default)]
     pub neighbor_cell_broadcast: u8,
-    // 2 bits, from 18.4.2.1 D-MLE-SYNC
+    /// 2 bits, cell load, from 18.4.2.1 D-MLE-SYNC
     #[serde(default)]
     pub cell_load_ca: u8,
-    // 1 bit, from 18.4.2.1 D-MLE-SYNC
-    #[serde(default)]
+    /// 1 bit, late entry supported, from 18.4.2.1 D-MLE-SYNC. If set, the D-SETUP of an
+    /// active group call is repeated for MSs that enter the call late
+    #[serde(default = "default_late_entry_supported")]
     pub late_entry_supported: bool,
 
-    /// 12 bits, from MAC SYSINFO
+    /// 12 bits, main carrier number, from MAC SYSINFO
     #[serde(default = "default_main_carrier")]
     pub main_carrier: u16,
-    /// 4 bits, from MAC SYSINFO
+    /// 4 bits, frequency band, from MAC SYSINFO
     #[serde(default = "default_freq_band")]
     pub freq_band: u8,
     /// Offset in Hz from 25kHz aligned carrier. Options: 0, 6250, -6250, 12500 Hz
@@ -81,60 +440,167 @@ pub struct CfgCellInfo {
     #[serde(default)]
     pub freq_offset_hz: i16,
     /// Index in duplex setting table. Sent in SYSINFO. Maps to a specific duplex spacing in Hz.
-    /// Custom spacing can be provided optionally by setting 
+    /// Custom spacing can be provided optionally by setting custom_duplex_spacing
     #[serde(default)]
     pub duplex_spacing_id: u8,
     /// Custom duplex spacing in Hz, for users that use a modified, non-standard duplex spacing table. 
     #[serde(default)]
     pub custom_duplex_spacing: Option<u32>,
-    /// 1 bits, from MAC SYSINFO
+    /// 1 bit, the uplink is above the downlink, from MAC SYSINFO
     #[serde(default)]
     pub reverse_operation: bool,
 
-    // 14 bits, from 18.4.2.2 D-MLE-SYSINFO
+    /// 14 bits, location area, from 18.4.2.2 D-MLE-SYSINFO
     #[serde(default)]
     pub location_area: u16,
-    // 16 bits, from 18.4.2.2 D-MLE-SYSINFO
+    /// 16 bits, subscriber classes allowed in the cell, from 18.4.2.2 D-MLE-SYSINFO
     #[serde(default)]
     pub subscriber_class: u16,
+    /// 16 bits, subscriber class assigned to registering MSs in D-LOCATION UPDATE ACCEPT.
+    /// If set, MSs none of whose classes are allowed by `subscriber_class` are rejected.
+    #[serde(default)]
+    pub ms_subscriber_class: Option<u16>,
 
-    // 1-bit service flags
+    // 1-bit service flags, from 18.5.2 BS service details
+    /// 1 bit, registration is required
     #[serde(default)]
     pub registration: bool,
+    /// 1 bit, deregistration is required
     #[serde(default)]
     pub deregistration: bool,
+    /// 1 bit, the cell is a priority cell
     #[serde(default)]
     pub priority_cell: bool,
+    /// 1 bit, the cell never uses minimum mode. If cleared, the cell may use minimum
+    /// mode, and SYNC and SYSINFO are only broadcast on the MCCH in frame 18
     #[serde(default)]
     pub no_minimum_mode: bool,
+    /// 1 bit, MSs from other networks may register, and are assigned a (V)ASSI
     #[serde(default)]
     pub migration: bool,
+    /// Edition1 or Edition2, edition of the standard whose values are used for elements
+    /// no longer used since
+    #[serde(default)]
+    pub protocol_edition: ProtocolEdition,
+    /// 1 bit, system wide services are supported
     #[serde(default)]
     pub system_wide_services: bool,
+    /// 1 bit, voice service is supported
     #[serde(default)]
     pub voice_service: bool,
+    /// 1 bit, circuit mode data service is supported
     #[serde(default)]
     pub circuit_mode_data_service: bool,
+    /// 1 bit, SNDCP service is supported
     #[serde(default)]
     pub sndcp_service: bool,
+    /// 1 bit, air interface encryption is supported, required for any security_class
+    /// other than Clear
     #[serde(default)]
     pub aie_service: bool,
+    /// 1 bit, advanced link is supported
     #[serde(default)]
     pub advanced_link: bool,
 
+    // Extended services broadcast section 1, sent on frame 18 if frame_18_ext is set
+    /// 1 bit, data priority is supported, in the extended services broadcast
+    #[serde(default)]
+    pub data_priority_service: bool,
+    /// 1 bit, extended advanced links are supported, in the extended services broadcast
+    #[serde(default)]
+    pub extended_advanced_links: bool,
+    /// 1 bit, QoS negotiation is supported, in the extended services broadcast
+    #[serde(default)]
+    pub qos_negotiation_service: bool,
+
+    // Air interface encryption, advertised in the SYSINFO security information element
+    /// Clear, Sck or Cck. Security class of this cell. Any class other than Clear requires aie_service
+    #[serde(default)]
+    pub security_class: SecurityClass,
+    /// SCK number (1-32) in use, required for security class Sck
+    #[serde(default)]
+    pub sck_number: Option<u8>,
+    /// 16 bits, CCK identifier broadcast in SYSINFO, required for security class Cck
+    #[serde(default)]
+    pub cck_id: Option<u16>,
+
     // From SYNC
+    /// 4 bits, system code, from SYNC
     #[serde(default)]
     pub system_code: u8,
+    /// 6 bits, colour code, from SYNC
     #[serde(default)]
     pub colour_code: u8,
+    /// 2 bits, sharing mode, from SYNC. 2 is MCCH sharing
     #[serde(default)]
     pub sharing_mode: u8,
+    /// 3 bits, TS reserved frames, from SYNC
     #[serde(default)]
     pub ts_reserved_frames: u8,
+    /// 1 bit, U-plane DTX is supported, from SYNC
     #[serde(default)]
     pub u_plane_dtx: bool,
+    /// 1 bit, frame 18 extension is supported, from SYNC
     #[serde(default)]
     pub frame_18_ext: bool,
+
+    // Control channel allocation on the main carrier
+    /// Timeslot (1-4) carrying the MCCH
+    #[serde(default = "default_mcch_timeslot")]
+    pub mcch_timeslot: u8,
+    /// If set, the timeslot following the MCCH carries a common SCCH
+    #[serde(default)]
+    pub secondary_control_channels: bool,
+
+    /// Radius of the cell in km. Bursts arriving later than the propagation delay
+    /// from this distance are flagged as likely interference or reflections, and with
+    /// power_control, the MS is told its maximum path delay is exceeded.
+    /// If not set, bursts up to 7 symbols late (about 58 km) are tolerated.
+    #[serde(default)]
+    pub cell_radius_km: Option<f32>,
+
+    // Broadcast cadence, in multiframes (1-60)
+    /// SYNC is broadcast in one out of every this many multiframes (1-60)
+    #[serde(default = "default_broadcast_period")]
+    pub sync_period_multiframes: u8,
+    /// SYSINFO is broadcast in one out of every this many multiframes (1-60)
+    #[serde(default = "default_broadcast_period")]
+    pub sysinfo_period_multiframes: u8,
+
+    /// Frames (1-17) of every multiframe in which the BS does not transmit, to listen
+    /// on the downlink frequency. Nothing is scheduled on the downlink in these frames.
+    #[serde(default)]
+    pub tx_off_frames: Vec<u8>,
+
+    /// Largest TM-SDU in bits the MAC sends on the downlink. Longer SDUs are fragmented
+    /// over several slots, SDUs above this limit are rejected.
+    #[serde(default = "default_max_tm_sdu_bits")]
+    pub max_tm_sdu_bits: usize,
+}
+
+impl CfgCellInfo {
+    /// Largest tolerated burst arrival delay in symbols, from the cell radius if configured.
+    /// Used both to flag late bursts in the PHY and for the power control element.
+    pub fn max_path_delay_symbols(&self) -> f32 {
+        self.cell_radius_km.map_or(DEFAULT_MAX_PATH_DELAY_SYMBOLS, path_delay_symbols)
+    }
+
+    /// Whether the cell may use minimum mode. MSs in minimum mode only receive the MCCH
+    /// in frame 18 (EN 300 392-2 clause 9.5.1), so SYNC and SYSINFO are broadcast on the
+    /// MCCH in frame 18 only
+    pub fn minimum_mode(&self) -> bool {
+        !self.no_minimum_mode
+    }
+
+    /// Timeslot carrying the common SCCH, the one following the MCCH, if secondary control channels are used
+    pub fn scch_timeslot(&self) -> Option<u8> {
+        self.secondary_control_channels.then_some(self.mcch_timeslot % 4 + 1)
+    }
+
+    /// Whether timeslot `ts` (1-4) of the main carrier carries common control signalling
+    pub fn is_control_timeslot(&self, ts: u8) -> bool {
+        ts == self.mcch_timeslot || self.scch_timeslot() == Some(ts)
+    }
 }
 
 impl Default for CfgCellInfo {
@@ -149,20 +615,29 @@ impl Default for CfgCellInfo {
 
             neighbor_cell_broadcast: 0,
             cell_load_ca: 0,
-            late_entry_supported: false,
+            late_entry_supported: default_late_entry_supported(),
             location_area: 0,
             subscriber_class: 0,
+            ms_subscriber_class: None,
             registration: true,
             deregistration: true,
             priority_cell: false,
             no_minimum_mode: false,
             migration: false,
+            protocol_edition: ProtocolEdition::default(),
             system_wide_services: false,
             voice_service: false,
             circuit_mode_data_service: false,
             sndcp_service: false,
             aie_service: false,
             advanced_link: false,
+            data_priority_service: false,
+            extended_advanced_links: false,
+            qos_negotiation_service: false,
+
+            security_class: SecurityClass::Clear,
+            sck_number: None,
+            cck_id: None,
 
             system_code: 0,
             colour_code: 0,
@@ -170,6 +645,14 @@ impl Default for CfgCellInfo {
             ts_reserved_frames: 0,
             u_plane_dtx: false,
             frame_18_ext: false,
+
+            mcch_timeslot: default_mcch_timeslot(),
+            secondary_control_channels: false,
+            cell_radius_km: None,
+            sync_period_multiframes: default_broadcast_period(),
+            sysinfo_period_multiframes: default_broadcast_period(),
+            tx_off_frames: Vec::new(),
+            max_tm_sdu_bits: default_max_tm_sdu_bits(),
         }
     }
 }
@@ -179,6 +662,26 @@ fn default_freq_band() -> u8 {
     4
 }
 
+#[inline]
+fn default_broadcast_period() -> u8 {
+    1
+}
+
+#[inline]
+fn default_late_entry_supported() -> bool {
+    true
+}
+
+fn default_mcch_timeslot() -> u8 {
+    1
+}
+
+/// Matches the defragmentation buffer of the receiving MAC
+#[inline]
+fn default_max_tm_sdu_bits() -> usize {
+    4096
+}
+
 #[inline]
 fn default_main_carrier() -> u16 {
     1521
@@ -186,19 +689,59 @@ fn default_main_carrier() -> u16 {
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct StackConfig {
+    /// Bs, Ms or Mon. Only Bs is implemented
     #[serde(default = "default_stack_mode")]
     pub stack_mode: StackMode,
+    /// File to write a debug log to, in addition to the console
     #[serde(default)]
     pub debug_log: Option<String>,
 
+    /// PHY layer I/O
     #[serde(default)]
     pub phy_io: CfgPhyIo,
 
-    /// Network info is REQUIRED - no default provided
+    /// Network identity. Required, no default provided
     pub net: CfgNetInfo,
 
+    /// Cell parameters, broadcast in SYNC and SYSINFO
     #[serde(default)]
     pub cell: CfgCellInfo,
+
+    /// ISSI based access control, consulted upon registration
+    #[serde(default)]
+    pub access_control: CfgAccessControl,
+
+    /// Uplink power control. Disabled if not set.
+    #[serde(default)]
+    pub power_control: Option<CfgPowerControl>,
+
+    /// U-plane frame passthrough. Traffic frames are dropped if not set.
+    #[serde(default)]
+    pub uplane: Option<CfgUplane>,
+
+    /// SDS auto-responder. Incoming SDS is not answered if not set.
+    #[serde(default)]
+    pub sds_autoresponder: Option<CfgSdsAutoresponder>,
+
+    /// Text messages originated by the BS
+    #[serde(default)]
+    pub sds: CfgSds,
+
+    /// Call control behaviour of the BS
+    #[serde(default)]
+    pub call_control: CfgCallControl,
+
+    /// Temporary addresses for calls. Calls carry no temporary address if not set.
+    #[serde(default)]
+    pub temporary_addresses: Option<CfgTemporaryAddresses>,
+
+    /// CA neighbour cells, at most 7
+    #[serde(default)]
+    pub neighbor_cells: Vec<CfgNeighborCell>,
+
+    /// Predefined subscribers. Any ISSI may register if empty.
+    #[serde(default)]
+    pub subscribers: SubscriberDb,
 }
 
 fn default_stack_mode() -> StackMode {
@@ -214,20 +757,47 @@ impl StackConfig {
             phy_io: CfgPhyIo::default(),
             net: CfgNetInfo { mcc, mnc },
             cell: CfgCellInfo::default(),
+            access_control: CfgAccessControl::default(),
+            power_control: None,
+            uplane: None,
+            sds_autoresponder: None,
+            sds: CfgSds::default(),
+            call_control: CfgCallControl::default(),
+            temporary_addresses: None,
+            neighbor_cells: Vec::new(),
+            subscribers: SubscriberDb::default(),
         }
     }
 
     /// Validate that all required configuration fields are properly set.
+    /// See `check` for the problems found.
     pub fn validate(&self) -> Result<(), &str> {
+        if self.check().is_empty() {
+            Ok(())
+        } else {
+            Err("invalid configuration")
+        }
+    }
 
-        // Check input device settings
-        match self.phy_io.backend {
+    /// Checks all configuration fields, returning every problem found
+    pub fn check(&self) -> Vec<ConfigError> {
+        let mut errors = Vec::new();
+        self.check_phy_io(&mut errors);
+        self.check_bit_widths(&mut errors);
+        self.check_cell(&mut errors);
+        self.check_services(&mut errors);
+        self.check_neighbors_and_subscribers(&mut errors);
+        errors
+    }
 
+    fn check_phy_io(&self, errors: &mut Vec<ConfigError>) {
+        match self.phy_io.backend {
             PhyBackend::SoapySdr => {
                 let Some(ref soapy_cfg) = self.phy_io.soapysdr else {
-                    return Err("soapysdr configuration must be provided for Soapysdr backend");
+                    errors.push(ConfigError::new("phy_io.soapysdr", "required for the SoapySdr backend"));
+                    return;
                 };
-                
+
                 // Validate that exactly one hardware configuration is present
                 let config_count = [
                     soapy_cfg.io_cfg.iocfg_usrpb2xx.is_some(),
@@ -235,54 +805,328 @@ impl StackConfig {
                     soapy_cfg.io_cfg.iocfg_sxceiver.is_some(),
                 ].iter().filter(|&&x| x).count();
                 if config_count != 1 {
-                    return Err("soapysdr backend requires exactly one hardware configuration (iocfg_usrpb2xx, iocfg_limesdr, or iocfg_sxceiver)");
+                    errors.push(ConfigError::new("phy_io.soapysdr", "requires exactly one of iocfg_usrpb2xx, iocfg_limesdr and iocfg_sxceiver"));
+                }
+
+                let stream_sizes = [
+                    ("rx_stream_mtu", soapy_cfg.rx_stream_mtu),
+                    ("rx_stream_buffers", soapy_cfg.rx_stream_buffers),
+                    ("tx_stream_mtu", soapy_cfg.tx_stream_mtu),
+                    ("tx_stream_buffers", soapy_cfg.tx_stream_buffers),
+                ];
+                for (name, size) in stream_sizes {
+                    if size == Some(0) {
+                        errors.push(ConfigError::new(format!("phy_io.soapysdr.{}", name), "must be non-zero when set"));
+                    }
+                }
+            },
+            PhyBackend::Loopback => {
+                if let Some(ref lb_cfg) = self.phy_io.loopback {
+                    if lb_cfg.noise_snr_db.is_some_and(|snr| !snr.is_finite()) {
+                        errors.push(ConfigError::new("phy_io.loopback.noise_snr_db", "must be a finite number"));
+                    }
+                    if !lb_cfg.freq_offset_hz.is_finite() || lb_cfg.freq_offset_hz.abs() >= 36000.0 {
+                        errors.push(ConfigError::new("phy_io.loopback.freq_offset_hz", "must be within the 72 kHz modem bandwidth"));
+                    }
+                    if !lb_cfg.clock_ppm.is_finite() || lb_cfg.clock_ppm.abs() > 1000.0 {
+                        errors.push(ConfigError::new("phy_io.loopback.clock_ppm", "must be within +-1000 ppm"));
+                    }
                 }
             },
             PhyBackend::None => {}, // For testing
             PhyBackend::Undefined => {
-                return Err("phy_io backend must be defined");
+                errors.push(ConfigError::new("phy_io.backend", "must be defined"));
             },
         };
 
+        if !self.phy_io.rssi_offset_db.is_finite() {
+            errors.push(ConfigError::new("phy_io.rssi_offset_db", "must be a finite number"));
+        }
+
+        if let Some(ref rec) = self.phy_io.recording {
+            if rec.file.is_empty() {
+                errors.push(ConfigError::new("phy_io.recording.file", "must not be empty"));
+            }
+            if rec.tx_file.as_ref().is_some_and(|f| f.is_empty()) {
+                errors.push(ConfigError::new("phy_io.recording.tx_file", "must not be empty"));
+            }
+        }
+
+        if let Some(ref agc) = self.phy_io.agc {
+            let values = [agc.target_rms, agc.attack_ms, agc.decay_ms];
+            if values.iter().any(|v| !v.is_finite() || *v <= 0.0) || !agc.max_gain_db.is_finite() {
+                errors.push(ConfigError::new("phy_io.agc", "settings must be finite, with positive target_rms, attack_ms and decay_ms"));
+            }
+        }
+    }
+
+    /// Fields broadcast over the air must fit the width of their PDU element, as they
+    /// would otherwise be silently truncated
+    fn check_bit_widths(&self, errors: &mut Vec<ConfigError>) {
+        check_bits(errors, "net_info.mcc", self.net.mcc as u64, 10);
+        check_bits(errors, "net_info.mnc", self.net.mnc as u64, 14);
+
+        let cell = &self.cell;
+        check_bits(errors, "cell_info.neighbor_cell_broadcast", cell.neighbor_cell_broadcast as u64, 2);
+        check_bits(errors, "cell_info.cell_load_ca", cell.cell_load_ca as u64, 2);
+        check_bits(errors, "cell_info.main_carrier", cell.main_carrier as u64, 12);
+        check_bits(errors, "cell_info.freq_band", cell.freq_band as u64, 4);
+        check_bits(errors, "cell_info.duplex_spacing", cell.duplex_spacing_id as u64, 3);
+        check_bits(errors, "cell_info.location_area", cell.location_area as u64, 14);
+        check_bits(errors, "cell_info.system_code", cell.system_code as u64, 4);
+        check_bits(errors, "cell_info.colour_code", cell.colour_code as u64, 6);
+        check_bits(errors, "cell_info.sharing_mode", cell.sharing_mode as u64, 2);
+        check_bits(errors, "cell_info.ts_reserved_frames", cell.ts_reserved_frames as u64, 3);
+    }
+
+    fn check_cell(&self, errors: &mut Vec<ConfigError>) {
+        // Frequencies can only be derived from fields of valid width, see check_bit_widths
+        let mut freqs_valid = fits_bits(self.cell.main_carrier as u64, 12)
+            && fits_bits(self.cell.freq_band as u64, 4)
+            && fits_bits(self.cell.duplex_spacing_id as u64, 3);
+
+        // The duplex spacing setting broadcast in SYSINFO must be defined for the frequency band
+        // (ETSI TS 100 392-15 clause 6), unless the radios use a custom duplex spacing table
+        let (band, setting) = (self.cell.freq_band, self.cell.duplex_spacing_id);
+        if freqs_valid && self.cell.custom_duplex_spacing.is_none() && FreqInfo::get_default_duplex_spacing(band, setting).is_none() {
+            errors.push(ConfigError::new("cell_info.duplex_spacing", format!("{} is not defined for freq_band {}", setting, band)));
+            freqs_valid = false;
+        }
+        // The carrier offset is sent in SYSINFO as one of four values
+        if FreqInfo::freq_offset_hz_to_id(self.cell.freq_offset_hz).is_none() {
+            errors.push(ConfigError::new("cell_info.freq_offset", "must be one of 0, 6250, -6250 or 12500 Hz"));
+            freqs_valid = false;
+        }
+
         // Sanity check on main carrier property fields in SYSINFO
-        if self.phy_io.backend == PhyBackend::SoapySdr {
-            let soapy_cfg = self.phy_io.soapysdr.as_ref().expect("SoapySdr config must be set for SoapySdr PhyIo");
-
-            // let Ok(freqinfo) = FreqInfo::from_dlul_freqs(soapy_cfg.dl_freq as u32, soapy_cfg.ul_freq as u32) else {
-            //     return Err("Invalid PhyIo DL/UL frequencies (can't map to TETRA SYSINFO settings)");
-            // };
-            let     Ok(freq_info) = FreqInfo::from_components(
-                    self.cell.freq_band, 
-                    self.cell.main_carrier, 
-                    self.cell.freq_offset_hz, 
-                    self.cell.reverse_operation,
-                    self.cell.duplex_spacing_id,
-                    self.cell.custom_duplex_spacing) else {
-                return Err("Invalid cell info frequency settings");
-            };
+        if freqs_valid && self.phy_io.backend == PhyBackend::SoapySdr
+            && let Some(ref soapy_cfg) = self.phy_io.soapysdr {
+            self.check_soapy_freqs(soapy_cfg, errors);
+        }
 
-            let (dlfreq, ulfreq) = freq_info.get_freqs();
-            
-            println!("    {:?}", freq_info);
-            println!("    Derived DL freq: {} Hz, UL freq: {} Hz\n", dlfreq, ulfreq);
+        if self.cell.cell_radius_km.is_some_and(|r| !(r.is_finite() && r > 0.0)) {
+            errors.push(ConfigError::new("cell_info.cell_radius_km", "must be positive"));
+        }
 
-            if soapy_cfg.dl_freq as u32 != dlfreq {
-                return Err("PhyIo DlFrequency does not match computed FreqInfo");
-            };
-            if soapy_cfg.ul_freq as u32 != ulfreq {
-                return Err("PhyIo UlFrequency does not match computed FreqInfo");
+        // Control channel allocation
+        if !(1..=60).contains(&self.cell.sync_period_multiframes) {
+            errors.push(ConfigError::new("cell_info.sync_period_multiframes", "out of range 1-60"));
+        }
+        if !(1..=60).contains(&self.cell.sysinfo_period_multiframes) {
+            errors.push(ConfigError::new("cell_info.sysinfo_period_multiframes", "out of range 1-60"));
+        }
+        // Frame 18 carries SYNC and the uplink control frame, and can not be switched off
+        if self.cell.tx_off_frames.iter().any(|f| !(1..=17).contains(f)) {
+            errors.push(ConfigError::new("cell_info.tx_off_frames", "frames must be in range 1-17"));
+        }
+        if self.cell.max_tm_sdu_bits == 0 {
+            errors.push(ConfigError::new("cell_info.max_tm_sdu_bits", "must be non-zero"));
+        }
+        if !(1..=4).contains(&self.cell.mcch_timeslot) {
+            errors.push(ConfigError::new("cell_info.mcch_timeslot", "out of range 1-4"));
+        }
+        // In MCCH sharing mode, ts_reserved_frames designates frames of timeslot 1 reserved for this cell
+        if self.cell.sharing_mode == 2 {
+            if self.cell.mcch_timeslot != 1 {
+                errors.push(ConfigError::new("cell_info.mcch_timeslot", "must be 1 for MCCH sharing, as ts_reserved_frames applies to timeslot 1"));
+            }
+            if self.cell.secondary_control_channels {
+                errors.push(ConfigError::new("cell_info.secondary_control_channels", "can't be used in MCCH sharing mode"));
+            }
+        }
+
+        // Air interface encryption
+        if self.cell.aie_service != (self.cell.security_class != SecurityClass::Clear) {
+            errors.push(ConfigError::new("cell_info.aie_service", "must be set if and only if security_class is not Clear"));
+        }
+        match self.cell.security_class {
+            SecurityClass::Clear => {},
+            SecurityClass::Sck => {
+                if !self.cell.sck_number.is_some_and(|n| (1..=32).contains(&n)) {
+                    errors.push(ConfigError::new("cell_info.sck_number", "must be in range 1-32 for security_class Sck"));
+                }
+            },
+            SecurityClass::Cck => {
+                if self.cell.cck_id.is_none() {
+                    errors.push(ConfigError::new("cell_info.cck_id", "required for security_class Cck"));
+                }
+            },
+        }
+    }
+
+    /// The frequencies of the SoapySdr device must match those broadcast for the cell
+    fn check_soapy_freqs(&self, soapy_cfg: &CfgSoapySdr, errors: &mut Vec<ConfigError>) {
+        let Ok(freq_info) = FreqInfo::from_components(
+                self.cell.freq_band,
+                self.cell.main_carrier,
+                self.cell.freq_offset_hz,
+                self.cell.reverse_operation,
+                self.cell.duplex_spacing_id,
+                self.cell.custom_duplex_spacing) else {
+            errors.push(ConfigError::new("cell_info", "invalid frequency settings"));
+            return;
+        };
+
+        let (dlfreq, ulfreq) = freq_info.get_freqs();
+
+        println!("    {:?}", freq_info);
+        println!("    Derived DL freq: {} Hz, UL freq: {} Hz\n", dlfreq, ulfreq);
+
+        if soapy_cfg.dl_freq as u32 != dlfreq {
+            errors.push(ConfigError::new("phy_io.soapysdr.tx_freq", format!("does not match the {} Hz derived from cell_info", dlfreq)));
+        }
+        if soapy_cfg.ul_freq as u32 != ulfreq {
+            // In reverse operation the uplink is above the downlink
+            let (_, mirrored_ulfreq) = FreqInfo { reverse_operation: !freq_info.reverse_operation, ..freq_info }.get_freqs();
+            let message = if soapy_cfg.ul_freq as u32 != mirrored_ulfreq {
+                format!("does not match the {} Hz derived from cell_info", ulfreq)
+            } else if freq_info.reverse_operation {
+                "is below tx_freq, but reverse_operation is set".to_string()
+            } else {
+                "is above tx_freq, which requires reverse_operation".to_string()
             };
+            errors.push(ConfigError::new("phy_io.soapysdr.rx_freq", message));
+        }
+    }
+
+    fn check_services(&self, errors: &mut Vec<ConfigError>) {
+        if let Some(ref pc) = self.power_control
+            && !(pc.rssi_min_dbm.is_finite() && pc.rssi_max_dbm.is_finite() && pc.rssi_min_dbm < pc.rssi_max_dbm) {
+            errors.push(ConfigError::new("power_control.rssi_min_dbm", "must be below rssi_max_dbm"));
+        }
+        if self.access_control.detach_ack_status.is_some_and(|s| s > 63) {
+            errors.push(ConfigError::new("access_control.detach_ack_status", "out of range (max 63)"));
+        }
+        if self.access_control.vassi_range.is_some_and(|(first, last)| first > last || last > 0xFFFFFF) {
+            errors.push(ConfigError::new("access_control.vassi_range", "must be a range of 24-bit SSIs, first to last"));
+        }
+        if let Some(ref up) = self.uplane
+            && up.frame_dir.is_some() == up.udp_target.is_some() {
+            errors.push(ConfigError::new("uplane", "requires exactly one of frame_dir and udp_target"));
+        }
+        if self.sds.sender_ssi == 0 || self.sds.sender_ssi > 0xFFFFFF {
+            errors.push(ConfigError::new("sds.sender_ssi", "must be a nonzero 24-bit SSI"));
+        }
+        if let Some(ref ta) = self.temporary_addresses
+            && (ta.first > ta.last || ta.last > 0xFFFFFF) {
+            errors.push(ConfigError::new("temporary_addresses", "must be a range of 24-bit SSIs, first to last"));
         }
+        if let Some(ref ta) = self.temporary_addresses {
+            let (vassi_first, vassi_last) = self.access_control.vassi_range.unwrap_or(DEFAULT_VASSI_RANGE);
+            if ta.first <= vassi_last && vassi_first <= ta.last {
+                errors.push(ConfigError::new("temporary_addresses", "overlaps access_control.vassi_range"));
+            }
+        }
+        if let Some(ref sds) = self.sds_autoresponder {
+            for (i, rule) in sds.rules.iter().enumerate() {
+                if rule.reply_status.is_some() == rule.reply_text.is_some() {
+                    errors.push(ConfigError::new(format!("sds_autoresponder.rules[{}]", i), "requires exactly one of reply_status and reply_text"));
+                }
+                if rule.reply_text.as_ref().is_some_and(|t| !t.is_ascii() || t.len() > MAX_SDS_REPLY_TEXT_LEN) {
+                    errors.push(ConfigError::new(format!("sds_autoresponder.rules[{}].reply_text", i), "must be ASCII, at most 200 characters"));
+                }
+            }
+        }
+
+        let cc = &self.call_control;
+        if !(1..=15).contains(&cc.emergency_priority) {
+            errors.push(ConfigError::new("call_control.emergency_priority", "out of range 1-15"));
+        }
+        for (name, value) in [("t301_s", cc.t301_s), ("t302_s", cc.t302_s), ("t310_s", cc.t310_s)] {
+            if value == 0 {
+                errors.push(ConfigError::new(format!("call_control.{}", name), "must be non-zero"));
+            }
+        }
+        if cc.t310_reset_interval_s.is_some_and(|interval| interval == 0 || interval >= cc.t310_s) {
+            errors.push(ConfigError::new("call_control.t310_reset_interval_s", "must be non-zero and shorter than t310_s"));
+        }
+    }
+
+    fn check_neighbors_and_subscribers(&self, errors: &mut Vec<ConfigError>) {
+        // Neighbour cells must fit D-NWRK-BROADCAST
+        if self.neighbor_cells.len() > 7 {
+            errors.push(ConfigError::new("neighbor_cell", "at most 7 neighbor cells can be broadcast"));
+        }
+        for (i, neighbor) in self.neighbor_cells.iter().enumerate() {
+            check_bits(errors, format!("neighbor_cell[{}].cell_id", i), neighbor.cell_id as u64, 5);
+            check_bits(errors, format!("neighbor_cell[{}].main_carrier", i), neighbor.main_carrier as u64, 12);
+            if let Some(la) = neighbor.location_area {
+                check_bits(errors, format!("neighbor_cell[{}].location_area", i), la as u64, 14);
+            }
+            check_bits(errors, format!("neighbor_cell[{}].reselection_types", i), neighbor.reselection_types as u64, 2);
+        }
+
+        // Subscriber identities must fit in 24 bits
+        for (i, subscriber) in self.subscribers.iter().enumerate() {
+            check_bits(errors, format!("subscriber[{}].issi", i), subscriber.issi as u64, 24);
+            for (j, &gssi) in subscriber.groups.iter().enumerate() {
+                check_bits(errors, format!("subscriber[{}].groups[{}]", i, j), gssi as u64, 24);
+            }
+        }
+    }
+}
 
+fn fits_bits(value: u64, bits: u32) -> bool {
+    value >> bits == 0
+}
+
+/// Records an error for field `path` if `value` does not fit in `bits` bits
+fn check_bits(errors: &mut Vec<ConfigError>, path: impl Into<String>, value: u64, bits: u32) {
+    if !fits_bits(value, bits) {
+        errors.push(ConfigError::new(path, format!("out of range (max {})", (1u64 << bits) - 1)));
+    }
+}
+
+/// A problem found in the configuration, with the path of the offending field
+/// as written in the TOML file, e.g. `cell_info.main_carrier`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigError {
+    pub path: String,
+    pub message: String,
+}
+
+impl ConfigError {
+    pub fn new(path: impl Into<String>, message: impl Into<String>) -> Self {
+        Self { path: path.into(), message: message.into() }
+    }
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+/// All problems found in a configuration, reported together
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigErrors(pub Vec<ConfigError>);
+
+impl fmt::Display for ConfigErrors {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} configuration errors", self.0.len())?;
+        for e in &self.0 {
+            write!(f, "\n  {}", e)?;
+        }
         Ok(())
     }
 }
 
+impl std::error::Error for ConfigErrors {}
+
 /// Mutable, stack-editable state (mutex-protected).
 #[derive(Debug, Clone)]
 #[derive(Default)]
 pub struct StackState {
+    /// 2 bits, cell load broadcast in D-MLE-SYNC, overriding cell_info.cell_load_ca
     pub cell_load_ca: u8,
+    /// SDR RX overruns since startup
+    pub sdr_rx_overruns: u64,
+    /// SDR TX underruns since startup
+    pub sdr_tx_underruns: u64,
+    /// SDR TX stream re-initializations after sustained underrun
+    pub sdr_tx_reinits: u64,
+    /// Jumps in the slot numbering of the RX/TX device relative to the stack, since startup
+    pub tdma_discontinuities: u64,
 }
 
 
@@ -45,44 +45,69 @@ impl Default for SoapySdrIoCfg {
 /// Configuration for Ettus USRP B2xx series
 #[derive(Debug, Clone, Deserialize)]
 pub struct UsrpB2xxCfg {
+    /// RX antenna port
     pub rx_ant: Option<String>,
+    /// TX antenna port
     pub tx_ant: Option<String>,
+    /// RX PGA gain in dB
     pub rx_gain_pga: Option<f64>,
+    /// TX PGA gain in dB
     pub tx_gain_pga: Option<f64>,
 }
 
 /// Configuration for LimeSDR
 #[derive(Debug, Clone, Deserialize)]
 pub struct LimeSdrCfg {
+    /// RX antenna port, LNAL or LNAW
     pub rx_ant: Option<String>,
+    /// TX antenna port, BAND1 or BAND2
     pub tx_ant: Option<String>,
+    /// RX LNA gain in dB
     pub rx_gain_lna: Option<f64>,
+    /// RX TIA gain in dB
     pub rx_gain_tia: Option<f64>,
+    /// RX PGA gain in dB
     pub rx_gain_pga: Option<f64>,
+    /// TX PAD gain in dB
     pub tx_gain_pad: Option<f64>,
+    /// TX IAMP gain in dB
     pub tx_gain_iamp: Option<f64>,
 }
 
 /// Configuration for SXceiver
 #[derive(Debug, Clone, Deserialize)]
 pub struct SXceiverCfg {
+    /// RX antenna port
     pub rx_ant: Option<String>,
+    /// TX antenna port
     pub tx_ant: Option<String>,
+    /// RX LNA gain in dB
     pub rx_gain_lna: Option<f64>,
+    /// RX PGA gain in dB
     pub rx_gain_pga: Option<f64>,
+    /// TX DAC gain in dB
     pub tx_gain_dac: Option<f64>,
+    /// TX mixer gain in dB
     pub tx_gain_mixer: Option<f64>,
 }
 
 /// SoapySDR configuration
 #[derive(Debug, Clone, Deserialize)]
 pub struct CfgSoapySdr {
-    /// Uplink frequency in Hz
+    /// Uplink frequency in Hz, received by the BS. Keep cell_info in line with it
     pub ul_freq: f64,
-    /// Downlink frequency in Hz
+    /// Downlink frequency in Hz, transmitted by the BS. Keep cell_info in line with it
     pub dl_freq: f64,
-    /// PPM frequency error correction
+    /// Tuning error of the SDR in ppm, corrected for
     pub ppm_err: Option<f64>,
+    /// RX stream MTU in samples per transfer, driver default if not set
+    pub rx_stream_mtu: Option<usize>,
+    /// Number of RX stream buffers, driver default if not set
+    pub rx_stream_buffers: Option<usize>,
+    /// TX stream MTU in samples per transfer, driver default if not set
+    pub tx_stream_mtu: Option<usize>,
+    /// Number of TX stream buffers, driver default if not set
+    pub tx_stream_buffers: Option<usize>,
     /// Hardware-specific I/O configuration
     #[serde(flatten)]
     pub io_cfg: SoapySdrIoCfg,
@@ -110,6 +135,10 @@ impl Default for CfgSoapySdr {
             ul_freq: 0.0,
             dl_freq: 0.0,
             ppm_err: None,
+            rx_stream_mtu: None,
+            rx_stream_buffers: None,
+            tx_stream_mtu: None,
+            tx_stream_buffers: None,
             io_cfg: SoapySdrIoCfg::default(),
         }
     }
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+/// Services a subscriber may use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum SubscriberService {
+    IndividualCall,
+    GroupCall,
+    Sds,
+}
+
+impl SubscriberService {
+    pub const ALL: [SubscriberService; 3] = [Self::IndividualCall, Self::GroupCall, Self::Sds];
+}
+
+/// A predefined subscriber, one `[[subscriber]]` table in the config file
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CfgSubscriber {
+    /// 24 bits, ISSI of the subscriber
+    pub issi: u32,
+    /// Subscriber class assigned upon registration, replacing `cell_info.ms_subscriber_class`
+    #[serde(default)]
+    pub subscriber_class: Option<u16>,
+    /// Services the subscriber may use: IndividualCall, GroupCall and Sds. All services if not set
+    #[serde(default = "default_services")]
+    pub services: Vec<SubscriberService>,
+    /// Groups the subscriber is attached to as soon as it registers
+    #[serde(default)]
+    pub groups: Vec<u32>,
+}
+
+fn default_services() -> Vec<SubscriberService> {
+    SubscriberService::ALL.to_vec()
+}
+
+/// Static subscriber database, loaded from the config file at startup. If it is empty,
+/// any ISSI may register and use all services; otherwise only the listed subscribers may.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(from = "Vec<CfgSubscriber>")]
+pub struct SubscriberDb {
+    /// Subscribers in config file order
+    subscribers: Vec<CfgSubscriber>,
+    /// Index into `subscribers` by ISSI
+    by_issi: HashMap<u32, usize>,
+}
+
+impl From<Vec<CfgSubscriber>> for SubscriberDb {
+    fn from(subscribers: Vec<CfgSubscriber>) -> Self {
+        let by_issi = subscribers.iter().enumerate().map(|(i, s)| (s.issi, i)).collect();
+        Self { subscribers, by_issi }
+    }
+}
+
+impl SubscriberDb {
+    pub fn get(&self, issi: u32) -> Option<&CfgSubscriber> {
+        self.by_issi.get(&issi).map(|&i| &self.subscribers[i])
+    }
+
+    /// Iterates the subscribers in config file order
+    pub fn iter(&self) -> impl Iterator<Item = &CfgSubscriber> {
+        self.subscribers.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_issi.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.subscribers.is_empty()
+    }
+
+    /// Returns whether the ISSI may register
+    pub fn is_issi_allowed(&self, issi: u32) -> bool {
+        self.is_empty() || self.by_issi.contains_key(&issi)
+    }
+
+    /// Returns whether the ISSI may use `service`
+    pub fn allows_service(&self, issi: u32, service: SubscriberService) -> bool {
+        match self.get(issi) {
+            Some(subscriber) => subscriber.services.contains(&service),
+            None => self.is_empty(),
+        }
+    }
+
+    /// Subscriber class predefined for the ISSI, if any
+    pub fn subscriber_class(&self, issi: u32) -> Option<u16> {
+        self.get(issi).and_then(|s| s.subscriber_class)
+    }
+
+    /// Groups the ISSI is attached to upon registration
+    pub fn home_groups(&self, issi: u32) -> &[u32] {
+        self.get(issi).map(|s| s.groups.as_slice()).unwrap_or_default()
+    }
+}
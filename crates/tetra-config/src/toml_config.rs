@@ -6,44 +6,77 @@ use std::path::Path;
 use serde::Deserialize;
 use toml::Value;
 
-use super::stack_config::{CfgPhyIo, PhyBackend, CfgCellInfo, CfgNetInfo, SharedConfig, StackConfig, StackMode, StackState};
+use super::stack_config::{CfgAgc, CfgRecording, ConfigError, ConfigErrors, CfgLoopback, CfgPhyIo, PhyBackend, SampleFormat, CfgAccessControl, CfgCellInfo, CfgPowerControl, CfgUplane, CfgSds, CfgSdsAutoresponder, CfgCallControl, CfgSdsRule, CfgTemporaryAddresses, CfgNeighborCell, CfgNetInfo, ProtocolEdition, SdsTextCoding, SecurityClass, SharedConfig, StackConfig, StackMode, StackState};
 use super::stack_config_soapy::{CfgSoapySdr, LimeSdrCfg, SXceiverCfg, UsrpB2xxCfg};
+use super::subscriber_db::{CfgSubscriber, SubscriberDb, SubscriberService};
 
-/// Build `SharedConfig` from a TOML configuration file
+/// Build `SharedConfig` from a TOML configuration file.
+/// All problems found in a well-formed file are reported together, as `ConfigErrors`.
 pub fn from_toml_str(toml_str: &str) -> Result<SharedConfig, Box<dyn std::error::Error>> {
     let root: TomlConfigRoot = toml::from_str(toml_str)?;
 
-    // Various sanity checks
+    // Various sanity checks. Problems are collected, to report them all at once.
+    let mut errors = Vec::new();
     let expected_config_version = "0.5";
     if !root.config_version.eq(expected_config_version) {
-        return Err(format!("Unrecognized config_version: {}, expect {}", root.config_version, expected_config_version).into());
-    }
-    if !root.extra.is_empty() {
-        return Err(format!("Unrecognized top-level fields: {:?}", sorted_keys(&root.extra)).into());
+        errors.push(ConfigError::new("config_version", format!("unrecognized version {}, expected {}", root.config_version, expected_config_version)));
     }
+    check_unrecognized(&mut errors, "", &root.extra);
     if let Some(ref phy) = root.phy_io {
-        if !phy.extra.is_empty() {
-            return Err(format!("Unrecognized fields: phy_io::{:?}", sorted_keys(&phy.extra)).into());
-        }
+        check_unrecognized(&mut errors, "phy_io", &phy.extra);
         if let Some(ref soapy) = phy.soapysdr {
-            if !soapy.extra.is_empty() {
-                return Err(format!("Unrecognized fields: phy_io.soapysdr::{:?}", sorted_keys(&soapy.extra)).into());
-            }
+            check_unrecognized(&mut errors, "phy_io.soapysdr", &soapy.extra);
+        }
+        if let Some(ref lb) = phy.loopback {
+            check_unrecognized(&mut errors, "phy_io.loopback", &lb.extra);
+        }
+        if let Some(ref agc) = phy.agc {
+            check_unrecognized(&mut errors, "phy_io.agc", &agc.extra);
+        }
+        if let Some(ref rec) = phy.recording {
+            check_unrecognized(&mut errors, "phy_io.recording", &rec.extra);
         }
     }
-    if !root.net_info.extra.is_empty() {
-        return Err(format!("Unrecognized fields in net_info: {:?}", sorted_keys(&root.net_info.extra)).into());
-    }
+    check_unrecognized(&mut errors, "net_info", &root.net_info.extra);
     if let Some(ref ci) = root.cell_info {
-        if !ci.extra.is_empty() {
-            return Err(format!("Unrecognized fields in cell_info: {:?}", sorted_keys(&ci.extra)).into());
+        check_unrecognized(&mut errors, "cell_info", &ci.extra);
+    }
+    if let Some(ref ac) = root.access_control {
+        check_unrecognized(&mut errors, "access_control", &ac.extra);
+    }
+    if let Some(ref pc) = root.power_control {
+        check_unrecognized(&mut errors, "power_control", &pc.extra);
+    }
+    if let Some(ref up) = root.uplane {
+        check_unrecognized(&mut errors, "uplane", &up.extra);
+    }
+    if let Some(ref sds) = root.sds {
+        check_unrecognized(&mut errors, "sds", &sds.extra);
+    }
+    if let Some(ref cc) = root.call_control {
+        check_unrecognized(&mut errors, "call_control", &cc.extra);
+    }
+    if let Some(ref ta) = root.temporary_addresses {
+        check_unrecognized(&mut errors, "temporary_addresses", &ta.extra);
+    }
+    if let Some(ref sds) = root.sds_autoresponder {
+        check_unrecognized(&mut errors, "sds_autoresponder", &sds.extra);
+        for (i, rule) in sds.rules.iter().enumerate() {
+            check_unrecognized(&mut errors, &format!("sds_autoresponder.rules[{}]", i), &rule.extra);
         }
     }
-    if let Some(ref ss) = root.stack_state {
-        if !ss.extra.is_empty() {
-            return Err(format!("Unrecognized fields in stack_state: {:?}", sorted_keys(&ss.extra)).into());
+    for (i, nc) in root.neighbor_cell.iter().enumerate() {
+        check_unrecognized(&mut errors, &format!("neighbor_cell[{}]", i), &nc.extra);
+    }
+    for (i, sub) in root.subscriber.iter().enumerate() {
+        check_unrecognized(&mut errors, &format!("subscriber[{}]", i), &sub.extra);
+        if root.subscriber[..i].iter().any(|other| other.issi == sub.issi) {
+            errors.push(ConfigError::new(format!("subscriber[{}].issi", i), format!("duplicate issi {}", sub.issi)));
         }
     }
+    if let Some(ref ss) = root.stack_state {
+        check_unrecognized(&mut errors, "stack_state", &ss.extra);
+    }
 
     // Build config from required and optional values
     let mut cfg = StackConfig {
@@ -52,6 +85,15 @@ pub fn from_toml_str(toml_str: &str) -> Result<SharedConfig, Box<dyn std::error:
         phy_io: CfgPhyIo::default(),
         net: CfgNetInfo { mcc: root.net_info.mcc, mnc: root.net_info.mnc },
         cell: CfgCellInfo::default(),
+        access_control: CfgAccessControl::default(),
+        power_control: None,
+        uplane: None,
+        sds_autoresponder: None,
+        sds: CfgSds::default(),
+        call_control: CfgCallControl::default(),
+        temporary_addresses: None,
+        neighbor_cells: Vec::new(),
+        subscribers: SubscriberDb::default(),
     };
 
     // Handle new phy_io structure
@@ -63,6 +105,94 @@ pub fn from_toml_str(toml_str: &str) -> Result<SharedConfig, Box<dyn std::error:
         apply_cell_info_patch(&mut cfg.cell, ci);
     }
 
+    if let Some(ac) = root.access_control {
+        cfg.access_control.allow = ac.allow.unwrap_or_default();
+        cfg.access_control.deny = ac.deny.unwrap_or_default();
+        cfg.access_control.allow_disable = ac.allow_disable.unwrap_or(false);
+        cfg.access_control.max_registered_ms = ac.max_registered_ms;
+        cfg.access_control.inactivity_timeout_s = ac.inactivity_timeout_s;
+        cfg.access_control.require_authentication = ac.require_authentication.unwrap_or(false);
+        cfg.access_control.detach_ack_status = ac.detach_ack_status;
+        cfg.access_control.vassi_range = ac.vassi_range;
+    }
+
+    if let Some(pc) = root.power_control {
+        let defaults = CfgPowerControl::default();
+        cfg.power_control = Some(CfgPowerControl {
+            rssi_min_dbm: pc.rssi_min_dbm.unwrap_or(defaults.rssi_min_dbm),
+            rssi_max_dbm: pc.rssi_max_dbm.unwrap_or(defaults.rssi_max_dbm),
+        });
+    }
+
+    if let Some(up) = root.uplane {
+        cfg.uplane = Some(CfgUplane {
+            frame_dir: up.frame_dir,
+            udp_target: up.udp_target,
+        });
+    }
+
+    if let Some(sds) = root.sds_autoresponder {
+        cfg.sds_autoresponder = Some(CfgSdsAutoresponder {
+            rules: sds.rules.into_iter().map(|r| CfgSdsRule {
+                match_status: r.match_status,
+                reply_status: r.reply_status,
+                reply_text: r.reply_text,
+            }).collect(),
+        });
+    }
+
+    if let Some(sds) = root.sds {
+        if let Some(v) = sds.text_coding {
+            cfg.sds.text_coding = v;
+        }
+        if let Some(v) = sds.sender_ssi {
+            cfg.sds.sender_ssi = v;
+        }
+    }
+
+    if let Some(cc) = root.call_control {
+        if let Some(v) = cc.preemption {
+            cfg.call_control.preemption = v;
+        }
+        if let Some(v) = cc.emergency_priority {
+            cfg.call_control.emergency_priority = v;
+        }
+        if let Some(v) = cc.t301_s {
+            cfg.call_control.t301_s = v;
+        }
+        if let Some(v) = cc.t302_s {
+            cfg.call_control.t302_s = v;
+        }
+        if let Some(v) = cc.t310_s {
+            cfg.call_control.t310_s = v;
+        }
+        if let Some(v) = cc.t310_reset_interval_s {
+            cfg.call_control.t310_reset_interval_s = Some(v);
+        }
+    }
+
+    if let Some(ta) = root.temporary_addresses {
+        cfg.temporary_addresses = Some(CfgTemporaryAddresses {
+            first: ta.first,
+            last: ta.last,
+        });
+    }
+
+    cfg.neighbor_cells = root.neighbor_cell.into_iter().map(|nc| CfgNeighborCell {
+        cell_id: nc.cell_id,
+        main_carrier: nc.main_carrier,
+        location_area: nc.location_area,
+        reselection_types: nc.reselection_types.unwrap_or(0),
+        synchronized: nc.synchronized.unwrap_or(false),
+    }).collect();
+
+    cfg.subscribers = root.subscriber.into_iter().map(|sub| CfgSubscriber {
+        issi: sub.issi,
+        subscriber_class: sub.subscriber_class,
+        services: sub.services.unwrap_or_else(|| SubscriberService::ALL.to_vec()),
+        groups: sub.groups.unwrap_or_default(),
+    }).collect::<Vec<_>>().into();
+
     // Mutable runtime state. Currently just a placeholder and not yet actually used
     let mut state = StackState::default();
     if let Some(ss) = root.stack_state {
@@ -71,9 +201,21 @@ pub fn from_toml_str(toml_str: &str) -> Result<SharedConfig, Box<dyn std::error:
         }
     }
 
+    errors.extend(cfg.check());
+    if !errors.is_empty() {
+        return Err(ConfigErrors(errors).into());
+    }
     Ok(SharedConfig::from_parts(cfg, state))
 }
 
+/// Reports each key in `extra`, the fields of `section` that were not recognized
+fn check_unrecognized(errors: &mut Vec<ConfigError>, section: &str, extra: &HashMap<String, Value>) {
+    for key in sorted_keys(extra) {
+        let path = if section.is_empty() { key.to_string() } else { format!("{}.{}", section, key) };
+        errors.push(ConfigError::new(path, "unrecognized field"));
+    }
+}
+
 /// Build `SharedConfig` from any reader.
 pub fn from_reader<R: Read>(reader: R) -> Result<SharedConfig, Box<dyn std::error::Error>> {
     let mut contents = String::new();
@@ -97,6 +239,12 @@ fn apply_phy_io_patch(dst: &mut CfgPhyIo, src: PhyIoDto) {
     dst.ul_rx_file = src.ul_rx_file;
     dst.ul_input_file = src.ul_input_file;
     dst.dl_input_file = src.dl_input_file;
+    if let Some(v) = src.rssi_offset_db {
+        dst.rssi_offset_db = v;
+    }
+    if let Some(v) = src.sample_format {
+        dst.sample_format = v;
+    }
 
     
     if let Some(soapy_dto) = src.soapysdr {
@@ -104,6 +252,10 @@ fn apply_phy_io_patch(dst: &mut CfgPhyIo, src: PhyIoDto) {
         soapy_cfg.ul_freq = soapy_dto.rx_freq;
         soapy_cfg.dl_freq = soapy_dto.tx_freq;
         soapy_cfg.ppm_err = soapy_dto.ppm_err;
+        soapy_cfg.rx_stream_mtu = soapy_dto.rx_stream_mtu;
+        soapy_cfg.rx_stream_buffers = soapy_dto.rx_stream_buffers;
+        soapy_cfg.tx_stream_mtu = soapy_dto.tx_stream_mtu;
+        soapy_cfg.tx_stream_buffers = soapy_dto.tx_stream_buffers;
         
         // Apply hardware-specific configurations
         if let Some(usrp_dto) = soapy_dto.iocfg_usrpb2xx {
@@ -140,6 +292,32 @@ fn apply_phy_io_patch(dst: &mut CfgPhyIo, src: PhyIoDto) {
         
         dst.soapysdr = Some(soapy_cfg);
     }
+
+    if let Some(lb_dto) = src.loopback {
+        dst.loopback = Some(CfgLoopback {
+            noise_snr_db: lb_dto.noise_snr_db,
+            delay_samples: lb_dto.delay_samples.unwrap_or_default(),
+            freq_offset_hz: lb_dto.freq_offset_hz.unwrap_or_default(),
+            clock_ppm: lb_dto.clock_ppm.unwrap_or_default(),
+        });
+    }
+
+    if let Some(agc_dto) = src.agc {
+        let defaults = CfgAgc::default();
+        dst.agc = Some(CfgAgc {
+            target_rms: agc_dto.target_rms.unwrap_or(defaults.target_rms),
+            attack_ms: agc_dto.attack_ms.unwrap_or(defaults.attack_ms),
+            decay_ms: agc_dto.decay_ms.unwrap_or(defaults.decay_ms),
+            max_gain_db: agc_dto.max_gain_db.unwrap_or(defaults.max_gain_db),
+        });
+    }
+
+    if let Some(rec_dto) = src.recording {
+        dst.recording = Some(CfgRecording {
+            file: rec_dto.file,
+            tx_file: rec_dto.tx_file,
+        });
+    }
 }
 
 fn apply_cell_info_patch(dst: &mut CfgCellInfo, ci: CellInfoDto) {
@@ -167,6 +345,8 @@ fn apply_cell_info_patch(dst: &mut CfgCellInfo, ci: CellInfoDto) {
     if let Some(v) = ci.subscriber_class {
         dst.subscriber_class = v;
     }
+    // Option
+    dst.ms_subscriber_class = ci.ms_subscriber_class;
     if let Some(v) = ci.registration {
         dst.registration = v;
     }
@@ -182,6 +362,9 @@ fn apply_cell_info_patch(dst: &mut CfgCellInfo, ci: CellInfoDto) {
     if let Some(v) = ci.migration {
         dst.migration = v;
     }
+    if let Some(v) = ci.protocol_edition {
+        dst.protocol_edition = v;
+    }
     if let Some(v) = ci.system_wide_services {
         dst.system_wide_services = v;
     }
@@ -200,6 +383,21 @@ fn apply_cell_info_patch(dst: &mut CfgCellInfo, ci: CellInfoDto) {
     if let Some(v) = ci.advanced_link {
         dst.advanced_link = v;
     }
+    if let Some(v) = ci.data_priority_service {
+        dst.data_priority_service = v;
+    }
+    if let Some(v) = ci.extended_advanced_links {
+        dst.extended_advanced_links = v;
+    }
+    if let Some(v) = ci.qos_negotiation_service {
+        dst.qos_negotiation_service = v;
+    }
+    if let Some(v) = ci.security_class {
+        dst.security_class = v;
+    }
+    // Option
+    dst.sck_number = ci.sck_number;
+    dst.cck_id = ci.cck_id;
     if let Some(v) = ci.system_code {
         dst.system_code = v;
     }
@@ -218,6 +416,25 @@ fn apply_cell_info_patch(dst: &mut CfgCellInfo, ci: CellInfoDto) {
     if let Some(v) = ci.frame_18_ext {
         dst.frame_18_ext = v;
     }
+    if let Some(v) = ci.mcch_timeslot {
+        dst.mcch_timeslot = v;
+    }
+    if let Some(v) = ci.secondary_control_channels {
+        dst.secondary_control_channels = v;
+    }
+    dst.cell_radius_km = ci.cell_radius_km;
+    if let Some(v) = ci.sync_period_multiframes {
+        dst.sync_period_multiframes = v;
+    }
+    if let Some(v) = ci.sysinfo_period_multiframes {
+        dst.sysinfo_period_multiframes = v;
+    }
+    if let Some(v) = ci.tx_off_frames {
+        dst.tx_off_frames = v;
+    }
+    if let Some(v) = ci.max_tm_sdu_bits {
+        dst.max_tm_sdu_bits = v;
+    }
 }
 
 fn sorted_keys(map: &HashMap<String, Value>) -> Vec<&str> {
@@ -230,6 +447,7 @@ fn sorted_keys(map: &HashMap<String, Value>) -> Vec<&str> {
 
 #[derive(Deserialize)]
 struct TomlConfigRoot {
+    /// Version of the configuration format
     config_version: String,
     stack_mode: StackMode,
     debug_log: Option<String>,
@@ -244,6 +462,36 @@ struct TomlConfigRoot {
     #[serde(default)]
     cell_info: Option<CellInfoDto>,
 
+    #[serde(default)]
+    access_control: Option<AccessControlDto>,
+
+    #[serde(default)]
+    power_control: Option<PowerControlDto>,
+
+    #[serde(default)]
+    uplane: Option<UplaneDto>,
+
+    #[serde(default)]
+    sds_autoresponder: Option<SdsAutoresponderDto>,
+
+    #[serde(default)]
+    sds: Option<SdsDto>,
+
+    #[serde(default)]
+    call_control: Option<CallControlDto>,
+
+    #[serde(default)]
+    temporary_addresses: Option<TemporaryAddressesDto>,
+
+    /// Array of tables, one `[[neighbor_cell]]` per CA neighbour cell
+    #[serde(default)]
+    neighbor_cell: Vec<NeighborCellDto>,
+
+    /// Array of tables, one `[[subscriber]]` per predefined subscriber
+    #[serde(default)]
+    subscriber: Vec<SubscriberDto>,
+
+    /// Initial values of the mutable stack state
     #[serde(default)]
     stack_state: Option<StackStatePatch>,
 
@@ -259,10 +507,52 @@ struct PhyIoDto {
     ul_rx_file: Option<String>,
     ul_input_file: Option<String>,
     dl_input_file: Option<String>,
+    rssi_offset_db: Option<f32>,
+    sample_format: Option<SampleFormat>,
     
     #[serde(default)]
     pub soapysdr: Option<SoapySdrDto>,
 
+    #[serde(default)]
+    pub loopback: Option<LoopbackDto>,
+
+    #[serde(default)]
+    pub agc: Option<AgcDto>,
+
+    #[serde(default)]
+    pub recording: Option<RecordingDto>,
+
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct AgcDto {
+    pub target_rms: Option<f32>,
+    pub attack_ms: Option<f32>,
+    pub decay_ms: Option<f32>,
+    pub max_gain_db: Option<f32>,
+
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct RecordingDto {
+    pub file: String,
+    pub tx_file: Option<String>,
+
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct LoopbackDto {
+    pub noise_snr_db: Option<f32>,
+    pub delay_samples: Option<usize>,
+    pub freq_offset_hz: Option<f32>,
+    pub clock_ppm: Option<f32>,
+
     #[serde(flatten)]
     extra: HashMap<String, Value>,
 }
@@ -272,6 +562,10 @@ struct SoapySdrDto {
     pub rx_freq: f64,
     pub tx_freq: f64,
     pub ppm_err: Option<f64>,
+    pub rx_stream_mtu: Option<usize>,
+    pub rx_stream_buffers: Option<usize>,
+    pub tx_stream_mtu: Option<usize>,
+    pub tx_stream_buffers: Option<usize>,
     
     #[serde(default)]
     pub iocfg_usrpb2xx: Option<UsrpB2xxDto>,
@@ -344,18 +638,27 @@ struct CellInfoDto {
     
 
     pub subscriber_class: Option<u16>,
+    pub ms_subscriber_class: Option<u16>,
 
     pub registration: Option<bool>,
     pub deregistration: Option<bool>,
     pub priority_cell: Option<bool>,
     pub no_minimum_mode: Option<bool>,
     pub migration: Option<bool>,
+    pub protocol_edition: Option<ProtocolEdition>,
     pub system_wide_services: Option<bool>,
     pub voice_service: Option<bool>,
     pub circuit_mode_data_service: Option<bool>,
     pub sndcp_service: Option<bool>,
     pub aie_service: Option<bool>,
     pub advanced_link: Option<bool>,
+    pub data_priority_service: Option<bool>,
+    pub extended_advanced_links: Option<bool>,
+    pub qos_negotiation_service: Option<bool>,
+
+    pub security_class: Option<SecurityClass>,
+    pub sck_number: Option<u8>,
+    pub cck_id: Option<u16>,
 
     pub system_code: Option<u8>,
     pub colour_code: Option<u8>,
@@ -364,6 +667,125 @@ struct CellInfoDto {
     pub u_plane_dtx: Option<bool>,
     pub frame_18_ext: Option<bool>,
 
+    pub mcch_timeslot: Option<u8>,
+    pub secondary_control_channels: Option<bool>,
+
+    pub cell_radius_km: Option<f32>,
+
+    pub sync_period_multiframes: Option<u8>,
+    pub sysinfo_period_multiframes: Option<u8>,
+
+    pub tx_off_frames: Option<Vec<u8>>,
+
+    pub max_tm_sdu_bits: Option<usize>,
+
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Default, Deserialize)]
+struct AccessControlDto {
+    pub allow: Option<Vec<u32>>,
+    pub deny: Option<Vec<u32>>,
+    pub allow_disable: Option<bool>,
+    pub max_registered_ms: Option<usize>,
+    pub inactivity_timeout_s: Option<u32>,
+    pub require_authentication: Option<bool>,
+    pub detach_ack_status: Option<u8>,
+    pub vassi_range: Option<(u32, u32)>,
+
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct PowerControlDto {
+    pub rssi_min_dbm: Option<f32>,
+    pub rssi_max_dbm: Option<f32>,
+
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct UplaneDto {
+    pub frame_dir: Option<String>,
+    pub udp_target: Option<String>,
+
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct SdsAutoresponderDto {
+    /// Array of tables, one `[[sds_autoresponder.rules]]` per rule
+    #[serde(default)]
+    pub rules: Vec<SdsRuleDto>,
+
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct SdsRuleDto {
+    pub match_status: u16,
+    pub reply_status: Option<u16>,
+    pub reply_text: Option<String>,
+
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct NeighborCellDto {
+    pub cell_id: u8,
+    pub main_carrier: u16,
+    pub location_area: Option<u16>,
+    pub reselection_types: Option<u8>,
+    pub synchronized: Option<bool>,
+
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct SdsDto {
+    pub text_coding: Option<SdsTextCoding>,
+    pub sender_ssi: Option<u32>,
+
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct CallControlDto {
+    pub preemption: Option<bool>,
+    pub emergency_priority: Option<u8>,
+    pub t301_s: Option<u32>,
+    pub t302_s: Option<u32>,
+    pub t310_s: Option<u32>,
+    pub t310_reset_interval_s: Option<u32>,
+
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct TemporaryAddressesDto {
+    pub first: u32,
+    pub last: u32,
+
+    #[serde(flatten)]
+    extra: HashMap<String, Value>,
+}
+
+#[derive(Deserialize)]
+struct SubscriberDto {
+    pub issi: u32,
+    pub subscriber_class: Option<u16>,
+    pub services: Option<Vec<SubscriberService>>,
+    pub groups: Option<Vec<u32>>,
+
     #[serde(flatten)]
     extra: HashMap<String, Value>,
 }
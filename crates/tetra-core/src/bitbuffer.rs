@@ -10,6 +10,12 @@ pub struct BitBuffer {
     flag_autoexpand: bool,   // if true, ignores end pointer on writes and reallocates buffer if insufficient capacity
 }
 
+/// Saved read/write position of a BitBuffer, see BitBuffer::checkpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Checkpoint {
+    pos: usize, // absolute
+}
+
 impl BitBuffer {
     /// Create a zeroed buffer capable of holding exactly `len_bits` bits.
     pub fn new(len_bits: usize) -> Self {
@@ -84,6 +90,15 @@ impl BitBuffer {
         buf
     }
 
+    /// Construct a BitBuffer from a string of '0'/'1' characters in which every octet is given
+    /// LSB-first, as used by some external capture tools. A trailing partial octet is also taken
+    /// to be LSB-first. The resulting buffer holds the bits in (MSB-first) TETRA order.
+    pub fn from_bitstr_lsb(bitstr: &str) -> Self {
+        let mut buf = BitBuffer::from_bitstr(bitstr);
+        buf.reverse_bits();
+        buf
+    }
+
     /// Construct a BitBuffer directly from a byte array of '0'/'1' bytes.
     pub fn from_bitarr(data: &[u8]) -> Self {
         
@@ -130,6 +145,25 @@ impl BitBuffer {
         buf
     }
 
+    /// Construct an independent BitBuffer holding a copy of `len_bits` bits, starting at `start_bit`
+    /// (relative to window start). The returned buffer is positioned at its start, so nested
+    /// contents (e.g. of a type-3/4 element) can be parsed without touching this buffer's pos.
+    /// Panics if the range exceeds the window.
+    pub fn slice(&self, start_bit: usize, len_bits: usize) -> BitBuffer {
+        assert!(self.start + start_bit + len_bits <= self.end,
+            "slice out of window: got [{},{}), allowed [{},{}]", self.start + start_bit, self.start + start_bit + len_bits, self.start, self.end);
+
+        let mut buf = BitBuffer::new(len_bits);
+        let mut offset = 0;
+        while offset < len_bits {
+            let chunk_bits = usize::min(len_bits - offset, 64);
+            buf.write_bits(self.read_bits_at_unchecked(self.start + start_bit + offset, chunk_bits), chunk_bits);
+            offset += chunk_bits;
+        }
+        buf.pos = buf.start;
+        buf
+    }
+
     /// Takes slice as parameter for output. Reads slice.len() bits from bitbuf[pos], and writes to output slice. 1 bit per byte.
     pub fn to_bitarr(&mut self, buf: &mut[u8]) {
         // TODO bounds check here, optimize performance
@@ -186,6 +220,20 @@ impl BitBuffer {
         Some(self.read_bits_at_unchecked(abs_pos, num_bits))
     }
 
+    /// Similar to peek_bits, but returns a ParseError::BufferEnded with the given error_string if not enough bits are available.
+    /// Does not advance pos, so a subsequent read_field of the same width returns the same value.
+    pub fn peek_field(&self, num_bits: usize, error_string: &'static str) -> Result<u64, PduParseErr> {
+        self.peek_bits(num_bits).ok_or(PduParseErr::BufferEnded { field: Some(error_string) })
+    }
+
+    /// Peeks the `num_bits` wide PDU type at pos and converts it to the PDU type enum `T`, without advancing.
+    /// Lets a dispatcher route on the PDU type, while the concrete parser still reads and validates it.
+    /// Returns PduParseErr::UnknownPduType with the raw value if the type is not defined for `T`.
+    pub fn peek_pdu_type<T: TryFrom<u64>>(&self, num_bits: usize) -> Result<T, PduParseErr> {
+        let raw = self.peek_field(num_bits, "pdu_type")?;
+        T::try_from(raw).map_err(|_| PduParseErr::UnknownPduType { found: raw })
+    }
+
     /// Read `num_bits` at the current pos, advancing on success.
     pub fn read_bits(&mut self, num_bits: usize) -> Option<u64> {
         let v = self.peek_bits_startoffset(self.pos - self.start, num_bits)?;
@@ -287,6 +335,25 @@ impl BitBuffer {
         Some(())
     }
 
+    /// Reverses the bit order within each octet of the window (start to end), converting between
+    /// MSB-first and LSB-first octet order. Octets are counted from start; a trailing partial
+    /// octet is reversed within itself. Applying this twice restores the original buffer.
+    /// Does not move pos.
+    pub fn reverse_bits(&mut self) {
+        let mut octet_start = self.start;
+        while octet_start < self.end {
+            let octet_len = min(8, self.end - octet_start);
+            for i in 0..octet_len / 2 {
+                let lo = octet_start + i;
+                let hi = octet_start + octet_len - 1 - i;
+                let (bit_lo, bit_hi) = (self.read_bit_at_unchecked(lo), self.read_bit_at_unchecked(hi));
+                self.write_bit_at_unchecked(lo, bit_hi);
+                self.write_bit_at_unchecked(hi, bit_lo);
+            }
+            octet_start += octet_len;
+        }
+    }
+
     /// Write a single bit to pos
     pub fn write_bit(&mut self, value: u8) {
         assert!(value == 0 || value == 1, "write_bit: value must be 0 or 1");
@@ -328,6 +395,27 @@ impl BitBuffer {
         }
     }
 
+    /// Pad up to `len_bits` (relative to window start) with fill bits: a single 1-bit followed by 0-bits.
+    /// Does nothing if the position is already at or beyond `len_bits`.
+    pub fn pad_to(&mut self, len_bits: usize) {
+        let pos = self.get_pos();
+        if pos >= len_bits {
+            return;
+        }
+        self.write_bit(1);
+        self.write_zeroes(len_bits - pos - 1);
+    }
+
+    /// Similar to write_bits, but returns a ParseError::ValueTooWide for the given field
+    /// if the value does not fit in num_bits, instead of panicking.
+    pub fn write_field(&mut self, value: u64, num_bits: usize, field: &'static str) -> Result<(), PduParseErr> {
+        if num_bits < 64 && value >> num_bits != 0 {
+            return Err(PduParseErr::ValueTooWide { field, value, bits: num_bits });
+        }
+        self.write_bits(value, num_bits);
+        Ok(())
+    }
+
     /// Write up to 64 bits, advancing pos. 
     /// If autoexpand is enabled, will advance end as well and/or realloc if buffer full
     /// If disables, panics if exceeds end. 
@@ -441,6 +529,11 @@ impl BitBuffer {
         self.end - self.pos
     }
 
+    /// Returns true if no bits are left to read, i.e. pos is at the window end.
+    pub fn at_end(&self) -> bool {
+        self.pos >= self.end
+    }
+
     /// Number of bits written, from start to pos.
     pub fn get_len_written(&self) -> usize {
         self.pos - self.start
@@ -458,6 +551,33 @@ impl BitBuffer {
         self.pos = abs;
     }
 
+    /// Seek `pos` to bit `bit` (relative to window start), like seek, but returns
+    /// PduParseErr::BufferEnded instead of panicking if `bit` lies beyond the window end.
+    /// On error, pos is left unchanged.
+    pub fn seek_abs(&mut self, bit: usize) -> Result<(), PduParseErr> {
+        if bit > self.get_len() {
+            return Err(PduParseErr::BufferEnded { field: None });
+        }
+        self.pos = self.start + bit;
+        Ok(())
+    }
+
+    /// Saves the current position, so a speculative parse can be rolled back with restore.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint { pos: self.pos }
+    }
+
+    /// Rewinds (or advances) pos to a previously taken checkpoint, like seek_abs, returning
+    /// PduParseErr::BufferEnded if the checkpoint lies outside the current window.
+    /// On error, pos is left unchanged.
+    pub fn restore(&mut self, checkpoint: Checkpoint) -> Result<(), PduParseErr> {
+        if checkpoint.pos < self.start || checkpoint.pos > self.end {
+            return Err(PduParseErr::BufferEnded { field: None });
+        }
+        self.pos = checkpoint.pos;
+        Ok(())
+    }
+
     /// Move the current bit‐pointer by `offset` bits (can be negative).
     /// Panics if the resulting position would lie outside the window `[start..=end]`.
     pub fn seek_rel(&mut self, offset: isize) {
@@ -623,6 +743,17 @@ impl BitBuffer {
     fn read_bit_at_unchecked(&self, bit_pos: usize) -> u8 {
         (self.buffer[bit_pos / 8] >> (7 - (bit_pos % 8))) & 1
     }
+
+    /// Writes 1 bit at absolute `bit_pos`, without bounds checks.
+    /// **Caller must ensure** `bit_pos < end`.
+    fn write_bit_at_unchecked(&mut self, bit_pos: usize, value: u8) {
+        let mask = 1 << (7 - (bit_pos % 8));
+        if value == 1 {
+            self.buffer[bit_pos / 8] |= mask;
+        } else {
+            self.buffer[bit_pos / 8] &= !mask;
+        }
+    }
 }
 
 
@@ -680,6 +811,14 @@ mod tests {
         bb.write_bits(0b11111, 4);
     }
 
+    #[test]
+    fn test_write_field_too_wide() {
+        let mut bb = BitBuffer::new_autoexpand(8);
+        assert_eq!(bb.write_field(0b1111, 4, "field"), Ok(()));
+        assert_eq!(bb.write_field(0b10000, 4, "field"), Err(PduParseErr::ValueTooWide { field: "field", value: 0b10000, bits: 4 }));
+        assert_eq!(bb.get_pos(), 4);
+    }
+
     #[test]
     fn test_write_autoexpand() {
         let mut bb = BitBuffer::new_autoexpand(10);
@@ -746,6 +885,19 @@ mod tests {
         assert_eq!(bb.into_bytes(), vec![0xAA, 0xAA, 0xAA]);
     }
 
+    #[test]
+    fn test_pad_to() {
+        let mut bb = BitBuffer::new_autoexpand(16);
+        bb.write_bits(0b101, 3);
+        bb.pad_to(8);
+        assert_eq!(bb.to_bitstr(), "10110000");
+
+        // Already at or past the target, nothing is written
+        bb.pad_to(8);
+        bb.pad_to(4);
+        assert_eq!(bb.get_len(), 8);
+    }
+
     #[test]
     fn test_dump_hex() {
         let mut bb = BitBuffer::from_vec(vec![0xAB, 0xCD]);
@@ -866,4 +1018,175 @@ mod tests {
         println!("{}", bb.dump_bin());
         assert_eq!(bb.to_bitstr(), "001100000011000000110000001100000011000000110000001100000011000000001100");
     }
+
+    #[test]
+    fn test_reverse_bits_involution() {
+        let orig = "1011000111110000101";
+        let mut bb = BitBuffer::from_bitstr(orig);
+        bb.seek(5);
+        bb.reverse_bits();
+        assert_eq!(bb.to_bitstr(), "1000110100001111101");
+        assert_eq!(bb.get_pos(), 5);
+        bb.reverse_bits();
+        assert_eq!(bb.to_bitstr(), orig);
+
+        // Octets are counted from the window start, not from the underlying byte boundary
+        let mut bb = BitBuffer::from_bitstr("0011000000001");
+        bb.set_raw_pos(1);
+        bb.set_raw_start(1);
+        bb.reverse_bits();
+        assert_eq!(bb.to_bitstr(), "000001101000");
+        bb.reverse_bits();
+        assert_eq!(bb.to_bitstr(), "011000000001");
+    }
+
+    #[test]
+    fn test_from_bitstr_lsb() {
+        let msb = "110100100000000111";
+        let mut reversed = BitBuffer::from_bitstr(msb);
+        reversed.reverse_bits();
+
+        let lsb = BitBuffer::from_bitstr_lsb(&reversed.to_bitstr());
+        assert_eq!(lsb.to_bitstr(), msb);
+        assert_eq!(lsb.get_pos(), 0);
+
+        // A single LSB-first octet 0x01 is 0x80 in TETRA order
+        let mut bb = BitBuffer::from_bitstr_lsb("00000001");
+        assert_eq!(bb.read_bits(8), Some(0x80));
+    }
+
+    #[test]
+    fn test_slice() {
+        // 4-bit header, 70-bit element contents, 6-bit trailer
+        let contents = "1100101011110000110011001010101011110000111100001111000011110000101011";
+        let bitstr = format!("1001{}111111", contents);
+        let mut parent = BitBuffer::from_bitstr(&bitstr);
+        assert_eq!(parent.read_bits(4), Some(0b1001));
+
+        let mut nested = parent.slice(4, 70);
+        assert_eq!(nested.get_pos(), 0);
+        assert_eq!(nested.get_len(), 70);
+        assert_eq!(nested.to_bitstr(), contents);
+
+        // Parsing the slice does not move the parent's read position
+        assert_eq!(nested.read_bits(8), Some(0b11001010));
+        nested.seek(66);
+        assert_eq!(nested.read_bits(4), Some(0b1011));
+        assert!(nested.read_bits(1).is_none());
+        assert_eq!(parent.get_pos(), 4);
+
+        // The slice is an independent copy
+        nested.seek(0);
+        nested.write_zeroes(8);
+        assert_eq!(parent.to_bitstr(), bitstr);
+
+        // Slice offsets are relative to the parent's window
+        parent.set_raw_start(4);
+        assert_eq!(parent.slice(0, 8).to_bitstr(), "11001010");
+        assert_eq!(parent.slice(70, 6).to_bitstr(), "111111");
+        assert_eq!(parent.slice(76, 0).get_len(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "slice out of window")]
+    fn test_slice_out_of_window() {
+        let bb = BitBuffer::from_bitstr("10110011");
+        let _ = bb.slice(4, 5);
+    }
+
+    #[test]
+    fn test_peek_field() {
+        let mut bb = BitBuffer::from_bitstr("1011001");
+        bb.seek(1);
+        assert_eq!(bb.peek_field(4, "field"), Ok(0b0110));
+        assert_eq!(bb.get_pos(), 1);
+        assert_eq!(bb.read_field(4, "field"), Ok(0b0110));
+        assert_eq!(bb.peek_field(3, "field"), Err(PduParseErr::BufferEnded { field: Some("field") }));
+        assert_eq!(bb.get_pos(), 5);
+    }
+
+    #[test]
+    fn test_peek_pdu_type() {
+        #[derive(Debug, PartialEq)]
+        enum TestPduType { A, B }
+        impl TryFrom<u64> for TestPduType {
+            type Error = ();
+            fn try_from(x: u64) -> Result<Self, ()> {
+                match x { 1 => Ok(TestPduType::A), 2 => Ok(TestPduType::B), _ => Err(()) }
+            }
+        }
+
+        let mut bb = BitBuffer::from_bitstr("1011");
+        assert_eq!(bb.peek_pdu_type::<TestPduType>(2), Ok(TestPduType::B));
+        assert_eq!(bb.get_pos(), 0);
+        assert_eq!(bb.read_bits(2), Some(2));
+
+        assert_eq!(bb.peek_pdu_type::<TestPduType>(2), Err(PduParseErr::UnknownPduType { found: 3 }));
+        assert_eq!(bb.get_pos(), 2);
+        bb.seek(4);
+        assert_eq!(bb.peek_pdu_type::<TestPduType>(2), Err(PduParseErr::BufferEnded { field: Some("pdu_type") }));
+    }
+
+    #[test]
+    fn test_seek_abs() {
+        let mut bb = BitBuffer::from_bitstr("0000111100001111");
+        bb.set_raw_pos(2);
+        bb.set_raw_start(2);
+
+        // Save, read forward, restore
+        bb.seek_abs(4).unwrap();
+        let saved = bb.get_pos();
+        assert_eq!(saved, 4);
+        assert_eq!(bb.read_bits(6), Some(0b110000));
+        assert_eq!(bb.get_pos(), 10);
+        bb.seek_abs(saved).unwrap();
+        assert_eq!(bb.get_pos(), 4);
+        assert_eq!(bb.get_raw_pos(), 6);
+
+        // Seek backward to window start and forward to window end
+        bb.seek_abs(0).unwrap();
+        assert_eq!(bb.read_bits(2), Some(0b00));
+        bb.seek_abs(14).unwrap();
+        assert!(bb.at_end());
+
+        // Out of range seeks are rejected and leave pos untouched
+        assert_eq!(bb.seek_abs(15), Err(PduParseErr::BufferEnded { field: None }));
+        assert_eq!(bb.get_pos(), 14);
+    }
+
+    #[test]
+    fn test_checkpoint_restore() {
+        // Speculative parser: 3-bit type, 2-bit value that must not be 3
+        fn parse(buf: &mut BitBuffer) -> Result<(u64, u64), PduParseErr> {
+            let pdu_type = buf.read_field(3, "pdu_type")?;
+            let value = buf.read_field(2, "value")?;
+            if value == 3 {
+                return Err(PduParseErr::InvalidValue { field: "value", value });
+            }
+            Ok((pdu_type, value))
+        }
+
+        let mut bb = BitBuffer::from_bitstr("110101110001");
+        bb.seek(2);
+        let checkpoint = bb.checkpoint();
+        assert!(parse(&mut bb).is_err());
+        assert_eq!(bb.get_pos(), 7);
+
+        // Failed parse is rolled back to exactly where the checkpoint was taken
+        bb.restore(checkpoint).unwrap();
+        assert_eq!(bb.get_pos(), 2);
+        assert_eq!(bb.checkpoint(), checkpoint);
+
+        // Alternative parse from the same position
+        assert_eq!(bb.read_bits(4), Some(0b0101));
+        bb.restore(checkpoint).unwrap();
+        assert_eq!(bb.to_bitstr(), "110101110001");
+        assert_eq!(bb.get_pos(), 2);
+
+        // A checkpoint outside a narrowed window is rejected and leaves pos untouched
+        bb.seek(6);
+        bb.set_raw_start(4);
+        assert_eq!(bb.restore(checkpoint), Err(PduParseErr::BufferEnded { field: None }));
+        assert_eq!(bb.get_raw_pos(), 6);
+    }
 }
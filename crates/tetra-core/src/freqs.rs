@@ -74,11 +74,11 @@ impl FreqInfo {
     }
 
     /// Get the standardized duplex spacing in hz for the current frequency band and a given 
-    /// duplex spacing table index, as given in the Sysinfo message
+    /// duplex spacing table index, as given in the Sysinfo message.
+    /// Returns None if the setting is not defined for the band.
     pub fn get_default_duplex_spacing(band: u8, duplex_setting: u8) -> Option<u32> {
-        assert!(duplex_setting < 8, "Invalid duplex setting {}", duplex_setting);
-        let duplex_spacing = TETRA_DUPLEX_SPACING[duplex_setting as usize][band as usize];
-        duplex_spacing.map(|v| v * 1000)
+        let duplex_spacing = (*TETRA_DUPLEX_SPACING.get(duplex_setting as usize)?.get(band as usize)?)?;
+        Some(duplex_spacing * 1000)
     }
 
     /// Get the downlink and uplink frequencies for this instance
@@ -127,4 +127,39 @@ mod tests {
         assert_eq!(dlfreq - duplex_spacing, ulfreq);
         assert!(!f1.reverse_operation);
     }
+
+    #[test]
+    fn test_freq_offset() {
+        for (id, hz) in [(0, 0), (1, 6250), (2, -6250), (3, 12500)] {
+            assert_eq!(FreqInfo::freq_offset_id_to_hz(id), Some(hz));
+            assert_eq!(FreqInfo::freq_offset_hz_to_id(hz), Some(id));
+            let (dlfreq, ulfreq) = FreqInfo::from_components(4, 1521, hz, false, 4, None).unwrap().get_freqs();
+            assert_eq!(dlfreq as i64, 438_025_000 + hz as i64);
+            assert_eq!(dlfreq - ulfreq, 5_000_000);
+        }
+        assert_eq!(FreqInfo::freq_offset_id_to_hz(4), None);
+        assert_eq!(FreqInfo::freq_offset_hz_to_id(3125), None);
+    }
+
+    #[test]
+    fn test_freqinfo_reverse_operation() {
+        // Same carrier and duplex spacing, the uplink moves from below to above the downlink
+        let normal = FreqInfo::from_components(4, 1521, 0, false, 4, None).unwrap();
+        let reverse = FreqInfo::from_components(4, 1521, 0, true, 4, None).unwrap();
+        assert_eq!(normal.get_freqs(), (438_025_000, 433_025_000));
+        assert_eq!(reverse.get_freqs(), (438_025_000, 443_025_000));
+        assert_eq!(reverse.duplex_spacing_val, normal.duplex_spacing_val);
+    }
+
+    #[test]
+    fn test_default_duplex_spacing() {
+        assert_eq!(FreqInfo::get_default_duplex_spacing(1, 1), Some(4_500_000));
+        assert_eq!(FreqInfo::get_default_duplex_spacing(1, 3), None);
+        assert_eq!(FreqInfo::get_default_duplex_spacing(4, 4), Some(5_000_000));
+        assert_eq!(FreqInfo::get_default_duplex_spacing(4, 6), None);
+        // Setting 2 is for simplex use in every band
+        assert_eq!(FreqInfo::get_default_duplex_spacing(15, 2), Some(0));
+        assert_eq!(FreqInfo::get_default_duplex_spacing(16, 2), None);
+        assert_eq!(FreqInfo::get_default_duplex_spacing(4, 8), None);
+    }
 }
@@ -5,12 +5,14 @@
 //! - TdmaTime for TDMA frame timing
 //! - Address types (ISSI, GSSI, etc.)
 //! - PHY types (PhyBlockNum, BurstType, etc.)
+//! - PduLog for recording primitives with their TDMA position
 //! - Common macros and debug utilities
 
 pub mod address;
 pub mod bitbuffer;
 pub mod debug;
 pub mod freqs;
+pub mod pdu_log;
 pub mod pdu_parse_error;
 pub mod phy_types;
 pub mod tdma_time;
@@ -21,10 +23,10 @@ pub mod direction;
 
 // Re-export commonly used items
 pub use address::*;
-pub use bitbuffer::BitBuffer;
+pub use bitbuffer::{BitBuffer, Checkpoint};
 pub use pdu_parse_error::PduParseErr;
 pub use phy_types::*;
-pub use tdma_time::TdmaTime;
+pub use tdma_time::{TdmaSubslotTime, TdmaTime};
 pub use tetra_common::*;
 pub use direction::Direction;
 
@@ -0,0 +1,171 @@
+use core::fmt;
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+use crate::{Direction, TdmaTime, TetraAddress};
+use crate::tetra_entities::TetraEntity;
+
+
+/// Implemented by PDUs that carry the address of a party other than the MS the PDU is exchanged with,
+/// such as the called party in a U-SETUP or the SSI assigned in a D-LOCATION UPDATE ACCEPT.
+/// For downlink CMCE PDUs the calling party is the MS that originated the call or message,
+/// for uplink CMCE PDUs the called party is the MS or group it is directed to.
+/// Short number addresses and external subscriber numbers do not resolve to an SSI.
+pub trait AddressedPdu {
+    /// The SSI the PDU concerns, by default the calling party, or else the called party
+    fn addressed_ssi(&self) -> Option<u32> {
+        self.calling_ssi().or_else(|| self.called_ssi()).map(|address| address.ssi)
+    }
+
+    fn calling_ssi(&self) -> Option<TetraAddress> {
+        None
+    }
+
+    fn called_ssi(&self) -> Option<TetraAddress> {
+        None
+    }
+}
+
+/// A single primitive passing between two entities, with the TDMA position it was created at
+#[derive(Debug, Clone)]
+pub struct PduLogEntry {
+    pub time: TdmaTime,
+    /// Subslot the primitive relates to, 1 or 2, if it concerns a half-slot block
+    pub subslot: Option<u8>,
+    /// Air interface direction the primitive relates to
+    pub dir: Direction,
+    /// Entity the primitive was delivered to
+    pub entity: TetraEntity,
+    /// Address the primitive concerns, if any
+    pub ssi: Option<u32>,
+    /// Address carried inside the PDU itself, see AddressedPdu
+    pub addressed_ssi: Option<u32>,
+    pub pdu: String,
+}
+
+impl fmt::Display for PduLogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.subslot {
+            Some(subslot) => write!(f, "{}", self.time.with_subslot(subslot))?,
+            None => write!(f, "{}", self.time)?,
+        }
+        write!(f, " {:?} {:?}", self.dir, self.entity)?;
+        if let Some(ssi) = self.ssi {
+            write!(f, " ssi {}", ssi)?;
+        }
+        if let Some(ssi) = self.addressed_ssi {
+            write!(f, " addressed {}", ssi)?;
+        }
+        write!(f, " {}", self.pdu)
+    }
+}
+
+/// Bounded, in-memory log of primitives for forensic analysis.
+/// When full, the oldest entries are dropped.
+pub struct PduLog {
+    entries: VecDeque<PduLogEntry>,
+    capacity: usize,
+}
+
+impl PduLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    pub fn record(&mut self, entry: PduLogEntry) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back(entry);
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &PduLogEntry> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Entries sent to or received from the given SSI, or carrying it as an address inside the PDU
+    pub fn filter_by_ssi(&self, ssi: u32) -> Vec<&PduLogEntry> {
+        self.entries.iter()
+            .filter(|e| e.ssi == Some(ssi) || e.addressed_ssi == Some(ssi))
+            .collect()
+    }
+
+    /// Entries with `from <= time <= to`, taking hyperframe wrap-around into account
+    pub fn filter_time(&self, from: TdmaTime, to: TdmaTime) -> impl Iterator<Item = &PduLogEntry> {
+        self.entries.iter().filter(move |e| e.time.diff(from) >= 0 && to.diff(e.time) >= 0)
+    }
+
+    /// Formats all entries, one per line
+    pub fn dump(&self) -> String {
+        let mut out = String::new();
+        for entry in &self.entries {
+            let _ = writeln!(out, "{}", entry);
+        }
+        out
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(time: TdmaTime, ssi: Option<u32>, addressed_ssi: Option<u32>) -> PduLogEntry {
+        PduLogEntry { time, subslot: None, dir: Direction::Ul, entity: TetraEntity::Mm, ssi, addressed_ssi, pdu: "UDemo".to_string() }
+    }
+
+    #[test]
+    fn test_filters() {
+        let t0 = TdmaTime::default();
+        let mut log = PduLog::new(3);
+        log.record(entry(t0, Some(1000), None));
+        log.record(entry(t0.add_timeslots(1), None, None));
+        log.record(entry(t0.add_timeslots(2), Some(2000), None));
+        log.record(entry(t0.add_timeslots(3), Some(1000), None));
+
+        // Oldest entry was dropped
+        assert_eq!(log.len(), 3);
+        assert_eq!(log.filter_by_ssi(1000).len(), 1);
+        assert_eq!(log.filter_time(t0.add_timeslots(1), t0.add_timeslots(2)).count(), 2);
+        assert!(log.dump().lines().next().unwrap().ends_with(":01:01:2 Ul Mm UDemo"));
+    }
+
+    #[test]
+    fn test_display_subslot() {
+        let t0 = TdmaTime { t: 3, f: 18, m: 7, h: 1234 };
+        let mut half_slot = entry(t0, Some(1000), None);
+        half_slot.subslot = Some(2);
+        assert_eq!(half_slot.to_string(), " 1234:07:18:3:2 Ul Mm ssi 1000 UDemo");
+        assert_eq!(entry(t0, None, None).to_string(), " 1234:07:18:3 Ul Mm UDemo");
+    }
+
+    #[test]
+    fn test_filter_by_addressed_ssi() {
+        let t0 = TdmaTime::default();
+        let mut log = PduLog::new(10);
+        // MS 1000 calls 3000, MS 2000 registers, 3000 is called back by 1000
+        log.record(entry(t0, Some(1000), Some(3000)));
+        log.record(entry(t0.add_timeslots(1), Some(2000), None));
+        log.record(entry(t0.add_timeslots(2), Some(3000), Some(1000)));
+
+        let filtered = log.filter_by_ssi(3000);
+        assert_eq!(filtered.len(), 2);
+        assert!(filtered.iter().all(|e| e.ssi != Some(2000)));
+        assert_eq!(log.filter_by_ssi(2000).len(), 1);
+        assert!(log.filter_by_ssi(4000).is_empty());
+    }
+}
@@ -1,6 +1,8 @@
 #[derive(Debug, PartialEq, Eq)]
 pub enum PduParseErr {
     InvalidPduType { expected: u64, found: u64 },
+    /// PDU type `found` is not defined, e.g. reserved, so no parser exists for it
+    UnknownPduType { found: u64 },
     BufferEnded { field: Option<&'static str> },
     InvalidTrailingMbitValue,
     InvalidElemId { found: u64 },
@@ -9,6 +11,8 @@ pub enum PduParseErr {
     InconsistentLength { expected: usize, found: usize },
     Inconsistency { field: &'static str, reason: &'static str },
     NotImplemented { field: Option<&'static str> },
+    /// Value does not fit in the `bits` wide field it is to be serialized into
+    ValueTooWide { field: &'static str, value: u64, bits: usize },
 }
 
 /// Checks whether a PDU type value matches the expected value. If not, returns PduParseErr::InvalidPduType
@@ -59,3 +59,60 @@ pub enum TrainingSequence {
     #[default]
     NotFound = 0,
 }
+
+/// Reception quality of a demodulated burst
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct RxQuality {
+    /// Received signal strength over the burst. Measured in dBFS by the PHY
+    /// and converted to dBm with the configured calibration offset.
+    pub rssi_dbm: f32,
+    /// Bit error rate estimated from errors in the training sequence, between 0 and 1
+    pub ber_estimate: f32,
+    /// Arrival time of the burst relative to its nominal position in the slot, in symbols.
+    /// Positive when the burst arrives late, e.g. due to propagation delay to a distant MS.
+    pub delay_symbols: f32,
+}
+
+impl RxQuality {
+    /// Whether the burst arrived later than `max_delay_symbols`
+    pub fn exceeds_path_delay(&self, max_delay_symbols: f32) -> bool {
+        self.delay_symbols > max_delay_symbols
+    }
+}
+
+/// Largest tolerated burst arrival delay if no cell radius is configured, in symbols (about 58 km)
+pub const DEFAULT_MAX_PATH_DELAY_SYMBOLS: f32 = 7.0;
+
+/// Distance to an MS per symbol of round trip propagation delay, in km
+pub const KM_PER_DELAY_SYMBOL: f32 = 299_792.0 / 18000.0 / 2.0;
+
+/// Round trip propagation delay to an MS at `distance_km`, in symbols
+pub fn path_delay_symbols(distance_km: f32) -> f32 {
+    distance_km / KM_PER_DELAY_SYMBOL
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_delay_symbols() {
+        assert_eq!(path_delay_symbols(0.0), 0.0);
+        // One symbol is 1/18000 s, during which a signal travels about 16.7 km
+        assert!((path_delay_symbols(8.33) - 1.0).abs() < 1e-3);
+        assert!((path_delay_symbols(30.0) - 3.6).abs() < 1e-2);
+        assert!((path_delay_symbols(58.3) * KM_PER_DELAY_SYMBOL - 58.3).abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_exceeds_path_delay() {
+        let max = path_delay_symbols(20.0);
+        let quality = |delay_symbols| RxQuality { delay_symbols, ..Default::default() };
+        assert!(!quality(0.0).exceeds_path_delay(max));
+        assert!(!quality(-0.2).exceeds_path_delay(max));
+        assert!(!quality(path_delay_symbols(19.0)).exceeds_path_delay(max));
+        assert!(quality(path_delay_symbols(21.0)).exceeds_path_delay(max));
+        assert!(quality(6.0).exceeds_path_delay(max));
+    }
+}
@@ -57,6 +57,18 @@ impl TdmaTime {
         TdmaTime{ t, f, m, h }
     }
 
+    /// Number of timeslots since 0:01:01:1, for logging and cross-referencing.
+    /// Counts up to TIME_INT_WRAP, after which the hyperframe number wraps back to 0.
+    pub fn to_total_slots(self) -> u64 {
+        self.to_int() as u64
+    }
+
+    /// Converts a timeslot count since 0:01:01:1 into a TdmaTime,
+    /// wrapping the hyperframe number like add_timeslots does
+    pub fn from_total_slots(slots: u64) -> TdmaTime {
+        TdmaTime::from_int((slots % TIME_INT_WRAP as u64) as i32)
+    }
+
     /// Add a number of timeslots to a TdmaTime
     pub fn add_timeslots(self, num_slots: i32) -> TdmaTime {
         TdmaTime::from_int(self.to_int() + num_slots)
@@ -96,17 +108,39 @@ impl TdmaTime {
         self.f == 18 && self.t == 4 - ((self.m + 1) % 4)
         // self.f == 18 && (self.m + self.t) % 4 == 3
     }
+
+    /// Time of subslot `subslot` (1 or 2) of this timeslot, displayed as HF:MF:FN:TN:STN
+    pub fn with_subslot(self, subslot: u8) -> TdmaSubslotTime {
+        TdmaSubslotTime { time: self, subslot }
+    }
+}
+
+/// Time of a subslot (half slot), see TdmaTime::with_subslot
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct TdmaSubslotTime {
+    pub time: TdmaTime,
+    /// Subslot, 1 or 2
+    pub subslot: u8,
+}
+
+impl fmt::Display for TdmaSubslotTime {
+    /// Formats as HF:MF:FN:TN:STN, with the hyperframe number padded like TdmaTime
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.time, self.subslot)
+    }
 }
 
 impl fmt::Display for TdmaTime {
+    /// Formats as HF:MF:FN:TN, with the hyperframe number padded to a fixed width for log columns.
+    /// A TdmaTime does not track subslots, use with_subslot to include the subslot number.
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:5}/{:02}/{:02}/{}", self.h, self.m, self.f, self.t)    
+        write!(f, "{:5}:{:02}:{:02}:{}", self.h, self.m, self.f, self.t)
     }
 }
 
 impl fmt::Debug for TdmaTime {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:5}/{:02}/{:02}/{}", self.h, self.m, self.f, self.t)    
+        fmt::Display::fmt(self, f)
     }
 }
 
@@ -153,4 +187,40 @@ mod tests {
             assert_eq!(TdmaTime::from_int(time_int).diff(TdmaTime::from_int(0)), time_int);
         }
     }
+
+    #[test]
+    fn test_total_slots() {
+        assert_eq!(TdmaTime::default().to_total_slots(), 0);
+        assert_eq!(TdmaTime { t: 2, f: 1, m: 2, h: 1 }.to_total_slots(), 1 + 4 * 18 + 4 * 18 * 60);
+        assert_eq!(TdmaTime::from_total_slots(TIME_INT_WRAP as u64 - 1), TdmaTime { t: 4, f: 18, m: 60, h: 65535 });
+        assert_eq!(TdmaTime::from_total_slots(TIME_INT_WRAP as u64), TdmaTime::default());
+
+        let times = [
+            TdmaTime::default(),
+            TdmaTime { t: 3, f: 17, m: 42, h: 1234 },
+            TdmaTime { t: 4, f: 18, m: 60, h: 65535 },
+        ];
+        for time in times {
+            assert_eq!(TdmaTime::from_total_slots(time.to_total_slots()), time);
+            for n in [0, 1, 71, 4320, 123456789] {
+                assert_eq!(TdmaTime::from_total_slots(time.to_total_slots() + n as u64), time.add_timeslots(n), "{:?} + {}", time, n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(TdmaTime::default().to_string(), "    0:01:01:1");
+        assert_eq!(TdmaTime { t: 3, f: 18, m: 7, h: 1234 }.to_string(), " 1234:07:18:3");
+        // Wrapping into the next frame, multiframe and hyperframe
+        assert_eq!(TdmaTime { t: 4, f: 1, m: 1, h: 0 }.add_timeslots(1).to_string(), "    0:01:02:1");
+        assert_eq!(TdmaTime { t: 4, f: 18, m: 1, h: 0 }.add_timeslots(1).to_string(), "    0:02:01:1");
+        assert_eq!(TdmaTime { t: 4, f: 18, m: 60, h: 9 }.add_timeslots(1).to_string(), "   10:01:01:1");
+        assert_eq!(TdmaTime { t: 4, f: 18, m: 60, h: 65535 }.to_string(), "65535:60:18:4");
+        assert_eq!(TdmaTime { t: 4, f: 18, m: 60, h: 65535 }.add_timeslots(1).to_string(), "    0:01:01:1");
+
+        assert_eq!(TdmaTime::default().with_subslot(1).to_string(), "    0:01:01:1:1");
+        assert_eq!(TdmaTime { t: 4, f: 18, m: 60, h: 65535 }.with_subslot(2).to_string(), "65535:60:18:4:2");
+        assert_eq!(format!("{:?}", TdmaTime::default()), "    0:01:01:1");
+    }
 }
@@ -1,4 +1,6 @@
-#[derive(Debug, PartialEq, Eq)]
+use crate::PduParseErr;
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Type4FieldGeneric {
     pub field_id: u64,
     pub len:   usize,
@@ -7,7 +9,7 @@ pub struct Type4FieldGeneric {
     pub data:  u64,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct Type3FieldGeneric {
     pub field_id: u64,
     pub len:   usize,
@@ -15,6 +17,46 @@ pub struct Type3FieldGeneric {
     pub data:  u64,
 }
 
+/// Annex H Proprietary element, as carried in the CMCE and MM Proprietary type3 element.
+/// The element starts with an 8-bit proprietary element owner, identifying the vendor,
+/// followed by vendor-specific contents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProprietaryElement {
+    /// 8 bits, proprietary element owner
+    pub owner: u8,
+    /// Length of the vendor-specific payload in bits (may exceed 56, in which case payload is truncated)
+    pub payload_len: usize,
+    /// Up to 56 bits of vendor-specific payload (later bits are discarded)
+    pub payload: u64,
+}
+
+impl Type3FieldGeneric {
+    /// Type3 element identifier for Proprietary, identical for CMCE and MM
+    pub const PROPRIETARY_ELEM_ID: u64 = 15;
+
+    /// Interpret this element as a proprietary element, splitting off the leading owner byte.
+    pub fn as_proprietary(&self) -> Result<ProprietaryElement, PduParseErr> {
+        if self.field_id != Self::PROPRIETARY_ELEM_ID {
+            return Err(PduParseErr::InvalidElemId { found: self.field_id });
+        }
+        if self.len < 8 {
+            return Err(PduParseErr::InconsistentLength { expected: 8, found: self.len });
+        }
+
+        // data holds the first min(len, 64) bits of the element, right-aligned
+        let stored_bits = self.len.min(64);
+        let owner = (self.data >> (stored_bits - 8)) as u8;
+        let payload_bits = stored_bits - 8;
+        let payload = if payload_bits == 0 { 0 } else { self.data & ((1u64 << payload_bits) - 1) };
+
+        Ok(ProprietaryElement {
+            owner,
+            payload_len: self.len - 8,
+            payload,
+        })
+    }
+}
+
 /// Helper functions for dealing with type2, type3 and type4 fields for MLE, CMCE, MM and SNDCP PDUs.
 pub mod delimiters {
     use crate::{bitbuffer::BitBuffer, pdu_parse_error::PduParseErr};
@@ -406,7 +448,21 @@ pub mod typed {
 
         tracing::trace!("parse_type4_header got header for {:2}, len {}, count {}: {}", id, len_bits, num_elems, buffer.dump_bin());
 
-        Ok(Some((num_elems, len_bits-6)))
+        let contents_len = check_type4_len(buffer, len_bits)?;
+        Ok(Some((num_elems, contents_len)))
+    }
+
+    /// Bounds the length of a type-4 element, read from its header, to what the PDU can hold.
+    /// The length includes the 6-bit element count, and may not run past the end of the PDU.
+    /// Returns the length of the element contents, following the element count.
+    fn check_type4_len(buffer: &BitBuffer, len_bits: usize) -> Result<usize, PduParseErr> {
+        if len_bits < 6 {
+            return Err(PduParseErr::InvalidValue { field: "parse_type4_header len_bits", value: len_bits as u64 });
+        }
+        if len_bits - 6 > buffer.get_len_remaining() {
+            return Err(PduParseErr::BufferEnded { field: Some("parse_type4_header contents") });
+        }
+        Ok(len_bits - 6)
     }
 
     /// Parse a Type-4 element into a Vec of structs that implement `from_bitbuf`.
@@ -434,11 +490,16 @@ pub mod typed {
             }
             Some((num_elems, len_bits)) => {
                 // Field is present, and we've gout our total lenght and number of elements
-                let mut elems = Vec::with_capacity(num_elems);
+                let mut elems = Vec::new();
                 let start_pos = buffer.get_pos();
                 
-                // Parse all elements into array structs
+                // Parse all elements into array structs. Each element takes at least one bit,
+                // so a count exceeding the element length is caught before reading past it
                 for _ in 0..num_elems {
+                    if buffer.get_pos() >= start_pos + len_bits {
+                        tracing::warn!("Type4 element {} holds fewer than the announced {} elements", id, num_elems);
+                        return Err(PduParseErr::Inconsistency { field: "type4 num_elems", reason: "more elements than fit in the element length" });
+                    }
                     let elem = parser(buffer)?;
                     elems.push(elem);
                 }
@@ -578,4 +639,43 @@ pub mod typed {
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BitBuffer;
+    use crate::typed_pdu_fields::typed;
+
+    #[test]
+    fn test_proprietary_owner_and_payload() {
+        // mbit, id 15, len 24, owner 0x5A, payload 0xBEEF, trailing mbit 0
+        let mut buf = BitBuffer::from_bitstr("11111000000110000101101010111110111011110");
+        let field = typed::parse_type3_generic(true, &mut buf, 15u64).unwrap().unwrap();
+        let prop = field.as_proprietary().unwrap();
+        assert_eq!(prop.owner, 0x5A);
+        assert_eq!(prop.payload_len, 16);
+        assert_eq!(prop.payload, 0xBEEF);
+    }
+
+    #[test]
+    fn test_type4_length_checked() {
+        // mbit, id 5, len 2047, 1 element, followed by only a few bits
+        let mut buf = BitBuffer::from_bitstr("10101111111111110000010000");
+        let result = typed::parse_type4_generic(true, &mut buf, 5u64);
+        assert_eq!(result, Err(PduParseErr::BufferEnded { field: Some("parse_type4_header contents") }));
+
+        // len shorter than the element count itself
+        let mut buf = BitBuffer::from_bitstr("1010100000000011000001");
+        let result = typed::parse_type4_generic(true, &mut buf, 5u64);
+        assert_eq!(result, Err(PduParseErr::InvalidValue { field: "parse_type4_header len_bits", value: 3 }));
+    }
+
+    #[test]
+    fn test_proprietary_rejects_other_elements() {
+        let field = Type3FieldGeneric { field_id: 3, len: 16, data: 0x1234 };
+        assert_eq!(field.as_proprietary(), Err(PduParseErr::InvalidElemId { found: 3 }));
+        let field = Type3FieldGeneric { field_id: 15, len: 4, data: 0x1 };
+        assert!(field.as_proprietary().is_err());
+    }
+}
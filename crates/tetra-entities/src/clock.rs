@@ -0,0 +1,69 @@
+//! Source of the TDMA time the MessageRouter passes to entities on each tick.
+//! The stack normally advances by one timeslot per tick. Tests can instead step
+//! time by hand, to hit timer expiry at an exact timeslot.
+
+use std::sync::{Arc, Mutex};
+
+use tetra_core::TdmaTime;
+
+pub trait Clock: Send {
+    /// Current downlink TDMA time
+    fn now(&self) -> TdmaTime;
+
+    /// Sets the current downlink TDMA time
+    fn set(&mut self, ts: TdmaTime);
+
+    /// Called by the router at the end of each tick
+    fn tick(&mut self);
+}
+
+/// Advances by one timeslot per tick. Used by the running stack.
+#[derive(Debug, Default)]
+pub struct TdmaClock {
+    ts: TdmaTime,
+}
+
+impl Clock for TdmaClock {
+    fn now(&self) -> TdmaTime {
+        self.ts
+    }
+
+    fn set(&mut self, ts: TdmaTime) {
+        self.ts = ts;
+    }
+
+    fn tick(&mut self) {
+        self.ts = self.ts.add_timeslots(1);
+    }
+}
+
+/// Only moves when advanced by hand. Clones share the same time, so a test keeps
+/// a clone to step the time of a router it handed the clock to.
+#[derive(Debug, Clone, Default)]
+pub struct ManualClock {
+    ts: Arc<Mutex<TdmaTime>>,
+}
+
+impl ManualClock {
+    pub fn new(ts: TdmaTime) -> Self {
+        Self { ts: Arc::new(Mutex::new(ts)) }
+    }
+
+    /// Moves time forward by `timeslots`
+    pub fn advance(&self, timeslots: i32) {
+        let mut ts = self.ts.lock().expect("ManualClock mutex poisoned");
+        *ts = ts.add_timeslots(timeslots);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> TdmaTime {
+        *self.ts.lock().expect("ManualClock mutex poisoned")
+    }
+
+    fn set(&mut self, ts: TdmaTime) {
+        *self.ts.lock().expect("ManualClock mutex poisoned") = ts;
+    }
+
+    fn tick(&mut self) {}
+}
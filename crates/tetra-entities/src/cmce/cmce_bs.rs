@@ -1,13 +1,25 @@
+use std::collections::VecDeque;
 
-use tetra_config::SharedConfig;
+use tetra_config::{SharedConfig, SubscriberService};
 use tetra_core::tetra_entities::TetraEntity;
 use tetra_core::{Sap, TdmaTime, unimplemented_log};
 use crate::{MessageQueue, TetraEntityTrait};
 use tetra_saps::{SapMsg, SapMsgInner};
+use tetra_saps::control::group_control::GroupControl;
 
 use tetra_pdus::cmce::enums::cmce_pdu_type_ul::CmcePduTypeUl;
 
-use super::subentities::cc_bs::CcBsSubentity;
+use tetra_saps::lcmc::CallId;
+
+use super::components::call_timers::{CallTimer, CallTimers};
+use super::components::circuit_mgr::CircuitMgr;
+use super::components::group_poll::GroupPoll;
+use super::components::group_registry::GroupRegistry;
+use super::components::location_store::{LocationStore, TimedLocation};
+use super::components::paging_queue::{PagingCmd, PagingQueue};
+use super::components::sds_protocol::SdsProtocol;
+
+use super::subentities::cc_bs::{CcBsSubentity, DISCONNECT_CAUSE_CONGESTION, DISCONNECT_CAUSE_NOT_REACHABLE, GroupCall, IndividualSetupRequest, SetupRequest};
 use super::subentities::sds_bs::SdsBsSubentity;
 use super::subentities::ss_bs::SsBsSubentity;
 
@@ -17,18 +29,218 @@ pub struct CmceBs {
     cc: CcBsSubentity,
     sds: SdsBsSubentity,
     ss: SsBsSubentity,
+
+    paging: PagingQueue,
+
+    /// Group attachments, as reported by MM
+    groups: GroupRegistry,
 }
 
 impl CmceBs {
     pub fn new(config: SharedConfig) -> Self {
+        let mut cc = CcBsSubentity::new();
+        let c = config.config();
+        cc.set_traffic_timeslots((1..=4).filter(|&ts| !c.cell.is_control_timeslot(ts)).collect());
+        if let Some(ta) = &c.temporary_addresses {
+            cc.set_temporary_address_range(ta.first, ta.last);
+        }
+        cc.set_late_entry(c.cell.late_entry_supported);
+        cc.set_protocol_edition(c.cell.protocol_edition);
+        cc.set_preemption(c.call_control.preemption);
+        cc.set_emergency_priority(c.call_control.emergency_priority);
+        cc.set_call_timers(CallTimers::new(&c.call_control));
         Self { 
             config,
             sds: SdsBsSubentity::new(),
-            cc: CcBsSubentity::new(),
+            cc,
             ss: SsBsSubentity::new(),
+            paging: PagingQueue::new(),
+            groups: GroupRegistry::new(),
          }
     }
 
+    /// Sets up a call to group `gssi`, if any MS that may use group calls is attached to it.
+    /// Returns the call identifier.
+    pub fn setup_group_call(&mut self, queue: &mut MessageQueue, gssi: u32, dltime: TdmaTime) -> Option<CallId> {
+        self.setup_group_call_with_priority(queue, gssi, 0, dltime)
+    }
+
+    /// Sets up a call to group `gssi` with call priority `priority` (0-15). With pre-emption
+    /// enabled, an emergency call releases a lower priority call if no circuit is free.
+    pub fn setup_group_call_with_priority(&mut self, queue: &mut MessageQueue, gssi: u32, priority: u8, dltime: TdmaTime) -> Option<CallId> {
+        let members = self.group_members(gssi);
+        if members.is_empty() {
+            tracing::info!("Not setting up call to group {}: no MSs attached", gssi);
+            return None;
+        }
+        tracing::info!("Setting up call to group {} with members {:?}", gssi, members);
+        self.cc.setup_group_call(queue, gssi, members, priority, dltime)
+    }
+
+    /// MSs attached to group `gssi` that may use group calls
+    fn group_members(&self, gssi: u32) -> Vec<u32> {
+        let config = self.config.config();
+        let mut members = self.groups.members(gssi);
+        members.retain(|&issi| config.subscribers.allows_service(issi, SubscriberService::GroupCall));
+        members
+    }
+
+    /// Group call with identifier `call_id`, if it is a group call
+    pub fn group_call(&self, call_id: CallId) -> Option<&GroupCall> {
+        self.cc.group_call(call_id)
+    }
+
+    /// Queues the calls requested by MSs in U-SETUP. A call to a group without members is
+    /// rejected at once.
+    fn admit_setup_requests(&mut self, queue: &mut MessageQueue, dltime: TdmaTime) {
+        for request in self.cc.take_received_setups() {
+            if self.group_members(request.gssi).is_empty() {
+                tracing::info!("Rejecting call from {} to group {}: no MSs attached", request.calling_ssi, request.gssi);
+                self.cc.reject_setup_request(queue, &request, DISCONNECT_CAUSE_NOT_REACHABLE, dltime);
+                continue;
+            }
+            self.cc.queue_setup_request(queue, request, dltime);
+        }
+    }
+
+    /// Sets up the calls requested by MSs for which a circuit is available, emergency calls first
+    fn serve_setup_requests(&mut self, queue: &mut MessageQueue, dltime: TdmaTime) {
+        while let Some(request) = self.cc.take_setup_request() {
+            // The group may have lost its members while the request was queued
+            let members = self.group_members(request.gssi);
+            if members.is_empty() {
+                tracing::info!("Rejecting call from {} to group {}: no MSs attached", request.calling_ssi, request.gssi);
+                self.cc.reject_setup_request(queue, &request, DISCONNECT_CAUSE_NOT_REACHABLE, dltime);
+                continue;
+            }
+            tracing::info!("Setting up call requested by {}: {:?}", request.calling_ssi, request);
+            if self.cc.setup_requested_group_call(queue, &request, members, dltime).is_none() {
+                self.cc.reject_setup_request(queue, &request, DISCONNECT_CAUSE_CONGESTION, dltime);
+            }
+        }
+    }
+
+    /// Sets up a call to the MS with `issi`, if it may use individual calls. The MS is paged
+    /// with the D-SETUP of the call until it alerts or connects. Returns the call identifier.
+    pub fn setup_individual_call(&mut self, queue: &mut MessageQueue, issi: u32, dltime: TdmaTime) -> Option<CallId> {
+        if !self.config.config().subscribers.allows_service(issi, SubscriberService::IndividualCall) {
+            tracing::info!("Not setting up call to {}: service not allowed for subscriber", issi);
+            return None;
+        }
+        if self.paging.is_paging(issi) {
+            tracing::info!("Not setting up call to {}: already being paged", issi);
+            return None;
+        }
+        let call_id = self.cc.setup_individual_call(queue, issi, dltime)?;
+        self.paging.enqueue(issi, call_id);
+        Some(call_id)
+    }
+
+    /// Sets up the individual calls requested by MSs in U-SETUP. The called MS is paged as for
+    /// `setup_individual_call`; a call that can not be set up is rejected at once.
+    fn setup_requested_individual_calls(&mut self, queue: &mut MessageQueue, dltime: TdmaTime) {
+        for request in self.cc.take_received_individual_setups() {
+            if let Err(cause) = self.try_setup_requested_individual_call(queue, &request, dltime) {
+                self.cc.reject_individual_setup(queue, &request, cause, dltime);
+            }
+        }
+    }
+
+    /// Sets up the individual call of `request`, or returns the disconnect cause to reject it with
+    fn try_setup_requested_individual_call(&mut self, queue: &mut MessageQueue, request: &IndividualSetupRequest, dltime: TdmaTime) -> Result<CallId, u8> {
+        let IndividualSetupRequest { calling_ssi, called_ssi, .. } = *request;
+        if !self.config.config().subscribers.allows_service(called_ssi, SubscriberService::IndividualCall) {
+            tracing::info!("Rejecting call from {} to {}: service not allowed for subscriber", calling_ssi, called_ssi);
+            return Err(DISCONNECT_CAUSE_NOT_REACHABLE);
+        }
+        if self.paging.is_paging(called_ssi) {
+            tracing::info!("Rejecting call from {} to {}: already being paged", calling_ssi, called_ssi);
+            return Err(DISCONNECT_CAUSE_NOT_REACHABLE);
+        }
+        tracing::info!("Setting up call requested by {}: {:?}", calling_ssi, request);
+        let call_id = self.cc.setup_requested_individual_call(queue, request, dltime).ok_or(DISCONNECT_CAUSE_CONGESTION)?;
+        self.paging.enqueue(called_ssi, call_id);
+        Ok(call_id)
+    }
+
+    /// Whether call `call_id` is set up and not released yet
+    pub fn is_call_active(&self, call_id: CallId) -> bool {
+        self.cc.is_active(call_id)
+    }
+
+    /// Whether call timer `timer` runs for call `call_id`
+    pub fn is_call_timer_running(&self, call_id: CallId, timer: CallTimer) -> bool {
+        self.cc.is_timer_running(call_id, timer)
+    }
+
+    /// Calls requested by MSs that wait for a circuit, in the order they will be set up
+    pub fn setup_requests(&self) -> &VecDeque<SetupRequest> {
+        self.cc.setup_requests()
+    }
+
+    /// Priority of call `call_id`, if it is active
+    pub fn call_priority(&self, call_id: CallId) -> Option<u8> {
+        self.cc.call_priority(call_id)
+    }
+
+    /// Temporary address assigned to call `call_id`, if any
+    pub fn temporary_address(&self, call_id: CallId) -> Option<u32> {
+        self.cc.temporary_address(call_id)
+    }
+
+    /// Polls the members of acknowledged group call `call_id`. Their U-INFO poll
+    /// responses are tallied in `group_poll`. Returns false if there is no such group call.
+    pub fn poll_group_call(&mut self, queue: &mut MessageQueue, call_id: CallId, dltime: TdmaTime) -> bool {
+        self.cc.send_poll_request(queue, call_id, dltime)
+    }
+
+    /// Poll of group call `call_id`, if one was started
+    pub fn group_poll(&self, call_id: CallId) -> Option<&GroupPoll> {
+        self.cc.poll(call_id)
+    }
+
+    pub fn paging(&self) -> &PagingQueue {
+        &self.paging
+    }
+
+    /// Sends `text` to `issi` as a simple text message, in the configured coding scheme.
+    /// Returns false if the text can not be represented in it or is too long.
+    pub fn send_text_sds(&mut self, queue: &mut MessageQueue, issi: u32, text: &str, dltime: TdmaTime) -> bool {
+        let sds = self.config.config().sds.clone();
+        self.sds.send_text(queue, sds.sender_ssi, issi, text, sds.text_coding, dltime)
+    }
+
+    /// Number of type 4 SDS received from MSs using `protocol`
+    pub fn sds_received(&self, protocol: SdsProtocol) -> usize {
+        self.sds.received(protocol)
+    }
+
+    /// Locations reported by MSs over LIP
+    pub fn locations(&self) -> &LocationStore {
+        self.sds.locations()
+    }
+
+    /// Latest location reported by `ssi` over LIP, if any
+    pub fn ms_location(&self, ssi: u32) -> Option<&TimedLocation> {
+        self.sds.locations().latest(ssi)
+    }
+
+    /// Sets up a test group call on a free timeslot
+    pub fn run_call_test(&mut self, queue: &mut MessageQueue, dltime: TdmaTime) {
+        self.cc.run_call_test(queue, dltime);
+    }
+
+    /// Circuits currently managed by the call control sub-entity
+    pub fn circuits(&self) -> &CircuitMgr {
+        self.cc.circuits()
+    }
+
+    /// Keeps the group registry in step with the group attachments reported by MM
+    fn rx_group_control(&mut self, change: &GroupControl) {
+        tracing::debug!("<- MM {:?}", change);
+        self.groups.apply(change);
+    }
+
     pub fn rx_lcmc_mle_unitdata_ind(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_lcmc_mle_unitdata_ind");
         
@@ -43,6 +255,17 @@ impl CmceBs {
             return;
         };
 
+        let ssi = prim.received_tetra_address.ssi;
+        if let Some(q) = prim.rx_quality {
+            tracing::debug!("{:?} from {}: rssi {:.1} dBm, ber {:.3}", pdu_type, ssi, q.rssi_dbm, q.ber_estimate);
+        }
+
+        // A paged MS answers the D-SETUP by alerting, connecting or restoring the call
+        if matches!(pdu_type, CmcePduTypeUl::UAlert | CmcePduTypeUl::UConnect | CmcePduTypeUl::UCallRestore)
+            && let Some(call_id) = self.paging.respond(ssi) {
+            self.cc.proceed_paged_call(call_id, message.dltime);
+        }
+
         match pdu_type {
             CmcePduTypeUl::UAlert |
             CmcePduTypeUl::UConnect |
@@ -54,11 +277,21 @@ impl CmceBs {
             CmcePduTypeUl::UTxCeased |
             CmcePduTypeUl::UTxDemand |
             CmcePduTypeUl::UCallRestore => {
+                let dltime = message.dltime;
                 self.cc.route_xx_deliver(_queue, message);
+                if pdu_type == CmcePduTypeUl::USetup {
+                    self.admit_setup_requests(_queue, dltime);
+                    self.serve_setup_requests(_queue, dltime);
+                    self.setup_requested_individual_calls(_queue, dltime);
+                }
             },
             CmcePduTypeUl::USdsData => {
-                unimplemented_log!("{:?}", pdu_type);
-                // self.sds.route_xx_deliver(_queue, message);
+                let config = self.config.config();
+                if !config.subscribers.allows_service(ssi, SubscriberService::Sds) {
+                    tracing::info!("Dropping SDS from {}: service not allowed for subscriber", ssi);
+                    return;
+                }
+                self.sds.route_xx_deliver(_queue, message, config.sds_autoresponder.as_ref());
             },
             CmcePduTypeUl::UFacility => {
                 unimplemented_log!("{:?}", pdu_type);
@@ -90,6 +323,14 @@ impl TetraEntityTrait for CmceBs {
 
         // Propagate tick to subentities
         self.cc.tick_start(queue, ts);
+        self.serve_setup_requests(queue, ts);
+
+        for cmd in self.paging.tick_start(ts) {
+            match cmd {
+                PagingCmd::SendPage(ssi, call_id) => self.cc.send_page(queue, ssi, call_id, ts),
+                PagingCmd::Abandon(ssi, call_id) => self.cc.abandon_paged_call(queue, ssi, call_id, ts),
+            }
+        }
     }
 
     fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
@@ -97,13 +338,13 @@ impl TetraEntityTrait for CmceBs {
         tracing::debug!("rx_prim: {:?}", message);
         // tracing::debug!(ts=%message.dltime, "rx_prim: {:?}", message);
         
-        // There is only one SAP for CMCE
-        assert!(message.sap == Sap::LcmcSap);
-
-        match message.msg {
-            SapMsgInner::LcmcMleUnitdataInd(_) => {
+        match (message.sap, &message.msg) {
+            (Sap::LcmcSap, SapMsgInner::LcmcMleUnitdataInd(_)) => {
                 self.rx_lcmc_mle_unitdata_ind(queue, message);
             }
+            (Sap::Control, SapMsgInner::MmGroupControl(change)) => {
+                self.rx_group_control(change);
+            }
             _ => {
                 panic!();
             }
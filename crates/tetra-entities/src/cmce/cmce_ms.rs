@@ -1,10 +1,11 @@
 use tetra_config::SharedConfig;
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::Sap;
+use tetra_core::{Sap, unimplemented_log};
 use crate::{MessageQueue, TetraEntityTrait};
 use tetra_saps::{SapMsg, SapMsgInner};
 
 use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
+use tetra_pdus::cmce::pdus::cmce_pdu_dl::CmcePduDl;
 
 use super::subentities::cc_ms::CcMsSubentity;
 use super::subentities::sds_ms::SdsMsSubentity;
@@ -33,13 +34,13 @@ impl CmceMs {
         
         // Handle the incoming unit data indication
         let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else { panic!(); };
-        let Some(bits) = prim.sdu.peek_bits(5) else {
-            tracing::warn!("insufficient bits: {}", prim.sdu.dump_bin());
-            return;
-        };
-        let Ok(pdu_type) = CmcePduTypeDl::try_from(bits) else {
-            tracing::warn!("invalid pdu type: {} in {}", bits, prim.sdu.dump_bin());
-            return;
+        // An undefined PDU type only invalidates this PDU, not any others received in the same MAC block
+        let pdu_type = match CmcePduDl::peek_pdu_type(&prim.sdu) {
+            Ok(pdu_type) => pdu_type,
+            Err(e) => {
+                tracing::warn!("skipping pdu: {:?} in {}", e, prim.sdu.dump_bin());
+                return;
+            }
         };
 
         match pdu_type {
@@ -66,8 +67,8 @@ impl CmceMs {
             CmcePduTypeDl::DTxWait => {
                 self.cc.route_rd_deliver(queue, message);
             }
-            _ => {
-                panic!();
+            CmcePduTypeDl::CmceFunctionNotSupported => {
+                unimplemented_log!("{}", pdu_type);
             }
         }
     }
@@ -0,0 +1,154 @@
+//! Call control timers run by the BS for each call (EN 300 392-2 clause 14.6).
+//! Timers are kept per call identifier and checked on every tick; an expired timer
+//! means the call stalled and is to be released.
+
+use std::collections::HashMap;
+
+use tetra_config::CfgCallControl;
+use tetra_core::TdmaTime;
+use tetra_pdus::cmce::enums::call_timeout::CallTimeout;
+use tetra_saps::lcmc::CallId;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CallTimer {
+    /// Call set-up: D-SETUP was sent to the called MS, waiting for U-ALERT or U-CONNECT
+    T301,
+    /// Alerting: the called MS sent U-ALERT, waiting for U-CONNECT
+    T302,
+    /// Call length: the call is released when it expires
+    T310,
+    /// Not a timer of the standard: interval at which T310 of an active call is reset
+    /// by D-INFO, if configured
+    T310Reset,
+}
+
+/// Converts seconds to timeslots, a timeslot lasts 85/6 ms
+fn secs_to_slots(secs: u32) -> i32 {
+    (secs as i64 * 1200 / 17).min(i32::MAX as i64) as i32
+}
+
+pub struct CallTimers {
+    t301: i32,
+    t302: i32,
+    t310: i32,
+    t310_reset: Option<i32>,
+    /// Call time-out advertised to MSs, covering T310
+    call_timeout: CallTimeout,
+    /// Expiry time of each running timer
+    running: HashMap<(CallId, CallTimer), TdmaTime>,
+}
+
+impl CallTimers {
+    pub fn new(config: &CfgCallControl) -> Self {
+        Self {
+            t301: secs_to_slots(config.t301_s),
+            t302: secs_to_slots(config.t302_s),
+            t310: secs_to_slots(config.t310_s),
+            t310_reset: config.t310_reset_interval_s.map(secs_to_slots),
+            call_timeout: CallTimeout::at_least_secs(config.t310_s),
+            running: HashMap::new(),
+        }
+    }
+
+    /// Duration of `timer` in timeslots, None if it is not used
+    pub fn duration(&self, timer: CallTimer) -> Option<i32> {
+        match timer {
+            CallTimer::T301 => Some(self.t301),
+            CallTimer::T302 => Some(self.t302),
+            CallTimer::T310 => Some(self.t310),
+            CallTimer::T310Reset => self.t310_reset,
+        }
+    }
+
+    /// Call time-out sent in D-SETUP and D-CONNECT: the shortest one an MS would not
+    /// release the call before the BS does on T310 expiry
+    pub fn call_timeout(&self) -> CallTimeout {
+        self.call_timeout
+    }
+
+    /// Starts `timer` for call `call_id`, restarting it if it is running.
+    /// Does nothing if the timer is not used.
+    pub fn start(&mut self, call_id: CallId, timer: CallTimer, now: TdmaTime) {
+        if let Some(duration) = self.duration(timer) {
+            self.running.insert((call_id, timer), now.add_timeslots(duration));
+        }
+    }
+
+    /// Starts the timers running for as long as call `call_id` is active
+    pub fn start_call(&mut self, call_id: CallId, now: TdmaTime) {
+        self.start(call_id, CallTimer::T310, now);
+        self.start(call_id, CallTimer::T310Reset, now);
+    }
+
+    pub fn stop(&mut self, call_id: CallId, timer: CallTimer) {
+        self.running.remove(&(call_id, timer));
+    }
+
+    /// Stops all timers of call `call_id`
+    pub fn stop_all(&mut self, call_id: CallId) {
+        self.running.retain(|(id, _), _| *id != call_id);
+    }
+
+    pub fn is_running(&self, call_id: CallId, timer: CallTimer) -> bool {
+        self.running.contains_key(&(call_id, timer))
+    }
+
+    /// Removes and returns the timers expired at `now`, earliest first
+    pub fn tick(&mut self, now: TdmaTime) -> Vec<(CallId, CallTimer)> {
+        let mut expired: Vec<_> = self.running.iter()
+            .filter(|(_, expiry)| expiry.age(now) >= 0)
+            .map(|(&key, &expiry)| (expiry.age(now), key))
+            .collect();
+        expired.sort_by_key(|(age, (call_id, _))| (-age, *call_id));
+        expired.into_iter()
+            .map(|(_, key)| {
+                self.running.remove(&key);
+                key
+            })
+            .collect()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timer_expiry() {
+        let config = CfgCallControl { t301_s: 1, t302_s: 2, ..Default::default() };
+        let mut timers = CallTimers::new(&config);
+        assert_eq!(timers.duration(CallTimer::T301), Some(70));
+        assert_eq!(timers.duration(CallTimer::T310Reset), None);
+
+        let t0 = TdmaTime::default();
+        timers.start(5, CallTimer::T301, t0);
+        timers.start(6, CallTimer::T302, t0);
+        timers.start(7, CallTimer::T301, t0);
+        timers.stop(7, CallTimer::T301);
+
+        assert!(timers.tick(t0.add_timeslots(69)).is_empty());
+        assert_eq!(timers.tick(t0.add_timeslots(70)), vec![(5, CallTimer::T301)]);
+        assert!(!timers.is_running(5, CallTimer::T301));
+        assert!(timers.is_running(6, CallTimer::T302));
+
+        timers.stop_all(6);
+        assert!(timers.tick(t0.add_timeslots(1000)).is_empty());
+
+        // T310 reset is only started if an interval is configured
+        timers.start_call(8, t0);
+        assert!(timers.is_running(8, CallTimer::T310));
+        assert!(!timers.is_running(8, CallTimer::T310Reset));
+    }
+
+    #[test]
+    fn test_advertised_call_timeout() {
+        let timeout = |t310_s| CallTimers::new(&CfgCallControl { t310_s, ..Default::default() }).call_timeout();
+        assert_eq!(timeout(10), CallTimeout::T30s);
+        assert_eq!(timeout(30), CallTimeout::T30s);
+        assert_eq!(timeout(31), CallTimeout::T45s);
+        assert_eq!(timeout(600), CallTimeout::T10m);
+        assert_eq!(timeout(1800), CallTimeout::T30m);
+        assert_eq!(timeout(1801), CallTimeout::Infinite);
+    }
+}
@@ -12,6 +12,9 @@ pub enum DlCallState {
     /// Call is created but setup messages have not yet been sent
     Init,
 
+    /// Called MS is being paged, D-SETUP is sent once it responds
+    Paging,
+
     /// Call is active.
     /// Traffic is sent either downlink, uplink, or both
     CallActive,
@@ -188,6 +191,10 @@ pub enum RemoteEvent {
     NewCall,
     CallActive,
     TxCeased,
+    /// Paged MS responded, see PagingQueue
+    PageResponse,
+    /// Paged MS did not respond to any page
+    PageTimeout,
 
     
 }
@@ -348,6 +355,7 @@ impl Call {
 
                 }
             }
+            DlCallState::Paging |
             DlCallState::Disconnected => {
                 self.discard(pdu);
             } 
@@ -359,11 +367,28 @@ impl Call {
             (DlCallState::Init, RemoteEvent::NewCall) => {
                 assert!(self.callee_is_local);
                 assert!(self.current_caller_is_local == false);
+                if self.is_group {
+                    self.send_d_setup(self.callee_ssi);
+                    self.signal_umac_dl_circuit();
+                    self.state = DlCallState::CallActive;
+                } else {
+                    // An individual callee is paged first
+                    self.send_page(self.callee_ssi);
+                    self.state = DlCallState::Paging;
+                }
+            },
+
+            (DlCallState::Paging, RemoteEvent::PageResponse) => {
                 self.send_d_setup(self.callee_ssi);
                 self.signal_umac_dl_circuit();
                 self.state = DlCallState::CallActive;
             },
 
+            (DlCallState::Paging, RemoteEvent::PageTimeout) => {
+                tracing::info!("Callee {} did not respond to paging", self.callee_ssi);
+                self.state = DlCallState::Disconnected;
+            },
+
             (DlCallState::CallActive, RemoteEvent::CallActive) => {
                 self.send_d_call_proceeding(self.current_caller_issi);
             },
@@ -390,6 +415,10 @@ impl Call {
         false
     }
 
+    fn send_page(&mut self, ssi: u32) {
+        tracing::info!("Paging SSI {}", ssi);
+    }
+
     fn send_d_setup(&mut self, ssi: u32) {
         tracing::info!("Sending D-SETUP to SSI {}", ssi);
     }
@@ -434,6 +463,23 @@ mod test {
             false);
         call.handle_pdu(super::UlCallPdu::UAlert);
     }
+
+    #[test]
+    fn test_dl_call_paging() {
+        debug::setup_logging_verbose();
+        let mut call = Call::new(1001, false, 2001, true, false, true);
+        call.handle_remote_event(RemoteEvent::NewCall);
+        assert_eq!(call.state, DlCallState::Paging);
+        call.handle_pdu(UlCallPdu::UConnect);
+        assert_eq!(call.state, DlCallState::Paging);
+        call.handle_remote_event(RemoteEvent::PageResponse);
+        assert_eq!(call.state, DlCallState::CallActive);
+
+        let mut call = Call::new(1001, false, 2001, true, false, true);
+        call.handle_remote_event(RemoteEvent::NewCall);
+        call.handle_remote_event(RemoteEvent::PageTimeout);
+        assert_eq!(call.state, DlCallState::Disconnected);
+    }
 }
 
 // // ========= FSM-facing types =========
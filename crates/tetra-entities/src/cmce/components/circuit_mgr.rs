@@ -12,7 +12,7 @@ pub enum CircuitErr {
 }
 
 pub enum CircuitMgrCmd {
-    SendDSetup(CallId, u8), // call id and usage number
+    SendDSetup(CallId, u8, u8), // call id, timeslot and usage number
     SendClose(CallId, CmceCircuit),
 }
 
@@ -32,6 +32,17 @@ pub struct CircuitMgr {
     /// 5-bit usage number. Values 0-3 are reserved. 
     pub next_usage_number: u8,
 
+    /// Number of calls a circuit was allocated for since start, counted once per call identifier
+    pub calls_handled: u64,
+
+    /// Timeslots that may carry traffic, i.e. those not reserved for common control signalling
+    traffic_ts: Vec<u8>,
+
+    /// Whether D-SETUPs are repeated for the lifetime of a call, so MSs can enter it late
+    late_entry: bool,
+
+    /// Age in timeslots at which Ul-only circuits, which have no call timers, are closed
+    ul_only_max_age: i32,
 }
 
 impl CircuitMgr {
@@ -43,9 +54,29 @@ impl CircuitMgr {
             tx_data: [VecDeque::new(), VecDeque::new(), VecDeque::new(), VecDeque::new()],
             next_call_identifier: 4,
             next_usage_number: 4,
+            calls_handled: 0,
+            traffic_ts: vec![2, 3, 4],
+            late_entry: true,
+            ul_only_max_age: 10 * 18 * 4,
         }
     }
 
+    /// Sets the timeslots (1-4) circuits may be allocated on
+    pub fn set_traffic_timeslots(&mut self, timeslots: Vec<u8>) {
+        assert!(timeslots.iter().all(|ts| (1..=4).contains(ts)), "invalid traffic timeslots {:?}", timeslots);
+        self.traffic_ts = timeslots;
+    }
+
+    /// Sets the age in timeslots at which Ul-only circuits are closed
+    pub fn set_ul_only_max_age(&mut self, max_age: i32) {
+        self.ul_only_max_age = max_age;
+    }
+
+    /// Enables or disables the late entry repetition of D-SETUPs of active calls
+    pub fn set_late_entry(&mut self, enabled: bool) {
+        self.late_entry = enabled;
+    }
+
     /// Checks if a circuit is active on the given timeslot
     /// Returns (dl_active, ul_active)
     pub fn is_active(&self, ts: u8) -> (bool, bool) {
@@ -120,7 +151,7 @@ impl CircuitMgr {
     /// Finds a free timeslot for the given direction (Ul, Dl or Both)
     fn get_free_ts(&self, dir: Direction) -> Result<u8, CircuitErr> {
         // TODO FIXME we may do a bit smarter allocation here
-        for ts in 2..=4 {
+        for &ts in &self.traffic_ts {
             let (dl_active, ul_active) = self.is_active(ts);
             match (dir, dl_active, ul_active) {
                 (Direction::Dl, false, _) => return Ok(ts),
@@ -139,10 +170,21 @@ impl CircuitMgr {
         Err(CircuitErr::NoCircuitFree)
     }
 
+    /// Whether a circuit can be allocated for the given direction
+    pub fn has_free_ts(&self, dir: Direction) -> bool {
+        self.get_free_ts(dir).is_ok()
+    }
+
     pub fn allocate_circuit(&mut self, dir: Direction, comm_type: CommunicationType) -> Result<&CmceCircuit, CircuitErr> {
-        // Get timeslot, call_id and usage
-        let ts = self.get_free_ts(dir)?;
+        self.get_free_ts(dir)?;
         let call_id = self.get_next_call_id();
+        self.allocate_circuit_with_id(dir, comm_type, call_id)
+    }
+
+    /// Allocates a circuit for a call whose identifier was taken earlier with `get_next_call_id`
+    pub fn allocate_circuit_with_id(&mut self, dir: Direction, comm_type: CommunicationType, call_id: CallId) -> Result<&CmceCircuit, CircuitErr> {
+        // Get timeslot and usage
+        let ts = self.get_free_ts(dir)?;
         let usage = self.get_next_usage_number();
         
         // Create circuit
@@ -160,7 +202,10 @@ impl CircuitMgr {
             etee_encrypted: false, // TODO, no encryption for now
         };
         
-        // Register circuit and return
+        // Register circuit and return. A call spanning several circuits is counted once
+        if !self.dl.iter().chain(&self.ul_only).flatten().any(|c| c.call_id == call_id) {
+            self.calls_handled += 1;
+        }
         Ok(self.open_circuit(dir, circuit)?)
     }
 
@@ -237,23 +282,19 @@ impl CircuitMgr {
         }
     }
 
-    /// Closes any circuits that have expired
+    /// Closes any Ul-only circuits that have expired. Calls on other circuits are
+    /// released by their call timers.
     fn close_expired_circuits(&mut self, mut tasks: Option<Vec<CircuitMgrCmd>>) -> Option<Vec<CircuitMgrCmd>> {
-        let mut to_close: Vec<_> = self.dl.iter()
+        let to_close: Vec<_> = self.ul_only.iter()
             .filter_map(|circuit| circuit.as_ref())
-            .filter(|circuit| circuit.ts_created.age(self.dltime) > 10 * 18 * 4)
+            .filter(|circuit| circuit.ts_created.age(self.dltime) > self.ul_only_max_age)
             .map(|circuit| (circuit.direction, circuit.ts, circuit.call_id))
             .collect();
-        to_close.extend(
-            self.ul_only.iter()
-                .filter_map(|circuit| circuit.as_ref())
-                .filter(|circuit| circuit.ts_created.age(self.dltime) > 10 * 18 * 4)
-                .map(|circuit| (circuit.direction, circuit.ts, circuit.call_id))
-        );
         for (dir, ts, call_id) in to_close {
-            let circuit = self.close_circuit(dir, ts).unwrap(); // TODO FIXME not so sure about this one
-            tasks.get_or_insert_with(Vec::new)
-                .push(CircuitMgrCmd::SendClose(call_id, circuit));
+            if let Ok(circuit) = self.close_circuit(dir, ts) {
+                tasks.get_or_insert_with(Vec::new)
+                    .push(CircuitMgrCmd::SendClose(call_id, circuit));
+            }
         }
         tasks
     }
@@ -274,10 +315,11 @@ impl CircuitMgr {
                     // Circuit exists
                     if circuit.ts_created.age(dltime) < 4 * 4 {
                         tasks.get_or_insert_with(Vec::new)
-                            .push(CircuitMgrCmd::SendDSetup(circuit.call_id, circuit.usage));
-                    } else if (circuit.ts_created.age(dltime) - 4) % 3 == 2 {
+                            .push(CircuitMgrCmd::SendDSetup(circuit.call_id, circuit.ts, circuit.usage));
+                    } else if self.late_entry && (circuit.ts_created.age(dltime) - 4) % 3 == 2 {
+                        // Late entry: repeated for MSs that arrive while the call is active
                         tasks.get_or_insert_with(Vec::new)
-                            .push(CircuitMgrCmd::SendDSetup(circuit.call_id, circuit.usage));
+                            .push(CircuitMgrCmd::SendDSetup(circuit.call_id, circuit.ts, circuit.usage));
                     }
                 }
             }
@@ -286,3 +328,41 @@ impl CircuitMgr {
         None
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ul_only_circuit_expiry() {
+        let mut circuits = CircuitMgr::new();
+        circuits.set_ul_only_max_age(4 * 18 * 4);
+        let start = TdmaTime::default().add_timeslots(4 * 18 * 4);
+        circuits.tick_start(start);
+        let call_id = circuits.allocate_circuit(Direction::Ul, CommunicationType::P2p).unwrap().call_id;
+
+        // Still open at its maximum age, closed on the first frame start after it
+        let mut dltime = start.add_timeslots(4 * 18 * 4);
+        assert!(circuits.tick_start(dltime).is_none());
+        let closed = loop {
+            dltime = dltime.add_timeslots(1);
+            if let Some(tasks) = circuits.tick_start(dltime) {
+                break tasks;
+            }
+        };
+        assert_eq!(dltime.t, 1);
+        assert!(matches!(closed.as_slice(), [CircuitMgrCmd::SendClose(id, _)] if *id == call_id));
+        assert!(circuits.ul_only.iter().all(Option::is_none));
+    }
+
+    #[test]
+    fn test_calls_handled_counts_call_ids() {
+        let mut circuits = CircuitMgr::new();
+        let call_id = circuits.allocate_circuit(Direction::Dl, CommunicationType::P2p).unwrap().call_id;
+        circuits.allocate_circuit_with_id(Direction::Ul, CommunicationType::P2p, call_id).unwrap();
+        assert_eq!(circuits.calls_handled, 1);
+
+        circuits.allocate_circuit(Direction::Dl, CommunicationType::P2Mp).unwrap();
+        assert_eq!(circuits.calls_handled, 2);
+    }
+}
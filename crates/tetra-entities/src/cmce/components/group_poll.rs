@@ -0,0 +1,81 @@
+use std::collections::BTreeSet;
+
+use tetra_saps::lcmc::CallId;
+
+
+/// Largest value of the 6-bit poll response number element
+pub const POLL_RESPONSE_NUMBER_MAX: u8 = 63;
+
+/// Poll of the members of an acknowledged group call. Started by sending D-INFO with
+/// poll request to the group; every member answers with U-INFO with poll response.
+#[derive(Debug, Clone)]
+pub struct GroupPoll {
+    pub call_id: CallId,
+    /// MSs attached to the group when the poll was started
+    members: Vec<u32>,
+    responders: BTreeSet<u32>,
+}
+
+impl GroupPoll {
+    pub fn new(call_id: CallId, members: Vec<u32>) -> Self {
+        Self {
+            call_id,
+            members,
+            responders: BTreeSet::new(),
+        }
+    }
+
+    /// Registers a poll response from `ssi`. Returns false if the SSI is not a
+    /// member of the polled group; repeated responses are only counted once.
+    pub fn respond(&mut self, ssi: u32) -> bool {
+        if !self.members.contains(&ssi) {
+            return false;
+        }
+        self.responders.insert(ssi);
+        true
+    }
+
+    /// Members that responded so far, sorted by SSI
+    pub fn responders(&self) -> Vec<u32> {
+        self.responders.iter().copied().collect()
+    }
+
+    /// Poll response number element, the number of responses saturated at 63
+    pub fn response_number(&self) -> u8 {
+        self.responders.len().min(POLL_RESPONSE_NUMBER_MAX as usize) as u8
+    }
+
+    /// Poll response percentage element, in steps of 2% (0 = 0%, 50 = 100%)
+    pub fn response_percentage(&self) -> u8 {
+        if self.members.is_empty() {
+            return 0;
+        }
+        (self.responders.len() * 50 / self.members.len()) as u8
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_poll_tally() {
+        let mut poll = GroupPoll::new(5, vec![2040001, 2040002, 2040003, 2040004]);
+        assert_eq!(poll.response_number(), 0);
+        assert_eq!(poll.response_percentage(), 0);
+
+        assert!(poll.respond(2040003));
+        assert!(poll.respond(2040003));
+        assert!(!poll.respond(2049999));
+        assert_eq!(poll.response_number(), 1);
+        assert_eq!(poll.response_percentage(), 12);
+
+        assert!(poll.respond(2040001));
+        assert!(poll.respond(2040002));
+        assert!(poll.respond(2040004));
+        assert_eq!(poll.responders(), vec![2040001, 2040002, 2040003, 2040004]);
+        assert_eq!(poll.response_number(), 4);
+        assert_eq!(poll.response_percentage(), 50);
+    }
+}
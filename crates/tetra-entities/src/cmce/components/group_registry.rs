@@ -0,0 +1,82 @@
+//! Reverse index of group attachments: the ISSIs attached to each GSSI. Kept in step
+//! with the group control primitives sent by MM, and consulted to address group calls.
+
+use std::collections::{BTreeSet, HashMap};
+
+use tetra_saps::control::group_control::GroupControl;
+
+#[derive(Default)]
+pub struct GroupRegistry {
+    groups: HashMap<u32, BTreeSet<u32>>,
+}
+
+impl GroupRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn attach(&mut self, gssi: u32, issi: u32) {
+        self.groups.entry(gssi).or_default().insert(issi);
+    }
+
+    pub fn detach(&mut self, gssi: u32, issi: u32) {
+        if let Some(members) = self.groups.get_mut(&gssi) {
+            members.remove(&issi);
+            if members.is_empty() {
+                self.groups.remove(&gssi);
+            }
+        }
+    }
+
+    /// Detaches `issi` from every group it is attached to
+    pub fn detach_all(&mut self, issi: u32) {
+        self.groups.retain(|_, members| {
+            members.remove(&issi);
+            !members.is_empty()
+        });
+    }
+
+    /// Applies a group attachment change reported by MM
+    pub fn apply(&mut self, change: &GroupControl) {
+        match *change {
+            GroupControl::Attach { issi, gssi } => self.attach(gssi, issi),
+            GroupControl::Detach { issi, gssi } => self.detach(gssi, issi),
+            GroupControl::DetachAll { issi } => self.detach_all(issi),
+        }
+    }
+
+    /// ISSIs attached to `gssi`, in ascending order
+    pub fn members(&self, gssi: u32) -> Vec<u32> {
+        self.groups.get(&gssi).map(|members| members.iter().copied().collect()).unwrap_or_default()
+    }
+
+    /// Whether any MS is attached to `gssi`
+    pub fn has_members(&self, gssi: u32) -> bool {
+        self.groups.contains_key(&gssi)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attach_detach_members() {
+        let mut reg = GroupRegistry::new();
+        reg.attach(91, 2040002);
+        reg.attach(91, 2040001);
+        reg.attach(26, 2040001);
+        assert_eq!(reg.members(91), vec![2040001, 2040002]);
+        assert_eq!(reg.members(26), vec![2040001]);
+        assert_eq!(reg.members(7), Vec::<u32>::new());
+
+        reg.detach(91, 2040002);
+        assert_eq!(reg.members(91), vec![2040001]);
+
+        // Empty groups are dropped
+        reg.detach_all(2040001);
+        assert!(!reg.has_members(91));
+        assert!(!reg.has_members(26));
+    }
+}
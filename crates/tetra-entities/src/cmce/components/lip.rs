@@ -0,0 +1,124 @@
+//! Location Information Protocol (ETSI TS 100 392-18-1), carried as SDS type 4 user
+//! data with protocol identifier 0x0A. Only the short location report is decoded.
+
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use super::sds_protocol::SdsProtocol;
+
+/// LIP PDU type of the short location report
+const PDU_TYPE_SHORT_LOCATION_REPORT: u64 = 0;
+
+/// Horizontal velocity value for an unknown velocity
+const VELOCITY_UNKNOWN: u64 = 127;
+
+/// Upper bound of the position error in metres, indexed by its 3-bit code.
+/// Code 6 is above 200 km and code 7 unknown.
+const POSITION_ERROR_M: [u32; 6] = [2, 20, 200, 2_000, 20_000, 200_000];
+
+/// Position reported by an MS in a LIP short location report
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LocationReport {
+    /// Degrees, WGS84, positive towards the north
+    pub latitude: f64,
+    /// Degrees, WGS84, positive towards the east
+    pub longitude: f64,
+    /// 2 bits, time elapsed since the position was determined: <5 s, <5 min, <30 min, unknown
+    pub time_elapsed: u8,
+    /// Upper bound of the position error in metres, None if unknown or above 200 km
+    pub position_error_m: Option<u32>,
+    /// Horizontal velocity in km/h, None if unknown
+    pub velocity_kmh: Option<f32>,
+    /// Direction of travel in degrees clockwise from north, in steps of 22.5
+    pub direction_deg: f32,
+    /// Reason for sending, if the additional data carries one rather than user defined data
+    pub reason_for_sending: Option<u8>,
+}
+
+/// Sign-extends the `bits` wide two's complement `value`
+fn sign_extend(value: u64, bits: u32) -> i64 {
+    let shift = 64 - bits;
+    ((value << shift) as i64) >> shift
+}
+
+/// Horizontal velocity in km/h of a 7-bit code: linear up to 28 km/h, logarithmic above
+fn velocity_kmh(code: u64) -> Option<f32> {
+    match code {
+        VELOCITY_UNKNOWN => None,
+        0..28 => Some(code as f32),
+        _ => Some(16.0 * 1.038f32.powi(code as i32 - 13)),
+    }
+}
+
+/// Decodes the short location report in SDS user data of `len_bits` bits, starting with
+/// the protocol identifier. Other LIP PDUs are reported as not implemented.
+pub fn decode_location_report(data: &[u8], len_bits: usize) -> Result<LocationReport, PduParseErr> {
+    let mut buf = BitBuffer::from_bytes(data);
+    if len_bits < buf.get_len() {
+        buf.set_raw_end(len_bits);
+    }
+
+    let pid = buf.read_field(8, "protocol_identifier")?;
+    if SdsProtocol::from_pid(pid as u8) != SdsProtocol::Lip {
+        return Err(PduParseErr::InvalidValue { field: "protocol_identifier", value: pid });
+    }
+    let pdu_type = buf.read_field(2, "pdu_type")?;
+    if pdu_type != PDU_TYPE_SHORT_LOCATION_REPORT {
+        return Err(PduParseErr::NotImplemented { field: Some("pdu_type") });
+    }
+
+    let time_elapsed = buf.read_field(2, "time_elapsed")? as u8;
+    let longitude = sign_extend(buf.read_field(25, "longitude")?, 25) as f64 * 360.0 / (1 << 25) as f64;
+    let latitude = sign_extend(buf.read_field(24, "latitude")?, 24) as f64 * 180.0 / (1 << 24) as f64;
+    let position_error = buf.read_field(3, "position_error")?;
+    let velocity = buf.read_field(7, "horizontal_velocity")?;
+    let direction = buf.read_field(4, "direction_of_travel")?;
+    let user_defined = buf.read_field(1, "type_of_additional_data")? != 0;
+    let additional_data = buf.read_field(8, "additional_data")? as u8;
+
+    Ok(LocationReport {
+        latitude,
+        longitude,
+        time_elapsed,
+        position_error_m: POSITION_ERROR_M.get(position_error as usize).copied(),
+        velocity_kmh: velocity_kmh(velocity),
+        direction_deg: direction as f32 * 22.5,
+        reason_for_sending: (!user_defined).then_some(additional_data),
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_location_report() {
+        // 52.0 N 4.5 E, <5 min old, error <200 m, 30 km/h towards the east, reason 0x21
+        let data = [0x0a, 0x10, 0x33, 0x33, 0x32, 0x4f, 0xa4, 0xfa, 0x3c, 0x82, 0x10];
+        let report = decode_location_report(&data, 84).unwrap();
+        assert!((report.latitude - 52.0).abs() < 1e-5, "{}", report.latitude);
+        assert!((report.longitude - 4.5).abs() < 1e-5, "{}", report.longitude);
+        assert_eq!(report.time_elapsed, 1);
+        assert_eq!(report.position_error_m, Some(200));
+        assert!(report.velocity_kmh.is_some_and(|v| (v - 16.0 * 1.038f32.powi(17)).abs() < 1e-3));
+        assert_eq!(report.direction_deg, 90.0);
+        assert_eq!(report.reason_for_sending, Some(0x21));
+
+        // 33.5 S 70.25 W, position error and velocity unknown, user defined additional data
+        let data = [0x0a, 0x3c, 0xe0, 0xb6, 0x0e, 0x82, 0xd8, 0x37, 0xfe, 0x15, 0x50];
+        let report = decode_location_report(&data, 84).unwrap();
+        assert!((report.latitude + 33.5).abs() < 1e-5, "{}", report.latitude);
+        assert!((report.longitude + 70.25).abs() < 1e-5, "{}", report.longitude);
+        assert_eq!(report.position_error_m, None);
+        assert_eq!(report.velocity_kmh, None);
+        assert_eq!(report.reason_for_sending, None);
+    }
+
+    #[test]
+    fn test_truncated_or_other_reports() {
+        let data = [0x0a, 0x10, 0x33, 0x33, 0x32, 0x4f, 0xa4, 0xfa, 0x3c, 0x82, 0x10];
+        assert!(matches!(decode_location_report(&data, 80), Err(PduParseErr::BufferEnded { .. })));
+        assert!(matches!(decode_location_report(&[0x0a, 0x40], 16), Err(PduParseErr::NotImplemented { .. })));
+        assert!(matches!(decode_location_report(&[0x02, 0x01], 16), Err(PduParseErr::InvalidValue { .. })));
+    }
+}
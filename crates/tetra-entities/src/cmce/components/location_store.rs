@@ -0,0 +1,104 @@
+//! History of the locations reported by each MS over LIP.
+
+use std::collections::{HashMap, VecDeque};
+
+use tetra_core::TdmaTime;
+
+use super::lip::LocationReport;
+
+/// Number of reports kept per MS. When full, the oldest report is dropped.
+pub const LOCATION_HISTORY_LEN: usize = 16;
+
+/// A location report with the TDMA time it was received at
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimedLocation {
+    pub time: TdmaTime,
+    pub report: LocationReport,
+}
+
+/// Bounded location history per ISSI
+pub struct LocationStore {
+    history: HashMap<u32, VecDeque<TimedLocation>>,
+    capacity: usize,
+}
+
+impl LocationStore {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            history: HashMap::new(),
+            capacity,
+        }
+    }
+
+    pub fn record(&mut self, issi: u32, time: TdmaTime, report: LocationReport) {
+        if self.capacity == 0 {
+            return;
+        }
+        let history = self.history.entry(issi).or_default();
+        if history.len() == self.capacity {
+            history.pop_front();
+        }
+        history.push_back(TimedLocation { time, report });
+    }
+
+    /// Most recent location reported by `issi`
+    pub fn latest(&self, issi: u32) -> Option<&TimedLocation> {
+        self.history.get(&issi).and_then(|h| h.back())
+    }
+
+    /// Locations reported by `issi`, oldest first
+    pub fn history(&self, issi: u32) -> impl Iterator<Item = &TimedLocation> {
+        self.history.get(&issi).into_iter().flatten()
+    }
+
+    /// ISSIs that reported a location
+    pub fn issis(&self) -> impl Iterator<Item = u32> + '_ {
+        self.history.keys().copied()
+    }
+}
+
+impl Default for LocationStore {
+    fn default() -> Self {
+        Self::new(LOCATION_HISTORY_LEN)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(latitude: f64, longitude: f64) -> LocationReport {
+        LocationReport {
+            latitude,
+            longitude,
+            time_elapsed: 0,
+            position_error_m: Some(20),
+            velocity_kmh: None,
+            direction_deg: 0.0,
+            reason_for_sending: None,
+        }
+    }
+
+    #[test]
+    fn test_latest_location() {
+        let mut store = LocationStore::new(2);
+        let t0 = TdmaTime::default();
+        assert!(store.latest(2040001).is_none());
+
+        store.record(2040001, t0, report(52.0, 4.5));
+        store.record(2040001, t0.add_timeslots(100), report(52.1, 4.6));
+        store.record(2040002, t0.add_timeslots(50), report(48.0, 2.0));
+
+        let latest = store.latest(2040001).unwrap();
+        assert_eq!(latest.report.latitude, 52.1);
+        assert_eq!(latest.time, t0.add_timeslots(100));
+        assert_eq!(store.latest(2040002).unwrap().report.longitude, 2.0);
+
+        // The oldest report is dropped once the history is full
+        store.record(2040001, t0.add_timeslots(200), report(52.2, 4.7));
+        let latitudes: Vec<f64> = store.history(2040001).map(|l| l.report.latitude).collect();
+        assert_eq!(latitudes, vec![52.1, 52.2]);
+        assert_eq!(store.history(2040003).count(), 0);
+    }
+}
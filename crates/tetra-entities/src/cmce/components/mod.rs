@@ -1,2 +1,10 @@
+pub mod call_timers;
 pub mod cc_bs_fsm;
-pub mod circuit_mgr;
\ No newline at end of file
+pub mod circuit_mgr;
+pub mod group_poll;
+pub mod group_registry;
+pub mod lip;
+pub mod location_store;
+pub mod paging_queue;
+pub mod sds_protocol;
+pub mod sds_text;
\ No newline at end of file
@@ -0,0 +1,157 @@
+use std::collections::VecDeque;
+
+use tetra_core::TdmaTime;
+use tetra_saps::lcmc::CallId;
+
+
+/// Timeslots to wait for a response before a page is repeated, one multiframe
+pub const PAGING_REPEAT_INTERVAL: i32 = 18 * 4;
+
+/// Number of pages sent before the call is abandoned
+pub const PAGING_MAX_ATTEMPTS: u8 = 3;
+
+pub enum PagingCmd {
+    /// Transmit a page to the SSI on the MCCH
+    SendPage(u32, CallId),
+    /// The SSI did not respond to any page, the call should be abandoned
+    Abandon(u32, CallId),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PagingEntry {
+    pub ssi: u32,
+    pub call_id: CallId,
+    /// Number of pages sent so far
+    pub attempts: u8,
+    /// Time the last page was sent, None if none was sent yet
+    pub last_sent: Option<TdmaTime>,
+}
+
+/// Pages called MSs on the MCCH with the D-SETUP of an incoming call.
+/// Pages are repeated every PAGING_REPEAT_INTERVAL until the MS responds,
+/// or abandoned after PAGING_MAX_ATTEMPTS unanswered pages.
+pub struct PagingQueue {
+    entries: VecDeque<PagingEntry>,
+}
+
+impl PagingQueue {
+    pub fn new() -> Self {
+        Self {
+            entries: VecDeque::new(),
+        }
+    }
+
+    /// Queues a page for `ssi`, sent from the next tick on.
+    /// Returns false if the SSI is already being paged.
+    pub fn enqueue(&mut self, ssi: u32, call_id: CallId) -> bool {
+        if self.is_paging(ssi) {
+            tracing::warn!("PagingQueue: ssi {} already being paged", ssi);
+            return false;
+        }
+        self.entries.push_back(PagingEntry { ssi, call_id, attempts: 0, last_sent: None });
+        true
+    }
+
+    pub fn is_paging(&self, ssi: u32) -> bool {
+        self.entries.iter().any(|e| e.ssi == ssi)
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &PagingEntry> {
+        self.entries.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Handles a response from `ssi`. If it was being paged, the page is completed
+    /// and the call identifier it was paged for is returned.
+    pub fn respond(&mut self, ssi: u32) -> Option<CallId> {
+        let index = self.entries.iter().position(|e| e.ssi == ssi)?;
+        let entry = self.entries.remove(index).unwrap();
+        tracing::info!("PagingQueue: ssi {} responded after {} pages, call {}", ssi, entry.attempts, entry.call_id);
+        Some(entry.call_id)
+    }
+
+    /// Returns the pages to be (re)transmitted this tick, and the calls to be abandoned
+    pub fn tick_start(&mut self, dltime: TdmaTime) -> Vec<PagingCmd> {
+        let mut cmds = Vec::new();
+        self.entries.retain_mut(|entry| {
+            if entry.last_sent.is_some_and(|t| t.age(dltime) < PAGING_REPEAT_INTERVAL) {
+                return true;
+            }
+            if entry.attempts >= PAGING_MAX_ATTEMPTS {
+                tracing::info!("PagingQueue: no response from ssi {}, abandoning call {}", entry.ssi, entry.call_id);
+                cmds.push(PagingCmd::Abandon(entry.ssi, entry.call_id));
+                return false;
+            }
+            entry.attempts += 1;
+            entry.last_sent = Some(dltime);
+            cmds.push(PagingCmd::SendPage(entry.ssi, entry.call_id));
+            true
+        });
+        cmds
+    }
+}
+
+impl Default for PagingQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_pages(cmds: &[PagingCmd]) -> usize {
+        cmds.iter().filter(|c| matches!(c, PagingCmd::SendPage(..))).count()
+    }
+
+    #[test]
+    fn test_page_response() {
+        let mut queue = PagingQueue::new();
+        let t0 = TdmaTime::default();
+        assert!(queue.enqueue(2040814, 5));
+        assert!(!queue.enqueue(2040814, 6));
+
+        // First page is sent immediately, the repeat only after the interval
+        assert_eq!(count_pages(&queue.tick_start(t0)), 1);
+        assert_eq!(count_pages(&queue.tick_start(t0.add_timeslots(1))), 0);
+        assert_eq!(count_pages(&queue.tick_start(t0.add_timeslots(PAGING_REPEAT_INTERVAL))), 1);
+        assert_eq!(queue.entries().next().unwrap().attempts, 2);
+
+        assert_eq!(queue.respond(1000), None);
+        assert_eq!(queue.respond(2040814), Some(5));
+        assert!(queue.is_empty());
+        assert!(queue.tick_start(t0.add_timeslots(2 * PAGING_REPEAT_INTERVAL)).is_empty());
+    }
+
+    #[test]
+    fn test_page_timeout() {
+        let mut queue = PagingQueue::new();
+        let mut t = TdmaTime::default();
+        queue.enqueue(2040814, 5);
+
+        let mut pages = 0;
+        let abandoned = loop {
+            let cmds = queue.tick_start(t);
+            pages += count_pages(&cmds);
+            if let Some(PagingCmd::Abandon(ssi, call_id)) = cmds.into_iter().find(|c| matches!(c, PagingCmd::Abandon(..))) {
+                break (ssi, call_id);
+            }
+            t = t.add_timeslots(1);
+        };
+
+        assert_eq!(abandoned, (2040814, 5));
+        assert_eq!(pages, PAGING_MAX_ATTEMPTS as usize);
+        assert_eq!(t.diff(TdmaTime::default()), PAGING_MAX_ATTEMPTS as i32 * PAGING_REPEAT_INTERVAL);
+        assert!(queue.is_empty());
+        assert_eq!(queue.respond(2040814), None);
+    }
+}
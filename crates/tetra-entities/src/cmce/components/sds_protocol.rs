@@ -0,0 +1,82 @@
+//! Protocol identifiers of SDS type 4 user data, EN 300 392-2 clause 29.4.3.9.
+//! The first byte of the user data selects the sub-protocol. Identifiers from
+//! 0x80 on are followed by an SDS-TL header.
+
+/// Sub-protocol of SDS type 4 user data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SdsProtocol {
+    /// 0x01, over the air re-keying for end-to-end encryption
+    Otak,
+    /// 0x02, simple text messaging
+    SimpleText,
+    /// 0x09, simple immediate text messaging
+    SimpleImmediateText,
+    /// 0x0A, location information protocol
+    Lip,
+    /// 0x82, text messaging with SDS-TL
+    TlText,
+    /// 0x89, immediate text messaging with SDS-TL
+    TlImmediateText,
+    /// Any other protocol with SDS-TL
+    SdsTl(u8),
+    /// Any other protocol without SDS-TL
+    Other(u8),
+}
+
+/// Protocol identifiers from this value on use SDS-TL
+const PID_SDS_TL_FIRST: u8 = 0x80;
+
+impl SdsProtocol {
+    pub fn from_pid(pid: u8) -> Self {
+        match pid {
+            0x01 => Self::Otak,
+            0x02 => Self::SimpleText,
+            0x09 => Self::SimpleImmediateText,
+            0x0A => Self::Lip,
+            0x82 => Self::TlText,
+            0x89 => Self::TlImmediateText,
+            pid if pid >= PID_SDS_TL_FIRST => Self::SdsTl(pid),
+            pid => Self::Other(pid),
+        }
+    }
+
+    pub fn pid(&self) -> u8 {
+        match self {
+            Self::Otak => 0x01,
+            Self::SimpleText => 0x02,
+            Self::SimpleImmediateText => 0x09,
+            Self::Lip => 0x0A,
+            Self::TlText => 0x82,
+            Self::TlImmediateText => 0x89,
+            Self::SdsTl(pid) | Self::Other(pid) => *pid,
+        }
+    }
+
+    /// Whether the protocol identifier is followed by an SDS-TL header
+    pub fn uses_sds_tl(&self) -> bool {
+        self.pid() >= PID_SDS_TL_FIRST
+    }
+
+    /// Whether the user data is a text message
+    pub fn is_text(&self) -> bool {
+        matches!(self, Self::SimpleText | Self::SimpleImmediateText | Self::TlText | Self::TlImmediateText)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pid_roundtrip() {
+        for pid in 0..=255u8 {
+            assert_eq!(SdsProtocol::from_pid(pid).pid(), pid);
+        }
+        assert_eq!(SdsProtocol::from_pid(0x0A), SdsProtocol::Lip);
+        assert!(!SdsProtocol::SimpleText.uses_sds_tl());
+        assert!(SdsProtocol::TlText.uses_sds_tl());
+        assert!(SdsProtocol::from_pid(0x8A).uses_sds_tl());
+        assert!(!SdsProtocol::Lip.is_text());
+    }
+}
@@ -0,0 +1,160 @@
+//! SDS-TL simple text messaging (EN 300 392-2 clause 29.5.4), as carried in the
+//! user defined data 4 of (U/D)-SDS-DATA. The payload starts with the protocol
+//! identifier and a byte holding the timestamp flag and text coding scheme.
+
+use tetra_config::SdsTextCoding;
+
+use super::sds_protocol::SdsProtocol;
+
+/// SDS-TL protocol identifier for simple text messaging
+pub const PROTOCOL_ID_SIMPLE_TEXT: u8 = 0x02;
+
+/// SDS-TL message type of SDS-TRANSFER, in the upper bits of the byte following the protocol identifier
+const SDS_TL_TRANSFER: u8 = 0x0;
+
+/// Text coding scheme values, Table 29.29
+const CODING_GSM7: u8 = 0x00;
+const CODING_LATIN1: u8 = 0x01;
+
+/// Timestamp flag in the coding scheme byte. If set, a 24-bit timestamp follows it.
+const TIMESTAMP_FLAG: u8 = 0x80;
+
+/// Escape to the extension table in the 7-bit alphabet, not supported
+const GSM7_ESCAPE: u8 = 0x1B;
+
+/// 7-bit default alphabet of ETSI TS 100 900, indexed by code
+const GSM7_ALPHABET: [char; 128] = [
+    '@', '£', '$', '¥', 'è', 'é', 'ù', 'ì', 'ò', 'Ç', '\n', 'Ø', 'ø', '\r', 'Å', 'å',
+    'Δ', '_', 'Φ', 'Γ', 'Λ', 'Ω', 'Π', 'Ψ', 'Σ', 'Θ', 'Ξ', '\u{1b}', 'Æ', 'æ', 'ß', 'É',
+    ' ', '!', '"', '#', '¤', '%', '&', '\'', '(', ')', '*', '+', ',', '-', '.', '/',
+    '0', '1', '2', '3', '4', '5', '6', '7', '8', '9', ':', ';', '<', '=', '>', '?',
+    '¡', 'A', 'B', 'C', 'D', 'E', 'F', 'G', 'H', 'I', 'J', 'K', 'L', 'M', 'N', 'O',
+    'P', 'Q', 'R', 'S', 'T', 'U', 'V', 'W', 'X', 'Y', 'Z', 'Ä', 'Ö', 'Ñ', 'Ü', '§',
+    '¿', 'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o',
+    'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z', 'ä', 'ö', 'ñ', 'ü', 'à',
+];
+
+/// Packs values of varying width MSB-first, leaving the last byte left-aligned
+#[derive(Default)]
+struct BitPacker {
+    data: Vec<u8>,
+    len: usize,
+}
+
+impl BitPacker {
+    fn push(&mut self, value: u8, num_bits: usize) {
+        for i in (0..num_bits).rev() {
+            if self.len.is_multiple_of(8) {
+                self.data.push(0);
+            }
+            let bit = (value >> i) & 1;
+            *self.data.last_mut().unwrap() |= bit << (7 - self.len % 8);
+            self.len += 1;
+        }
+    }
+}
+
+/// Reads `num_bits` from `data` at bit offset `pos`, MSB-first
+fn read_bits(data: &[u8], pos: usize, num_bits: usize) -> u8 {
+    (pos..pos + num_bits).fold(0, |acc, i| (acc << 1) | ((data[i / 8] >> (7 - i % 8)) & 1))
+}
+
+/// Encodes `text` as a simple text message without timestamp. Returns the payload
+/// and its length in bits, or None if `text` holds a character the coding can not represent.
+pub fn encode_text(text: &str, coding: SdsTextCoding) -> Option<(Vec<u8>, usize)> {
+    let mut packer = BitPacker::default();
+    packer.push(PROTOCOL_ID_SIMPLE_TEXT, 8);
+    match coding {
+        SdsTextCoding::Gsm7 => {
+            packer.push(CODING_GSM7, 8);
+            for c in text.chars() {
+                let code = GSM7_ALPHABET.iter().position(|&a| a == c)
+                    .filter(|&code| code as u8 != GSM7_ESCAPE)?;
+                packer.push(code as u8, 7);
+            }
+        }
+        SdsTextCoding::Latin1 => {
+            packer.push(CODING_LATIN1, 8);
+            for c in text.chars() {
+                packer.push(u8::try_from(c as u32).ok()?, 8);
+            }
+        }
+    }
+    Some((packer.data, packer.len))
+}
+
+/// Decodes a text message payload of `len_bits` bits, with or without SDS-TL. Returns None
+/// if it is not a text message, or uses a coding scheme other than those of SdsTextCoding.
+pub fn decode_text(data: &[u8], len_bits: usize) -> Option<String> {
+    if len_bits < 8 || data.len() * 8 < len_bits {
+        return None;
+    }
+    let protocol = SdsProtocol::from_pid(data[0]);
+    if !protocol.is_text() {
+        return None;
+    }
+
+    // SDS-TL header: message type and flags, then the message reference
+    let coding_pos = if protocol.uses_sds_tl() { 3 } else { 1 };
+    if len_bits < (coding_pos + 1) * 8 || (protocol.uses_sds_tl() && data[1] >> 4 != SDS_TL_TRANSFER) {
+        return None;
+    }
+    let coding = data[coding_pos];
+    let mut pos = (coding_pos + 1) * 8;
+    if coding & TIMESTAMP_FLAG != 0 {
+        pos += 24;
+    }
+    let (char_bits, decode): (usize, fn(u8) -> char) = match coding & !TIMESTAMP_FLAG {
+        CODING_GSM7 => (7, |code| GSM7_ALPHABET[code as usize]),
+        CODING_LATIN1 => (8, char::from),
+        _ => return None,
+    };
+    let mut text = String::new();
+    while pos + char_bits <= len_bits {
+        text.push(decode(read_bits(data, pos, char_bits)));
+        pos += char_bits;
+    }
+    Some(text)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_text_roundtrip() {
+        let text = "Hello, TETRA! Ärger @ 12:00 £5";
+        for coding in [SdsTextCoding::Gsm7, SdsTextCoding::Latin1] {
+            let (data, len) = encode_text(text, coding).unwrap();
+            assert_eq!(decode_text(&data, len).as_deref(), Some(text), "{:?}", coding);
+        }
+
+        // 7-bit characters are packed, 8 characters take 7 bytes
+        let (data, len) = encode_text("abcdefgh", SdsTextCoding::Gsm7).unwrap();
+        assert_eq!(len, 16 + 8 * 7);
+        assert_eq!(data.len(), 2 + 7);
+        let (data, len) = encode_text("abcdefgh", SdsTextCoding::Latin1).unwrap();
+        assert_eq!(len, 16 + 8 * 8);
+        assert_eq!(&data[..3], &[PROTOCOL_ID_SIMPLE_TEXT, CODING_LATIN1, b'a']);
+    }
+
+    #[test]
+    fn test_unrepresentable_text() {
+        assert_eq!(encode_text("€", SdsTextCoding::Latin1), None);
+        assert_eq!(encode_text("{", SdsTextCoding::Gsm7), None);
+        assert_eq!(encode_text("\u{1b}", SdsTextCoding::Gsm7), None);
+        assert_eq!(decode_text(&[0x03, CODING_LATIN1, b'a'], 24), None);
+    }
+
+    #[test]
+    fn test_decode_sds_tl_text() {
+        // SDS-TRANSFER without delivery report, message reference 0x17
+        let data = [0x82, 0x00, 0x17, CODING_LATIN1, b'O', b'K'];
+        assert_eq!(decode_text(&data, 48).as_deref(), Some("OK"));
+
+        // Other SDS-TL message types, such as SDS-REPORT, carry no text
+        let data = [0x82, 0x10, 0x17, CODING_LATIN1, b'O', b'K'];
+        assert_eq!(decode_text(&data, 48), None);
+    }
+}
@@ -1,17 +1,85 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 
+use tetra_config::{CfgCallControl, ProtocolEdition};
 use tetra_core::{BitBuffer, Direction, Sap, SsiType, TdmaTime, TetraAddress, tetra_entities::TetraEntity, unimplemented_log};
-use tetra_pdus::cmce::{enums::{call_timeout::CallTimeout, call_timeout_setup_phase::CallTimeoutSetupPhase, cmce_pdu_type_ul::CmcePduTypeUl, transmission_grant::TransmissionGrant}, fields::basic_service_information::BasicServiceInformation, pdus::{d_call_proceeding::DCallProceeding, d_connect::DConnect, d_release::DRelease, d_setup::DSetup, u_setup::USetup}, structs::cmce_circuit::CmceCircuit};
-use tetra_saps::{SapMsg, SapMsgInner, control::{call_control::{CallControl, Circuit}, enums::communication_type::CommunicationType}, lcmc::{LcmcMleUnitdataReq, enums::{alloc_type::ChanAllocType, ul_dl_assignment::UlDlAssignment}, fields::chan_alloc_req::CmceChanAllocReq}};
+use tetra_pdus::cmce::{enums::{call_timeout::CallTimeout, call_timeout_setup_phase::CallTimeoutSetupPhase, cmce_pdu_type_ul::CmcePduTypeUl, transmission_grant::TransmissionGrant}, fields::basic_service_information::BasicServiceInformation, pdus::{d_alert::DAlert, d_call_proceeding::DCallProceeding, d_connect::DConnect, d_connect_acknowledge::DConnectAcknowledge, d_info::DInfo, d_release::DRelease, d_setup::DSetup, d_tx_granted::DTxGranted, u_alert::UAlert, u_connect::UConnect, u_info::UInfo, u_setup::USetup, u_tx_demand::UTxDemand}, structs::cmce_circuit::CmceCircuit};
+use tetra_pdus::parse_timing::timed_parse;
+use tetra_saps::{SapMsg, SapMsgInner, control::{call_control::{CallControl, Circuit}, enums::communication_type::CommunicationType}, lcmc::{CallId, LcmcMleUnitdataReq, enums::{alloc_type::ChanAllocType, ul_dl_assignment::UlDlAssignment}, fields::chan_alloc_req::CmceChanAllocReq}};
 
-use crate::{MessageQueue, cmce::components::circuit_mgr::{CircuitMgr, CircuitMgrCmd}};
+use crate::{MessageQueue, cmce::components::{call_timers::{CallTimer, CallTimers}, circuit_mgr::{CircuitMgr, CircuitMgrCmd}, group_poll::GroupPoll}, ssi_allocator::SsiAllocator};
 
+/// Number of calls requested by MSs that may wait for a circuit. Further requests are
+/// rejected, except emergency calls, which take the place of the latest routine request.
+pub const MAX_SETUP_REQUESTS: usize = 8;
+
+/// 14.8.18 Disconnect cause: called party not reachable
+pub const DISCONNECT_CAUSE_NOT_REACHABLE: u8 = 3;
+/// 14.8.18 Disconnect cause: congestion in infrastructure
+pub const DISCONNECT_CAUSE_CONGESTION: u8 = 5;
+/// 14.8.18 Disconnect cause: pre-emptive use of resource
+const DISCONNECT_CAUSE_PREEMPTION: u8 = 9;
+/// 14.8.18 Disconnect cause: expiry of timer
+const DISCONNECT_CAUSE_TIMER_EXPIRY: u8 = 13;
+
+/// A call addressed to a group, with the MSs attached to the group when it was set up
+#[derive(Debug, Clone)]
+pub struct GroupCall {
+    pub gssi: u32,
+    pub members: Vec<u32>,
+}
+
+/// A group call requested by an MS in U-SETUP, waiting for a circuit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetupRequest {
+    pub calling_ssi: u32,
+    pub gssi: u32,
+    pub priority: u8,
+    /// Identifier given to the call in D-CALL PROCEEDING, kept once it is set up
+    pub call_id: CallId,
+}
+
+/// A call set up to a single MS
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndividualCall {
+    pub called_ssi: u32,
+    /// MS that requested the call in U-SETUP, None for calls set up by the BS
+    pub calling_ssi: Option<u32>,
+}
+
+/// An individual call requested by an MS in U-SETUP
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndividualSetupRequest {
+    pub calling_ssi: u32,
+    pub called_ssi: u32,
+    /// Identifier given to the call in D-CALL PROCEEDING, kept once it is set up
+    pub call_id: CallId,
+}
 
 /// Clause 11 Call Control CMCE sub-entity
 pub struct CcBsSubentity{
     dltime: TdmaTime,
     cached_setups: HashMap<u16, DSetup>,
     circuits: CircuitMgr,
+    group_calls: HashMap<CallId, GroupCall>,
+    /// Calls set up to a single MS
+    individual_calls: HashMap<CallId, IndividualCall>,
+    timers: CallTimers,
+    polls: HashMap<CallId, GroupPoll>,
+    /// Pool of temporary addresses for calls, None if calls carry no temporary address
+    temporary_address_pool: Option<SsiAllocator>,
+    temporary_addresses: HashMap<CallId, u32>,
+    /// Whether emergency calls may release lower priority calls
+    preemption: bool,
+    /// Call priority from which a requested call is an emergency call
+    emergency_priority: u8,
+    /// Calls requested by MSs, emergency calls first, otherwise in order of arrival
+    setup_requests: VecDeque<SetupRequest>,
+    /// Calls requested in U-SETUP that were not admitted to `setup_requests` yet
+    received_setups: Vec<SetupRequest>,
+    /// Individual calls requested in U-SETUP that were not set up yet
+    received_individual_setups: Vec<IndividualSetupRequest>,
+    /// Edition of the standard whose values are sent in elements no longer used since
+    protocol_edition: ProtocolEdition,
 }
 
 impl CcBsSubentity {
@@ -21,42 +89,447 @@ impl CcBsSubentity {
             dltime: TdmaTime::default(),
             cached_setups: HashMap::new(),
             circuits: CircuitMgr::new(),
+            group_calls: HashMap::new(),
+            individual_calls: HashMap::new(),
+            timers: CallTimers::new(&CfgCallControl::default()),
+            polls: HashMap::new(),
+            temporary_address_pool: None,
+            temporary_addresses: HashMap::new(),
+            preemption: false,
+            emergency_priority: 15,
+            setup_requests: VecDeque::new(),
+            received_setups: Vec::new(),
+            received_individual_setups: Vec::new(),
+            protocol_edition: ProtocolEdition::default(),
         }
     }
 
-    pub fn run_call_test(&mut self, queue: &mut MessageQueue, dltime: TdmaTime) {
+    /// Sets the call priority from which a call requested in U-SETUP is an emergency call
+    pub fn set_emergency_priority(&mut self, priority: u8) {
+        self.emergency_priority = priority;
+    }
+
+    /// Calls requested by MSs that are waiting to be set up, in the order they will be served
+    pub fn setup_requests(&self) -> &VecDeque<SetupRequest> {
+        &self.setup_requests
+    }
+
+    /// Calls requested in U-SETUP since the last call, to be checked and queued or rejected
+    pub fn take_received_setups(&mut self) -> Vec<SetupRequest> {
+        std::mem::take(&mut self.received_setups)
+    }
+
+    /// Individual calls requested in U-SETUP since the last call, to be set up or rejected
+    pub fn take_received_individual_setups(&mut self) -> Vec<IndividualSetupRequest> {
+        std::mem::take(&mut self.received_individual_setups)
+    }
+
+    /// Queues a requested call and answers the calling MS with D-CALL PROCEEDING. An emergency
+    /// call goes ahead of all other calls but earlier emergency calls. If `MAX_SETUP_REQUESTS`
+    /// calls are queued, the request is rejected, unless it is an emergency call and a routine
+    /// request can be rejected in its place.
+    pub fn queue_setup_request(&mut self, queue: &mut MessageQueue, request: SetupRequest, dltime: TdmaTime) {
+        let emergency = request.priority >= self.emergency_priority;
+        if self.setup_requests.len() >= MAX_SETUP_REQUESTS {
+            let last_routine = self.setup_requests.iter().rposition(|queued| queued.priority < self.emergency_priority);
+            match last_routine {
+                Some(pos) if emergency => {
+                    let dropped = self.setup_requests.remove(pos).unwrap();
+                    tracing::warn!("Setup queue full, dropping call from {} for emergency call", dropped.calling_ssi);
+                    self.reject_setup_request(queue, &dropped, DISCONNECT_CAUSE_CONGESTION, dltime);
+                }
+                _ => {
+                    tracing::warn!("Setup queue full, rejecting call from {} to group {}", request.calling_ssi, request.gssi);
+                    self.reject_setup_request(queue, &request, DISCONNECT_CAUSE_CONGESTION, dltime);
+                    return;
+                }
+            }
+        }
+
+        if emergency {
+            tracing::info!("Emergency call from {} to group {}", request.calling_ssi, request.gssi);
+            let pos = self.setup_requests.iter()
+                .position(|queued| queued.priority < self.emergency_priority)
+                .unwrap_or(self.setup_requests.len());
+            self.setup_requests.insert(pos, request);
+        } else {
+            self.setup_requests.push_back(request);
+        }
+        self.send_d_call_proceeding(queue, request.call_id, request.calling_ssi, dltime);
+    }
+
+    /// Rejects a requested call by sending D-RELEASE with `disconnect_cause` to the calling MS
+    pub fn reject_setup_request(&mut self, queue: &mut MessageQueue, request: &SetupRequest, disconnect_cause: u8, dltime: TdmaTime) {
+        Self::send_d_release_to(queue, request.call_id, request.calling_ssi, disconnect_cause, dltime);
+    }
+
+    /// Rejects a requested individual call by sending D-RELEASE with `disconnect_cause` to the calling MS
+    pub fn reject_individual_setup(&mut self, queue: &mut MessageQueue, request: &IndividualSetupRequest, disconnect_cause: u8, dltime: TdmaTime) {
+        Self::send_d_release_to(queue, request.call_id, request.calling_ssi, disconnect_cause, dltime);
+    }
+
+    fn send_d_release_to(queue: &mut MessageQueue, call_id: CallId, ssi: u32, disconnect_cause: u8, dltime: TdmaTime) {
+        let sdu = Self::build_d_release(call_id, disconnect_cause);
+        let prim = Self::build_sapmsg(sdu, None, dltime, TetraAddress::new(ssi, SsiType::Issi));
+        queue.push_back(prim);
+    }
+
+    /// Takes the first queued call request, if a circuit can be found for it now,
+    /// possibly by pre-empting a lower priority call
+    pub fn take_setup_request(&mut self) -> Option<SetupRequest> {
+        let request = self.setup_requests.front()?;
+        if !self.circuits.has_free_ts(Direction::Dl) && self.preemption_victim(request.priority).is_none() {
+            return None;
+        }
+        self.setup_requests.pop_front()
+    }
+
+    /// Sets the timeslots (1-4) circuits may be allocated on
+    pub fn set_traffic_timeslots(&mut self, timeslots: Vec<u8>) {
+        self.circuits.set_traffic_timeslots(timeslots);
+    }
+
+    /// Enables or disables the late entry repetition of the D-SETUPs of active group calls
+    pub fn set_late_entry(&mut self, enabled: bool) {
+        self.circuits.set_late_entry(enabled);
+    }
 
+    /// Sets the T301, T302 and T310 durations of calls set up from now on
+    pub fn set_call_timers(&mut self, timers: CallTimers) {
+        if let Some(t310) = timers.duration(CallTimer::T310) {
+            self.circuits.set_ul_only_max_age(t310);
+        }
+        self.timers = timers;
+    }
+
+    /// Sets the edition of the standard whose values are sent in elements no longer used since
+    pub fn set_protocol_edition(&mut self, edition: ProtocolEdition) {
+        self.protocol_edition = edition;
+    }
+
+    /// Allows emergency calls to release a lower priority call when
+    /// no circuit is free
+    pub fn set_preemption(&mut self, enabled: bool) {
+        self.preemption = enabled;
+    }
+
+    /// Assigns each call set up from now on a temporary address from `first` to `last`
+    pub fn set_temporary_address_range(&mut self, first: u32, last: u32) {
+        self.temporary_address_pool = Some(SsiAllocator::new(first, last));
+    }
+
+    /// Temporary address assigned to call `call_id`, if any
+    pub fn temporary_address(&self, call_id: CallId) -> Option<u32> {
+        self.temporary_addresses.get(&call_id).copied()
+    }
+
+    /// Takes a temporary address for call `call_id` from the pool, if one is configured.
+    /// The call proceeds without one if the pool is exhausted.
+    fn assign_temporary_address(&mut self, call_id: CallId) -> Option<u32> {
+        let pool = self.temporary_address_pool.as_mut()?;
+        let Some(address) = pool.allocate() else {
+            tracing::warn!("No temporary address left for call id {}", call_id);
+            return None;
+        };
+        self.temporary_addresses.insert(call_id, address);
+        Some(address)
+    }
+
+    /// Returns the temporary address of call `call_id`, if any, to the pool
+    fn release_temporary_address(&mut self, call_id: CallId) {
+        if let Some(address) = self.temporary_addresses.remove(&call_id)
+            && let Some(pool) = self.temporary_address_pool.as_mut() {
+            pool.release(address);
+        }
+    }
+
+    pub fn circuits(&self) -> &CircuitMgr {
+        &self.circuits
+    }
+
+    pub fn run_call_test(&mut self, queue: &mut MessageQueue, dltime: TdmaTime) {
         tracing::error!("-------- Running call test -------");
-        
-        // Create a new circuit
-        let circuit = match self.circuits.allocate_circuit(
-            Direction::Dl, 
-            CommunicationType::P2Mp) 
-        {
+        self.setup_group_call(queue, Self::test_group_address().ssi, Vec::new(), 0, dltime);
+    }
+
+    /// Sets up a call to group `gssi` on a free timeslot. The D-SETUP is sent once, addressed
+    /// to the GSSI, and reaches all `members` attached to it. If no timeslot is free and
+    /// `priority` is an emergency priority, a lower priority call may be released to make room.
+    pub fn setup_group_call(&mut self, queue: &mut MessageQueue, gssi: u32, members: Vec<u32>, priority: u8, dltime: TdmaTime) -> Option<CallId> {
+        self.open_group_call(queue, gssi, members, priority, None, dltime)
+    }
+
+    /// Sets up the group call requested in `request` like `setup_group_call`, and connects
+    /// the calling MS with D-CONNECT, granting it transmission
+    pub fn setup_requested_group_call(&mut self, queue: &mut MessageQueue, request: &SetupRequest, members: Vec<u32>, dltime: TdmaTime) -> Option<CallId> {
+        let call_id = self.open_group_call(queue, request.gssi, members, request.priority, Some(request), dltime)?;
+        self.send_d_connect(queue, call_id, request.calling_ssi, dltime);
+        Some(call_id)
+    }
+
+    fn open_group_call(&mut self, queue: &mut MessageQueue, gssi: u32, members: Vec<u32>, priority: u8, request: Option<&SetupRequest>, dltime: TdmaTime) -> Option<CallId> {
+
+        if !self.circuits.has_free_ts(Direction::Dl) {
+            self.preempt_call(queue, priority, dltime);
+        }
+
+        // Create a new circuit, keeping the call identifier given to a requesting MS
+        let allocated = match request {
+            Some(request) => self.circuits.allocate_circuit_with_id(Direction::Dl, CommunicationType::P2Mp, request.call_id),
+            None => self.circuits.allocate_circuit(Direction::Dl, CommunicationType::P2Mp),
+        };
+        let circuit = match allocated {
             Ok(circuit) => circuit,
             Err(e) => {
-                tracing::error!("Failed to allocate circuit for call test: {:?}", e);
-                return;
+                tracing::error!("Failed to allocate circuit for call to group {}: {:?}", gssi, e);
+                return None;
             }
         };  
 
         // Signal UMAC to setup the circuit
-        Self::signal_umac_circuit_open(queue, &circuit, dltime);
+        Self::signal_umac_circuit_open(queue, circuit, gssi, dltime);
 
         // Build D-SETUP PDU and send down the stack
-        let pdu_d_setup = Self::build_d_setup_pdu_from_circuit(&circuit);
-        self.cached_setups.insert(circuit.call_id, pdu_d_setup);
-        let pdu_ref = self.cached_setups.get(&circuit.call_id).unwrap();
+        let call_id = circuit.call_id;
+        let (ts, usage) = (circuit.ts, circuit.usage);
+        let mut pdu_d_setup = Self::build_d_setup_pdu_from_circuit(circuit);
+        pdu_d_setup.call_time_out = self.timers.call_timeout();
+        pdu_d_setup.call_priority = priority;
+        if let Some(request) = request {
+            // The group hears the calling MS, which is granted transmission in D-CONNECT
+            pdu_d_setup.calling_party_address_ssi = Some(request.calling_ssi);
+            pdu_d_setup.transmission_grant = TransmissionGrant::GrantedToOtherUser;
+        }
+        pdu_d_setup.temporary_address = self.assign_temporary_address(call_id).map(u64::from);
+        let (pdu, chan_alloc) = Self::build_d_setup_prim_from_pdu(&pdu_d_setup, ts, usage);
+        self.cached_setups.insert(call_id, pdu_d_setup);
+        self.group_calls.insert(call_id, GroupCall { gssi, members });
+        self.timers.start_call(call_id, dltime);
+
+        let prim = Self::build_sapmsg(pdu, Some(chan_alloc), dltime, TetraAddress::new(gssi, SsiType::Gssi));
+        queue.push_back(prim);
+        Some(call_id)
+    }
+
+    /// Sets up a call to the MS with `issi` on a free timeslot. The D-SETUP is only cached:
+    /// it is sent as the page for the MS by `send_page`.
+    pub fn setup_individual_call(&mut self, queue: &mut MessageQueue, issi: u32, dltime: TdmaTime) -> Option<CallId> {
+        self.open_individual_call(queue, issi, None, dltime)
+    }
+
+    /// Sets up the individual call requested in `request` like `setup_individual_call`, and
+    /// answers the calling MS with D-CALL PROCEEDING. It is alerted and connected once the
+    /// called MS alerts and connects.
+    pub fn setup_requested_individual_call(&mut self, queue: &mut MessageQueue, request: &IndividualSetupRequest, dltime: TdmaTime) -> Option<CallId> {
+        let call_id = self.open_individual_call(queue, request.called_ssi, Some(request), dltime)?;
+        self.send_d_call_proceeding(queue, call_id, request.calling_ssi, dltime);
+        Some(call_id)
+    }
+
+    fn open_individual_call(&mut self, queue: &mut MessageQueue, issi: u32, request: Option<&IndividualSetupRequest>, dltime: TdmaTime) -> Option<CallId> {
+        let allocated = match request {
+            Some(request) => self.circuits.allocate_circuit_with_id(Direction::Dl, CommunicationType::P2p, request.call_id),
+            None => self.circuits.allocate_circuit(Direction::Dl, CommunicationType::P2p),
+        };
+        let circuit = match allocated {
+            Ok(circuit) => circuit,
+            Err(e) => {
+                tracing::error!("Failed to allocate circuit for call to {}: {:?}", issi, e);
+                return None;
+            }
+        };
+        Self::signal_umac_circuit_open(queue, circuit, issi, dltime);
+
+        let call_id = circuit.call_id;
+        let mut pdu_d_setup = Self::build_d_setup_pdu_from_circuit(circuit);
+        pdu_d_setup.call_time_out = self.timers.call_timeout();
+        if let Some(request) = request {
+            pdu_d_setup.calling_party_address_ssi = Some(request.calling_ssi);
+        }
+        pdu_d_setup.temporary_address = self.assign_temporary_address(call_id).map(u64::from);
+        self.cached_setups.insert(call_id, pdu_d_setup);
+        self.individual_calls.insert(call_id, IndividualCall {
+            called_ssi: issi,
+            calling_ssi: request.map(|request| request.calling_ssi),
+        });
+        self.timers.start_call(call_id, dltime);
+        Some(call_id)
+    }
+
+    /// Whether call `call_id` is set up, from its allocation until its release
+    pub fn is_active(&self, call_id: CallId) -> bool {
+        self.cached_setups.contains_key(&call_id)
+    }
+
+    /// Whether `timer` runs for call `call_id`
+    pub fn is_timer_running(&self, call_id: CallId, timer: CallTimer) -> bool {
+        self.timers.is_running(call_id, timer)
+    }
+
+    /// Priority of active call `call_id`, as sent in its D-SETUP
+    pub fn call_priority(&self, call_id: CallId) -> Option<u8> {
+        self.cached_setups.get(&call_id).map(|pdu| pdu.call_priority)
+    }
+
+    /// Releases the active call with the lowest priority below `priority`, if pre-emption
+    /// is enabled and `priority` is an emergency priority. Returns whether a call was released.
+    fn preempt_call(&mut self, queue: &mut MessageQueue, priority: u8, dltime: TdmaTime) -> bool {
+        let Some((victim_priority, call_id, dir, ts)) = self.preemption_victim(priority) else {
+            return false;
+        };
+        match self.circuits.close_circuit(dir, ts) {
+            Ok(circuit) => {
+                tracing::info!("Pre-empting call id {} with priority {} for a call with priority {}", call_id, victim_priority, priority);
+                self.release_call(queue, call_id, circuit, DISCONNECT_CAUSE_PREEMPTION, dltime);
+                true
+            }
+            Err(e) => {
+                tracing::warn!("preempt_call: failed closing circuit for call id {}: {:?}", call_id, e);
+                false
+            }
+        }
+    }
+
+    /// Active call that a call with `priority` would pre-empt: the one with the lowest
+    /// priority below it, if pre-emption is enabled and `priority` is an emergency priority.
+    /// Returns its priority, call identifier, direction and timeslot.
+    fn preemption_victim(&self, priority: u8) -> Option<(u8, CallId, Direction, u8)> {
+        if !self.preemption || priority < self.emergency_priority {
+            return None;
+        }
+        self.circuits.dl.iter()
+            .flatten()
+            .map(|circuit| (self.call_priority(circuit.call_id).unwrap_or(0), circuit.call_id, circuit.direction, circuit.ts))
+            .filter(|(victim_priority, ..)| *victim_priority < priority)
+            .min_by_key(|(victim_priority, ..)| *victim_priority)
+    }
+
+    /// Sends D-RELEASE with `disconnect_cause` for call `call_id`, whose circuit was
+    /// closed, and signals UMAC to release the circuit
+    fn release_call(&mut self, queue: &mut MessageQueue, call_id: CallId, circuit: CmceCircuit, disconnect_cause: u8, dltime: TdmaTime) {
+        // Take our cached D-SETUP, build a prim and send it down the stack
+        match self.cached_setups.remove(&call_id) {
+            Some(pdu) => {
+                let sdu = Self::build_d_release(pdu.call_identifier, disconnect_cause);
+                let prim = Self::build_sapmsg(sdu, None, dltime, self.call_address(call_id));
+                queue.push_back(prim);
+            }
+            None => tracing::error!("No cached D-SETUP for call id {}", call_id),
+        }
+        self.group_calls.remove(&call_id);
+        self.individual_calls.remove(&call_id);
+        self.polls.remove(&call_id);
+        self.timers.stop_all(call_id);
+        self.release_temporary_address(call_id);
+
+        // Signal UMAC to release the circuit
+        Self::signal_umac_circuit_close(queue, circuit, dltime);
+    }
+
+    /// Group call with identifier `call_id`, if it is a group call
+    pub fn group_call(&self, call_id: CallId) -> Option<&GroupCall> {
+        self.group_calls.get(&call_id)
+    }
+
+    /// Polls the members of group call `call_id` by sending D-INFO with poll request to the
+    /// group. A poll already running for the call is restarted. Returns false if there is no
+    /// such group call.
+    pub fn send_poll_request(&mut self, queue: &mut MessageQueue, call_id: CallId, dltime: TdmaTime) -> bool {
+        let Some(call) = self.group_calls.get(&call_id) else {
+            tracing::warn!("send_poll_request: no group call with call id {}", call_id);
+            return false;
+        };
+        self.polls.insert(call_id, GroupPoll::new(call_id, call.members.clone()));
+
+        let pdu = DInfo {
+            call_identifier: call_id,
+            poll_request: true,
+            ..Default::default()
+        };
+        tracing::info!("-> {:?}", pdu);
 
-        let (pdu, chan_alloc) = Self::build_d_setup_prim_from_pdu(pdu_ref, circuit.usage);
-        let prim = Self::build_sapmsg(pdu, Some(chan_alloc), dltime);
+        let mut sdu = BitBuffer::new_autoexpand(21);
+        pdu.to_bitbuf(&mut sdu).expect("Failed to serialize DInfo");
+        sdu.seek(0);
+        let prim = Self::build_sapmsg(sdu, None, dltime, self.call_address(call_id));
+        queue.push_back(prim);
+        true
+    }
+
+    /// Poll of group call `call_id`, if one was started
+    pub fn poll(&self, call_id: CallId) -> Option<&GroupPoll> {
+        self.polls.get(&call_id)
+    }
+
+    /// Address that the PDUs of call `call_id` are sent to
+    fn call_address(&self, call_id: CallId) -> TetraAddress {
+        if let Some(call) = self.group_calls.get(&call_id) {
+            return TetraAddress::new(call.gssi, SsiType::Gssi);
+        }
+        match self.individual_calls.get(&call_id) {
+            Some(call) => TetraAddress::new(call.called_ssi, SsiType::Issi),
+            None => Self::test_group_address(),
+        }
+    }
+
+    /// Group addressed by the call test
+    fn test_group_address() -> TetraAddress {
+        TetraAddress::new(26, SsiType::Gssi)
+    }
+
+    /// Pages `ssi` for call `call_id` by sending it the cached D-SETUP of the call on the MCCH.
+    /// The channel is allocated once the MS connects, in D-CONNECT ACKNOWLEDGE.
+    pub fn send_page(&mut self, queue: &mut MessageQueue, ssi: u32, call_id: CallId, dltime: TdmaTime) {
+        let Some(pdu) = self.cached_setups.get(&call_id) else {
+            tracing::warn!("send_page: no cached D-SETUP for call id {}", call_id);
+            return;
+        };
+        tracing::info!("-> {:?} to ssi {}", pdu, ssi);
+
+        let mut sdu = BitBuffer::new_autoexpand(80);
+        pdu.to_bitbuf(&mut sdu).expect("Failed to serialize DSetup");
+        sdu.seek(0);
+        let prim = Self::build_sapmsg(sdu, None, dltime, TetraAddress::new(ssi, SsiType::Issi));
         queue.push_back(prim);
     }
 
+    /// Paged MS responded to the D-SETUP of call `call_id`. Unless its response alerts or
+    /// connects, it is to do so before T301 expires.
+    pub fn proceed_paged_call(&mut self, call_id: CallId, dltime: TdmaTime) {
+        if self.individual_calls.contains_key(&call_id) {
+            self.timers.start(call_id, CallTimer::T301, dltime);
+        }
+    }
+
+    /// Paged MS did not respond, drop the call and release its circuit, if any
+    pub fn abandon_paged_call(&mut self, queue: &mut MessageQueue, ssi: u32, call_id: CallId, dltime: TdmaTime) {
+        tracing::info!("abandon_paged_call: ssi {} did not respond, releasing call id {}", ssi, call_id);
+        self.cached_setups.remove(&call_id);
+        self.group_calls.remove(&call_id);
+        self.individual_calls.remove(&call_id);
+        self.polls.remove(&call_id);
+        self.timers.stop_all(call_id);
+        self.release_temporary_address(call_id);
+        let Some((dir, ts)) = self.find_circuit(call_id).map(|circuit| (circuit.direction, circuit.ts)) else {
+            return;
+        };
+        match self.circuits.close_circuit(dir, ts) {
+            Ok(circuit) => Self::signal_umac_circuit_close(queue, circuit, dltime),
+            Err(e) => tracing::warn!("abandon_paged_call: failed closing circuit for call id {}: {:?}", call_id, e),
+        }
+    }
+
+    fn find_circuit(&self, call_id: CallId) -> Option<&CmceCircuit> {
+        self.circuits.dl.iter()
+            .chain(self.circuits.ul_only.iter())
+            .flatten()
+            .find(|circuit| circuit.call_id == call_id)
+    }
+
     fn build_d_setup_pdu_from_circuit(circuit: &CmceCircuit) -> DSetup {
         DSetup { 
             call_identifier: circuit.call_id,
-            call_time_out: CallTimeout::Infinite, 
+            call_time_out: CallTimeout::Infinite, // Set from T310 by the caller
             hook_method_selection: false, 
             simplex_duplex_selection: circuit.simplex_duplex, 
             basic_service_information: BasicServiceInformation { 
@@ -80,7 +553,7 @@ impl CcBsSubentity {
         }
     }
 
-    fn build_d_setup_prim_from_pdu(pdu: &DSetup, usage: u8) -> (BitBuffer, CmceChanAllocReq) {
+    fn build_d_setup_prim_from_pdu(pdu: &DSetup, ts: u8, usage: u8) -> (BitBuffer, CmceChanAllocReq) {
 
         tracing::info!("-> {:?}", pdu);
 
@@ -88,18 +561,21 @@ impl CcBsSubentity {
         pdu.to_bitbuf(&mut sdu).expect("Failed to serialize DSetup");
         sdu.seek(0);
         
-        // Construct ChanAlloc descriptor
-        let chan_alloc = CmceChanAllocReq {
+        (sdu, Self::build_chan_alloc(ts, usage, UlDlAssignment::Dl))
+    }
+
+    /// Channel allocation moving the addressed MSs to the circuit on `ts` with `usage`
+    fn build_chan_alloc(ts: u8, usage: u8, ul_dl_assigned: UlDlAssignment) -> CmceChanAllocReq {
+        CmceChanAllocReq {
             usage: Some(usage),
             alloc_type: ChanAllocType::Replace,
             carrier: None,
-            timeslots: [false, true, false, false],
-            ul_dl_assigned: UlDlAssignment::Dl,
-        };
-        (sdu, chan_alloc)
+            timeslots: std::array::from_fn(|i| i + 1 == ts as usize),
+            ul_dl_assigned,
+        }
     }
 
-    fn build_sapmsg(sdu: BitBuffer, chan_alloc: Option<CmceChanAllocReq>, dltime: TdmaTime) -> SapMsg {
+    fn build_sapmsg(sdu: BitBuffer, chan_alloc: Option<CmceChanAllocReq>, dltime: TdmaTime, main_address: TetraAddress) -> SapMsg {
 
         // Construct prim
         SapMsg {
@@ -118,19 +594,17 @@ impl CcBsSubentity {
                 stealing_permission: false,
                 stealing_repeats_flag: false,
                 chan_alloc,
-                main_address: TetraAddress::new(26, SsiType::Gssi),
+                main_address,
             })
         }
     }
 
-    fn build_d_release_from_d_setup(d_setup_pdu: &DSetup) -> BitBuffer {
+    fn build_d_release(call_identifier: CallId, disconnect_cause: u8) -> BitBuffer {
 
         let pdu = DRelease {
-            call_identifier: d_setup_pdu.call_identifier,
-            disconnect_cause: 13, // todo fixme
-            notification_indicator: None,
-            facility: None,
-            proprietary: None,
+            call_identifier,
+            disconnect_cause,
+            ..Default::default()
         };
         tracing::info!("-> {:?}", pdu);
                         
@@ -140,16 +614,16 @@ impl CcBsSubentity {
         sdu
     }
 
-    fn send_d_call_proceeding(&mut self, queue: &mut MessageQueue, message: &SapMsg, pdu_request: &USetup, call_id: u16) {
+    /// Acknowledges the U-SETUP of call `call_id` to the calling MS while the call is set up
+    fn send_d_call_proceeding(&mut self, queue: &mut MessageQueue, call_id: CallId, calling_ssi: u32, dltime: TdmaTime) {
         tracing::trace!("send_d_call_proceeding");
 
-        let SapMsgInner::LcmcMleUnitdataInd(prim) = &message.msg else {panic!()};
-        
+        // U-SETUP with hook method or duplex selection is not accepted, see feature_check_u_setup
         let pdu_response = DCallProceeding {
             call_identifier: call_id,
             call_time_out_set_up_phase: CallTimeoutSetupPhase::T10s,
-            hook_method_selection: pdu_request.hook_method_selection,
-            simplex_duplex_selection: pdu_request.simplex_duplex_selection,
+            hook_method_selection: false,
+            simplex_duplex_selection: false,
             basic_service_information: None, // Only needed if different from requested
             call_status: None,
             notification_indicator: None,
@@ -162,47 +636,30 @@ impl CcBsSubentity {
         sdu.seek(0);
         tracing::debug!("send_d_call_proceeding: -> {:?} sdu {}", pdu_response, sdu.dump_bin());
 
-        let msg = SapMsg {
-            sap: Sap::LcmcSap,
-            src: TetraEntity::Cmce,
-            dest: TetraEntity::Mle,
-            dltime: message.dltime,
-            msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq{
-                sdu: sdu,
-                handle: prim.handle,
-                endpoint_id: prim.endpoint_id,
-                link_id: prim.link_id,
-                layer2service: 0,
-                pdu_prio: 0,
-                layer2_qos: 0,
-                stealing_permission: false,
-                stealing_repeats_flag: false,
-
-                chan_alloc: None,
-                main_address: prim.received_tetra_address,
-                // redundant_transmission: 1,
-            })
-        };
-        queue.push_back(msg);
+        let prim = Self::build_sapmsg(sdu, None, dltime, TetraAddress::new(calling_ssi, SsiType::Issi));
+        queue.push_back(prim);
     }
 
-
-    fn send_d_connect(&mut self, queue: &mut MessageQueue, message: &SapMsg, pdu_request: &USetup, call_id: u16) {
+    /// Connects the calling MS of call `call_id`, which was set up, and grants it transmission
+    fn send_d_connect(&mut self, queue: &mut MessageQueue, call_id: CallId, calling_ssi: u32, dltime: TdmaTime) {
         tracing::trace!("send_d_connect");
 
-        let SapMsgInner::LcmcMleUnitdataInd(prim) = &message.msg else {panic!()};
+        let Some((ts, usage)) = self.find_circuit(call_id).map(|circuit| (circuit.ts, circuit.usage)) else {
+            tracing::warn!("send_d_connect: no circuit for call id {}", call_id);
+            return;
+        };
 
         let pdu_response = DConnect {
             call_identifier: call_id,
-            call_time_out: CallTimeout::T30m,
-            hook_method_selection: pdu_request.hook_method_selection,
-            simplex_duplex_selection: pdu_request.simplex_duplex_selection,
+            call_time_out: self.timers.call_timeout(),
+            hook_method_selection: false,
+            simplex_duplex_selection: false,
             transmission_grant: TransmissionGrant::Granted,
             transmission_request_permission: false, // CHECKME an MS may not ask for transmit permission
             call_ownership: false, // Group call meaning: false = not a call owner
             call_priority: None,
             basic_service_information: None,
-            temporary_address: None,
+            temporary_address: self.temporary_address(call_id).map(u64::from),
             notification_indicator: None,
             facility: None,
             proprietary: None,
@@ -213,28 +670,79 @@ impl CcBsSubentity {
         sdu.seek(0);
         tracing::debug!("send_d_connect: -> {:?} sdu {}", pdu_response, sdu.dump_bin());
 
-        let msg = SapMsg {
-            sap: Sap::LcmcSap,
-            src: TetraEntity::Cmce,
-            dest: TetraEntity::Mle,
-            dltime: message.dltime,
-            msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq{
-                sdu: sdu,
-                handle: prim.handle,
-                endpoint_id: prim.endpoint_id,
-                link_id: prim.link_id,
-                layer2service: 0,
-                pdu_prio: 0,
-                layer2_qos: 0,
-                stealing_permission: false,
-                stealing_repeats_flag: false,
-                
-                chan_alloc: None,
-                main_address: prim.received_tetra_address,
-                // redundant_transmission: 1,
-            })
+        let chan_alloc = Self::build_chan_alloc(ts, usage, UlDlAssignment::Both);
+        let prim = Self::build_sapmsg(sdu, Some(chan_alloc), dltime, TetraAddress::new(calling_ssi, SsiType::Issi));
+        queue.push_back(prim);
+    }
+
+    /// Acknowledges the connection of the called MS of individual call `call_id` and moves it to
+    /// the circuit, with transmission as per `grant`
+    fn send_d_connect_acknowledge(&mut self, queue: &mut MessageQueue, call_id: CallId, called_ssi: u32, grant: TransmissionGrant, dltime: TdmaTime) {
+        let Some((ts, usage)) = self.find_circuit(call_id).map(|circuit| (circuit.ts, circuit.usage)) else {
+            tracing::warn!("send_d_connect_acknowledge: no circuit for call id {}", call_id);
+            return;
         };
-        queue.push_back(msg);
+        let pdu = DConnectAcknowledge {
+            call_identifier: call_id,
+            call_time_out: self.timers.call_timeout().into_raw() as u8,
+            transmission_grant: grant.into_raw() as u8,
+            transmission_request_permission: false,
+            notification_indicator: None,
+            facility: None,
+            proprietary: None,
+        };
+        tracing::info!("-> {:?}", pdu);
+
+        let mut sdu = BitBuffer::new_autoexpand(30);
+        pdu.to_bitbuf(&mut sdu).expect("Failed to serialize DConnectAcknowledge");
+        sdu.seek(0);
+        let chan_alloc = Self::build_chan_alloc(ts, usage, UlDlAssignment::Both);
+        let prim = Self::build_sapmsg(sdu, Some(chan_alloc), dltime, TetraAddress::new(called_ssi, SsiType::Issi));
+        queue.push_back(prim);
+    }
+
+    /// Tells the calling MS of individual call `call_id` that the called user is being alerted
+    fn send_d_alert(&mut self, queue: &mut MessageQueue, call_id: CallId, calling_ssi: u32, dltime: TdmaTime) {
+        let pdu = Self::build_d_alert(call_id, self.protocol_edition);
+        tracing::info!("-> {:?}", pdu);
+
+        let mut sdu = BitBuffer::new_autoexpand(25);
+        pdu.to_bitbuf(&mut sdu).expect("Failed to serialize DAlert");
+        sdu.seek(0);
+        let prim = Self::build_sapmsg(sdu, None, dltime, TetraAddress::new(calling_ssi, SsiType::Issi));
+        queue.push_back(prim);
+    }
+
+    fn build_d_alert(call_id: CallId, edition: ProtocolEdition) -> DAlert {
+        DAlert {
+            call_identifier: call_id,
+            call_time_out_set_up_phase: CallTimeoutSetupPhase::T10s.into_raw() as u8,
+            ..DAlert::for_edition(edition)
+        }
+    }
+
+    /// Grants transmission on group call `call_id` to `ssi`, and tells the other members
+    /// who is transmitting
+    fn send_d_tx_granted(&mut self, queue: &mut MessageQueue, call_id: CallId, ssi: u32, dltime: TdmaTime) {
+        let granted = Self::build_d_tx_granted(call_id, TransmissionGrant::Granted, None, self.protocol_edition);
+        let to_others = Self::build_d_tx_granted(call_id, TransmissionGrant::GrantedToOtherUser, Some(ssi), self.protocol_edition);
+        for (pdu, address) in [(granted, TetraAddress::new(ssi, SsiType::Issi)), (to_others, self.call_address(call_id))] {
+            tracing::info!("-> {:?}", pdu);
+            let mut sdu = BitBuffer::new_autoexpand(60);
+            pdu.to_bitbuf(&mut sdu).expect("Failed to serialize DTxGranted");
+            sdu.seek(0);
+            queue.push_back(Self::build_sapmsg(sdu, None, dltime, address));
+        }
+    }
+
+    fn build_d_tx_granted(call_id: CallId, grant: TransmissionGrant, transmitting_ssi: Option<u32>, edition: ProtocolEdition) -> DTxGranted {
+        DTxGranted {
+            call_identifier: call_id,
+            transmission_grant: grant.into_raw() as u8,
+            transmitting_party_type_identifier: transmitting_ssi.map(|_| 1),
+            transmitting_party_address_ssi: transmitting_ssi.map(u64::from),
+            ..DTxGranted::for_edition(edition)
+        }
     }
 
 
@@ -303,10 +811,12 @@ impl CcBsSubentity {
     //     queue.push_back(msg);
     // }
 
-    fn signal_umac_circuit_open(queue: &mut MessageQueue, call: &CmceCircuit, dltime: TdmaTime) {
+    fn signal_umac_circuit_open(queue: &mut MessageQueue, call: &CmceCircuit, ssi: u32, dltime: TdmaTime) {
         let circuit = Circuit {
             direction: call.direction,
-            ts: call.ts,            
+            ts: call.ts,
+            call_id: call.call_id,
+            ssi,
             usage: call.usage,
             circuit_mode: call.circuit_mode,
             speech_service: call.speech_service,
@@ -342,7 +852,7 @@ impl CcBsSubentity {
         let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {panic!()};
         // let calling_party = prim.received_tetra_address.clone();
         
-        let pdu = match USetup::from_bitbuf(&mut prim.sdu) {
+        let pdu = match timed_parse("USetup", &mut prim.sdu, USetup::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -359,6 +869,30 @@ impl CcBsSubentity {
             return;
         }
 
+        // Group calls get a call identifier now, and are checked and queued by CmceBs,
+        // which sets them up once a circuit is available
+        if pdu.basic_service_information.communication_type == CommunicationType::P2Mp
+            && let Some(gssi) = pdu.called_party_ssi {
+            self.received_setups.push(SetupRequest {
+                calling_ssi: prim.received_tetra_address.ssi,
+                gssi: gssi as u32,
+                priority: pdu.call_priority,
+                call_id: self.circuits.get_next_call_id(),
+            });
+            return;
+        }
+
+        // Individual calls likewise, and are set up by CmceBs, which pages the called MS
+        if pdu.basic_service_information.communication_type == CommunicationType::P2p
+            && let Some(called_ssi) = pdu.called_party_ssi {
+            self.received_individual_setups.push(IndividualSetupRequest {
+                calling_ssi: prim.received_tetra_address.ssi,
+                called_ssi: called_ssi as u32,
+                call_id: self.circuits.get_next_call_id(),
+            });
+            return;
+        }
+
         // let tx_grant = pdu.request_to_transmit_send_data;
 
         // Let's reserve an identifier, create the call FSM
@@ -384,6 +918,120 @@ impl CcBsSubentity {
         // queue.push_back(ctl_msg);
     }
 
+    fn rx_u_info(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_u_info: {:?}", message);
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {panic!()};
+        let ssi = prim.received_tetra_address.ssi;
+
+        let pdu = match timed_parse("UInfo", &mut prim.sdu, UInfo::from_bitbuf) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing UInfo: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        if !pdu.poll_response {
+            unimplemented_log!("UInfo without poll response");
+            return;
+        }
+        let Some(poll) = self.polls.get_mut(&pdu.call_identifier) else {
+            tracing::warn!("rx_u_info: poll response from {} for call id {} without poll", ssi, pdu.call_identifier);
+            return;
+        };
+        if !poll.respond(ssi) {
+            tracing::warn!("rx_u_info: poll response from {}, not a member of call id {}", ssi, pdu.call_identifier);
+            return;
+        }
+        tracing::info!("Poll of call id {}: {} responses ({}%)",
+            pdu.call_identifier, poll.response_number(), poll.response_percentage() as u32 * 2);
+    }
+
+    fn rx_u_alert(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_u_alert: {:?}", message);
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {panic!()};
+
+        let pdu = match timed_parse("UAlert", &mut prim.sdu, UAlert::from_bitbuf) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing UAlert: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        // The called user is alerted, and has until T302 expiry to answer
+        if self.timers.is_running(pdu.call_identifier, CallTimer::T301) {
+            self.timers.stop(pdu.call_identifier, CallTimer::T301);
+            self.timers.start(pdu.call_identifier, CallTimer::T302, message.dltime);
+        }
+        if let Some(calling_ssi) = self.individual_calls.get(&pdu.call_identifier).and_then(|call| call.calling_ssi) {
+            self.send_d_alert(queue, pdu.call_identifier, calling_ssi, message.dltime);
+        }
+    }
+
+    fn rx_u_connect(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_u_connect: {:?}", message);
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {panic!()};
+
+        let pdu = match timed_parse("UConnect", &mut prim.sdu, UConnect::from_bitbuf) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing UConnect: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        self.timers.stop(pdu.call_identifier, CallTimer::T301);
+        self.timers.stop(pdu.call_identifier, CallTimer::T302);
+
+        // The called MS is moved to the circuit. The calling MS of a requested
+        // individual call is connected along with it, and talks first.
+        let Some(&IndividualCall { called_ssi, calling_ssi }) = self.individual_calls.get(&pdu.call_identifier) else {
+            return;
+        };
+        if let Some(calling_ssi) = calling_ssi {
+            self.send_d_connect(queue, pdu.call_identifier, calling_ssi, message.dltime);
+        }
+        let grant = match calling_ssi {
+            Some(_) => TransmissionGrant::GrantedToOtherUser,
+            None => TransmissionGrant::Granted,
+        };
+        self.send_d_connect_acknowledge(queue, pdu.call_identifier, called_ssi, grant, message.dltime);
+    }
+
+    fn rx_u_tx_demand(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_u_tx_demand: {:?}", message);
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else {panic!()};
+        let ssi = prim.received_tetra_address.ssi;
+
+        let pdu = match timed_parse("UTxDemand", &mut prim.sdu, UTxDemand::from_bitbuf) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing UTxDemand: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        // TODO FIXME no floor control yet, the latest demand is granted
+        if !self.group_calls.contains_key(&pdu.call_identifier) {
+            tracing::warn!("rx_u_tx_demand: no group call with call id {}", pdu.call_identifier);
+            return;
+        }
+        self.send_d_tx_granted(queue, pdu.call_identifier, ssi, message.dltime);
+    }
+
     pub fn route_xx_deliver(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
         
         tracing::trace!("route_xx_deliver");
@@ -402,14 +1050,18 @@ impl CcBsSubentity {
         match pdu_type {
             CmcePduTypeUl::USetup => 
                 self.rx_u_setup(_queue, message),
-            CmcePduTypeUl::UAlert |
-            CmcePduTypeUl::UConnect |
+            CmcePduTypeUl::UInfo =>
+                self.rx_u_info(_queue, message),
+            CmcePduTypeUl::UAlert =>
+                self.rx_u_alert(_queue, message),
+            CmcePduTypeUl::UConnect =>
+                self.rx_u_connect(_queue, message),
+            CmcePduTypeUl::UTxDemand =>
+                self.rx_u_tx_demand(_queue, message),
             CmcePduTypeUl::UDisconnect |
-            CmcePduTypeUl::UInfo |
             CmcePduTypeUl::URelease |
             CmcePduTypeUl::UStatus |
             CmcePduTypeUl::UTxCeased |
-            CmcePduTypeUl::UTxDemand |
             CmcePduTypeUl::UCallRestore => {
                 unimplemented_log!("{}", pdu_type);
             }
@@ -424,36 +1076,69 @@ impl CcBsSubentity {
         if let Some(tasks) = self.circuits.tick_start(dltime) {
             for task in tasks {
                 match task {
-                    CircuitMgrCmd::SendDSetup(call_id, usage) => {
+                    CircuitMgrCmd::SendDSetup(call_id, ts, usage) => {
+                        // Individual calls are only sent their D-SETUP after paging
+                        if self.individual_calls.contains_key(&call_id) {
+                            continue;
+                        }
                         // Get our cached D-SETUP, build a prim and send it down the stack
                         let Some(pdu) = self.cached_setups.get(&call_id) else {
                             tracing::error!("No cached D-SETUP for call id {}", call_id);
                             return; 
                         };
                         tracing::info!("-> {:?}", pdu);
-                        let (pdu, chan_alloc) = Self::build_d_setup_prim_from_pdu(pdu, usage);
-                        let prim = Self::build_sapmsg(pdu, Some(chan_alloc), self.dltime);
+                        let (pdu, chan_alloc) = Self::build_d_setup_prim_from_pdu(pdu, ts, usage);
+                        let prim = Self::build_sapmsg(pdu, Some(chan_alloc), self.dltime, self.call_address(call_id));
                         queue.push_back(prim);
                     },
-
                     CircuitMgrCmd::SendClose(call_id, circuit) => {
-                        tracing::warn!("need to send CLOSE for call id {}", call_id);
-                        // Get our cached D-SETUP, build a prim and send it down the stack
-                        let Some(pdu) = self.cached_setups.get(&call_id) else {
-                            tracing::error!("No cached D-SETUP for call id {}", call_id);
-                            return; 
-                        };
-                        
-                        let sdu = Self::build_d_release_from_d_setup(pdu);
-                        let prim = Self::build_sapmsg(sdu, None, self.dltime);
-                        queue.push_back(prim);
-
-                        // Signal UMAC to release the circuit
-                        Self::signal_umac_circuit_close(queue, circuit, self.dltime);
+                        tracing::info!("Ul-only circuit of call id {} expired", call_id);
+                        self.release_call(queue, call_id, circuit, DISCONNECT_CAUSE_TIMER_EXPIRY, self.dltime);
                     }
                 }
             }
         }
+
+        for (call_id, timer) in self.timers.tick(dltime) {
+            match timer {
+                CallTimer::T310Reset => self.send_t310_reset(queue, call_id, dltime),
+                _ => self.expire_call(queue, call_id, timer, dltime),
+            }
+        }
+    }
+
+    /// Keeps active call `call_id` alive by sending D-INFO resetting T310 to its
+    /// address, and restarting T310 and the reset interval
+    fn send_t310_reset(&mut self, queue: &mut MessageQueue, call_id: CallId, dltime: TdmaTime) {
+        let pdu = DInfo {
+            call_identifier: call_id,
+            reset_call_time_out_timer_t310_: true,
+            ..Default::default()
+        };
+        tracing::info!("-> {:?}", pdu);
+
+        let mut sdu = BitBuffer::new_autoexpand(21);
+        pdu.to_bitbuf(&mut sdu).expect("Failed to serialize DInfo");
+        sdu.seek(0);
+        let prim = Self::build_sapmsg(sdu, None, dltime, self.call_address(call_id));
+        queue.push_back(prim);
+        self.timers.start_call(call_id, dltime);
+    }
+
+    /// Releases call `call_id`, which stalled or exceeded its maximum length
+    fn expire_call(&mut self, queue: &mut MessageQueue, call_id: CallId, timer: CallTimer, dltime: TdmaTime) {
+        let Some((dir, ts)) = self.find_circuit(call_id).map(|circuit| (circuit.direction, circuit.ts)) else {
+            tracing::warn!("expire_call: {:?} expired for call id {} without circuit", timer, call_id);
+            self.timers.stop_all(call_id);
+            return;
+        };
+        match self.circuits.close_circuit(dir, ts) {
+            Ok(circuit) => {
+                tracing::info!("{:?} expired, releasing call id {}", timer, call_id);
+                self.release_call(queue, call_id, circuit, DISCONNECT_CAUSE_TIMER_EXPIRY, dltime);
+            }
+            Err(e) => tracing::warn!("expire_call: failed closing circuit for call id {}: {:?}", call_id, e),
+        }
     }
 
     fn feature_check_u_setup(pdu: &USetup) -> bool {
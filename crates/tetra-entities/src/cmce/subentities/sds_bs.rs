@@ -1,25 +1,217 @@
-use tetra_core::unimplemented_log;
+use std::collections::HashMap;
+
+use tetra_config::{CfgSdsAutoresponder, CfgSdsRule, SdsTextCoding};
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, tetra_entities::TetraEntity, unimplemented_log};
 use crate::MessageQueue;
-use tetra_saps::{SapMsg, SapMsgInner};
+use crate::cmce::components::{lip::decode_location_report, location_store::LocationStore, sds_protocol::SdsProtocol, sds_text::{decode_text, encode_text}};
+use tetra_saps::{SapMsg, SapMsgInner, lcmc::LcmcMleUnitdataReq};
+
+use tetra_pdus::cmce::{enums::cmce_pdu_type_dl::CmcePduTypeDl, pdus::{d_sds_data::DSdsData, u_sds_data::USdsData}};
+use tetra_pdus::parse_timing::timed_parse;
 
-use tetra_pdus::cmce::{enums::cmce_pdu_type_dl::CmcePduTypeDl, pdus::d_sds_data::DSdsData};
+/// Longest user defined data 4 the length indicator can describe
+const MAX_USER_DEFINED_DATA_4_BITS: usize = (1 << 11) - 1;
 
 /// Clause 13 Short Data Service CMCE sub-entity
 pub struct SdsBsSubentity{
-
+    /// Number of type 4 SDS received per protocol
+    received: HashMap<SdsProtocol, usize>,
+    /// Locations reported by each MS
+    locations: LocationStore,
 }
 
 impl SdsBsSubentity {
     /// Create a new instance of the SdsSubentity
     pub fn new() -> Self {
-        SdsBsSubentity {}
+        SdsBsSubentity {
+            received: HashMap::new(),
+            locations: LocationStore::default(),
+        }
+    }
+
+    /// Number of type 4 SDS received from MSs using `protocol`
+    pub fn received(&self, protocol: SdsProtocol) -> usize {
+        self.received.get(&protocol).copied().unwrap_or(0)
+    }
+
+    /// Locations reported by MSs over LIP
+    pub fn locations(&self) -> &LocationStore {
+        &self.locations
+    }
+
+    /// Dispatches SDS type 4 user data from `ssi` to the handler of its protocol
+    fn rx_user_data(&mut self, ssi: u32, pdu: &USdsData, data: &[u8], len_bits: usize, dltime: TdmaTime) {
+        let Some(&pid) = data.first().filter(|_| len_bits >= 8) else {
+            tracing::warn!("rx_user_data: SDS from {} without protocol identifier", ssi);
+            return;
+        };
+        let protocol = SdsProtocol::from_pid(pid);
+        *self.received.entry(protocol).or_default() += 1;
+        match protocol {
+            SdsProtocol::SimpleText | SdsProtocol::SimpleImmediateText | SdsProtocol::TlText | SdsProtocol::TlImmediateText => {
+                Self::rx_text(ssi, pdu, data, len_bits)
+            }
+            SdsProtocol::Lip => self.rx_lip(ssi, data, len_bits, dltime),
+            _ => unimplemented_log!("rx_user_data: SDS protocol {:?} from {}", protocol, ssi),
+        }
+    }
+
+    /// Text messages are not forwarded anywhere yet
+    fn rx_text(ssi: u32, pdu: &USdsData, data: &[u8], len_bits: usize) {
+        match decode_text(data, len_bits) {
+            Some(text) => tracing::info!("SDS text from {} to {:?}: {:?}", ssi, pdu.called_party_ssi, text),
+            None => tracing::warn!("rx_text: undecodable text message from {}", ssi),
+        }
+    }
+
+    /// Adds the position of a LIP short location report to the location history of `ssi`
+    fn rx_lip(&mut self, ssi: u32, data: &[u8], len_bits: usize, dltime: TdmaTime) {
+        match decode_location_report(data, len_bits) {
+            Ok(report) => {
+                tracing::info!("Location of {}: {:.5} {:.5}, error {:?} m", ssi, report.latitude, report.longitude, report.position_error_m);
+                self.locations.record(ssi, dltime, report);
+            }
+            Err(e) => tracing::warn!("rx_lip: failed decoding location report from {}: {:?}", ssi, e),
+        }
+    }
+
+    /// Builds the auto-responder reply to an SDS sent to `called_ssi`
+    fn build_reply(rule: &CfgSdsRule, called_ssi: u64) -> DSdsData {
+        let mut pdu = DSdsData {
+            calling_party_type_identifier: 1,
+            calling_party_address_ssi: Some(called_ssi),
+            ..Default::default()
+        };
+        if let Some(status) = rule.reply_status {
+            pdu.short_data_type_identifier = 0;
+            pdu.user_defined_data_1 = Some(status as u64);
+        } else if let Some(ref text) = rule.reply_text {
+            let (data, len) = encode_text(text, SdsTextCoding::Latin1).expect("reply_text is ASCII");
+            pdu.short_data_type_identifier = 3;
+            pdu.length_indicator = Some(len as u64);
+            pdu.user_defined_data_4 = Some(data);
+        }
+        pdu
+    }
+
+    /// Sends `text` to `issi` as a simple text message from `sender_ssi`. Returns false,
+    /// sending nothing, if the text can not be represented in `coding` or is too long
+    /// for the 11-bit length indicator.
+    pub fn send_text(&mut self, queue: &mut MessageQueue, sender_ssi: u32, issi: u32, text: &str, coding: SdsTextCoding, dltime: TdmaTime) -> bool {
+        let Some((data, len)) = encode_text(text, coding) else {
+            tracing::warn!("send_text: text to {} can not be represented in {:?}", issi, coding);
+            return false;
+        };
+        if len > MAX_USER_DEFINED_DATA_4_BITS {
+            tracing::warn!("send_text: text to {} too long, {} bits", issi, len);
+            return false;
+        }
+        let pdu = DSdsData {
+            calling_party_type_identifier: 1,
+            calling_party_address_ssi: Some(sender_ssi as u64),
+            short_data_type_identifier: 3,
+            length_indicator: Some(len as u64),
+            user_defined_data_4: Some(data),
+            ..Default::default()
+        };
+        tracing::info!("-> {:?} to ssi {}", pdu, issi);
+        let mut sdu = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut sdu).expect("Failed to serialize DSdsData");
+        sdu.seek(0);
+
+        queue.push_back(SapMsg {
+            sap: Sap::LcmcSap,
+            src: TetraEntity::Cmce,
+            dest: TetraEntity::Mle,
+            dltime,
+            msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
+                sdu,
+                handle: 0,
+                endpoint_id: 0,
+                link_id: 0,
+                layer2service: 0,
+                pdu_prio: 0,
+                layer2_qos: 0,
+                stealing_permission: false,
+                stealing_repeats_flag: false,
+                chan_alloc: None,
+                main_address: TetraAddress::new(issi, SsiType::Issi),
+            }),
+        });
+        true
+    }
+
+    fn rx_u_sds_data(&mut self, queue: &mut MessageQueue, mut message: SapMsg, autoresponder: Option<&CfgSdsAutoresponder>) {
+        tracing::trace!("rx_u_sds_data");
+
+        let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else { panic!(); };
+        let pdu = match timed_parse("USdsData", &mut prim.sdu, USdsData::from_bitbuf) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing USdsData: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        // Type 4 user data is dispatched by protocol, statuses are matched against the auto-responder
+        if let (Some(data), Some(len)) = (pdu.user_defined_data_4.as_deref(), pdu.length_indicator) {
+            self.rx_user_data(prim.received_tetra_address.ssi, &pdu, data, len as usize, message.dltime);
+            return;
+        }
+        let Some(status) = pdu.user_defined_data_1 else {
+            unimplemented_log!("rx_u_sds_data: SDS type {}", pdu.short_data_type_identifier + 1);
+            return;
+        };
+        let Some(rule) = autoresponder.and_then(|a| a.rule_for(status as u16)) else {
+            tracing::debug!("rx_u_sds_data: no auto-responder rule for status {:#06x}", status);
+            return;
+        };
+        let Some(called_ssi) = pdu.called_party_ssi else {
+            tracing::warn!("rx_u_sds_data: can not reply to SDS sent to a short number address");
+            return;
+        };
+
+        let reply = Self::build_reply(rule, called_ssi);
+        tracing::info!("-> {:?}", reply);
+        let mut sdu = BitBuffer::new_autoexpand(64);
+        reply.to_bitbuf(&mut sdu).expect("Failed to serialize DSdsData");
+        sdu.seek(0);
+
+        queue.push_back(SapMsg {
+            sap: Sap::LcmcSap,
+            src: TetraEntity::Cmce,
+            dest: TetraEntity::Mle,
+            dltime: message.dltime,
+            msg: SapMsgInner::LcmcMleUnitdataReq(LcmcMleUnitdataReq {
+                sdu,
+                handle: prim.handle,
+                endpoint_id: prim.endpoint_id,
+                link_id: prim.link_id,
+                layer2service: 0,
+                pdu_prio: 0,
+                layer2_qos: 0,
+                stealing_permission: false,
+                stealing_repeats_flag: false,
+                chan_alloc: None,
+                main_address: prim.received_tetra_address,
+            }),
+        });
+    }
+
+    /// Handles SDS received from an MS, answering it if it matches an auto-responder rule
+    pub fn route_xx_deliver(&mut self, queue: &mut MessageQueue, message: SapMsg, autoresponder: Option<&CfgSdsAutoresponder>) {
+        tracing::trace!("route_xx_deliver");
+        self.rx_u_sds_data(queue, message, autoresponder);
     }
 
     pub fn rx_sds_data(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_sds_data");
 
         let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else { panic!(); };
-        let _pdu = match DSdsData::from_bitbuf(&mut prim.sdu) {
+        let _pdu = match timed_parse("DSdsData", &mut prim.sdu, DSdsData::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("Received DSdsData: {:?}", pdu);
                 pdu
@@ -1,5 +1,6 @@
 use tetra_core::unimplemented_log;
 use tetra_pdus::cmce::{enums::cmce_pdu_type_dl::CmcePduTypeDl, pdus::d_sds_data::DSdsData};
+use tetra_pdus::parse_timing::timed_parse;
 use tetra_saps::{SapMsg, SapMsgInner};
 
 use crate::MessageQueue;
@@ -19,7 +20,7 @@ impl SdsMsSubentity {
         tracing::trace!("rx_sds_data");
 
         let SapMsgInner::LcmcMleUnitdataInd(prim) = &mut message.msg else { panic!(); };
-        let _pdu = match DSdsData::from_bitbuf(&mut prim.sdu) {
+        let _pdu = match timed_parse("DSdsData", &mut prim.sdu, DSdsData::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("Received DSdsData: {:?}", pdu);
                 pdu
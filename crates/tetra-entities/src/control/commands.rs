@@ -0,0 +1,190 @@
+use core::fmt;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use tetra_config::SharedConfig;
+
+use crate::control::queries::{ControlQuery, ControlRequest};
+
+/// Maximum time to wait for the stack thread to answer a query
+const QUERY_TIMEOUT: Duration = Duration::from_secs(1);
+
+
+#[derive(Debug, PartialEq)]
+pub enum ControlErr {
+    EmptyCommand,
+    UnknownCommand(String),
+    UnknownKey(String),
+    MissingArgument(&'static str),
+    TooManyArguments,
+    InvalidValue { key: &'static str, value: String },
+    OutOfRange { key: &'static str, value: u64, max: u64 },
+    StackNotResponding,
+}
+
+impl fmt::Display for ControlErr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ControlErr::EmptyCommand => write!(f, "empty command"),
+            ControlErr::UnknownCommand(cmd) => write!(f, "unknown command: {}", cmd),
+            ControlErr::UnknownKey(key) => write!(f, "unknown key: {}", key),
+            ControlErr::MissingArgument(arg) => write!(f, "missing argument: {}", arg),
+            ControlErr::TooManyArguments => write!(f, "too many arguments"),
+            ControlErr::InvalidValue { key, value } => write!(f, "invalid value for {}: {}", key, value),
+            ControlErr::OutOfRange { key, value, max } => write!(f, "value for {} out of range: {} (max {})", key, value, max),
+            ControlErr::StackNotResponding => write!(f, "stack not responding"),
+        }
+    }
+}
+
+/// Parses and executes a single control command line, returning the response text.
+/// Supported commands:
+/// - `get <key>`: read a StackState value
+/// - `set <key> <value>`: validate and write a StackState value
+/// - `list clients` / `list calls`: snapshot of registered MSs or active calls, answered by the stack thread
+/// - `list network`: summary of the broadcast parameters observed in MS / monitor mode
+/// - `location <issi>`: latest location the MS reported over LIP
+/// - `stats pdus`: number of MM and CMCE PDUs decoded and generated, by PDU type
+pub fn handle_command(config: &SharedConfig, queries: &Sender<ControlRequest>, line: &str) -> Result<String, ControlErr> {
+    let mut args = line.split_whitespace();
+    let cmd = args.next().ok_or(ControlErr::EmptyCommand)?;
+    match cmd {
+        "get" => {
+            let key = args.next().ok_or(ControlErr::MissingArgument("key"))?;
+            if args.next().is_some() {
+                return Err(ControlErr::TooManyArguments);
+            }
+            get_state(config, key)
+        }
+        "set" => {
+            let key = args.next().ok_or(ControlErr::MissingArgument("key"))?;
+            let value = args.next().ok_or(ControlErr::MissingArgument("value"))?;
+            if args.next().is_some() {
+                return Err(ControlErr::TooManyArguments);
+            }
+            set_state(config, key, value)
+        }
+        "list" => {
+            let what = args.next().ok_or(ControlErr::MissingArgument("what"))?;
+            if args.next().is_some() {
+                return Err(ControlErr::TooManyArguments);
+            }
+            let query = match what {
+                "clients" => ControlQuery::ListClients,
+                "calls" => ControlQuery::ListCalls,
+                "network" => ControlQuery::NetworkSummary,
+                _ => return Err(ControlErr::UnknownKey(what.to_string())),
+            };
+            run_query(queries, query)
+        }
+        "stats" => {
+            let what = args.next().ok_or(ControlErr::MissingArgument("what"))?;
+            if args.next().is_some() {
+                return Err(ControlErr::TooManyArguments);
+            }
+            match what {
+                "pdus" => run_query(queries, ControlQuery::PduStats),
+                _ => Err(ControlErr::UnknownKey(what.to_string())),
+            }
+        }
+        "location" => {
+            let issi = args.next().ok_or(ControlErr::MissingArgument("issi"))?;
+            if args.next().is_some() {
+                return Err(ControlErr::TooManyArguments);
+            }
+            let issi = parse_ranged("issi", issi, 0xFFFFFF)?;
+            run_query(queries, ControlQuery::Location(issi as u32))
+        }
+        _ => Err(ControlErr::UnknownCommand(cmd.to_string())),
+    }
+}
+
+/// Submits a query to the stack thread and waits for its answer
+pub(crate) fn run_query(queries: &Sender<ControlRequest>, query: ControlQuery) -> Result<String, ControlErr> {
+    let (reply, answer) = crossbeam_channel::bounded(1);
+    queries.send(ControlRequest { query, reply }).map_err(|_| ControlErr::StackNotResponding)?;
+    answer.recv_timeout(QUERY_TIMEOUT).map_err(|_| ControlErr::StackNotResponding)
+}
+
+fn get_state(config: &SharedConfig, key: &str) -> Result<String, ControlErr> {
+    let state = config.state_read();
+    match key {
+        "cell_load_ca" => Ok(format!("cell_load_ca = {}", state.cell_load_ca)),
+        _ => Err(ControlErr::UnknownKey(key.to_string())),
+    }
+}
+
+fn set_state(config: &SharedConfig, key: &str, value: &str) -> Result<String, ControlErr> {
+    match key {
+        "cell_load_ca" => {
+            // 2 bits, as broadcast in D-MLE-SYNC
+            let v = parse_ranged("cell_load_ca", value, 3)?;
+            config.state_write().cell_load_ca = v as u8;
+            tracing::info!("control: set cell_load_ca = {}", v);
+            Ok(format!("cell_load_ca = {}", v))
+        }
+        _ => Err(ControlErr::UnknownKey(key.to_string())),
+    }
+}
+
+/// Parses a decimal or 0x-prefixed hexadecimal value and checks it does not exceed `max`
+fn parse_ranged(key: &'static str, value: &str, max: u64) -> Result<u64, ControlErr> {
+    let parsed = if let Some(hex) = value.strip_prefix("0x") {
+        u64::from_str_radix(hex, 16)
+    } else {
+        value.parse::<u64>()
+    };
+    let v = parsed.map_err(|_| ControlErr::InvalidValue { key, value: value.to_string() })?;
+    if v > max {
+        return Err(ControlErr::OutOfRange { key, value: v, max });
+    }
+    Ok(v)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tetra_config::{PhyBackend, StackConfig, StackMode};
+
+    use super::*;
+
+    fn test_config() -> SharedConfig {
+        let mut cfg = StackConfig::new(StackMode::Bs, 204, 1337);
+        cfg.phy_io.backend = PhyBackend::None;
+        SharedConfig::from_config(cfg)
+    }
+
+    #[test]
+    fn test_set_cell_load_ca() {
+        let config = test_config();
+        let (queries, _rx) = crossbeam_channel::unbounded();
+        assert_eq!(config.state_read().cell_load_ca, 0);
+
+        assert_eq!(handle_command(&config, &queries, "set cell_load_ca 2"), Ok("cell_load_ca = 2".to_string()));
+        assert_eq!(config.state_read().cell_load_ca, 2);
+
+        assert_eq!(handle_command(&config, &queries, "  set  cell_load_ca   0x3 "), Ok("cell_load_ca = 3".to_string()));
+        assert_eq!(handle_command(&config, &queries, "get cell_load_ca"), Ok("cell_load_ca = 3".to_string()));
+    }
+
+    #[test]
+    fn test_set_rejects_invalid_input() {
+        let config = test_config();
+        let (queries, _rx) = crossbeam_channel::unbounded();
+        handle_command(&config, &queries, "set cell_load_ca 1").unwrap();
+
+        assert_eq!(handle_command(&config, &queries, "set cell_load_ca 4"), Err(ControlErr::OutOfRange { key: "cell_load_ca", value: 4, max: 3 }));
+        assert_eq!(handle_command(&config, &queries, "set cell_load_ca two"), Err(ControlErr::InvalidValue { key: "cell_load_ca", value: "two".to_string() }));
+        assert_eq!(handle_command(&config, &queries, "set cell_load_ca"), Err(ControlErr::MissingArgument("value")));
+        assert_eq!(handle_command(&config, &queries, "set cell_load_ca 1 2"), Err(ControlErr::TooManyArguments));
+        assert_eq!(handle_command(&config, &queries, "set colour_code 2"), Err(ControlErr::UnknownKey("colour_code".to_string())));
+        assert_eq!(handle_command(&config, &queries, "reboot"), Err(ControlErr::UnknownCommand("reboot".to_string())));
+        assert_eq!(handle_command(&config, &queries, ""), Err(ControlErr::EmptyCommand));
+        assert_eq!(handle_command(&config, &queries, "list groups"), Err(ControlErr::UnknownKey("groups".to_string())));
+        assert_eq!(handle_command(&config, &queries, "stats frames"), Err(ControlErr::UnknownKey("frames".to_string())));
+        assert_eq!(handle_command(&config, &queries, "location 0x1000000"), Err(ControlErr::OutOfRange { key: "issi", value: 0x1000000, max: 0xFFFFFF }));
+
+        // State is unchanged by any of the rejected commands
+        assert_eq!(config.state_read().cell_load_ca, 1);
+    }
+}
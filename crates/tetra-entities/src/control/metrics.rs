@@ -0,0 +1,114 @@
+//! Stack counters in the Prometheus text exposition format
+
+use std::fmt::Write;
+
+use crate::stack_summary::StackSummary;
+
+
+/// Writes the `# HELP` and `# TYPE` lines introducing metric `name`
+fn write_header(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Formats the counters of `summary`, one metric family per counter.
+/// Counters of entities that are not registered are left out.
+pub fn prometheus_text(summary: &StackSummary) -> String {
+    let mut out = String::new();
+
+    write_header(&mut out, "tetra_ticks_total", "counter", "Timeslots run by the stack");
+    let _ = writeln!(out, "tetra_ticks_total {}", summary.ticks);
+
+    write_header(&mut out, "tetra_primitives_total", "counter", "Primitives routed between entities, by primitive");
+    for (name, count) in &summary.primitives {
+        let _ = writeln!(out, "tetra_primitives_total{{primitive=\"{}\"}} {}", name, count);
+    }
+
+    write_header(&mut out, "tetra_pdus_total", "counter", "MM and CMCE PDUs decoded from the air interface or generated, by PDU type");
+    for (pdu, count) in &summary.pdus {
+        let _ = writeln!(out, "tetra_pdus_total{{pdu=\"{}\",origin=\"decoded\"}} {}", pdu, count.decoded);
+        let _ = writeln!(out, "tetra_pdus_total{{pdu=\"{}\",origin=\"generated\"}} {}", pdu, count.generated);
+    }
+
+    if let Some(calls) = summary.calls_handled {
+        write_header(&mut out, "tetra_calls_handled_total", "counter", "Calls a circuit was allocated for");
+        let _ = writeln!(out, "tetra_calls_handled_total {}", calls);
+    }
+    if let Some(registered) = summary.ms_registered {
+        write_header(&mut out, "tetra_ms_registered", "gauge", "MSs currently registered");
+        let _ = writeln!(out, "tetra_ms_registered {}", registered);
+    }
+    if let Some(registrations) = summary.registrations {
+        write_header(&mut out, "tetra_registrations_total", "counter", "Successful registrations, including re-registrations");
+        let _ = writeln!(out, "tetra_registrations_total {}", registrations);
+    }
+
+    write_header(&mut out, "tetra_sdr_rx_overruns_total", "counter", "SDR RX overruns");
+    let _ = writeln!(out, "tetra_sdr_rx_overruns_total {}", summary.sdr_rx_overruns);
+    write_header(&mut out, "tetra_sdr_tx_underruns_total", "counter", "SDR TX underruns");
+    let _ = writeln!(out, "tetra_sdr_tx_underruns_total {}", summary.sdr_tx_underruns);
+    write_header(&mut out, "tetra_sdr_tx_reinits_total", "counter", "SDR TX stream re-initializations after sustained underrun");
+    let _ = writeln!(out, "tetra_sdr_tx_reinits_total {}", summary.sdr_tx_reinits);
+    write_header(&mut out, "tetra_tdma_discontinuities_total", "counter", "Jumps in the slot numbering of the RX/TX device relative to the stack");
+    let _ = writeln!(out, "tetra_tdma_discontinuities_total {}", summary.tdma_discontinuities);
+    out
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use crate::stack_summary::PduCount;
+
+    use super::*;
+
+    #[test]
+    fn test_prometheus_text() {
+        let summary = StackSummary {
+            ticks: 1234,
+            primitives: BTreeMap::from([("LcmcMleUnitdataReq", 7), ("TmvUnitdataReq", 1234)]),
+            pdus: BTreeMap::from([("DSetup".to_string(), PduCount { decoded: 0, generated: 3 })]),
+            calls_handled: Some(2),
+            ms_registered: Some(1),
+            registrations: Some(4),
+            sdr_rx_overruns: 5,
+            sdr_tx_underruns: 6,
+            sdr_tx_reinits: 0,
+            tdma_discontinuities: 1,
+        };
+        let out = prometheus_text(&summary);
+
+        // Every sample belongs to a family introduced by HELP and TYPE lines
+        let mut types = HashMap::new();
+        let mut helps = 0;
+        for line in out.lines() {
+            if let Some(help) = line.strip_prefix("# HELP ") {
+                let (name, text) = help.split_once(' ').unwrap();
+                assert!(!text.is_empty(), "{}", line);
+                assert!(!types.contains_key(name), "HELP after TYPE: {}", line);
+                helps += 1;
+            } else if let Some(kind) = line.strip_prefix("# TYPE ") {
+                let (name, kind) = kind.split_once(' ').unwrap();
+                assert!(kind == "counter" || kind == "gauge", "{}", line);
+                assert!(types.insert(name.to_string(), kind.to_string()).is_none(), "duplicate TYPE: {}", line);
+            } else {
+                let (metric, value) = line.rsplit_once(' ').unwrap();
+                let name = metric.split('{').next().unwrap();
+                assert!(types.contains_key(name), "sample without TYPE: {}", line);
+                assert!(value.parse::<u64>().is_ok(), "{}", line);
+            }
+        }
+        assert_eq!(helps, 10);
+        assert_eq!(types.len(), 10);
+        assert_eq!(types["tetra_ms_registered"], "gauge");
+
+        assert!(out.contains("tetra_ticks_total 1234\n"));
+        assert!(out.contains("tetra_primitives_total{primitive=\"LcmcMleUnitdataReq\"} 7\n"));
+        assert!(out.contains("tetra_pdus_total{pdu=\"DSetup\",origin=\"generated\"} 3\n"));
+        assert!(out.contains("tetra_calls_handled_total 2\n"));
+        assert!(out.contains("tetra_sdr_rx_overruns_total 5\n"));
+        assert!(out.contains("tetra_sdr_tx_underruns_total 6\n"));
+        assert!(out.contains("tetra_tdma_discontinuities_total 1\n"));
+    }
+}
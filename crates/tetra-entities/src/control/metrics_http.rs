@@ -0,0 +1,73 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+
+use crate::control::commands::run_query;
+use crate::control::queries::{ControlQuery, ControlRequest};
+
+/// Time a client may take to send its request or receive the response. Clients are
+/// served one at a time, so a stalled client would otherwise block the endpoint.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Binds an HTTP endpoint at `addr` serving the stack counters in Prometheus text format
+/// at `/metrics`, from a background thread. Clients are served one at a time.
+/// The counters are collected through `queries`, see MessageRouter::open_control_queries.
+pub fn spawn_metrics_endpoint(queries: Sender<ControlRequest>, addr: SocketAddr) -> std::io::Result<thread::JoinHandle<()>> {
+    let listener = TcpListener::bind(addr)?;
+    tracing::info!("metrics: listening on http://{}/metrics", addr);
+
+    let handle = thread::Builder::new()
+        .name("metrics-http".to_string())
+        .spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => serve_client(&queries, stream),
+                    Err(e) => tracing::warn!("metrics: failed accepting connection: {}", e),
+                }
+            }
+        })?;
+    Ok(handle)
+}
+
+fn serve_client(queries: &Sender<ControlRequest>, stream: TcpStream) {
+    if let Err(e) = stream.set_read_timeout(Some(CLIENT_TIMEOUT)).and_then(|_| stream.set_write_timeout(Some(CLIENT_TIMEOUT))) {
+        tracing::warn!("metrics: failed setting client timeouts: {}", e);
+        return;
+    }
+    let mut writer = match stream.try_clone() {
+        Ok(w) => w,
+        Err(e) => {
+            tracing::warn!("metrics: failed cloning stream: {}", e);
+            return;
+        }
+    };
+
+    // Request line, then headers up to an empty line. Any request body is ignored.
+    let mut lines = BufReader::new(stream).lines();
+    let Some(Ok(request)) = lines.next() else { return };
+    for line in lines.by_ref() {
+        match line {
+            Ok(line) if !line.is_empty() => continue,
+            _ => break,
+        }
+    }
+
+    let mut parts = request.split_whitespace();
+    let response = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => match run_query(queries, ControlQuery::Metrics) {
+            Ok(body) => response("200 OK", "text/plain; version=0.0.4", &body),
+            Err(e) => response("503 Service Unavailable", "text/plain", &format!("{}\n", e)),
+        },
+        (Some("GET"), Some(_)) => response("404 Not Found", "text/plain", "not found\n"),
+        _ => response("405 Method Not Allowed", "text/plain", "method not allowed\n"),
+    };
+    let _ = writer.write_all(response.as_bytes());
+}
+
+fn response(status: &str, content_type: &str, body: &str) -> String {
+    format!("HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status, content_type, body.len(), body)
+}
@@ -0,0 +1,10 @@
+//! Operator control interface: simple text commands to inspect and modify the running stack
+
+pub mod commands;
+pub mod metrics;
+pub mod queries;
+
+#[cfg(feature = "metrics-http")]
+pub mod metrics_http;
+#[cfg(feature = "control-socket")]
+pub mod socket;
@@ -0,0 +1,198 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use crossbeam_channel::Sender;
+use tetra_core::tetra_entities::TetraEntity;
+
+use crate::MessageRouter;
+use crate::control::metrics::prometheus_text;
+use crate::cmce::cmce_bs::CmceBs;
+use crate::cmce::components::circuit_mgr::CircuitMgr;
+use crate::cmce::components::location_store::LocationStore;
+use crate::mle::mle_bs_ms::Mle;
+use crate::mm::components::client_state::MmClientMgr;
+use crate::mm::mm_bs::MmBs;
+use crate::stack_summary::PduCount;
+
+
+/// Read-only queries on entity state, which is owned by the stack thread
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ControlQuery {
+    ListClients,
+    ListCalls,
+    NetworkSummary,
+    /// Latest location reported by an ISSI
+    Location(u32),
+    /// Number of MM and CMCE PDUs decoded and generated, by PDU type
+    PduStats,
+    /// Stack summary counters in Prometheus text format
+    Metrics,
+}
+
+/// A query submitted to the stack thread, answered through `reply` in between ticks.
+/// The control thread thus never touches entity state directly, and no locks are shared.
+pub struct ControlRequest {
+    pub query: ControlQuery,
+    pub reply: Sender<String>,
+}
+
+/// Executes a query against the entities registered in the router
+pub fn answer_query(router: &mut MessageRouter, query: ControlQuery) -> String {
+    match query {
+        ControlQuery::ListClients => {
+            let mm = router.get_entity(TetraEntity::Mm)
+                .and_then(|e| e.as_any().downcast_ref::<MmBs>());
+            match mm {
+                Some(mm) => list_clients(&mm.client_mgr),
+                None => "no MM entity available".to_string(),
+            }
+        }
+        ControlQuery::ListCalls => {
+            let cmce = router.get_entity(TetraEntity::Cmce)
+                .and_then(|e| e.as_any().downcast_ref::<CmceBs>());
+            match cmce {
+                Some(cmce) => list_calls(cmce.circuits()),
+                None => "no CMCE entity available".to_string(),
+            }
+        }
+        ControlQuery::Location(issi) => {
+            let cmce = router.get_entity(TetraEntity::Cmce)
+                .and_then(|e| e.as_any().downcast_ref::<CmceBs>());
+            match cmce {
+                Some(cmce) => latest_location(cmce.locations(), issi),
+                None => "no CMCE entity available".to_string(),
+            }
+        }
+        ControlQuery::PduStats => pdu_stats(&router.stats().pdus),
+        ControlQuery::Metrics => prometheus_text(&router.summary()),
+        ControlQuery::NetworkSummary => {
+            let mle = router.get_entity(TetraEntity::Mle)
+                .and_then(|e| e.as_any().downcast_ref::<Mle>());
+            match mle {
+                Some(mle) => mle.observer().summary(),
+                None => "no MLE entity available".to_string(),
+            }
+        }
+    }
+}
+
+/// Formats a snapshot of all registered clients, ordered by SSI
+pub fn list_clients(client_mgr: &MmClientMgr) -> String {
+    let mut clients: Vec<_> = client_mgr.clients().collect();
+    clients.sort_by_key(|c| c.ssi);
+
+    let mut out = format!("{} clients", clients.len());
+    for client in clients {
+        let mut groups: Vec<_> = client.groups.iter().copied().collect();
+        groups.sort_unstable();
+        let _ = write!(out, "; ssi {} state {:?} groups {:?}", client.ssi, client.state, groups);
+    }
+    out
+}
+
+/// Formats a snapshot of all active circuits, ordered by timeslot
+pub fn list_calls(circuits: &CircuitMgr) -> String {
+    let active: Vec<_> = circuits.dl.iter()
+        .chain(circuits.ul_only.iter())
+        .flatten()
+        .collect();
+
+    let mut out = format!("{} calls", active.len());
+    for circuit in active {
+        let _ = write!(out, "; call_id {} ts {} dir {:?} usage {} mode {} comm {}",
+            circuit.call_id, circuit.ts, circuit.direction, circuit.usage, circuit.circuit_mode, circuit.comm_type);
+    }
+    out
+}
+
+/// Formats the PDU counters, ordered by PDU type
+pub fn pdu_stats(pdus: &BTreeMap<String, PduCount>) -> String {
+    let mut out = format!("{} pdu types", pdus.len());
+    for (pdu_type, count) in pdus {
+        let _ = write!(out, "; {} decoded {} generated {}", pdu_type, count.decoded, count.generated);
+    }
+    out
+}
+
+/// Formats the latest location reported by `issi`
+pub fn latest_location(locations: &LocationStore, issi: u32) -> String {
+    let Some(location) = locations.latest(issi) else {
+        return format!("no location for ssi {}", issi);
+    };
+    let report = &location.report;
+    let mut out = format!("ssi {} at {} lat {:.5} lon {:.5}", issi, location.time, report.latitude, report.longitude);
+    if let Some(error) = report.position_error_m {
+        let _ = write!(out, " error {} m", error);
+    }
+    if let Some(velocity) = report.velocity_kmh {
+        let _ = write!(out, " velocity {:.0} km/h direction {}", velocity, report.direction_deg);
+    }
+    out
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::{Direction, TdmaTime};
+
+    use crate::cmce::components::lip::LocationReport;
+    use tetra_saps::control::enums::communication_type::CommunicationType;
+
+    use super::*;
+
+    #[test]
+    fn test_list_clients() {
+        let mut client_mgr = MmClientMgr::new();
+        assert_eq!(list_clients(&client_mgr), "0 clients");
+
+        client_mgr.try_register_client(2040814, true).unwrap();
+        client_mgr.try_register_client(1000001, false).unwrap();
+        client_mgr.client_group_attach(2040814, 91, true).unwrap();
+        client_mgr.client_group_attach(2040814, 26, true).unwrap();
+
+        let out = list_clients(&client_mgr);
+        assert_eq!(out, "2 clients; ssi 1000001 state Unknown groups []; ssi 2040814 state Attached groups [26, 91]");
+    }
+
+    #[test]
+    fn test_list_calls() {
+        let mut circuits = CircuitMgr::new();
+        assert_eq!(list_calls(&circuits), "0 calls");
+
+        let call_id = circuits.allocate_circuit(Direction::Dl, CommunicationType::P2Mp).unwrap().call_id;
+        let out = list_calls(&circuits);
+        assert!(out.starts_with("1 calls; "));
+        assert!(out.contains(&format!("call_id {} ", call_id)));
+    }
+
+    #[test]
+    fn test_pdu_stats() {
+        let mut pdus = BTreeMap::new();
+        assert_eq!(pdu_stats(&pdus), "0 pdu types");
+
+        pdus.insert("ULocationUpdateDemand".to_string(), PduCount { decoded: 3, generated: 0 });
+        pdus.insert("DLocationUpdateAccept".to_string(), PduCount { decoded: 0, generated: 2 });
+        pdus.insert("DSetup".to_string(), PduCount { decoded: 0, generated: 7 });
+        assert_eq!(pdu_stats(&pdus), "3 pdu types; DLocationUpdateAccept decoded 0 generated 2; \
+            DSetup decoded 0 generated 7; ULocationUpdateDemand decoded 3 generated 0");
+    }
+
+    #[test]
+    fn test_latest_location() {
+        let mut locations = LocationStore::default();
+        assert_eq!(latest_location(&locations, 2040814), "no location for ssi 2040814");
+
+        let report = LocationReport {
+            latitude: 52.0,
+            longitude: -4.5,
+            time_elapsed: 0,
+            position_error_m: Some(20),
+            velocity_kmh: None,
+            direction_deg: 0.0,
+            reason_for_sending: None,
+        };
+        let time = TdmaTime::default();
+        locations.record(2040814, time, report);
+        assert_eq!(latest_location(&locations, 2040814), format!("ssi 2040814 at {} lat 52.00000 lon -4.50000 error 20 m", time));
+    }
+}
@@ -0,0 +1,60 @@
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::path::Path;
+use std::thread;
+
+use crossbeam_channel::Sender;
+use tetra_config::SharedConfig;
+
+use crate::control::commands::handle_command;
+use crate::control::queries::ControlRequest;
+
+
+/// Binds a Unix-domain control socket at `path` and serves it from a background thread.
+/// Each line received is executed as a control command and answered with a single line,
+/// prefixed with `OK` or `ERR`. Clients are served one at a time.
+/// Queries on entity state are forwarded through `queries`, see MessageRouter::open_control_queries.
+pub fn spawn_control_socket(config: SharedConfig, queries: Sender<ControlRequest>, path: &Path) -> std::io::Result<thread::JoinHandle<()>> {
+    // Remove stale socket left behind by a previous run
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    tracing::info!("control: listening on {}", path.display());
+
+    let handle = thread::Builder::new()
+        .name("control-socket".to_string())
+        .spawn(move || {
+            for stream in listener.incoming() {
+                match stream {
+                    Ok(stream) => serve_client(&config, &queries, stream),
+                    Err(e) => tracing::warn!("control: failed accepting connection: {}", e),
+                }
+            }
+        })?;
+    Ok(handle)
+}
+
+fn serve_client(config: &SharedConfig, queries: &Sender<ControlRequest>, stream: UnixStream) {
+    let mut writer = match stream.try_clone() {
+        Ok(w) => w,
+        Err(e) => {
+            tracing::warn!("control: failed cloning stream: {}", e);
+            return;
+        }
+    };
+
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else { break };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let response = match handle_command(config, queries, &line) {
+            Ok(text) => format!("OK {}\n", text),
+            Err(e) => format!("ERR {}\n", e),
+        };
+        if writer.write_all(response.as_bytes()).is_err() {
+            break;
+        }
+    }
+}
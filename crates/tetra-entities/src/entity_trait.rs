@@ -1,5 +1,5 @@
 use as_any::AsAny;
-use tetra_core::{TdmaTime, tetra_entities::TetraEntity};
+use tetra_core::{Sap, TdmaTime, tetra_entities::TetraEntity};
 use tetra_config::SharedConfig;
 use tetra_saps::SapMsg;
 use crate::MessageQueue;
@@ -9,6 +9,10 @@ use crate::MessageQueue;
 pub trait TetraEntityTrait: Send + AsAny {
     /// Returns the entity type identifier
     fn entity(&self) -> TetraEntity;
+
+    /// SAPs on which a U-plane entity receives primitives addressed to TetraEntity::User.
+    /// An entity serving no particular SAP receives those on any SAP not served by another.
+    fn user_saps(&self) -> &[Sap] { &[] }
     
     /// Handle incoming SAP primitive
     fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg);
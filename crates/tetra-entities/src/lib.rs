@@ -1,15 +1,21 @@
 #![allow(dead_code)]
 
+pub mod clock;
 pub mod cmce;
+pub mod control;
 pub mod entity_trait;
 pub mod llc;
 pub mod lmac;
 pub mod messagerouter;
 pub mod mle;
 pub mod mm;
+pub mod pdu_addressing;
 pub mod phy;
 pub mod sndcp;
+pub mod ssi_allocator;
+pub mod stack_summary;
 pub mod umac;
+pub mod uplane;
 
 pub mod network;
 pub mod tnmm_net;
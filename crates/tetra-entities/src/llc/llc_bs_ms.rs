@@ -14,6 +14,7 @@ use tetra_pdus::llc::pdus::bl_ack::BlAck;
 use tetra_pdus::llc::pdus::bl_adata::BlAdata;
 use tetra_pdus::llc::pdus::bl_data::BlData;
 use tetra_pdus::llc::pdus::bl_udata::BlUdata;
+use tetra_pdus::parse_timing::timed_parse;
 
 pub struct AckData {
     pub addr: TetraAddress,
@@ -114,11 +115,42 @@ impl Llc {
         }
     }
 
-    fn rx_tla_tlunitdata_req_bl(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
+    /// See Clause 22.3.3 for Unacknowledged data transmission in basic link
+    fn rx_tla_tlunitdata_req_bl(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_tla_tlunitdata_req_bl");
-        let SapMsgInner::TlaTlUnitdataReqBl(_prim) = message.msg else { panic!() };
+        let SapMsgInner::TlaTlUnitdataReqBl(mut prim) = message.msg else { panic!() };
 
-        unimplemented_log!("rx_tla_tlunitdata_req_bl");
+        // BL-UDATA (with or without FCS)
+        let mut pdu_buf = BitBuffer::new_autoexpand(32);
+        let pdu = BlUdata {
+            has_fcs: prim.fcs_flag,
+        };
+        pdu.to_bitbuf(&mut pdu_buf);
+        // Append SDU
+        let sdu_len = prim.tl_sdu.get_len_remaining();
+        pdu_buf.copy_bits(&mut prim.tl_sdu, sdu_len);
+        pdu_buf.seek(0);
+        tracing::debug!("-> {:?} sdu {}", pdu, pdu_buf.dump_bin());
+
+        let sapmsg = SapMsg {
+            sap: Sap::TmaSap,
+            src: self.entity(),
+            dest: TetraEntity::Umac,
+            dltime: message.dltime,
+            msg: SapMsgInner::TmaUnitdataReq(TmaUnitdataReq {
+                req_handle: prim.req_handle,
+                pdu: pdu_buf,
+                main_address: prim.main_address,
+                endpoint_id: prim.endpoint_id,
+                stealing_permission: prim.stealing_permission,
+                subscriber_class: prim.subscriber_class,
+                air_interface_encryption: Some(prim.air_interface_encryption),
+                stealing_repeats_flag: None,
+                data_category: prim.data_class_info,
+                chan_alloc: None,
+            })
+        };
+        queue.push_back(sapmsg);
     }
 
     /// See Clause 22.3.2.3 for Acknowledged data transmission in basic link
@@ -273,7 +305,7 @@ impl Llc {
 
         let (has_fcs, ns, nr) = match pdu_type {
             LlcPduType::BlAdata | LlcPduType::BlAdataFcs => {
-                match BlAdata::from_bitbuf(&mut pdu) {
+                match timed_parse("BlAdata", &mut pdu, BlAdata::from_bitbuf) {
                     Ok(pdu) => {
                         tracing::debug!("<- {:?}", pdu);
                         (pdu.has_fcs, Some(pdu.ns), Some(pdu.nr))
@@ -286,7 +318,7 @@ impl Llc {
             }
 
             LlcPduType::BlData | LlcPduType ::BlDataFcs => {
-                match BlData::from_bitbuf(&mut pdu) {
+                match timed_parse("BlData", &mut pdu, BlData::from_bitbuf) {
                     Ok(pdu) => {
                         tracing::debug!("<- {:?}", pdu);
                         (pdu.has_fcs, Some(pdu.ns), None)
@@ -298,7 +330,7 @@ impl Llc {
                 }
             }
             LlcPduType::BlAck | LlcPduType::BlAckFcs => {
-                match BlAck::from_bitbuf(&mut pdu) {
+                match timed_parse("BlAck", &mut pdu, BlAck::from_bitbuf) {
                     Ok(pdu) => {
                         tracing::debug!("<- {:?}", pdu);
                         (pdu.has_fcs, None, Some(pdu.nr))
@@ -310,7 +342,7 @@ impl Llc {
                 }
             }
             LlcPduType::BlUdata | LlcPduType::BlUdataFcs => {
-                match BlUdata::from_bitbuf(&mut pdu) {
+                match timed_parse("BlUdata", &mut pdu, BlUdata::from_bitbuf) {
                     Ok(pdu) => {
                         tracing::debug!("<- {:?}", pdu);
                         (pdu.has_fcs, None, None)
@@ -372,6 +404,7 @@ impl Llc {
                 chan_change_handle: prim.chan_change_handle,
                 chan_info: prim.chan_info,
                 report: None, // TODO FIXME
+                rx_quality: prim.rx_quality,
             };
             SapMsg{ 
                 sap: Sap::TlaSap,
@@ -397,6 +430,7 @@ impl Llc {
                 chan_change_handle: prim.chan_change_handle,
                 chan_info: prim.chan_info,
                 req_handle: 0, // TODO FIXME
+                rx_quality: prim.rx_quality,
             };
             SapMsg{ 
                 sap: Sap::TlaSap,
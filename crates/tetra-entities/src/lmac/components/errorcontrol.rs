@@ -6,6 +6,7 @@ use tetra_saps::tp::TpUnitdataInd;
 use crate::lmac::components::convenc::{self, ConvEncState, RcpcPunctMode};
 use crate::lmac::components::{crc16, errorcontrol_params, interleaver, rm3014, viterbi};
 use crate::lmac::components::scrambler;
+use crate::umac::subcomp::bs_sched::MacError;
 
 const MAX_TYPE1_BITS: usize = 268;
 const MAX_TYPE2_BITS: usize = 288;
@@ -67,6 +68,15 @@ pub fn encode_cp(mut prim: TmvUnitdataReq) -> BitBuffer {
     type5
 }
 
+/// Decodes control plane message from type5 to type1 bits, descrambling with `scramb_code`.
+/// Returns the type1 bits, or MacError::CrcFail if the CRC check failed.
+pub fn decode_cp_checked(lchan: LogicalChannel, prim: TpUnitdataInd, scramb_code: u32) -> Result<BitBuffer, MacError> {
+    match decode_cp(lchan, prim, Some(scramb_code)) {
+        (Some(type1bits), true) => Ok(type1bits),
+        _ => Err(MacError::CrcFail),
+    }
+}
+
 /// Decodes control plane message from type5 to type1 bits
 /// Returns (buf, bool) tuple
 /// buf is BitBuffer with type1 bits if decoding successful
@@ -299,7 +309,8 @@ mod tests {
             burst_type: BurstType::SDB, 
             block_type: PhyBlockType::SB2, 
             block_num: PhyBlockNum::Block2, 
-            block: type5
+            block: type5,
+            rx_quality: None,
         };
 
         let (type1, crc_ok) = decode_cp(lchan, prim_ind, Some(scramb_code));
@@ -329,7 +340,8 @@ mod tests {
             burst_type: BurstType::SDB, 
             block_type: PhyBlockType::SB2, 
             block_num: PhyBlockNum::Block2, 
-            block: type5
+            block: type5,
+            rx_quality: None,
         };
 
         let (type1, crc_ok) = decode_cp(lchan, prim_ind, Some(scramb_code));
@@ -376,7 +388,8 @@ mod tests {
             burst_type: BurstType::NDB, 
             block_type: PhyBlockType::NDB, 
             block_num: PhyBlockNum::Both, 
-            block: type5
+            block: type5,
+            rx_quality: None,
         };
 
         let (type1, crc_ok) = decode_cp(lchan, prim_ind, Some(scramb_code));
@@ -1,6 +1,6 @@
 use tetra_config::{SharedConfig, StackMode};
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BurstType, PhyBlockNum, PhysicalChannel, Sap, TdmaTime, TrainingSequence, unimplemented_log};
+use tetra_core::{BurstType, PhyBlockNum, PhysicalChannel, Sap, TdmaTime, TrainingSequence};
 use tetra_saps::tmv::TmvUnitdataInd;
 use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
 use tetra_saps::tp::{TpUnitdataInd, TpUnitdataReqSlot};
@@ -166,8 +166,31 @@ impl LmacBs {
         }
     }
 
-    fn rx_blk_traffic(&mut self, _queue: &mut MessageQueue, _blk: TpUnitdataInd, _lchan: LogicalChannel) {
-        unimplemented_log!("rx_blk_traffic: Traffic channel reception not implemented yet");
+    fn rx_blk_traffic(&mut self, queue: &mut MessageQueue, mut blk: TpUnitdataInd, lchan: LogicalChannel) {
+
+        // Speech frames are not channel decoded here; the codec handles the type5 bits
+        // itself. Only undo the scrambling and pass the block on to the U-plane.
+        blk.block.seek(0);
+        scrambler::tetra_scramb_bits(self.scrambling_code, &mut blk.block);
+
+        let m = SapMsg {
+            sap: Sap::TmvSap,
+            src: TetraEntity::Lmac,
+            dest: TetraEntity::Umac,
+            dltime: self.dltime.add_timeslots(-2),
+            msg: SapMsgInner::TmvUnitdataInd(
+                TmvUnitdataInd {
+                    pdu: blk.block,
+                    logical_channel: lchan,
+                    block_num: blk.block_num,
+                    // Traffic channels carry no CRC
+                    crc_pass: true,
+                    scrambling_code: self.scrambling_code,
+                    rx_quality: blk.rx_quality,
+                }
+            )
+        };
+        queue.push_back(m);
     }
 
     fn rx_blk_control(&mut self, queue: &mut MessageQueue, blk: TpUnitdataInd, lchan: LogicalChannel) {
@@ -175,20 +198,20 @@ impl LmacBs {
         assert!(lchan.is_control_channel(), "rx_blk_cp: lchan {:?} is not a signalling channel", lchan);
 
         let block_num = blk.block_num;
-        let (type1bits, crc_pass) = 
-                errorcontrol::decode_cp(lchan, blk, Some(self.scrambling_code));
-        let type1bits = type1bits.unwrap(); // Guaranteed since scramb code set
-
-        if tracing::enabled!(tracing::Level::DEBUG) {
-            tracing::debug!("rx_blk_cp {:?} CRC: {} type1 {:?}", lchan, if crc_pass { "ok" } else { "WRONG" }, type1bits);
-        } else {
-            tracing::info!("rx_blk_cp {:?} CRC: {}", lchan, if crc_pass { "ok" } else { "WRONG" });
-        }
-
+        let rx_quality = blk.rx_quality;
         // TODO FIXME, for now, we're not passing broken CRC msgs up to Lmac
         // If we see purpose, we may pass it up in the future
-        if !crc_pass {
-            return;
+        let type1bits = match errorcontrol::decode_cp_checked(lchan, blk, self.scrambling_code) {
+            Ok(type1bits) => type1bits,
+            Err(e) => {
+                tracing::info!("rx_blk_cp {:?}: {:?}", lchan, e);
+                return;
+            }
+        };
+        if tracing::enabled!(tracing::Level::DEBUG) {
+            tracing::debug!("rx_blk_cp {:?} CRC: ok type1 {:?}", lchan, type1bits);
+        } else {
+            tracing::info!("rx_blk_cp {:?} CRC: ok", lchan);
         }
 
         // Pass block to the upper mac
@@ -202,8 +225,9 @@ impl LmacBs {
                     pdu: type1bits,
                     logical_channel: lchan,
                     block_num,
-                    crc_pass,
-                    scrambling_code: self.scrambling_code
+                    crc_pass: true,
+                    scrambling_code: self.scrambling_code,
+                    rx_quality,
                 }
             )
         };
@@ -74,6 +74,7 @@ impl LmacMs {
         
         // tracing::trace!("rx_bbk: {:?}", bbk.block.dump_bin());
 
+        let rx_quality = bbk.rx_quality;
         let type5 = bbk.block;
         tracing::trace!("rx_bbk type5: {:?}", type5.dump_bin_full(true));
 
@@ -97,7 +98,8 @@ impl LmacMs {
                     block_num: PhyBlockNum::Undefined,
                     logical_channel: LogicalChannel::Aach,
                     crc_pass: true,
-                    scrambling_code
+                    scrambling_code,
+                    rx_quality,
                 }
             )
         };
@@ -156,6 +158,7 @@ impl LmacMs {
     fn rx_blk_cp(&mut self, queue: &mut MessageQueue, blk: TpUnitdataInd, lchan: LogicalChannel) {
 
         let block_num = blk.block_num;
+        let rx_quality = blk.rx_quality;
         let (type1bits, crc_pass) = 
                 errorcontrol::decode_cp(lchan, blk, self.scrambling_code);
 
@@ -190,7 +193,8 @@ impl LmacMs {
                         block_num,
                         logical_channel: lchan,
                         crc_pass,
-                        scrambling_code: scramb_code
+                        scrambling_code: scramb_code,
+                        rx_quality,
                     }
                 )
             };
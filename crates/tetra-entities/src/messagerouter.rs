@@ -1,10 +1,19 @@
+use std::cmp::Ordering;
 use std::collections::{HashMap, VecDeque};
 
-use tetra_config::SharedConfig;
-use tetra_core::{TdmaTime, tetra_entities::TetraEntity};
+use crossbeam_channel::{Receiver, Sender};
+
+use tetra_config::{SharedConfig, StackMode};
+use tetra_core::pdu_log::{PduLog, PduLogEntry};
+use tetra_core::{Direction, Sap, TdmaTime, tetra_entities::TetraEntity};
+use tetra_pdus::cmce::pdus::cmce_pdu::CmcePdu;
 use tetra_saps::SapMsg;
 
 use crate::TetraEntityTrait;
+use crate::clock::{Clock, TdmaClock};
+use crate::control::queries::{ControlRequest, answer_query};
+use crate::pdu_addressing::{addressed_ssi, decode_cmce_pdu, pdu_type};
+use crate::stack_summary::{RouterStats, StackSummary, stack_summary};
 
 
 #[derive(Default)]
@@ -47,17 +56,34 @@ impl MessageQueue {
     }
 }
 
+/// Observer of decoded CMCE PDUs, see MessageRouter::on_pdu
+pub type PduObserver = Box<dyn FnMut(Direction, &CmcePdu) + Send>;
+
 pub struct MessageRouter {
-    /// While currently unused by the MessageRouter, this may change in the future
-    /// As such, we provide the MessageRouter with a copy of the SharedConfig
-    _config: SharedConfig,
+    /// Used by the MessageRouter to determine the stack mode when logging primitives
+    config: SharedConfig,
     entities: HashMap<TetraEntity, Box<dyn TetraEntityTrait>>,
+    /// U-plane entities, which share TetraEntity::User and are told apart by the SAPs they serve
+    user_entities: Vec<Box<dyn TetraEntityTrait>>,
     msg_queue: MessageQueue,
 
-    /// The current TDMA time, if applicable. 
+    /// Source of the current TDMA time, if applicable. 
     /// For Bs mode, this is always available
     /// For Ms/Mon mode, it is recovered from a received SYNC frame and communicated in a different way
-    ts: TdmaTime,
+    clock: Box<dyn Clock>,
+
+    /// Pending queries from the control interface, answered in between ticks
+    control_rx: Option<Receiver<ControlRequest>>,
+    control_tx: Option<Sender<ControlRequest>>,
+
+    /// If enabled, records every delivered primitive with its TDMA time
+    pdu_log: Option<PduLog>,
+
+    /// Ticks run and primitives routed, for the summary at shutdown
+    stats: RouterStats,
+
+    /// Called for every CMCE PDU passing between the MLE and CMCE. PDUs are only decoded if any are registered.
+    pdu_observers: Vec<PduObserver>,
 }
 
 
@@ -65,31 +91,137 @@ impl MessageRouter {
     pub fn new(config: SharedConfig) -> Self {
         Self {
             entities: HashMap::new(),
+            user_entities: Vec::new(),
             msg_queue: MessageQueue {
                 messages: VecDeque::new(),
             },
-            _config: config,
-            ts: TdmaTime::default(),
+            config,
+            clock: Box::new(TdmaClock::default()),
+            control_rx: None,
+            control_tx: None,
+            pdu_log: None,
+            stats: RouterStats::default(),
+            pdu_observers: Vec::new(),
         }
     }
 
     /// For BS mode, sets global TDMA time
     /// Incremented each tick and passed to entities in tick() function
     pub fn set_dl_time(&mut self, ts: TdmaTime) {        
-        self.ts = ts;
+        self.clock.set(ts);
+    }
+
+    /// Replaces the clock driving the TDMA time, e.g. with a ManualClock in tests
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.clock = clock;
     }
 
     pub fn register_entity(&mut self, entity: Box<dyn TetraEntityTrait>) {
         let comp_type = entity.entity();
         tracing::debug!("register_entity {:?}", comp_type);
-        self.entities.insert(comp_type, entity);
+        if comp_type != TetraEntity::User {
+            self.entities.insert(comp_type, entity);
+            return;
+        }
+
+        // U-plane entities may not serve the same SAP
+        let saps = entity.user_saps();
+        let conflict = self.user_entities.iter().any(|other| {
+            let other_saps = other.user_saps();
+            (saps.is_empty() && other_saps.is_empty()) || saps.iter().any(|sap| other_saps.contains(sap))
+        });
+        if conflict {
+            tracing::error!("register_entity: {:?} already served by another U-plane entity, not registering", saps);
+            return;
+        }
+        self.user_entities.push(entity);
     }
 
-    /// Returns a mut ref to a component of the requested type
+    /// Returns a mut ref to a component of the requested type. For TetraEntity::User,
+    /// returns the first U-plane entity registered.
     pub fn get_entity(&mut self, comp: TetraEntity) -> Option<&mut dyn TetraEntityTrait> {
+        if comp == TetraEntity::User {
+            return self.user_entities.first_mut().map(|entity| entity.as_mut());
+        }
         self.entities.get_mut(&comp).map(|entity| entity.as_mut())
     }
 
+    /// Index of the U-plane entity serving `sap`, or else of the one serving no particular SAP
+    fn user_entity_index(&self, sap: Sap) -> Option<usize> {
+        self.user_entities.iter().position(|entity| entity.user_saps().contains(&sap))
+            .or_else(|| self.user_entities.iter().position(|entity| entity.user_saps().is_empty()))
+    }
+
+    /// Opens the channel through which the control interface submits queries on entity state.
+    /// Queries are answered by run_stack at the end of each tick. Calling it again returns
+    /// another sender on the same channel, so several interfaces can submit queries.
+    pub fn open_control_queries(&mut self) -> Sender<ControlRequest> {
+        if let Some(tx) = &self.control_tx {
+            return tx.clone();
+        }
+        let (tx, rx) = crossbeam_channel::unbounded();
+        self.control_rx = Some(rx);
+        self.control_tx = Some(tx.clone());
+        tx
+    }
+
+    /// Answers all pending control queries. Replies to clients that went away are dropped.
+    pub fn serve_control_queries(&mut self) {
+        let Some(rx) = &self.control_rx else { return };
+        let requests: Vec<ControlRequest> = rx.try_iter().collect();
+        for request in requests {
+            let answer = answer_query(self, request.query);
+            let _ = request.reply.send(answer);
+        }
+    }
+
+    /// Starts recording all delivered primitives, keeping at most `capacity` entries
+    pub fn enable_pdu_log(&mut self, capacity: usize) {
+        self.pdu_log = Some(PduLog::new(capacity));
+    }
+
+    pub fn pdu_log(&self) -> Option<&PduLog> {
+        self.pdu_log.as_ref()
+    }
+
+    /// Registers `observer` to be called with every CMCE PDU delivered between the MLE and CMCE,
+    /// along with its air interface direction. PDUs that fail to parse are not reported.
+    pub fn on_pdu(&mut self, observer: impl FnMut(Direction, &CmcePdu) + Send + 'static) {
+        self.pdu_observers.push(Box::new(observer));
+    }
+
+    pub fn config(&self) -> &SharedConfig {
+        &self.config
+    }
+
+    pub fn stats(&self) -> &RouterStats {
+        &self.stats
+    }
+
+    /// Summary of the run so far, including the counters of CMCE and MM
+    pub fn summary(&mut self) -> StackSummary {
+        stack_summary(self)
+    }
+
+    /// Logs the summary of the run so far
+    pub fn log_summary(&mut self) {
+        tracing::info!("{}", self.summary().to_string().trim_end());
+    }
+
+    /// Air interface direction a primitive relates to, derived from whether it travels up or down the stack.
+    /// For a BS, primitives travelling down concern the downlink, for an MS, the uplink.
+    fn primitive_direction(&self, message: &SapMsg) -> Direction {
+        let (Some(src), Some(dest)) = (layer_of(*message.get_source()), layer_of(*message.get_dest())) else {
+            return Direction::None;
+        };
+        let is_bs = self.config.config().stack_mode == StackMode::Bs;
+        match (dest.cmp(&src), is_bs) {
+            (Ordering::Less, true) | (Ordering::Greater, false) => Direction::Dl,
+            (Ordering::Greater, true) | (Ordering::Less, false) => Direction::Ul,
+            (Ordering::Equal, _) => Direction::None,
+        }
+    }
+
     pub fn submit_message(&mut self, message: SapMsg) {
         tracing::debug!("submit_message {:?}: {:?} -> {:?}", message.get_sap(), message.get_source(), message.get_dest());
         self.msg_queue.push_back(message);
@@ -105,8 +237,46 @@ impl MessageRouter {
             // Determine the destination entity
             let dest = message.get_dest();
 
+            let dir = self.primitive_direction(&message);
+            if self.pdu_log.is_some() && message.sap != Sap::Control {
+                let entry = PduLogEntry {
+                    time: message.dltime,
+                    subslot: message.get_subslot(),
+                    dir,
+                    entity: *dest,
+                    ssi: message.get_ssi(),
+                    addressed_ssi: addressed_ssi(&message, dir),
+                    pdu: format!("{:?}", message.msg),
+                };
+                if let Some(log) = &mut self.pdu_log {
+                    log.record(entry);
+                }
+            }
+
+            if !self.pdu_observers.is_empty()
+                && let Some(pdu) = decode_cmce_pdu(&message, dir) {
+                for observer in &mut self.pdu_observers {
+                    observer(dir, &pdu);
+                }
+            }
+
+            *self.stats.primitives.entry(message.msg.name()).or_default() += 1;
+            if let Some((pdu_type, decoded)) = pdu_type(&message, dir) {
+                let count = self.stats.pdus.entry(pdu_type).or_default();
+                if decoded {
+                    count.decoded += 1;
+                } else {
+                    count.generated += 1;
+                }
+            }
+
             // Check if the destination entity registered and deliver if found
-            if let Some(entity) = self.entities.get_mut(dest) {
+            let entity = if *dest == TetraEntity::User {
+                self.user_entity_index(message.sap).and_then(|i| self.user_entities.get_mut(i))
+            } else {
+                self.entities.get_mut(dest)
+            };
+            if let Some(entity) = entity {
                 entity.rx_prim(&mut self.msg_queue, message);
             } else {
                 tracing::warn!("deliver_message: entity {:?} not found for {:?}: {:?} -> {:?}", dest, message.get_sap(), message.get_source(), message.get_dest());
@@ -130,11 +300,12 @@ impl MessageRouter {
         
         // tracing::info!("--- tick dl {} ul {} txdl {} ----------------------------",
         //     self.ts, self.ts.add_timeslots(-2), self.ts.add_timeslots(MACSCHED_TX_AHEAD as i32));
-        tracing::info!("--- tick dl {} ----------------------------", self.ts);
+        let ts = self.clock.now();
+        tracing::info!("--- tick dl {} ----------------------------", ts);
         
         // Call tick on all entities
-        for entity in self.entities.values_mut() {
-            entity.tick_start(&mut self.msg_queue, self.ts);
+        for entity in self.entities.values_mut().chain(&mut self.user_entities) {
+            entity.tick_start(&mut self.msg_queue, ts);
         }
     }
 
@@ -148,12 +319,13 @@ impl MessageRouter {
     pub fn tick_end(&mut self) {
 
         tracing::debug!("############################ end-of-tick ############################");
+        let ts = self.clock.now();
 
         // Llc should send down outstanding BL-ACKs
         let target = TetraEntity::Llc;
         if let Some(entity) = self.entities.get_mut(&target) {
             tracing::trace!("tick_end for entity {:?}", target);
-            entity.tick_end(&mut self.msg_queue, self.ts);
+            entity.tick_end(&mut self.msg_queue, ts);
         }
         self.deliver_all_messages();
 
@@ -161,41 +333,50 @@ impl MessageRouter {
         let target = TetraEntity::Umac;
         if let Some(entity) = self.entities.get_mut(&target) {
             tracing::trace!("tick_end for entity {:?}", target);
-            entity.tick_end(&mut self.msg_queue, self.ts);
+            entity.tick_end(&mut self.msg_queue, ts);
         }
         self.deliver_all_messages();
 
         // Then call tick_end on all other entities
-        for entity in self.entities.values_mut() {
+        for entity in self.entities.values_mut().chain(&mut self.user_entities) {
             let entity_id = entity.entity();
             if entity_id == TetraEntity::Llc || entity_id == TetraEntity::Umac {
                 continue;
             }
-            entity.tick_end(&mut self.msg_queue, self.ts);
+            entity.tick_end(&mut self.msg_queue, ts);
         }
         self.deliver_all_messages();
 
         // Increment the TDMA time if set
-        self.ts = self.ts.add_timeslots(1);
+        self.clock.tick();
+        self.stats.ticks += 1;
     }
 
 
+    /// Runs the full stack for a single timeslot
+    pub fn tick(&mut self) {
+        // Send tick_start event
+        self.tick_start();
+
+        // Deliver messages until queue empty
+        while self.get_msgqueue_len() > 0{
+            self.deliver_all_messages();
+        }
+
+        // Send tick_end event and process final messages
+        self.tick_end();
+
+        // Answer any queries from the control interface, while no entity is mid-tick
+        self.serve_control_queries();
+    }
+
     /// Runs the full stack either forever or for a specified number of ticks.
     pub fn run_stack(&mut self, num_ticks: Option<usize>) {
         
         let mut ticks: usize = 0;
 
         loop {
-            // Send tick_start event
-            self.tick_start();
-            
-            // Deliver messages until queue empty
-            while self.get_msgqueue_len() > 0{
-                self.deliver_all_messages();
-            }
-
-            // Send tick_end event and process final messages
-            self.tick_end();
+            self.tick();
             
             // Check if we should stop
             ticks += 1;
@@ -207,3 +388,17 @@ impl MessageRouter {
         }
     }
 }
+
+/// Position of an entity in the stack, counting up from the air interface.
+/// User-plane entities have no fixed position, as their SAP determines routing.
+fn layer_of(entity: TetraEntity) -> Option<u8> {
+    match entity {
+        TetraEntity::Phy => Some(0),
+        TetraEntity::Lmac => Some(1),
+        TetraEntity::Umac => Some(2),
+        TetraEntity::Llc => Some(3),
+        TetraEntity::Mle => Some(4),
+        TetraEntity::Mm | TetraEntity::Cmce | TetraEntity::Sndcp => Some(5),
+        TetraEntity::User => None,
+    }
+}
@@ -1 +1,3 @@
-pub mod mle_router;
\ No newline at end of file
+pub mod mle_router;
+pub mod network_observer;
+pub mod nwrk_broadcast;
@@ -0,0 +1,224 @@
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::fmt::Write;
+
+use tetra_config::{CfgCellInfo, CfgNetInfo};
+use tetra_pdus::mle::fields::neighbour_cell_information_for_ca::NeighbourCellInformationForCa;
+
+
+/// Number of most recent cell load samples kept for the trend
+const LOAD_HISTORY_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LoadTrend {
+    /// Fewer than two samples received so far
+    Unknown,
+    Stable,
+    Rising,
+    Falling,
+}
+
+/// Aggregates the broadcast parameters of an observed cell over time,
+/// as decoded from D-MLE-SYNC, D-MLE-SYSINFO and D-NWRK-BROADCAST
+pub struct NetworkObserver {
+    net: Option<CfgNetInfo>,
+    cell: CfgCellInfo,
+    /// Whether `cell` was ever updated from a received PDU
+    cell_observed: bool,
+    load_history: VecDeque<u8>,
+    neighbours: Vec<NeighbourCellInformationForCa>,
+}
+
+impl NetworkObserver {
+    pub fn new() -> Self {
+        Self {
+            net: None,
+            cell: CfgCellInfo::default(),
+            cell_observed: false,
+            load_history: VecDeque::with_capacity(LOAD_HISTORY_LEN),
+            neighbours: Vec::new(),
+        }
+    }
+
+    /// Network identity from the last received D-MLE-SYNC, if any
+    pub fn net(&self) -> Option<&CfgNetInfo> {
+        self.net.as_ref()
+    }
+
+    /// Latest cell parameters. Fields not yet observed hold their defaults.
+    pub fn cell(&self) -> &CfgCellInfo {
+        &self.cell
+    }
+
+    pub fn neighbours(&self) -> &[NeighbourCellInformationForCa] {
+        &self.neighbours
+    }
+
+    pub fn has_observed(&self) -> bool {
+        self.net.is_some() || self.cell_observed
+    }
+
+    /// Records the network identity, logging if it differs from the one seen before
+    pub fn update_net(&mut self, net: CfgNetInfo) {
+        if self.net.as_ref() != Some(&net) {
+            tracing::info!("Detected network mcc {} mnc {}", net.mcc, net.mnc);
+        }
+        self.net = Some(net);
+    }
+
+    /// Records decoded cell parameters and samples the cell load
+    pub fn update_cell(&mut self, cell: CfgCellInfo) {
+        if !self.cell_observed || cell.location_area != self.cell.location_area {
+            tracing::info!("Detected location area {}", cell.location_area);
+        }
+        if self.load_history.len() == LOAD_HISTORY_LEN {
+            self.load_history.pop_front();
+        }
+        self.load_history.push_back(cell.cell_load_ca);
+        self.cell = cell;
+        self.cell_observed = true;
+    }
+
+    /// Replaces the known neighbour cells by those of a received D-NWRK-BROADCAST
+    pub fn update_neighbours(&mut self, neighbours: Vec<NeighbourCellInformationForCa>) {
+        self.neighbours = neighbours;
+    }
+
+    /// Compares the latest cell load sample against the oldest one still in the history
+    pub fn load_trend(&self) -> LoadTrend {
+        let (Some(first), Some(last)) = (self.load_history.front(), self.load_history.back()) else {
+            return LoadTrend::Unknown;
+        };
+        if self.load_history.len() < 2 {
+            return LoadTrend::Unknown;
+        }
+        match last.cmp(first) {
+            Ordering::Greater => LoadTrend::Rising,
+            Ordering::Less => LoadTrend::Falling,
+            Ordering::Equal => LoadTrend::Stable,
+        }
+    }
+
+    /// Formats a single-line summary of everything observed so far
+    pub fn summary(&self) -> String {
+        let mut out = match &self.net {
+            Some(net) => format!("mcc {} mnc {}", net.mcc, net.mnc),
+            None => "mcc ? mnc ?".to_string(),
+        };
+        if !self.cell_observed {
+            out.push_str("; no cell parameters observed");
+            return out;
+        }
+
+        let c = &self.cell;
+        let _ = write!(out, " la {} subscriber_class {:#06x}", c.location_area, c.subscriber_class);
+        let history: Vec<_> = self.load_history.iter().collect();
+        let _ = write!(out, "; load {} trend {:?} history {:?}", c.cell_load_ca, self.load_trend(), history);
+
+        let services: Vec<&str> = [
+            (c.registration, "registration"),
+            (c.deregistration, "deregistration"),
+            (c.priority_cell, "priority_cell"),
+            (c.no_minimum_mode, "no_minimum_mode"),
+            (c.migration, "migration"),
+            (c.system_wide_services, "system_wide_services"),
+            (c.voice_service, "voice"),
+            (c.circuit_mode_data_service, "circuit_mode_data"),
+            (c.sndcp_service, "sndcp"),
+            (c.aie_service, "aie"),
+            (c.advanced_link, "advanced_link"),
+        ].iter().filter(|(set, _)| *set).map(|(_, name)| *name).collect();
+        let _ = write!(out, "; services {:?}", services);
+
+        let _ = write!(out, "; {} neighbours", self.neighbours.len());
+        for n in &self.neighbours {
+            let _ = write!(out, "; cell {} carrier {}", n.cell_identifier_ca, n.main_carrier_number);
+            if let Some(la) = n.location_area {
+                let _ = write!(out, " la {}", la);
+            }
+        }
+        out
+    }
+}
+
+impl Default for NetworkObserver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::{BitBuffer, debug};
+    use tetra_pdus::mle::fields::bs_service_details::BsServiceDetails;
+    use tetra_pdus::mle::pdus::d_mle_sysinfo::DMleSysinfo;
+
+    use crate::mle::mle_bs_ms::Mle;
+
+    use super::*;
+
+    fn sysinfo(location_area: u16, voice_service: bool) -> BitBuffer {
+        let pdu = DMleSysinfo {
+            location_area,
+            subscriber_class: 0xffff,
+            bs_service_details: BsServiceDetails {
+                registration: true,
+                deregistration: true,
+                priority_cell: false,
+                no_minimum_mode: false,
+                migration: false,
+                system_wide_services: false,
+                voice_service,
+                circuit_mode_data_service: false,
+                sndcp_service: false,
+                aie_service: false,
+                advanced_link: false,
+            },
+        };
+        let mut buf = BitBuffer::new_autoexpand(42);
+        pdu.to_bitbuf(&mut buf);
+        buf.seek(0);
+        buf
+    }
+
+    #[test]
+    fn test_summary_follows_sysinfo_sequence() {
+        debug::setup_logging_verbose();
+        let mut observer = NetworkObserver::new();
+        assert!(!observer.has_observed());
+        assert_eq!(observer.load_trend(), LoadTrend::Unknown);
+        observer.update_net(CfgNetInfo { mcc: 204, mnc: 1337 });
+
+        // Cell load is carried in SYNC, set it on the base before decoding each SYSINFO
+        for (load, la, voice) in [(0, 10, false), (1, 10, false), (1, 11, true), (3, 11, true)] {
+            let base = CfgCellInfo { cell_load_ca: load, ..observer.cell().clone() };
+            let cell = Mle::parse_sysinfo(&mut sysinfo(la, voice), &base).unwrap();
+            observer.update_cell(cell);
+        }
+
+        assert_eq!(observer.load_trend(), LoadTrend::Rising);
+        assert_eq!(observer.summary(),
+            "mcc 204 mnc 1337 la 11 subscriber_class 0xffff; load 3 trend Rising history [0, 1, 1, 3]; \
+            services [\"registration\", \"deregistration\", \"voice\"]; 0 neighbours");
+
+        let base = CfgCellInfo { cell_load_ca: 2, ..observer.cell().clone() };
+        observer.update_cell(Mle::parse_sysinfo(&mut sysinfo(11, true), &base).unwrap());
+        assert_eq!(observer.load_trend(), LoadTrend::Rising);
+        for _ in 0..LOAD_HISTORY_LEN {
+            observer.update_cell(observer.cell().clone());
+        }
+        assert_eq!(observer.load_trend(), LoadTrend::Stable);
+    }
+
+    #[test]
+    fn test_summary_lists_neighbours() {
+        let mut observer = NetworkObserver::new();
+        observer.update_cell(CfgCellInfo::default());
+        observer.update_neighbours(vec![
+            NeighbourCellInformationForCa { cell_identifier_ca: 1, main_carrier_number: 1522, location_area: Some(3), ..Default::default() },
+            NeighbourCellInformationForCa { cell_identifier_ca: 2, main_carrier_number: 1530, ..Default::default() },
+        ]);
+        assert!(observer.summary().ends_with("; 2 neighbours; cell 1 carrier 1522 la 3; cell 2 carrier 1530"));
+    }
+}
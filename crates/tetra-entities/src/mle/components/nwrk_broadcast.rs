@@ -0,0 +1,99 @@
+use tetra_config::SharedConfig;
+use tetra_core::TdmaTime;
+use tetra_pdus::mle::fields::neighbour_cell_information_for_ca::NeighbourCellInformationForCa;
+use tetra_pdus::mle::pdus::d_nwrk_broadcast::DNwrkBroadcast;
+
+
+/// D-NWRK-BROADCAST is repeated once every this many multiframes (about 10 seconds)
+const NWRK_BROADCAST_PERIOD_MULTIFRAMES: u8 = 10;
+
+/// Returns true if a D-NWRK-BROADCAST is due at the given downlink time.
+/// It is sent in the first timeslot of the first frame of every broadcast period.
+pub fn is_nwrk_broadcast_due(dltime: TdmaTime) -> bool {
+    dltime.t == 1 && dltime.f == 1 && dltime.m.is_multiple_of(NWRK_BROADCAST_PERIOD_MULTIFRAMES)
+}
+
+/// Builds the D-NWRK-BROADCAST PDU announcing the configured CA neighbour cells.
+/// Returns None if no neighbour cells are configured.
+pub fn build_d_nwrk_broadcast(config: &SharedConfig) -> Option<DNwrkBroadcast> {
+    let cfg = config.config();
+    if cfg.neighbor_cells.is_empty() {
+        return None;
+    }
+
+    let neighbours: Vec<NeighbourCellInformationForCa> = cfg.neighbor_cells.iter().map(|nc| {
+        NeighbourCellInformationForCa {
+            cell_identifier_ca: nc.cell_id,
+            cell_reselection_types_supported: nc.reselection_types,
+            neighbour_cell_synchronized: nc.synchronized,
+            main_carrier_number: nc.main_carrier,
+            location_area: nc.location_area.map(|la| la as u64),
+            ..Default::default()
+        }
+    }).collect();
+
+    Some(DNwrkBroadcast {
+        // TODO FIXME reselection thresholds and hysteresis are not configurable yet
+        cell_re_select_parameters: 0,
+        cell_load_ca: config.state_read().cell_load_ca,
+        tetra_network_time: None,
+        number_of_ca_neighbour_cells: Some(neighbours.len() as u64),
+        neighbour_cell_information_for_ca: neighbours,
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::BitBuffer;
+
+    use super::*;
+
+    #[test]
+    fn test_build_d_nwrk_broadcast_from_toml() {
+        let toml = r#"
+            config_version = "0.5"
+            stack_mode = "Bs"
+
+            [phy_io]
+            backend = "None"
+
+            [net_info]
+            mcc = 204
+            mnc = 1337
+
+            [[neighbor_cell]]
+            cell_id = 1
+            main_carrier = 1522
+            location_area = 3
+
+            [[neighbor_cell]]
+            cell_id = 2
+            main_carrier = 1530
+            synchronized = true
+        "#;
+        let config = tetra_config::from_toml_str(toml).expect("Failed loading config");
+        assert_eq!(config.config().neighbor_cells.len(), 2);
+
+        let pdu = build_d_nwrk_broadcast(&config).expect("No PDU built");
+        assert_eq!(pdu.number_of_ca_neighbour_cells, Some(2));
+        let cells = &pdu.neighbour_cell_information_for_ca;
+        assert_eq!((cells[0].cell_identifier_ca, cells[0].main_carrier_number, cells[0].location_area), (1, 1522, Some(3)));
+        assert_eq!((cells[1].cell_identifier_ca, cells[1].main_carrier_number, cells[1].location_area), (2, 1530, None));
+        assert!(cells[1].neighbour_cell_synchronized);
+
+        // Both neighbours survive serialization
+        let mut buf = BitBuffer::new_autoexpand(128);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        buf.seek(0);
+        assert_eq!(DNwrkBroadcast::from_bitbuf(&mut buf).unwrap(), pdu);
+    }
+
+    #[test]
+    fn test_no_d_nwrk_broadcast_without_neighbours() {
+        let mut cfg = tetra_config::StackConfig::new(tetra_config::StackMode::Bs, 204, 1337);
+        cfg.phy_io.backend = tetra_config::PhyBackend::None;
+        let config = SharedConfig::from_config(cfg);
+        assert!(build_d_nwrk_broadcast(&config).is_none());
+    }
+}
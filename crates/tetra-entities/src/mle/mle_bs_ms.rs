@@ -1,26 +1,38 @@
-use tetra_config::SharedConfig;
+use tetra_config::{CfgCellInfo, CfgNetInfo, SharedConfig, StackMode};
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Sap, unimplemented_log};
+use tetra_core::pdu_parse_error::PduParseErr;
+use tetra_core::{BitBuffer, Sap, SsiType, TdmaTime, TetraAddress, unimplemented_log};
 use crate::mle::components::mle_router::MleRouter;
+use crate::mle::components::network_observer::NetworkObserver;
+use crate::mle::components::nwrk_broadcast;
 use crate::{MessageQueue, TetraEntityTrait};
+use tetra_saps::control::ms_activity::MsActivity;
 use tetra_saps::lcmc::LcmcMleUnitdataInd;
 use tetra_saps::lmm::LmmMleUnitdataInd;
 use tetra_saps::ltpd::LtpdMleUnitdataInd;
-use tetra_saps::tla::TlaTlDataReqBl;
+use tetra_saps::tla::{TlaTlDataReqBl, TlaTlUnitdataReqBl};
 use tetra_saps::{SapMsg, SapMsgInner};
 
 use tetra_pdus::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
 use tetra_pdus::mle::enums::mle_protocol_discriminator::MleProtocolDiscriminator;
 use tetra_pdus::mle::pdus::d_mle_sync::DMleSync;
 use tetra_pdus::mle::pdus::d_mle_sysinfo::DMleSysinfo;
+use tetra_pdus::mle::pdus::d_nwrk_broadcast::DNwrkBroadcast;
+use tetra_pdus::parse_timing::timed_parse;
 
 
+/// All-ones group address, received by every MS on the cell
+const BROADCAST_SSI: u32 = 0xFFFFFF;
+
 pub struct Mle {
     // config: Option<SharedConfig>,
     self_component: TetraEntity,
     config: SharedConfig,
 
     router: MleRouter,
+
+    /// Network and cell parameters as broadcast by the cell we are listening to (MS / monitor mode)
+    observer: NetworkObserver,
 }
 
 impl Mle {
@@ -30,25 +42,16 @@ impl Mle {
             config,
 
             router: MleRouter::new(),
+
+            observer: NetworkObserver::new(),
         }
     }
 
-    fn rx_tla_mle_pdu(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
+    /// Handles an MLE PDU, with `sdu` positioned just after the protocol discriminator
+    fn rx_tla_mle_pdu(&mut self, _queue: &mut MessageQueue, mut sdu: BitBuffer) {
         
         tracing::trace!("rx_tla_mle_pdu");
 
-        // Extract tm_sdu from whatever primitive we have
-        let tm_sdu = {
-            match message.msg {
-                SapMsgInner::TlaTlDataIndBl(prim) => {prim.tl_sdu}
-                _ => { panic!(); }
-            }
-        };
-        let Some(sdu) = tm_sdu else {
-            tracing::debug!("rx_tla_mle_pdu: no tm_sdu");
-            return;
-        };
-
         // Determine which type of TL-SDU we have and call handler function
         let Some(bits) = sdu.peek_bits(3) else {
             tracing::warn!("insufficient bits: {}", sdu.dump_bin());
@@ -62,7 +65,15 @@ impl Mle {
         match pdu_type {
             MlePduTypeDl::DNewCell => { unimplemented_log!("DNewCell") }
             MlePduTypeDl::DPrepareFail => { unimplemented_log!("DPrepareFail") }
-            MlePduTypeDl::DNwrkBroadcast => { unimplemented_log!("DNwrkBroadcast") }
+            MlePduTypeDl::DNwrkBroadcast => {
+                match timed_parse("DNwrkBroadcast", &mut sdu, DNwrkBroadcast::from_bitbuf) {
+                    Ok(pdu) => {
+                        tracing::debug!("<- {}", pdu);
+                        self.observer.update_neighbours(pdu.neighbour_cell_information_for_ca);
+                    }
+                    Err(e) => tracing::warn!("Failed parsing DNwrkBroadcast: {:?}", e),
+                }
+            }
             MlePduTypeDl::DNwrkBroadcastExt => { unimplemented_log!("DNwrkBroadcastExt") } // TODO FIXME CHECK this option and assocaited int
             MlePduTypeDl::DRestoreAck => { unimplemented_log!("DRestoreAck") }
             MlePduTypeDl::DRestoreFail => { unimplemented_log!("DRestoreFail") }
@@ -71,9 +82,33 @@ impl Mle {
         }
     }
 
+    /// Reports an uplink PDU to MM, which keeps track of when it last heard from each MS.
+    /// MM PDUs are left out, as MM sees those itself.
+    fn report_ms_activity(&self, queue: &mut MessageQueue, message: &SapMsg) {
+        let (address, sdu) = match &message.msg {
+            SapMsgInner::TlaTlDataIndBl(prim) => (prim.main_address, prim.tl_sdu.as_ref()),
+            SapMsgInner::TlaTlUnitdataIndBl(prim) => (prim.main_address, prim.tl_sdu.as_ref()),
+            _ => return,
+        };
+        let discriminator = sdu.and_then(|sdu| sdu.peek_bits(3)).and_then(|bits| MleProtocolDiscriminator::try_from(bits).ok());
+        if address.ssi_type == SsiType::Gssi || discriminator == Some(MleProtocolDiscriminator::Mm) {
+            return;
+        }
+        queue.push_back(SapMsg {
+            sap: Sap::Control,
+            src: self.self_component,
+            dest: TetraEntity::Mm,
+            dltime: message.dltime,
+            msg: SapMsgInner::MleMsActivity(MsActivity { ssi: address.ssi }),
+        });
+    }
+
     fn rx_tla_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         
         tracing::trace!("rx_tla_prim");
+        if self.config.config().stack_mode == StackMode::Bs {
+            self.report_ms_activity(queue, &message);
+        }
         match message.msg {
             SapMsgInner::TlaTlDataIndBl(_) => {
                 self.rx_tla_data_ind_bl(queue, message);
@@ -108,6 +143,7 @@ impl Mle {
                     sdu,
                     handle,
                     received_address: prim.main_address,
+                    rx_quality: prim.rx_quality,
                 };
                 let msg = SapMsg {
                     sap: Sap::LmmSap,
@@ -128,6 +164,7 @@ impl Mle {
                     link_id: prim.link_id,
                     chan_change_resp_req: false, // TODO FIXME
                     chan_change_handle: None, // TODO FIXME
+                    rx_quality: prim.rx_quality,
                 };
                 let msg = SapMsg {
                     sap: Sap::LcmcSap,
@@ -157,7 +194,7 @@ impl Mle {
                 queue.push_back(msg);
             }
             MleProtocolDiscriminator::Mle => {
-                self.rx_tla_mle_pdu(queue, message);
+                self.rx_tla_mle_pdu(queue, sdu);
             }
             MleProtocolDiscriminator::TetraManagementEntity => {
                 unimplemented_log!("MleProtocolDiscriminator::TetraManagementEntity");
@@ -198,6 +235,7 @@ impl Mle {
                     sdu,
                     handle,
                     received_address: prim.main_address,
+                    rx_quality: prim.rx_quality,
                 };
                 let msg = SapMsg {
                     sap: Sap::LmmSap,
@@ -219,6 +257,7 @@ impl Mle {
                     received_tetra_address: prim.main_address,
                     chan_change_resp_req: false, // TODO FIXME
                     chan_change_handle: None, // TODO FIXME
+                    rx_quality: prim.rx_quality,
                 };
                 let msg = SapMsg {
                     sap: Sap::LcmcSap,
@@ -248,7 +287,7 @@ impl Mle {
                 queue.push_back(msg);
             }
             MleProtocolDiscriminator::Mle => {
-                self.rx_tla_mle_pdu(queue, message);
+                self.rx_tla_mle_pdu(queue, sdu);
             }
             MleProtocolDiscriminator::TetraManagementEntity => {
                 unimplemented_log!("MleProtocolDiscriminator::TetraManagementEntity");
@@ -269,121 +308,86 @@ impl Mle {
         }
     }
 
-    pub fn rx_tlmb_tl_sysinfo_ind(&self, _queue: &mut MessageQueue, mut message: SapMsg) {
+    /// Decodes a received D-MLE-SYSINFO TL-SDU. Returns `base` with the location area,
+    /// subscriber class and BS service details replaced by those broadcast by the cell.
+    pub fn parse_sysinfo(sdu: &mut BitBuffer, base: &CfgCellInfo) -> Result<CfgCellInfo, PduParseErr> {
+        let pdu = timed_parse("DMleSysinfo", sdu, DMleSysinfo::from_bitbuf)?;
+        tracing::debug!("<- {}", pdu);
+
+        let details = &pdu.bs_service_details;
+        Ok(CfgCellInfo {
+            location_area: pdu.location_area,
+            subscriber_class: pdu.subscriber_class,
+            registration: details.registration,
+            deregistration: details.deregistration,
+            priority_cell: details.priority_cell,
+            no_minimum_mode: details.no_minimum_mode,
+            migration: details.migration,
+            system_wide_services: details.system_wide_services,
+            voice_service: details.voice_service,
+            circuit_mode_data_service: details.circuit_mode_data_service,
+            sndcp_service: details.sndcp_service,
+            aie_service: details.aie_service,
+            advanced_link: details.advanced_link,
+            ..base.clone()
+        })
+    }
+
+    /// Decodes a received D-MLE-SYNC TL-SDU into the network identity, and `base` with the
+    /// cell parameters carried in SYNC replaced by those broadcast by the cell.
+    pub fn parse_sync(sdu: &mut BitBuffer, base: &CfgCellInfo) -> Result<(CfgNetInfo, CfgCellInfo), PduParseErr> {
+        let pdu = timed_parse("DMleSync", sdu, DMleSync::from_bitbuf)?;
+        tracing::debug!("<- {}", pdu);
+
+        let net = CfgNetInfo { mcc: pdu.mcc, mnc: pdu.mnc };
+        let cell = CfgCellInfo {
+            neighbor_cell_broadcast: pdu.neighbor_cell_broadcast,
+            cell_load_ca: pdu.cell_load_ca,
+            late_entry_supported: pdu.late_entry_supported,
+            ..base.clone()
+        };
+        Ok((net, cell))
+    }
+
+    pub fn rx_tlmb_tl_sysinfo_ind(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_tlmb_tl_sysinfo_ind");
         
         let SapMsgInner::TlmbSysinfoInd(inner) = &mut message.msg else {panic!()};
 
         // Parse the TL-SDU
-        let _pdu = match DMleSysinfo::from_bitbuf(&mut inner.tl_sdu) {
-            Ok(pdu) => {
-                tracing::debug!("<- {:?}", pdu);
-                pdu
-            }
+        let cell = match Self::parse_sysinfo(&mut inner.tl_sdu, self.observer.cell()) {
+            Ok(cell) => cell,
             Err(e) => {
                 tracing::warn!("Failed parsing DMleSysinfo: {:?} {}", e, inner.tl_sdu.dump_bin());
                 return;
             }
         };
-
-        unimplemented_log!("rx_tlmb_tl_sysinfo_ind");
-        // let need_global_state_update = {
-        //     let cfg = self.config.read();
-
-        //     pdu.location_area != cfg.la_info.location_area
-        //     || pdu.subscriber_class != cfg.la_info.subscriber_class
-        //     || pdu.bs_service_details.registration != cfg.la_info.registration
-        //     || pdu.bs_service_details.deregistration != cfg.la_info.deregistration
-        //     || pdu.bs_service_details.priority_cell != cfg.la_info.priority_cell
-        //     || pdu.bs_service_details.no_minimum_mode != cfg.la_info.no_minimum_mode
-        //     || pdu.bs_service_details.migration != cfg.la_info.migration
-        //     || pdu.bs_service_details.system_wide_services != cfg.la_info.system_wide_services
-        //     || pdu.bs_service_details.voice_service != cfg.la_info.voice_service
-        //     || pdu.bs_service_details.circuit_mode_data_service != cfg.la_info.circuit_mode_data_service
-        //     || pdu.bs_service_details.sndcp_service != cfg.la_info.sndcp_service
-        //     || pdu.bs_service_details.aie_service != cfg.la_info.aie_service
-        //     || pdu.bs_service_details.advanced_link != cfg.la_info.advanced_link                
-        // };
-
-        // if need_global_state_update {
-        //     let mut cfg = self.config.write();
-        //     cfg.la_info.location_area = pdu.location_area;
-        //     cfg.la_info.subscriber_class = pdu.subscriber_class;
-        //     cfg.la_info.registration = pdu.bs_service_details.registration;
-        //     cfg.la_info.deregistration = pdu.bs_service_details.deregistration;
-        //     cfg.la_info.priority_cell = pdu.bs_service_details.priority_cell;
-        //     cfg.la_info.no_minimum_mode = pdu.bs_service_details.no_minimum_mode;
-        //     cfg.la_info.migration = pdu.bs_service_details.migration;
-        //     cfg.la_info.system_wide_services = pdu.bs_service_details.system_wide_services;
-        //     cfg.la_info.voice_service = pdu.bs_service_details.voice_service;
-        //     cfg.la_info.circuit_mode_data_service = pdu.bs_service_details.circuit_mode_data_service;
-        //     cfg.la_info.sndcp_service = pdu.bs_service_details.sndcp_service;
-        //     cfg.la_info.aie_service = pdu.bs_service_details.aie_service;
-        //     cfg.la_info.advanced_link = pdu.bs_service_details.advanced_link;
-        //     tracing::info!("Updated TetraGlobalState: {:?}", pdu);
-        // } else {
-        //     tracing::trace!("rx_tlmb_tl_sysinfo_ind: TetraGlobalState update not required");
-        // }
+        self.observer.update_cell(cell);
     }
 
-    pub fn rx_tlmb_tl_sync_ind(&self, _queue: &mut MessageQueue, mut message: SapMsg) {
+    pub fn rx_tlmb_tl_sync_ind(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_tlmb_tl_sync_ind");
         
         let SapMsgInner::TlmbSyncInd(inner) = &mut message.msg else {panic!()};
 
         // Parse the TL-SDU
-        let _pdu = match DMleSync::from_bitbuf(&mut inner.tl_sdu) {
-            Ok(pdu) => {
-                tracing::debug!("<- {:?}", pdu);
-                pdu
-            }
+        let (net, cell) = match Self::parse_sync(&mut inner.tl_sdu, self.observer.cell()) {
+            Ok(parsed) => parsed,
             Err(e) => {
                 tracing::warn!("Failed parsing DMleSync: {:?} {}", e, inner.tl_sdu.dump_bin());
                 return;
             }
         };
+        self.observer.update_net(net);
+        self.observer.update_cell(cell);
 
-        unimplemented_log!("rx_tlmb_tl_sync_ind");
-
-        // let need_global_state_update = {
-        //     let cfg = self.config.read();
-        //         pdu.mcc  != cfg.la_info.mcc
-        //         || pdu.mnc  != cfg.la_info.mnc
-        //         || pdu.neighbor_cell_broadcast != cfg.la_info.neighbor_cell_broadcast
-        //         || pdu.cell_load_ca            != cfg.la_info.cell_load_ca
-        //         || pdu.late_entry_supported    != cfg.la_info.late_entry_supported
-        // };
-
-        // // Update global state if needed
-        // if need_global_state_update {
-        //     let mut cfg = self.config.write();
-        //     cfg.la_info.mcc                    = pdu.mcc;
-        //     cfg.la_info.mnc                    = pdu.mnc;
-        //     cfg.la_info.neighbor_cell_broadcast = pdu.neighbor_cell_broadcast;
-        //     cfg.la_info.cell_load_ca           = pdu.cell_load_ca;
-        //     cfg.la_info.late_entry_supported   = pdu.late_entry_supported;
-        //     tracing::info!("Updated TetraGlobalState: {:?}", pdu);
-
-        //     // TODO FIXME: This is ugly. We should pass the message through all the intermediate layers
-        //     let m = SapMsg {
-        //         sap: Sap::TlmcSap,
-        //         src: self.self_component,
-        //         dest: TetraComponent::Umac,
-        //         t_submit: message.t_submit,
-        //         msg: SapMsgInner::TlmcConfigureReq(
-        //             TlmcConfigureReq{
-        //                 valid_addresses: Some(TlmcValidAddress {
-        //                     mcc: cfg.la_info.mcc,
-        //                     mnc: cfg.la_info.mnc,
-        //                 }),
-        //                 ..Default::default()                        
-        //             }
-        //         )
-        //     };
-        //     queue.push_back(m);
-        // } else {
-        //     tracing::trace!("rx_tlmb_tl_sysinfo_ind: TetraGlobalState update not required");
-        // }
+        // TODO FIXME: configure the valid addresses in the UMAC through TlmcConfigureReq
+        // once the TLMC primitives are passed through the intermediate layers
+    }
+
+    /// Broadcast parameters of the observed network, decoded from received SYNC, SYSINFO and D-NWRK-BROADCAST
+    pub fn observer(&self) -> &NetworkObserver {
+        &self.observer
     }
 
     
@@ -498,6 +502,49 @@ impl Mle {
         queue.push_back(sapmsg);
     }
 
+    /// Broadcasts the configured CA neighbour cells in a D-NWRK-BROADCAST, if any
+    fn send_d_nwrk_broadcast(&mut self, queue: &mut MessageQueue, dltime: TdmaTime) {
+        let Some(pdu) = nwrk_broadcast::build_d_nwrk_broadcast(&self.config) else {
+            return;
+        };
+        tracing::debug!("-> {}", pdu);
+
+        let mut sdu = BitBuffer::new_autoexpand(64);
+        sdu.write_bits(MleProtocolDiscriminator::Mle.into_raw(), 3);
+        if let Err(e) = pdu.to_bitbuf(&mut sdu) {
+            tracing::warn!("Failed serializing DNwrkBroadcast: {:?}", e);
+            return;
+        }
+        sdu.seek(0);
+
+        let sapmsg = SapMsg {
+            sap: Sap::TlaSap,
+            src: self.self_component,
+            dest: TetraEntity::Llc,
+            dltime,
+            msg: SapMsgInner::TlaTlUnitdataReqBl(TlaTlUnitdataReqBl {
+                main_address: TetraAddress::new(BROADCAST_SSI, SsiType::Gssi),
+                link_id: 0,
+                endpoint_id: 0,
+                tl_sdu: sdu,
+                scrambling_code: 0,
+                pdu_prio: 0,
+                stealing_permission: false,
+                subscriber_class: 0,
+                fcs_flag: false,
+                air_interface_encryption: 0,
+                data_prio: 0,
+                packet_data_flag: false,
+                n_tlsdu_repeats: None,
+                scheduled_data_status: 0,
+                max_schedule_interval: None,
+                data_class_info: None,
+                req_handle: 0,
+            }),
+        };
+        queue.push_back(sapmsg);
+    }
+
     fn rx_lcmc_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         tracing::trace!("rx_lcmc_prim");
         match &message.msg {
@@ -510,12 +557,27 @@ impl Mle {
 }
 
 
+impl Drop for Mle {
+    fn drop(&mut self) {
+        if self.observer.has_observed() {
+            tracing::info!("Observed network: {}", self.observer.summary());
+        }
+    }
+}
+
+
 impl TetraEntityTrait for Mle {
     
     fn entity(&self) -> TetraEntity {
         TetraEntity::Mle
     }
 
+    fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
+        if self.config.config().stack_mode == StackMode::Bs && nwrk_broadcast::is_nwrk_broadcast_due(ts) {
+            self.send_d_nwrk_broadcast(queue, ts);
+        }
+    }
+
     fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         
         tracing::debug!("rx_prim: {:?}", message);
@@ -546,3 +608,60 @@ impl TetraEntityTrait for Mle {
         }
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use tetra_config::{PhyBackend, StackConfig};
+    use tetra_core::debug;
+
+    use crate::umac::umac_bs::UmacBs;
+
+    use super::*;
+
+    fn bs_config() -> SharedConfig {
+        let mut cfg = StackConfig::new(StackMode::Bs, 204, 1337);
+        cfg.phy_io.backend = PhyBackend::None;
+        cfg.cell.location_area = 42;
+        cfg.cell.priority_cell = true;
+        SharedConfig::from_config(cfg)
+    }
+
+    #[test]
+    fn test_parse_sync_from_builder() {
+        debug::setup_logging_verbose();
+        let config = bs_config();
+        config.state_write().cell_load_ca = 2;
+        let precomps = UmacBs::generate_precomps(&config);
+
+        let mut sdu = BitBuffer::new_autoexpand(29);
+        precomps.mle_sync.to_bitbuf(&mut sdu);
+        sdu.seek(0);
+
+        let (net, cell) = Mle::parse_sync(&mut sdu, &CfgCellInfo::default()).unwrap();
+        assert_eq!(net, config.config().net);
+        assert_eq!(cell.cell_load_ca, 2);
+        assert_eq!(cell.neighbor_cell_broadcast, precomps.mle_sync.neighbor_cell_broadcast);
+        assert!(cell.late_entry_supported);
+    }
+
+    #[test]
+    fn test_parse_sysinfo_from_builder() {
+        debug::setup_logging_verbose();
+        let config = bs_config();
+        let precomps = UmacBs::generate_precomps(&config);
+
+        let mut sdu = BitBuffer::new_autoexpand(42);
+        precomps.mle_sysinfo.to_bitbuf(&mut sdu);
+        sdu.seek(0);
+
+        // Fields not carried in SYSINFO are kept from the base
+        let base = CfgCellInfo { cell_load_ca: 3, ..CfgCellInfo::default() };
+        let cell = Mle::parse_sysinfo(&mut sdu, &base).unwrap();
+        assert_eq!(cell.location_area, 42);
+        assert_eq!(cell.subscriber_class, 65535);
+        assert!(cell.priority_cell);
+        assert_eq!(cell.registration, config.config().cell.registration);
+        assert_eq!(cell.cell_load_ca, 3);
+    }
+}
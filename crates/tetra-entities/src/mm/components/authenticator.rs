@@ -0,0 +1,15 @@
+//! Hook for authenticating MSs upon registration (EN 300 392-7 clause 4). The
+//! D-AUTHENTICATION and U-AUTHENTICATION PDUs and the TAA1 algorithms are not
+//! implemented in this stack; an `Authenticator` supplies the challenge and checks
+//! the response in their place.
+
+use tetra_core::BitBuffer;
+
+pub trait Authenticator: Send {
+    /// Returns the D-AUTHENTICATION DEMAND SDU challenging `issi`, or None if the
+    /// MS can not be authenticated, for example because its key is unknown
+    fn challenge(&mut self, issi: u32) -> Option<BitBuffer>;
+
+    /// Checks a U-AUTHENTICATION PDU received from `issi` in response to its challenge
+    fn verify(&mut self, issi: u32, sdu: &mut BitBuffer) -> bool;
+}
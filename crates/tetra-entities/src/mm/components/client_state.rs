@@ -1,3 +1,8 @@
+use std::io::Write;
+
+use tetra_core::TdmaTime;
+use tetra_saps::control::group_control::GroupControl;
+
 #[derive(Debug)]
 pub enum ClientMgrErr {
     ClientNotFound { issi: u32 },
@@ -11,13 +16,16 @@ pub enum MmClientState {
     Unknown,
     Attached,
     Detached,
+    /// Subscription disabled through D-DISABLE
+    Disabled,
 }
 
 pub struct MmClientProperties {
     pub ssi: u32,
     pub state: MmClientState,
     pub groups: std::collections::HashSet<u32>,
-    // pub last_seen: TdmaTime,
+    /// Time of the last PDU received from this client
+    pub last_seen: TdmaTime,
 }
 
 impl MmClientProperties {
@@ -26,7 +34,7 @@ impl MmClientProperties {
             ssi,
             state: MmClientState::Unknown,
             groups: std::collections::HashSet::new(),
-            // last_seen: TdmaTime::default(),
+            last_seen: TdmaTime::default(),
         }
     }
 }
@@ -43,12 +51,22 @@ fn may_attach(_issi: u32, _gssi: u32) -> bool { return true; }
 
 pub struct MmClientMgr {
     clients: std::collections::HashMap<u32, MmClientProperties>,
+    /// ISSIs disabled through D-DISABLE. Kept apart from `clients`, so the disabled
+    /// state survives detach and re-registration for the lifetime of the stack
+    disabled: std::collections::HashSet<u32>,
+    /// Number of successful registrations since start, including re-registrations
+    registrations: u64,
+    /// Group attachment changes not yet reported to CMCE
+    group_changes: Vec<GroupControl>,
 }
 
 impl MmClientMgr {
     pub fn new() -> Self {
         MmClientMgr {
             clients: std::collections::HashMap::new(),
+            disabled: std::collections::HashSet::new(),
+            registrations: 0,
+            group_changes: Vec::new(),
         }
     }
 
@@ -60,6 +78,36 @@ impl MmClientMgr {
         self.clients.contains_key(&issi)
     }
 
+    /// Number of currently known clients
+    pub fn client_count(&self) -> usize {
+        self.clients.len()
+    }
+
+    /// Iterates over all known clients, in no particular order
+    pub fn clients(&self) -> impl Iterator<Item = &MmClientProperties> {
+        self.clients.values()
+    }
+
+    /// Number of successful registrations since start, including re-registrations
+    pub fn registrations(&self) -> u64 {
+        self.registrations
+    }
+
+    /// Writes the state of all known clients as CSV, one row per client ordered by ISSI.
+    /// Groups are separated by spaces, last_seen is formatted as HF:MF:FN:TN.
+    pub fn export_csv<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writeln!(writer, "issi,state,last_seen,groups")?;
+        let mut clients: Vec<&MmClientProperties> = self.clients.values().collect();
+        clients.sort_by_key(|client| client.ssi);
+        for client in clients {
+            let mut groups: Vec<u32> = client.groups.iter().copied().collect();
+            groups.sort_unstable();
+            let groups = groups.iter().map(|gssi| gssi.to_string()).collect::<Vec<_>>().join(" ");
+            writeln!(writer, "{},{:?},{},{}", client.ssi, client.state, client.last_seen.to_string().trim_start(), groups)?;
+        }
+        Ok(())
+    }
+
     /// Registers a fresh state for a client, based on ssi
     /// If client is already registered, previous state is discarded. 
     pub fn try_register_client(&mut self, issi: u32, attached: bool) -> Result <bool, ClientMgrErr> {
@@ -69,25 +117,102 @@ impl MmClientMgr {
         };
         
         // discard previous state if any
-        self.clients.remove(&issi); 
+        self.forget_client(issi);
         
         // Create and insert new client state
         let mut elem = MmClientProperties::new(issi);
-        elem.state = if attached { MmClientState::Attached } else { MmClientState::Unknown };
+        elem.state = if self.is_disabled(issi) {
+            MmClientState::Disabled
+        } else if attached {
+            MmClientState::Attached
+        } else {
+            MmClientState::Unknown
+        };
         self.clients.insert(issi, elem);
+        self.registrations += 1;
         
         Ok(true)
     }
 
+    /// Returns whether the given ISSI has been disabled
+    pub fn is_disabled(&self, issi: u32) -> bool {
+        self.disabled.contains(&issi)
+    }
+
+    /// Marks an ISSI as disabled. Its state is retained even if the client is not currently registered
+    pub fn disable_client(&mut self, issi: u32) {
+        self.disabled.insert(issi);
+        if let Some(client) = self.clients.get_mut(&issi) {
+            client.state = MmClientState::Disabled;
+        }
+    }
+
+    /// Lifts the disabled state of an ISSI. Returns false if it was not disabled
+    pub fn enable_client(&mut self, issi: u32) -> bool {
+        if !self.disabled.remove(&issi) {
+            return false;
+        }
+        if let Some(client) = self.clients.get_mut(&issi) && client.state == MmClientState::Disabled {
+            client.state = MmClientState::Attached;
+        }
+        true
+    }
+
     /// Removes a client from the registry, returning its properties if found
     pub fn remove_client(&mut self, ssi: u32) -> Option<MmClientProperties> {
-        self.clients.remove(&ssi)
+        self.forget_client(ssi)
+    }
+
+    /// Removes a client, reporting its detachment from any groups it was attached to
+    fn forget_client(&mut self, issi: u32) -> Option<MmClientProperties> {
+        let client = self.clients.remove(&issi)?;
+        if !client.groups.is_empty() {
+            self.group_changes.push(GroupControl::DetachAll { issi });
+        }
+        Some(client)
+    }
+
+    /// ISSIs attached to `gssi`, in ascending order
+    pub fn group_members(&self, gssi: u32) -> Vec<u32> {
+        let mut members: Vec<u32> = self.clients.values()
+            .filter(|client| client.groups.contains(&gssi))
+            .map(|client| client.ssi)
+            .collect();
+        members.sort_unstable();
+        members
+    }
+
+    /// Takes the group attachment changes made since the last call, to be reported to CMCE
+    pub fn take_group_changes(&mut self) -> Vec<GroupControl> {
+        std::mem::take(&mut self.group_changes)
+    }
+
+    /// Records that a PDU was received from a known client at `now`
+    pub fn touch_client(&mut self, issi: u32, now: TdmaTime) {
+        if let Some(client) = self.clients.get_mut(&issi) {
+            client.last_seen = now;
+        }
+    }
+
+    /// Removes all clients not heard from for more than `timeout_slots`, returning their ISSIs
+    pub fn remove_inactive_clients(&mut self, now: TdmaTime, timeout_slots: i32) -> Vec<u32> {
+        let stale: Vec<u32> = self.clients.values()
+            .filter(|client| client.last_seen.age(now) > timeout_slots)
+            .map(|client| client.ssi)
+            .collect();
+        for &issi in &stale {
+            self.forget_client(issi);
+        }
+        stale
     }
 
     /// Detaches all groups from a client
     pub fn client_detach_all_groups(&mut self, issi: u32) -> Result<bool, ClientMgrErr> {
         if let Some(client) = self.clients.get_mut(&issi) {
-            client.groups.clear();
+            if !client.groups.is_empty() {
+                client.groups.clear();
+                self.group_changes.push(GroupControl::DetachAll { issi });
+            }
             Ok(true)
         } else {
             Err(ClientMgrErr::ClientNotFound { issi })
@@ -110,13 +235,41 @@ impl MmClientMgr {
 
         if let Some(client) = self.clients.get_mut(&issi) {
             if do_attach {
-                client.groups.insert(gssi);
-            } else {
-                client.groups.remove(&gssi);
+                if client.groups.insert(gssi) {
+                    self.group_changes.push(GroupControl::Attach { issi, gssi });
+                }
+            } else if client.groups.remove(&gssi) {
+                self.group_changes.push(GroupControl::Detach { issi, gssi });
             }
             Ok(true)
         } else {
             Err(ClientMgrErr::ClientNotFound { issi })
         }
     }
-}
\ No newline at end of file
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_export_csv() {
+        let mut mgr = MmClientMgr::new();
+        mgr.try_register_client(2040002, true).unwrap();
+        mgr.try_register_client(2040001, true).unwrap();
+        mgr.try_register_client(2040003, false).unwrap();
+        mgr.client_group_attach(2040001, 92, true).unwrap();
+        mgr.client_group_attach(2040001, 91, true).unwrap();
+        mgr.touch_client(2040002, TdmaTime { h: 2, m: 3, f: 7, t: 2 });
+
+        let mut csv = Vec::new();
+        mgr.export_csv(&mut csv).unwrap();
+        assert_eq!(String::from_utf8(csv).unwrap(), "\
+issi,state,last_seen,groups
+2040001,Attached,0:01:01:1,91 92
+2040002,Attached,2:03:07:2,
+2040003,Unknown,0:01:01:1,
+");
+    }
+}
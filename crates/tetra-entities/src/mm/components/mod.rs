@@ -1,2 +1,3 @@
+pub mod authenticator;
 pub mod client_state;
-pub mod not_supported;
\ No newline at end of file
+pub mod not_supported;
@@ -1,43 +1,116 @@
-use tetra_config::SharedConfig;
+use std::collections::HashMap;
+
+use tetra_config::{DEFAULT_VASSI_RANGE, SharedConfig};
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_core::{BitBuffer, Sap, SsiType, TetraAddress, assert_warn, unimplemented_log};
+use tetra_core::{BitBuffer, MleHandle, Sap, SsiType, TdmaTime, TetraAddress, assert_warn, unimplemented_log};
 use crate::{MessageQueue, TetraEntityTrait};
+use crate::ssi_allocator::SsiAllocator;
 use tetra_saps::lmm::LmmMleUnitdataReq;
 use tetra_saps::{SapMsg, SapMsgInner};
 
+use crate::mm::components::authenticator::Authenticator;
 use crate::mm::components::client_state::MmClientMgr;
 use crate::mm::components::not_supported::make_ul_mm_pdu_function_not_supported;
 use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
 use tetra_pdus::mm::enums::mm_pdu_type_ul::MmPduTypeUl;
+use tetra_pdus::mm::enums::reject_cause::RejectCause;
 use tetra_pdus::mm::enums::status_uplink::StatusUplink;
 use tetra_pdus::mm::fields::group_identity_attachment::GroupIdentityAttachment;
 use tetra_pdus::mm::fields::group_identity_downlink::GroupIdentityDownlink;
 use tetra_pdus::mm::fields::group_identity_location_accept::GroupIdentityLocationAccept;
 use tetra_pdus::mm::fields::group_identity_uplink::GroupIdentityUplink;
+use tetra_pdus::mm::fields::subscriber_class::SubscriberClass;
 use tetra_pdus::mm::pdus::d_attach_detach_group_identity_acknowledgement::DAttachDetachGroupIdentityAcknowledgement;
+use tetra_pdus::mm::pdus::d_disable::DDisable;
+use tetra_pdus::mm::pdus::d_enable::DEnable;
 use tetra_pdus::mm::pdus::d_location_update_accept::DLocationUpdateAccept;
+use tetra_pdus::mm::pdus::d_location_update_proceeding::DLocationUpdateProceeding;
+use tetra_pdus::mm::pdus::d_location_update_reject::DLocationUpdateReject;
+use tetra_pdus::mm::pdus::d_mm_status::DMmStatus;
 use tetra_pdus::mm::pdus::u_attach_detach_group_identity::UAttachDetachGroupIdentity;
+use tetra_pdus::mm::pdus::u_disable_status::UDisableStatus;
 use tetra_pdus::mm::pdus::u_itsi_detach::UItsiDetach;
 use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
 use tetra_pdus::mm::pdus::u_mm_status::UMmStatus;
+use tetra_pdus::parse_timing::timed_parse;
+
 
 
+/// Time an MS is given to answer its authentication challenge, 30 s in timeslots of 85/6 ms
+const AUTHENTICATION_TIMEOUT_SLOTS: i32 = 30 * 1200 / 17;
+
+/// Location update held back until the MS has answered its authentication challenge
+struct PendingAuthentication {
+    handle: MleHandle,
+    pdu: ULocationUpdateDemand,
+    challenged: TdmaTime,
+}
 
 pub struct MmBs {
     config: SharedConfig,
     pub client_mgr: MmClientMgr,
+    authenticator: Option<Box<dyn Authenticator>>,
+    pending_auth: HashMap<u32, PendingAuthentication>,
+    /// (V)ASSIs for MSs migrating from other networks
+    vassis: SsiAllocator,
+    /// Home MNI and ISSI of each visiting MS, by (V)ASSI
+    visitors: HashMap<u32, (u32, u32)>,
 }
 
 impl MmBs {
     pub fn new(config: SharedConfig) -> Self {
-        Self { config, client_mgr: MmClientMgr::new() }
+        let vassis = Self::build_vassi_allocator(&config);
+        Self { config, client_mgr: MmClientMgr::new(), authenticator: None, pending_auth: HashMap::new(), vassis, visitors: HashMap::new() }
+    }
+
+    /// (V)ASSI allocator for the configured range
+    fn build_vassi_allocator(config: &SharedConfig) -> SsiAllocator {
+        let (first, last) = config.config().access_control.vassi_range.unwrap_or(DEFAULT_VASSI_RANGE);
+        SsiAllocator::new(first, last)
+    }
+
+    /// Home MNI and ISSI of the visiting MS that was assigned `vassi`
+    pub fn visitor(&self, vassi: u32) -> Option<(u32, u32)> {
+        self.visitors.get(&vassi).copied()
+    }
+
+    /// Releases the (V)ASSI of a deregistered MS, if it was a visitor
+    fn release_visitor(&mut self, ssi: u32) {
+        if let Some((mni, issi)) = self.visitors.remove(&ssi) {
+            tracing::info!("Releasing (V)ASSI {} of MS {} from MNI {:#08x}", ssi, issi, mni);
+            self.vassis.release(ssi);
+        }
+    }
+
+    /// ISSIs attached to `gssi`, for addressing group calls
+    pub fn group_members(&self, gssi: u32) -> Vec<u32> {
+        self.client_mgr.group_members(gssi)
     }
 
-    fn rx_u_itsi_detach(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
+    /// Reports the group attachment changes made since the last call to CMCE
+    fn send_group_changes(&mut self, queue: &mut MessageQueue, dltime: TdmaTime) {
+        for change in self.client_mgr.take_group_changes() {
+            tracing::debug!("-> CMCE {:?}", change);
+            queue.push_back(SapMsg {
+                sap: Sap::Control,
+                src: TetraEntity::Mm,
+                dest: TetraEntity::Cmce,
+                dltime,
+                msg: SapMsgInner::MmGroupControl(change),
+            });
+        }
+    }
+
+    /// Installs the authenticator used if `access_control.require_authentication` is set
+    pub fn set_authenticator(&mut self, authenticator: Box<dyn Authenticator>) {
+        self.authenticator = Some(authenticator);
+    }
+
+    fn rx_u_itsi_detach(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_u_itsi_detach");
         let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {panic!()};
         
-        let pdu = match UItsiDetach::from_bitbuf(&mut prim.sdu) {
+        let pdu = match timed_parse("UItsiDetach", &mut prim.sdu, UItsiDetach::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -55,7 +128,12 @@ impl MmBs {
         }
 
         let ssi = prim.received_address.ssi;
+        if let Some(status_downlink) = self.config.config().access_control.detach_ack_status {
+            Self::send_d_mm_status(queue, message.dltime, ssi, status_downlink);
+        }
+
         let detached_client = self.client_mgr.remove_client(ssi);
+        self.release_visitor(ssi);
         if detached_client.is_none() {
             tracing::warn!("Received UItsiDetach for unknown client with SSI: {}", ssi);
             // return;
@@ -66,7 +144,7 @@ impl MmBs {
         tracing::trace!("rx_location_update_demand");
         let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {panic!()};
 
-        let pdu = match ULocationUpdateDemand::from_bitbuf(&mut prim.sdu) {
+        let pdu = match timed_parse("ULocationUpdateDemand", &mut prim.sdu, ULocationUpdateDemand::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -83,6 +161,135 @@ impl MmBs {
             return;
         }
 
+        // An MS from another network identifies itself by the MNI of its home network
+        let home_mni = Self::home_mni(&self.config);
+        if let Some(mni) = pdu.address_extension.map(|mni| mni as u32)
+            && mni != home_mni {
+            self.rx_migrating_location_update(queue, message.dltime, prim.handle, prim.received_address.ssi, mni, pdu);
+            return;
+        }
+
+        // The (V)ASSI range is kept for visitors, which re-register under their assigned (V)ASSI
+        let issi = prim.received_address.ssi;
+        if self.vassis.contains(issi) && !self.visitors.contains_key(&issi) {
+            tracing::info!("Rejecting MS {}: ISSI within the (V)ASSI range", issi);
+            Self::send_d_location_update_reject(queue, message.dltime, prim.handle, issi, pdu.location_update_type, RejectCause::IllegalMs);
+            return;
+        }
+
+        // Check whether this ISSI is permitted by the access control lists
+        if !self.config.config().access_control.is_issi_allowed(issi) {
+            tracing::info!("Rejecting MS {}: ISSI not allowed by access control", issi);
+            Self::send_d_location_update_reject(queue, message.dltime, prim.handle, issi, pdu.location_update_type, RejectCause::IllegalMs);
+            return;
+        }
+
+        // If subscribers are predefined, only they may register
+        if !self.config.config().subscribers.is_issi_allowed(issi) {
+            tracing::info!("Rejecting MS {}: ISSI not in subscriber database", issi);
+            Self::send_d_location_update_reject(queue, message.dltime, prim.handle, issi, pdu.location_update_type, RejectCause::IllegalMs);
+            return;
+        }
+
+        // A disabled MS may not register until it is enabled again
+        if self.client_mgr.is_disabled(issi) {
+            tracing::info!("Rejecting MS {}: subscription is disabled", issi);
+            Self::send_d_location_update_reject(queue, message.dltime, prim.handle, issi, pdu.location_update_type, RejectCause::ServiceNotSubscribed);
+            return;
+        }
+
+        // New MSs are only accepted while below the configured capacity
+        if let Some(max) = self.config.config().access_control.max_registered_ms
+            && !self.client_mgr.client_is_known(issi)
+            && self.client_mgr.client_count() >= max {
+            tracing::info!("Rejecting MS {}: maximum of {} registered MSs reached", issi, max);
+            Self::send_d_location_update_reject(queue, message.dltime, prim.handle, issi, pdu.location_update_type, RejectCause::Congestion);
+            return;
+        }
+
+        // Check whether the subscriber class we assign to this MS is allowed on this cell
+        if let Some(class) = self.ms_subscriber_class(issi) {
+            let cell_classes = self.config.config().cell.subscriber_class;
+            if !class.is_allowed(cell_classes) {
+                tracing::info!("Rejecting MS {}: {} not allowed on cell with subscriber classes {:#06x}", issi, class, cell_classes);
+                Self::send_d_location_update_reject(queue, message.dltime, prim.handle, issi, pdu.location_update_type, RejectCause::LaNotAllowed);
+                return;
+            }
+        }
+
+        // Withhold the accept until the MS has authenticated itself
+        if self.config.config().access_control.require_authentication {
+            let challenge = self.authenticator.as_mut().and_then(|auth| auth.challenge(issi));
+            let Some(sdu) = challenge else {
+                tracing::warn!("Rejecting MS {}: authentication required, but no challenge available", issi);
+                Self::send_d_location_update_reject(queue, message.dltime, prim.handle, issi, pdu.location_update_type, RejectCause::AuthenticationFailure);
+                return;
+            };
+            tracing::info!("Challenging MS {} for authentication", issi);
+            Self::send_dl_sdu(queue, message.dltime, issi, sdu);
+            self.pending_auth.insert(issi, PendingAuthentication { handle: prim.handle, pdu, challenged: message.dltime });
+            return;
+        }
+
+        self.accept_location_update(queue, message.dltime, prim.handle, issi, pdu);
+    }
+
+    /// MNI of this network, as carried in address extensions
+    fn home_mni(config: &SharedConfig) -> u32 {
+        let net = &config.config().net;
+        ((net.mcc as u32) << 14) | net.mnc as u32
+    }
+
+    /// Handles the location update of an MS migrating from network `mni`, if migration is
+    /// enabled. The MS is assigned a (V)ASSI with D-LOCATION UPDATE PROCEEDING, and is then
+    /// registered under it. The access lists and subscriber database of this network do not
+    /// apply to its ISSI, which belongs to its home network.
+    fn rx_migrating_location_update(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, handle: MleHandle, ussi: u32, mni: u32, pdu: ULocationUpdateDemand) {
+        if !self.config.config().cell.migration {
+            tracing::info!("Rejecting MS {} from MNI {:#08x}: migration not enabled", ussi, mni);
+            Self::send_d_location_update_reject(queue, dltime, handle, ussi, pdu.location_update_type, RejectCause::MigrationNotSupported);
+            return;
+        }
+
+        // The ISSI in the home network may be given explicitly, or else is the address used
+        let issi = pdu.ssi.map(|ssi| ssi as u32).unwrap_or(ussi);
+        let known_vassi = self.visitors.iter().find(|(_, visitor)| **visitor == (mni, issi)).map(|(vassi, _)| *vassi);
+
+        if let Some(max) = self.config.config().access_control.max_registered_ms
+            && known_vassi.is_none_or(|vassi| !self.client_mgr.client_is_known(vassi))
+            && self.client_mgr.client_count() >= max {
+            tracing::info!("Rejecting MS {} from MNI {:#08x}: maximum of {} registered MSs reached", issi, mni, max);
+            Self::send_d_location_update_reject(queue, dltime, handle, ussi, pdu.location_update_type, RejectCause::Congestion);
+            return;
+        }
+
+        // A returning visitor keeps its (V)ASSI
+        let Some(vassi) = known_vassi.or_else(|| self.vassis.allocate()) else {
+            tracing::warn!("Rejecting MS {} from MNI {:#08x}: no (V)ASSI available", issi, mni);
+            Self::send_d_location_update_reject(queue, dltime, handle, ussi, pdu.location_update_type, RejectCause::Congestion);
+            return;
+        };
+        self.visitors.insert(vassi, (mni, issi));
+
+        tracing::info!("Accepting migration of MS {} from MNI {:#08x} as (V)ASSI {}", issi, mni, vassi);
+        let pdu_response = DLocationUpdateProceeding {
+            ssi: vassi,
+            address_extension: mni,
+            proprietary: None,
+        };
+        let mut sdu = BitBuffer::new_autoexpand(4+24+24+1);
+        pdu_response.to_bitbuf(&mut sdu).unwrap(); // we want to know when this happens
+        sdu.seek(0);
+        tracing::debug!("-> {} sdu {}", pdu_response, sdu.dump_bin());
+        Self::send_dl_sdu(queue, dltime, ussi, sdu);
+
+        self.accept_location_update(queue, dltime, handle, vassi, pdu);
+    }
+
+    /// Registers the MS and sends D-LOCATION UPDATE ACCEPT, once all checks have passed
+    fn accept_location_update(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, handle: MleHandle, issi: u32, pdu: ULocationUpdateDemand) {
+        let ms_subscriber_class = self.ms_subscriber_class(issi);
+
         // Handle Energy Saving Mode request
         // TODO FIXME this does not yet seem to be functional, and prevents the MS from remaining 
         // properly registered. 
@@ -101,7 +308,6 @@ impl MmBs {
         let esi = None;
 
         // Try to register the client
-        let issi = prim.received_address.ssi;
         match self.client_mgr.try_register_client(issi, true) {
             Ok(_) => {},
             Err(e) => {
@@ -111,6 +317,13 @@ impl MmBs {
             }
         }
 
+        // Attach predefined subscribers to their home groups
+        for &gssi in self.config.config().subscribers.home_groups(issi) {
+            if let Err(e) = self.client_mgr.client_group_attach(issi, gssi, true) {
+                tracing::warn!("Failed attaching MS {} to home group {}: {:?}", issi, gssi, e);
+            }
+        }
+
         // Process optional GroupIdentityLocationDemand field
         let gila = if let Some(gild) = pdu.group_identity_location_demand {
             // Try to attach to requested groups, then build GroupIdentityLocationAccept element
@@ -134,18 +347,10 @@ impl MmBs {
         let pdu_response = DLocationUpdateAccept {
             location_update_accept_type: pdu.location_update_type, // Practically identical besides minor migration-related difference
             ssi: Some(issi as u64),
-            address_extension: None,
-            subscriber_class: None,
+            subscriber_class: ms_subscriber_class.map(|class| class.0 as u64),
             energy_saving_information: esi,
-            scch_information_and_distribution_on_18th_frame: None,
-            new_registered_area: None,
-            security_downlink: None,
             group_identity_location_accept: gila,
-            default_group_attachment_lifetime: None,
-            authentication_downlink: None,
-            group_identity_security_related_information: None,
-            cell_type_control: None,
-            proprietary: None,
+            ..Default::default()
         };
 
         // Convert pdu to bits
@@ -161,10 +366,10 @@ impl MmBs {
             sap: Sap::LmmSap,
             src: TetraEntity::Mm,
             dest: TetraEntity::Mle,
-            dltime: message.dltime,
+            dltime,
             msg: SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq{
                 sdu,
-                handle: prim.handle,
+                handle,
                 address: addr,
                 layer2service: 0,
                 stealing_permission: false,
@@ -176,11 +381,51 @@ impl MmBs {
         queue.push_back(msg);        
     }
 
+    /// Subscriber class assigned to the MS: its predefined class, or else the default of the cell
+    fn ms_subscriber_class(&self, issi: u32) -> Option<SubscriberClass> {
+        let config = self.config.config();
+        config.subscribers.subscriber_class(issi).or(config.cell.ms_subscriber_class).map(SubscriberClass)
+    }
+
+    /// Sends a D-LOCATION UPDATE REJECT with the given reject cause to the MS
+    fn send_d_location_update_reject(queue: &mut MessageQueue, dltime: TdmaTime, handle: MleHandle, issi: u32, location_update_type: LocationUpdateType, reject_cause: RejectCause) {
+        let pdu_response = DLocationUpdateReject {
+            location_update_type: location_update_type.into_raw() as u8,
+            reject_cause: reject_cause.into_raw() as u8,
+            cipher_control: false,
+            ..Default::default()
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(4+3+5+1+1);
+        pdu_response.to_bitbuf(&mut sdu).unwrap(); // we want to know when this happens
+        sdu.seek(0);
+        tracing::debug!("-> {} sdu {}", pdu_response, sdu.dump_bin());
+
+        let addr = TetraAddress { encrypted: false, ssi_type: SsiType::Ssi, ssi: issi };
+        let msg = SapMsg {
+            sap: Sap::LmmSap,
+            src: TetraEntity::Mm,
+            dest: TetraEntity::Mle,
+            dltime,
+            msg: SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq{
+                sdu,
+                handle,
+                address: addr,
+                layer2service: 0,
+                stealing_permission: false,
+                stealing_repeats_flag: false, 
+                encryption_flag: false,
+                is_null_pdu: false,
+            })
+        };
+        queue.push_back(msg);
+    }
+
     fn rx_u_mm_status(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_u_mm_status");
         let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {panic!()};
         
-        let pdu = match UMmStatus::from_bitbuf(&mut prim.sdu) {
+        let pdu = match timed_parse("UMmStatus", &mut prim.sdu, UMmStatus::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -230,12 +475,157 @@ impl MmBs {
         }
     }
 
+    /// Address extension (MCC and MNC) of this network, as used in D-DISABLE and D-ENABLE
+    fn address_extension(&self) -> u64 {
+        let net = &self.config.config().net;
+        ((net.mcc as u64) << 14) | net.mnc as u64
+    }
+
+    /// Sends a D-DISABLE to the MS, disabling its subscription temporarily or permanently.
+    /// Returns false, without sending anything, if disabling is not permitted by the configuration.
+    pub fn send_d_disable(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, permanent: bool) -> bool {
+        if !self.config.config().access_control.allow_disable {
+            tracing::warn!("Refusing to disable MS {}: access_control.allow_disable is not set", issi);
+            return false;
+        }
+
+        // Without authentication, the intent phase is skipped and the disabling is confirmed right away
+        let pdu = DDisable {
+            intent_confirm: true,
+            disabling_type: permanent,
+            subscription_disable: true,
+            address_extension: Some(self.address_extension()),
+            ssi: Some(issi as u64),
+            ..Default::default()
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(4+3+1+48+1);
+        pdu.to_bitbuf(&mut sdu).unwrap(); // we want to know when this happens
+        sdu.seek(0);
+        tracing::debug!("-> {} sdu {}", pdu, sdu.dump_bin());
+        Self::send_dl_sdu(queue, dltime, issi, sdu);
+        self.client_mgr.disable_client(issi);
+        true
+    }
+
+    /// Sends a D-ENABLE to the MS, re-enabling its temporarily disabled subscription.
+    /// Returns false, without sending anything, if disabling is not permitted by the configuration.
+    pub fn send_d_enable(&mut self, queue: &mut MessageQueue, dltime: TdmaTime, issi: u32) -> bool {
+        if !self.config.config().access_control.allow_disable {
+            tracing::warn!("Refusing to enable MS {}: access_control.allow_disable is not set", issi);
+            return false;
+        }
+
+        let pdu = DEnable {
+            intent_confirm: true,
+            subscription_enable: true,
+            address_extension: Some(self.address_extension()),
+            ssi: Some(issi as u64),
+            ..Default::default()
+        };
+
+        let mut sdu = BitBuffer::new_autoexpand(4+2+1+48+1);
+        pdu.to_bitbuf(&mut sdu).unwrap(); // we want to know when this happens
+        sdu.seek(0);
+        tracing::debug!("-> {} sdu {}", pdu, sdu.dump_bin());
+        Self::send_dl_sdu(queue, dltime, issi, sdu);
+        self.client_mgr.enable_client(issi);
+        true
+    }
+
+    /// Submits an MM SDU addressed to the given ISSI to the MLE
+    /// Sends a D-MM STATUS without status downlink dependent information
+    fn send_d_mm_status(queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, status_downlink: u8) {
+        let pdu = DMmStatus { status_downlink, status_downlink_dependent_information: None };
+        let mut sdu = BitBuffer::new_autoexpand(4+6+1);
+        pdu.to_bitbuf(&mut sdu).unwrap();
+        sdu.seek(0);
+        tracing::debug!("-> {} sdu {}", pdu, sdu.dump_bin());
+        Self::send_dl_sdu(queue, dltime, issi, sdu);
+    }
+
+    fn send_dl_sdu(queue: &mut MessageQueue, dltime: TdmaTime, issi: u32, sdu: BitBuffer) {
+        let addr = TetraAddress { encrypted: false, ssi_type: SsiType::Ssi, ssi: issi };
+        let msg = SapMsg {
+            sap: Sap::LmmSap,
+            src: TetraEntity::Mm,
+            dest: TetraEntity::Mle,
+            dltime,
+            msg: SapMsgInner::LmmMleUnitdataReq(LmmMleUnitdataReq{
+                sdu,
+                handle: 0,
+                address: addr,
+                layer2service: 0,
+                stealing_permission: false,
+                stealing_repeats_flag: false, 
+                encryption_flag: false,
+                is_null_pdu: false,
+            })
+        };
+        queue.push_back(msg);
+    }
+
+    /// Completes or rejects a location update held back for authentication
+    fn rx_u_authentication(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_u_authentication");
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {panic!()};
+
+        let issi = prim.received_address.ssi;
+        let Some(pending) = self.pending_auth.remove(&issi) else {
+            tracing::warn!("Received UAuthentication from MS {} without pending challenge", issi);
+            return;
+        };
+        let Some(authenticator) = self.authenticator.as_mut() else {
+            return;
+        };
+
+        if authenticator.verify(issi, &mut prim.sdu) {
+            tracing::info!("MS {} authenticated", issi);
+            self.accept_location_update(queue, message.dltime, pending.handle, issi, pending.pdu);
+        } else {
+            tracing::info!("Rejecting MS {}: authentication failed", issi);
+            Self::send_d_location_update_reject(queue, message.dltime, pending.handle, issi, pending.pdu.location_update_type, RejectCause::AuthenticationFailure);
+        }
+    }
+
+    fn rx_u_disable_status(&mut self, _queue: &mut MessageQueue, mut message: SapMsg) {
+        tracing::trace!("rx_u_disable_status");
+        let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {panic!()};
+
+        let pdu = match timed_parse("UDisableStatus", &mut prim.sdu, UDisableStatus::from_bitbuf) {
+            Ok(pdu) => {
+                tracing::debug!("<- {:?}", pdu);
+                pdu
+            }
+            Err(e) => {
+                tracing::warn!("Failed parsing UDisableStatus: {:?} {}", e, prim.sdu.dump_bin());
+                return;
+            }
+        };
+
+        let issi = prim.received_address.ssi;
+        if !self.config.config().access_control.allow_disable {
+            tracing::info!("Ignoring UDisableStatus from MS {}: access_control.allow_disable is not set", issi);
+            return;
+        }
+        if pdu.enable_disable_result != 0 {
+            tracing::warn!("MS {} reports failed enable/disable, result {}", issi, pdu.enable_disable_result);
+            return;
+        }
+
+        // The disabled state is set by the D-DISABLE / D-ENABLE commands; the MS report is only checked against it
+        tracing::info!("MS {} reports subscription {}", issi, pdu.subscription_status);
+        if pdu.subscription_status.is_disabled() != self.client_mgr.is_disabled(issi) {
+            tracing::warn!("MS {} reports subscription {}, which does not match the commanded state", issi, pdu.subscription_status);
+        }
+    }
+
     fn rx_u_attach_detach_group_identity(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_u_attach_detach_group_identity");
         let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {panic!()};
         
         let issi = prim.received_address.ssi;
-        let pdu = match UAttachDetachGroupIdentity::from_bitbuf(&mut prim.sdu) {
+        let pdu = match timed_parse("UAttachDetachGroupIdentity", &mut prim.sdu, UAttachDetachGroupIdentity::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -306,10 +696,38 @@ impl MmBs {
         queue.push_back(msg);
     }
 
+    /// Deregisters clients not heard from within the configured inactivity timeout
+    fn remove_inactive_clients(&mut self, now: TdmaTime) {
+        let Some(timeout_s) = self.config.config().access_control.inactivity_timeout_s else {
+            return;
+        };
+        // A timeslot lasts 85/6 ms
+        let timeout_slots = (timeout_s as i64 * 1200 / 17).min(i32::MAX as i64) as i32;
+        for issi in self.client_mgr.remove_inactive_clients(now, timeout_slots) {
+            tracing::info!("Deregistering MS {}: not heard from for {} s", issi, timeout_s);
+            self.release_visitor(issi);
+        }
+    }
+
+    /// Rejects the location updates of MSs that did not answer their challenge in time
+    fn expire_pending_authentications(&mut self, queue: &mut MessageQueue, now: TdmaTime) {
+        let expired: Vec<u32> = self.pending_auth.iter()
+            .filter(|(_, pending)| pending.challenged.age(now) > AUTHENTICATION_TIMEOUT_SLOTS)
+            .map(|(issi, _)| *issi)
+            .collect();
+        for issi in expired {
+            let Some(pending) = self.pending_auth.remove(&issi) else { continue };
+            tracing::info!("Rejecting MS {}: no answer to authentication challenge", issi);
+            Self::send_d_location_update_reject(queue, now, pending.handle, issi, pending.pdu.location_update_type, RejectCause::AuthenticationFailure);
+        }
+    }
+
     fn rx_lmm_mle_unitdata_ind(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
 
         // unimplemented_log!("rx_lmm_mle_unitdata_ind for MM component");
         let SapMsgInner::LmmMleUnitdataInd(prim) = &mut message.msg else {panic!()};
+        let issi = prim.received_address.ssi;
+        let dltime = message.dltime;
 
         let Some(bits) = prim.sdu.peek_bits(4) else {
             tracing::warn!("insufficient bits: {}", prim.sdu.dump_bin());
@@ -321,9 +739,13 @@ impl MmBs {
             return;
         };
 
+        if let Some(q) = prim.rx_quality {
+            tracing::debug!("{:?} from {}: rssi {:.1} dBm, ber {:.3}", pdu_type, prim.received_address.ssi, q.rssi_dbm, q.ber_estimate);
+        }
+
         match pdu_type {
             MmPduTypeUl::UAuthentication => 
-                unimplemented_log!("UAuthentication"),
+                self.rx_u_authentication(queue, message),
             MmPduTypeUl::UItsiDetach => 
                 self.rx_u_itsi_detach(queue, message),
             MmPduTypeUl::ULocationUpdateDemand => 
@@ -343,10 +765,13 @@ impl MmBs {
             MmPduTypeUl::UTeiProvide => 
                 unimplemented_log!("UTeiProvide"),
             MmPduTypeUl::UDisableStatus => 
-                unimplemented_log!("UDisableStatus"),
+                self.rx_u_disable_status(queue, message),
             MmPduTypeUl::MmPduFunctionNotSupported => 
                 unimplemented_log!("MmPduFunctionNotSupported"),
         };
+
+        // Done after handling, so a client that just registered is included
+        self.client_mgr.touch_client(issi, dltime);
     }
 
     fn try_attach_detach_groups(&mut self, issi: u32, giu_vec: &Vec<GroupIdentityUplink>) -> Vec<GroupIdentityDownlink> {
@@ -358,23 +783,24 @@ impl MmBs {
             }   
 
             let gssi = giu.gssi.unwrap(); // can't fail
-            match self.client_mgr.client_group_attach(issi, gssi, true) {
+            let do_attach = giu.group_identity_detachment_uplink.is_none();
+            match self.client_mgr.client_group_attach(issi, gssi, do_attach) {
                 Ok(_) => {
-                    // We have added the client to this group. Add an entry to the downlink response
+                    // We have added or removed the client to or from this group. Add an entry to the downlink response
                     let gid = GroupIdentityDownlink {
-                        group_identity_attachment: Some(GroupIdentityAttachment {
+                        group_identity_attachment: do_attach.then(|| GroupIdentityAttachment {
                             group_identity_attachment_lifetime: 3, // re-attach after location update
                             class_of_usage: giu.class_of_usage.unwrap_or(0),
                         }),
-                        group_identity_detachment_uplink: None,
-                        gssi: Some(giu.gssi.unwrap()),
+                        group_identity_detachment_uplink: giu.group_identity_detachment_uplink,
+                        gssi: Some(gssi),
                         address_extension: None,
                         vgssi: None
                     };
                     accepted_groups.push(gid);
                 },
                 Err(e) => {
-                    tracing::warn!("Failed attaching MS {} to group {}: {:?}", issi, gssi, e);
+                    tracing::warn!("Failed {} MS {} to group {}: {:?}", if do_attach { "attaching" } else { "detaching" }, issi, gssi, e);
                 }
             }
         }
@@ -395,7 +821,8 @@ impl MmBs {
 
     fn feature_check_u_location_update_demand(pdu: &ULocationUpdateDemand) -> bool {
         let mut supported = true;
-        if pdu.location_update_type != LocationUpdateType::RoamingLocationUpdating && pdu.location_update_type != LocationUpdateType::ItsiAttach {
+        // Value 1 is migrating location updating in U-LOCATION UPDATE DEMAND
+        if !matches!(pdu.location_update_type, LocationUpdateType::RoamingLocationUpdating | LocationUpdateType::ItsiAttach | LocationUpdateType::TemporaryRegistration) {
             unimplemented_log!("Unsupported {}", pdu.location_update_type);
             supported = false;
         }
@@ -420,12 +847,6 @@ impl MmBs {
         if pdu.la_information.is_some() {
             unimplemented_log!("Unsupported la_information present");
         }
-        if pdu.ssi.is_some() {
-            unimplemented_log!("Unsupported ssi present");
-        }
-        if pdu.address_extension.is_some() {
-            unimplemented_log!("Unsupported address_extension present");
-        }
         if pdu.group_report_response.is_some() {
             unimplemented_log!("Unsupported group_report_response present");
         }
@@ -476,19 +897,27 @@ impl TetraEntityTrait for MmBs {
         self.config = config;
     }
 
+    fn tick_start(&mut self, queue: &mut MessageQueue, ts: TdmaTime) {
+        self.remove_inactive_clients(ts);
+        self.expire_pending_authentications(queue, ts);
+        self.send_group_changes(queue, ts);
+    }
+
     fn rx_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         
         tracing::debug!("rx_prim: {:?}", message);
         // tracing::debug!(ts=%message.dltime, "rx_prim: {:?}", message);
         
-        // There is only one SAP for MM
-        assert!(message.sap == Sap::LmmSap);
-        
-        match message.msg {
-            SapMsgInner::LmmMleUnitdataInd(_) => {
+        let dltime = message.dltime;
+        match (message.sap, &message.msg) {
+            (Sap::LmmSap, SapMsgInner::LmmMleUnitdataInd(_)) => {
                 self.rx_lmm_mle_unitdata_ind(queue, message);
             }
+            (Sap::Control, SapMsgInner::MleMsActivity(activity)) => {
+                self.client_mgr.touch_client(activity.ssi, dltime);
+            }
             _ => { panic!(); }
         }
+        self.send_group_changes(queue, dltime);
     }
 }
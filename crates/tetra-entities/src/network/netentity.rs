@@ -125,6 +125,10 @@ impl<W: NetEntityWorker> TetraEntityTrait for NetEntity<W> {
     fn entity(&self) -> TetraEntity {
         self.entity_self
     }
+
+    fn user_saps(&self) -> &[Sap] {
+        std::slice::from_ref(&self.sap)
+    }
     
     fn set_config(&mut self, config: SharedConfig) {
         self.config = config;
@@ -0,0 +1,128 @@
+use tetra_core::pdu_log::AddressedPdu;
+use tetra_core::{BitBuffer, Direction, PduParseErr};
+use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
+use tetra_pdus::cmce::enums::cmce_pdu_type_ul::CmcePduTypeUl;
+use tetra_pdus::cmce::pdus::cmce_pdu::CmcePdu;
+use tetra_pdus::cmce::pdus::cmce_pdu_dl::CmcePduDl;
+use tetra_pdus::cmce::pdus::cmce_pdu_ul::CmcePduUl;
+use tetra_pdus::cmce::pdus::d_sds_data::DSdsData;
+use tetra_pdus::cmce::pdus::d_setup::DSetup;
+use tetra_pdus::cmce::pdus::d_status::DStatus;
+use tetra_pdus::cmce::pdus::d_tx_granted::DTxGranted;
+use tetra_pdus::cmce::pdus::u_sds_data::USdsData;
+use tetra_pdus::cmce::pdus::u_setup::USetup;
+use tetra_pdus::cmce::pdus::u_status::UStatus;
+use tetra_pdus::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use tetra_pdus::mm::enums::mm_pdu_type_ul::MmPduTypeUl;
+use tetra_pdus::mm::pdus::d_disable::DDisable;
+use tetra_pdus::mm::pdus::d_enable::DEnable;
+use tetra_pdus::mm::pdus::d_location_update_accept::DLocationUpdateAccept;
+use tetra_pdus::mm::pdus::d_location_update_proceeding::DLocationUpdateProceeding;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
+use tetra_saps::{SapMsg, SapMsgInner};
+
+
+/// Extracts the address carried inside an MM or CMCE PDU, for primitives between the MLE and MM/CMCE.
+/// The SDU is not consumed; `dir` determines whether it holds an uplink or downlink PDU.
+/// Returns None for other primitives, PDUs without address, and PDUs that fail to parse.
+pub fn addressed_ssi(message: &SapMsg, dir: Direction) -> Option<u32> {
+    match (&message.msg, dir) {
+        (SapMsgInner::LmmMleUnitdataInd(prim), dir) => mm_addressed_ssi(&prim.sdu, dir),
+        (SapMsgInner::LmmMleUnitdataReq(prim), dir) => mm_addressed_ssi(&prim.sdu, dir),
+        (SapMsgInner::LcmcMleUnitdataInd(prim), dir) => cmce_addressed_ssi(&prim.sdu, dir),
+        (SapMsgInner::LcmcMleUnitdataReq(prim), dir) => cmce_addressed_ssi(&prim.sdu, dir),
+        _ => None,
+    }
+}
+
+fn mm_addressed_ssi(sdu: &BitBuffer, dir: Direction) -> Option<u32> {
+    let bits = sdu.peek_bits(4)?;
+    match dir {
+        Direction::Ul => match MmPduTypeUl::try_from(bits).ok()? {
+            MmPduTypeUl::ULocationUpdateDemand => parse_addressed(sdu, ULocationUpdateDemand::from_bitbuf),
+            _ => None,
+        },
+        Direction::Dl => match MmPduTypeDl::try_from(bits).ok()? {
+            MmPduTypeDl::DLocationUpdateAccept => parse_addressed(sdu, DLocationUpdateAccept::from_bitbuf),
+            MmPduTypeDl::DLocationUpdateProceeding => parse_addressed(sdu, DLocationUpdateProceeding::from_bitbuf),
+            MmPduTypeDl::DDisable => parse_addressed(sdu, DDisable::from_bitbuf),
+            MmPduTypeDl::DEnable => parse_addressed(sdu, DEnable::from_bitbuf),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn cmce_addressed_ssi(sdu: &BitBuffer, dir: Direction) -> Option<u32> {
+    let bits = sdu.peek_bits(5)?;
+    match dir {
+        Direction::Ul => match CmcePduTypeUl::try_from(bits).ok()? {
+            CmcePduTypeUl::USetup => parse_addressed(sdu, USetup::from_bitbuf),
+            CmcePduTypeUl::USdsData => parse_addressed(sdu, USdsData::from_bitbuf),
+            CmcePduTypeUl::UStatus => parse_addressed(sdu, UStatus::from_bitbuf),
+            _ => None,
+        },
+        Direction::Dl => match CmcePduTypeDl::try_from(bits).ok()? {
+            CmcePduTypeDl::DSetup => parse_addressed(sdu, DSetup::from_bitbuf),
+            CmcePduTypeDl::DSdsData => parse_addressed(sdu, DSdsData::from_bitbuf),
+            CmcePduTypeDl::DStatus => parse_addressed(sdu, DStatus::from_bitbuf),
+            CmcePduTypeDl::DTxGranted => parse_addressed(sdu, DTxGranted::from_bitbuf),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Type of the MM or CMCE PDU in a primitive between the MLE and MM/CMCE, and whether it was
+/// received over the air (an indication) rather than generated by the stack (a request).
+/// `dir` determines whether it holds an uplink or downlink PDU. Returns None for other primitives
+/// and unknown PDU types.
+pub fn pdu_type(message: &SapMsg, dir: Direction) -> Option<(String, bool)> {
+    match &message.msg {
+        SapMsgInner::LmmMleUnitdataInd(prim) => Some((mm_pdu_type(&prim.sdu, dir)?, true)),
+        SapMsgInner::LmmMleUnitdataReq(prim) => Some((mm_pdu_type(&prim.sdu, dir)?, false)),
+        SapMsgInner::LcmcMleUnitdataInd(prim) => Some((cmce_pdu_type(&prim.sdu, dir)?, true)),
+        SapMsgInner::LcmcMleUnitdataReq(prim) => Some((cmce_pdu_type(&prim.sdu, dir)?, false)),
+        _ => None,
+    }
+}
+
+fn mm_pdu_type(sdu: &BitBuffer, dir: Direction) -> Option<String> {
+    let bits = sdu.peek_bits(4)?;
+    match dir {
+        Direction::Ul => MmPduTypeUl::try_from(bits).ok().map(|t| t.to_string()),
+        Direction::Dl => MmPduTypeDl::try_from(bits).ok().map(|t| t.to_string()),
+        _ => None,
+    }
+}
+
+fn cmce_pdu_type(sdu: &BitBuffer, dir: Direction) -> Option<String> {
+    let bits = sdu.peek_bits(5)?;
+    match dir {
+        Direction::Ul => CmcePduTypeUl::try_from(bits).ok().map(|t| t.to_string()),
+        Direction::Dl => CmcePduTypeDl::try_from(bits).ok().map(|t| t.to_string()),
+        _ => None,
+    }
+}
+
+/// Parses a copy of the SDU from its current position
+fn parse_addressed<P: AddressedPdu>(sdu: &BitBuffer, parse: fn(&mut BitBuffer) -> Result<P, PduParseErr>) -> Option<u32> {
+    let mut copy = BitBuffer::from_bitbuffer_pos(sdu);
+    parse(&mut copy).ok()?.addressed_ssi()
+}
+
+/// Decodes the CMCE PDU carried by a primitive between the MLE and CMCE, without consuming the SDU.
+/// Returns None for other primitives and PDUs that fail to parse.
+pub fn decode_cmce_pdu(message: &SapMsg, dir: Direction) -> Option<CmcePdu> {
+    let sdu = match &message.msg {
+        SapMsgInner::LcmcMleUnitdataInd(prim) => &prim.sdu,
+        SapMsgInner::LcmcMleUnitdataReq(prim) => &prim.sdu,
+        _ => return None,
+    };
+    let mut copy = BitBuffer::from_bitbuffer_pos(sdu);
+    match dir {
+        Direction::Ul => CmcePduUl::from_bitbuf(&mut copy).ok().map(CmcePdu::Ul),
+        Direction::Dl => CmcePduDl::from_bitbuf(&mut copy).ok().map(CmcePdu::Dl),
+        _ => None,
+    }
+}
@@ -0,0 +1,105 @@
+//! Automatic gain control for received signals at the modem sample rate.
+
+use tetra_config::CfgAgc;
+
+use super::demodulator;
+use super::dsp_types::*;
+
+pub struct Agc {
+    target_rms: RealSample,
+    /// Smoothing factor per sample while signal power increases
+    attack: RealSample,
+    /// Smoothing factor per sample while signal power decreases
+    decay: RealSample,
+    max_gain: RealSample,
+    /// Estimated input signal power
+    power: RealSample,
+}
+
+impl Agc {
+    pub fn new(cfg: &CfgAgc) -> Self {
+        Self {
+            target_rms: cfg.target_rms,
+            attack: smoothing_factor(cfg.attack_ms),
+            decay: smoothing_factor(cfg.decay_ms),
+            max_gain: 10f32.powf(cfg.max_gain_db / 20.0),
+            power: 0.0,
+        }
+    }
+
+    /// Update the power estimate with one input sample and return it scaled
+    pub fn process(&mut self, input: ComplexSample) -> ComplexSample {
+        let power = input.norm_sqr();
+        let alpha = if power > self.power { self.attack } else { self.decay };
+        self.power += alpha * (power - self.power);
+        input * self.gain()
+    }
+
+    /// Current gain applied to the signal
+    pub fn gain(&self) -> RealSample {
+        if self.power > 0.0 {
+            (self.target_rms / self.power.sqrt()).min(self.max_gain)
+        } else {
+            self.max_gain
+        }
+    }
+
+    /// Current gain in dB, to refer measured signal strength back to the input
+    pub fn gain_db(&self) -> RealSample {
+        20.0 * self.gain().log10()
+    }
+}
+
+/// Single pole smoothing factor for a time constant in milliseconds
+fn smoothing_factor(time_constant_ms: RealSample) -> RealSample {
+    let samples = time_constant_ms * 1e-3 * demodulator::SAMPLE_RATE as RealSample;
+    1.0 - (-1.0 / samples).exp()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// RMS of a constant amplitude signal with rotating phase
+    fn run(agc: &mut Agc, amplitude: RealSample, n: usize) -> RealSample {
+        let mut power = 0.0;
+        for i in 0..n {
+            let input = ComplexSample::from_polar(amplitude, i as RealSample * 0.3);
+            power += agc.process(input).norm_sqr();
+        }
+        (power / n as RealSample).sqrt()
+    }
+
+    #[test]
+    fn test_agc_converges_after_amplitude_step() {
+        let cfg = CfgAgc { target_rms: 0.5, attack_ms: 1.0, decay_ms: 10.0, max_gain_db: 60.0 };
+        let mut agc = Agc::new(&cfg);
+
+        // Weak signal: settled after a few decay time constants
+        run(&mut agc, 0.01, 72 * 50);
+        let rms = run(&mut agc, 0.01, 720);
+        assert!((rms - 0.5).abs() < 0.01, "rms {}", rms);
+
+        // 40 dB step up is followed within a few attack time constants
+        run(&mut agc, 1.0, 72 * 5);
+        let rms = run(&mut agc, 1.0, 720);
+        assert!((rms - 0.5).abs() < 0.01, "rms {}", rms);
+
+        // Step down follows more slowly
+        let rms = run(&mut agc, 0.1, 72);
+        assert!(rms < 0.1, "rms {}", rms);
+        run(&mut agc, 0.1, 72 * 100);
+        let rms = run(&mut agc, 0.1, 720);
+        assert!((rms - 0.5).abs() < 0.01, "rms {}", rms);
+    }
+
+    #[test]
+    fn test_agc_gain_is_limited() {
+        let cfg = CfgAgc { max_gain_db: 20.0, ..Default::default() };
+        let mut agc = Agc::new(&cfg);
+        assert!((agc.gain() - 10.0).abs() < 1e-3);
+        let rms = run(&mut agc, 1e-4, 72 * 100);
+        assert!((rms - 1e-3).abs() < 1e-5, "rms {}", rms);
+    }
+}
@@ -1,6 +1,7 @@
 use num;
 use num::complex::ComplexFloat;
 
+use tetra_core::RxQuality;
 use tetra_core::TdmaTime;
 use tetra_core::TrainingSequence;
 use tetra_pdus::phy::traits::rxtx_dev::RxBurstBits;
@@ -23,6 +24,12 @@ pub const SAMPLES_SYMBOL: SampleCount = SPS as SampleCount;
 /// Samples per slot
 const SAMPLES_SLOT: SampleCount = SAMPLES_SYMBOL * 255;
 
+/// Start of uplink bursts within a (sub)slot in bits, following the ramp-up period
+const UL_BURST_START_BITS: usize = 34;
+
+/// Length of an uplink subslot in bits
+const UL_SUBSLOT_BITS: usize = 255;
+
 /// Input sample rate
 pub const SAMPLE_RATE: f64 = 18000.0 * SPS as f64;
 
@@ -77,6 +84,9 @@ pub struct Demodulator {
     /// Absolute values of past samples,
     /// used for symbol timing estimation.
     past_samples_abs: history::History<RealSample, {SPS * 512}>,
+    /// Power of past input samples before matched filtering,
+    /// used for signal strength measurement.
+    past_input_power: history::History<RealSample, {SPS * 512}>,
 }
 
 impl Demodulator {
@@ -101,6 +111,7 @@ impl Demodulator {
             matched_filter: fir::FirComplexSym::new(CHANNEL_FILTER_TAPS.len()),
             past_samples:     history::History::new(num::zero()),
             past_samples_abs: history::History::new(num::zero()),
+            past_input_power: history::History::new(num::zero()),
         };
 
         self_.set_slot_ready_time();
@@ -167,6 +178,7 @@ impl Demodulator {
         let filtered = self.matched_filter.sample(&CHANNEL_FILTER_TAPS, input);
         self.past_samples.write(filtered);
         self.past_samples_abs.write(filtered.abs());
+        self.past_input_power.write(input.norm_sqr());
 
         let tdiff = sample_counter - self.slot_ready_time;
         // Lost samples (gaps in sample_counter) or adjustment of reference_time
@@ -249,6 +261,17 @@ impl Demodulator {
 
         burst_finder.clear();
 
+        // Nominal start of a burst in this window, in bits from slot beginning
+        let nominal_burst_start = match (self.mode, subslot_number) {
+            // Subslot 2 begins in the middle of a symbol
+            (Mode::Ul, 2) => (UL_SUBSLOT_BITS + UL_BURST_START_BITS).next_multiple_of(2),
+            (Mode::Ul, _) => UL_BURST_START_BITS,
+            _ => 0,
+        };
+        // Time of the first sample in the window relative to the nominal burst start
+        let window_offset = Self::slot_ready_from_begin(self.mode) - d as SampleCount
+            - (nominal_burst_start * SPS / 2) as SampleCount;
+
         let bits = &mut burst_finder.bits;
         let mut previous_symbol: Option<ComplexSample> = None;
         for i in (first_symbol_index..first_symbol_index + SPS*n_symbols).step_by(SPS) {
@@ -305,6 +328,9 @@ impl Demodulator {
             },
             Mode::Dl => {
                 training_sequence_found = burst_finder.check_slot(SlotType::Dl);
+                if training_sequence_found {
+                    burst_finder.measure_quality(&self.past_input_power, d, symbol_timing, window_offset);
+                }
 
                 // If no valid training sequences have been detected for some time,
                 // assume signal is lost and switch back to Mode::DlUnsynchronized.
@@ -323,6 +349,9 @@ impl Demodulator {
             },
             Mode::Ul => {
                 training_sequence_found = burst_finder.check_slot(if subslot_number == 0 { SlotType::UlFull } else { SlotType::UlSub });
+                if training_sequence_found {
+                    burst_finder.measure_quality(&self.past_input_power, d, symbol_timing, window_offset);
+                }
 
                 // Uplink slot numbering is offset from downlink by 2.
                 // This could also be done by using a different reference_time for UL
@@ -464,6 +493,12 @@ struct SlotBurstFinder {
     burst_pos: usize,
     /// Length of burst
     burst_len: usize,
+    /// Length of training sequence found
+    train_len: usize,
+    /// Mean input power over the burst
+    power: RealSample,
+    /// Arrival time of the burst relative to its nominal position, in samples
+    delay: RealSample,
 }
 
 impl SlotBurstFinder {
@@ -486,6 +521,9 @@ impl SlotBurstFinder {
             train_errs: Self::ERRS_NO_BURST,
             burst_pos: 0,
             burst_len: 0,
+            train_len: 0,
+            power: 0.0,
+            delay: 0.0,
         }
     }
 
@@ -495,6 +533,9 @@ impl SlotBurstFinder {
         self.train_errs = Self::ERRS_NO_BURST;
         self.burst_pos = 0;
         self.burst_len = 0;
+        self.train_len = 0;
+        self.power = 0.0;
+        self.delay = 0.0;
     }
 
     fn check_sequence(
@@ -518,6 +559,7 @@ impl SlotBurstFinder {
             self.burst_pos = train_pos - train_pos_in_burst;
             self.train_errs = dist;
             self.burst_len = burst_len;
+            self.train_len = train_len;
             self.train_type = train_type;
             tracing::info!("Found {:?} at {} with {} errors", train_type, train_pos, dist);
             true
@@ -575,10 +617,143 @@ impl SlotBurstFinder {
         false
     }
 
+    /// Measure input power and arrival time of the burst found, using the same
+    /// window and timing as given to process_slot. `window_offset` is the time
+    /// of the first window sample relative to the nominal burst start.
+    fn measure_quality(
+        &mut self,
+        input_power: &history::History<RealSample, {SPS * 512}>,
+        d: usize,
+        symbol_timing: RealSample,
+        window_offset: SampleCount,
+    ) {
+        let first_symbol_index = symbol_timing.floor() as usize;
+        // Input power history is not delayed by the matched filter
+        let d_input = d + CHANNEL_FILTER_TAPS.len();
+        let first = first_symbol_index + self.burst_pos / 2 * SPS;
+        let last = first_symbol_index + (self.burst_pos + self.burst_len) / 2 * SPS;
+        let sum: RealSample = (first..last).map(|i| input_power.delayed(d_input - i)).sum();
+        self.power = sum / (last - first) as RealSample;
+
+        // The first burst symbol follows the phase reference symbol of its first bits.
+        // Delay of the matched filter is half a sample less than compensated for.
+        let burst_start = symbol_timing + ((self.burst_pos / 2 + 1) * SPS) as RealSample + 0.5;
+        self.delay = window_offset as RealSample + burst_start;
+    }
+
     fn get_burst<'a>(&'a mut self) -> RxBurstBits<'a> {
+        let quality = if self.train_len > 0 {
+            RxQuality {
+                rssi_dbm: 10.0 * self.power.max(RealSample::MIN_POSITIVE).log10(),
+                ber_estimate: self.train_errs as RealSample / self.train_len as RealSample,
+                delay_symbols: self.delay / SPS as RealSample,
+            }
+        } else {
+            RxQuality::default()
+        };
         RxBurstBits {
             train_type: self.train_type,
-            bits: &self.bits[self.burst_pos .. self.burst_pos + self.burst_len]
+            bits: &self.bits[self.burst_pos .. self.burst_pos + self.burst_len],
+            quality,
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use tetra_pdus::phy::traits::rxtx_dev::TxSlotBits;
+
+    use super::super::modulator;
+    use super::super::slotter::bitseq;
+    use super::*;
+
+    /// Uplink burst with random data around a training sequence
+    fn ul_burst(train_seq: &[u8], block_len: usize, rng: &mut StdRng) -> Vec<u8> {
+        let mut burst = bitseq::t.to_vec();
+        burst.extend((0..block_len).map(|_| rng.random_range(0..2u8)));
+        burst.extend_from_slice(train_seq);
+        burst.extend((0..block_len).map(|_| rng.random_range(0..2u8)));
+        burst.extend_from_slice(&bitseq::t);
+        burst
+    }
+
+    /// Transmits `slot_bits` in consecutive slots, delays the signal by `delay` samples
+    /// and returns the training sequence and quality of the full slot and both subslots
+    /// of each slot demodulated by an uplink demodulator
+    fn demodulate_delayed(slot_bits: &[u8; 510], delay: usize, num_slots: i32) -> Vec<[(TrainingSequence, RxQuality); 3]> {
+        let mut modulator = modulator::Modulator::new(modulator::Mode::Dl);
+        let mut tx = Vec::new();
+        for slot in 0..num_slots {
+            let tx_slot = TxSlotBits { time: TdmaTime::from_int(slot), slot: Some(slot_bits) };
+            while let Ok(sample) = modulator.sample(tx.len() as SampleCount, &tx_slot) {
+                tx.push(sample);
+            }
+        }
+
+        let mut demodulator = Demodulator::new(Mode::Ul);
+        let mut slots = Vec::new();
+        let padding = SAMPLES_SLOT as usize;
+        let input = std::iter::repeat_n(ComplexSample::ZERO, delay)
+            .chain(tx)
+            .chain(std::iter::repeat_n(ComplexSample::ZERO, padding));
+        for (i, sample) in input.enumerate() {
+            demodulator.sample(sample, i as SampleCount);
+            if let Some(slot) = demodulator.take_demodulated_slot() {
+                slots.push([slot.slot, slot.subslot1, slot.subslot2].map(|b| (b.train_type, b.quality)));
+            }
+        }
+        slots.truncate(num_slots as usize);
+        slots
+    }
+
+    /// Burst arrival time for a signal delayed by `delay` samples. The modulator
+    /// transmits half a sample early as it compensates its filter delay the same way.
+    fn expected_delay_symbols(delay: usize) -> RealSample {
+        (delay as RealSample - 0.5) / SPS as RealSample
+    }
+
+    #[test]
+    fn test_delay_of_normal_uplink_burst() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let burst = ul_burst(&bitseq::n, 216, &mut rng);
+        let mut slot_bits = [0; 510];
+        slot_bits[UL_BURST_START_BITS..UL_BURST_START_BITS + burst.len()].copy_from_slice(&burst);
+
+        for delay in [0, 1, 6, 13, 26] {
+            let slots = demodulate_delayed(&slot_bits, delay, 4);
+            assert_eq!(slots.len(), 4);
+            for [(train_type, quality), _, _] in slots {
+                assert_eq!(train_type, TrainingSequence::NormalTrainSeq1);
+                let expected = expected_delay_symbols(delay);
+                assert!((quality.delay_symbols - expected).abs() < 0.05,
+                    "delay {} samples measured as {} symbols", delay, quality.delay_symbols);
+            }
+        }
+    }
+
+    #[test]
+    fn test_delay_of_control_uplink_bursts() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let burst = ul_burst(&bitseq::x, 84, &mut rng);
+        let subslot2_start = (UL_SUBSLOT_BITS + UL_BURST_START_BITS).next_multiple_of(2);
+        let mut slot_bits = [0; 510];
+        slot_bits[UL_BURST_START_BITS..UL_BURST_START_BITS + burst.len()].copy_from_slice(&burst);
+        slot_bits[subslot2_start..subslot2_start + burst.len()].copy_from_slice(&burst);
+
+        for delay in [0, 5, 18] {
+            let slots = demodulate_delayed(&slot_bits, delay, 4);
+            assert_eq!(slots.len(), 4);
+            for [_, subslot1, subslot2] in slots {
+                for (train_type, quality) in [subslot1, subslot2] {
+                    assert_eq!(train_type, TrainingSequence::ExtendedTrainSeq);
+                    let expected = expected_delay_symbols(delay);
+                    assert!((quality.delay_symbols - expected).abs() < 0.05,
+                        "delay {} samples measured as {} symbols", delay, quality.delay_symbols);
+                }
+            }
         }
     }
 }
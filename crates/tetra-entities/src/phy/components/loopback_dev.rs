@@ -0,0 +1,385 @@
+//! RX/TX device that loops the transmitted signal back into a receiver,
+//! for testing the PHY without RF hardware.
+//!
+//! The downlink signal is modulated at the modem sample rate, optionally delayed,
+//! resampled to simulate a sample clock offset, shifted in frequency and impaired by
+//! white Gaussian noise at a given SNR, and demodulated by a downlink monitor
+//! demodulator. Demodulated slots are made available through a monitor channel.
+//! Nothing is returned to the BS as uplink, since the looped-back signal is a downlink signal.
+//!
+//! The device does not pace itself to real time; the stack runs as fast as it can.
+
+use std::collections::VecDeque;
+
+use crossbeam_channel::{Receiver, Sender};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use tetra_config::{CfgLoopback, SharedConfig};
+use tetra_core::{RxQuality, TdmaTime, TrainingSequence};
+use tetra_pdus::phy::traits::rxtx_dev::{RxSlotBits, RxTxDev, RxTxDevError, SampleTap, TxSlotBits};
+
+use super::agc;
+use super::demodulator;
+use super::dsp_types::*;
+use super::modem_common::CHANNEL_FILTER_TAPS;
+use super::modulator;
+
+/// Fixed seed, so noisy loopback runs are reproducible
+const NOISE_SEED: u64 = 0x7e7a;
+
+/// A slot demodulated from the looped-back signal
+#[derive(Debug, Clone)]
+pub struct LoopbackSlot {
+    /// Slot number as counted by the monitor demodulator
+    pub time: TdmaTime,
+    /// Training sequence found in the slot
+    pub train_type: TrainingSequence,
+    /// Demodulated bits of the full slot
+    pub bits: Vec<u8>,
+    /// Reception quality of the full slot burst, measured before AGC
+    pub quality: RxQuality,
+}
+
+pub struct RxTxDevLoopback {
+    modulator: modulator::Modulator,
+    agc: Option<agc::Agc>,
+    demodulator: demodulator::Demodulator,
+
+    /// Modulator timing is anchored to the first slot to transmit
+    anchored: bool,
+    /// Sample counter of the next TX sample
+    tx_count: SampleCount,
+    /// Sample counter of the next RX sample
+    rx_count: SampleCount,
+
+    /// Samples in flight between TX and RX
+    delay_line: VecDeque<ComplexSample>,
+    /// TX samples advanced per RX sample
+    resample_step: f64,
+    /// Position of the next RX sample, in TX samples after the previous TX sample
+    resample_pos: f64,
+    /// Previous TX sample, for interpolation
+    resample_prev: ComplexSample,
+    /// Carrier phase advance per RX sample, in radians
+    phase_step: f64,
+    /// Current carrier phase offset, in radians
+    phase: f64,
+    /// Per-component standard deviation of the added noise, zero for no noise
+    noise_sigma: RealSample,
+    rng: StdRng,
+
+    monitor: Option<Sender<LoopbackSlot>>,
+
+    /// Receives the TX samples and the impaired RX samples of each slot, if set
+    sample_tap: Option<Box<dyn SampleTap>>,
+    tap_tx: Vec<ComplexSample>,
+    tap_rx: Vec<ComplexSample>,
+}
+
+impl RxTxDevLoopback {
+    pub fn new(cfg: &SharedConfig) -> Self {
+        let lb_cfg = cfg.config().phy_io.loopback.clone().unwrap_or_default();
+        let mut dev = Self::from_cfg(&lb_cfg);
+        dev.agc = cfg.config().phy_io.agc.as_ref().map(agc::Agc::new);
+        dev
+    }
+
+    pub fn from_cfg(lb_cfg: &CfgLoopback) -> Self {
+        tracing::info!("Loopback PHY: SNR {:?} dB, delay {} samples, freq offset {} Hz, clock offset {} ppm",
+            lb_cfg.noise_snr_db, lb_cfg.delay_samples, lb_cfg.freq_offset_hz, lb_cfg.clock_ppm);
+        let noise_sigma = match lb_cfg.noise_snr_db {
+            // Noise power is split evenly over I and Q
+            Some(snr_db) => (signal_power() / 10f32.powf(snr_db / 10.0) / 2.0).sqrt(),
+            None => 0.0,
+        };
+        Self {
+            modulator: modulator::Modulator::new(modulator::Mode::Dl),
+            agc: None,
+            demodulator: demodulator::Demodulator::new(demodulator::Mode::DlUnsynchronized),
+            anchored: false,
+            tx_count: 0,
+            rx_count: 0,
+            delay_line: std::iter::repeat_n(ComplexSample::ZERO, lb_cfg.delay_samples).collect(),
+            resample_step: 1.0 / (1.0 + lb_cfg.clock_ppm as f64 * 1e-6),
+            resample_pos: 1.0,
+            resample_prev: ComplexSample::ZERO,
+            phase_step: 2.0 * std::f64::consts::PI * lb_cfg.freq_offset_hz as f64 / demodulator::SAMPLE_RATE,
+            phase: 0.0,
+            noise_sigma,
+            rng: StdRng::seed_from_u64(NOISE_SEED),
+            monitor: None,
+            sample_tap: None,
+            tap_tx: Vec::new(),
+            tap_rx: Vec::new(),
+        }
+    }
+
+    /// Returns a receiver for slots demodulated from the looped-back signal.
+    /// Slots are only collected once a monitor has been requested; a new request
+    /// replaces the previous receiver.
+    pub fn monitor(&mut self) -> Receiver<LoopbackSlot> {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        self.monitor = Some(tx);
+        rx
+    }
+
+    /// Standard complex Gaussian sample, Box-Muller transform
+    fn noise_sample(&mut self) -> ComplexSample {
+        let u1: RealSample = 1.0 - self.rng.random::<RealSample>();
+        let u2: RealSample = self.rng.random();
+        let r = (-2.0 * u1.ln()).sqrt();
+        let phi = 2.0 * std::f32::consts::PI * u2;
+        ComplexSample::new(r * phi.cos(), r * phi.sin())
+    }
+
+    fn receive_sample(&mut self, sample: ComplexSample) {
+        self.delay_line.push_back(sample);
+        let sample = self.delay_line.pop_front().unwrap();
+
+        // Sample the TX signal at the RX sample clock, interpolating linearly
+        let prev = std::mem::replace(&mut self.resample_prev, sample);
+        while self.resample_pos <= 1.0 {
+            let interpolated = prev + (sample - prev) * self.resample_pos as RealSample;
+            self.demodulate_sample(interpolated);
+            self.resample_pos += self.resample_step;
+        }
+        self.resample_pos -= 1.0;
+    }
+
+    fn demodulate_sample(&mut self, mut sample: ComplexSample) {
+        if self.phase_step != 0.0 {
+            sample *= ComplexSample::from_polar(1.0, self.phase as RealSample);
+            self.phase = (self.phase + self.phase_step) % (2.0 * std::f64::consts::PI);
+        }
+        if self.noise_sigma > 0.0 {
+            sample += self.noise_sample() * self.noise_sigma;
+        }
+        if self.sample_tap.is_some() {
+            self.tap_rx.push(sample);
+        }
+
+        if let Some(agc) = &mut self.agc {
+            sample = agc.process(sample);
+        }
+
+        self.demodulator.sample(sample, self.rx_count);
+        self.rx_count += 1;
+
+        let gain_db = self.agc.as_ref().map_or(0.0, agc::Agc::gain_db);
+        if let Some(slot) = self.demodulator.take_demodulated_slot() {
+            if slot.slot.train_type == TrainingSequence::NotFound {
+                return;
+            }
+            tracing::debug!("Loopback monitor got {:?} in slot {}", slot.slot.train_type, slot.time);
+            if let Some(monitor) = &self.monitor {
+                let _ = monitor.send(LoopbackSlot {
+                    time: slot.time,
+                    train_type: slot.slot.train_type,
+                    bits: slot.slot.bits.to_vec(),
+                    quality: RxQuality { rssi_dbm: slot.slot.quality.rssi_dbm - gain_db, ..slot.slot.quality },
+                });
+            }
+        }
+    }
+}
+
+/// Average power of the modulator output. Symbols have unit power and there is one
+/// symbol every 4 samples, shaped by the symmetric channel filter (of which half the taps are stored).
+fn signal_power() -> RealSample {
+    let half_taps_energy: RealSample = CHANNEL_FILTER_TAPS.iter().map(|t| t * t).sum();
+    2.0 * half_taps_energy / demodulator::SPS as RealSample
+}
+
+impl RxTxDev for RxTxDevLoopback {
+    fn rxtx_timeslot(&mut self, tx_slot: &[TxSlotBits]) -> Result<Vec<Option<RxSlotBits<'_>>>, RxTxDevError> {
+        let Some(tx_slot) = tx_slot.first() else {
+            return Ok(Vec::new());
+        };
+
+        if !self.anchored {
+            self.modulator.reanchor(self.tx_count, tx_slot.time);
+            self.anchored = true;
+        }
+
+        // Modulate the whole slot and pass it through the channel
+        while let Ok(sample) = self.modulator.sample(self.tx_count, tx_slot) {
+            self.tx_count += 1;
+            if self.sample_tap.is_some() {
+                self.tap_tx.push(sample);
+            }
+            self.receive_sample(sample);
+        }
+
+        if let Some(tap) = &mut self.sample_tap {
+            tap.tx_samples(&self.tap_tx);
+            tap.rx_samples(&self.tap_rx);
+            self.tap_tx.clear();
+            self.tap_rx.clear();
+        }
+
+        Ok(Vec::new())
+    }
+
+    fn set_sample_tap(&mut self, tap: Box<dyn SampleTap>) -> bool {
+        self.sample_tap = Some(tap);
+        true
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tetra_config::CfgAgc;
+    use tetra_core::{BitBuffer, BurstType, PhyBlockNum, PhyBlockType};
+    use tetra_pdus::umac::pdus::mac_sync::MacSync;
+    use tetra_saps::tmv::TmvUnitdataReq;
+    use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
+    use tetra_saps::tp::TpUnitdataInd;
+
+    use crate::lmac::components::{errorcontrol, scrambler};
+    use crate::phy::components::slotter;
+    use crate::umac::subcomp::bs_sched::MacError;
+
+    use super::*;
+
+    fn with_snr(snr_db: f32) -> CfgLoopback {
+        CfgLoopback { noise_snr_db: Some(snr_db), ..Default::default() }
+    }
+
+    /// Loops back a stream of SYNC bursts through `dev`. Returns the SYNC bursts found by the monitor.
+    fn loop_back_sync_slots(mut dev: RxTxDevLoopback, num_slots: usize) -> Vec<LoopbackSlot> {
+        let sync = MacSync {
+            system_code: 1,
+            colour_code: 42,
+            time: TdmaTime { t: 1, f: 1, m: 1, h: 0 },
+            sharing_mode: 0,
+            ts_reserved_frames: 0,
+            u_plane_dtx: false,
+            frame_18_ext: false,
+        };
+        let mut sb1 = BitBuffer::new(60);
+        sync.to_bitbuf(&mut sb1);
+        sb1.seek(0);
+        let mut blk1 = [0u8; 120];
+        errorcontrol::encode_cp(TmvUnitdataReq {
+            mac_block: sb1,
+            logical_channel: LogicalChannel::Bsch,
+            scrambling_code: scrambler::SCRAMB_INIT,
+        }).to_bitarr(&mut blk1);
+        let burst = slotter::build_sdb(&blk1, &[0u8; 30], &[0u8; 216]);
+
+        let monitor = dev.monitor();
+        let mut time = TdmaTime::default();
+        for _ in 0..num_slots {
+            dev.rxtx_timeslot(&[TxSlotBits { time, slot: Some(&burst) }]).unwrap();
+            time = time.add_timeslots(1);
+        }
+
+        monitor.try_iter()
+            .filter(|slot| slot.train_type == TrainingSequence::SyncTrainSeq)
+            .collect()
+    }
+
+    /// Loops back a stream of SYNC bursts through the given channel. Returns the number of
+    /// SYNC bursts found by the monitor and the SB1 decoding results for them.
+    /// Decodes the SYNC PDU in block 1 of a received synchronization burst
+    fn decode_sync(bits: &[u8]) -> Result<MacSync, MacError> {
+        let prim = TpUnitdataInd {
+            train_type: TrainingSequence::SyncTrainSeq,
+            burst_type: BurstType::SDB,
+            block_type: PhyBlockType::SB1,
+            block_num: PhyBlockNum::Block1,
+            block: BitBuffer::from_bitarr(&bits[94..214]),
+            rx_quality: None,
+        };
+        let mut type1 = errorcontrol::decode_cp_checked(LogicalChannel::Bsch, prim, scrambler::SCRAMB_INIT)?;
+        Ok(MacSync::from_bitbuf(&mut type1).unwrap())
+    }
+
+    fn loop_back_syncs(lb_cfg: CfgLoopback, num_slots: usize) -> (usize, Vec<Result<MacSync, MacError>>) {
+        let slots = loop_back_sync_slots(RxTxDevLoopback::from_cfg(&lb_cfg), num_slots);
+        let decoded = slots.iter().map(|slot| decode_sync(&slot.bits)).collect();
+        (slots.len(), decoded)
+    }
+
+    #[test]
+    fn test_high_snr_recovers_sync() {
+        let (found, decoded) = loop_back_syncs(with_snr(20.0), 40);
+        // The first slot is needed to synchronize the monitor
+        assert!(found >= 38, "only {} SYNC bursts found", found);
+        for sync in decoded {
+            let sync = sync.expect("SYNC not recovered at high SNR");
+            assert_eq!((sync.system_code, sync.colour_code), (1, 42));
+        }
+    }
+
+    #[test]
+    fn test_low_snr_fails_crc() {
+        // Around 0 dB the training sequence is still found, but decoding fails on CRC
+        let (found, decoded) = loop_back_syncs(with_snr(0.0), 40);
+        assert!(found > 0);
+        assert!(decoded.iter().any(|sync| matches!(sync, Err(MacError::CrcFail))));
+
+        // Far below that, nothing is found at all
+        let (found, _) = loop_back_syncs(with_snr(-10.0), 40);
+        assert_eq!(found, 0);
+    }
+
+    #[test]
+    fn test_bit_flipped_burst_fails_crc() {
+        let slots = loop_back_sync_slots(RxTxDevLoopback::from_cfg(&CfgLoopback::default()), 4);
+        let mut bits = slots[0].bits.clone();
+        assert!(decode_sync(&bits).is_ok());
+
+        // More errors than the convolutional code corrects
+        for bit in &mut bits[120..150] {
+            *bit ^= 1;
+        }
+        assert_eq!(decode_sync(&bits).err(), Some(MacError::CrcFail));
+    }
+
+    #[test]
+    fn test_sync_locks_with_small_offsets() {
+        for freq_offset_hz in [-500.0, -100.0, 0.0, 100.0, 500.0] {
+            for clock_ppm in [-20.0, 0.0, 20.0] {
+                let lb_cfg = CfgLoopback { freq_offset_hz, clock_ppm, ..with_snr(20.0) };
+                let (found, decoded) = loop_back_syncs(lb_cfg, 40);
+                assert!(found >= 38, "only {} SYNC bursts found at {} Hz, {} ppm", found, freq_offset_hz, clock_ppm);
+                assert!(decoded.iter().all(|sync| sync.as_ref().is_ok_and(|s| s.colour_code == 42)),
+                    "SYNC not recovered at {} Hz, {} ppm", freq_offset_hz, clock_ppm);
+            }
+        }
+
+        // Far outside the tolerance of the receiver, nothing locks
+        let (found, _) = loop_back_syncs(CfgLoopback { freq_offset_hz: 3000.0, ..with_snr(20.0) }, 40);
+        assert_eq!(found, 0);
+    }
+
+    #[test]
+    fn test_rssi_from_sample_power() {
+        let expected_dbfs = 10.0 * signal_power().log10();
+
+        let slots = loop_back_sync_slots(RxTxDevLoopback::from_cfg(&CfgLoopback::default()), 20);
+        assert!(slots.len() >= 18);
+        for slot in &slots {
+            assert!((slot.quality.rssi_dbm - expected_dbfs).abs() < 0.5, "rssi {} expected {}", slot.quality.rssi_dbm, expected_dbfs);
+            assert_eq!(slot.quality.ber_estimate, 0.0);
+        }
+
+        // Noise at 10 dB SNR adds 10 % to the measured power
+        let slots = loop_back_sync_slots(RxTxDevLoopback::from_cfg(&with_snr(10.0)), 20);
+        let mean_rssi = slots.iter().map(|slot| slot.quality.rssi_dbm).sum::<f32>() / slots.len() as f32;
+        assert!((mean_rssi - expected_dbfs - 10.0 * 1.1f32.log10()).abs() < 0.5, "rssi {}", mean_rssi);
+
+        // Signal strength is measured before the AGC scales the signal,
+        // once the AGC has settled during the first burst
+        let mut dev = RxTxDevLoopback::from_cfg(&CfgLoopback::default());
+        dev.agc = Some(agc::Agc::new(&CfgAgc { target_rms: 1.0, ..Default::default() }));
+        let slots = loop_back_sync_slots(dev, 20);
+        assert!(slots.len() >= 18);
+        for slot in &slots[1..] {
+            assert!((slot.quality.rssi_dbm - expected_dbfs).abs() < 0.5, "rssi {} with AGC", slot.quality.rssi_dbm);
+        }
+    }
+}
@@ -2,6 +2,7 @@
 pub mod burst_consts;
 pub mod train_consts;
 
+pub mod agc;
 pub mod demodulator;
 pub mod dsp_types;
 pub mod fcfb;
@@ -12,10 +13,15 @@ pub mod modulator;
 pub mod soapy_defaults;
 pub mod soapy_time;
 pub mod soapyio;
+pub mod sync_detect;
+pub mod xrun_monitor;
 
 pub mod soapy_dev;
+pub mod loopback_dev;
+pub mod recording_dev;
 // pub mod _rxtxdev_buffer;
 
 pub mod slotter;
 
-pub mod phy_io_file;
\ No newline at end of file
+pub mod phy_io_file;
+pub mod sample_format;
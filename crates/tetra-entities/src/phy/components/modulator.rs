@@ -13,7 +13,7 @@ use crate::phy::components::modem_common::*;
 const SPS: SampleCount = 4;
 
 /// Samples per slot
-const SAMPLES_SLOT: SampleCount = SPS * 255;
+pub const SAMPLES_SLOT: SampleCount = SPS * 255;
 
 /// Output sample rate
 pub const SAMPLE_RATE: f64 = 18000.0 * SPS as f64;
@@ -50,6 +50,14 @@ impl Modulator {
         }
     }
 
+    /// Re-anchor TDMA timing so that slot `time` begins at `sample_counter`,
+    /// e.g. after the TX stream has been restarted.
+    /// The slot is then output in full, delayed by the pulse shaping filter.
+    pub fn reanchor(&mut self, sample_counter: SampleCount, time: TdmaTime) {
+        let sample_counter = sample_counter + CHANNEL_FILTER_TAPS.len() as SampleCount;
+        self.reference_time = sample_counter - TdmaTime::to_int(time) as SampleCount * SAMPLES_SLOT;
+    }
+
     /// Produce one output sample.
     pub fn sample(&mut self, sample_counter: SampleCount, tx_slot: &TxSlotBits) -> Result<ComplexSample, Error> {
         // Compensate for delay of pulse shaping filter in sample count
@@ -90,7 +98,7 @@ impl Modulator {
 
 
 
-struct DqpskMapper {
+pub(crate) struct DqpskMapper {
     pub phase: i8,
 }
 
@@ -128,3 +136,25 @@ impl DqpskMapper {
         CONSTELLATION[self.phase as usize]
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reanchor_outputs_whole_slot() {
+        let bits = [1u8; 510];
+        let time = TdmaTime { h: 0, m: 1, f: 2, t: 3 };
+        let slot = TxSlotBits { time, slot: Some(&bits) };
+        let mut modulator = Modulator::new(Mode::Dl);
+        modulator.reanchor(1000, time);
+
+        // No symbol is skipped while the filter fills up
+        let mut count = 0;
+        while modulator.sample(1000 + count, &slot).is_ok() {
+            count += 1;
+        }
+        assert_eq!(count, SAMPLES_SLOT);
+    }
+}
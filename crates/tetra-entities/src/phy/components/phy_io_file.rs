@@ -2,12 +2,19 @@ use std::fs::{File, OpenOptions};
 use std::io::{self, Read, Write, Seek, SeekFrom};
 use std::path::Path;
 use std::thread;
+use std::time::{Duration, Instant};
 use crossbeam_channel::{unbounded, Sender};
+use tetra_config::SampleFormat;
+
+use super::dsp_types::ComplexSample;
+use super::sample_format;
 
 #[derive(Debug, Clone)]
 pub enum FileWriteMsg {
     WriteBlock(Vec<u8>),
     WriteHeaderAndBlock(u8, u64, Vec<u8>),
+    /// Flushes the file, then acknowledges over the given channel
+    Flush(Sender<()>),
     Shutdown,
 }
 
@@ -30,10 +37,33 @@ impl From<io::Error> for PhyIoError {
     }
 }
 
+/// Interval at which a drained FIFO is polled for more data
+const FIFO_POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+/// Time a FIFO whose writer hung up is waited on for a new writer, before the stream ends
+pub const FIFO_HANGUP_TIMEOUT: Duration = Duration::from_secs(10);
+
 pub struct PhyIoFile {
     file: File,
     mode: PhyIoFileMode,
     file_size: u64,
+    /// The file is a named pipe, fed in real time by another process. Reads block for
+    /// more data instead of treating EOF as the end of the stream.
+    is_fifo: bool,
+    /// Time the writer of the FIFO hung up, if no writer is connected
+    hung_up_at: Option<Instant>,
+    hangup_timeout: Duration,
+}
+
+#[cfg(unix)]
+fn is_fifo(file: &File) -> io::Result<bool> {
+    use std::os::unix::fs::FileTypeExt;
+    Ok(file.metadata()?.file_type().is_fifo())
+}
+
+#[cfg(not(unix))]
+fn is_fifo(_file: &File) -> io::Result<bool> {
+    Ok(false)
 }
 
 impl PhyIoFile {
@@ -42,6 +72,8 @@ impl PhyIoFile {
     /// # Arguments
     /// * `filename` - Path to the file
     /// * `mode` - Write, Read, or ReadRepeat mode
+    ///
+    /// Opening a FIFO for reading blocks until a writer opened it.
     pub fn new<P: AsRef<Path>>(filename: P, mode: PhyIoFileMode) -> io::Result<Self> {
         let file = match mode {
             PhyIoFileMode::Read | PhyIoFileMode::ReadRepeat => {
@@ -57,8 +89,13 @@ impl PhyIoFile {
                     .open(&filename)?
             }
         };
+        Self::from_file(file, mode)
+    }
 
-        let file_size = if mode == PhyIoFileMode::Read {
+    /// Wraps an opened file, which may be a FIFO or pipe when reading
+    pub fn from_file(file: File, mode: PhyIoFileMode) -> io::Result<Self> {
+        let is_fifo = mode != PhyIoFileMode::Write && is_fifo(&file)?;
+        let file_size = if mode == PhyIoFileMode::Read && !is_fifo {
             file.metadata()?.len()
         } else {
             0
@@ -68,9 +105,17 @@ impl PhyIoFile {
             file,
             mode,
             file_size,
+            is_fifo,
+            hung_up_at: None,
+            hangup_timeout: FIFO_HANGUP_TIMEOUT,
         })
     }
 
+    /// Sets the time a FIFO whose writer hung up is waited on for a new writer
+    pub fn set_hangup_timeout(&mut self, timeout: Duration) {
+        self.hangup_timeout = timeout;
+    }
+
     /// Read a block of data from the file
     /// 
     /// # Arguments
@@ -78,7 +123,9 @@ impl PhyIoFile {
     /// 
     /// # Returns
     /// * `Ok(())` - Block successfully read
-    /// * `Err(PhyIoError::Eof)` - EOF reached and eof_behavior is Stop
+    /// * `Err(PhyIoError::Eof)` - EOF reached and eof_behavior is Stop. A FIFO is waited
+    ///   on until the block is complete; it only ends once its writer hung up and no new
+    ///   writer opened it within the hangup timeout
     /// * `Err(PhyIoError::Io)` - I/O error occurred
     pub fn read_block(&mut self, buffer: &mut [u8]) -> Result<(), PhyIoError> {
         
@@ -87,6 +134,19 @@ impl PhyIoFile {
 
         while bytes_read < block_size {
             match self.file.read(&mut buffer[bytes_read..]) {
+                Ok(0) if self.is_fifo => {
+                    // A read only returns nothing once all writers closed the FIFO. As we keep
+                    // it open for reading, a new writer may still open it and continue
+                    let hung_up_at = *self.hung_up_at.get_or_insert_with(|| {
+                        tracing::warn!("FIFO writer hung up, waiting up to {:?} for a new writer", self.hangup_timeout);
+                        Instant::now()
+                    });
+                    if hung_up_at.elapsed() >= self.hangup_timeout {
+                        tracing::error!("No new FIFO writer within {:?}, ending the stream", self.hangup_timeout);
+                        return Err(PhyIoError::Eof);
+                    }
+                    thread::sleep(FIFO_POLL_INTERVAL);
+                }
                 Ok(0) => {
                     // EOF reached
                     match self.mode {
@@ -111,6 +171,9 @@ impl PhyIoFile {
                     }
                 }
                 Ok(n) => {
+                    if self.hung_up_at.take().is_some() {
+                        tracing::info!("FIFO writer connected again");
+                    }
                     bytes_read += n;
                 }
                 Err(e) => {
@@ -122,6 +185,24 @@ impl PhyIoFile {
         Ok(())
     }
 
+    /// Read a block of IQ samples stored in `format`, scaled to the internal representation
+    ///
+    /// # Arguments
+    /// * `format` - Storage format of the samples in the file
+    /// * `samples` - Buffer to read samples into (size determines block size)
+    ///
+    /// # Returns
+    /// The same as `read_block`
+    pub fn read_samples(&mut self, format: SampleFormat, samples: &mut [ComplexSample]) -> Result<(), PhyIoError> {
+        let mut block = vec![0u8; samples.len() * sample_format::bytes_per_sample(format)];
+        self.read_block(&mut block)?;
+
+        let mut decoded = Vec::with_capacity(samples.len());
+        sample_format::decode(format, &block, &mut decoded);
+        samples.copy_from_slice(&decoded);
+        Ok(())
+    }
+
     pub fn write_header_and_block(&mut self, field_type: u8, timestamp: u64, data: &[u8]) -> Result<(), PhyIoError> {
         if self.mode != PhyIoFileMode::Write {
             return Err(PhyIoError::Io("File not opened for writing".to_string()));
@@ -161,7 +242,12 @@ impl PhyIoFile {
         self.file.stream_position()
     }
 
-    /// Get the file size (only meaningful for read mode)
+    /// Whether the file is a FIFO, streaming data in real time
+    pub fn is_fifo(&self) -> bool {
+        self.is_fifo
+    }
+
+    /// Get the file size (only meaningful for read mode, zero for a FIFO)
     pub fn file_size(&self) -> u64 {
         self.file_size
     }
@@ -173,30 +259,32 @@ impl PhyIoFile {
 
     /// Create an async writer that spawns a background thread for file writes
     /// Returns a Sender that can be used to queue write operations
+    ///
+    /// The file is opened before the thread is spawned, so failing to open it is returned here.
     pub fn create_async_writer<P: AsRef<Path>>(filename: P, thread_name: String) -> io::Result<Sender<FileWriteMsg>> {
-        let file_path = filename.as_ref().to_path_buf();
+        let mut file = PhyIoFile::new(filename, PhyIoFileMode::Write)?;
         let (sender, receiver) = unbounded::<FileWriteMsg>();
-        // let thread_name = format!("phy-io-writer-{}", file_path.display());
-                
+
         thread::Builder::new()
             .name(thread_name)
             .spawn(move || {
-                if let Ok(mut file) = PhyIoFile::new(&file_path, PhyIoFileMode::Write) {
-                    while let Ok(msg) = receiver.recv() {
-                        match msg {
-                            FileWriteMsg::WriteBlock(data) => {
-                                let _ = file.write_block(&data);
-                            }
-                            FileWriteMsg::WriteHeaderAndBlock(field_type, timestamp, data) => {
-                                let _ = file.write_header_and_block(field_type, timestamp, &data);
-                            }
-                            FileWriteMsg::Shutdown => break,
+                while let Ok(msg) = receiver.recv() {
+                    match msg {
+                        FileWriteMsg::WriteBlock(data) => {
+                            let _ = file.write_block(&data);
                         }
+                        FileWriteMsg::WriteHeaderAndBlock(field_type, timestamp, data) => {
+                            let _ = file.write_header_and_block(field_type, timestamp, &data);
+                        }
+                        FileWriteMsg::Flush(ack) => {
+                            let _ = file.flush();
+                            let _ = ack.send(());
+                        }
+                        FileWriteMsg::Shutdown => break,
                     }
                 }
-            })
-            .expect("Failed to spawn phy-io-writer thread");
-        
+            })?;
+
         Ok(sender)
     }
 }
@@ -292,6 +380,99 @@ mod tests {
         let _ = std::fs::remove_file(&path);
     }
 
+    #[test]
+    #[cfg(unix)]
+    fn test_fifo_streaming() {
+        let (pipe_rx, mut pipe_tx) = std::io::pipe().unwrap();
+        let file = File::from(std::os::fd::OwnedFd::from(pipe_rx));
+        let mut reader = PhyIoFile::from_file(file, PhyIoFileMode::Read).unwrap();
+        assert!(reader.is_fifo());
+        assert_eq!(reader.file_size(), 0);
+
+        // The writer streams in chunks not aligned to blocks, pausing in between
+        let writer = thread::spawn(move || {
+            for chunk in (0..24u8).collect::<Vec<_>>().chunks(5) {
+                pipe_tx.write_all(chunk).unwrap();
+                thread::sleep(Duration::from_millis(5));
+            }
+        });
+
+        let mut buffer = [0u8; 8];
+        for block in 0..3u8 {
+            reader.read_block(&mut buffer).unwrap();
+            assert_eq!(buffer, core::array::from_fn(|i| block * 8 + i as u8));
+        }
+        writer.join().unwrap();
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_fifo_writer_hangup() {
+        let (pipe_rx, mut pipe_tx) = std::io::pipe().unwrap();
+        let file = File::from(std::os::fd::OwnedFd::from(pipe_rx));
+        let mut reader = PhyIoFile::from_file(file, PhyIoFileMode::Read).unwrap();
+        reader.set_hangup_timeout(Duration::from_millis(50));
+
+        pipe_tx.write_all(&[7u8; 12]).unwrap();
+        drop(pipe_tx);
+
+        // The stream ends once no writer turned up, dropping the partial block
+        let mut buffer = [0u8; 8];
+        reader.read_block(&mut buffer).unwrap();
+        assert_eq!(buffer, [7u8; 8]);
+        let start = Instant::now();
+        assert!(matches!(reader.read_block(&mut buffer), Err(PhyIoError::Eof)));
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    /// Samples spread over the full scale, with I and Q of either sign
+    fn reference_samples() -> Vec<ComplexSample> {
+        (0..64)
+            .map(|n| ComplexSample::from_polar(0.9, n as f32 * 0.3))
+            .collect()
+    }
+
+    /// Reads `bytes` as samples in `format`, checking they match the reference within `tolerance`
+    fn assert_reads_reference_samples(format: SampleFormat, bytes: &[u8], tolerance: f32) {
+        let (_filename, path) = create_temp_file(bytes);
+        let mut reader = PhyIoFile::new(&path, PhyIoFileMode::Read).unwrap();
+        let reference = reference_samples();
+        let mut samples = vec![ComplexSample::default(); reference.len()];
+        reader.read_samples(format, &mut samples).unwrap();
+        assert!(matches!(reader.read_samples(format, &mut samples[..1]), Err(PhyIoError::Eof)));
+        let _ = std::fs::remove_file(&path);
+
+        for (s, r) in samples.iter().zip(&reference) {
+            assert!((s - r).norm() <= tolerance, "{:?}: {} read as {}", format, r, s);
+        }
+    }
+
+    #[test]
+    fn test_read_samples_cf32() {
+        let bytes: Vec<u8> = reference_samples().iter()
+            .flat_map(|s| [s.re.to_le_bytes(), s.im.to_le_bytes()].concat())
+            .collect();
+        assert_reads_reference_samples(SampleFormat::Cf32, &bytes, 0.0);
+    }
+
+    #[test]
+    fn test_read_samples_cs16() {
+        let bytes: Vec<u8> = reference_samples().iter()
+            .flat_map(|s| [(s.re * 32768.0).round() as i16, (s.im * 32768.0).round() as i16])
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        assert_reads_reference_samples(SampleFormat::Cs16, &bytes, 1.0 / 32768.0);
+    }
+
+    #[test]
+    fn test_read_samples_cs8() {
+        let bytes: Vec<u8> = reference_samples().iter()
+            .flat_map(|s| [(s.re * 128.0).round() as i8, (s.im * 128.0).round() as i8])
+            .map(|v| v as u8)
+            .collect();
+        assert_reads_reference_samples(SampleFormat::Cs8, &bytes, 1.0 / 128.0);
+    }
+
     #[test]
     fn test_partial_block_loop() {
         let (_filename, path) = create_temp_file(&[1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
@@ -0,0 +1,217 @@
+//! RX/TX device decorator recording the IQ samples passing through any other device,
+//! for post-hoc analysis.
+//!
+//! The received samples, and optionally the samples handed over for transmission, are
+//! written to files by background writers, so recording does not hold up the stack.
+//! Samples are recorded at the sample rate of the wrapped device, in the configured
+//! sample format.
+
+use std::io;
+
+use crossbeam_channel::Sender;
+
+use tetra_config::{CfgRecording, SampleFormat};
+use tetra_pdus::phy::traits::rxtx_dev::{RxSlotBits, RxTxDev, RxTxDevError, SampleTap, TxSlotBits};
+
+use crate::phy::components::dsp_types::ComplexSample;
+
+use super::phy_io_file::{FileWriteMsg, PhyIoFile};
+use super::sample_format;
+
+/// Sample tap queueing the samples to the writers of the recording files
+struct SampleRecorder {
+    format: SampleFormat,
+    rx_writer: Sender<FileWriteMsg>,
+    tx_writer: Option<Sender<FileWriteMsg>>,
+}
+
+impl SampleRecorder {
+    fn write(&self, writer: &Sender<FileWriteMsg>, samples: &[ComplexSample]) {
+        if samples.is_empty() {
+            return;
+        }
+        let mut block = Vec::new();
+        sample_format::encode(self.format, samples, &mut block);
+        let _ = writer.try_send(FileWriteMsg::WriteBlock(block));
+    }
+}
+
+impl SampleTap for SampleRecorder {
+    fn rx_samples(&mut self, samples: &[ComplexSample]) {
+        self.write(&self.rx_writer, samples);
+    }
+
+    fn tx_samples(&mut self, samples: &[ComplexSample]) {
+        if let Some(writer) = &self.tx_writer {
+            self.write(writer, samples);
+        }
+    }
+}
+
+pub struct RxTxDevRecording<D: RxTxDev> {
+    inner: D,
+    writers: Vec<Sender<FileWriteMsg>>,
+}
+
+impl<D: RxTxDev> RxTxDevRecording<D> {
+    /// Wraps `inner`, recording to the files configured in `cfg`, with samples stored in `format`.
+    /// Fails if a file can't be opened, or `inner` has no IQ samples to record.
+    pub fn new(mut inner: D, cfg: &CfgRecording, format: SampleFormat) -> io::Result<Self> {
+        let rx_writer = PhyIoFile::create_async_writer(&cfg.file, "phy-recorder-rx".to_string())?;
+        let tx_writer = match &cfg.tx_file {
+            Some(tx_file) => Some(PhyIoFile::create_async_writer(tx_file, "phy-recorder-tx".to_string())?),
+            None => None,
+        };
+        let writers = std::iter::once(rx_writer.clone()).chain(tx_writer.clone()).collect();
+
+        if !inner.set_sample_tap(Box::new(SampleRecorder { format, rx_writer, tx_writer })) {
+            return Err(io::Error::new(io::ErrorKind::Unsupported, "device provides no IQ samples to record"));
+        }
+        match &cfg.tx_file {
+            Some(tx_file) => tracing::info!("Recording {:?} RX samples to {}, TX samples to {}", format, cfg.file, tx_file),
+            None => tracing::info!("Recording {:?} RX samples to {}", format, cfg.file),
+        }
+        Ok(Self { inner, writers })
+    }
+
+    /// Blocks until everything recorded so far is written to the files
+    pub fn flush(&self) {
+        for writer in &self.writers {
+            let (ack_tx, ack_rx) = crossbeam_channel::bounded(1);
+            if writer.send(FileWriteMsg::Flush(ack_tx)).is_ok() {
+                let _ = ack_rx.recv();
+            }
+        }
+    }
+}
+
+impl<D: RxTxDev> Drop for RxTxDevRecording<D> {
+    fn drop(&mut self) {
+        for writer in &self.writers {
+            let _ = writer.send(FileWriteMsg::Shutdown);
+        }
+    }
+}
+
+impl<D: RxTxDev> RxTxDev for RxTxDevRecording<D> {
+    fn rxtx_timeslot(&mut self, tx_slot: &[TxSlotBits]) -> Result<Vec<Option<RxSlotBits<'_>>>, RxTxDevError> {
+        self.inner.rxtx_timeslot(tx_slot)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use tetra_config::CfgLoopback;
+    use tetra_core::TdmaTime;
+
+    use crate::phy::components::loopback_dev::RxTxDevLoopback;
+    use crate::phy::components::modulator::SAMPLES_SLOT;
+    use crate::phy::components::phy_io_file::PhyIoFileMode;
+    use crate::phy::components::train_consts::TIMESLOT_TYPE4_BITS;
+
+    use super::*;
+
+    /// Device passing bits only, like a device replaying demodulated bursts
+    struct BitsOnly;
+
+    impl RxTxDev for BitsOnly {
+        fn rxtx_timeslot(&mut self, _tx_slot: &[TxSlotBits]) -> Result<Vec<Option<RxSlotBits<'_>>>, RxTxDevError> {
+            Ok(Vec::new())
+        }
+    }
+
+    fn temp_path(name: &str) -> String {
+        env::temp_dir()
+            .join(format!("recording_dev_test_{}_{}.bin", name, std::process::id()))
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    /// Runs `num_slots` slots through a recorded loopback device, returning the recorded RX and TX files
+    fn record_loopback(name: &str, format: SampleFormat, record_tx: bool, num_slots: usize) -> (String, Option<String>) {
+        let cfg = CfgRecording {
+            file: temp_path(&format!("{}_rx", name)),
+            tx_file: record_tx.then(|| temp_path(&format!("{}_tx", name))),
+        };
+        let mut dev = RxTxDevRecording::new(RxTxDevLoopback::from_cfg(&CfgLoopback::default()), &cfg, format).unwrap();
+
+        let burst = [1u8; TIMESLOT_TYPE4_BITS];
+        let mut time = TdmaTime::default();
+        for _ in 0..num_slots {
+            dev.rxtx_timeslot(&[TxSlotBits { time, slot: Some(&burst) }]).unwrap();
+            time = time.add_timeslots(1);
+        }
+        dev.flush();
+        (cfg.file, cfg.tx_file)
+    }
+
+    fn take_file(path: &String) -> Vec<u8> {
+        let recorded = std::fs::read(path).unwrap();
+        let _ = std::fs::remove_file(path);
+        recorded
+    }
+
+    /// Reads back all samples of a recording in `format`
+    fn take_samples(path: &String, format: SampleFormat) -> Vec<ComplexSample> {
+        let mut reader = PhyIoFile::new(path, PhyIoFileMode::Read).unwrap();
+        let mut samples = vec![ComplexSample::default(); SAMPLES_SLOT as usize];
+        let mut recorded = Vec::new();
+        while reader.read_samples(format, &mut samples).is_ok() {
+            recorded.extend_from_slice(&samples);
+        }
+        let _ = std::fs::remove_file(path);
+        recorded
+    }
+
+    #[test]
+    fn test_records_rx_samples() {
+        let (rx, tx) = record_loopback("rx", SampleFormat::Cf32, false, 10);
+        let rx = take_file(&rx);
+        assert_eq!(rx.len(), 10 * SAMPLES_SLOT as usize * 8);
+        assert!(tx.is_none());
+        assert!(rx.iter().any(|&b| b != 0));
+    }
+
+    #[test]
+    fn test_records_tx_samples() {
+        let (rx, tx) = record_loopback("tx", SampleFormat::Cf32, true, 10);
+        let (rx, tx) = (take_file(&rx), take_file(&tx.unwrap()));
+        assert_eq!(tx.len(), 10 * SAMPLES_SLOT as usize * 8);
+        assert_eq!(rx.len(), tx.len());
+        assert!(tx.iter().any(|&b| b != 0));
+    }
+
+    #[test]
+    fn test_recorded_formats_decode_to_equivalent_samples() {
+        let (reference, _) = record_loopback("format_ref", SampleFormat::Cf32, false, 4);
+        let reference = take_samples(&reference, SampleFormat::Cf32);
+        assert_eq!(reference.len(), 4 * SAMPLES_SLOT as usize);
+
+        for (format, bytes_per_sample, step) in [(SampleFormat::Cs16, 4, 1.0 / 32768.0), (SampleFormat::Cs8, 2, 1.0 / 128.0)] {
+            let (rx, _) = record_loopback(&format!("format_{:?}", format), format, false, 4);
+            assert_eq!(std::fs::metadata(&rx).unwrap().len(), (reference.len() * bytes_per_sample) as u64);
+            let samples = take_samples(&rx, format);
+            assert_eq!(samples.len(), reference.len());
+            for (s, r) in samples.iter().zip(&reference) {
+                assert!((s - r).norm() <= step, "{:?}: {} read back as {}", format, r, s);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bits_only_device_is_rejected() {
+        let cfg = CfgRecording { file: temp_path("bits_only"), tx_file: None };
+        let err = RxTxDevRecording::new(BitsOnly, &cfg, SampleFormat::Cf32).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+        let _ = std::fs::remove_file(&cfg.file);
+    }
+
+    #[test]
+    fn test_unopenable_file_is_reported() {
+        let cfg = CfgRecording { file: "/nonexistent-dir/recording.bin".to_string(), tx_file: None };
+        assert!(RxTxDevRecording::new(RxTxDevLoopback::from_cfg(&CfgLoopback::default()), &cfg, SampleFormat::Cf32).is_err());
+    }
+}
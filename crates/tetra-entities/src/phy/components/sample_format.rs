@@ -0,0 +1,87 @@
+//! Conversion of IQ samples from and to their storage formats, as written by SDR recorders
+//! and GNU Radio file sinks. Samples are interleaved I and Q, little-endian. Integer formats
+//! are scaled so that full scale maps to the -1.0 to 1.0 range of the internal representation.
+
+use tetra_config::SampleFormat;
+
+use super::dsp_types::{ComplexSample, RealSample};
+
+/// Size of a complex sample in bytes
+pub fn bytes_per_sample(format: SampleFormat) -> usize {
+    match format {
+        SampleFormat::Cf32 => 8,
+        SampleFormat::Cs16 => 4,
+        SampleFormat::Cs8 => 2,
+    }
+}
+
+/// Integer value corresponding to 1.0, unused for Cf32
+fn full_scale(format: SampleFormat) -> RealSample {
+    match format {
+        SampleFormat::Cf32 => 1.0,
+        SampleFormat::Cs16 => 32768.0,
+        SampleFormat::Cs8 => 128.0,
+    }
+}
+
+/// Decodes the complete samples in `bytes`, appending them to `out`.
+/// Returns the number of samples decoded; trailing bytes of a partial sample are ignored.
+pub fn decode(format: SampleFormat, bytes: &[u8], out: &mut Vec<ComplexSample>) -> usize {
+    let chunks = bytes.chunks_exact(bytes_per_sample(format));
+    let num_samples = chunks.len();
+    let scale = 1.0 / full_scale(format);
+    out.extend(chunks.map(|c| {
+        let (i, q) = match format {
+            SampleFormat::Cf32 => (
+                f32::from_le_bytes([c[0], c[1], c[2], c[3]]),
+                f32::from_le_bytes([c[4], c[5], c[6], c[7]]),
+            ),
+            SampleFormat::Cs16 => (
+                i16::from_le_bytes([c[0], c[1]]) as RealSample * scale,
+                i16::from_le_bytes([c[2], c[3]]) as RealSample * scale,
+            ),
+            SampleFormat::Cs8 => (c[0] as i8 as RealSample * scale, c[1] as i8 as RealSample * scale),
+        };
+        ComplexSample::new(i, q)
+    }));
+    num_samples
+}
+
+/// Encodes `samples`, appending them to `out`. Integer formats saturate at full scale.
+pub fn encode(format: SampleFormat, samples: &[ComplexSample], out: &mut Vec<u8>) {
+    out.reserve(samples.len() * bytes_per_sample(format));
+    let scale = full_scale(format);
+    for s in samples {
+        for v in [s.re, s.im] {
+            match format {
+                SampleFormat::Cf32 => out.extend_from_slice(&v.to_le_bytes()),
+                // Float to integer casts saturate
+                SampleFormat::Cs16 => out.extend_from_slice(&((v * scale).round() as i16).to_le_bytes()),
+                SampleFormat::Cs8 => out.push((v * scale).round() as i8 as u8),
+            }
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_integer_scaling() {
+        let mut decoded = Vec::new();
+        decode(SampleFormat::Cs16, &[0x00, 0x80, 0x00, 0x40], &mut decoded);
+        decode(SampleFormat::Cs8, &[0x80, 0x40], &mut decoded);
+        assert_eq!(decoded, vec![ComplexSample::new(-1.0, 0.5); 2]);
+
+        // Out of range samples saturate instead of wrapping
+        let mut bytes = Vec::new();
+        encode(SampleFormat::Cs16, &[ComplexSample::new(2.0, -2.0)], &mut bytes);
+        encode(SampleFormat::Cs8, &[ComplexSample::new(2.0, -2.0)], &mut bytes);
+        assert_eq!(bytes, vec![0xFF, 0x7F, 0x00, 0x80, 0x7F, 0x80]);
+
+        // A partial sample at the end is ignored
+        assert_eq!(decode(SampleFormat::Cs16, &[0x00, 0x40, 0x00], &mut decoded), 0);
+    }
+}
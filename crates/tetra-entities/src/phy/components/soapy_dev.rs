@@ -2,16 +2,21 @@
 //! between SDR device and modulator/demodulator code.
 
 use rustfft;
+use tetra_config::CfgAgc;
 use tetra_config::SharedConfig;
 use tetra_config::StackMode;
+use tetra_core::TdmaTime;
+use tetra_core::freqs::FreqInfo;
 
 use tetra_pdus::phy::traits::rxtx_dev::RxSlotBits;
 use tetra_pdus::phy::traits::rxtx_dev::RxTxDev;
 use tetra_pdus::phy::traits::rxtx_dev::RxTxDevError;
+use tetra_pdus::phy::traits::rxtx_dev::SampleTap;
 use tetra_pdus::phy::traits::rxtx_dev::TxSlotBits;
 
 use crate::phy::components::soapy_dev;
 
+use super::agc;
 use super::demodulator;
 use super::modulator;
 use super::fcfb;
@@ -36,12 +41,17 @@ pub struct PhyConfig<'a> {
     pub bs_dl_frequencies: &'a [f64],
     /// Uplink carrier frequencies for a BS.
     pub bs_ul_frequencies: &'a [f64],
+    /// Automatic gain control for each received channel
+    pub agc: Option<CfgAgc>,
 }
 
 pub struct RxTxDevSoapySdr {
+    config: SharedConfig,
     sdr: soapyio::SoapyIo,
     rx_dsp: Option<RxDsp>,
     tx_dsp: Option<TxDsp>,
+    /// Receives the samples exchanged with the SDR, if set
+    sample_tap: Option<Box<dyn SampleTap>>,
 }
 
 
@@ -107,9 +117,16 @@ impl RxTxDevSoapySdr {
             ul_corrected / 1e6
         );
 
+        // The carrier offset is part of the configured frequencies, which are checked against the cell settings
+        let cell = &config_guard.cell;
+        tracing::info!("Carrier {} in band {}, offset {} Hz (SYSINFO frequency offset {})",
+            cell.main_carrier, cell.freq_band, cell.freq_offset_hz,
+            FreqInfo::freq_offset_hz_to_id(cell.freq_offset_hz).unwrap_or_default());
+
         let phy_config = soapy_dev::PhyConfig {
             bs_dl_frequencies: &[dl_corrected],
             bs_ul_frequencies: &[ul_corrected],
+            agc: config_guard.phy_io.agc.clone(),
             ..Default::default()
         };
 
@@ -127,16 +144,36 @@ impl RxTxDevSoapySdr {
                 Some(TxDsp::new(&mut fft_planner, &mut sdr, &phy_config))
             } else { None },
 
+            config: cfg.clone(),
             sdr,
+            sample_tap: None,
         }        
     }
 
+    /// Publish overrun/underrun counters to the shared stack state, if changed
+    fn publish_xrun_counts(&self) {
+        let xrun = self.sdr.xrun();
+        let counts = (xrun.rx_overruns(), xrun.tx_underruns(), xrun.tx_reinits());
+        let published = {
+            let state = self.config.state_read();
+            (state.sdr_rx_overruns, state.sdr_tx_underruns, state.sdr_tx_reinits)
+        };
+        if counts != published {
+            let mut state = self.config.state_write();
+            (state.sdr_rx_overruns, state.sdr_tx_underruns, state.sdr_tx_reinits) = counts;
+        }
+    }
+
     /// Process a block of received signal.
     /// Return true if processing can be continued,
     /// false if a slot has been demodulated and rxtx_timeslot should return.
     fn process_rx_block(&mut self) -> Result<bool, RxTxDevError> {
         if let Some(rx_dsp) = &mut self.rx_dsp {
-            rx_dsp.process_block(&mut self.sdr)
+            let result = rx_dsp.process_block(&mut self.sdr);
+            if let (Ok(_), Some(tap)) = (&result, &mut self.sample_tap) {
+                tap.rx_samples(rx_dsp.new_samples());
+            }
+            result
         } else {
             Ok(false)
         }
@@ -152,6 +189,7 @@ impl RxTxDevSoapySdr {
                 tx_dsp.process_block(&mut self.sdr,
                     self.rx_dsp.as_ref().map(|rx_dsp| { rx_dsp.rx_block_count }),
                     tx_slot,
+                    self.sample_tap.as_mut(),
                 )
             } else {
                 Ok(false)
@@ -176,12 +214,19 @@ impl RxTxDev for RxTxDevSoapySdr {
             while self.process_tx_block(tx_slot)? { }
         }
 
+        self.publish_xrun_counts();
+
         if let Some(rx_dsp) = &mut self.rx_dsp {
             Ok(rx_dsp.take_slot_bits())
         } else {
             Ok(Default::default())
         }
     }
+
+    fn set_sample_tap(&mut self, tap: Box<dyn SampleTap>) -> bool {
+        self.sample_tap = Some(tap);
+        true
+    }
 }
 
 struct RxDsp {
@@ -230,13 +275,15 @@ impl RxDsp {
                         fft_planner,
                         rx_fcfb_params,
                         *dl_freq,
-                        demodulator::Mode::DlUnsynchronized
+                        demodulator::Mode::DlUnsynchronized,
+                        phy_config.agc.as_ref(),
                     ),
                     ul: ul_freq.as_ref().map(|ul_freq| DemodulatorChannel::new(
                         fft_planner,
                         rx_fcfb_params,
                         *ul_freq,
                         demodulator::Mode::Idle,
+                        phy_config.agc.as_ref(),
                     ))
                 }
             }).collect(),
@@ -246,7 +293,8 @@ impl RxDsp {
                     fft_planner,
                     rx_fcfb_params,
                     *ul_freq,
-                    demodulator::Mode::Ul
+                    demodulator::Mode::Ul,
+                    phy_config.agc.as_ref(),
                 )
             }).collect(),
         }
@@ -276,6 +324,11 @@ impl RxDsp {
         Ok(continue_processing)
     }
 
+    /// Samples of the latest block received, following the overlap kept from the previous block
+    fn new_samples(&self) -> &[ComplexSample] {
+        &self.rx_buffer[self.rx_block_size.overlap..]
+    }
+
     fn receive_block(&mut self, sdr: &mut soapyio::SoapyIo) -> Result<(), RxTxDevError> {
         self.rx_block_count += 1;
 
@@ -334,16 +387,16 @@ impl RxDsp {
         let mut slot_bits = Vec::with_capacity(2 * self.monitors.len() + self.ul_demodulators.len());
 
         for pair in self.monitors.iter_mut() {
-            slot_bits.push(pair.dl.demodulator.take_demodulated_slot());
+            slot_bits.push(pair.dl.take_demodulated_slot());
             slot_bits.push(if let Some(ul) = &mut pair.ul {
-                ul.demodulator.take_demodulated_slot()
+                ul.take_demodulated_slot()
             } else {
                 None
             });
         }
 
         for demod in self.ul_demodulators.iter_mut() {
-            slot_bits.push(demod.demodulator.take_demodulated_slot());
+            slot_bits.push(demod.take_demodulated_slot());
         }
 
         slot_bits
@@ -356,6 +409,9 @@ struct TxDsp {
     block_count: fcfb::BlockCount,
     initial_time: i64,
     modulators: Vec<ModulatorChannel>,
+    /// Set after the TX stream has been restarted; the next slot is then
+    /// re-anchored to the first block that can still be transmitted.
+    reanchor_pending: bool,
 }
 
 impl TxDsp {
@@ -389,6 +445,7 @@ impl TxDsp {
             block_count: 0,
             initial_time: 0, // TODO: get it from RX
             modulators,
+            reanchor_pending: false,
         }
     }
 
@@ -397,7 +454,12 @@ impl TxDsp {
         sdr: &mut soapyio::SoapyIo,
         latest_rx_block: Option<fcfb::BlockCount>,
         tx_slot: &[TxSlotBits],
+        sample_tap: Option<&mut Box<dyn SampleTap>>,
     ) -> Result<bool, RxTxDevError> {
+        if sdr.take_tx_reanchor() {
+            self.reanchor_pending = true;
+        }
+
         let current_sample = sdr.tx_current_count()?;
         // Current time as block count
         let current_block = current_sample.div_euclid(self.fcfb.output_block_size() as SampleCount);
@@ -430,6 +492,16 @@ impl TxDsp {
             }
         }
 
+        if self.reanchor_pending {
+            // Pending TX slots are late after an underrun. Restart TDMA timing with the
+            // current slot at the earliest block that can still be transmitted.
+            self.reanchor_pending = false;
+            for (modulator, tx_slot) in self.modulators.iter_mut().zip(tx_slot) {
+                modulator.reanchor(self.block_count, tx_slot.time);
+            }
+            tracing::warn!("TX timing re-anchored at block {}", self.block_count);
+        }
+
         for (modulator, tx_slot) in self.modulators.iter_mut().zip(tx_slot) {
             if !modulator.process(&mut self.fcfb, self.block_count, tx_slot) {
                 return Ok(false);
@@ -445,6 +517,9 @@ impl TxDsp {
         // so we do not end up producing the same block again even if transmit fails.
         self.block_count += 1;
 
+        if let Some(tap) = sample_tap {
+            tap.tx_samples(tx_signal);
+        }
         sdr.transmit(tx_signal, Some(sdr_sample_count))?;
 
         // tracing::trace!("Produced transmit block {} ({} samples in future)",
@@ -460,6 +535,7 @@ impl TxDsp {
 
 struct DemodulatorChannel {
     downconverter: fcfb::AnalysisOutputProcessor,
+    agc: Option<agc::Agc>,
     demodulator: demodulator::Demodulator,
 }
 
@@ -469,8 +545,10 @@ impl DemodulatorChannel {
         analysis_in_params: fcfb::AnalysisInputParameters,
         frequency: f64,
         mode: demodulator::Mode,
+        agc_cfg: Option<&CfgAgc>,
     ) -> Self {
         Self {
+            agc: agc_cfg.map(agc::Agc::new),
             downconverter: fcfb::AnalysisOutputProcessor::new_with_frequency(
                 fft_planner,
                 analysis_in_params,
@@ -487,11 +565,25 @@ impl DemodulatorChannel {
     fn process(&mut self, fcfb_result: &fcfb::AnalysisIntermediateResult, block_count: fcfb::BlockCount) -> bool {
         let samples = self.downconverter.process(fcfb_result);
         for (i, sample) in samples.iter().enumerate() {
+            let sample = match &mut self.agc {
+                Some(agc) => agc.process(*sample),
+                None => *sample,
+            };
             // TODO: include delay of FCFB in sample count
-            self.demodulator.sample(*sample, block_count as SampleCount * samples.len() as SampleCount + i as SampleCount);
+            self.demodulator.sample(sample, block_count as SampleCount * samples.len() as SampleCount + i as SampleCount);
         }
         !self.demodulator.demodulated_slot_available()
     }
+
+    /// Take a demodulated slot, with signal strength measured before AGC
+    fn take_demodulated_slot(&mut self) -> Option<RxSlotBits<'_>> {
+        let gain_db = self.agc.as_ref().map_or(0.0, agc::Agc::gain_db);
+        let mut slot = self.demodulator.take_demodulated_slot()?;
+        for burst in [&mut slot.slot, &mut slot.subslot1, &mut slot.subslot2] {
+            burst.quality.rssi_dbm -= gain_db;
+        }
+        Some(slot)
+    }
 }
 
 struct ModulatorChannel {
@@ -504,6 +596,14 @@ struct ModulatorChannel {
 }
 
 impl ModulatorChannel {
+    /// Re-anchor the modulator TDMA timing so that slot `time` starts at block `block_count`
+    fn reanchor(&mut self, block_count: fcfb::BlockCount, time: TdmaTime) {
+        let block_len = self.buffer.buffer_in().len() as SampleCount;
+        // Discard a partially modulated block
+        self.buffer_i = 0;
+        self.modulator.reanchor(block_count as SampleCount * block_len, time);
+    }
+
     fn new(
         fft_planner: &mut FftPlanner,
         synthesis_out_params: fcfb::SynthesisOutputParameters,
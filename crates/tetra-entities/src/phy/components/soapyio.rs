@@ -7,6 +7,7 @@ use super::dsp_types;
 use super::soapy_time::{ticks_to_time_ns, time_ns_to_ticks};
 use super::dsp_types::*;
 use super::soapy_defaults::SdrSettings;
+use super::xrun_monitor::{XrunAction, XrunMonitor};
 
 type StreamType = ComplexSample;
 
@@ -17,6 +18,56 @@ pub enum Mode {
     Mon,
 }
 
+/// Bytes per sample on the wire between host and SDR, assuming complex 16-bit (sc16) transfers
+const WIRE_BYTES_PER_SAMPLE: usize = 4;
+
+/// Stream buffer tuning for one direction. None leaves the driver default in place.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StreamBufferCfg {
+    /// Samples per transfer
+    pub mtu: Option<usize>,
+    /// Number of transfer buffers
+    pub num_buffers: Option<usize>,
+}
+
+impl StreamBufferCfg {
+    /// Driver-specific SoapySDR stream arguments implementing this tuning
+    pub fn stream_args(&self, driver: &str, direction: soapysdr::Direction) -> Vec<(&'static str, String)> {
+        let mut args = Vec::new();
+        match driver {
+            "uhd" => {
+                let (frame_size_key, num_frames_key) = match direction {
+                    soapysdr::Direction::Rx => ("recv_frame_size", "num_recv_frames"),
+                    soapysdr::Direction::Tx => ("send_frame_size", "num_send_frames"),
+                };
+                if let Some(mtu) = self.mtu {
+                    args.push((frame_size_key, (mtu * WIRE_BYTES_PER_SAMPLE).to_string()));
+                }
+                if let Some(num_buffers) = self.num_buffers {
+                    args.push((num_frames_key, num_buffers.to_string()));
+                }
+            }
+            "lime" => {
+                if let Some(mtu) = self.mtu {
+                    args.push(("bufferLength", mtu.to_string()));
+                }
+                if self.num_buffers.is_some() {
+                    tracing::warn!("SoapySDR: number of stream buffers is not configurable for LimeSDR, ignoring");
+                }
+            }
+            _ => {
+                if let Some(mtu) = self.mtu {
+                    args.push(("bufflen", (mtu * WIRE_BYTES_PER_SAMPLE).to_string()));
+                }
+                if let Some(num_buffers) = self.num_buffers {
+                    args.push(("buffers", num_buffers.to_string()));
+                }
+            }
+        }
+        args
+    }
+}
+
 pub struct RxResult {
     /// Number of samples read
     pub len: usize,
@@ -45,6 +96,12 @@ pub struct SoapyIo {
     rx:  Option<soapysdr::RxStream<StreamType>>,
     /// Transmit stream. None if transmitting is disabled.
     tx:  Option<soapysdr::TxStream<StreamType>>,
+
+    /// Overrun/underrun tracking and TX recovery decisions
+    xrun: XrunMonitor,
+    /// Set when the TX stream has been re-initialized and TX timing
+    /// still needs to be re-anchored, see take_tx_reanchor
+    tx_reanchor_pending: bool,
 }
 
 /// It is annoying to repeat error handling so do that in a macro.
@@ -266,7 +323,20 @@ impl SoapyIo {
         // be set for minimum latency for TMO BS
         // but for maximum throughput for TMO monitor.
         let mut rx_args = soapysdr::Args::new();
-        let tx_args = soapysdr::Args::new();
+        let mut tx_args = soapysdr::Args::new();
+
+        // Apply stream buffer tuning from config
+        let rx_buffers = StreamBufferCfg { mtu: soapy_cfg.rx_stream_mtu, num_buffers: soapy_cfg.rx_stream_buffers };
+        let tx_buffers = StreamBufferCfg { mtu: soapy_cfg.tx_stream_mtu, num_buffers: soapy_cfg.tx_stream_buffers };
+        for (key, value) in rx_buffers.stream_args(driver, soapysdr::Direction::Rx) {
+            tracing::info!("SoapySDR: RX stream {} = {}", key, value);
+            rx_args.set(key, value);
+        }
+        for (key, value) in tx_buffers.stream_args(driver, soapysdr::Direction::Tx) {
+            tracing::info!("SoapySDR: TX stream {} = {}", key, value);
+            tx_args.set(key, value);
+        }
+
         // hack to test the idea above, TODO properly
         match mode {
             Mode::Bs | Mode::Ms => {
@@ -310,6 +380,8 @@ impl SoapyIo {
             dev,
             rx,
             tx,
+            xrun: XrunMonitor::new(),
+            tx_reanchor_pending: false,
         })
     }
 
@@ -337,7 +409,10 @@ impl SoapyIo {
                         count
                     })
                 },
-                Err(_) => Err(RxTxDevError::RxReadError),
+                Err(err) => {
+                    self.xrun.on_rx_read(err.code == soapysdr::ErrorCode::Overflow);
+                    Err(RxTxDevError::RxReadError)
+                },
             }
         } else {
             // RX is disabled
@@ -346,22 +421,53 @@ impl SoapyIo {
     }
 
     pub fn transmit(&mut self, buffer: &[StreamType], count: Option<SampleCount>) -> Result<(), RxTxDevError> {
+        let Some(tx) = &mut self.tx else {
+            // TX is disabled
+            return Err(RxTxDevError::RxReadError);
+        };
+        let Some(initial_time) = self.initial_time else {
+            // initial_time is not available, so TX is not possible yet
+            return Err(RxTxDevError::RxReadError);
+        };
+
+        let result = tx.write_all(&[buffer],
+            count.map(|count|
+                initial_time + ticks_to_time_ns(count, self.tx_fs)
+            ),
+            false, 1000000
+        );
+
+        let underflow = matches!(&result, Err(err) if err.code == soapysdr::ErrorCode::Underflow);
+        if self.xrun.on_tx_write(underflow) == XrunAction::ReinitTx {
+            self.reinit_tx_stream();
+        }
+        result.map_err(|_| RxTxDevError::RxReadError)
+    }
+
+    /// Recover from a sustained TX underrun by restarting the TX stream.
+    /// TX timing is re-anchored by the caller, see take_tx_reanchor.
+    fn reinit_tx_stream(&mut self) {
         if let Some(tx) = &mut self.tx {
-            if let Some(initial_time) = self.initial_time {
-                tx.write_all(&[buffer],
-                    count.map(|count|
-                        initial_time + ticks_to_time_ns(count, self.tx_fs)
-                    ),
-                    false, 1000000
-                ).map_err(|_| RxTxDevError::RxReadError)
-            } else {
-                // initial_time is not available, so TX is not possible yet
-                Err(RxTxDevError::RxReadError)
+            if let Err(err) = tx.deactivate(None) {
+                tracing::warn!("SoapySDR: Failed to deactivate TX stream: {}", err);
+            }
+            if let Err(err) = tx.activate(None) {
+                tracing::error!("SoapySDR: Failed to re-activate TX stream: {}", err);
             }
-        } else {
-            // TX is disabled
-            Err(RxTxDevError::RxReadError)
         }
+        self.xrun.on_tx_reinit();
+        self.tx_reanchor_pending = true;
+    }
+
+    /// Returns true once after the TX stream has been re-initialized,
+    /// signalling that TX timing should be re-anchored to the current hardware time.
+    pub fn take_tx_reanchor(&mut self) -> bool {
+        std::mem::take(&mut self.tx_reanchor_pending)
+    }
+
+    /// Overrun/underrun counters
+    pub fn xrun(&self) -> &XrunMonitor {
+        &self.xrun
     }
 
     pub fn current_time(&self) -> Result<i64, RxTxDevError> {
@@ -427,3 +533,103 @@ impl SoapyIo {
         self.tx.is_some()
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use tetra_config::ConfigError;
+    use tetra_core::freqs::FreqInfo;
+
+    use super::*;
+
+    fn soapy_toml(driver_section: &str, stream_fields: &str) -> String {
+        soapy_toml_freqs(driver_section, stream_fields, 433025000, false)
+    }
+
+    fn soapy_toml_freqs(driver_section: &str, stream_fields: &str, rx_freq: u32, reverse_operation: bool) -> String {
+        format!(r#"
+            config_version = "0.5"
+            stack_mode = "Bs"
+            [phy_io]
+            backend = "SoapySdr"
+            [phy_io.soapysdr]
+            tx_freq = 438025000
+            rx_freq = {rx_freq}
+            {stream_fields}
+            [phy_io.soapysdr.{driver_section}]
+            [net_info]
+            mcc = 204
+            mnc = 1337
+            [cell_info]
+            main_carrier = 1521
+            freq_band = 4
+            freq_offset = 0
+            duplex_spacing = 4
+            reverse_operation = {reverse_operation}
+            location_area = 2
+            colour_code = 1
+        "#)
+    }
+
+    fn stream_buffers(cfg: &SharedConfig) -> (StreamBufferCfg, StreamBufferCfg) {
+        let c = cfg.config();
+        let soapy_cfg = c.phy_io.soapysdr.as_ref().unwrap();
+        (
+            StreamBufferCfg { mtu: soapy_cfg.rx_stream_mtu, num_buffers: soapy_cfg.rx_stream_buffers },
+            StreamBufferCfg { mtu: soapy_cfg.tx_stream_mtu, num_buffers: soapy_cfg.tx_stream_buffers },
+        )
+    }
+
+    #[test]
+    fn test_stream_buffer_config_plumbing() {
+        let toml = soapy_toml("iocfg_usrpb2xx", "rx_stream_mtu = 2000\nrx_stream_buffers = 32\ntx_stream_buffers = 8");
+        let cfg = tetra_config::from_toml_str(&toml).expect("Failed loading config");
+        let (rx, tx) = stream_buffers(&cfg);
+        assert_eq!(rx, StreamBufferCfg { mtu: Some(2000), num_buffers: Some(32) });
+        assert_eq!(tx, StreamBufferCfg { mtu: None, num_buffers: Some(8) });
+
+        let driver = cfg.config().phy_io.soapysdr.as_ref().unwrap().io_cfg.get_soapy_driver_name();
+        assert_eq!(rx.stream_args(driver, soapysdr::Direction::Rx), vec![("recv_frame_size", "8000".to_string()), ("num_recv_frames", "32".to_string())]);
+        assert_eq!(tx.stream_args(driver, soapysdr::Direction::Tx), vec![("num_send_frames", "8".to_string())]);
+    }
+
+    #[test]
+    fn test_stream_buffer_defaults() {
+        // Without tuning, no stream arguments are added and driver defaults apply
+        let cfg = tetra_config::from_toml_str(&soapy_toml("iocfg_limesdr", "")).expect("Failed loading config");
+        let (rx, tx) = stream_buffers(&cfg);
+        assert_eq!(rx, StreamBufferCfg::default());
+        assert!(rx.stream_args("lime", soapysdr::Direction::Rx).is_empty());
+        assert!(tx.stream_args("lime", soapysdr::Direction::Tx).is_empty());
+
+        let rx = StreamBufferCfg { mtu: Some(1024), num_buffers: Some(4) };
+        assert_eq!(rx.stream_args("lime", soapysdr::Direction::Rx), vec![("bufferLength", "1024".to_string())]);
+        assert_eq!(rx.stream_args("sx", soapysdr::Direction::Rx), vec![("bufflen", "4096".to_string()), ("buffers", "4".to_string())]);
+
+        // Zero sized buffers are rejected
+        let mut raw_cfg = (*cfg.config()).clone();
+        raw_cfg.validate().unwrap();
+        raw_cfg.phy_io.soapysdr.as_mut().unwrap().tx_stream_mtu = Some(0);
+        assert!(raw_cfg.validate().is_err());
+    }
+
+    #[test]
+    fn test_reverse_operation_frequencies() {
+        // In reverse operation, the receiver is tuned above the transmitter
+        let cfg = tetra_config::from_toml_str(&soapy_toml_freqs("iocfg_limesdr", "", 443025000, true)).expect("Failed loading config");
+        let c = cfg.config();
+        let soapy_cfg = c.phy_io.soapysdr.as_ref().unwrap();
+        let freq_info = FreqInfo::from_components(c.cell.freq_band, c.cell.main_carrier, c.cell.freq_offset_hz,
+            c.cell.reverse_operation, c.cell.duplex_spacing_id, None).unwrap();
+        assert_eq!(freq_info.get_freqs(), (soapy_cfg.dl_freq as u32, soapy_cfg.ul_freq as u32));
+        assert!(soapy_cfg.ul_freq > soapy_cfg.dl_freq);
+
+        // The flag must agree with the side the uplink is on
+        let mut raw_cfg = (*c).clone();
+        raw_cfg.cell.reverse_operation = false;
+        assert_eq!(raw_cfg.check(), vec![ConfigError::new("phy_io.soapysdr.rx_freq", "is above tx_freq, which requires reverse_operation")]);
+        raw_cfg.cell.reverse_operation = true;
+        raw_cfg.phy_io.soapysdr.as_mut().unwrap().ul_freq = 433025000.0;
+        assert_eq!(raw_cfg.check(), vec![ConfigError::new("phy_io.soapysdr.rx_freq", "is below tx_freq, but reverse_operation is set")]);
+    }
+}
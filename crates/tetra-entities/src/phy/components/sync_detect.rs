@@ -0,0 +1,204 @@
+//! Detection of bursts in a sample stream by cross-correlating against
+//! the known training sequences they contain.
+//!
+//! Input is expected at the modem sample rate (4 samples per symbol), before
+//! matched filtering. The detector applies the channel filter itself.
+//! Since modulation is differential, sequences are correlated non-coherently:
+//! the carrier phase at each sequence does not need to be known.
+
+use tetra_core::TrainingSequence;
+
+use super::demodulator::SPS;
+use super::dsp_types::*;
+use super::fir;
+use super::modem_common::CHANNEL_FILTER_TAPS;
+use super::modulator::DqpskMapper;
+use super::slotter::bitseq;
+use super::train_consts::{SEQ_NORM_DL_OFFSET, SEQ_SYNC_OFFSET};
+
+/// Offset of the frequency correction field within a synchronization burst, in bits
+const FREQ_CORR_OFFSET: usize = 14;
+
+/// A known bit sequence at a fixed position in a burst
+pub struct TrainingReference {
+    /// Modulated symbols, starting with the symbol preceding the sequence,
+    /// which serves as phase reference for the first bits
+    symbols: Vec<ComplexSample>,
+    /// Index of the first reference symbol within the burst
+    first_symbol: usize,
+    energy: RealSample,
+}
+
+impl TrainingReference {
+    /// Reference for `bits`, found at `bit_offset` (which must be even) within the burst
+    pub fn new(bits: &[u8], bit_offset: usize) -> Self {
+        assert!(bit_offset.is_multiple_of(2) && bit_offset >= 2 && bits.len().is_multiple_of(2));
+        let mut mapper = DqpskMapper::new();
+        let mut symbols = vec![ComplexSample::new(1.0, 0.0)];
+        symbols.extend(bits.chunks(2).map(|b| mapper.symbol(b[0] != 0, b[1] != 0)));
+        Self {
+            energy: symbols.len() as RealSample,
+            symbols,
+            first_symbol: bit_offset / 2 - 1,
+        }
+    }
+
+    /// Number of samples from the burst start to past the last reference symbol
+    fn span(&self) -> usize {
+        (self.first_symbol + self.symbols.len() - 1) * SPS + 1
+    }
+
+    /// Normalized correlation magnitude between 0 and 1 for a burst
+    /// starting at index `burst_start` of the filtered signal
+    fn correlate(&self, filtered: &[ComplexSample], burst_start: usize) -> RealSample {
+        let start = burst_start + self.first_symbol * SPS;
+        let mut corr = ComplexSample::ZERO;
+        let mut energy: RealSample = 0.0;
+        for (i, reference) in self.symbols.iter().enumerate() {
+            let sample = filtered[start + i * SPS];
+            corr += sample * reference.conj();
+            energy += sample.norm_sqr();
+        }
+        if energy > 0.0 {
+            corr.norm() / (energy * self.energy).sqrt()
+        } else {
+            0.0
+        }
+    }
+}
+
+/// A burst found in the sample stream
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BurstDetection {
+    /// Index of the input sample at which the first symbol of the burst is centered
+    pub burst_start: usize,
+    /// Average normalized correlation of all training sequences, between 0 and 1
+    pub confidence: RealSample,
+}
+
+/// Finds a burst by correlating against all of its training sequences
+pub struct BurstDetector {
+    references: Vec<TrainingReference>,
+}
+
+impl BurstDetector {
+    pub fn new(references: Vec<TrainingReference>) -> Self {
+        assert!(!references.is_empty());
+        Self { references }
+    }
+
+    /// Detector for the synchronization burst, using the frequency correction
+    /// field and the synchronization training sequence
+    pub fn sync_burst() -> Self {
+        Self::new(vec![
+            TrainingReference::new(&bitseq::f, FREQ_CORR_OFFSET),
+            TrainingReference::new(&bitseq::y, SEQ_SYNC_OFFSET),
+        ])
+    }
+
+    /// Detector for a normal downlink burst with the given training sequence
+    pub fn normal_dl_burst(train_type: TrainingSequence) -> Self {
+        let bits: &[u8] = match train_type {
+            TrainingSequence::NormalTrainSeq1 => &bitseq::n,
+            TrainingSequence::NormalTrainSeq2 => &bitseq::p,
+            _ => panic!("No normal downlink burst with {:?}", train_type),
+        };
+        Self::new(vec![TrainingReference::new(bits, SEQ_NORM_DL_OFFSET)])
+    }
+
+    /// Finds the most likely burst start within `samples`.
+    /// Returns None if `samples` is too short to contain the training sequences.
+    pub fn detect(&self, samples: &[ComplexSample]) -> Option<BurstDetection> {
+        // Matched filter, compensating for its delay in the sample index
+        let delay = CHANNEL_FILTER_TAPS.len();
+        let mut filter = fir::FirComplexSym::new(CHANNEL_FILTER_TAPS.len());
+        let filtered: Vec<ComplexSample> = samples.iter()
+            .chain(std::iter::repeat_n(&ComplexSample::ZERO, delay))
+            .map(|sample| filter.sample(&CHANNEL_FILTER_TAPS, *sample))
+            .skip(delay)
+            .collect();
+
+        let span = self.references.iter().map(|r| r.span()).max().unwrap();
+        if filtered.len() < span {
+            return None;
+        }
+
+        (0..=filtered.len() - span)
+            .map(|burst_start| {
+                let sum: RealSample = self.references.iter().map(|r| r.correlate(&filtered, burst_start)).sum();
+                BurstDetection { burst_start, confidence: sum / self.references.len() as RealSample }
+            })
+            .max_by(|a, b| a.confidence.total_cmp(&b.confidence))
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+    use tetra_core::TdmaTime;
+    use tetra_pdus::phy::traits::rxtx_dev::TxSlotBits;
+
+    use crate::phy::components::{modulator, slotter};
+
+    use super::*;
+
+    /// Modulates `burst` so that it starts at sample `offset`, and adds noise.
+    /// The burst is centered on the output after the delay of the modulator's filter.
+    fn burst_in_noise(burst: Option<&[u8]>, offset: usize, len: usize, noise_amplitude: RealSample, rng: &mut StdRng) -> Vec<ComplexSample> {
+        let mut modulator = modulator::Modulator::new(modulator::Mode::Dl);
+        let slot = TxSlotBits { time: TdmaTime::default(), slot: burst };
+        modulator.reanchor(offset as SampleCount, slot.time);
+        (0..len)
+            .map(|i| {
+                let signal = modulator.sample(i as SampleCount, &slot).unwrap_or(ComplexSample::ZERO);
+                let noise = ComplexSample::new(rng.random::<RealSample>() - 0.5, rng.random::<RealSample>() - 0.5);
+                signal + noise * noise_amplitude
+            })
+            .collect()
+    }
+
+    fn random_bits<const N: usize>(rng: &mut StdRng) -> [u8; N] {
+        std::array::from_fn(|_| rng.random_range(0..2))
+    }
+
+    #[test]
+    fn test_detect_sync_burst() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let burst = slotter::build_sdb(&random_bits(&mut rng), &random_bits(&mut rng), &random_bits(&mut rng));
+        let detector = BurstDetector::sync_burst();
+
+        for offset in [0, 37, 500, 1021] {
+            let samples = burst_in_noise(Some(&burst), offset, 3000, 0.5, &mut rng);
+            let detection = detector.detect(&samples).unwrap();
+            let offset = offset + CHANNEL_FILTER_TAPS.len();
+            assert!(detection.burst_start.abs_diff(offset) <= 1, "expected burst at {}, found {:?}", offset, detection);
+            assert!(detection.confidence > 0.7, "low confidence {:?}", detection);
+        }
+
+        // Noise only
+        let samples = burst_in_noise(None, 0, 3000, 0.5, &mut rng);
+        assert!(detector.detect(&samples).unwrap().confidence < 0.5);
+
+        // Too short to contain the training sequences
+        assert!(detector.detect(&samples[..500]).is_none());
+    }
+
+    #[test]
+    fn test_detect_normal_training_sequence() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let blk: [u8; 216] = random_bits(&mut rng);
+        let bbk: [u8; 30] = random_bits(&mut rng);
+        let burst = slotter::build_ndb(TrainingSequence::NormalTrainSeq2, &blk, &bbk, &blk);
+
+        let samples = burst_in_noise(Some(&burst), 123, 2500, 0.5, &mut rng);
+        let detection = BurstDetector::normal_dl_burst(TrainingSequence::NormalTrainSeq2).detect(&samples).unwrap();
+        assert!(detection.burst_start.abs_diff(123 + CHANNEL_FILTER_TAPS.len()) <= 1, "found {:?}", detection);
+        assert!(detection.confidence > 0.7);
+
+        // The other training sequence does not match as well
+        let other = BurstDetector::normal_dl_burst(TrainingSequence::NormalTrainSeq1).detect(&samples).unwrap();
+        assert!(other.confidence < detection.confidence);
+    }
+}
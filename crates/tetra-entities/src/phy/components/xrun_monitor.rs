@@ -0,0 +1,132 @@
+//! Tracking of SDR stream overruns (RX overflows) and underruns (TX underflows),
+//! and the decision when TX needs to be recovered.
+
+/// Number of consecutive TX writes reporting an underflow after which the
+/// underrun is considered sustained and the TX stream is re-initialized.
+pub const SUSTAINED_UNDERRUN_WRITES: u32 = 8;
+
+/// What the SDR I/O should do after a TX write
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum XrunAction {
+    /// Nothing to do
+    None,
+    /// TX has been underrunning for a while. Re-initialize the TX stream and
+    /// re-anchor the TX timing to the current hardware time.
+    ReinitTx,
+}
+
+#[derive(Debug, Default)]
+pub struct XrunMonitor {
+    /// Total RX overruns
+    rx_overruns: u64,
+    /// Total TX underruns
+    tx_underruns: u64,
+    /// TX writes in a row that reported an underflow
+    consecutive_underruns: u32,
+    /// Number of times TX has been re-initialized
+    tx_reinits: u64,
+}
+
+impl XrunMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the outcome of an RX read
+    pub fn on_rx_read(&mut self, overflow: bool) {
+        if overflow {
+            self.rx_overruns += 1;
+            tracing::warn!("SDR RX overrun ({} so far)", self.rx_overruns);
+        }
+    }
+
+    /// Register the outcome of a TX write. Returns ReinitTx once the underrun is sustained;
+    /// the caller should then re-initialize TX and report back with on_tx_reinit.
+    pub fn on_tx_write(&mut self, underflow: bool) -> XrunAction {
+        if !underflow {
+            self.consecutive_underruns = 0;
+            return XrunAction::None;
+        }
+
+        self.tx_underruns += 1;
+        self.consecutive_underruns += 1;
+        tracing::warn!("SDR TX underrun ({} so far, {} in a row)", self.tx_underruns, self.consecutive_underruns);
+        if self.consecutive_underruns >= SUSTAINED_UNDERRUN_WRITES {
+            XrunAction::ReinitTx
+        } else {
+            XrunAction::None
+        }
+    }
+
+    /// Register that TX has been re-initialized, starting a new underrun streak
+    pub fn on_tx_reinit(&mut self) {
+        self.tx_reinits += 1;
+        self.consecutive_underruns = 0;
+        tracing::warn!("SDR TX re-initialized after sustained underrun ({} times so far)", self.tx_reinits);
+    }
+
+    pub fn rx_overruns(&self) -> u64 {
+        self.rx_overruns
+    }
+
+    pub fn tx_underruns(&self) -> u64 {
+        self.tx_underruns
+    }
+
+    pub fn tx_reinits(&self) -> u64 {
+        self.tx_reinits
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overruns_are_counted() {
+        let mut mon = XrunMonitor::new();
+        for overflow in [false, true, true, false, true] {
+            mon.on_rx_read(overflow);
+        }
+        assert_eq!(mon.rx_overruns(), 3);
+        assert_eq!(mon.tx_underruns(), 0);
+    }
+
+    #[test]
+    fn test_isolated_underruns_do_not_trigger_recovery() {
+        let mut mon = XrunMonitor::new();
+        for _ in 0..10 {
+            // Every streak is interrupted by a clean write before reaching the threshold
+            for _ in 0..SUSTAINED_UNDERRUN_WRITES - 1 {
+                assert_eq!(mon.on_tx_write(true), XrunAction::None);
+            }
+            assert_eq!(mon.on_tx_write(false), XrunAction::None);
+        }
+        assert_eq!(mon.tx_underruns(), 10 * (SUSTAINED_UNDERRUN_WRITES as u64 - 1));
+        assert_eq!(mon.tx_reinits(), 0);
+    }
+
+    #[test]
+    fn test_sustained_underrun_triggers_reinit() {
+        let mut mon = XrunMonitor::new();
+        for _ in 0..SUSTAINED_UNDERRUN_WRITES - 1 {
+            assert_eq!(mon.on_tx_write(true), XrunAction::None);
+        }
+        assert_eq!(mon.on_tx_write(true), XrunAction::ReinitTx);
+
+        // Until the caller reports the reinit, continuing underruns keep asking for it
+        assert_eq!(mon.on_tx_write(true), XrunAction::ReinitTx);
+        mon.on_tx_reinit();
+        assert_eq!(mon.tx_reinits(), 1);
+
+        // After reinit, a new full streak is needed before recovering again
+        assert_eq!(mon.on_tx_write(true), XrunAction::None);
+        assert_eq!(mon.on_tx_write(false), XrunAction::None);
+        for _ in 0..SUSTAINED_UNDERRUN_WRITES - 1 {
+            assert_eq!(mon.on_tx_write(true), XrunAction::None);
+        }
+        assert_eq!(mon.on_tx_write(true), XrunAction::ReinitTx);
+        assert_eq!(mon.tx_underruns(), 2 * SUSTAINED_UNDERRUN_WRITES as u64 + 2);
+    }
+}
@@ -3,7 +3,7 @@ use std::panic;
 use crossbeam_channel::Sender;
 
 use tetra_config::SharedConfig;
-use tetra_core::{BitBuffer, BurstType, PhyBlockNum, PhyBlockType, Sap, TdmaTime, TrainingSequence};
+use tetra_core::{BitBuffer, BurstType, PhyBlockNum, PhyBlockType, RxQuality, Sap, TdmaTime, TrainingSequence};
 use tetra_core::tetra_entities::TetraEntity;
 use tetra_saps::{SapMsg, SapMsgInner};
 use tetra_saps::tp::TpUnitdataInd;
@@ -17,6 +17,11 @@ use crate::umac::subcomp::bs_sched::MACSCHED_TX_AHEAD;
 
 use super::components::phy_io_file::PhyIoFile;
 
+/// Number of slots by which the time of a received slot may deviate from the
+/// expected advance before it counts as a discontinuity. Several slots may be
+/// returned for one tick, so small deviations are normal.
+pub const TIME_DISCONTINUITY_THRESHOLD_SLOTS: i32 = 2;
+
 pub struct PhyBs<D: RxTxDev> {
 
     config: SharedConfig,
@@ -35,6 +40,17 @@ pub struct PhyBs<D: RxTxDev> {
     /// RX/TX device
     rxtxdev: D,
 
+    /// Calibration from received signal strength in dBFS to dBm
+    rssi_offset_db: f32,
+    /// Largest tolerated burst arrival delay, see CfgCellInfo::max_path_delay_symbols
+    max_path_delay_symbols: f32,
+    /// Frames in which no burst is transmitted, to listen on the downlink frequency
+    tx_off_frames: Vec<u8>,
+
+    /// Offset of the slot time derived from the device sample clock relative to the
+    /// stack downlink time, learnt from the first received slot
+    rx_time_offset: Option<i32>,
+
     tick: u64,
 }
 
@@ -42,6 +58,8 @@ impl <D: RxTxDev>PhyBs<D> {
     pub fn new(config: SharedConfig, rxtxdev: D) -> Self {
 
         let c = &config.config().phy_io;
+        let max_path_delay_symbols = config.config().cell.max_path_delay_symbols();
+        let tx_off_frames = config.config().cell.tx_off_frames.clone();
         
         // Create async writers for file logging of generated DL and received UL signals
         let dl_tx_logger = c.dl_tx_file.as_ref()
@@ -69,10 +87,15 @@ impl <D: RxTxDev>PhyBs<D> {
             dl_input_file,
             ul_input_file,
             rxtxdev,
+            rssi_offset_db: c.rssi_offset_db,
+            max_path_delay_symbols,
+            tx_off_frames,
+            rx_time_offset: None,
             tick: 0,
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn send_rxblock_to_lmac(
         queue: &mut MessageQueue, 
         train_type: TrainingSequence, 
@@ -80,6 +103,7 @@ impl <D: RxTxDev>PhyBs<D> {
         block_type: PhyBlockType, 
         block_num: PhyBlockNum, 
         bits: BitBuffer, 
+        rx_quality: RxQuality,
         dltime: TdmaTime
     ) {
         // Uplink timeslot is two after downlink. Thus was transmitted at dltime - 2
@@ -94,15 +118,28 @@ impl <D: RxTxDev>PhyBs<D> {
                 burst_type,
                 block_type,
                 block_num,
-                block: bits
+                block: bits,
+                rx_quality: Some(rx_quality),
             }),
         };
         queue.push_back(sapmsg);
     }
 
-    fn split_rxslot_and_send_to_lmac(queue: &mut MessageQueue, burst: &RxBurstBits<'_>, dltime: TdmaTime) {
+    fn split_rxslot_and_send_to_lmac(
+        queue: &mut MessageQueue,
+        burst: &RxBurstBits<'_>,
+        rssi_offset_db: f32,
+        max_path_delay_symbols: f32,
+        dltime: TdmaTime,
+    ) {
+
+        let quality = RxQuality { rssi_dbm: burst.quality.rssi_dbm + rssi_offset_db, ..burst.quality };
 
         let train_seq = burst.train_type;
+        if quality.exceeds_path_delay(max_path_delay_symbols) {
+            tracing::warn!("{:?} at {} arrived {:.1} symbols late, beyond the cell radius: likely co-channel interference or a reflection",
+                train_seq, dltime, quality.delay_symbols);
+        }
         match train_seq {
             TrainingSequence::NormalTrainSeq1 => { 
 
@@ -120,6 +157,7 @@ impl <D: RxTxDev>PhyBs<D> {
                     PhyBlockType::NUB, 
                     PhyBlockNum::Both, 
                     blk,
+                    quality,
                     dltime);
             }
 
@@ -137,6 +175,7 @@ impl <D: RxTxDev>PhyBs<D> {
                     PhyBlockType::NUB, 
                     PhyBlockNum::Block1, 
                     blk1,
+                    quality,
                     dltime
                 );
                 Self::send_rxblock_to_lmac(queue, 
@@ -144,7 +183,8 @@ impl <D: RxTxDev>PhyBs<D> {
                     BurstType::NUB, 
                     PhyBlockType::NUB, 
                     PhyBlockNum::Block2, 
-                    blk2, 
+                    blk2,
+                    quality,
                     dltime
                 );
             }
@@ -164,6 +204,7 @@ impl <D: RxTxDev>PhyBs<D> {
                     PhyBlockType::SSN1, 
                     PhyBlockNum::Block1, 
                     blk,
+                    quality,
                     dltime
                 );
             }
@@ -172,6 +213,24 @@ impl <D: RxTxDev>PhyBs<D> {
         }
     }
 
+    /// Compares the time of a received slot against the stack downlink time. A jump in
+    /// the offset between them, e.g. after samples were lost in an overrun, is logged and
+    /// counted in the stack state, after which the new offset is used as reference.
+    fn check_rx_time(config: &SharedConfig, rx_time_offset: &mut Option<i32>, rx_time: TdmaTime, dltime: TdmaTime) {
+        let offset = rx_time.diff(dltime);
+        let Some(expected) = *rx_time_offset else {
+            *rx_time_offset = Some(offset);
+            return;
+        };
+        let jump = offset - expected;
+        if jump.abs() > TIME_DISCONTINUITY_THRESHOLD_SLOTS {
+            tracing::warn!("TDMA time discontinuity at {}: received slot {} is {} slots off the expected time",
+                dltime, rx_time, jump);
+            *rx_time_offset = Some(offset);
+            config.state_write().tdma_discontinuities += 1;
+        }
+    }
+
     fn rx_tpsap_prim(&mut self, queue: &mut MessageQueue, message: SapMsg) {
         
         // Handle TpUnitdataReq with a TX slot
@@ -237,15 +296,18 @@ impl <D: RxTxDev>PhyBs<D> {
             };
         }
 
-        // Prepare the TX slot for the tx device
+        // Prepare the TX slot for the tx device. In TX-off frames the device still
+        // receives, but the carrier is off.
+        let tx_time = message.dltime.add_timeslots(MACSCHED_TX_AHEAD as i32);
+        let tx_off = self.tx_off_frames.contains(&tx_time.f);
         let tx_slot: [TxSlotBits; 1] = [TxSlotBits {
-            time: message.dltime.add_timeslots(MACSCHED_TX_AHEAD as i32),
-            slot: Some(&dl_burst),
-            ..Default::default()
+            time: tx_time,
+            slot: if tx_off { None } else { Some(&dl_burst) },
         }];
 
         // Code for testing mode, when capturing all DL output to file
-        if let Some(dl_tx_sender) = &self.dl_tx_sender {
+        if let Some(dl_tx_sender) = &self.dl_tx_sender
+            && !tx_off {
             let _ = dl_tx_sender.try_send(FileWriteMsg::WriteBlock(dl_burst.to_vec()));
         } 
 
@@ -263,6 +325,8 @@ impl <D: RxTxDev>PhyBs<D> {
         // The Lmac error correction will eliminate the false positives
         for rx_slot in rx {
             if let Some(rx_slot) = rx_slot {
+                Self::check_rx_time(&self.config, &mut self.rx_time_offset, rx_slot.time, self.dltime);
+
                 let mut slot_sent = false;
                 if rx_slot.slot.train_type != TrainingSequence::NotFound {
                     tracing::info!(ts=%self.dltime, "rx_tpsap_prim got {:?} in fullslot", rx_slot.slot.train_type);
@@ -272,7 +336,7 @@ impl <D: RxTxDev>PhyBs<D> {
                         let _ = ul_rx_sender.try_send(FileWriteMsg::WriteHeaderAndBlock(3, self.tick, rx_slot.slot.bits.to_vec()));
                     }
 
-                    Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.slot, self.dltime);
+                    Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.slot, self.rssi_offset_db, self.max_path_delay_symbols, self.dltime);
                     slot_sent = true;
                 }
                 if rx_slot.subslot1.train_type != TrainingSequence::NotFound {
@@ -285,7 +349,7 @@ impl <D: RxTxDev>PhyBs<D> {
                         let _ = ul_rx_sender.try_send(FileWriteMsg::WriteHeaderAndBlock(1, self.tick, rx_slot.subslot1.bits.to_vec()));
                     }
 
-                    Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.subslot1, self.dltime);
+                    Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.subslot1, self.rssi_offset_db, self.max_path_delay_symbols, self.dltime);
                     slot_sent = true;
                 }
                 if rx_slot.subslot2.train_type != TrainingSequence::NotFound {
@@ -298,7 +362,7 @@ impl <D: RxTxDev>PhyBs<D> {
                         let _ = ul_rx_sender.try_send(FileWriteMsg::WriteHeaderAndBlock(2, self.tick, rx_slot.subslot2.bits.to_vec()));
                     }
 
-                    Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.subslot2, self.dltime);
+                    Self::split_rxslot_and_send_to_lmac(queue, &rx_slot.subslot2, self.rssi_offset_db, self.max_path_delay_symbols, self.dltime);
                 }
             }
         }
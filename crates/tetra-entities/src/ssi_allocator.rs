@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+
+/// SSIs that are never handed out: 0 is not a valid address, 0xFFFFFF is the all-call group
+const RESERVED_SSIS: [u32; 2] = [0, 0xFFFFFF];
+
+/// Hands out SSIs from a range, such as (V)ASSIs to MSs migrating from other networks
+/// in MM, or temporary addresses to calls in CMCE.
+/// SSIs marked as reserved are skipped.
+/// Released SSIs are reused only after the rest of the range has been handed out.
+pub struct SsiAllocator {
+    first: u32,
+    last: u32,
+    reserved: HashSet<u32>,
+    allocated: HashSet<u32>,
+    /// SSI to try first on the next allocation
+    next: u32,
+}
+
+impl SsiAllocator {
+    /// Allocator for the SSIs from `first` up to and including `last`
+    pub fn new(first: u32, last: u32) -> Self {
+        assert!(first <= last, "SsiAllocator: empty range {}-{}", first, last);
+        Self {
+            first,
+            last,
+            reserved: RESERVED_SSIS.into_iter().collect(),
+            allocated: HashSet::new(),
+            next: first,
+        }
+    }
+
+    /// Whether `ssi` lies within the range of this allocator
+    pub fn contains(&self, ssi: u32) -> bool {
+        (self.first..=self.last).contains(&ssi)
+    }
+
+    /// Excludes `ssi` from allocation
+    pub fn reserve(&mut self, ssi: u32) {
+        self.reserved.insert(ssi);
+    }
+
+    /// Hands out a free SSI, or None if the range is exhausted
+    pub fn allocate(&mut self) -> Option<u32> {
+        let range_len = (self.last - self.first) as u64 + 1;
+        for _ in 0..range_len {
+            let ssi = self.next;
+            self.next = if ssi == self.last { self.first } else { ssi + 1 };
+            if !self.reserved.contains(&ssi) && self.allocated.insert(ssi) {
+                return Some(ssi);
+            }
+        }
+        None
+    }
+
+    /// Returns `ssi` to the pool. Returns false if it was not allocated.
+    pub fn release(&mut self, ssi: u32) -> bool {
+        self.allocated.remove(&ssi)
+    }
+
+    pub fn is_allocated(&self, ssi: u32) -> bool {
+        self.allocated.contains(&ssi)
+    }
+
+    /// Number of SSIs currently handed out
+    pub fn allocated_count(&self) -> usize {
+        self.allocated.len()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allocate_within_range() {
+        let mut alloc = SsiAllocator::new(1000, 1009);
+        alloc.reserve(1001);
+        alloc.reserve(2000);
+        let ssis: Vec<u32> = std::iter::from_fn(|| alloc.allocate()).collect();
+        assert_eq!(ssis, vec![1000, 1002, 1003, 1004, 1005, 1006, 1007, 1008, 1009]);
+        assert_eq!(alloc.allocated_count(), 9);
+    }
+
+    #[test]
+    fn test_reserved_ssis_skipped() {
+        let mut alloc = SsiAllocator::new(0xFFFFFE, 0xFFFFFF);
+        assert_eq!(alloc.allocate(), Some(0xFFFFFE));
+        assert_eq!(alloc.allocate(), None);
+        let mut alloc = SsiAllocator::new(0, 1);
+        assert_eq!(alloc.allocate(), Some(1));
+    }
+
+    #[test]
+    fn test_exhaustion_and_reuse() {
+        let mut alloc = SsiAllocator::new(500, 502);
+        assert_eq!(alloc.allocate(), Some(500));
+        assert_eq!(alloc.allocate(), Some(501));
+        assert_eq!(alloc.allocate(), Some(502));
+        assert_eq!(alloc.allocate(), None);
+
+        assert!(alloc.release(501));
+        assert!(!alloc.release(501));
+        assert!(!alloc.is_allocated(501));
+        assert_eq!(alloc.allocate(), Some(501));
+        assert_eq!(alloc.allocate(), None);
+
+        // Released SSIs are handed out in turn
+        assert!(alloc.release(502));
+        assert!(alloc.release(500));
+        assert_eq!(alloc.allocate(), Some(502));
+        assert_eq!(alloc.allocate(), Some(500));
+    }
+}
@@ -0,0 +1,103 @@
+//! Summary of a stack run, reported when `run_stack` returns
+
+use core::fmt;
+use std::collections::BTreeMap;
+
+use tetra_core::tetra_entities::TetraEntity;
+
+use crate::MessageRouter;
+use crate::cmce::cmce_bs::CmceBs;
+use crate::mm::mm_bs::MmBs;
+
+
+/// Counters kept by the router itself
+#[derive(Debug, Default, Clone)]
+pub struct RouterStats {
+    /// Number of completed ticks
+    pub ticks: u64,
+    /// Number of routed primitives, by primitive name
+    pub primitives: BTreeMap<&'static str, u64>,
+    /// Number of MM and CMCE PDUs passing the MLE, by PDU type
+    pub pdus: BTreeMap<String, PduCount>,
+}
+
+/// Number of PDUs of a single type received over the air and decoded, and generated by the stack
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct PduCount {
+    pub decoded: u64,
+    pub generated: u64,
+}
+
+/// Router counters, combined with those of the CMCE and MM entities if registered,
+/// and with the device error counters kept in the stack state
+#[derive(Debug, Clone)]
+pub struct StackSummary {
+    pub ticks: u64,
+    pub primitives: BTreeMap<&'static str, u64>,
+    pub pdus: BTreeMap<String, PduCount>,
+    /// Calls a circuit was allocated for
+    pub calls_handled: Option<u64>,
+    /// MSs registered at the time of the summary
+    pub ms_registered: Option<usize>,
+    /// Successful registrations, including re-registrations
+    pub registrations: Option<u64>,
+    /// SDR RX overruns, TX underruns and TX re-initializations, from the stack state
+    pub sdr_rx_overruns: u64,
+    pub sdr_tx_underruns: u64,
+    pub sdr_tx_reinits: u64,
+    /// Jumps in the slot numbering of the RX/TX device, from the stack state
+    pub tdma_discontinuities: u64,
+}
+
+impl StackSummary {
+    /// Number of routed primitives named `name`
+    pub fn primitive_count(&self, name: &str) -> u64 {
+        self.primitives.get(name).copied().unwrap_or(0)
+    }
+}
+
+/// Collects the summary from the router and the entities registered in it
+pub fn stack_summary(router: &mut MessageRouter) -> StackSummary {
+    let stats = router.stats().clone();
+    let state = router.config().state_read().clone();
+    let calls_handled = router.get_entity(TetraEntity::Cmce)
+        .and_then(|e| e.as_any().downcast_ref::<CmceBs>())
+        .map(|cmce| cmce.circuits().calls_handled);
+    let mm = router.get_entity(TetraEntity::Mm)
+        .and_then(|e| e.as_any().downcast_ref::<MmBs>())
+        .map(|mm| (mm.client_mgr.clients().count(), mm.client_mgr.registrations()));
+
+    StackSummary {
+        ticks: stats.ticks,
+        primitives: stats.primitives,
+        pdus: stats.pdus,
+        calls_handled,
+        ms_registered: mm.map(|(registered, _)| registered),
+        registrations: mm.map(|(_, registrations)| registrations),
+        sdr_rx_overruns: state.sdr_rx_overruns,
+        sdr_tx_underruns: state.sdr_tx_underruns,
+        sdr_tx_reinits: state.sdr_tx_reinits,
+        tdma_discontinuities: state.tdma_discontinuities,
+    }
+}
+
+impl fmt::Display for StackSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Stack summary: {} ticks", self.ticks)?;
+        writeln!(f, "  primitives routed: {}", self.primitives.values().sum::<u64>())?;
+        for (name, count) in &self.primitives {
+            writeln!(f, "    {:<20} {}", name, count)?;
+        }
+        if let Some(calls) = self.calls_handled {
+            writeln!(f, "  calls handled: {}", calls)?;
+        }
+        if let (Some(registered), Some(registrations)) = (self.ms_registered, self.registrations) {
+            writeln!(f, "  MSs registered: {} ({} registrations)", registered, registrations)?;
+        }
+        if self.sdr_rx_overruns + self.sdr_tx_underruns + self.tdma_discontinuities > 0 {
+            writeln!(f, "  device errors: {} RX overruns, {} TX underruns ({} TX re-inits), {} TDMA discontinuities",
+                self.sdr_rx_overruns, self.sdr_tx_underruns, self.sdr_tx_reinits, self.tdma_discontinuities)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,186 @@
+use tetra_core::{BitBuffer, SsiType, TdmaTime, TetraAddress};
+
+
+#[derive(Debug, PartialEq)]
+pub enum AirEncryptionErr {
+    /// The ESI could not be mapped back to an SSI
+    UnknownEsi { esi: u32 },
+    /// No key is available to process traffic for this address
+    NoKey { ssi: u32 },
+    /// The PDU is encrypted but no cipher is installed to resolve the ESI and decrypt it
+    NoCipher { esi: u32 },
+}
+
+/// Air interface encryption (TEA) hook, EN 300 392-7 clause 6.
+/// The UMAC calls into this trait for MAC PDUs with the encrypted flag set. Implementations
+/// apply the keystream in place, from the current buffer position up to the end of the buffer,
+/// and map Encrypted Short Identities (ESIs) back to the SSIs they were derived from.
+pub trait AirEncryption: Send {
+    /// Whether encryption is active on this cell, i.e. a cipher with a loaded key is installed
+    fn is_active(&self) -> bool;
+
+    /// Encrypts the MAC SDU for the given (unencrypted) address in place
+    fn encrypt(&mut self, addr: &TetraAddress, time: TdmaTime, sdu: &mut BitBuffer) -> Result<(), AirEncryptionErr>;
+
+    /// Decrypts the MAC SDU for the given (unencrypted) address in place
+    fn decrypt(&mut self, addr: &TetraAddress, time: TdmaTime, sdu: &mut BitBuffer) -> Result<(), AirEncryptionErr>;
+
+    /// Maps an ESI back to the SSI it was derived from, and the type of that SSI
+    fn esi_to_ssi(&self, esi: u32, time: TdmaTime) -> Option<(u32, SsiType)>;
+}
+
+/// Encryption class 1 (no encryption): no cipher is installed, so encrypted PDUs and ESIs are passed through untouched
+#[derive(Debug, Default)]
+pub struct NullEncryption;
+
+impl AirEncryption for NullEncryption {
+    fn is_active(&self) -> bool {
+        false
+    }
+
+    fn encrypt(&mut self, _addr: &TetraAddress, _time: TdmaTime, _sdu: &mut BitBuffer) -> Result<(), AirEncryptionErr> {
+        Ok(())
+    }
+
+    fn decrypt(&mut self, _addr: &TetraAddress, _time: TdmaTime, _sdu: &mut BitBuffer) -> Result<(), AirEncryptionErr> {
+        Ok(())
+    }
+
+    fn esi_to_ssi(&self, _esi: u32, _time: TdmaTime) -> Option<(u32, SsiType)> {
+        None
+    }
+}
+
+/// Resolves an ESI to the plain SSI and its type. The address is returned unchanged, and thus
+/// still marked encrypted, if encryption is not active or the cipher does not know the ESI.
+pub fn resolve_esi(enc: &dyn AirEncryption, addr: TetraAddress, time: TdmaTime) -> TetraAddress {
+    if !addr.encrypted || !enc.is_active() {
+        return addr;
+    }
+    match enc.esi_to_ssi(addr.ssi, time) {
+        Some((ssi, ssi_type)) => TetraAddress { ssi, ssi_type, encrypted: false },
+        None => addr,
+    }
+}
+
+/// Resolves the ESI in `addr` and decrypts the SDU that follows it, returning the plain address.
+/// Unencrypted addresses and SDUs are passed through unchanged. An encrypted PDU can't be
+/// resolved without active encryption, so it is refused rather than passed up as plaintext.
+pub fn decrypt_addressed(enc: &mut dyn AirEncryption, addr: TetraAddress, time: TdmaTime, sdu: &mut BitBuffer) -> Result<TetraAddress, AirEncryptionErr> {
+    if !addr.encrypted {
+        return Ok(addr);
+    }
+    if !enc.is_active() {
+        return Err(AirEncryptionErr::NoCipher { esi: addr.ssi });
+    }
+
+    let plain = resolve_esi(enc, addr, time);
+    if plain.encrypted {
+        return Err(AirEncryptionErr::UnknownEsi { esi: addr.ssi });
+    }
+    enc.decrypt(&plain, time, sdu)?;
+    Ok(plain)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const KNOWN_ESI: u32 = 2040814 ^ 0xABCDEF;
+
+    /// Toy cipher: only KNOWN_ESI resolves, to ISSI 2040814, and SDU bits are inverted
+    struct XorEncryption;
+
+    impl AirEncryption for XorEncryption {
+        fn is_active(&self) -> bool {
+            true
+        }
+
+        fn encrypt(&mut self, addr: &TetraAddress, time: TdmaTime, sdu: &mut BitBuffer) -> Result<(), AirEncryptionErr> {
+            self.decrypt(addr, time, sdu)
+        }
+
+        fn decrypt(&mut self, _addr: &TetraAddress, _time: TdmaTime, sdu: &mut BitBuffer) -> Result<(), AirEncryptionErr> {
+            let pos = sdu.get_pos();
+            while sdu.get_len_remaining() > 0 {
+                let bit = sdu.read_bits(1).unwrap();
+                sdu.seek_rel(-1);
+                sdu.write_bits(bit ^ 1, 1);
+            }
+            sdu.seek(pos);
+            Ok(())
+        }
+
+        fn esi_to_ssi(&self, esi: u32, _time: TdmaTime) -> Option<(u32, SsiType)> {
+            (esi == KNOWN_ESI).then_some((esi ^ 0xABCDEF, SsiType::Issi))
+        }
+    }
+
+    #[test]
+    fn test_null_encryption_passthrough() {
+        let mut enc = NullEncryption;
+        let plain = TetraAddress { ssi: 2040814, ssi_type: SsiType::Issi, encrypted: false };
+        let mut sdu = BitBuffer::from_bitstr("0110100111");
+
+        // Unencrypted PDUs pass through untouched
+        let addr = decrypt_addressed(&mut enc, plain, TdmaTime::default(), &mut sdu).unwrap();
+        assert_eq!((addr.ssi, addr.ssi_type, addr.encrypted), (2040814, SsiType::Issi, false));
+        assert_eq!(sdu.to_bitstr(), "0110100111");
+
+        enc.encrypt(&addr, TdmaTime::default(), &mut sdu).unwrap();
+        assert_eq!(sdu.to_bitstr(), "0110100111");
+    }
+
+    #[test]
+    fn test_null_encryption_refuses_encrypted_pdu() {
+        let mut enc = NullEncryption;
+        let esi = TetraAddress { ssi: KNOWN_ESI, ssi_type: SsiType::Ssi, encrypted: true };
+        let mut sdu = BitBuffer::from_bitstr("0110100111");
+
+        // Without an installed cipher, the ESI can't be resolved nor the SDU decrypted
+        let err = decrypt_addressed(&mut enc, esi, TdmaTime::default(), &mut sdu).unwrap_err();
+        assert_eq!(err, AirEncryptionErr::NoCipher { esi: KNOWN_ESI });
+        assert_eq!(sdu.to_bitstr(), "0110100111");
+    }
+
+    #[test]
+    fn test_decrypt_clears_encrypted_flag() {
+        let mut enc = XorEncryption;
+        let esi = TetraAddress { ssi: KNOWN_ESI, ssi_type: SsiType::Ssi, encrypted: true };
+        let mut sdu = BitBuffer::from_bitstr("0110");
+
+        let plain = decrypt_addressed(&mut enc, esi, TdmaTime::default(), &mut sdu).unwrap();
+        assert_eq!(plain.ssi, 2040814);
+        assert_eq!(plain.ssi_type, SsiType::Issi);
+        assert!(!plain.encrypted);
+        assert_eq!(sdu.to_bitstr(), "1001");
+
+        // Unencrypted addresses and their SDUs are left alone
+        let again = decrypt_addressed(&mut enc, plain, TdmaTime::default(), &mut sdu).unwrap();
+        assert_eq!(again.ssi, 2040814);
+        assert_eq!(sdu.to_bitstr(), "1001");
+
+        // An unknown ESI can not be decrypted
+        let unknown = TetraAddress { ssi: 1234, ssi_type: SsiType::Ssi, encrypted: true };
+        assert_eq!(decrypt_addressed(&mut enc, unknown, TdmaTime::default(), &mut sdu).unwrap_err(), AirEncryptionErr::UnknownEsi { esi: 1234 });
+    }
+
+    #[test]
+    fn test_resolve_esi() {
+        let esi = TetraAddress { ssi: KNOWN_ESI, ssi_type: SsiType::Ssi, encrypted: true };
+
+        // Null cipher: no change
+        let addr = resolve_esi(&NullEncryption, esi, TdmaTime::default());
+        assert_eq!((addr.ssi, addr.ssi_type, addr.encrypted), (KNOWN_ESI, SsiType::Ssi, true));
+
+        // Stub cipher maps the known ESI to an ISSI
+        let addr = resolve_esi(&XorEncryption, esi, TdmaTime::default());
+        assert_eq!((addr.ssi, addr.ssi_type, addr.encrypted), (2040814, SsiType::Issi, false));
+
+        // Unknown ESIs remain encrypted
+        let unknown = TetraAddress { ssi: 1234, ..esi };
+        let addr = resolve_esi(&XorEncryption, unknown, TdmaTime::default());
+        assert_eq!((addr.ssi, addr.ssi_type, addr.encrypted), (1234, SsiType::Ssi, true));
+    }
+}
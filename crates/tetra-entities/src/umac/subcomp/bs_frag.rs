@@ -37,6 +37,16 @@ impl BsFragger {
         }
     }
 
+    /// Whether the MAC-RESOURCE was sent and only MAC-FRAG or MAC-END chunks remain
+    pub fn is_started(&self) -> bool {
+        self.mac_hdr_is_written
+    }
+
+    /// SSI the resource is addressed to
+    pub fn ssi(&self) -> Option<u32> {
+        self.resource.addr.map(|addr| addr.ssi)
+    }
+
     /// Writes MAC-RESOURCE to dest_buf, starting fragmentation if needed. 
     /// Then, writes as many SDU bits as possible. 
     /// Returns true if the entire SDU was consumed, false if the PDU is fragmented
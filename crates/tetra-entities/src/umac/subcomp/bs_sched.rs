@@ -1,9 +1,12 @@
-use tetra_core::{BitBuffer, Direction, PhyBlockNum, PhysicalChannel, TdmaTime, TetraAddress, Todo, unimplemented_log};
+use tetra_core::{BitBuffer, DEFAULT_MAX_PATH_DELAY_SYMBOLS, Direction, PhyBlockNum, PhysicalChannel, TdmaTime, TetraAddress, Todo, unimplemented_log};
 use tetra_saps::{control::call_control::Circuit, tmv::{TmvUnitdataReq, TmvUnitdataReqSlot, enums::logical_chans::LogicalChannel}};
 
 use tetra_pdus::{mle::pdus::{d_mle_sync::DMleSync, d_mle_sysinfo::DMleSysinfo}, umac::{enums::{access_assign_dl_usage::AccessAssignDlUsage, access_assign_ul_usage::AccessAssignUlUsage, basic_slotgrant_cap_alloc::BasicSlotgrantCapAlloc, basic_slotgrant_granting_delay::BasicSlotgrantGrantingDelay, reservation_requirement::ReservationRequirement}, fields::basic_slotgrant::BasicSlotgrant, pdus::{access_assign::{AccessAssign, AccessField}, access_assign_fr18::AccessAssignFr18, mac_resource::MacResource, mac_sync::MacSync, mac_sysinfo::MacSysinfo}}};
 
-use crate::{lmac::components::scrambler, phy::components::burst_consts::{NDB_BBK1_BITS, NDB_BITS}, umac::subcomp::{bs_frag::BsFragger, circuit_mgr::CircuitMgr}};
+use tetra_config::CfgPowerControl;
+
+use crate::{lmac::components::scrambler, phy::components::burst_consts::{NDB_BBK1_BITS, NDB_BITS}, umac::subcomp::{bs_frag::BsFragger, circuit_mgr::CircuitMgr, power_ctrl::PowerControl, round_robin::DlRoundRobin, tch_assignment::{TchAssignErr, TchAssignment, TchAssignmentMap}}};
+use crate::umac::umac_bs::UmacBs;
 
 
 /// We submit this many TX timeslots ahead of the current time
@@ -27,6 +30,36 @@ pub struct PrecomputedUmacPdus {
     pub mle_sync: DMleSync,
 }
 
+/// Reservation of a main carrier timeslot for common control signalling
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SlotReservation {
+    /// Main control channel
+    Mcch,
+    /// Common secondary control channel
+    Scch,
+    /// Not reserved, may be allocated to traffic
+    Unreserved,
+}
+
+impl SlotReservation {
+    pub fn is_signalling(self) -> bool {
+        self != SlotReservation::Unreserved
+    }
+}
+
+/// Builds the main carrier slot table for the given MCCH timeslot (1-4).
+/// If secondary control channels are active, a common SCCH is placed on the timeslot following the MCCH.
+pub fn build_slot_table(mcch_timeslot: u8, secondary_control_channels: bool) -> [SlotReservation; 4] {
+    assert!((1..=4).contains(&mcch_timeslot), "invalid mcch timeslot {}", mcch_timeslot);
+    let mut table = [SlotReservation::Unreserved; 4];
+    let mcch_index = mcch_timeslot as usize - 1;
+    table[mcch_index] = SlotReservation::Mcch;
+    if secondary_control_channels {
+        table[(mcch_index + 1) % 4] = SlotReservation::Scch;
+    }
+    table
+}
+
 #[derive(Debug)]
 pub struct TimeslotSchedule {
     pub ul1: Option<u32>,
@@ -40,14 +73,43 @@ pub struct BsChannelScheduler {
     pub cur_dltime: TdmaTime,
     scrambling_code: u32,
     precomps: PrecomputedUmacPdus,
+    /// Signalling reservation per timeslot, see build_slot_table
+    slot_table: [SlotReservation; 4],
     /// Collect dltx traffic here that can't be sent this slot. 
     /// Swapped back into the dltx_queues method at the end of the tick. 
     dltx_next_slot_queue: Vec<DlSchedElem>,
     /// Four queues for scheduled downlink traffic, one per timeslot
     dltx_queues: [Vec<DlSchedElem>; 4],
+    /// Order in which the addresses queued on each timeslot are served
+    dltx_round_robin: [DlRoundRobin; 4],
     ulsched: [[TimeslotSchedule; MACSCHED_NUM_FRAMES]; 4],
 
     circuits: CircuitMgr,
+    /// Call and SSI each traffic timeslot is assigned to
+    tch_assignments: TchAssignmentMap,
+
+    /// Uplink power control commands, piggybacked on the next MAC-RESOURCE for an MS
+    power_ctrl: PowerControl,
+
+    /// SYNC and SYSINFO are broadcast in one out of every this many multiframes
+    sync_period: u8,
+    sysinfo_period: u8,
+
+    /// Frames in which the BS does not transmit, see CfgCellInfo::tx_off_frames
+    tx_off_frames: Vec<u8>,
+    /// SYNC and SYSINFO are only broadcast on the MCCH in frame 18, see CfgCellInfo::minimum_mode
+    minimum_mode: bool,
+
+    /// Largest TM-SDU accepted for the downlink, see CfgCellInfo::max_tm_sdu_bits
+    max_tm_sdu_bits: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MacError {
+    /// TM-SDU of `len` bits exceeds the `max` bits the MAC sends, even when fragmented
+    LengthMismatch { len: usize, max: usize },
+    /// Received block failed its CRC check, e.g. corrupted by noise
+    CrcFail,
 }
 
 #[derive(Debug)]
@@ -72,6 +134,18 @@ pub enum DlSchedElem {
     // Traffic(BitBuffer), 
 }
 
+impl DlSchedElem {
+    /// SSI the element is addressed to, if any
+    fn ssi(&self) -> Option<u32> {
+        match self {
+            DlSchedElem::RandomAccessAck(addr) | DlSchedElem::Grant(addr, _) => Some(addr.ssi),
+            DlSchedElem::Resource(pdu, _) => pdu.addr.map(|addr| addr.ssi),
+            DlSchedElem::FragBuf(fragger) => fragger.ssi(),
+            DlSchedElem::Broadcast(_) => None,
+        }
+    }
+}
+
 const EMPTY_SCHED_ELEM: TimeslotSchedule = TimeslotSchedule {
     ul1: None,
     ul2: None,
@@ -81,16 +155,25 @@ const EMPTY_SCHED_CHANNEL: [TimeslotSchedule; MACSCHED_NUM_FRAMES] = [EMPTY_SCHE
 const EMPTY_SCHED: [[TimeslotSchedule; MACSCHED_NUM_FRAMES]; 4] = [EMPTY_SCHED_CHANNEL; 4];
 
 impl BsChannelScheduler {
-    pub fn new(scrambling_code: u32, precomps: PrecomputedUmacPdus) -> Self {
+    pub fn new(scrambling_code: u32, precomps: PrecomputedUmacPdus, slot_table: [SlotReservation; 4]) -> Self {
         
         BsChannelScheduler {
             cur_dltime: TdmaTime {t: 0, f: 0, m: 0, h: 0}, // Intentionally invalid, updated in tick function
             scrambling_code: scrambling_code,
             precomps,
+            slot_table,
             dltx_next_slot_queue: Vec::new(),
             dltx_queues: [Vec::new(), Vec::new(), Vec::new(), Vec::new()],
+            dltx_round_robin: Default::default(),
             ulsched: EMPTY_SCHED,
             circuits: CircuitMgr::new(),
+            tch_assignments: TchAssignmentMap::new(slot_table),
+            power_ctrl: PowerControl::new(None, DEFAULT_MAX_PATH_DELAY_SYMBOLS),
+            sync_period: 1,
+            sysinfo_period: 1,
+            tx_off_frames: Vec::new(),
+            minimum_mode: false,
+            max_tm_sdu_bits: usize::MAX,
         }
     }
 
@@ -104,6 +187,89 @@ impl BsChannelScheduler {
     //     unimplemented!("need to refresh some msgs possibly");
     // }
 
+    /// Updates the cell load broadcast in D-MLE-SYNC
+    pub fn set_cell_load_ca(&mut self, cell_load_ca: u8) {
+        self.precomps.mle_sync.cell_load_ca = cell_load_ca;
+    }
+
+    /// Sets the broadcast cadence of SYNC and SYSINFO, in multiframes
+    pub fn set_broadcast_periods(&mut self, sync_period: u8, sysinfo_period: u8) {
+        assert!(sync_period >= 1 && sysinfo_period >= 1);
+        self.sync_period = sync_period;
+        self.sysinfo_period = sysinfo_period;
+    }
+
+    /// Sets the frames (1-17) in which nothing is scheduled on the downlink
+    pub fn set_tx_off_frames(&mut self, frames: Vec<u8>) {
+        assert!(frames.iter().all(|f| (1..=17).contains(f)));
+        self.tx_off_frames = frames;
+    }
+
+    /// Confines SYNC and SYSINFO on the MCCH to frame 18, for cells that may use minimum mode
+    pub fn set_minimum_mode(&mut self, enabled: bool) {
+        self.minimum_mode = enabled;
+    }
+
+    /// Sets the largest TM-SDU accepted by dl_enqueue_tma
+    pub fn set_max_tm_sdu_bits(&mut self, max_bits: usize) {
+        self.max_tm_sdu_bits = max_bits;
+    }
+
+    /// Whether the BS does not transmit in the frame of `ts`
+    pub fn is_tx_off(&self, ts: TdmaTime) -> bool {
+        self.tx_off_frames.contains(&ts.f)
+    }
+
+    /// Whether SYNC is broadcast at `ts`, given its multiframe and frame
+    pub fn sync_due(&self, ts: TdmaTime) -> bool {
+        !self.is_minimum_mode_mcch(ts) && broadcast_due(self.sync_period, ts)
+    }
+
+    /// Whether SYSINFO is broadcast at `ts`, given its multiframe and frame
+    pub fn sysinfo_due(&self, ts: TdmaTime) -> bool {
+        !self.is_minimum_mode_mcch(ts) && broadcast_due(self.sysinfo_period, ts)
+    }
+
+    /// Whether `ts` is on the MCCH in frames 1-17 of a cell that may use minimum mode. MSs
+    /// in minimum mode only receive the MCCH in frame 18, so no broadcast is sent there.
+    fn is_minimum_mode_mcch(&self, ts: TdmaTime) -> bool {
+        self.minimum_mode && ts.f != 18 && self.slot_table[ts.t as usize - 1] == SlotReservation::Mcch
+    }
+
+    /// Whether the extended services broadcast may be sent in SYSINFO at `ts`: on frame 18
+    /// if the frame 18 extension is enabled, and throughout on cells with air interface
+    /// encryption, as it carries their security class
+    pub fn ext_services_due(&self, ts: TdmaTime) -> bool {
+        self.precomps.mle_sysinfo.bs_service_details.aie_service
+            || (ts.f == 18 && self.precomps.mac_sync.frame_18_ext)
+    }
+
+    /// Sets the target RSSI window for uplink power control, or disables it, and the
+    /// largest burst arrival delay tolerated before the maximum path delay is exceeded
+    pub fn set_power_control(&mut self, window: Option<CfgPowerControl>, max_path_delay_symbols: f32) {
+        self.power_ctrl = PowerControl::new(window, max_path_delay_symbols);
+    }
+
+    /// Registers the signal strength of a burst received from an MS, for power control
+    pub fn ul_report_rssi(&mut self, ssi: u32, rssi_dbm: f32) {
+        self.power_ctrl.report_rssi(ssi, rssi_dbm);
+    }
+
+    /// Registers the arrival delay of a burst received from an MS, in symbols.
+    /// Call after ul_report_rssi for the same burst.
+    pub fn ul_report_path_delay(&mut self, ssi: u32, delay_symbols: f32) {
+        self.power_ctrl.report_path_delay(ssi, delay_symbols);
+    }
+
+    pub fn slot_table(&self) -> &[SlotReservation; 4] {
+        &self.slot_table
+    }
+
+    /// Whether the given timeslot (1-4) is reserved for common control signalling
+    pub fn is_signalling_slot(&self, ts: u8) -> bool {
+        self.slot_table[ts as usize - 1].is_signalling()
+    }
+
     /// Fully wipe the schedule
     pub fn purge_schedule(&mut self) {
         self.dltx_queues = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
@@ -304,10 +470,16 @@ impl BsChannelScheduler {
         self.dltx_queues[ts as usize - 1].push(elem);
     }
 
-    pub fn dl_enqueue_tma(&mut self, ts: u8, pdu: MacResource, sdu: BitBuffer) {
+    /// Enqueues a MAC-RESOURCE with its TM-SDU. SDUs that do not fit a single slot are
+    /// fragmented when scheduled, SDUs above the configured maximum are refused.
+    pub fn dl_enqueue_tma(&mut self, ts: u8, pdu: MacResource, sdu: BitBuffer) -> Result<(), MacError> {
+        if sdu.get_len() > self.max_tm_sdu_bits {
+            return Err(MacError::LengthMismatch { len: sdu.get_len(), max: self.max_tm_sdu_bits });
+        }
         tracing::debug!("dl_enqueue_tma: ts {} enqueueing PDU {:?} SDU {}", ts, pdu, sdu.dump_bin());
         let elem = DlSchedElem::Resource(pdu, sdu);
         self.dltx_queues[ts as usize - 1].push(elem);
+        Ok(())
     }
 
     fn dl_enqueue_tma_frag_next_frame(&mut self, fragger: BsFragger) {
@@ -340,6 +512,27 @@ impl BsChannelScheduler {
         self.circuits.create_circuit(dir, circuit);
     }
 
+    pub fn tch_assignments(&self) -> &TchAssignmentMap {
+        &self.tch_assignments
+    }
+
+    /// Assigns the timeslot of `circuit` to its call, unless it is reserved for signalling
+    /// or carries another call
+    pub fn assign_tch(&mut self, circuit: &Circuit) -> Result<(), TchAssignErr> {
+        self.tch_assignments.assign(circuit.call_id, circuit.ssi, circuit.ts)
+    }
+
+    /// Releases the assignment of timeslot `ts` once no circuit is left on it.
+    /// Returns the released assignment, if any.
+    pub fn release_tch_if_idle(&mut self, ts: u8) -> Option<TchAssignment> {
+        if self.circuits.is_active(Direction::Dl, ts) || self.circuits.is_active(Direction::Ul, ts) {
+            return None;
+        }
+        let assignment = self.tch_assignments.release(ts)?;
+        tracing::debug!("released ts {} from call id {} (ssi {})", ts, assignment.call_id, assignment.ssi);
+        Some(assignment)
+    }
+
     /// Takes a block or None value.
     /// If block is present and some signalling channel, and space is available, 
     /// adds a trailing Null PDU. 
@@ -507,7 +700,12 @@ impl BsChannelScheduler {
                             unimplemented_log!("finalize_ts_for_tick: Broadcast scheduling not implemented");
                         },
 
-                        DlSchedElem::Resource(pdu, sdu) => {
+                        DlSchedElem::Resource(mut pdu, sdu) => {
+                            // Attach pending power control command for the addressed MS
+                            if let Some(addr) = pdu.addr
+                                && pdu.power_control_element.is_none() {
+                                pdu.power_control_element = self.power_ctrl.take_element(addr.ssi);
+                            }
                             // Allocate bitbuf if not already done
                             let mut buf = buf_opt.unwrap_or_else(|| BitBuffer::new(SCH_F_CAP));
                             // Create fragger, either to send the whole PDU or to start fragmentation
@@ -549,6 +747,8 @@ impl BsChannelScheduler {
             assert!(a.is_empty(), "queue should be empty");
             std::mem::swap(a, b);
         }
+        let queue = &self.dltx_queues[ts.t as usize - 1];
+        self.dltx_round_robin[ts.t as usize - 1].retain(|ssi| queue.iter().any(|e| e.ssi() == Some(ssi)));
 
         buf_opt
     }
@@ -583,35 +783,42 @@ impl BsChannelScheduler {
 
     /// Return first queued grant. 
     /// If none; return first in-progress fragmented message. 
-    /// If none; return first to-be-transmitted resource. 
+    /// If none; return the first resource, or fragmented message not started yet, for the
+    /// address least recently served on this timeslot.
     /// If none, return None.
     pub fn dl_take_prioritized_sched_item(&mut self, ts: TdmaTime) -> Option<DlSchedElem> {
 
-        if ts.f == 18 {
-            // No resources on frame 18
+        if ts.f == 18 || self.is_tx_off(ts) {
+            // No resources on frame 18, nor in frames we do not transmit in
             return None;
         }
 
         // Map 1-based ts to 0-based index, bail on 0 or out of range.
         let slot = ts.t as usize - 1;
         let q = self.dltx_queues.get_mut(slot).unwrap();
+        let rr = &mut self.dltx_round_robin[slot];
 
         // Return grants first
         if let Some(i) = q.iter().position(|e| matches!(e, DlSchedElem::Grant(_, _))) {
             return Some(q.remove(i));
         }
 
-        // Return FragBufs next
-        if let Some(i) = q.iter().position(|e| matches!(e, DlSchedElem::FragBuf(_))) {
-            return Some(q.remove(i));
-        }
-
-        // Return Resources last
-        if let Some(i) = q.iter().position(|e| matches!(e, DlSchedElem::Resource(_, _))) {
-            return Some(q.remove(i));
+        // Return FragBufs next, so a started PDU is completed first
+        let i = q.iter().position(|e| matches!(e, DlSchedElem::FragBuf(f) if f.is_started()))
+            .or_else(|| {
+                // Pending resources last, taking turns between addresses
+                let pending = q.iter().enumerate()
+                    .filter(|(_, e)| matches!(e, DlSchedElem::Resource(_, _) | DlSchedElem::FragBuf(_)))
+                    .map(|(i, e)| (i, e.ssi()))
+                    .collect::<Vec<_>>();
+                rr.pick(pending.iter().map(|(_, ssi)| *ssi)).map(|p| pending[p].0)
+            })?;
+
+        let elem = q.remove(i);
+        if let Some(ssi) = elem.ssi() {
+            rr.served(ssi);
         }
-
-        None
+        Some(elem)
     }
 
     pub fn tick_start(&mut self, ts: TdmaTime) {
@@ -631,9 +838,16 @@ impl BsChannelScheduler {
         let ts = self.cur_dltime.add_timeslots(MACSCHED_TX_AHEAD as i32);
         self.precomps.mac_sync.time = ts;
         self.precomps.mac_sysinfo1.hyperframe_number = Some(ts.h);
-        self.precomps.mac_sysinfo2.hyperframe_number = Some(ts.h);
+        if self.precomps.mac_sysinfo2.cck_id.is_none() {
+            self.precomps.mac_sysinfo2.hyperframe_number = Some(ts.h);
+        }
 
-        let dl_is_traffic = self.circuits.is_active(Direction::Dl, ts.t) && ts.f != 18;
+        // Traffic for a frame we do not transmit in is dropped, so the circuit does not fall behind
+        let tx_off = self.is_tx_off(ts);
+        if tx_off && self.circuits.is_active(Direction::Dl, ts.t) {
+            self.circuits.take_block(ts.t);
+        }
+        let dl_is_traffic = self.circuits.is_active(Direction::Dl, ts.t) && ts.f != 18 && !tx_off;
 
         // Build the block for this timeslot with anything scheduled (traffic or signalling)
         let (buf_opt, logical_chan) = if dl_is_traffic {
@@ -703,27 +917,32 @@ impl BsChannelScheduler {
         let blk1_lchan = elem.blk1.as_ref().unwrap().logical_channel;
         assert!(blk1_lchan != LogicalChannel::Stch, "unimplemented");
 
-        // Populate blk2 with SYSINFO if blk1 is half-slot
+        // Populate blk2 with SYSINFO if blk1 is half-slot, or with a Null pdu if SYSINFO is not due
         if elem.blk2.is_none() && (blk1_lchan == LogicalChannel::Bsch || blk1_lchan == LogicalChannel::SchHd || blk1_lchan == LogicalChannel::Stch) {
             
             // Check blk1 is indeed short (124 for half-slot or 60 for SYNC)
             assert!(elem.blk1.as_ref().unwrap().mac_block.get_len() <= 124);
             
-            let mut buf = BitBuffer::new(124);
+            let mut buf = BitBuffer::new(SCH_HD_CAP);
             
-            // Write MAC-SYSINFO (alternating sysinfo1/sysinfo2), followed by MLE-SYSINFO
-            if ts.t % 2 == 1 {
-                self.precomps.mac_sysinfo1.to_bitbuf(&mut buf);
+            if self.sysinfo_due(ts) {
+                // Write MAC-SYSINFO, followed by MLE-SYSINFO. Where the extended services are
+                // broadcast, even timeslots carry them instead of the access code A definition.
+                if self.ext_services_due(ts) && ts.t.is_multiple_of(2) {
+                    self.precomps.mac_sysinfo2.to_bitbuf(&mut buf);
+                } else {
+                    self.precomps.mac_sysinfo1.to_bitbuf(&mut buf);
+                }
+                self.precomps.mle_sysinfo.to_bitbuf(&mut buf);
+
+                elem.blk2 = Some(TmvUnitdataReq {
+                    logical_channel: LogicalChannel::Bnch,
+                    mac_block: buf,
+                    scrambling_code: self.scrambling_code,
+                })
             } else {
-                self.precomps.mac_sysinfo2.to_bitbuf(&mut buf);
+                elem.blk2 = Some(UmacBs::build_null_pdu(LogicalChannel::SchHd, self.scrambling_code));
             }
-            self.precomps.mle_sysinfo.to_bitbuf(&mut buf);
-
-            elem.blk2 = Some(TmvUnitdataReq {
-                logical_channel: LogicalChannel::Bnch,
-                mac_block: buf,
-                scrambling_code: self.scrambling_code,
-            })
         } else {
             // We're done, no blk2 needed. Just a quick sanity check blk1 indeed fills both half slots
             assert!(elem.blk1.as_ref().unwrap().mac_block.get_len() >= 268, "blk1 is long, but blk2 is set!");
@@ -774,10 +993,10 @@ impl BsChannelScheduler {
             
             let mut aach = AccessAssign::default();
             
-            match ts.t {
-                1 => {
-                    assert!(dl_traffic_usage.is_none(), "DL ts 1 can't be traffic");
-                    assert!(ul_traffic_usage.is_none(), "UL ts 1 can't be traffic (is this allowed?"); // TODO FIXME check spec
+            match self.slot_table[ts.t as usize - 1] {
+                SlotReservation::Mcch | SlotReservation::Scch => {
+                    assert!(dl_traffic_usage.is_none(), "DL ts {} is reserved for signalling, can't be traffic", ts.t);
+                    assert!(ul_traffic_usage.is_none(), "UL ts {} is reserved for signalling, can't be traffic", ts.t);
 
                     // STRATEGY:
                     // - Send UL AssignedOnly if both ul1 and ul2 has been granted to an MS
@@ -809,7 +1028,7 @@ impl BsChannelScheduler {
                         }
                     }
                 },
-                2..=4 => {
+                SlotReservation::Unreserved => {
                     // Additional channels, unallocated except we sent a chanalloc
                     // Those are currently unimplemented, so, unallocated it is
                     
@@ -824,7 +1043,6 @@ impl BsChannelScheduler {
                         AccessAssignUlUsage::Unallocated
                     };
                 },
-                _ => panic!("finalize_ts_for_tick: invalid timeslot {}", ts.t),
             }
             
             aach.to_bitbuf(&mut aach_bb);
@@ -857,39 +1075,25 @@ impl BsChannelScheduler {
 
     fn generate_default_blks(&self, ts: TdmaTime) -> TmvUnitdataReq {
         
-        match (ts.f, ts.t) {
-            (1..=17, 1) => {
+        match (ts.f, self.is_signalling_slot(ts.t)) {
+            (1..=17, true) => {
                 // Two options: [Blk1: Null | Blk2: SYSINFO] or [Both: Null]
                 // We'll alternate based on multiframe
                 match ts.m % 2 {
                     0 => {
                         // Null + SYSINFO
                         // SYSINFO gets added later, su we just make a half-slot Null pdu here
-                        let mut buf1 = BitBuffer::new(SCH_F_CAP);
-                        let blk1 = MacResource::null_pdu();
-                        blk1.to_bitbuf(&mut buf1);
-                        TmvUnitdataReq {
-                            logical_channel: LogicalChannel::SchF,
-                            mac_block: buf1,
-                            scrambling_code: self.scrambling_code,
-                        }
+                        UmacBs::build_null_pdu(LogicalChannel::SchF, self.scrambling_code)
                     },
                     1 => {
                         // Full-slot Null pdu
-                        let mut buf = BitBuffer::new(SCH_F_CAP);
-                        let blk = MacResource::null_pdu();
-                        blk.to_bitbuf(&mut buf);
-                        TmvUnitdataReq {
-                            logical_channel: LogicalChannel::SchF,
-                            mac_block: buf,
-                            scrambling_code: self.scrambling_code,
-                        }
+                        UmacBs::build_null_pdu(LogicalChannel::SchF, self.scrambling_code)
                     },
                     _ => panic!(), // never happens
                 }
             },
-            (1..=17, 2..=4) |
-            (18, _) => { 
+            (1..=17, false) |
+            (18, _) if self.sync_due(ts) => { 
                 // SYNC + SYSINFO (added later)
                 let mut buf = BitBuffer::new(60);
                 self.precomps.mac_sync.to_bitbuf(&mut buf);
@@ -900,6 +1104,16 @@ impl BsChannelScheduler {
                     scrambling_code: scrambler::SCRAMB_INIT,
                 }
             },
+            (1..=17, false) |
+            (18, _) if self.sysinfo_due(ts) => {
+                // No SYNC in this multiframe: half-slot Null + SYSINFO (added later)
+                UmacBs::build_null_pdu(LogicalChannel::SchHd, self.scrambling_code)
+            },
+            (1..=17, false) |
+            (18, _) => {
+                // Neither SYNC nor SYSINFO in this multiframe: full-slot Null pdu
+                UmacBs::build_null_pdu(LogicalChannel::SchF, self.scrambling_code)
+            },
             _ => panic!() // never happens
         }
     }
@@ -955,6 +1169,11 @@ impl BsChannelScheduler {
     }
 }
 
+/// Whether a broadcast repeated every `period` multiframes falls in the multiframe of `ts`
+fn broadcast_due(period: u8, ts: TdmaTime) -> bool {
+    (ts.h as u32 * 60 + ts.m as u32 - 1).is_multiple_of(period as u32)
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -963,6 +1182,13 @@ mod tests {
 
     use tetra_pdus::{mle::{fields::bs_service_details::BsServiceDetails, pdus::{d_mle_sync::DMleSync, d_mle_sysinfo::DMleSysinfo}}, umac::{enums::sysinfo_opt_field_flag::SysinfoOptFieldFlag, fields::{sysinfo_default_def_for_access_code_a::SysinfoDefaultDefForAccessCodeA, sysinfo_ext_services::SysinfoExtendedServices}, pdus::{mac_sync::MacSync, mac_sysinfo::MacSysinfo}}};
 
+    use tetra_config::{PhyBackend, SharedConfig, StackConfig, StackMode};
+
+    use tetra_pdus::umac::pdus::{mac_end_dl::MacEndDl, mac_frag_dl::MacFragDl};
+
+    use crate::umac::subcomp::power_ctrl;
+    use crate::umac::umac_bs::UmacBs;
+
     use super::*;
 
     pub fn get_testing_slotter() -> BsChannelScheduler {
@@ -1077,7 +1303,7 @@ mod tests {
             mle_sync: mle_sync_pdu,
         }; 
         
-        let mut sched = BsChannelScheduler::new(1, precomps);
+        let mut sched = BsChannelScheduler::new(1, precomps, build_slot_table(1, false));
         sched.set_dl_time(TdmaTime::default().add_timeslots(2));
         sched
     }
@@ -1159,7 +1385,7 @@ mod tests {
         let addr = TetraAddress { encrypted: false, ssi_type: SsiType::Issi, ssi: 1234 };
         let pdu = BsChannelScheduler::dl_make_minimal_resource(&addr, None, false);
         let sdu = BitBuffer::new(0);
-        sched.dl_enqueue_tma(ts.t, pdu, sdu);
+        sched.dl_enqueue_tma(ts.t, pdu, sdu).unwrap();
 
         let grant = BasicSlotgrant {
             capacity_allocation: BasicSlotgrantCapAlloc::FirstSubslotGranted,
@@ -1184,10 +1410,172 @@ mod tests {
 
     }
 
-    // #[test]
-    // fn test_downlink_fragmentation() {
-    //     unimplemented!("write tests for downlink fragmentation")
-    // }
+    /// Acknowledges a random access from an MS and returns the MAC-RESOURCE sent to it
+    fn send_ack_resource(sched: &mut BsChannelScheduler, ts: TdmaTime, ssi: u32) -> MacResource {
+        let addr = TetraAddress { encrypted: false, ssi_type: SsiType::Issi, ssi };
+        sched.dl_enqueue_random_access_ack(ts.t, addr);
+        sched.dl_integrate_sched_elems_for_timeslot(ts);
+        let mut blk = sched.dl_build_block_from_signalling_schedule(ts).unwrap();
+        blk.seek(0);
+        let pdu = MacResource::from_bitbuf(&mut blk).unwrap();
+        assert_eq!(pdu.addr.unwrap().ssi, ssi);
+        pdu
+    }
+
+    #[test]
+    fn test_power_control_in_next_resource() {
+        let mut sched = get_testing_slotter();
+        sched.set_power_control(Some(CfgPowerControl { rssi_min_dbm: -95.0, rssi_max_dbm: -75.0 }), DEFAULT_MAX_PATH_DELAY_SYMBOLS);
+        let ts = TdmaTime::default();
+
+        // Too strong: power down
+        sched.ul_report_rssi(1001, -60.0);
+        let pdu = send_ack_resource(&mut sched, ts, 1001);
+        assert_eq!(pdu.power_control_element, Some(power_ctrl::PCE_DECREASE_1 + 2));
+
+        // Too weak: power up
+        sched.ul_report_rssi(1002, -103.0);
+        let pdu = send_ack_resource(&mut sched, ts, 1002);
+        assert_eq!(pdu.power_control_element, Some(power_ctrl::PCE_INCREASE_1 + 1));
+
+        // Within the window, or command already sent: no element
+        sched.ul_report_rssi(1003, -80.0);
+        assert_eq!(send_ack_resource(&mut sched, ts, 1003).power_control_element, None);
+        assert_eq!(send_ack_resource(&mut sched, ts, 1001).power_control_element, None);
+
+        // Burst arriving beyond the maximum path delay
+        sched.ul_report_rssi(1004, -80.0);
+        sched.ul_report_path_delay(1004, 8.0);
+        let pdu = send_ack_resource(&mut sched, ts, 1004);
+        assert_eq!(pdu.power_control_element, Some(power_ctrl::PCE_MAX_PATH_DELAY_EXCEEDED));
+    }
+
+    #[test]
+    fn test_no_signalling_in_tx_off_frames() {
+        let mut sched = get_testing_slotter();
+        sched.set_tx_off_frames(vec![2]);
+        let addr = TetraAddress { encrypted: false, ssi_type: SsiType::Issi, ssi: 1234 };
+        let off = TdmaTime { t: 1, f: 2, m: 1, h: 0 };
+        sched.dl_enqueue_random_access_ack(off.t, addr);
+
+        // Held back in the off frame, sent in the next one
+        sched.dl_integrate_sched_elems_for_timeslot(off);
+        assert!(sched.dl_build_block_from_signalling_schedule(off).is_none());
+        let next = off.add_timeslots(4);
+        assert!(sched.dl_build_block_from_signalling_schedule(next).is_some());
+    }
+
+    #[test]
+    fn test_configured_mcch_slot_reserved() {
+        let mut cfg = StackConfig::new(StackMode::Bs, 204, 1337);
+        cfg.phy_io.backend = PhyBackend::None;
+        cfg.cell.mcch_timeslot = 3;
+        cfg.cell.secondary_control_channels = true;
+        assert!(cfg.validate().is_ok());
+        let umac = UmacBs::new(SharedConfig::from_config(cfg));
+        let sched = &umac.channel_scheduler;
+
+        assert_eq!(sched.slot_table(), &[
+            SlotReservation::Unreserved,
+            SlotReservation::Unreserved,
+            SlotReservation::Mcch,
+            SlotReservation::Scch,
+        ]);
+        assert!(sched.is_signalling_slot(3));
+        assert!(!sched.is_signalling_slot(1));
+
+        // The MCCH carries signalling on frames 1-17, while unreserved timeslots get SYNC
+        let ts = TdmaTime { t: 3, f: 5, m: 1, h: 0 };
+        assert_eq!(sched.generate_default_blks(ts).logical_channel, LogicalChannel::SchF);
+        let ts = TdmaTime { t: 1, f: 5, m: 1, h: 0 };
+        assert_eq!(sched.generate_default_blks(ts).logical_channel, LogicalChannel::Bsch);
+    }
+
+    #[test]
+    fn test_minimum_mode_mcch_broadcasts() {
+        let mut cfg = StackConfig::new(StackMode::Bs, 204, 1337);
+        cfg.phy_io.backend = PhyBackend::None;
+        cfg.cell.no_minimum_mode = true;
+        let mut sched = UmacBs::new(SharedConfig::from_config(cfg)).channel_scheduler;
+        let mcch_f5 = TdmaTime { t: 1, f: 5, m: 1, h: 0 };
+        let mcch_f18 = TdmaTime { t: 1, f: 18, m: 1, h: 0 };
+        let traffic_f5 = TdmaTime { t: 2, f: 5, m: 1, h: 0 };
+        for ts in [mcch_f5, mcch_f18, traffic_f5] {
+            assert!(sched.sync_due(ts) && sched.sysinfo_due(ts));
+        }
+
+        // A cell that may use minimum mode keeps the MCCH broadcasts to frame 18
+        sched.set_minimum_mode(true);
+        assert!(!sched.sync_due(mcch_f5) && !sched.sysinfo_due(mcch_f5));
+        for ts in [mcch_f18, traffic_f5] {
+            assert!(sched.sync_due(ts) && sched.sysinfo_due(ts));
+        }
+    }
+
+    #[test]
+    fn test_downlink_fragmentation() {
+        let mut sched = get_testing_slotter();
+        sched.set_max_tm_sdu_bits(1024);
+        let addr = TetraAddress { encrypted: false, ssi_type: SsiType::Issi, ssi: 1234 };
+        let mut ts = TdmaTime { t: 1, f: 1, m: 1, h: 0 };
+
+        // Over the limit: refused rather than silently dropped by the scheduler
+        let pdu = BsChannelScheduler::dl_make_minimal_resource(&addr, None, false);
+        let res = sched.dl_enqueue_tma(ts.t, pdu, BitBuffer::new(1025));
+        assert_eq!(res, Err(MacError::LengthMismatch { len: 1025, max: 1024 }));
+        assert!(sched.dltx_queues[ts.t as usize - 1].is_empty());
+
+        // Longer than a slot: fragmented over MAC-RESOURCE, MAC-FRAG and MAC-END
+        let pdu = BsChannelScheduler::dl_make_minimal_resource(&addr, None, false);
+        sched.dl_enqueue_tma(ts.t, pdu, BitBuffer::new(600)).unwrap();
+
+        let mut blk = sched.dl_build_block_from_signalling_schedule(ts).unwrap();
+        blk.seek(0);
+        let pdu = MacResource::from_bitbuf(&mut blk).unwrap();
+        assert_eq!(pdu.length_ind, 0b111111, "fragmentation should be started");
+        assert_eq!(pdu.addr.unwrap().ssi, 1234);
+
+        ts = ts.add_timeslots(4);
+        let mut blk = sched.dl_build_block_from_signalling_schedule(ts).unwrap();
+        blk.seek(0);
+        assert!(MacFragDl::from_bitbuf(&mut blk).is_ok());
+
+        ts = ts.add_timeslots(4);
+        let mut blk = sched.dl_build_block_from_signalling_schedule(ts).unwrap();
+        blk.seek(0);
+        assert!(MacEndDl::from_bitbuf(&mut blk).is_ok());
+        assert!(sched.dltx_queues[ts.t as usize - 1].is_empty());
+    }
+
+    #[test]
+    fn test_round_robin_between_calls() {
+        let mut sched = get_testing_slotter();
+        let mut ts = TdmaTime { t: 1, f: 1, m: 1, h: 0 };
+
+        // Three calls with pending signalling, the first one with a backlog. Each
+        // PDU fills a slot on its own.
+        let calls = [1001, 1002, 1003];
+        for (ssi, num_pdus) in calls.into_iter().zip([4, 1, 1]) {
+            let addr = TetraAddress { encrypted: false, ssi_type: SsiType::Gssi, ssi };
+            for _ in 0..num_pdus {
+                let pdu = BsChannelScheduler::dl_make_minimal_resource(&addr, None, false);
+                sched.dl_enqueue_tma(ts.t, pdu, BitBuffer::new(216)).unwrap();
+            }
+        }
+
+        let mut served = Vec::new();
+        for _ in 0..6 {
+            let mut blk = sched.dl_build_block_from_signalling_schedule(ts).unwrap();
+            blk.seek(0);
+            served.push(MacResource::from_bitbuf(&mut blk).unwrap().addr.unwrap().ssi);
+            ts = ts.add_timeslots(4);
+        }
+
+        // Every call is serviced within the first three frames, instead of after the backlog
+        assert_eq!(&served[..3], &calls);
+        assert_eq!(&served[3..], &[1001, 1001, 1001]);
+        assert!(sched.dl_build_block_from_signalling_schedule(ts).is_none());
+    }
 
     // #[test]
     // fn test_downlink_fragmentation_multiple_ssis() {
@@ -22,6 +22,23 @@ pub mod removal {
         
         0
     }
+
+    /// Returns true if the bits from the current position to the end of the window are fill bits:
+    /// a single 1 followed by zeroes, or only zeroes.
+    pub fn only_fill_bits_remain(bitbuf: &BitBuffer) -> bool {
+        let remaining = bitbuf.get_len_remaining() as isize;
+        (1..remaining).all(|i| bitbuf.peek_bits_posoffset(i, 1) == Some(0))
+    }
+
+    /// Returns true if another MAC PDU may follow at the current position in the MAC block.
+    /// This is not the case once the block is exhausted, fewer bits remain than a Null PDU
+    /// occupies, or only fill bits remain.
+    pub fn next_pdu_follows(mac_block: &BitBuffer) -> bool {
+        const NULL_PDU_LEN_BITS: usize = 16;
+        !mac_block.at_end()
+            && mac_block.get_len_remaining() >= NULL_PDU_LEN_BITS
+            && !only_fill_bits_remain(mac_block)
+    }
 }
 
 pub mod addition {
@@ -44,12 +61,50 @@ pub mod addition {
             } else {
                 // Zero fill bits
             }
-        } else if bitbuf.get_len_remaining() > 0{
-            // Fill  buf
-            bitbuf.write_bit(1);    
-            bitbuf.write_zeroes(bitbuf.get_len_remaining());
         } else {
-            // No space left
+            // Fill buf (if any space left)
+            finalize(bitbuf);
         }
     }
-}
\ No newline at end of file
+
+    /// Closes an assembled MAC block by padding it with fill bits up to the block boundary
+    pub fn finalize(mac_block: &mut BitBuffer) {
+        mac_block.pad_to(mac_block.get_len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::bitbuffer::BitBuffer;
+
+    use super::{addition, removal};
+
+    #[test]
+    fn test_finalize_half_slot() {
+        // 63-bit PDU in a 124-bit half-slot: one 1-bit followed by 60 0-bits
+        let mut mac_block = BitBuffer::new(124);
+        mac_block.write_zeroes(63);
+        addition::finalize(&mut mac_block);
+
+        assert_eq!(mac_block.get_len_remaining(), 0);
+        let expected = format!("{}1{}", "0".repeat(63), "0".repeat(60));
+        assert_eq!(mac_block.to_bitstr(), expected);
+    }
+
+    #[test]
+    fn test_next_pdu_follows() {
+        // Null PDU followed by fill bits
+        let mut mac_block = BitBuffer::from_bitstr("0000000000010000100000000000000000000");
+        assert!(removal::next_pdu_follows(&mac_block));
+        mac_block.seek(16);
+        assert!(removal::only_fill_bits_remain(&mac_block));
+        assert!(!removal::next_pdu_follows(&mac_block));
+
+        // Too short for another PDU, or exhausted
+        let mut mac_block = BitBuffer::from_bitstr("001000010001");
+        assert!(!removal::next_pdu_follows(&mac_block));
+        mac_block.seek(12);
+        assert!(mac_block.at_end());
+        assert!(!removal::next_pdu_follows(&mac_block));
+    }
+}
@@ -2,10 +2,14 @@ pub mod defrag;
 pub mod bs_frag;
 pub mod bs_defrag;
 pub mod bs_sched;
+pub mod power_ctrl;
+pub mod round_robin;
+pub mod tch_assignment;
 
 pub mod circuit_mgr;
 
 pub mod ms_defrag;
 
+pub mod air_encryption;
 pub mod event_label_store;
 pub mod fillbits;
@@ -0,0 +1,148 @@
+//! Closed loop uplink power control. Compares the received signal strength of each MS
+//! against a target window and derives the power control element for the next
+//! MAC-RESOURCE addressed to it.
+//!
+//! TETRA has no continuous timing advance. Bursts from an MS beyond the range
+//! covered by the uplink guard period arrive too late, which is signalled in the
+//! same element as "maximum path delay exceeded".
+
+use std::collections::HashMap;
+
+use tetra_config::CfgPowerControl;
+use tetra_core::KM_PER_DELAY_SYMBOL;
+
+/// Nominal change in MS transmit power per power control step, in dB
+pub const POWER_STEP_DB: f32 = 5.0;
+
+/// Largest number of steps a single power control element can command
+pub const MAX_POWER_STEPS: u8 = 6;
+
+/// Power control element: increase by 1 step. 2 to 6 steps follow consecutively.
+pub const PCE_INCREASE_1: u8 = 0b0001;
+/// Power control element: decrease by 1 step. 2 to 6 steps follow consecutively.
+pub const PCE_DECREASE_1: u8 = 0b1001;
+/// Power control element: maximum path delay exceeded
+pub const PCE_MAX_PATH_DELAY_EXCEEDED: u8 = 0b0111;
+
+/// Returns the power control element that brings `rssi_dbm` back into the window,
+/// or None if it is already within it
+pub fn power_control_element(window: &CfgPowerControl, rssi_dbm: f32) -> Option<u8> {
+    let steps = |excess_db: f32| ((excess_db / POWER_STEP_DB).ceil() as u8).clamp(1, MAX_POWER_STEPS);
+    if rssi_dbm > window.rssi_max_dbm {
+        Some(PCE_DECREASE_1 + steps(rssi_dbm - window.rssi_max_dbm) - 1)
+    } else if rssi_dbm < window.rssi_min_dbm {
+        Some(PCE_INCREASE_1 + steps(window.rssi_min_dbm - rssi_dbm) - 1)
+    } else {
+        None
+    }
+}
+
+/// Keeps the latest power control command per SSI until it can be sent
+pub struct PowerControl {
+    window: Option<CfgPowerControl>,
+    /// Largest tolerated burst arrival delay, see CfgCellInfo::max_path_delay_symbols
+    max_path_delay_symbols: f32,
+    pending: HashMap<u32, u8>,
+}
+
+impl PowerControl {
+    /// Power control is disabled if no window is given
+    pub fn new(window: Option<CfgPowerControl>, max_path_delay_symbols: f32) -> Self {
+        Self {
+            window,
+            max_path_delay_symbols,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Register the signal strength of a burst received from `ssi`. A newer measurement
+    /// replaces a command that has not been sent yet.
+    pub fn report_rssi(&mut self, ssi: u32, rssi_dbm: f32) {
+        let Some(window) = &self.window else {
+            return;
+        };
+        match power_control_element(window, rssi_dbm) {
+            Some(element) => {
+                tracing::debug!("power control: {} received at {:.1} dBm, element {:04b}", ssi, rssi_dbm, element);
+                self.pending.insert(ssi, element);
+            }
+            None => {
+                self.pending.remove(&ssi);
+            }
+        }
+    }
+
+    /// Register the arrival delay of a burst received from `ssi`. Exceeding the
+    /// maximum path delay takes precedence over a power adjustment for the same burst,
+    /// so this is to be called after `report_rssi`.
+    pub fn report_path_delay(&mut self, ssi: u32, delay_symbols: f32) {
+        if self.window.is_none() {
+            return;
+        }
+        if delay_symbols > self.max_path_delay_symbols {
+            tracing::debug!("power control: {} received {:.2} symbols late (about {:.0} km), maximum path delay exceeded",
+                ssi, delay_symbols, delay_symbols * KM_PER_DELAY_SYMBOL);
+            self.pending.insert(ssi, PCE_MAX_PATH_DELAY_EXCEEDED);
+        }
+    }
+
+    /// Take the pending power control element for `ssi`, if any
+    pub fn take_element(&mut self, ssi: u32) -> Option<u8> {
+        self.pending.remove(&ssi)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::DEFAULT_MAX_PATH_DELAY_SYMBOLS;
+
+    use super::*;
+
+    #[test]
+    fn test_power_control_element() {
+        let window = CfgPowerControl { rssi_min_dbm: -95.0, rssi_max_dbm: -75.0 };
+        assert_eq!(power_control_element(&window, -85.0), None);
+        assert_eq!(power_control_element(&window, -75.0), None);
+
+        // Too strong: decrease, one step per started 5 dB above the window, at most 6
+        assert_eq!(power_control_element(&window, -74.0), Some(PCE_DECREASE_1));
+        assert_eq!(power_control_element(&window, -64.0), Some(PCE_DECREASE_1 + 2));
+        assert_eq!(power_control_element(&window, -20.0), Some(0b1110));
+
+        // Too weak: increase
+        assert_eq!(power_control_element(&window, -97.0), Some(PCE_INCREASE_1));
+        assert_eq!(power_control_element(&window, -105.0), Some(PCE_INCREASE_1 + 1));
+        assert_eq!(power_control_element(&window, -140.0), Some(0b0110));
+    }
+
+    #[test]
+    fn test_pending_commands() {
+        let mut pc = PowerControl::new(Some(CfgPowerControl::default()), DEFAULT_MAX_PATH_DELAY_SYMBOLS);
+        pc.report_rssi(1001, -50.0);
+        pc.report_rssi(1002, -110.0);
+        pc.report_rssi(1003, -85.0);
+        assert!(pc.take_element(1001).is_some_and(|e| e >= PCE_DECREASE_1));
+        assert!(pc.take_element(1002).is_some_and(|e| (PCE_INCREASE_1..PCE_DECREASE_1).contains(&e)));
+        assert_eq!(pc.take_element(1003), None);
+
+        // Taken commands are sent once; a measurement within the window cancels a pending one
+        assert_eq!(pc.take_element(1001), None);
+        pc.report_rssi(1001, -50.0);
+        pc.report_rssi(1001, -80.0);
+        assert_eq!(pc.take_element(1001), None);
+
+        // Late bursts override a power adjustment
+        pc.report_rssi(1004, -50.0);
+        pc.report_path_delay(1004, 9.0);
+        assert_eq!(pc.take_element(1004), Some(PCE_MAX_PATH_DELAY_EXCEEDED));
+        pc.report_rssi(1004, -85.0);
+        pc.report_path_delay(1004, 2.5);
+        assert_eq!(pc.take_element(1004), None);
+
+        // Disabled without a window
+        let mut pc = PowerControl::new(None, DEFAULT_MAX_PATH_DELAY_SYMBOLS);
+        pc.report_rssi(1001, -50.0);
+        assert_eq!(pc.take_element(1001), None);
+    }
+}
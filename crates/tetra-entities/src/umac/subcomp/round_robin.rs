@@ -0,0 +1,89 @@
+//! Fair ordering of downlink signalling between the addresses queued on a timeslot.
+//! Each call is signalled to its own (group) address, so serving the least recently
+//! served address first keeps a call with a burst of pending signalling from
+//! starving the other calls sharing the control channel.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Default)]
+pub struct DlRoundRobin {
+    /// Turn at which each address was last served
+    last_served: HashMap<u32, u64>,
+    turn: u64,
+}
+
+impl DlRoundRobin {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Index of the element to serve next, given the address of each queued element.
+    /// Picks the first element addressed to the least recently served address, so the
+    /// elements for a single address keep their order. Unaddressed elements come first.
+    pub fn pick(&self, addrs: impl Iterator<Item = Option<u32>>) -> Option<usize> {
+        addrs
+            .enumerate()
+            .min_by_key(|(_, ssi)| ssi.and_then(|ssi| self.last_served.get(&ssi).copied()).unwrap_or(0))
+            .map(|(i, _)| i)
+    }
+
+    /// Registers that an element addressed to `ssi` was served
+    pub fn served(&mut self, ssi: u32) {
+        self.turn += 1;
+        self.last_served.insert(ssi, self.turn);
+    }
+
+    /// Forgets addresses with nothing queued that were served before any queued address.
+    /// Those would be served first anyway once they have something queued again, while a
+    /// drained address served more recently keeps its turn, so it cannot jump the queue
+    /// by refilling.
+    pub fn retain(&mut self, mut is_queued: impl FnMut(u32) -> bool) {
+        let queued: Vec<u32> = self.last_served.keys().copied().filter(|&ssi| is_queued(ssi)).collect();
+        let oldest_queued = queued.iter().filter_map(|ssi| self.last_served.get(ssi)).min().copied();
+        self.last_served.retain(|ssi, turn| queued.contains(ssi) || oldest_queued.is_some_and(|oldest| *turn > oldest));
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_least_recently_served_first() {
+        let mut rr = DlRoundRobin::new();
+        let queue = [Some(1), Some(1), Some(2), Some(3)];
+        assert_eq!(rr.pick(queue.into_iter()), Some(0));
+
+        rr.served(1);
+        assert_eq!(rr.pick(queue.into_iter()), Some(2));
+        rr.served(2);
+        assert_eq!(rr.pick(queue.into_iter()), Some(3));
+        rr.served(3);
+        // Back to the oldest, keeping the order of its elements
+        assert_eq!(rr.pick(queue.into_iter()), Some(0));
+
+        rr.retain(|ssi| ssi != 1);
+        assert_eq!(rr.pick([Some(2), Some(1)].into_iter()), Some(1));
+        assert_eq!(rr.pick(std::iter::empty()), None);
+    }
+
+    #[test]
+    fn test_bursty_address_keeps_turn() {
+        // Address 1 is always backlogged, address 2 drains after every PDU and refills
+        let mut rr = DlRoundRobin::new();
+        let mut queue = vec![1, 1, 1, 1, 1, 1, 1, 1];
+        let mut served = vec![];
+        for _ in 0..6 {
+            if !queue.contains(&2) {
+                queue.push(2);
+            }
+            let i = rr.pick(queue.iter().map(|&ssi| Some(ssi))).unwrap();
+            let ssi = queue.remove(i);
+            rr.served(ssi);
+            served.push(ssi);
+            rr.retain(|ssi| queue.contains(&ssi));
+        }
+        assert_eq!(served, [1, 2, 1, 2, 1, 2]);
+    }
+}
@@ -0,0 +1,108 @@
+//! Tracks which call and SSI each traffic timeslot is assigned to. Filled in when
+//! CMCE opens a circuit and consulted to route U-plane frames to their timeslot.
+
+use tetra_saps::lcmc::CallId;
+
+use super::bs_sched::SlotReservation;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TchAssignment {
+    pub call_id: CallId,
+    /// Individual or group SSI the call is addressed to
+    pub ssi: u32,
+    /// Timeslot (1-4) carrying the traffic channel
+    pub ts: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TchAssignErr {
+    /// Timeslot is reserved for control signalling in the slot table
+    SignallingSlot,
+    /// Timeslot is already assigned to another call
+    SlotInUse(CallId),
+}
+
+pub struct TchAssignmentMap {
+    slot_table: [SlotReservation; 4],
+    slots: [Option<TchAssignment>; 4],
+}
+
+impl TchAssignmentMap {
+    pub fn new(slot_table: [SlotReservation; 4]) -> Self {
+        Self {
+            slot_table,
+            slots: [None; 4],
+        }
+    }
+
+    /// Assigns timeslot `ts` to a call. Assigning the same call again, e.g. for
+    /// the second direction of a duplex circuit, is not an error.
+    pub fn assign(&mut self, call_id: CallId, ssi: u32, ts: u8) -> Result<(), TchAssignErr> {
+        if self.slot_table[ts as usize - 1].is_signalling() {
+            return Err(TchAssignErr::SignallingSlot);
+        }
+        let slot = &mut self.slots[ts as usize - 1];
+        match slot {
+            Some(existing) if existing.call_id != call_id => Err(TchAssignErr::SlotInUse(existing.call_id)),
+            _ => {
+                *slot = Some(TchAssignment { call_id, ssi, ts });
+                Ok(())
+            }
+        }
+    }
+
+    /// Releases the assignment of timeslot `ts`, returning it if there was one
+    pub fn release(&mut self, ts: u8) -> Option<TchAssignment> {
+        self.slots[ts as usize - 1].take()
+    }
+
+    pub fn get(&self, ts: u8) -> Option<&TchAssignment> {
+        self.slots[ts as usize - 1].as_ref()
+    }
+
+    /// Timeslot assigned to call `call_id`
+    pub fn ts_for_call(&self, call_id: CallId) -> Option<u8> {
+        self.slots.iter().flatten().find(|a| a.call_id == call_id).map(|a| a.ts)
+    }
+
+    /// Timeslot assigned to a call addressed to `ssi`
+    pub fn ts_for_ssi(&self, ssi: u32) -> Option<u8> {
+        self.slots.iter().flatten().find(|a| a.ssi == ssi).map(|a| a.ts)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::super::bs_sched::build_slot_table;
+    use super::*;
+
+    #[test]
+    fn test_assign_and_release() {
+        let mut map = TchAssignmentMap::new(build_slot_table(1, false));
+        assert_eq!(map.assign(5, 2040001, 2), Ok(()));
+        assert_eq!(map.assign(6, 26, 3), Ok(()));
+        assert_eq!(map.ts_for_call(5), Some(2));
+        assert_eq!(map.ts_for_ssi(26), Some(3));
+        assert_eq!(map.get(2), Some(&TchAssignment { call_id: 5, ssi: 2040001, ts: 2 }));
+
+        // Same call again is fine, another call on the same slot is not
+        assert_eq!(map.assign(5, 2040001, 2), Ok(()));
+        assert_eq!(map.assign(7, 2040002, 2), Err(TchAssignErr::SlotInUse(5)));
+
+        assert_eq!(map.release(2).map(|a| a.call_id), Some(5));
+        assert_eq!(map.ts_for_call(5), None);
+        assert_eq!(map.ts_for_ssi(2040001), None);
+        assert_eq!(map.release(2), None);
+        assert_eq!(map.assign(7, 2040002, 2), Ok(()));
+    }
+
+    #[test]
+    fn test_signalling_slots_refused() {
+        let mut map = TchAssignmentMap::new(build_slot_table(2, true));
+        assert_eq!(map.assign(5, 26, 2), Err(TchAssignErr::SignallingSlot));
+        assert_eq!(map.assign(5, 26, 3), Err(TchAssignErr::SignallingSlot));
+        assert_eq!(map.assign(5, 26, 4), Ok(()));
+        assert_eq!(map.ts_for_call(5), Some(4));
+    }
+}
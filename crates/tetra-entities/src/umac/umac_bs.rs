@@ -1,6 +1,6 @@
 use std::panic;
 
-use tetra_config::SharedConfig;
+use tetra_config::{SecurityClass, SharedConfig};
 use tetra_core::freqs::FreqInfo;
 use tetra_core::{BitBuffer, PhyBlockNum, Sap, TdmaTime, Todo, assert_warn, unimplemented_log};
 use tetra_core::tetra_entities::TetraEntity;
@@ -10,6 +10,8 @@ use tetra_saps::lcmc::enums::alloc_type::ChanAllocType;
 use tetra_saps::lcmc::enums::ul_dl_assignment::UlDlAssignment;
 use tetra_saps::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
 use tetra_saps::tma::{TmaReport, TmaReportInd, TmaUnitdataInd};
+use tetra_saps::tmd::TmdCircuitDataInd;
+use tetra_saps::tmv::TmvUnitdataReq;
 use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
 use tetra_saps::{SapMsg, SapMsgInner};
 use tetra_pdus::mle::fields::bs_service_details::BsServiceDetails;
@@ -18,7 +20,7 @@ use tetra_pdus::mle::pdus::d_mle_sysinfo::DMleSysinfo;
 use tetra_pdus::umac::enums::mac_pdu_type::MacPduType;
 use tetra_pdus::umac::enums::sysinfo_opt_field_flag::SysinfoOptFieldFlag;
 use tetra_pdus::umac::fields::sysinfo_default_def_for_access_code_a::SysinfoDefaultDefForAccessCodeA;
-use tetra_pdus::umac::fields::sysinfo_ext_services::SysinfoExtendedServices;
+use tetra_pdus::umac::fields::sysinfo_ext_services::{ExtServicesSection1, SysinfoExtendedServices};
 use tetra_pdus::umac::pdus::mac_access::MacAccess;
 use tetra_pdus::umac::pdus::mac_data::MacData;
 use tetra_pdus::umac::pdus::mac_end_hu::MacEndHu;
@@ -29,10 +31,12 @@ use tetra_pdus::umac::pdus::mac_sync::MacSync;
 use tetra_pdus::umac::pdus::mac_sysinfo::MacSysinfo;
 use tetra_pdus::umac::pdus::mac_u_blck::MacUBlck;
 use tetra_pdus::umac::pdus::mac_u_signal::MacUSignal;
+use tetra_pdus::parse_timing::timed_parse;
 
 use crate::{MessageQueue, TetraEntityTrait};
 use crate::lmac::components::scrambler;
-use crate::umac::subcomp::bs_sched::{BsChannelScheduler, PrecomputedUmacPdus};
+use crate::umac::subcomp::air_encryption::{AirEncryption, NullEncryption, decrypt_addressed};
+use crate::umac::subcomp::bs_sched::{BsChannelScheduler, PrecomputedUmacPdus, SCH_F_CAP, SCH_HD_CAP, build_slot_table};
 use crate::umac::subcomp::fillbits;
 
 use super::subcomp::bs_defrag::BsDefrag;
@@ -56,6 +60,9 @@ pub struct UmacBs {
     pub channel_scheduler: BsChannelScheduler,
     // ulrx_scheduler: UlScheduler,
 
+    /// Air interface encryption, applied to MAC PDUs with the encrypted flag set
+    air_encryption: Box<dyn AirEncryption>,
+
 }
 
 impl UmacBs {
@@ -63,6 +70,13 @@ impl UmacBs {
         let c = config.config();
         let scrambling_code = scrambler::tetra_scramb_get_init(c.net.mcc, c.net.mnc, c.cell.colour_code);
         let precomps = Self::generate_precomps(&config);
+        let slot_table = build_slot_table(c.cell.mcch_timeslot, c.cell.secondary_control_channels);
+        let mut channel_scheduler = BsChannelScheduler::new(scrambling_code, precomps, slot_table);
+        channel_scheduler.set_power_control(c.power_control.clone(), c.cell.max_path_delay_symbols());
+        channel_scheduler.set_broadcast_periods(c.cell.sync_period_multiframes, c.cell.sysinfo_period_multiframes);
+        channel_scheduler.set_tx_off_frames(c.cell.tx_off_frames.clone());
+        channel_scheduler.set_minimum_mode(c.cell.minimum_mode());
+        channel_scheduler.set_max_tm_sdu_bits(c.cell.max_tm_sdu_bits);
         Self { 
             self_component: TetraEntity::Umac,
             config,
@@ -70,10 +84,16 @@ impl UmacBs {
             endpoint_id: 1, 
             defrag: BsDefrag::new(),
             // event_label_store: EventLabelStore::new(),
-            channel_scheduler: BsChannelScheduler::new(scrambling_code, precomps),
+            channel_scheduler,
+            air_encryption: Box::new(NullEncryption),
         }
     }
 
+    /// Replaces the air interface encryption, which defaults to NullEncryption
+    pub fn set_air_encryption(&mut self, air_encryption: Box<dyn AirEncryption>) {
+        self.air_encryption = air_encryption;
+    }
+
     /// Precomputes SYNC, SYSINFO messages (and subfield variants) for faster TX msg building
     /// Precomputed PDUs are passed to scheduler
     /// Needs to be re-invoked if any network parameter changes
@@ -81,21 +101,29 @@ impl UmacBs {
 
         let c = config.config();
 
+        // Security information follows the configured security class; all zero on clear cells
         // TODO FIXME make more/all parameters configurable
+        let security_class = c.cell.security_class;
+        let is_class3 = security_class == SecurityClass::Cck;
         let ext_services = SysinfoExtendedServices {
             auth_required: false,
-            class1_supported: true,
-            class2_supported: true,
-            class3_supported: false,
-            sck_n: Some(0),
-            dck_retrieval_during_cell_select: None,
-            dck_retrieval_during_cell_reselect: None,
-            linked_gck_crypto_periods: None,
-            short_gck_vn: None,
+            class1_supported: security_class != SecurityClass::Clear,
+            class2_supported: security_class == SecurityClass::Sck,
+            class3_supported: is_class3,
+            sck_n: if security_class == SecurityClass::Sck { c.cell.sck_number.map(|n| n - 1) } else { None },
+            dck_retrieval_during_cell_select: is_class3.then_some(false),
+            dck_retrieval_during_cell_reselect: is_class3.then_some(false),
+            linked_gck_crypto_periods: is_class3.then_some(false),
+            short_gck_vn: is_class3.then_some(0),
             sdstl_addressing_method: 2,
             gck_supported: false,
             section: 0,
-            section_data: 0,
+            section_data: ExtServicesSection1 {
+                data_priority_supported: c.cell.data_priority_service,
+                ext_advanced_links_supported: c.cell.extended_advanced_links,
+                qos_negotiation_supported: c.cell.qos_negotiation_service,
+                ..Default::default()
+            }.to_bits(),
         };
 
         let def_access = SysinfoDefaultDefForAccessCodeA {
@@ -113,7 +141,7 @@ impl UmacBs {
             freq_offset_index: FreqInfo::freq_offset_hz_to_id(c.cell.freq_offset_hz).unwrap(),
             duplex_spacing: c.cell.duplex_spacing_id,
             reverse_operation: c.cell.reverse_operation,
-            num_of_csch: 0,
+            num_of_csch: c.cell.secondary_control_channels as u8,
             ms_txpwr_max_cell: 5,
             rxlev_access_min: 3,
             access_parameter: 7,
@@ -137,8 +165,9 @@ impl UmacBs {
             rxlev_access_min: sysinfo1.rxlev_access_min,
             access_parameter: sysinfo1.access_parameter,
             radio_dl_timeout: sysinfo1.radio_dl_timeout,
-            cck_id: None,
-            hyperframe_number: Some(0), // Updated dynamically in scheduler
+            // Class 3 cells broadcast the CCK identifier in place of the hyperframe number
+            cck_id: if is_class3 { c.cell.cck_id } else { None },
+            hyperframe_number: if is_class3 { None } else { Some(0) }, // Updated dynamically in scheduler
             option_field: SysinfoOptFieldFlag::ExtServicesBroadcast,
             ts_common_frames: None,
             default_access_code: None,
@@ -158,7 +187,7 @@ impl UmacBs {
                 voice_service: true,
                 circuit_mode_data_service: false,
                 sndcp_service: false,
-                aie_service: false,
+                aie_service: c.cell.aie_service,
                 advanced_link: false,
             }
         };
@@ -170,15 +199,15 @@ impl UmacBs {
             sharing_mode: 0, // Continuous transmission
             ts_reserved_frames: 0,
             u_plane_dtx: false,
-            frame_18_ext: false,
+            frame_18_ext: c.cell.frame_18_ext,
         };
 
         let mle_sync_pdu = DMleSync {
             mcc: c.net.mcc,
             mnc: c.net.mnc,
             neighbor_cell_broadcast: 2, // Broadcast supported, but enquiry not supported
-            cell_load_ca: 0, 
-            late_entry_supported: true,
+            cell_load_ca: config.state_read().cell_load_ca,
+            late_entry_supported: c.cell.late_entry_supported,
         };
 
         PrecomputedUmacPdus {
@@ -190,6 +219,23 @@ impl UmacBs {
         }
     }
 
+    /// Builds a block holding only a MAC-RESOURCE null PDU, the downlink filler sent
+    /// on SCH/F or SCH/HD when there is nothing else to transmit in it
+    pub fn build_null_pdu(logical_channel: LogicalChannel, scrambling_code: u32) -> TmvUnitdataReq {
+        let cap = match logical_channel {
+            LogicalChannel::SchF => SCH_F_CAP,
+            LogicalChannel::SchHd => SCH_HD_CAP,
+            _ => panic!("No null PDU on {:?}", logical_channel),
+        };
+        let mut mac_block = BitBuffer::new(cap);
+        MacResource::null_pdu().to_bitbuf(&mut mac_block);
+        TmvUnitdataReq {
+            logical_channel,
+            mac_block,
+            scrambling_code,
+        }
+    }
+
     fn cmce_to_mac_chanalloc(chan_alloc: &CmceChanAllocReq, carrier_num: u16) -> ChanAllocElement {
         // We grant clch permission for Replace and Additional allocations on the uplink
         let clch_permission = (chan_alloc.alloc_type == ChanAllocType::Replace || chan_alloc.alloc_type == ChanAllocType::Additional) && 
@@ -252,10 +298,45 @@ impl UmacBs {
                 assert!(matches!(prim.block_num, PhyBlockNum::Block1 | PhyBlockNum::Block2), "{:?} can't have block_num {:?}", prim.logical_channel, prim.block_num);
                 self.rx_tmv_sch(queue, message);
             },
+            LogicalChannel::TchS |
+            LogicalChannel::Tch24 |
+            LogicalChannel::Tch48 |
+            LogicalChannel::Tch72 => {
+                self.rx_tmv_traffic(queue, message);
+            },
             _ => unreachable!("invalid channel: {:?}", prim.logical_channel)
         }
     }
 
+    /// Receive a traffic block and pass it to the U-plane, tagged with the call
+    /// its timeslot is assigned to
+    fn rx_tmv_traffic(&mut self, queue: &mut MessageQueue, message: SapMsg) {
+        let ts = message.dltime.t;
+        let SapMsgInner::TmvUnitdataInd(mut prim) = message.msg else { panic!() };
+
+        let Some(assignment) = self.channel_scheduler.tch_assignments().get(ts) else {
+            tracing::warn!("rx_tmv_traffic: no call assigned to ts {}, dropping {:?} frame", ts, prim.logical_channel);
+            return;
+        };
+
+        let len = prim.pdu.get_len();
+        prim.pdu.seek(0);
+        let mut frame = BitBuffer::new(len);
+        frame.copy_bits(&mut prim.pdu, len);
+
+        queue.push_back(SapMsg {
+            sap: Sap::TmdSap,
+            src: TetraEntity::Umac,
+            dest: TetraEntity::User,
+            dltime: message.dltime,
+            msg: SapMsgInner::TmdCircuitDataInd(TmdCircuitDataInd {
+                call_id: assignment.call_id,
+                ts,
+                data: frame.into_bytes(),
+            }),
+        });
+    }
+
     /// Receive signalling (SCH, or STCH / BNCH)
     pub fn rx_tmv_sch(&mut self, queue: &mut MessageQueue, mut message: SapMsg) {
         tracing::trace!("rx_tmv_sch");
@@ -328,9 +409,9 @@ impl UmacBs {
             
             // Check if end of message reached by re-borrowing inner
             // If start was not updated, we also consider it end of message
-            // If 16 or more bits remain (len of null pdu), we continue parsing
+            // Otherwise, continue parsing unless the block is exhausted or only fill bits remain
             if let SapMsgInner::TmvUnitdataInd(prim) = &message.msg {
-                if prim.pdu.get_raw_start() != orig_start && prim.pdu.get_len() >= 16 {
+                if prim.pdu.get_raw_start() != orig_start && fillbits::removal::next_pdu_follows(&prim.pdu) {
                     tracing::trace!(
                         "orig {} now {}", orig_start, prim.pdu.get_raw_start()
                     );
@@ -353,7 +434,7 @@ impl UmacBs {
         let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {panic!()};
         assert!(prim.pdu.get_pos() == 0); // We should be at the start of the MAC PDU
 
-        let pdu = match MacData::from_bitbuf(&mut prim.pdu) {
+        let pdu = match timed_parse("MacData", &mut prim.pdu, MacData::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -448,11 +529,20 @@ impl UmacBs {
             return;
         }
         
-        // Decrypt if needed
-        if pdu.encrypted {
-            unimplemented_log!("rx_mac_data: Encryption mode > 0");
-            return;
-        } 
+        // Decrypt if needed, resolving the ESI to the SSI
+        let addr = match decrypt_addressed(self.air_encryption.as_mut(), addr, message.dltime, &mut prim.pdu) {
+            Ok(addr) => addr,
+            Err(e) => {
+                tracing::warn!("rx_mac_data: failed decrypting PDU for {}: {:?}", addr, e);
+                return;
+            }
+        };
+
+        // Track signal strength and path delay for uplink power control
+        if let Some(q) = prim.rx_quality {
+            self.channel_scheduler.ul_report_rssi(addr.ssi, q.rssi_dbm);
+            self.channel_scheduler.ul_report_path_delay(addr.ssi, q.delay_symbols);
+        }
 
         // Handle reservation if present
         // let ul_time = message.dltime.add_timeslots(-2);
@@ -520,7 +610,8 @@ impl UmacBs {
                             air_interface_encryption: pdu.encrypted as Todo,
                             chan_change_response_req: false,
                             chan_change_handle: None,
-                            chan_info: None
+                            chan_info: None,
+                            rx_quality: prim.rx_quality,
                         }
                     )
                 };
@@ -546,7 +637,7 @@ impl UmacBs {
         let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {panic!()};
         assert!(prim.pdu.get_pos() == 0); // We should be at the start of the MAC PDU
 
-        let pdu = match MacAccess::from_bitbuf(&mut prim.pdu) {
+        let pdu = match timed_parse("MacAccess", &mut prim.pdu, MacAccess::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -618,11 +709,20 @@ impl UmacBs {
         // let ul_time = message.dltime.add_timeslots(-2);
         self.channel_scheduler.dl_enqueue_random_access_ack(message.dltime.t, addr);
         
-        // Decrypt if needed
-        if pdu.encrypted {
-            unimplemented_log!("rx_mac_access: Encryption mode > 0");
-            return;
-        } 
+        // Decrypt if needed, resolving the ESI to the SSI
+        let addr = match decrypt_addressed(self.air_encryption.as_mut(), addr, message.dltime, &mut prim.pdu) {
+            Ok(addr) => addr,
+            Err(e) => {
+                tracing::warn!("rx_mac_access: failed decrypting PDU for {}: {:?}", addr, e);
+                return;
+            }
+        };
+
+        // Track signal strength and path delay for uplink power control
+        if let Some(q) = prim.rx_quality {
+            self.channel_scheduler.ul_report_rssi(addr.ssi, q.rssi_dbm);
+            self.channel_scheduler.ul_report_path_delay(addr.ssi, q.delay_symbols);
+        }
 
         // Handle reservation if present
         if let Some(res_req) = &pdu.reservation_req {
@@ -680,7 +780,8 @@ impl UmacBs {
                             air_interface_encryption: pdu.encrypted as Todo,
                             chan_change_response_req: false,
                             chan_change_handle: None,
-                            chan_info: None
+                            chan_info: None,
+                            rx_quality: prim.rx_quality,
                         }
                     )
                 };
@@ -706,7 +807,7 @@ impl UmacBs {
         assert!(prim.pdu.get_pos() == 0); // We should be at the start of the MAC PDU
         
         // Parse header and optional ChanAlloc
-        let pdu = match MacFragUl::from_bitbuf(&mut prim.pdu) {
+        let pdu = match timed_parse("MacFragUl", &mut prim.pdu, MacFragUl::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -752,7 +853,7 @@ impl UmacBs {
         assert!(prim.pdu.get_pos() == 0); // We should be at the start of the MAC PDU
 
         // Parse header and optional ChanAlloc
-        let pdu = match MacEndUl::from_bitbuf(&mut prim.pdu) {
+        let pdu = match timed_parse("MacEndUl", &mut prim.pdu, MacEndUl::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -838,7 +939,8 @@ impl UmacBs {
                     air_interface_encryption: 0, // TODO FIXME implement
                     chan_change_response_req: false,
                     chan_change_handle: None,
-                    chan_info: None
+                    chan_info: None,
+                    rx_quality: prim.rx_quality,
                 }
             )
         };
@@ -857,7 +959,7 @@ impl UmacBs {
         assert!(prim.pdu.get_pos() == 0); // We should be at the start of the MAC PDU
 
         // Parse header and optional ChanAlloc
-        let pdu = match MacEndHu::from_bitbuf(&mut prim.pdu) {
+        let pdu = match timed_parse("MacEndHu", &mut prim.pdu, MacEndHu::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -955,7 +1057,8 @@ impl UmacBs {
                     air_interface_encryption: 0, // TODO FIXME implement
                     chan_change_response_req: false,
                     chan_change_handle: None,
-                    chan_info: None
+                    chan_info: None,
+                    rx_quality: prim.rx_quality,
                 }
             )
         };
@@ -977,7 +1080,7 @@ impl UmacBs {
         // Extract sdu and parse pdu
         let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {panic!()};
 
-        let _pdu = match MacUSignal::from_bitbuf(&mut prim.pdu) {
+        let _pdu = match timed_parse("MacUSignal", &mut prim.pdu, MacUSignal::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -998,7 +1101,7 @@ impl UmacBs {
         // Extract sdu and parse pdu
         let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {panic!()};
 
-        let _pdu = match MacUBlck::from_bitbuf(&mut prim.pdu) {
+        let _pdu = match timed_parse("MacUBlck", &mut prim.pdu, MacUBlck::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -1048,7 +1151,11 @@ impl UmacBs {
 
         // Add to scheduler, who will handle scheduling and fragmentation (if required)
         // let ul_time = message.dltime.add_timeslots(-2);
-        self.channel_scheduler.dl_enqueue_tma(message.dltime.t, pdu, sdu);
+        if let Err(e) = self.channel_scheduler.dl_enqueue_tma(message.dltime.t, pdu, sdu) {
+            tracing::warn!("rx_ul_tma_unitdata_req: not sending TM-SDU for {}: {:?}", prim.main_address, e);
+            Self::send_tma_report_ind(queue, message.dltime, prim.req_handle, TmaReport::FragmentationFailure);
+            return;
+        }
 
         // TODO FIXME I'm not so sure whether we should send this now, or send it once the message is on its way
         Self::send_tma_report_ind(queue, message.dltime, prim.req_handle, TmaReport::SuccessDownlinked);
@@ -1073,7 +1180,11 @@ impl UmacBs {
         tracing::trace!("rx_tmd_prim");
         let SapMsgInner::TmdCircuitDataReq(prim) = message.msg else {panic!()};
 
-        self.channel_scheduler.dl_schedule_tmd(prim.ts, prim.data);
+        let Some(ts) = self.channel_scheduler.tch_assignments().ts_for_call(prim.call_id) else {
+            tracing::warn!("rx_tmd_prim: no traffic channel assigned to call id {}, dropping frame", prim.call_id);
+            return;
+        };
+        self.channel_scheduler.dl_schedule_tmd(ts, prim.data);
     }
 
     // fn signal_lmac_circuit_setup(&self, queue: &mut MessageQueue, circuit: Circuit) {
@@ -1104,7 +1215,18 @@ impl UmacBs {
     // }
 
 
-    fn rx_control_circuit_open(&mut self, _queue: &mut MessageQueue, prim: CallControl) {
+    /// Let the U-plane know a call's traffic channel was set up or released
+    fn signal_uplane_call_control(&self, queue: &mut MessageQueue, prim: CallControl) {
+        queue.push_back(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Umac,
+            dest: TetraEntity::User,
+            dltime: self.dltime,
+            msg: SapMsgInner::CmceCallControl(prim),
+        });
+    }
+
+    fn rx_control_circuit_open(&mut self, queue: &mut MessageQueue, prim: CallControl) {
         
         let CallControl::Open(circuit) = prim else {panic!()};
         let ts = circuit.ts;
@@ -1117,20 +1239,29 @@ impl UmacBs {
             self.channel_scheduler.close_circuit(dir, ts);
             panic!("rx_control_circuit_open: Circuit already exists for {:?} {}", dir, ts);
         }
+
+        if let Err(e) = self.channel_scheduler.assign_tch(&circuit) {
+            tracing::error!("rx_control_circuit_open: can't assign ts {} to call id {}: {:?}, refusing circuit", ts, circuit.call_id, e);
+            return;
+        }
         
         // Add circuit; signal Lmac as well
         // self.signal_lmac_circuit_setup(queue, circuit.clone());
+        self.signal_uplane_call_control(queue, CallControl::Open(circuit.clone()));
         self.channel_scheduler.create_circuit(dir, circuit);
         tracing::debug!("  rx_control_circuit_open: Setup circuit for ts {}", ts);
     }
 
-    fn rx_control_circuit_close(&mut self, _queue: &mut MessageQueue, prim: CallControl) {
+    fn rx_control_circuit_close(&mut self, queue: &mut MessageQueue, prim: CallControl) {
 
         let CallControl::Close(dir, ts) = prim else {panic!()};
         // self.signal_lmac_circuit_close(queue, dir, ts);
         match self.channel_scheduler.close_circuit(dir, ts) {
             Some(_) => {
                 tracing::info!("  rx_control_circuit_close: Closed circuit for ts {}", ts);
+                if self.channel_scheduler.release_tch_if_idle(ts).is_some() {
+                    self.signal_uplane_call_control(queue, CallControl::Close(dir, ts));
+                }
             },
             None => {
                 tracing::warn!("  rx_control_circuit_close: No circuit to close for ts {}", ts);
@@ -1209,6 +1340,9 @@ impl TetraEntityTrait for UmacBs {
             self.channel_scheduler.tick_start(ts);
         }
 
+        // Adopt runtime changes to the stack state (e.g. through the control socket)
+        self.channel_scheduler.set_cell_load_ca(self.config.state_read().cell_load_ca);
+
         // Collect/construct traffic that should be sent down to the LMAC
         // This is basically the _previous_ timeslot
         let elem = self.channel_scheduler.finalize_ts_for_tick();
@@ -18,6 +18,7 @@ use tetra_pdus::umac::pdus::mac_frag_dl::MacFragDl;
 use tetra_pdus::umac::pdus::mac_resource::MacResource;
 use tetra_pdus::umac::pdus::mac_sync::MacSync;
 use tetra_pdus::umac::pdus::mac_sysinfo::MacSysinfo;
+use tetra_pdus::parse_timing::timed_parse;
 
 use crate::{MessagePrio, MessageQueue, TetraEntityTrait};
 use crate::umac::subcomp::fillbits;
@@ -144,9 +145,9 @@ impl UmacMs {
 
             // Check if end of message reached by re-borrowing inner
             // If start was not updated, we also consider it end of message
-            // If 16 or more bits remain (len of null pdu), we continue parsing
+            // Otherwise, continue parsing unless the block is exhausted or only fill bits remain
             if let SapMsgInner::TmvUnitdataInd(prim) = &message.msg {
-                if prim.pdu.get_raw_start() != orig_start && prim.pdu.get_len() >= 16 {
+                if prim.pdu.get_raw_start() != orig_start && fillbits::removal::next_pdu_follows(&prim.pdu) {
                     tracing::trace!(
                         "rx_tmv_unitdata_ind_sch: Remaining {} bits: {:?}",
                         prim.pdu.get_len_remaining(),
@@ -185,7 +186,7 @@ impl UmacMs {
         let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {panic!()};
         
         // Parse SYSINFO header and optional data
-        let pdu = match MacSysinfo::from_bitbuf(&mut prim.pdu) {
+        let pdu = match timed_parse("MacSysinfo", &mut prim.pdu, MacSysinfo::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -247,7 +248,7 @@ impl UmacMs {
         assert!(prim.pdu.get_pos() == 0); // We should be at the start of the MAC PDU
 
         // Parse header and optional ChanAlloc
-        let pdu = match MacResource::from_bitbuf(&mut prim.pdu) {
+        let pdu = match timed_parse("MacResource", &mut prim.pdu, MacResource::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -370,7 +371,8 @@ impl UmacMs {
                             air_interface_encryption: pdu.encryption_mode as Todo,
                             chan_change_response_req: false,
                             chan_change_handle: None,
-                            chan_info: None
+                            chan_info: None,
+                            rx_quality: prim.rx_quality,
                         }
                     )
                 };
@@ -396,7 +398,7 @@ impl UmacMs {
         assert!(prim.pdu.get_pos() == 0); // We should be at the start of the MAC PDU
         
         // Parse header and optional ChanAlloc
-        let pdu = match MacFragDl::from_bitbuf(&mut prim.pdu) {
+        let pdu = match timed_parse("MacFragDl", &mut prim.pdu, MacFragDl::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -437,7 +439,7 @@ impl UmacMs {
         assert!(prim.pdu.get_pos() == 0); // We should be at the start of the MAC PDU
 
         // Parse header and optional ChanAlloc
-        let pdu = match MacEndDl::from_bitbuf(&mut prim.pdu) {
+        let pdu = match timed_parse("MacEndDl", &mut prim.pdu, MacEndDl::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -502,7 +504,8 @@ impl UmacMs {
                     air_interface_encryption: 0, // TODO FIXME implement
                     chan_change_response_req: false,
                     chan_change_handle: None,
-                    chan_info: None
+                    chan_info: None,
+                    rx_quality: prim.rx_quality,
                 }
             )
         };
@@ -540,7 +543,7 @@ impl UmacMs {
         
         let is_traffic = if message.dltime.f != 18 {
             
-            let pdu = match AccessAssign::from_bitbuf(&mut prim.pdu) {
+            let pdu = match timed_parse("AccessAssign", &mut prim.pdu, AccessAssign::from_bitbuf) {
                 Ok(pdu) => {
                     tracing::debug!("<- {:?}", pdu);
                     pdu
@@ -553,7 +556,7 @@ impl UmacMs {
 
             pdu.dl_usage.is_traffic()
         } else {
-            let _pdu = match AccessAssignFr18::from_bitbuf(&mut prim.pdu) {
+            let _pdu = match timed_parse("AccessAssignFr18", &mut prim.pdu, AccessAssignFr18::from_bitbuf) {
                 Ok(pdu) => {
                     tracing::debug!("<- {:?}", pdu);
                     pdu
@@ -594,7 +597,7 @@ impl UmacMs {
         let SapMsgInner::TmvUnitdataInd(prim) = &mut message.msg else {panic!()};
 
         // Unpack and validate with expected state
-        let _pdu = match MacSync::from_bitbuf(&mut prim.pdu) {
+        let _pdu = match timed_parse("MacSync", &mut prim.pdu, MacSync::from_bitbuf) {
             Ok(pdu) => {
                 tracing::debug!("<- {:?}", pdu);
                 pdu
@@ -0,0 +1,109 @@
+//! Destinations for U-plane frames leaving the stack. Frames are written as
+//! received from the air interface; no codec is applied.
+
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::net::UdpSocket;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use tetra_config::CfgUplane;
+use tetra_saps::lcmc::CallId;
+
+/// Receives the traffic frames of a single call
+pub trait FrameSink: Send {
+    fn write_frame(&mut self, frame: &[u8]) -> io::Result<()>;
+}
+
+/// Records the frames of a call to a file, so that captured calls can be
+/// decoded externally. Each frame is stored as its length in bytes (2 bytes
+/// big-endian), followed by the frame as received.
+pub struct FileFrameSink {
+    file: BufWriter<File>,
+    path: PathBuf,
+    frames: usize,
+}
+
+impl FileFrameSink {
+    /// Creates `call_<call_id>_ssi_<ssi>_<unix time>.tch` in `dir`
+    pub fn create(dir: &Path, call_id: CallId, ssi: u32) -> io::Result<Self> {
+        let secs = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+        let path = dir.join(format!("call_{}_ssi_{}_{}.tch", call_id, ssi, secs));
+        let file = BufWriter::new(File::create(&path)?);
+        tracing::info!("recording call {} (ssi {}) to {}", call_id, ssi, path.display());
+        Ok(Self { file, path, frames: 0 })
+    }
+}
+
+impl FrameSink for FileFrameSink {
+    fn write_frame(&mut self, frame: &[u8]) -> io::Result<()> {
+        let len = u16::try_from(frame.len()).map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "frame too long"))?;
+        self.file.write_all(&len.to_be_bytes())?;
+        self.file.write_all(frame)?;
+        self.frames += 1;
+        Ok(())
+    }
+}
+
+impl Drop for FileFrameSink {
+    fn drop(&mut self) {
+        if let Err(e) = self.file.flush() {
+            tracing::warn!("failed to finish recording {}: {}", self.path.display(), e);
+        } else {
+            tracing::info!("recorded {} frames to {}", self.frames, self.path.display());
+        }
+    }
+}
+
+/// Reads back the frames of a recording made by `FileFrameSink`
+pub fn read_recording(path: &Path) -> io::Result<Vec<Vec<u8>>> {
+    let data = fs::read(path)?;
+    let mut frames = Vec::new();
+    let mut rest = data.as_slice();
+    while !rest.is_empty() {
+        let truncated = || io::Error::new(io::ErrorKind::UnexpectedEof, "truncated frame");
+        let (len, tail) = rest.split_first_chunk::<2>().ok_or_else(truncated)?;
+        let len = u16::from_be_bytes(*len) as usize;
+        if tail.len() < len {
+            return Err(truncated());
+        }
+        frames.push(tail[..len].to_vec());
+        rest = &tail[len..];
+    }
+    Ok(frames)
+}
+
+/// Sends each frame as a UDP datagram, prefixed with the call id as 2 bytes big-endian
+/// so that a single receiver can demultiplex all calls
+pub struct UdpFrameSink {
+    socket: UdpSocket,
+    call_id: CallId,
+}
+
+impl UdpFrameSink {
+    pub fn connect(target: &str, call_id: CallId) -> io::Result<Self> {
+        let socket = UdpSocket::bind("0.0.0.0:0")?;
+        socket.connect(target)?;
+        Ok(Self { socket, call_id })
+    }
+}
+
+impl FrameSink for UdpFrameSink {
+    fn write_frame(&mut self, frame: &[u8]) -> io::Result<()> {
+        let mut datagram = Vec::with_capacity(2 + frame.len());
+        datagram.extend_from_slice(&self.call_id.to_be_bytes());
+        datagram.extend_from_slice(frame);
+        self.socket.send(&datagram).map(|_| ())
+    }
+}
+
+/// Opens the configured sink for the frames of `call_id`, addressed to `ssi`
+pub fn sink_for_call(cfg: &CfgUplane, call_id: CallId, ssi: u32) -> io::Result<Box<dyn FrameSink>> {
+    if let Some(dir) = &cfg.frame_dir {
+        Ok(Box::new(FileFrameSink::create(Path::new(dir), call_id, ssi)?))
+    } else if let Some(target) = &cfg.udp_target {
+        Ok(Box::new(UdpFrameSink::connect(target, call_id)?))
+    } else {
+        Err(io::Error::new(io::ErrorKind::InvalidInput, "no U-plane frame destination configured"))
+    }
+}
@@ -0,0 +1,2 @@
+pub mod frame_sink;
+pub mod uplane_bs;
@@ -0,0 +1,200 @@
+//! U-plane entity on the BS side. Moves traffic frames between the UMAC and an
+//! external sink per call, opened when the UMAC sets up the call's traffic
+//! channel and closed when it is released. Speech is not decoded or encoded here; frames are
+//! passed through as they are.
+
+use std::collections::HashMap;
+
+use tetra_config::SharedConfig;
+use tetra_core::{Sap, TdmaTime};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_saps::control::call_control::{CallControl, Circuit};
+use tetra_saps::lcmc::CallId;
+use tetra_saps::tmd::{TmdCircuitDataInd, TmdCircuitDataReq};
+use tetra_saps::{SapMsg, SapMsgInner};
+
+use crate::{MessageQueue, TetraEntityTrait};
+
+use super::frame_sink::{FrameSink, sink_for_call};
+
+pub struct UplaneBs {
+    config: SharedConfig,
+    /// Open sink per active call
+    sinks: HashMap<CallId, Box<dyn FrameSink>>,
+    /// Call carried on each traffic timeslot, as set up by the UMAC
+    calls: [Option<CallId>; 4],
+}
+
+impl UplaneBs {
+    pub fn new(config: SharedConfig) -> Self {
+        Self {
+            config,
+            sinks: HashMap::new(),
+            calls: [None; 4],
+        }
+    }
+
+    /// Direct the frames of `call_id` to `sink` instead of the configured destination
+    pub fn set_sink(&mut self, call_id: CallId, sink: Box<dyn FrameSink>) {
+        self.sinks.insert(call_id, sink);
+    }
+
+    /// Close the sink of a call that has ended
+    pub fn remove_sink(&mut self, call_id: CallId) {
+        self.sinks.remove(&call_id);
+    }
+
+    /// Queue a frame for transmission on the traffic channel of `call_id`
+    pub fn send_frame(&self, queue: &mut MessageQueue, call_id: CallId, data: Vec<u8>, dltime: TdmaTime) {
+        queue.push_back(SapMsg {
+            sap: Sap::TmdSap,
+            src: TetraEntity::User,
+            dest: TetraEntity::Umac,
+            dltime,
+            msg: SapMsgInner::TmdCircuitDataReq(TmdCircuitDataReq { call_id, data }),
+        });
+    }
+
+    /// A traffic channel was set up: open the configured sink for its call
+    fn rx_call_open(&mut self, circuit: Circuit) {
+        self.calls[circuit.ts as usize - 1] = Some(circuit.call_id);
+        if self.sinks.contains_key(&circuit.call_id) {
+            // Second direction of the same call, or a sink set explicitly
+            return;
+        }
+        let config = self.config.config();
+        let Some(cfg) = &config.uplane else {
+            return;
+        };
+        match sink_for_call(cfg, circuit.call_id, circuit.ssi) {
+            Ok(sink) => {
+                self.sinks.insert(circuit.call_id, sink);
+            }
+            Err(e) => {
+                tracing::warn!("could not open U-plane sink for call {}: {}", circuit.call_id, e);
+            }
+        }
+    }
+
+    /// The traffic channel on `ts` was released: close the sink of its call
+    fn rx_call_close(&mut self, ts: u8) {
+        let Some(call_id) = self.calls[ts as usize - 1].take() else {
+            tracing::warn!("rx_call_close: no call on ts {}", ts);
+            return;
+        };
+        tracing::debug!("rx_call_close: call {} ended on ts {}", call_id, ts);
+        self.remove_sink(call_id);
+    }
+
+    fn rx_tmd_circuit_data_ind(&mut self, prim: TmdCircuitDataInd) {
+        tracing::trace!("rx_tmd_circuit_data_ind: call {} ts {} {} bytes", prim.call_id, prim.ts, prim.data.len());
+
+        let Some(sink) = self.sinks.get_mut(&prim.call_id) else {
+            tracing::trace!("no U-plane sink for call {}, dropping frame", prim.call_id);
+            return;
+        };
+        if let Err(e) = sink.write_frame(&prim.data) {
+            tracing::warn!("writing frame of call {} failed, closing sink: {}", prim.call_id, e);
+            self.sinks.remove(&prim.call_id);
+        }
+    }
+}
+
+impl TetraEntityTrait for UplaneBs {
+    fn entity(&self) -> TetraEntity {
+        TetraEntity::User
+    }
+
+    fn user_saps(&self) -> &[Sap] {
+        &[Sap::TmdSap, Sap::Control]
+    }
+
+    fn set_config(&mut self, config: SharedConfig) {
+        self.config = config;
+    }
+
+    fn rx_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
+        tracing::debug!("rx_prim: {:?}", message.msg);
+
+        match (message.sap, message.msg) {
+            (Sap::TmdSap, SapMsgInner::TmdCircuitDataInd(prim)) => self.rx_tmd_circuit_data_ind(prim),
+            (Sap::Control, SapMsgInner::CmceCallControl(CallControl::Open(circuit))) => self.rx_call_open(circuit),
+            (Sap::Control, SapMsgInner::CmceCallControl(CallControl::Close(_, ts))) => self.rx_call_close(ts),
+            (sap, msg) => tracing::warn!("rx_prim: dropping unexpected message on {:?}: {:?}", sap, msg),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tetra_config::{PhyBackend, StackConfig, StackMode};
+
+    use super::*;
+
+    /// Collects frames in memory, shared with the test
+    struct MemorySink(Arc<Mutex<Vec<Vec<u8>>>>);
+
+    impl FrameSink for MemorySink {
+        fn write_frame(&mut self, frame: &[u8]) -> std::io::Result<()> {
+            self.0.lock().unwrap().push(frame.to_vec());
+            Ok(())
+        }
+    }
+
+    fn test_config() -> SharedConfig {
+        let mut cfg = StackConfig::new(StackMode::Bs, 204, 1337);
+        cfg.phy_io.backend = PhyBackend::None;
+        SharedConfig::from_config(cfg)
+    }
+
+    fn ind(call_id: CallId, ts: u8, data: &[u8]) -> SapMsg {
+        SapMsg {
+            sap: Sap::TmdSap,
+            src: TetraEntity::Umac,
+            dest: TetraEntity::User,
+            dltime: TdmaTime::default(),
+            msg: SapMsgInner::TmdCircuitDataInd(TmdCircuitDataInd { call_id, ts, data: data.to_vec() }),
+        }
+    }
+
+    #[test]
+    fn test_frames_reach_sink_of_their_call() {
+        let mut uplane = UplaneBs::new(test_config());
+        let mut queue = MessageQueue::new();
+
+        let frames_5 = Arc::new(Mutex::new(Vec::new()));
+        let frames_6 = Arc::new(Mutex::new(Vec::new()));
+        uplane.set_sink(5, Box::new(MemorySink(frames_5.clone())));
+        uplane.set_sink(6, Box::new(MemorySink(frames_6.clone())));
+
+        uplane.rx_prim(&mut queue, ind(5, 2, &[1, 2, 3]));
+        uplane.rx_prim(&mut queue, ind(6, 3, &[4, 5]));
+        uplane.rx_prim(&mut queue, ind(5, 2, &[6]));
+        // No sink and nothing configured: dropped
+        uplane.rx_prim(&mut queue, ind(7, 4, &[7]));
+
+        assert_eq!(*frames_5.lock().unwrap(), vec![vec![1, 2, 3], vec![6]]);
+        assert_eq!(*frames_6.lock().unwrap(), vec![vec![4, 5]]);
+
+        // Frames after the call ended are not written to its old sink
+        uplane.remove_sink(5);
+        uplane.rx_prim(&mut queue, ind(5, 2, &[8]));
+        assert_eq!(frames_5.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_send_frame_addresses_umac() {
+        let uplane = UplaneBs::new(test_config());
+        let mut queue = MessageQueue::new();
+
+        uplane.send_frame(&mut queue, 5, vec![0xA5; 4], TdmaTime::default());
+        let msg = queue.pop_front().unwrap();
+        assert_eq!(msg.dest, TetraEntity::Umac);
+        let SapMsgInner::TmdCircuitDataReq(prim) = msg.msg else { panic!() };
+        assert_eq!(prim.call_id, 5);
+        assert_eq!(prim.data, vec![0xA5; 4]);
+    }
+}
@@ -1,8 +1,9 @@
 use tetra_core::freqs::FreqInfo;
 use tetra_core::tetra_entities::TetraEntity;
 use tetra_core::TdmaTime;
-use tetra_config::{CfgCellInfo, CfgNetInfo, CfgPhyIo, PhyBackend, SharedConfig, StackConfig, StackMode, StackState};
+use tetra_config::{CfgAccessControl, CfgCallControl, CfgCellInfo, CfgNetInfo, CfgPhyIo, CfgSds, PhyBackend, SharedConfig, StackConfig, StackMode, StackState, SubscriberDb};
 use tetra_entities::{MessageRouter, TetraEntityTrait};
+use tetra_entities::clock::ManualClock;
 use tetra_saps::sapmsg::SapMsg;
 
 // BS imports
@@ -17,6 +18,7 @@ use tetra_entities::umac::umac_bs::UmacBs;
 
 // MS imports
 use tetra_entities::umac::umac_ms::UmacMs;
+use tetra_entities::uplane::uplane_bs::UplaneBs;
 use tetra_entities::lmac::lmac_ms::LmacMs;
 
 use super::sink::Sink;
@@ -46,6 +48,15 @@ pub fn default_test_config(stack_mode: StackMode) -> StackConfig {
         phy_io,
         net: net_info,
         cell: cell_info,
+        access_control: CfgAccessControl::default(),
+        power_control: None,
+        uplane: None,
+        sds_autoresponder: None,
+        sds: CfgSds::default(),
+        call_control: CfgCallControl::default(),
+        temporary_addresses: None,
+        neighbor_cells: Vec::new(),
+        subscribers: SubscriberDb::default(),
     }
 }
 
@@ -79,6 +90,14 @@ impl ComponentTest {
         }
     }
     
+    /// Drives the router from a ManualClock starting at the start time. Time then only
+    /// moves when the returned clock is advanced.
+    pub fn use_manual_clock(&mut self) -> ManualClock {
+        let clock = ManualClock::new(self.start_dl_time);
+        self.router.set_clock(Box::new(clock.clone()));
+        clock
+    }
+
     pub fn get_shared_config(&self) -> SharedConfig {
         self.config.clone()
     }
@@ -138,6 +157,10 @@ impl ComponentTest {
                     let cmce = CmceBs::new(self.config.clone());
                     self.router.register_entity(Box::new(cmce));
                 }
+                TetraEntity::User => {
+                    let uplane = UplaneBs::new(self.config.clone());
+                    self.router.register_entity(Box::new(uplane));
+                }
                 _ => {
                     panic!("Component not implemented: {:?}", component);
                 }
@@ -0,0 +1,981 @@
+mod common;
+
+use tetra_core::{BitBuffer, debug, Sap, SsiType, TdmaTime, TetraAddress};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_config::{CfgSdsAutoresponder, ProtocolEdition, CfgSdsRule, CfgTemporaryAddresses, SdsTextCoding, StackMode};
+use tetra_entities::MessageQueue;
+use tetra_entities::control::queries::{ControlQuery, answer_query};
+use tetra_entities::cmce::cmce_bs::CmceBs;
+use tetra_entities::cmce::components::call_timers::CallTimer;
+use tetra_entities::cmce::components::sds_protocol::SdsProtocol;
+use tetra_entities::cmce::components::sds_text::decode_text;
+use tetra_entities::cmce::components::paging_queue::{PAGING_MAX_ATTEMPTS, PAGING_REPEAT_INTERVAL};
+use tetra_entities::cmce::subentities::cc_bs::{DISCONNECT_CAUSE_CONGESTION, DISCONNECT_CAUSE_NOT_REACHABLE, MAX_SETUP_REQUESTS};
+use tetra_entities::mm::mm_bs::MmBs;
+use tetra_pdus::cmce::enums::call_timeout::CallTimeout;
+use tetra_pdus::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
+use tetra_pdus::cmce::enums::transmission_grant::TransmissionGrant;
+use tetra_pdus::cmce::pdus::d_call_proceeding::DCallProceeding;
+use tetra_pdus::cmce::pdus::d_connect::DConnect;
+use tetra_pdus::cmce::pdus::d_setup::DSetup;
+use tetra_pdus::cmce::pdus::d_info::DInfo;
+use tetra_pdus::cmce::pdus::d_release::DRelease;
+use tetra_pdus::cmce::pdus::d_sds_data::DSdsData;
+use tetra_pdus::cmce::pdus::d_tx_granted::DTxGranted;
+use tetra_pdus::cmce::pdus::u_alert::UAlert;
+use tetra_pdus::cmce::pdus::u_call_restore::UCallRestore;
+use tetra_pdus::cmce::pdus::u_connect::UConnect;
+use tetra_pdus::cmce::pdus::u_tx_demand::UTxDemand;
+use tetra_pdus::cmce::fields::basic_service_information::BasicServiceInformation;
+use tetra_pdus::cmce::pdus::u_info::UInfo;
+use tetra_pdus::cmce::pdus::u_setup::USetup;
+use tetra_pdus::cmce::pdus::u_sds_data::USdsData;
+use tetra_saps::lcmc::{CallId, LcmcMleUnitdataInd};
+use tetra_saps::lcmc::enums::ul_dl_assignment::UlDlAssignment;
+use tetra_saps::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
+use tetra_saps::control::call_control::CallControl;
+use tetra_saps::control::enums::{circuit_mode_type::CircuitModeType, communication_type::CommunicationType};
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use common::{ComponentTest, default_test_config};
+
+const PAGED_SSI: u32 = 2040814;
+
+fn cmce(test: &mut ComponentTest) -> &mut CmceBs {
+    test.router.get_entity(TetraEntity::Cmce).unwrap().as_any_mut().downcast_mut::<CmceBs>().unwrap()
+}
+
+/// Returns the call identifiers of all D-SETUP pages sent to PAGED_SSI
+fn pages_sent(msgs: Vec<SapMsg>) -> Vec<u16> {
+    msgs.into_iter().filter_map(|msg| {
+        let SapMsgInner::LcmcMleUnitdataReq(mut prim) = msg.msg else { return None };
+        assert_eq!(prim.main_address.ssi, PAGED_SSI);
+        assert!(prim.chan_alloc.is_none());
+        DSetup::from_bitbuf(&mut prim.sdu).ok().map(|pdu| pdu.call_identifier)
+    }).collect()
+}
+
+/// Sets up an individual call to PAGED_SSI, returning its call identifier
+fn setup_test(dltime: TdmaTime) -> (ComponentTest, CallId) {
+    let config = default_test_config(StackMode::Bs);
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+    let mut queue = MessageQueue::new();
+    let call_id = cmce(&mut test).setup_individual_call(&mut queue, PAGED_SSI, dltime).unwrap();
+    assert!(cmce(&mut test).paging().is_paging(PAGED_SSI));
+    (test, call_id)
+}
+
+#[test]
+fn test_paging_response() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let (mut test, call_id) = setup_test(dltime);
+
+    test.run_stack(Some(1));
+    assert_eq!(pages_sent(test.dump_sinks()), vec![call_id]);
+    assert!(cmce(&mut test).paging().is_paging(PAGED_SSI));
+
+    // Other PDUs from the MS are no response to the page
+    send_sds_status(&mut test, 1000, 0x8000);
+    assert!(cmce(&mut test).paging().is_paging(PAGED_SSI));
+
+    // The MS responds by alerting
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UAlert { call_identifier: call_id, ..Default::default() }.to_bitbuf(&mut sdu).unwrap();
+    submit_from_ms(&mut test, PAGED_SSI, sdu, dltime.add_timeslots(4));
+    assert!(cmce(&mut test).paging().is_empty());
+    assert!(cmce(&mut test).is_call_timer_running(call_id, CallTimer::T302));
+
+    // No further pages are sent
+    test.run_stack(Some(2 * PAGING_REPEAT_INTERVAL as usize));
+    assert!(pages_sent(test.dump_sinks()).is_empty());
+}
+
+#[test]
+fn test_paging_timeout() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let (mut test, call_id) = setup_test(dltime);
+
+    test.run_stack(Some(PAGING_MAX_ATTEMPTS as usize * PAGING_REPEAT_INTERVAL as usize + 1));
+    assert_eq!(pages_sent(test.dump_sinks()), vec![call_id; PAGING_MAX_ATTEMPTS as usize]);
+    assert!(cmce(&mut test).paging().is_empty());
+}
+
+#[test]
+fn test_paging_repeat_with_manual_clock() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let (mut test, call_id) = setup_test(dltime);
+    let clock = test.use_manual_clock();
+
+    test.run_stack(Some(1));
+    assert_eq!(pages_sent(test.dump_sinks()), vec![call_id]);
+
+    // Time stands still between ticks, so the page is not repeated early
+    test.run_stack(Some(10));
+    assert!(pages_sent(test.dump_sinks()).is_empty());
+    clock.advance(PAGING_REPEAT_INTERVAL - 1);
+    test.run_stack(Some(1));
+    assert!(pages_sent(test.dump_sinks()).is_empty());
+
+    // The page is repeated exactly when the repeat interval has passed
+    clock.advance(1);
+    test.run_stack(Some(1));
+    assert_eq!(pages_sent(test.dump_sinks()), vec![call_id]);
+    assert!(cmce(&mut test).paging().is_paging(PAGED_SSI));
+}
+
+#[test]
+fn test_send_text_sds() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut config = default_test_config(StackMode::Bs);
+    config.sds.text_coding = SdsTextCoding::Gsm7;
+    config.sds.sender_ssi = 9000;
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+
+    let text = "Meet at gate 3, 12:30 (Ø-side)";
+    let mut queue = MessageQueue::new();
+    assert!(cmce(&mut test).send_text_sds(&mut queue, PAGED_SSI, text, dltime));
+    let SapMsgInner::LcmcMleUnitdataReq(mut prim) = queue.pop_front().unwrap().msg else { panic!("Expected LcmcMleUnitdataReq") };
+    assert_eq!(prim.main_address.ssi, PAGED_SSI);
+    assert_eq!(prim.main_address.ssi_type, SsiType::Issi);
+
+    let pdu = DSdsData::from_bitbuf(&mut prim.sdu).unwrap();
+    assert_eq!(pdu.calling_party_address_ssi, Some(9000));
+    let len = pdu.length_indicator.unwrap() as usize;
+    assert_eq!(len, 16 + 7 * text.chars().count());
+    assert_eq!(decode_text(&pdu.user_defined_data_4.unwrap(), len).as_deref(), Some(text));
+
+    // Text the coding can not represent is not sent
+    assert!(!cmce(&mut test).send_text_sds(&mut queue, PAGED_SSI, "50 €", dltime));
+    assert!(queue.pop_front().is_none());
+}
+
+/// Submits an U-SDS-DATA status from PAGED_SSI to `called_ssi` and returns the D-SDS-DATA replies
+fn send_sds_status(test: &mut ComponentTest, called_ssi: u64, status: u16) -> Vec<DSdsData> {
+    send_sds(test, USdsData {
+        called_party_type_identifier: 1,
+        called_party_ssi: Some(called_ssi),
+        short_data_type_identifier: 0,
+        user_defined_data_1: Some(status as u64),
+        ..Default::default()
+    })
+}
+
+/// Submits an U-SDS-DATA from PAGED_SSI and returns the D-SDS-DATA replies
+fn send_sds(test: &mut ComponentTest, pdu: USdsData) -> Vec<DSdsData> {
+    let mut sdu = BitBuffer::new_autoexpand(64);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(SapMsg {
+        sap: Sap::LcmcSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Cmce,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+            sdu,
+            handle: 0,
+            endpoint_id: 0,
+            link_id: 0,
+            received_tetra_address: TetraAddress { encrypted: false, ssi_type: SsiType::Issi, ssi: PAGED_SSI },
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+            rx_quality: None,
+        }),
+    });
+    test.deliver_all_messages();
+    test.dump_sinks().into_iter().filter_map(|msg| {
+        let SapMsgInner::LcmcMleUnitdataReq(mut prim) = msg.msg else { return None };
+        assert_eq!(prim.main_address.ssi, PAGED_SSI);
+        DSdsData::from_bitbuf(&mut prim.sdu).ok()
+    }).collect()
+}
+
+#[test]
+fn test_pdu_stats_query() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let (mut test, call_id) = setup_test(dltime);
+
+    // The page is generated, the U-SDS-DATA from the paged MS decoded
+    test.run_stack(Some(1));
+    assert_eq!(pages_sent(test.dump_sinks()), vec![call_id]);
+    send_sds_status(&mut test, 1000, 0x8000);
+    send_sds_status(&mut test, 1000, 0x8001);
+
+    let out = answer_query(&mut test.router, ControlQuery::PduStats);
+    assert_eq!(out, "2 pdu types; DSetup decoded 0 generated 1; USdsData decoded 2 generated 0");
+}
+
+#[test]
+fn test_sds_protocol_dispatch() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(default_test_config(StackMode::Bs), None);
+    test.populate_entities(vec![TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+
+    let user_data = |data: Vec<u8>| USdsData {
+        called_party_type_identifier: 1,
+        called_party_ssi: Some(1000),
+        short_data_type_identifier: 3,
+        length_indicator: Some(data.len() as u64 * 8),
+        user_defined_data_4: Some(data),
+        ..Default::default()
+    };
+
+    // Simple text (PID 0x02) and a LIP short location report (PID 0x0A)
+    assert!(send_sds(&mut test, user_data(vec![0x02, 0x01, b'H', b'i'])).is_empty());
+    assert!(send_sds(&mut test, user_data(vec![0x0A, 0x00, 0x12, 0x34, 0x56, 0x78])).is_empty());
+    assert_eq!(cmce(&mut test).sds_received(SdsProtocol::SimpleText), 1);
+    assert_eq!(cmce(&mut test).sds_received(SdsProtocol::Lip), 1);
+
+    // Text with SDS-TL is told apart from simple text
+    send_sds(&mut test, user_data(vec![0x82, 0x00, 0x01, 0x01, b'H', b'i']));
+    assert_eq!(cmce(&mut test).sds_received(SdsProtocol::TlText), 1);
+    assert_eq!(cmce(&mut test).sds_received(SdsProtocol::SimpleText), 1);
+    assert_eq!(cmce(&mut test).sds_received(SdsProtocol::Lip), 1);
+}
+
+#[test]
+fn test_sds_location_report() {
+    debug::setup_logging_verbose();
+    let mut test = ComponentTest::new(default_test_config(StackMode::Bs), None);
+    test.populate_entities(vec![TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+    assert!(cmce(&mut test).ms_location(PAGED_SSI).is_none());
+
+    let lip = |data: Vec<u8>| USdsData {
+        called_party_type_identifier: 1,
+        called_party_ssi: Some(1000),
+        short_data_type_identifier: 3,
+        length_indicator: Some(84),
+        user_defined_data_4: Some(data),
+        ..Default::default()
+    };
+
+    // LIP short location reports of 52.0 N 4.5 E, then 33.5 S 70.25 W
+    send_sds(&mut test, lip(vec![0x0a, 0x10, 0x33, 0x33, 0x32, 0x4f, 0xa4, 0xfa, 0x3c, 0x82, 0x10]));
+    let report = cmce(&mut test).ms_location(PAGED_SSI).unwrap().report;
+    assert!((report.latitude - 52.0).abs() < 1e-5);
+    assert!((report.longitude - 4.5).abs() < 1e-5);
+
+    send_sds(&mut test, lip(vec![0x0a, 0x3c, 0xe0, 0xb6, 0x0e, 0x82, 0xd8, 0x37, 0xfe, 0x15, 0x50]));
+    let report = cmce(&mut test).ms_location(PAGED_SSI).unwrap().report;
+    assert!((report.latitude + 33.5).abs() < 1e-5);
+    assert!((report.longitude + 70.25).abs() < 1e-5);
+    assert_eq!(cmce(&mut test).locations().history(PAGED_SSI).count(), 2);
+}
+
+#[test]
+fn test_sds_autoresponder() {
+    debug::setup_logging_verbose();
+    let mut config = default_test_config(StackMode::Bs);
+    config.sds_autoresponder = Some(CfgSdsAutoresponder {
+        rules: vec![
+            CfgSdsRule { match_status: 0x8002, reply_status: Some(0x8003), reply_text: None },
+            CfgSdsRule { match_status: 0x8004, reply_status: None, reply_text: Some("OK".to_string()) },
+        ],
+    });
+    config.validate().unwrap();
+    let mut test = ComponentTest::new(config, Some(TdmaTime::default()));
+    test.populate_entities(vec![TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+
+    // Status reply, sent from the address the SDS was sent to
+    let replies = send_sds_status(&mut test, 1000, 0x8002);
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0].calling_party_address_ssi, Some(1000));
+    assert_eq!(replies[0].user_defined_data_1, Some(0x8003));
+
+    // Text reply, as a simple text message
+    let replies = send_sds_status(&mut test, 1000, 0x8004);
+    assert_eq!(replies.len(), 1);
+    assert_eq!(replies[0].short_data_type_identifier, 3);
+    assert_eq!(replies[0].user_defined_data_4, Some(vec![0x02, 0x01, b'O', b'K']));
+
+    // No rule for this status
+    assert!(send_sds_status(&mut test, 1000, 0x8005).is_empty());
+
+    // Rules need exactly one reply
+    let mut config = default_test_config(StackMode::Bs);
+    config.sds_autoresponder = Some(CfgSdsAutoresponder {
+        rules: vec![CfgSdsRule { match_status: 0x8002, reply_status: None, reply_text: None }],
+    });
+    assert!(config.validate().is_err());
+}
+
+/// Registers each ISSI with MM and attaches it to the GSSI paired with it
+fn attach_to_groups(test: &mut ComponentTest, attachments: &[(u32, u32)]) {
+    let mm = test.router.get_entity(TetraEntity::Mm).unwrap().as_any_mut().downcast_mut::<MmBs>().unwrap();
+    for &(issi, gssi) in attachments {
+        mm.client_mgr.try_register_client(issi, true).unwrap();
+        mm.client_mgr.client_group_attach(issi, gssi, true).unwrap();
+    }
+
+    // Report the attachments to CMCE, as MM does
+    let changes = mm.client_mgr.take_group_changes();
+    for change in changes {
+        test.submit_message(SapMsg {
+            sap: Sap::Control,
+            src: TetraEntity::Mm,
+            dest: TetraEntity::Cmce,
+            dltime: TdmaTime::default(),
+            msg: SapMsgInner::MmGroupControl(change),
+        });
+    }
+    test.deliver_all_messages();
+}
+
+#[test]
+fn test_group_call_setup() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let config = default_test_config(StackMode::Bs);
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm, TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+
+    // Two MSs attach to group 91, a third to another group
+    attach_to_groups(&mut test, &[(2040001, 91), (2040002, 91), (2040003, 26)]);
+
+    // No call is set up to a group without members
+    let mut queue = MessageQueue::new();
+    assert_eq!(cmce(&mut test).setup_group_call(&mut queue, 7, dltime), None);
+    assert!(queue.pop_front().is_none());
+
+    let call_id = cmce(&mut test).setup_group_call(&mut queue, 91, dltime).unwrap();
+    let group_call = cmce(&mut test).group_call(call_id).unwrap().clone();
+    assert_eq!(group_call.gssi, 91);
+    assert_eq!(group_call.members, vec![2040001, 2040002]);
+
+    // The circuit is opened and the D-SETUP sent once, addressed to the group
+    let SapMsgInner::CmceCallControl(CallControl::Open(circuit)) = queue.pop_front().unwrap().msg else { panic!("Expected circuit open") };
+    assert_eq!(circuit.ssi, 91);
+    let SapMsgInner::LcmcMleUnitdataReq(mut prim) = queue.pop_front().unwrap().msg else { panic!("Expected LcmcMleUnitdataReq") };
+    assert_eq!(prim.main_address.ssi, 91);
+    assert_eq!(prim.main_address.ssi_type, SsiType::Gssi);
+    assert_eq!(DSetup::from_bitbuf(&mut prim.sdu).unwrap().call_identifier, call_id);
+    assert!(queue.pop_front().is_none());
+}
+
+/// Sets up a call to group 91 and returns the downlink times of the D-SETUPs sent to the
+/// group in the following 5 multiframes
+fn group_call_d_setup_times(late_entry: bool) -> Vec<TdmaTime> {
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut config = default_test_config(StackMode::Bs);
+    config.cell.late_entry_supported = late_entry;
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm, TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+    attach_to_groups(&mut test, &[(2040001, 91)]);
+    let mut queue = MessageQueue::new();
+    cmce(&mut test).setup_group_call(&mut queue, 91, dltime).unwrap();
+    test.run_stack(Some(5 * 18 * 4));
+
+    test.dump_sinks().into_iter().filter_map(|msg| {
+        let SapMsgInner::LcmcMleUnitdataReq(mut prim) = msg.msg else { return None };
+        (prim.main_address.ssi == 91 && DSetup::from_bitbuf(&mut prim.sdu).is_ok()).then_some(msg.dltime)
+    }).collect()
+}
+
+#[test]
+fn test_group_call_late_entry() {
+    debug::setup_logging_verbose();
+
+    // Without late entry, the D-SETUP is only sent while the call is set up
+    let without = group_call_d_setup_times(false);
+    assert!(!without.is_empty());
+    assert!(without.iter().all(|t| t.m == 1));
+
+    // With late entry, it keeps being repeated for the lifetime of the call
+    let with = group_call_d_setup_times(true);
+    assert_eq!(with[..without.len()], without[..]);
+    assert!(with.len() > without.len());
+    for m in 2..=5 {
+        assert!(with.iter().any(|t| t.m == m), "no D-SETUP repeated in multiframe {}", m);
+    }
+}
+
+#[test]
+fn test_group_call_avoids_mcch_timeslot() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut config = default_test_config(StackMode::Bs);
+    config.cell.mcch_timeslot = 2;
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm, TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+    attach_to_groups(&mut test, &[(2040001, 91), (2040002, 92), (2040003, 93), (2040004, 94)]);
+
+    // Calls take timeslots 1, 3 and 4, never the MCCH on timeslot 2. The D-SETUP
+    // allocates the timeslot of the circuit.
+    let mut timeslots = Vec::new();
+    for gssi in [91, 92, 93] {
+        let mut queue = MessageQueue::new();
+        cmce(&mut test).setup_group_call(&mut queue, gssi, dltime).unwrap();
+        let SapMsgInner::CmceCallControl(CallControl::Open(circuit)) = queue.pop_front().unwrap().msg else { panic!("Expected circuit open") };
+        let SapMsgInner::LcmcMleUnitdataReq(prim) = queue.pop_front().unwrap().msg else { panic!("Expected LcmcMleUnitdataReq") };
+        let allocated: Vec<u8> = (1..=4).filter(|&ts| prim.chan_alloc.as_ref().unwrap().timeslots[ts as usize - 1]).collect();
+        assert_eq!(allocated, vec![circuit.ts]);
+        timeslots.push(circuit.ts);
+    }
+    timeslots.sort();
+    assert_eq!(timeslots, vec![1, 3, 4]);
+
+    let mut queue = MessageQueue::new();
+    assert_eq!(cmce(&mut test).setup_group_call(&mut queue, 94, dltime), None);
+}
+
+#[test]
+fn test_group_call_temporary_address() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut config = default_test_config(StackMode::Bs);
+    config.temporary_addresses = Some(CfgTemporaryAddresses { first: 0xFE0000, last: 0xFE0001 });
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm, TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+    attach_to_groups(&mut test, &[(2040001, 91), (2040002, 92), (2040003, 93)]);
+
+    // Each call carries its own temporary address in the D-SETUP
+    let mut addresses = Vec::new();
+    for gssi in [91, 92] {
+        let mut queue = MessageQueue::new();
+        let call_id = cmce(&mut test).setup_group_call(&mut queue, gssi, dltime).unwrap();
+        let address = cmce(&mut test).temporary_address(call_id).unwrap();
+        assert!((0xFE0000..=0xFE0001).contains(&address));
+        queue.pop_front();
+        let SapMsgInner::LcmcMleUnitdataReq(mut prim) = queue.pop_front().unwrap().msg else { panic!("Expected LcmcMleUnitdataReq") };
+        assert_eq!(DSetup::from_bitbuf(&mut prim.sdu).unwrap().temporary_address, Some(address as u64));
+        addresses.push((call_id, address));
+    }
+    assert_ne!(addresses[0].1, addresses[1].1);
+
+    // With the pool exhausted, a call is set up without one
+    let mut queue = MessageQueue::new();
+    let call_id = cmce(&mut test).setup_group_call(&mut queue, 93, dltime).unwrap();
+    assert_eq!(cmce(&mut test).temporary_address(call_id), None);
+
+    // Released calls return their temporary address to the pool
+    test.run_stack(Some(10 * 18 * 4 + 4 * 18));
+    for (call_id, _) in &addresses {
+        assert_eq!(cmce(&mut test).temporary_address(*call_id), None);
+    }
+    let mut queue = MessageQueue::new();
+    let call_id = cmce(&mut test).setup_group_call(&mut queue, 91, dltime).unwrap();
+    assert!(addresses.iter().any(|(_, address)| cmce(&mut test).temporary_address(call_id) == Some(*address)));
+}
+
+#[test]
+fn test_emergency_call_preempts_routine_call() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut config = default_test_config(StackMode::Bs);
+    config.call_control.preemption = true;
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm, TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+    attach_to_groups(&mut test, &[(2040001, 91), (2040002, 92), (2040003, 93), (2040004, 94)]);
+
+    // Routine calls take all traffic timeslots
+    let mut queue = MessageQueue::new();
+    let routine: Vec<_> = [91, 92, 93].into_iter()
+        .map(|gssi| cmce(&mut test).setup_group_call(&mut queue, gssi, dltime).unwrap())
+        .collect();
+    let mut queue = MessageQueue::new();
+    assert_eq!(cmce(&mut test).setup_group_call_with_priority(&mut queue, 94, 11, dltime), None);
+    assert!(queue.pop_front().is_none());
+
+    // An emergency call releases a routine call with the pre-emption cause and takes its circuit
+    let call_id = cmce(&mut test).setup_group_call_with_priority(&mut queue, 94, 15, dltime).unwrap();
+    assert_eq!(cmce(&mut test).call_priority(call_id), Some(15));
+    assert_eq!(cmce(&mut test).call_priority(routine[0]), None);
+    assert!(cmce(&mut test).group_call(routine[0]).is_none());
+
+    let SapMsgInner::LcmcMleUnitdataReq(mut prim) = queue.pop_front().unwrap().msg else { panic!("Expected LcmcMleUnitdataReq") };
+    assert_eq!(prim.main_address.ssi, 91);
+    let release = DRelease::from_bitbuf(&mut prim.sdu).unwrap();
+    assert_eq!(release.call_identifier, routine[0]);
+    assert_eq!(release.disconnect_cause, 9);
+    let SapMsgInner::CmceCallControl(CallControl::Close(_, ts)) = queue.pop_front().unwrap().msg else { panic!("Expected circuit close") };
+    let SapMsgInner::CmceCallControl(CallControl::Open(circuit)) = queue.pop_front().unwrap().msg else { panic!("Expected circuit open") };
+    assert_eq!((circuit.ts, circuit.ssi), (ts, 94));
+    let SapMsgInner::LcmcMleUnitdataReq(mut prim) = queue.pop_front().unwrap().msg else { panic!("Expected LcmcMleUnitdataReq") };
+    let setup = DSetup::from_bitbuf(&mut prim.sdu).unwrap();
+    assert_eq!((setup.call_identifier, setup.call_priority), (call_id, 15));
+    assert!(queue.pop_front().is_none());
+
+    // The remaining routine calls are pre-empted in turn, emergency calls do not pre-empt each other
+    let mut queue = MessageQueue::new();
+    for gssi in [92, 93] {
+        assert!(cmce(&mut test).setup_group_call_with_priority(&mut queue, gssi, 15, dltime).is_some());
+    }
+    assert!(routine.iter().all(|&routine_id| cmce(&mut test).call_priority(routine_id).is_none()));
+    assert_eq!(cmce(&mut test).setup_group_call_with_priority(&mut queue, 91, 15, dltime), None);
+    assert_eq!(cmce(&mut test).call_priority(call_id), Some(15));
+}
+
+/// Submits a U-SETUP from `calling_ssi` for a call to group `gssi`
+fn request_group_call(test: &mut ComponentTest, calling_ssi: u32, gssi: u32, priority: u8, dltime: TdmaTime) {
+    request_call(test, calling_ssi, gssi, CommunicationType::P2Mp, priority, dltime);
+}
+
+/// Submits a U-SETUP from `calling_ssi` for a call of `communication_type` to `called_ssi`
+fn request_call(test: &mut ComponentTest, calling_ssi: u32, called_ssi: u32, communication_type: CommunicationType, priority: u8, dltime: TdmaTime) {
+    let pdu = USetup {
+        area_selection: 0,
+        hook_method_selection: false,
+        simplex_duplex_selection: false,
+        basic_service_information: BasicServiceInformation {
+            circuit_mode_type: CircuitModeType::TchS,
+            encryption_flag: false,
+            communication_type,
+            slots_per_frame: None,
+            speech_service: Some(0),
+        },
+        request_to_transmit_send_data: true,
+        call_priority: priority,
+        clir_control: 0,
+        called_party_type_identifier: 1,
+        called_party_short_number_address: None,
+        called_party_ssi: Some(called_ssi as u64),
+        called_party_extension: None,
+        external_subscriber_number: None,
+        facility: None,
+        dm_ms_address: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(64);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    submit_from_ms(test, calling_ssi, sdu, dltime);
+}
+
+/// Delivers the CMCE PDU in `sdu` to CMCE as received from the MS with `ssi`
+fn submit_from_ms(test: &mut ComponentTest, ssi: u32, mut sdu: BitBuffer, dltime: TdmaTime) {
+    sdu.seek(0);
+    test.submit_message(SapMsg {
+        sap: Sap::LcmcSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+            sdu,
+            handle: 0,
+            endpoint_id: 0,
+            link_id: 0,
+            received_tetra_address: TetraAddress { encrypted: false, ssi_type: SsiType::Issi, ssi },
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+            rx_quality: None,
+        }),
+    });
+    test.deliver_all_messages();
+}
+
+#[test]
+fn test_emergency_call_request_served_first() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(default_test_config(StackMode::Bs), Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm, TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+    attach_to_groups(&mut test, &[(2040001, 91), (2040002, 92), (2040003, 93), (2040004, 94), (2040005, 95)]);
+
+    // With all circuits in use, a routine call is queued, then an emergency call goes ahead of it
+    let mut queue = MessageQueue::new();
+    for gssi in [91, 92, 93] {
+        cmce(&mut test).setup_group_call(&mut queue, gssi, dltime).unwrap();
+    }
+    request_group_call(&mut test, 2040004, 94, 0, dltime);
+    request_group_call(&mut test, 2040005, 95, 15, dltime);
+    let requested: Vec<_> = cmce(&mut test).setup_requests().iter().map(|r| (r.gssi, r.priority)).collect();
+    assert_eq!(requested, vec![(95, 15), (94, 0)]);
+
+    // Once the circuits expire, the emergency call is set up first
+    test.run_stack(Some(10 * 18 * 4 + 4 * 18));
+    let setups: Vec<u32> = test.dump_sinks().into_iter().filter_map(|msg| {
+        let SapMsgInner::LcmcMleUnitdataReq(mut prim) = msg.msg else { return None };
+        DSetup::from_bitbuf(&mut prim.sdu).ok().map(|_| prim.main_address.ssi)
+    }).filter(|ssi| [94, 95].contains(ssi)).collect();
+    assert_eq!(setups.first(), Some(&95));
+    assert!(setups.contains(&94));
+    assert!(cmce(&mut test).setup_requests().is_empty());
+}
+
+#[test]
+fn test_emergency_call_request_preempts() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut config = default_test_config(StackMode::Bs);
+    config.call_control.preemption = true;
+    config.call_control.emergency_priority = 14;
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm, TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+    attach_to_groups(&mut test, &[(2040001, 91), (2040002, 92), (2040003, 93), (2040004, 94), (2040005, 95)]);
+
+    let mut queue = MessageQueue::new();
+    for gssi in [91, 92, 93] {
+        cmce(&mut test).setup_group_call(&mut queue, gssi, dltime).unwrap();
+    }
+    test.dump_sinks();
+
+    // The routine request waits, the emergency request is set up at once on a pre-empted circuit
+    request_group_call(&mut test, 2040004, 94, 0, dltime);
+    request_group_call(&mut test, 2040005, 95, 14, dltime);
+    let requested: Vec<_> = cmce(&mut test).setup_requests().iter().map(|r| r.gssi).collect();
+    assert_eq!(requested, vec![94]);
+    let setups: Vec<u32> = test.dump_sinks().into_iter().filter_map(|msg| {
+        let SapMsgInner::LcmcMleUnitdataReq(mut prim) = msg.msg else { return None };
+        DSetup::from_bitbuf(&mut prim.sdu).ok().map(|_| prim.main_address.ssi)
+    }).collect();
+    assert_eq!(setups, vec![95]);
+}
+
+/// Takes the CMCE PDUs sent down the stack, with the SSI they are addressed to and their channel allocation
+fn sent_cmce_pdus(test: &mut ComponentTest) -> Vec<(u32, CmcePduTypeDl, BitBuffer, Option<CmceChanAllocReq>)> {
+    test.dump_sinks().into_iter().filter_map(|msg| {
+        let SapMsgInner::LcmcMleUnitdataReq(prim) = msg.msg else { return None };
+        let pdu_type = CmcePduTypeDl::try_from(prim.sdu.peek_bits(5).unwrap()).unwrap();
+        Some((prim.main_address.ssi, pdu_type, prim.sdu, prim.chan_alloc))
+    }).collect()
+}
+
+#[test]
+fn test_group_call_request_connects_caller() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(default_test_config(StackMode::Bs), Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm, TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+    attach_to_groups(&mut test, &[(2040001, 91), (2040002, 91)]);
+
+    // The caller is answered with D-CALL PROCEEDING, the group is sent the D-SETUP, and the
+    // caller is connected with transmission granted on the same call
+    request_group_call(&mut test, 2040001, 91, 0, dltime);
+    let mut pdus = sent_cmce_pdus(&mut test).into_iter();
+
+    let (ssi, pdu_type, mut sdu, _) = pdus.next().unwrap();
+    assert_eq!((ssi, pdu_type), (2040001, CmcePduTypeDl::DCallProceeding));
+    let call_id = DCallProceeding::from_bitbuf(&mut sdu).unwrap().call_identifier;
+
+    let (ssi, pdu_type, mut sdu, chan_alloc) = pdus.next().unwrap();
+    assert_eq!((ssi, pdu_type), (91, CmcePduTypeDl::DSetup));
+    let setup = DSetup::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(setup.call_identifier, call_id);
+    assert_eq!(setup.calling_party_address_ssi, Some(2040001));
+    assert_eq!(setup.transmission_grant, TransmissionGrant::GrantedToOtherUser);
+    let timeslots = chan_alloc.unwrap().timeslots;
+
+    let (ssi, pdu_type, mut sdu, chan_alloc) = pdus.next().unwrap();
+    assert_eq!((ssi, pdu_type), (2040001, CmcePduTypeDl::DConnect));
+    let connect = DConnect::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(connect.call_identifier, call_id);
+    assert_eq!(connect.transmission_grant, TransmissionGrant::Granted);
+    let chan_alloc = chan_alloc.unwrap();
+    assert_eq!((chan_alloc.timeslots, chan_alloc.ul_dl_assigned), (timeslots, UlDlAssignment::Both));
+    assert!(pdus.next().is_none());
+    assert_eq!(cmce(&mut test).group_call(call_id).unwrap().gssi, 91);
+}
+
+#[test]
+fn test_group_call_advertises_t310_timeout() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut config = default_test_config(StackMode::Bs);
+    config.call_control.t310_s = 600;
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm, TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+    attach_to_groups(&mut test, &[(2040001, 91), (2040002, 91)]);
+
+    // Both the D-SETUP to the group and the D-CONNECT to the caller carry the T310 length
+    request_group_call(&mut test, 2040001, 91, 0, dltime);
+    let pdus = sent_cmce_pdus(&mut test);
+    let timeouts: Vec<_> = pdus.into_iter().filter_map(|(_, pdu_type, mut sdu, _)| match pdu_type {
+        CmcePduTypeDl::DSetup => Some(DSetup::from_bitbuf(&mut sdu).unwrap().call_time_out),
+        CmcePduTypeDl::DConnect => Some(DConnect::from_bitbuf(&mut sdu).unwrap().call_time_out),
+        _ => None,
+    }).collect();
+    assert_eq!(timeouts, vec![CallTimeout::T10m, CallTimeout::T10m]);
+}
+
+#[test]
+fn test_group_call_request_to_empty_group_rejected() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(default_test_config(StackMode::Bs), Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm, TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+    attach_to_groups(&mut test, &[(2040001, 91)]);
+
+    request_group_call(&mut test, 2040001, 92, 0, dltime);
+    let pdus = sent_cmce_pdus(&mut test);
+    assert_eq!(pdus.len(), 1);
+    let (ssi, pdu_type, mut sdu, _) = pdus.into_iter().next().unwrap();
+    assert_eq!((ssi, pdu_type), (2040001, CmcePduTypeDl::DRelease));
+    assert_eq!(DRelease::from_bitbuf(&mut sdu).unwrap().disconnect_cause, DISCONNECT_CAUSE_NOT_REACHABLE);
+    assert!(cmce(&mut test).setup_requests().is_empty());
+    assert!(cmce(&mut test).circuits().dl.iter().all(Option::is_none));
+}
+
+#[test]
+fn test_group_call_requests_bounded() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(default_test_config(StackMode::Bs), Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm, TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+    attach_to_groups(&mut test, &[(2040001, 91), (2040002, 92), (2040003, 93), (2040004, 94)]);
+    let mut queue = MessageQueue::new();
+    for gssi in [91, 92, 93] {
+        cmce(&mut test).setup_group_call(&mut queue, gssi, dltime).unwrap();
+    }
+
+    // Routine requests wait until the queue is full, then are rejected for congestion
+    let callers: Vec<u32> = (0..=MAX_SETUP_REQUESTS as u32).map(|i| 2041000 + i).collect();
+    for &caller in &callers {
+        request_group_call(&mut test, caller, 94, 0, dltime);
+    }
+    assert_eq!(cmce(&mut test).setup_requests().len(), MAX_SETUP_REQUESTS);
+    let pdus = sent_cmce_pdus(&mut test);
+    assert!(pdus[..MAX_SETUP_REQUESTS].iter().all(|(_, pdu_type, ..)| *pdu_type == CmcePduTypeDl::DCallProceeding));
+    let (ssi, pdu_type, mut sdu, _) = pdus.into_iter().nth(MAX_SETUP_REQUESTS).unwrap();
+    assert_eq!((ssi, pdu_type), (callers[MAX_SETUP_REQUESTS], CmcePduTypeDl::DRelease));
+    assert_eq!(DRelease::from_bitbuf(&mut sdu).unwrap().disconnect_cause, DISCONNECT_CAUSE_CONGESTION);
+
+    // An emergency request takes the place of the latest routine request
+    request_group_call(&mut test, 2042000, 94, 15, dltime);
+    let pdus = sent_cmce_pdus(&mut test);
+    let addressed: Vec<_> = pdus.iter().map(|(ssi, pdu_type, ..)| (*ssi, *pdu_type)).collect();
+    assert_eq!(addressed, vec![
+        (callers[MAX_SETUP_REQUESTS - 1], CmcePduTypeDl::DRelease),
+        (2042000, CmcePduTypeDl::DCallProceeding),
+    ]);
+    let requests = cmce(&mut test).setup_requests();
+    assert_eq!(requests.len(), MAX_SETUP_REQUESTS);
+    assert_eq!(requests.front().unwrap().calling_ssi, 2042000);
+}
+
+#[test]
+fn test_group_call_poll() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let config = default_test_config(StackMode::Bs);
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm, TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+    attach_to_groups(&mut test, &[(2040001, 91), (2040002, 91), (2040003, 91), (2040004, 91), (2040005, 26)]);
+
+    let mut queue = MessageQueue::new();
+    let call_id = cmce(&mut test).setup_group_call(&mut queue, 91, dltime).unwrap();
+    assert!(!cmce(&mut test).poll_group_call(&mut queue, call_id + 1, dltime));
+    let mut queue = MessageQueue::new();
+
+    // The poll request is sent to the group
+    assert!(cmce(&mut test).poll_group_call(&mut queue, call_id, dltime));
+    let SapMsgInner::LcmcMleUnitdataReq(mut prim) = queue.pop_front().unwrap().msg else { panic!("Expected LcmcMleUnitdataReq") };
+    assert_eq!(prim.main_address.ssi, 91);
+    let pdu = DInfo::from_bitbuf(&mut prim.sdu).unwrap();
+    assert_eq!(pdu.call_identifier, call_id);
+    assert!(pdu.poll_request);
+
+    // Two members respond, one of them twice. A response from a non-member is ignored.
+    for ssi in [2040001, 2040003, 2040003, 2040005] {
+        let pdu = UInfo {
+            call_identifier: call_id,
+            poll_response: true,
+            ..Default::default()
+        };
+        let mut sdu = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut sdu).unwrap();
+        sdu.seek(0);
+        test.submit_message(SapMsg {
+            sap: Sap::LcmcSap,
+            src: TetraEntity::Mle,
+            dest: TetraEntity::Cmce,
+            dltime,
+            msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+                sdu,
+                handle: 0,
+                endpoint_id: 0,
+                link_id: 0,
+                received_tetra_address: TetraAddress { encrypted: false, ssi_type: SsiType::Issi, ssi },
+                chan_change_resp_req: false,
+                chan_change_handle: None,
+                rx_quality: None,
+            }),
+        });
+    }
+    test.deliver_all_messages();
+
+    let poll = cmce(&mut test).group_poll(call_id).unwrap();
+    assert_eq!(poll.responders(), vec![2040001, 2040003]);
+    assert_eq!(poll.response_number(), 2);
+    assert_eq!(poll.response_percentage(), 25);
+}
+
+#[test]
+fn test_individual_call_released_on_t301_expiry() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut config = default_test_config(StackMode::Bs);
+    config.call_control.t301_s = 1;
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+
+    let mut queue = MessageQueue::new();
+    let call_id = cmce(&mut test).setup_individual_call(&mut queue, PAGED_SSI, dltime).unwrap();
+    let SapMsgInner::CmceCallControl(CallControl::Open(circuit)) = queue.pop_front().unwrap().msg else { panic!("Expected circuit open") };
+    assert_eq!(circuit.ssi, PAGED_SSI);
+    test.run_stack(Some(1));
+    assert_eq!(pages_sent(test.dump_sinks()), vec![call_id]);
+
+    // The MS answers the page by restoring the call, which neither alerts nor connects
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    let pdu = UCallRestore {
+        call_identifier: call_id,
+        other_party_type_identifier: 1,
+        other_party_ssi: Some(2040001),
+        ..Default::default()
+    };
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    submit_from_ms(&mut test, PAGED_SSI, sdu, dltime.add_timeslots(1));
+    assert!(test.dump_sinks().is_empty());
+    assert!(cmce(&mut test).paging().is_empty());
+    assert!(cmce(&mut test).is_call_timer_running(call_id, CallTimer::T301));
+
+    // The MS neither alerts nor connects: the call is released once T301 (70 timeslots) expires
+    test.run_stack(Some(60));
+    assert!(cmce(&mut test).is_call_active(call_id));
+    assert!(test.dump_sinks().is_empty());
+    test.run_stack(Some(20));
+    assert!(!cmce(&mut test).is_call_active(call_id));
+    assert!(!cmce(&mut test).is_call_timer_running(call_id, CallTimer::T310));
+
+    let mut msgs = test.dump_sinks().into_iter();
+    let SapMsgInner::LcmcMleUnitdataReq(mut prim) = msgs.next().unwrap().msg else { panic!("Expected LcmcMleUnitdataReq") };
+    assert_eq!(prim.main_address.ssi, PAGED_SSI);
+    let release = DRelease::from_bitbuf(&mut prim.sdu).unwrap();
+    assert_eq!((release.call_identifier, release.disconnect_cause), (call_id, 13));
+    let SapMsgInner::CmceCallControl(CallControl::Close(_, ts)) = msgs.next().unwrap().msg else { panic!("Expected circuit close") };
+    assert_eq!(ts, circuit.ts);
+    assert!(msgs.next().is_none());
+}
+
+#[test]
+fn test_active_call_t310_reset() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut config = default_test_config(StackMode::Bs);
+    config.call_control.t310_s = 2;
+    config.call_control.t310_reset_interval_s = Some(1);
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm, TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+    attach_to_groups(&mut test, &[(2040001, 91)]);
+
+    let mut queue = MessageQueue::new();
+    let call_id = cmce(&mut test).setup_group_call(&mut queue, 91, dltime).unwrap();
+    test.dump_sinks();
+
+    // A D-INFO resetting T310 is sent to the group every 70 timeslots, well past T310 (141 timeslots)
+    test.run_stack(Some(3 * 70 + 5));
+    let resets: Vec<_> = test.dump_sinks().into_iter().filter_map(|msg| {
+        let SapMsgInner::LcmcMleUnitdataReq(mut prim) = msg.msg else { return None };
+        let pdu = DInfo::from_bitbuf(&mut prim.sdu).ok()?;
+        Some((prim.main_address.ssi, pdu.call_identifier, pdu.reset_call_time_out_timer_t310_))
+    }).collect();
+    assert_eq!(resets, vec![(91, call_id, true); 3]);
+    assert!(cmce(&mut test).is_call_active(call_id));
+    assert!(cmce(&mut test).is_call_timer_running(call_id, CallTimer::T310));
+}
+
+/// Sets up an individual call requested by 2040001 to `PAGED_SSI`, which alerts and connects.
+/// Returns the PDUs sent to the calling MS and then to the called MS.
+fn requested_individual_call(edition: ProtocolEdition) -> Vec<(u32, CmcePduTypeDl, BitBuffer, Option<CmceChanAllocReq>)> {
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut config = default_test_config(StackMode::Bs);
+    config.cell.protocol_edition = edition;
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+
+    // The calling MS is answered with D-CALL PROCEEDING, and the called MS paged
+    request_call(&mut test, 2040001, PAGED_SSI, CommunicationType::P2p, 0, dltime);
+    let pdus = sent_cmce_pdus(&mut test);
+    assert_eq!(pdus.len(), 1);
+    let (ssi, pdu_type, mut sdu, _) = pdus.into_iter().next().unwrap();
+    assert_eq!((ssi, pdu_type), (2040001, CmcePduTypeDl::DCallProceeding));
+    let call_id = DCallProceeding::from_bitbuf(&mut sdu).unwrap().call_identifier;
+    test.run_stack(Some(1));
+    assert_eq!(pages_sent(test.dump_sinks()), vec![call_id]);
+
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UAlert { call_identifier: call_id, ..Default::default() }.to_bitbuf(&mut sdu).unwrap();
+    submit_from_ms(&mut test, PAGED_SSI, sdu, dltime.add_timeslots(2));
+    let mut sdu = BitBuffer::new_autoexpand(32);
+    UConnect { call_identifier: call_id, ..Default::default() }.to_bitbuf(&mut sdu).unwrap();
+    submit_from_ms(&mut test, PAGED_SSI, sdu, dltime.add_timeslots(3));
+    sent_cmce_pdus(&mut test)
+}
+
+#[test]
+fn test_requested_individual_call() {
+    debug::setup_logging_verbose();
+    // The calling MS is alerted, then connected along with the called MS
+    let pdus = requested_individual_call(ProtocolEdition::Edition2);
+    let addressed: Vec<_> = pdus.iter().map(|(ssi, pdu_type, ..)| (*ssi, *pdu_type)).collect();
+    assert_eq!(addressed, vec![
+        (2040001, CmcePduTypeDl::DAlert),
+        (2040001, CmcePduTypeDl::DConnect),
+        (PAGED_SSI, CmcePduTypeDl::DConnectAcknowledge),
+    ]);
+
+    // Both MSs are moved to the circuit
+    let (connect_alloc, ack_alloc) = (pdus[1].3.as_ref().unwrap(), pdus[2].3.as_ref().unwrap());
+    assert_eq!(connect_alloc.timeslots, ack_alloc.timeslots);
+    assert_eq!(ack_alloc.ul_dl_assigned, UlDlAssignment::Both);
+}
+
+#[test]
+fn test_d_alert_reserved_by_edition() {
+    debug::setup_logging_verbose();
+    let d_alert_bits = |edition| {
+        let (_, pdu_type, sdu, _) = requested_individual_call(edition).into_iter().next().unwrap();
+        assert_eq!(pdu_type, CmcePduTypeDl::DAlert);
+        sdu.dump_bin_unformatted()
+    };
+    let (bits1, bits2) = (d_alert_bits(ProtocolEdition::Edition1), d_alert_bits(ProtocolEdition::Edition2));
+
+    // Only the reserved bit, following the call identifier and call time-out, differs
+    assert_eq!(&bits1[5 + 14 + 3..][..1], "0");
+    assert_eq!(&bits2[5 + 14 + 3..][..1], "1");
+    let diff: Vec<usize> = bits1.chars().zip(bits2.chars())
+        .enumerate().filter(|(_, (a, b))| a != b).map(|(i, _)| i).collect();
+    assert_eq!(diff, vec![5 + 14 + 3]);
+}
+
+#[test]
+fn test_tx_demand_granted() {
+    debug::setup_logging_verbose();
+    for edition in [ProtocolEdition::Edition1, ProtocolEdition::Edition2] {
+        let dltime = TdmaTime::default().add_timeslots(2);
+        let mut config = default_test_config(StackMode::Bs);
+        config.cell.protocol_edition = edition;
+        let mut test = ComponentTest::new(config, Some(dltime));
+        test.populate_entities(vec![TetraEntity::Mm, TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+        attach_to_groups(&mut test, &[(2040001, 91), (2040002, 91)]);
+        let mut queue = MessageQueue::new();
+        let call_id = cmce(&mut test).setup_group_call(&mut queue, 91, dltime).unwrap();
+        test.dump_sinks();
+
+        // The demanding MS is granted transmission, and the group told who transmits
+        let mut sdu = BitBuffer::new_autoexpand(32);
+        UTxDemand { call_identifier: call_id, ..Default::default() }.to_bitbuf(&mut sdu).unwrap();
+        submit_from_ms(&mut test, 2040002, sdu, dltime.add_timeslots(1));
+        let pdus = sent_cmce_pdus(&mut test);
+        assert_eq!(pdus.len(), 2);
+        for (ssi, pdu_type, mut sdu, _) in pdus {
+            assert_eq!(pdu_type, CmcePduTypeDl::DTxGranted);
+            // Reserved bit after call identifier, grant, request permission and encryption control
+            assert_eq!(&sdu.dump_bin_unformatted()[5 + 14 + 2 + 1 + 1..][..1], "0");
+            let granted = DTxGranted::from_bitbuf(&mut sdu).unwrap();
+            assert_eq!(granted.call_identifier, call_id);
+            match ssi {
+                2040002 => assert_eq!(granted.transmission_grant, TransmissionGrant::Granted.into_raw() as u8),
+                91 => {
+                    assert_eq!(granted.transmission_grant, TransmissionGrant::GrantedToOtherUser.into_raw() as u8);
+                    assert_eq!(granted.transmitting_party_address_ssi, Some(2040002));
+                }
+                _ => panic!("D-TX GRANTED sent to {}", ssi),
+            }
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use tetra_config::example_config::generate_example_toml;
+use tetra_config::{ConfigError, ConfigErrors, CfgCellInfo, StackConfig, StackMode, from_toml_str};
+use tetra_core::{DEFAULT_MAX_PATH_DELAY_SYMBOLS, path_delay_symbols};
+
+#[test]
+fn test_all_config_errors_reported() {
+    let toml = r#"
+        config_version = "0.5"
+        stack_mode = "Bs"
+
+        [phy_io]
+        backend = "None"
+
+        [net_info]
+        mcc = 204
+        mnc = 1337
+
+        [cell_info]
+        main_carrier = 5000
+        freq_band = 4
+        freq_offset = 0
+        duplex_spacing = 4
+        reverse_operation = false
+        location_area = 2
+        mcch_timeslot = 7
+        colour_cod = 1
+
+        [call_control]
+        emergency_priority = 0
+
+        [[neighbor_cell]]
+        cell_id = 1
+        main_carrier = 1522
+
+        [[neighbor_cell]]
+        cell_id = 40
+        main_carrier = 1530
+
+        [[subscriber]]
+        issi = 1001
+
+        [[subscriber]]
+        issi = 16777216
+        groups = [1, 20000000]
+    "#;
+    let err = from_toml_str(toml).err().expect("Invalid config was accepted");
+    let errors = err.downcast_ref::<ConfigErrors>().expect("Expected ConfigErrors");
+    let expected = [
+        ("cell_info.colour_cod", "unrecognized field"),
+        ("cell_info.main_carrier", "out of range (max 4095)"),
+        ("cell_info.mcch_timeslot", "out of range 1-4"),
+        ("call_control.emergency_priority", "out of range 1-15"),
+        ("neighbor_cell[1].cell_id", "out of range (max 31)"),
+        ("subscriber[1].issi", "out of range (max 16777215)"),
+        ("subscriber[1].groups[1]", "out of range (max 16777215)"),
+    ];
+    assert_eq!(errors.0, expected.map(|(path, message)| ConfigError::new(path, message)));
+
+    let text = err.to_string();
+    assert!(text.starts_with("7 configuration errors\n"), "{}", text);
+    assert!(text.contains("\n  cell_info.main_carrier: out of range (max 4095)"), "{}", text);
+}
+
+fn cell_toml(main_carrier: u32, location_area: u32) -> String {
+    format!(r#"
+        config_version = "0.5"
+        stack_mode = "Bs"
+
+        [phy_io]
+        backend = "None"
+
+        [net_info]
+        mcc = 204
+        mnc = 1337
+
+        [cell_info]
+        main_carrier = {main_carrier}
+        freq_band = 4
+        freq_offset = 0
+        duplex_spacing = 4
+        reverse_operation = false
+        location_area = {location_area}
+    "#)
+}
+
+fn config_errors(toml: &str) -> Vec<ConfigError> {
+    match from_toml_str(toml) {
+        Ok(_) => Vec::new(),
+        Err(e) => e.downcast_ref::<ConfigErrors>().expect("Expected ConfigErrors").0.clone(),
+    }
+}
+
+#[test]
+fn test_bit_width_ranges() {
+    // The largest values fitting 12 and 14 bits are accepted
+    assert_eq!(config_errors(&cell_toml(4095, 16383)), vec![]);
+
+    assert_eq!(config_errors(&cell_toml(4096, 2)), vec![ConfigError::new("cell_info.main_carrier", "out of range (max 4095)")]);
+    assert_eq!(config_errors(&cell_toml(1521, 16384)), vec![ConfigError::new("cell_info.location_area", "out of range (max 16383)")]);
+}
+
+#[test]
+fn test_generated_config_parses() {
+    let toml = generate_example_toml();
+    let cfg = from_toml_str(&toml).unwrap_or_else(|e| panic!("{}\n{}", e, toml));
+    let c = cfg.config();
+    assert_eq!(c.cell.mcch_timeslot, CfgCellInfo::default().mcch_timeslot);
+    assert!(toml.contains("# 12 bits, main carrier number, from MAC SYSINFO\nmain_carrier = "), "{}", toml);
+    assert!(toml.contains("\n# max_tm_sdu_bits = 4096\n"), "{}", toml);
+
+    // Uncommenting the optional cell_info fields keeps the config valid, except for
+    // those that only apply to other settings
+    let cell_info = toml.split("\n[cell_info]\n").nth(1).unwrap().split("\n\n").next().unwrap();
+    let skip = ["custom_duplex_spacing", "ms_subscriber_class", "sck_number", "cck_id"];
+    let uncommented: String = cell_info.lines()
+        .filter_map(|l| l.strip_prefix("# ").filter(|f| f.contains(" = ")).or(Some(l).filter(|l| !l.starts_with('#'))))
+        .filter(|l| !skip.iter().any(|s| l.starts_with(s)))
+        .map(|l| format!("{}\n", l))
+        .collect();
+    assert!(uncommented.contains("\nmax_tm_sdu_bits = 4096\n"), "{}", uncommented);
+    let toml = toml.replace(cell_info, &uncommented);
+    from_toml_str(&toml).unwrap_or_else(|e| panic!("{}\n{}", e, toml));
+}
+
+#[test]
+fn test_temporary_addresses_outside_vassi_range() {
+    let with_pool = |first: u32, last: u32| format!("{}\n[temporary_addresses]\nfirst = {first}\nlast = {last}\n", cell_toml(1521, 2));
+    assert_eq!(config_errors(&with_pool(0xFE0000, 0xFE00FF)), vec![]);
+
+    // The default (V)ASSI range starts at 0xFF0000
+    let overlap = vec![ConfigError::new("temporary_addresses", "overlaps access_control.vassi_range")];
+    assert_eq!(config_errors(&with_pool(0xFE0000, 0xFF0000)), overlap);
+
+    let toml = with_pool(0xFE0000, 0xFE00FF).replace("[net_info]", "[access_control]\nvassi_range = [0xFE0080, 0xFE0100]\n\n[net_info]");
+    assert_eq!(config_errors(&toml), overlap);
+}
+
+#[test]
+fn test_max_path_delay_from_cell_radius() {
+    // Flagging late bursts in the PHY and power control share this threshold
+    let mut cell = CfgCellInfo::default();
+    assert_eq!(cell.max_path_delay_symbols(), DEFAULT_MAX_PATH_DELAY_SYMBOLS);
+    cell.cell_radius_km = Some(25.0);
+    assert_eq!(cell.max_path_delay_symbols(), path_delay_symbols(25.0));
+}
+
+#[test]
+fn test_duplex_spacing_validated_for_band() {
+    let duplex_spacing_errors = |band: u8, setting: u8, custom: Option<u32>| {
+        let mut config = StackConfig::new(StackMode::Bs, 204, 1337);
+        config.cell.freq_band = band;
+        config.cell.duplex_spacing_id = setting;
+        config.cell.custom_duplex_spacing = custom;
+        config.check().into_iter().filter(|e| e.path == "cell_info.duplex_spacing").collect::<Vec<_>>()
+    };
+
+    // Band 1: setting 1 is 4.5 MHz, setting 3 is not defined
+    assert_eq!(duplex_spacing_errors(1, 1, None), vec![]);
+    assert_eq!(duplex_spacing_errors(1, 3, None), vec![ConfigError::new("cell_info.duplex_spacing", "3 is not defined for freq_band 1")]);
+
+    // Band 4: setting 4 is 5 MHz, setting 6 is not defined
+    assert_eq!(duplex_spacing_errors(4, 4, None), vec![]);
+    assert_eq!(duplex_spacing_errors(4, 6, None), vec![ConfigError::new("cell_info.duplex_spacing", "6 is not defined for freq_band 4")]);
+
+    // Anything goes with a custom duplex spacing table
+    assert_eq!(duplex_spacing_errors(4, 6, Some(6_000_000)), vec![]);
+}
@@ -26,7 +26,8 @@ fn test_udata_with_broken_mm_payload() {
         air_interface_encryption: 0,
         chan_change_response_req: false,
         chan_change_handle: None,
-        chan_info: None};
+        chan_info: None,
+        rx_quality: None};
     let test_sapmsg = SapMsg {
         sap: Sap::TmaSap,
         src: TetraEntity::Umac,
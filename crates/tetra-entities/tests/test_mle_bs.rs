@@ -0,0 +1,50 @@
+mod common;
+
+use tetra_config::{CfgNeighborCell, SharedConfig, StackMode};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_core::{debug, TdmaTime};
+use tetra_entities::umac::umac_bs::UmacBs;
+use tetra_pdus::mle::pdus::d_nwrk_broadcast::DNwrkBroadcast;
+use tetra_saps::sapmsg::SapMsgInner;
+use common::{ComponentTest, default_test_config};
+
+/// Runs the MLE for one hyperframe's worth of multiframes and returns the
+/// multiframe numbers in which a D-NWRK-BROADCAST was sent, with the PDUs
+fn nwrk_broadcasts_in_mode(neighbours: bool, minimum_mode: bool) -> Vec<(u8, DNwrkBroadcast)> {
+    let mut config = default_test_config(StackMode::Bs);
+    config.cell.no_minimum_mode = !minimum_mode;
+    if neighbours {
+        config.neighbor_cells.push(CfgNeighborCell { cell_id: 1, main_carrier: 1522, location_area: None, reselection_types: 0, synchronized: false });
+    }
+    let mut test = ComponentTest::new(config, Some(TdmaTime::default()));
+    test.populate_entities(vec![TetraEntity::Mle], vec![TetraEntity::Llc]);
+    test.run_stack(Some(18 * 18 * 4));
+
+    test.dump_sinks().into_iter().filter_map(|msg| {
+        let SapMsgInner::TlaTlUnitdataReqBl(mut prim) = msg.msg else { return None };
+        assert_eq!((msg.dltime.f, msg.dltime.t), (1, 1));
+        prim.tl_sdu.read_field(3, "protocol_discriminator").unwrap();
+        Some((msg.dltime.m, DNwrkBroadcast::from_bitbuf(&mut prim.tl_sdu).unwrap()))
+    }).collect()
+}
+
+#[test]
+fn test_neighbour_broadcast_in_minimum_mode() {
+    debug::setup_logging_verbose();
+
+    // Minimum mode only reduces SYNC and SYSINFO, neighbour cells are broadcast regardless
+    let sent = nwrk_broadcasts_in_mode(true, false);
+    assert_eq!(sent.iter().map(|(m, _)| *m).collect::<Vec<_>>(), vec![10]);
+    assert_eq!(nwrk_broadcasts_in_mode(true, true), sent);
+    assert!(nwrk_broadcasts_in_mode(false, true).is_empty());
+
+    // The SYNC advertisements do not depend on the mode, SYSINFO announces it
+    for no_minimum_mode in [true, false] {
+        let mut config = default_test_config(StackMode::Bs);
+        config.cell.no_minimum_mode = no_minimum_mode;
+        let precomps = UmacBs::generate_precomps(&SharedConfig::from_config(config));
+        assert!(precomps.mle_sync.late_entry_supported);
+        assert_eq!(precomps.mle_sync.neighbor_cell_broadcast, 2);
+        assert_eq!(precomps.mle_sysinfo.bs_service_details.no_minimum_mode, no_minimum_mode);
+    }
+}
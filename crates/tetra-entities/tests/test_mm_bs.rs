@@ -2,8 +2,28 @@ mod common;
 
 use tetra_core::{BitBuffer, debug, Sap, SsiType, TdmaTime, TetraAddress};
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_config::StackMode;
+use tetra_config::{StackConfig, StackMode, SubscriberService};
+use tetra_entities::MessageQueue;
+use tetra_entities::mm::components::authenticator::Authenticator;
+use tetra_entities::mm::components::client_state::MmClientState;
+use tetra_entities::mm::mm_bs::MmBs;
+use tetra_pdus::cmce::pdus::u_status::UStatus;
+use tetra_pdus::mle::enums::mle_protocol_discriminator::MleProtocolDiscriminator;
+use tetra_pdus::mm::enums::disable_status::DisableStatus;
+use tetra_pdus::mm::enums::location_update_type::LocationUpdateType;
+use tetra_pdus::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use tetra_pdus::mm::enums::reject_cause::RejectCause;
+use tetra_pdus::mm::fields::group_identity_uplink::GroupIdentityUplink;
+use tetra_pdus::mm::pdus::d_disable::DDisable;
+use tetra_pdus::mm::pdus::d_location_update_accept::DLocationUpdateAccept;
+use tetra_pdus::mm::pdus::d_location_update_proceeding::DLocationUpdateProceeding;
+use tetra_pdus::mm::pdus::d_location_update_reject::DLocationUpdateReject;
+use tetra_pdus::mm::pdus::u_attach_detach_group_identity::UAttachDetachGroupIdentity;
+use tetra_pdus::mm::pdus::u_disable_status::UDisableStatus;
+use tetra_pdus::mm::pdus::u_location_update_demand::ULocationUpdateDemand;
+use tetra_saps::control::group_control::GroupControl;
 use tetra_saps::lmm::LmmMleUnitdataInd;
+use tetra_saps::tla::TlaTlDataIndBl;
 use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
 use common::{ComponentTest, default_test_config};
 
@@ -18,6 +38,7 @@ fn test_unsupported_u_mm_status() {
         sdu: BitBuffer::from_bitstr(test_vec1),
         handle: 0,
         received_address: TetraAddress { encrypted: false, ssi_type: SsiType::Issi, ssi: 2040814 },
+        rx_quality: None,
     };
     let test_sapmsg1 = SapMsg {
         sap: Sap::LmmSap,
@@ -47,3 +68,801 @@ fn test_unsupported_u_mm_status() {
     tracing::info!("We have the expected MM message, but full validation of result not implemented");
 }
 
+
+
+/// Submits a U-LOCATION UPDATE DEMAND from ISSI 2040814 to the MM entity and returns the SDU of the single response
+fn run_u_location_update_demand(config: StackConfig) -> BitBuffer {
+    let mut test = ComponentTest::new(config, Some(TdmaTime::default().add_timeslots(2)));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle]);
+    submit_u_location_update_demand(&mut test)
+}
+
+/// Submits a U-LOCATION UPDATE DEMAND from ISSI 2040814 to an existing test stack and returns the SDU of the single response
+fn submit_u_location_update_demand(test: &mut ComponentTest) -> BitBuffer {
+    submit_u_location_update_demand_from(test, 2040814, TdmaTime::default().add_timeslots(2))
+}
+
+/// Submits a U-LOCATION UPDATE DEMAND from the given ISSI at the given time to an existing test stack and returns the SDU of the single response
+fn submit_u_location_update_demand_from(test: &mut ComponentTest, issi: u32, time_vec: TdmaTime) -> BitBuffer {
+    // ITSI attach with group identity location demand, from moto upon registration
+    let test_vec = "0010000001100010010010100000010000000001001100000111000001110000000010010000000101000000000000000000000001101000";
+    let test_prim = LmmMleUnitdataInd{
+        sdu: BitBuffer::from_bitstr(test_vec),
+        handle: 0,
+        received_address: TetraAddress { encrypted: false, ssi_type: SsiType::Issi, ssi: issi },
+        rx_quality: None,
+    };
+    let test_sapmsg = SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime: time_vec,
+        msg: SapMsgInner::LmmMleUnitdataInd(test_prim)};
+
+    test.submit_message(test_sapmsg);
+    test.run_stack(Some(1));
+    let mut sink_msgs = test.dump_sinks();
+
+    assert_eq!(sink_msgs.len(), 1);
+    let SapMsgInner::LmmMleUnitdataReq(prim) = sink_msgs.remove(0).msg else { panic!("Expected LmmMleUnitdataReq") };
+    prim.sdu
+}
+
+#[test]
+fn test_u_location_update_demand_subscriber_class_allowed() {
+    debug::setup_logging_verbose();
+    let mut config = default_test_config(StackMode::Bs);
+    config.cell.subscriber_class = 0x00FF;
+    config.cell.ms_subscriber_class = Some(0x0010);
+
+    let mut sdu = run_u_location_update_demand(config);
+    assert_eq!(sdu.peek_bits(4), Some(MmPduTypeDl::DLocationUpdateAccept.into_raw()));
+    let pdu = DLocationUpdateAccept::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(pdu.subscriber_class, Some(0x0010));
+}
+
+#[test]
+fn test_u_location_update_demand_subscriber_class_denied() {
+    debug::setup_logging_verbose();
+    let mut config = default_test_config(StackMode::Bs);
+    config.cell.subscriber_class = 0x00FF;
+    config.cell.ms_subscriber_class = Some(0x0100);
+
+    let mut sdu = run_u_location_update_demand(config);
+    assert_eq!(sdu.peek_bits(4), Some(MmPduTypeDl::DLocationUpdateReject.into_raw()));
+    let pdu = DLocationUpdateReject::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(pdu.reject_cause as u64, RejectCause::LaNotAllowed.into_raw());
+}
+
+#[test]
+fn test_u_location_update_demand_no_subscriber_class() {
+    // Without an MS subscriber class configured, no check is made, even if the cell allows no classes
+    debug::setup_logging_verbose();
+    let mut config = default_test_config(StackMode::Bs);
+    config.cell.subscriber_class = 0;
+
+    let mut sdu = run_u_location_update_demand(config);
+    let pdu = DLocationUpdateAccept::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(pdu.subscriber_class, None);
+}
+
+#[test]
+fn test_u_location_update_demand_issi_allowed() {
+    debug::setup_logging_verbose();
+    let mut config = default_test_config(StackMode::Bs);
+    config.access_control.allow = vec![2040001, 2040814];
+
+    let mut sdu = run_u_location_update_demand(config);
+    let pdu = DLocationUpdateAccept::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(pdu.ssi, Some(2040814));
+}
+
+#[test]
+fn test_u_location_update_demand_issi_denied() {
+    // Deny wins, even if the ISSI is also on the allow list
+    debug::setup_logging_verbose();
+    let mut config = default_test_config(StackMode::Bs);
+    config.access_control.allow = vec![2040814];
+    config.access_control.deny = vec![2040814];
+
+    let mut sdu = run_u_location_update_demand(config);
+    assert_eq!(sdu.peek_bits(4), Some(MmPduTypeDl::DLocationUpdateReject.into_raw()));
+    let pdu = DLocationUpdateReject::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(pdu.reject_cause as u64, RejectCause::IllegalMs.into_raw());
+}
+
+#[test]
+fn test_u_location_update_demand_issi_not_on_allow_list() {
+    debug::setup_logging_verbose();
+    let mut config = default_test_config(StackMode::Bs);
+    config.access_control.allow = vec![2040001];
+
+    let sdu = run_u_location_update_demand(config);
+    assert_eq!(sdu.peek_bits(4), Some(MmPduTypeDl::DLocationUpdateReject.into_raw()));
+}
+
+#[test]
+fn test_u_location_update_demand_access_control_default_open() {
+    // No allow or deny lists configured: any ISSI may register
+    debug::setup_logging_verbose();
+    let config = default_test_config(StackMode::Bs);
+    assert!(config.access_control.allow.is_empty() && config.access_control.deny.is_empty());
+
+    let sdu = run_u_location_update_demand(config);
+    assert_eq!(sdu.peek_bits(4), Some(MmPduTypeDl::DLocationUpdateAccept.into_raw()));
+}
+
+#[test]
+fn test_u_location_update_demand_max_registered_ms() {
+    debug::setup_logging_verbose();
+    let mut config = default_test_config(StackMode::Bs);
+    config.access_control.max_registered_ms = Some(3);
+    let start = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(config, Some(start));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle]);
+
+    for issi in 2040001..=2040003 {
+        let sdu = submit_u_location_update_demand_from(&mut test, issi, start);
+        assert_eq!(sdu.peek_bits(4), Some(MmPduTypeDl::DLocationUpdateAccept.into_raw()));
+    }
+
+    // Capacity reached: a new MS is rejected
+    let mut sdu = submit_u_location_update_demand_from(&mut test, 2040004, start);
+    assert_eq!(sdu.peek_bits(4), Some(MmPduTypeDl::DLocationUpdateReject.into_raw()));
+    let pdu = DLocationUpdateReject::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(pdu.reject_cause as u64, RejectCause::Congestion.into_raw());
+    assert!(!mm(&mut test).client_mgr.client_is_known(2040004));
+
+    // Already registered MSs are still served
+    let sdu = submit_u_location_update_demand_from(&mut test, 2040002, start);
+    assert_eq!(sdu.peek_bits(4), Some(MmPduTypeDl::DLocationUpdateAccept.into_raw()));
+}
+
+#[test]
+fn test_inactive_ms_deregistered() {
+    // 2 s is about 141 timeslots
+    debug::setup_logging_verbose();
+    let mut config = default_test_config(StackMode::Bs);
+    config.access_control.inactivity_timeout_s = Some(2);
+    let start = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(config, Some(start));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle]);
+
+    submit_u_location_update_demand_from(&mut test, 2040001, start);
+    submit_u_location_update_demand_from(&mut test, 2040002, start);
+    test.run_stack(Some(100));
+    assert!(mm(&mut test).client_mgr.client_is_known(2040001));
+    assert!(mm(&mut test).client_mgr.client_is_known(2040002));
+
+    // Only 2040001 remains active; 2040002 times out
+    submit_u_location_update_demand_from(&mut test, 2040001, start.add_timeslots(102));
+    test.run_stack(Some(100));
+    assert!(mm(&mut test).client_mgr.client_is_known(2040001));
+    assert!(!mm(&mut test).client_mgr.client_is_known(2040002));
+}
+
+/// Delivers `pdu` of the entity behind `discriminator` from `issi` to MLE, as received from LLC
+fn submit_to_mle(test: &mut ComponentTest, issi: u32, discriminator: MleProtocolDiscriminator, mut pdu: BitBuffer, dltime: TdmaTime) {
+    let pdu_len = pdu.get_len();
+    let mut sdu = BitBuffer::new(3 + pdu_len);
+    sdu.write_bits(discriminator.into_raw(), 3);
+    sdu.copy_bits(&mut pdu, pdu_len);
+    sdu.seek(0);
+    test.submit_message(SapMsg {
+        sap: Sap::TlaSap,
+        src: TetraEntity::Llc,
+        dest: TetraEntity::Mle,
+        dltime,
+        msg: SapMsgInner::TlaTlDataIndBl(TlaTlDataIndBl {
+            main_address: TetraAddress { encrypted: false, ssi_type: SsiType::Issi, ssi: issi },
+            link_id: 0,
+            endpoint_id: 0,
+            new_endpoint_id: None,
+            css_endpoint_id: None,
+            tl_sdu: Some(sdu),
+            scrambling_code: 0,
+            fcs_flag: false,
+            air_interface_encryption: 0,
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+            chan_info: None,
+            req_handle: 0,
+            rx_quality: None,
+        }),
+    });
+    test.deliver_all_messages();
+}
+
+#[test]
+fn test_ms_activity_keeps_ms_registered() {
+    // 2 s is about 141 timeslots
+    debug::setup_logging_verbose();
+    let mut config = default_test_config(StackMode::Bs);
+    config.access_control.inactivity_timeout_s = Some(2);
+    let start = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(config, Some(start));
+    test.populate_entities(vec![TetraEntity::Mm, TetraEntity::Mle], vec![TetraEntity::Llc, TetraEntity::Cmce]);
+    // ITSI attach with group identity location demand, from moto upon registration
+    let demand = "0010000001100010010010100000010000000001001100000111000001110000000010010000000101000000000000000000000001101000";
+    submit_to_mle(&mut test, 2040001, MleProtocolDiscriminator::Mm, BitBuffer::from_bitstr(demand), start);
+    submit_to_mle(&mut test, 2040002, MleProtocolDiscriminator::Mm, BitBuffer::from_bitstr(demand), start);
+    test.run_stack(Some(100));
+    assert!(mm(&mut test).client_mgr.client_is_known(2040001));
+    assert!(mm(&mut test).client_mgr.client_is_known(2040002));
+
+    // MLE reports the CMCE PDUs of 2040001 to MM, so only 2040002 times out
+    let mut status = BitBuffer::new_autoexpand(64);
+    UStatus { called_party_type_identifier: 1, called_party_ssi: Some(1000), pre_coded_status: 0x8000, ..Default::default() }
+        .to_bitbuf(&mut status).unwrap();
+    status.seek(0);
+    submit_to_mle(&mut test, 2040001, MleProtocolDiscriminator::Cmce, status, start.add_timeslots(102));
+    test.run_stack(Some(100));
+    assert!(mm(&mut test).client_mgr.client_is_known(2040001));
+    assert!(!mm(&mut test).client_mgr.client_is_known(2040002));
+}
+
+#[test]
+fn test_subscriber_db() {
+    debug::setup_logging_verbose();
+    let toml = r#"
+        config_version = "0.5"
+        stack_mode = "Bs"
+
+        [phy_io]
+        backend = "None"
+
+        [net_info]
+        mcc = 204
+        mnc = 1337
+
+        [[subscriber]]
+        issi = 2040001
+        subscriber_class = 0x0002
+        groups = [91, 92]
+
+        [[subscriber]]
+        issi = 2040002
+        subscriber_class = 0x0010
+        services = ["Sds"]
+    "#;
+    let loaded = tetra_config::from_toml_str(toml).expect("Failed loading config");
+    let mut config = default_test_config(StackMode::Bs);
+    config.subscribers = loaded.config().subscribers.clone();
+    config.cell.subscriber_class = 0x0003;
+    config.cell.ms_subscriber_class = Some(0x0001);
+    assert_eq!(config.subscribers.len(), 2);
+    assert!(config.subscribers.allows_service(2040001, SubscriberService::GroupCall));
+    assert!(!config.subscribers.allows_service(2040002, SubscriberService::GroupCall));
+
+    let start = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(config, Some(start));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle]);
+
+    // Accepted with its own subscriber class, and attached to its home groups
+    let mut sdu = submit_u_location_update_demand_from(&mut test, 2040001, start);
+    let pdu = DLocationUpdateAccept::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(pdu.subscriber_class, Some(0x0002));
+    assert_eq!(mm(&mut test).group_members(91), vec![2040001]);
+    assert_eq!(mm(&mut test).group_members(92), vec![2040001]);
+
+    // Its subscriber class is not allowed on this cell
+    let mut sdu = submit_u_location_update_demand_from(&mut test, 2040002, start);
+    let pdu = DLocationUpdateReject::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(pdu.reject_cause as u64, RejectCause::LaNotAllowed.into_raw());
+
+    // Not in the database
+    let mut sdu = submit_u_location_update_demand_from(&mut test, 2040003, start);
+    let pdu = DLocationUpdateReject::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(pdu.reject_cause as u64, RejectCause::IllegalMs.into_raw());
+    assert!(!mm(&mut test).client_mgr.client_is_known(2040003));
+}
+
+/// First (V)ASSI assigned in the migration tests
+const VASSI_FIRST: u32 = 0xFF0100;
+
+/// MNI of a foreign network, MCC 262 MNC 42
+const FOREIGN_MNI: u32 = (262 << 14) | 42;
+
+/// Submits a migrating U-LOCATION UPDATE DEMAND from an ISSI of the foreign network
+/// and returns the SDUs of all responses, with the SSIs they were sent to
+fn submit_migrating_location_update(test: &mut ComponentTest, issi: u32) -> Vec<(u32, BitBuffer)> {
+    let pdu = ULocationUpdateDemand {
+        location_update_type: LocationUpdateType::TemporaryRegistration, // Migrating location updating
+        request_to_append_la: false,
+        cipher_control: false,
+        ciphering_parameters: None,
+        class_of_ms: None,
+        energy_saving_mode: None,
+        la_information: None,
+        ssi: Some(issi as u64),
+        address_extension: Some(FOREIGN_MNI as u64),
+        group_identity_location_demand: None,
+        group_report_response: None,
+        authentication_uplink: None,
+        extended_capabilities: None,
+        proprietary: None,
+    };
+    let mut sdu = BitBuffer::new_autoexpand(80);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    let dltime = TdmaTime::default().add_timeslots(2);
+    test.submit_message(SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime,
+        msg: SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu,
+            handle: 0,
+            received_address: TetraAddress { encrypted: false, ssi_type: SsiType::Ussi, ssi: issi },
+            rx_quality: None,
+        }),
+    });
+    test.run_stack(Some(1));
+    test.dump_sinks().into_iter().map(|msg| {
+        let SapMsgInner::LmmMleUnitdataReq(prim) = msg.msg else { panic!("Expected LmmMleUnitdataReq") };
+        (prim.address.ssi, prim.sdu)
+    }).collect()
+}
+
+#[test]
+fn test_migration() {
+    debug::setup_logging_verbose();
+    let mut config = default_test_config(StackMode::Bs);
+    config.cell.migration = true;
+    config.access_control.vassi_range = Some((VASSI_FIRST, VASSI_FIRST + 1));
+    let mut test = ComponentTest::new(config, Some(TdmaTime::default().add_timeslots(2)));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle]);
+
+    // A (V)ASSI is assigned to the visitor, which is then registered under it
+    let mut responses = submit_migrating_location_update(&mut test, 3010001);
+    assert_eq!(responses.len(), 2);
+    let (dest, mut sdu) = responses.remove(0);
+    assert_eq!(dest, 3010001);
+    let pdu = DLocationUpdateProceeding::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(pdu.ssi, VASSI_FIRST);
+    assert_eq!(pdu.address_extension, FOREIGN_MNI);
+
+    let (dest, mut sdu) = responses.remove(0);
+    assert_eq!(dest, VASSI_FIRST);
+    let pdu = DLocationUpdateAccept::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(pdu.ssi, Some(VASSI_FIRST as u64));
+    assert!(mm(&mut test).client_mgr.client_is_known(VASSI_FIRST));
+    assert_eq!(mm(&mut test).visitor(VASSI_FIRST), Some((FOREIGN_MNI, 3010001)));
+
+    // A returning visitor keeps its (V)ASSI
+    let mut responses = submit_migrating_location_update(&mut test, 3010001);
+    let pdu = DLocationUpdateProceeding::from_bitbuf(&mut responses[0].1).unwrap();
+    assert_eq!(pdu.ssi, VASSI_FIRST);
+
+    // Another visitor gets the next one, until the range is exhausted
+    let mut responses = submit_migrating_location_update(&mut test, 3010002);
+    let pdu = DLocationUpdateProceeding::from_bitbuf(&mut responses[0].1).unwrap();
+    assert_eq!(pdu.ssi, VASSI_FIRST + 1);
+    let mut responses = submit_migrating_location_update(&mut test, 3010003);
+    assert_eq!(responses.len(), 1);
+    let pdu = DLocationUpdateReject::from_bitbuf(&mut responses[0].1).unwrap();
+    assert_eq!(pdu.reject_cause as u64, RejectCause::Congestion.into_raw());
+
+    // The (V)ASSI of a detached visitor is reused
+    submit_u_itsi_detach_from(&mut test, VASSI_FIRST);
+    assert_eq!(mm(&mut test).visitor(VASSI_FIRST), None);
+    let mut responses = submit_migrating_location_update(&mut test, 3010003);
+    let pdu = DLocationUpdateProceeding::from_bitbuf(&mut responses[0].1).unwrap();
+    assert_eq!(pdu.ssi, VASSI_FIRST);
+    assert_eq!(mm(&mut test).visitor(VASSI_FIRST), Some((FOREIGN_MNI, 3010003)));
+}
+
+#[test]
+fn test_issi_in_vassi_range_rejected() {
+    debug::setup_logging_verbose();
+    let mut config = default_test_config(StackMode::Bs);
+    config.cell.migration = true;
+    config.access_control.vassi_range = Some((VASSI_FIRST, VASSI_FIRST + 1));
+    let mut test = ComponentTest::new(config, Some(TdmaTime::default().add_timeslots(2)));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle]);
+
+    let mut sdu = submit_u_location_update_demand_from(&mut test, VASSI_FIRST, TdmaTime::default().add_timeslots(2));
+    let pdu = DLocationUpdateReject::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(pdu.reject_cause as u64, RejectCause::IllegalMs.into_raw());
+    assert!(!mm(&mut test).client_mgr.client_is_known(VASSI_FIRST));
+
+    // A visitor re-registers under its (V)ASSI
+    submit_migrating_location_update(&mut test, 3010001);
+    let mut sdu = submit_u_location_update_demand_from(&mut test, VASSI_FIRST, TdmaTime::default().add_timeslots(6));
+    assert_eq!(sdu.peek_bits(4), Some(MmPduTypeDl::DLocationUpdateAccept.into_raw()));
+    DLocationUpdateAccept::from_bitbuf(&mut sdu).unwrap();
+}
+
+#[test]
+fn test_migration_not_enabled() {
+    debug::setup_logging_verbose();
+    let config = default_test_config(StackMode::Bs);
+    let mut test = ComponentTest::new(config, Some(TdmaTime::default().add_timeslots(2)));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle]);
+
+    let mut responses = submit_migrating_location_update(&mut test, 3010001);
+    assert_eq!(responses.len(), 1);
+    let pdu = DLocationUpdateReject::from_bitbuf(&mut responses[0].1).unwrap();
+    assert_eq!(pdu.reject_cause as u64, RejectCause::MigrationNotSupported.into_raw());
+    assert_eq!(mm(&mut test).client_mgr.client_count(), 0);
+}
+
+fn mm(test: &mut ComponentTest) -> &mut MmBs {
+    test.router.get_entity(TetraEntity::Mm).unwrap().as_any_mut().downcast_mut::<MmBs>().unwrap()
+}
+
+/// Sets up an MM entity with ISSI 2040814 registered
+fn setup_disable_test(allow_disable: bool) -> ComponentTest {
+    let mut config = default_test_config(StackMode::Bs);
+    config.access_control.allow_disable = allow_disable;
+    let mut test = ComponentTest::new(config, Some(TdmaTime::default().add_timeslots(2)));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle]);
+    mm(&mut test).client_mgr.try_register_client(2040814, true).unwrap();
+    test
+}
+
+fn submit_u_disable_status(test: &mut ComponentTest, subscription_status: DisableStatus) {
+    let pdu = UDisableStatus { subscription_status, ..Default::default() };
+    let mut sdu = BitBuffer::new_autoexpand(16);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    test.submit_message(SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime: TdmaTime::default().add_timeslots(6),
+        msg: SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu,
+            handle: 0,
+            received_address: TetraAddress { encrypted: false, ssi_type: SsiType::Issi, ssi: 2040814 },
+            rx_quality: None,
+        }),
+    });
+    test.deliver_all_messages();
+}
+
+fn client_state(test: &mut ComponentTest) -> MmClientState {
+    mm(test).client_mgr.get_client_by_issi(2040814).unwrap().state
+}
+
+#[test]
+fn test_disable_not_allowed() {
+    // Without access_control.allow_disable, nothing is sent and U-DISABLE STATUS is ignored
+    debug::setup_logging_verbose();
+    let mut test = setup_disable_test(false);
+
+    let mut queue = MessageQueue::new();
+    assert!(!mm(&mut test).send_d_disable(&mut queue, TdmaTime::default(), 2040814, false));
+    assert!(queue.pop_front().is_none());
+
+    submit_u_disable_status(&mut test, DisableStatus::TemporarilyDisabled);
+    assert_eq!(client_state(&mut test), MmClientState::Attached);
+}
+
+#[test]
+fn test_disable_and_enable() {
+    debug::setup_logging_verbose();
+    let mut test = setup_disable_test(true);
+
+    let mut queue = MessageQueue::new();
+    assert!(mm(&mut test).send_d_disable(&mut queue, TdmaTime::default(), 2040814, false));
+    let SapMsgInner::LmmMleUnitdataReq(mut prim) = queue.pop_front().unwrap().msg else { panic!("Expected LmmMleUnitdataReq") };
+    assert_eq!(prim.address.ssi, 2040814);
+    let pdu = DDisable::from_bitbuf(&mut prim.sdu).unwrap();
+    assert!(pdu.subscription_disable && !pdu.disabling_type);
+    assert_eq!(pdu.ssi, Some(2040814));
+
+    assert_eq!(client_state(&mut test), MmClientState::Disabled);
+    submit_u_disable_status(&mut test, DisableStatus::TemporarilyDisabled);
+    assert_eq!(client_state(&mut test), MmClientState::Disabled);
+
+    assert!(mm(&mut test).send_d_enable(&mut queue, TdmaTime::default(), 2040814));
+    assert!(queue.pop_front().is_some());
+    assert_eq!(client_state(&mut test), MmClientState::Attached);
+    submit_u_disable_status(&mut test, DisableStatus::Enabled);
+    assert_eq!(client_state(&mut test), MmClientState::Attached);
+}
+
+#[test]
+fn test_u_location_update_demand_disabled_issi() {
+    debug::setup_logging_verbose();
+    let mut test = setup_disable_test(true);
+    let mut queue = MessageQueue::new();
+    assert!(mm(&mut test).send_d_disable(&mut queue, TdmaTime::default(), 2040814, false));
+
+    let mut sdu = submit_u_location_update_demand(&mut test);
+    assert_eq!(sdu.peek_bits(4), Some(MmPduTypeDl::DLocationUpdateReject.into_raw()));
+    let pdu = DLocationUpdateReject::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(pdu.reject_cause as u64, RejectCause::ServiceNotSubscribed.into_raw());
+}
+
+#[test]
+fn test_disabled_state_persists_across_reconnect() {
+    // Once disabled, the ISSI stays disabled after detaching, until it is enabled again
+    debug::setup_logging_verbose();
+    let mut test = setup_disable_test(true);
+    let mut queue = MessageQueue::new();
+    assert!(mm(&mut test).send_d_disable(&mut queue, TdmaTime::default(), 2040814, false));
+    assert!(mm(&mut test).client_mgr.remove_client(2040814).is_some());
+
+    let sdu = submit_u_location_update_demand(&mut test);
+    assert_eq!(sdu.peek_bits(4), Some(MmPduTypeDl::DLocationUpdateReject.into_raw()));
+    assert!(!mm(&mut test).client_mgr.client_is_known(2040814));
+
+    assert!(mm(&mut test).send_d_enable(&mut queue, TdmaTime::default(), 2040814));
+    let sdu = submit_u_location_update_demand(&mut test);
+    assert_eq!(sdu.peek_bits(4), Some(MmPduTypeDl::DLocationUpdateAccept.into_raw()));
+    assert_eq!(client_state(&mut test), MmClientState::Attached);
+}
+
+/// Challenges with a bare D-AUTHENTICATION PDU type and accepts a response whose first bit after the PDU type is set
+struct StubAuthenticator;
+
+impl Authenticator for StubAuthenticator {
+    fn challenge(&mut self, _issi: u32) -> Option<BitBuffer> {
+        Some(BitBuffer::from_bitstr("0001"))
+    }
+
+    fn verify(&mut self, _issi: u32, sdu: &mut BitBuffer) -> bool {
+        sdu.seek(4);
+        sdu.read_bits(1) == Some(1)
+    }
+}
+
+fn setup_authentication_test(authenticator: Option<Box<dyn Authenticator>>) -> ComponentTest {
+    let mut config = default_test_config(StackMode::Bs);
+    config.access_control.require_authentication = true;
+    let mut test = ComponentTest::new(config, Some(TdmaTime::default().add_timeslots(2)));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle]);
+    if let Some(authenticator) = authenticator {
+        mm(&mut test).set_authenticator(authenticator);
+    }
+    test
+}
+
+/// Submits a U-AUTHENTICATION from ISSI 2040814 and returns the SDU of the single response
+fn submit_u_authentication(test: &mut ComponentTest, sdu: &str) -> BitBuffer {
+    test.submit_message(SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime: TdmaTime::default().add_timeslots(6),
+        msg: SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu: BitBuffer::from_bitstr(sdu),
+            handle: 0,
+            received_address: TetraAddress { encrypted: false, ssi_type: SsiType::Issi, ssi: 2040814 },
+            rx_quality: None,
+        }),
+    });
+    test.run_stack(Some(1));
+    let mut sink_msgs = test.dump_sinks();
+    assert_eq!(sink_msgs.len(), 1);
+    let SapMsgInner::LmmMleUnitdataReq(prim) = sink_msgs.remove(0).msg else { panic!("Expected LmmMleUnitdataReq") };
+    prim.sdu
+}
+
+#[test]
+fn test_authentication_required() {
+    debug::setup_logging_verbose();
+    let mut test = setup_authentication_test(Some(Box::new(StubAuthenticator)));
+
+    // The demand is answered with a challenge instead of an accept
+    let sdu = submit_u_location_update_demand(&mut test);
+    assert_eq!(sdu.peek_bits(4), Some(MmPduTypeDl::DAuthentication.into_raw()));
+    assert!(!mm(&mut test).client_mgr.client_is_known(2040814));
+
+    // A valid response completes the registration
+    let sdu = submit_u_authentication(&mut test, "00001");
+    assert_eq!(sdu.peek_bits(4), Some(MmPduTypeDl::DLocationUpdateAccept.into_raw()));
+    assert!(mm(&mut test).client_mgr.client_is_known(2040814));
+}
+
+#[test]
+fn test_authentication_failed() {
+    debug::setup_logging_verbose();
+    let mut test = setup_authentication_test(Some(Box::new(StubAuthenticator)));
+    submit_u_location_update_demand(&mut test);
+
+    let mut sdu = submit_u_authentication(&mut test, "00000");
+    let pdu = DLocationUpdateReject::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(pdu.reject_cause as u64, RejectCause::AuthenticationFailure.into_raw());
+    assert!(!mm(&mut test).client_mgr.client_is_known(2040814));
+}
+
+#[test]
+fn test_authentication_timeout() {
+    // The challenge is answered within 30 s, about 2118 timeslots, or the registration is rejected
+    debug::setup_logging_verbose();
+    let mut test = setup_authentication_test(Some(Box::new(StubAuthenticator)));
+    submit_u_location_update_demand(&mut test);
+
+    test.run_stack(Some(2000));
+    assert!(test.dump_sinks().is_empty());
+
+    test.run_stack(Some(200));
+    let mut sink_msgs = test.dump_sinks();
+    assert_eq!(sink_msgs.len(), 1);
+    let SapMsgInner::LmmMleUnitdataReq(mut prim) = sink_msgs.remove(0).msg else { panic!("Expected LmmMleUnitdataReq") };
+    let pdu = DLocationUpdateReject::from_bitbuf(&mut prim.sdu).unwrap();
+    assert_eq!(pdu.reject_cause as u64, RejectCause::AuthenticationFailure.into_raw());
+    assert!(!mm(&mut test).client_mgr.client_is_known(2040814));
+
+    // A late answer no longer registers the MS
+    test.submit_message(SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime: TdmaTime::default().add_timeslots(2300),
+        msg: SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu: BitBuffer::from_bitstr("00001"),
+            handle: 0,
+            received_address: TetraAddress { encrypted: false, ssi_type: SsiType::Issi, ssi: 2040814 },
+            rx_quality: None,
+        }),
+    });
+    test.run_stack(Some(1));
+    assert!(test.dump_sinks().is_empty());
+    assert!(!mm(&mut test).client_mgr.client_is_known(2040814));
+}
+
+#[test]
+fn test_authentication_required_without_authenticator() {
+    debug::setup_logging_verbose();
+    let mut test = setup_authentication_test(None);
+    let mut sdu = submit_u_location_update_demand(&mut test);
+    let pdu = DLocationUpdateReject::from_bitbuf(&mut sdu).unwrap();
+    assert_eq!(pdu.reject_cause as u64, RejectCause::AuthenticationFailure.into_raw());
+}
+
+/// Submits a U-ITSI DETACH from registered ISSI 2040814 and returns all resulting messages
+fn run_u_itsi_detach(detach_ack_status: Option<u8>) -> (ComponentTest, Vec<SapMsg>) {
+    let mut config = default_test_config(StackMode::Bs);
+    config.access_control.detach_ack_status = detach_ack_status;
+    let mut test = ComponentTest::new(config, Some(TdmaTime::default().add_timeslots(2)));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle]);
+    mm(&mut test).client_mgr.try_register_client(2040814, true).unwrap();
+    let sink_msgs = submit_u_itsi_detach_from(&mut test, 2040814);
+    (test, sink_msgs)
+}
+
+/// Submits a U-ITSI DETACH from the given SSI and returns all responses
+fn submit_u_itsi_detach_from(test: &mut ComponentTest, ssi: u32) -> Vec<SapMsg> {
+    test.submit_message(SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime: TdmaTime::default().add_timeslots(6),
+        msg: SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu: BitBuffer::from_bitstr("00010"),
+            handle: 0,
+            received_address: TetraAddress { encrypted: false, ssi_type: SsiType::Issi, ssi },
+            rx_quality: None,
+        }),
+    });
+    test.run_stack(Some(1));
+    test.dump_sinks()
+}
+
+#[test]
+fn test_u_itsi_detach_acknowledged() {
+    debug::setup_logging_verbose();
+    let (mut test, mut sink_msgs) = run_u_itsi_detach(Some(32));
+    assert!(!mm(&mut test).client_mgr.client_is_known(2040814));
+
+    assert_eq!(sink_msgs.len(), 1);
+    let SapMsgInner::LmmMleUnitdataReq(prim) = sink_msgs.remove(0).msg else { panic!("Expected LmmMleUnitdataReq") };
+    assert_eq!(prim.address.ssi, 2040814);
+    let mut sdu = prim.sdu;
+    assert_eq!(sdu.read_bits(4), Some(MmPduTypeDl::DMmStatus.into_raw()));
+    assert_eq!(sdu.read_bits(6), Some(32));
+}
+
+#[test]
+fn test_u_itsi_detach_not_acknowledged() {
+    debug::setup_logging_verbose();
+    let (mut test, sink_msgs) = run_u_itsi_detach(None);
+    assert!(!mm(&mut test).client_mgr.client_is_known(2040814));
+    assert!(sink_msgs.is_empty());
+}
+
+/// Builds a U-ATTACH/DETACH GROUP IDENTITY in amendment mode from `issi`, attaching to or detaching from `gssi`
+fn group_attach_detach_msg(issi: u32, gssi: u32, attach: bool) -> SapMsg {
+    let pdu = UAttachDetachGroupIdentity {
+        group_identity_uplink: Some(vec![GroupIdentityUplink {
+            class_of_usage: attach.then_some(4),
+            group_identity_detachment_uplink: (!attach).then_some(0),
+            gssi: Some(gssi),
+            address_extension: None,
+            vgssi: None,
+        }]),
+        ..Default::default()
+    };
+    let mut sdu = BitBuffer::new_autoexpand(64);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime: TdmaTime::default().add_timeslots(6),
+        msg: SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu,
+            handle: 0,
+            received_address: TetraAddress { encrypted: false, ssi_type: SsiType::Issi, ssi: issi },
+            rx_quality: None,
+        }),
+    }
+}
+
+/// Submits a U-ATTACH/DETACH GROUP IDENTITY in amendment mode from `issi`, attaching to or detaching from `gssi`
+fn submit_group_attach_detach(test: &mut ComponentTest, issi: u32, gssi: u32, attach: bool) {
+    test.submit_message(group_attach_detach_msg(issi, gssi, attach));
+    test.run_stack(Some(1));
+    assert_eq!(test.dump_sinks().len(), 1);
+}
+
+#[test]
+fn test_group_registry() {
+    debug::setup_logging_verbose();
+    let config = default_test_config(StackMode::Bs);
+    let mut test = ComponentTest::new(config, Some(TdmaTime::default().add_timeslots(2)));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle]);
+    for issi in [2040001, 2040002, 2040003] {
+        mm(&mut test).client_mgr.try_register_client(issi, true).unwrap();
+    }
+
+    submit_group_attach_detach(&mut test, 2040002, 91, true);
+    submit_group_attach_detach(&mut test, 2040001, 91, true);
+    submit_group_attach_detach(&mut test, 2040003, 26, true);
+    assert_eq!(mm(&mut test).group_members(91), vec![2040001, 2040002]);
+    assert_eq!(mm(&mut test).group_members(26), vec![2040003]);
+    assert!(mm(&mut test).group_members(7).is_empty());
+
+    submit_group_attach_detach(&mut test, 2040002, 91, false);
+    assert_eq!(mm(&mut test).group_members(91), vec![2040001]);
+
+    // Deregistration removes an MS from all of its groups
+    mm(&mut test).client_mgr.remove_client(2040003);
+    assert!(mm(&mut test).group_members(26).is_empty());
+}
+
+#[test]
+fn test_group_changes_reported_to_cmce() {
+    debug::setup_logging_verbose();
+    let config = default_test_config(StackMode::Bs);
+    let mut test = ComponentTest::new(config, Some(TdmaTime::default().add_timeslots(2)));
+    test.populate_entities(vec![TetraEntity::Mm], vec![TetraEntity::Mle, TetraEntity::Cmce]);
+    mm(&mut test).client_mgr.try_register_client(2040001, true).unwrap();
+
+    let group_changes = |test: &mut ComponentTest| -> Vec<GroupControl> {
+        test.dump_sinks().into_iter().filter_map(|msg| match msg.msg {
+            SapMsgInner::MmGroupControl(change) => {
+                assert_eq!((msg.sap, msg.dest), (Sap::Control, TetraEntity::Cmce));
+                Some(change)
+            }
+            _ => None,
+        }).collect()
+    };
+
+    test.submit_message(group_attach_detach_msg(2040001, 91, true));
+    test.run_stack(Some(1));
+    assert_eq!(group_changes(&mut test), vec![GroupControl::Attach { issi: 2040001, gssi: 91 }]);
+
+    // Attaching again to the same group changes nothing
+    test.submit_message(group_attach_detach_msg(2040001, 91, true));
+    test.run_stack(Some(1));
+    assert!(group_changes(&mut test).is_empty());
+
+    test.submit_message(group_attach_detach_msg(2040001, 91, false));
+    test.run_stack(Some(1));
+    assert_eq!(group_changes(&mut test), vec![GroupControl::Detach { issi: 2040001, gssi: 91 }]);
+
+    // Deregistration detaches from all remaining groups
+    test.submit_message(group_attach_detach_msg(2040001, 26, true));
+    test.run_stack(Some(1));
+    assert_eq!(group_changes(&mut test).len(), 1);
+    mm(&mut test).client_mgr.remove_client(2040001);
+    test.run_stack(Some(1));
+    assert_eq!(group_changes(&mut test), vec![GroupControl::DetachAll { issi: 2040001 }]);
+}
@@ -0,0 +1,179 @@
+mod common;
+
+use std::sync::{Arc, Mutex};
+
+use tetra_config::{CfgSdsAutoresponder, CfgSdsRule, StackMode};
+use tetra_core::{BitBuffer, Direction, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress, debug};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_pdus::cmce::pdus::cmce_pdu::CmcePdu;
+use tetra_pdus::cmce::pdus::cmce_pdu_dl::CmcePduDl;
+use tetra_pdus::cmce::pdus::cmce_pdu_ul::CmcePduUl;
+use tetra_pdus::cmce::pdus::u_sds_data::USdsData;
+use tetra_saps::lcmc::LcmcMleUnitdataInd;
+use tetra_saps::lmm::LmmMleUnitdataInd;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tmv::{TmvUnitdataInd, enums::logical_chans::LogicalChannel};
+use common::{ComponentTest, default_test_config};
+
+#[test]
+fn test_pdu_log_time_advances() {
+    debug::setup_logging_verbose();
+    let config = default_test_config(StackMode::Bs);
+    let mut test = ComponentTest::new(config, None);
+    let components = vec![
+        TetraEntity::Umac,
+        TetraEntity::Llc,
+        TetraEntity::Mle,
+    ];
+    let sinks: Vec<TetraEntity> = vec![
+        TetraEntity::Lmac,
+    ];
+    test.populate_entities(components, sinks);
+    test.router.enable_pdu_log(1000);
+
+    // Run a full multiframe, the UMAC sends down a downlink slot every tick
+    test.run_stack(Some(72));
+    let log = test.router.pdu_log().unwrap();
+    let to_lmac: Vec<_> = log.entries().filter(|e| e.entity == TetraEntity::Lmac).collect();
+    assert!(to_lmac.len() >= 72);
+    assert!(to_lmac.iter().all(|e| e.dir == Direction::Dl));
+
+    // Entries are recorded in delivery order, so time never goes backwards
+    let entries: Vec<_> = log.entries().collect();
+    assert!(entries.windows(2).all(|w| w[1].time.diff(w[0].time) >= 0));
+    assert!(entries.last().unwrap().time.diff(entries[0].time) >= 71);
+}
+
+#[test]
+fn test_pdu_log_shows_subslot() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime { t: 3, f: 5, m: 7, h: 12 };
+    let config = default_test_config(StackMode::Bs);
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![], vec![TetraEntity::Umac]);
+    test.router.enable_pdu_log(10);
+
+    // Blocks received in the second subslot and in a full slot
+    for (block_num, logical_channel) in [(PhyBlockNum::Block2, LogicalChannel::SchHu), (PhyBlockNum::Both, LogicalChannel::SchF)] {
+        test.submit_message(SapMsg {
+            sap: Sap::TmvSap,
+            src: TetraEntity::Lmac,
+            dest: TetraEntity::Umac,
+            dltime,
+            msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+                pdu: BitBuffer::from_bitstr("0000"),
+                block_num,
+                logical_channel,
+                crc_pass: true,
+                scrambling_code: 864282631,
+                rx_quality: None,
+            }),
+        });
+    }
+    test.deliver_all_messages();
+
+    let dump = test.router.pdu_log().unwrap().dump();
+    let lines: Vec<_> = dump.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("   12:07:05:3:2 Ul Umac "), "{}", lines[0]);
+    assert!(lines[1].starts_with("   12:07:05:3 Ul Umac "), "{}", lines[1]);
+}
+
+/// Builds a U-SDS-DATA from `ssi` to `called_ssi`, as delivered by the MLE to the CMCE
+fn u_sds_data_msg(ssi: u32, called_ssi: u32, dltime: TdmaTime) -> SapMsg {
+    let pdu = USdsData {
+        called_party_type_identifier: 1,
+        called_party_ssi: Some(called_ssi as u64),
+        short_data_type_identifier: 0,
+        user_defined_data_1: Some(0x42),
+        ..Default::default()
+    };
+    let mut sdu = BitBuffer::new_autoexpand(64);
+    pdu.to_bitbuf(&mut sdu).unwrap();
+    sdu.seek(0);
+    SapMsg {
+        sap: Sap::LcmcSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Cmce,
+        dltime,
+        msg: SapMsgInner::LcmcMleUnitdataInd(LcmcMleUnitdataInd {
+            sdu,
+            handle: 0,
+            endpoint_id: 0,
+            link_id: 0,
+            received_tetra_address: TetraAddress { encrypted: false, ssi_type: SsiType::Issi, ssi },
+            chan_change_resp_req: false,
+            chan_change_handle: None,
+            rx_quality: None,
+        }),
+    }
+}
+
+#[test]
+fn test_pdu_log_filter_by_ssi() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let config = default_test_config(StackMode::Bs);
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![], vec![TetraEntity::Mm, TetraEntity::Cmce]);
+    test.router.enable_pdu_log(100);
+
+    // U-LOCATION UPDATE DEMAND from 2040814
+    let demand = "0010000001100010010010100000010000000001001100000111000001110000000010010000000101000000000000000000000001101000";
+    test.submit_message(SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime,
+        msg: SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu: BitBuffer::from_bitstr(demand),
+            handle: 0,
+            received_address: TetraAddress { encrypted: false, ssi_type: SsiType::Issi, ssi: 2040814 },
+            rx_quality: None,
+        }),
+    });
+    // Short data sent to 2040814 by another MS, and short data between two unrelated MSs
+    test.submit_message(u_sds_data_msg(1000002, 2040814, dltime));
+    test.submit_message(u_sds_data_msg(1000003, 1000004, dltime.add_timeslots(4)));
+    test.deliver_all_messages();
+
+    let log = test.router.pdu_log().unwrap();
+    assert_eq!(log.len(), 3);
+    assert!(log.entries().all(|e| e.dir == Direction::Ul));
+
+    let filtered = log.filter_by_ssi(2040814);
+    assert_eq!(filtered.len(), 2);
+    assert_eq!((filtered[0].entity, filtered[0].ssi), (TetraEntity::Mm, Some(2040814)));
+    assert_eq!((filtered[1].entity, filtered[1].ssi, filtered[1].addressed_ssi), (TetraEntity::Cmce, Some(1000002), Some(2040814)));
+    assert_eq!(log.filter_by_ssi(1000004).len(), 1);
+}
+
+#[test]
+fn test_pdu_observer() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut config = default_test_config(StackMode::Bs);
+    config.sds_autoresponder = Some(CfgSdsAutoresponder {
+        rules: vec![CfgSdsRule { match_status: 0x42, reply_status: Some(0x43), reply_text: None }],
+    });
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Cmce], vec![TetraEntity::Mle]);
+
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let seen_by_observer = seen.clone();
+    test.router.on_pdu(move |dir, pdu| seen_by_observer.lock().unwrap().push((dir, pdu.clone())));
+
+    // Both U-SDS-DATA are decoded on their way up, the auto-responder answers each with a D-SDS-DATA
+    test.submit_message(u_sds_data_msg(1000002, 2040814, dltime));
+    test.submit_message(u_sds_data_msg(1000003, 1000004, dltime));
+    test.deliver_all_messages();
+
+    let seen = seen.lock().unwrap();
+    let ul: Vec<_> = seen.iter().filter(|(dir, _)| *dir == Direction::Ul).collect();
+    let dl: Vec<_> = seen.iter().filter(|(dir, _)| *dir == Direction::Dl).collect();
+    assert_eq!(ul.len(), 2);
+    assert!(ul.iter().all(|(_, pdu)| matches!(pdu, CmcePdu::Ul(CmcePduUl::USdsData(_)))));
+    assert_eq!(dl.len(), 2);
+    assert!(dl.iter().all(|(_, pdu)| matches!(pdu, CmcePdu::Dl(CmcePduDl::DSdsData(d)) if d.user_defined_data_1 == Some(0x43))));
+    assert_eq!(seen.len(), 4);
+}
@@ -1,16 +1,25 @@
 mod common;
 
-use tetra_core::debug;
-use tetra_config::{PhyBackend, SharedConfig, StackMode};
+use std::sync::{Arc, Mutex};
+
+use tetra_core::{BitBuffer, BurstType, PhyBlockNum, PhyBlockType, TdmaTime, TrainingSequence, debug};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_config::{CfgAgc, CfgLoopback, PhyBackend, SharedConfig, StackMode};
 use tetra_config::stack_config_soapy::{CfgSoapySdr, LimeSdrCfg, UsrpB2xxCfg};
 use tetra_entities::{MessageRouter, TetraEntityTrait};
 use tetra_entities::mle::mle_bs_ms::Mle;
 use tetra_entities::lmac::lmac_bs::LmacBs;
+use tetra_entities::lmac::components::{errorcontrol, scrambler};
 use tetra_entities::mm::mm_bs::MmBs;
 use tetra_entities::llc::llc_bs_ms::Llc;
+use tetra_entities::phy::components::loopback_dev::RxTxDevLoopback;
 use tetra_entities::phy::components::soapy_dev::RxTxDevSoapySdr;
 use tetra_entities::phy::phy_bs::PhyBs;
 use tetra_entities::umac::umac_bs::UmacBs;
+use tetra_pdus::phy::traits::rxtx_dev::{RxSlotBits, RxTxDev, RxTxDevError, TxSlotBits};
+use tetra_pdus::umac::pdus::mac_sync::MacSync;
+use tetra_saps::tp::TpUnitdataInd;
+use tetra_saps::tmv::enums::logical_chans::LogicalChannel;
 use common::{ComponentTest, default_test_config};
 
 const DL_FREQ: f64 = 438.025e6;
@@ -120,3 +129,122 @@ fn test_usrp_bs() {
     test.register_entity(phy);
     test.run_stack(None);
 }
+
+#[test]
+fn test_loopback_sync_decoded_by_monitor() {
+    debug::setup_logging_default(None);
+    let mut raw_config = default_test_config(StackMode::Bs);
+    raw_config.phy_io.backend = PhyBackend::Loopback;
+    raw_config.phy_io.loopback = Some(CfgLoopback { noise_snr_db: Some(20.0), delay_samples: 37, ..Default::default() });
+    raw_config.phy_io.agc = Some(CfgAgc::default());
+    let colour_code = raw_config.cell.colour_code;
+
+    let mut test = ComponentTest::new(raw_config, None);
+    test.populate_entities(vec![TetraEntity::Lmac, TetraEntity::Umac, TetraEntity::Llc, TetraEntity::Mle, TetraEntity::Mm], vec![]);
+
+    // Loop the DL signal generated by LMAC back into the monitor demodulator
+    let mut rxdev = RxTxDevLoopback::new(&test.config);
+    let monitor = rxdev.monitor();
+    let phy = PhyBs::new(test.config.clone(), rxdev);
+    test.register_entity(phy);
+
+    // A SYNC is sent at least once per multiframe
+    test.run_stack(Some(4 * 18 * 2));
+
+    let sync_slots: Vec<_> = monitor.try_iter()
+        .filter(|slot| slot.train_type == TrainingSequence::SyncTrainSeq)
+        .collect();
+    assert!(!sync_slots.is_empty(), "No SYNC burst found in looped-back signal");
+
+    for slot in sync_slots {
+        let prim = TpUnitdataInd {
+            train_type: TrainingSequence::SyncTrainSeq,
+            burst_type: BurstType::SDB,
+            block_type: PhyBlockType::SB1,
+            block_num: PhyBlockNum::Block1,
+            block: BitBuffer::from_bitarr(&slot.bits[94..214]),
+            rx_quality: None,
+        };
+        let (type1, crc_ok) = errorcontrol::decode_cp(LogicalChannel::Bsch, prim, Some(scrambler::SCRAMB_INIT));
+        assert!(crc_ok, "SYNC CRC failed in slot {}", slot.time);
+        let sync = MacSync::from_bitbuf(&mut type1.unwrap()).expect("Failed parsing SYNC");
+        assert_eq!(sync.colour_code, colour_code);
+    }
+}
+
+/// RX/TX device recording, for each slot handed to it, whether a burst was transmitted
+struct RxTxDevRecorder {
+    slots: Arc<Mutex<Vec<(TdmaTime, bool)>>>,
+}
+
+impl RxTxDev for RxTxDevRecorder {
+    fn rxtx_timeslot(&mut self, tx_slot: &[TxSlotBits]) -> Result<Vec<Option<RxSlotBits<'_>>>, RxTxDevError> {
+        let mut slots = self.slots.lock().unwrap();
+        slots.extend(tx_slot.iter().map(|s| (s.time, s.slot.is_some())));
+        Ok(Vec::new())
+    }
+}
+
+#[test]
+fn test_no_tx_in_tx_off_frames() {
+    debug::setup_logging_default(None);
+    let mut raw_config = default_test_config(StackMode::Bs);
+    raw_config.cell.tx_off_frames = vec![5, 9];
+    raw_config.validate().unwrap();
+
+    let mut test = ComponentTest::new(raw_config, None);
+    test.populate_entities(vec![TetraEntity::Lmac, TetraEntity::Umac, TetraEntity::Llc, TetraEntity::Mle, TetraEntity::Mm], vec![]);
+    let slots = Arc::new(Mutex::new(Vec::new()));
+    let phy = PhyBs::new(test.config.clone(), RxTxDevRecorder { slots: slots.clone() });
+    test.register_entity(phy);
+    test.run_stack(Some(4 * 18 * 2));
+
+    // The device is still called every slot, but only transmits outside the off frames
+    let slots = slots.lock().unwrap();
+    assert!(slots.len() >= 4 * 18 * 2 - 1);
+    for (time, transmitted) in slots.iter() {
+        assert_eq!(*transmitted, time.f != 5 && time.f != 9, "slot {}", time);
+    }
+
+    // Frame 18 can not be switched off
+    let mut raw_config = default_test_config(StackMode::Bs);
+    raw_config.cell.tx_off_frames = vec![18];
+    assert!(raw_config.validate().is_err());
+}
+
+/// RX/TX device returning an empty slot each tick, numbered by its own slot counter.
+/// The counter skips `jump` slots at slot `jump_at`, as after lost samples.
+struct RxTxDevJumping {
+    slot: TdmaTime,
+    calls: usize,
+    jump_at: usize,
+    jump: i32,
+}
+
+impl RxTxDev for RxTxDevJumping {
+    fn rxtx_timeslot(&mut self, _tx_slot: &[TxSlotBits]) -> Result<Vec<Option<RxSlotBits<'_>>>, RxTxDevError> {
+        self.calls += 1;
+        self.slot = self.slot.add_timeslots(if self.calls == self.jump_at { 1 + self.jump } else { 1 });
+        Ok(vec![Some(RxSlotBits { time: self.slot, ..Default::default() })])
+    }
+}
+
+#[test]
+fn test_time_discontinuity_detected() {
+    debug::setup_logging_default(None);
+    let raw_config = default_test_config(StackMode::Bs);
+    let mut test = ComponentTest::new(raw_config, None);
+    test.populate_entities(vec![TetraEntity::Lmac, TetraEntity::Umac, TetraEntity::Llc, TetraEntity::Mle, TetraEntity::Mm], vec![]);
+
+    // Device time runs at an arbitrary offset from the stack; only its advance matters
+    let dev = RxTxDevJumping { slot: TdmaTime::from_int(1000), calls: 0, jump_at: 50, jump: 7 };
+    let phy = PhyBs::new(test.config.clone(), dev);
+    test.register_entity(phy);
+
+    test.run_stack(Some(40));
+    assert_eq!(test.config.state_read().tdma_discontinuities, 0);
+
+    // The jump is counted once, after which the new offset is followed again
+    test.run_stack(Some(60));
+    assert_eq!(test.config.state_read().tdma_discontinuities, 1);
+}
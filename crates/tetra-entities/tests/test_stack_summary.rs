@@ -0,0 +1,65 @@
+mod common;
+
+use tetra_core::{BitBuffer, debug, Sap, SsiType, TdmaTime, TetraAddress};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_config::StackMode;
+use tetra_entities::MessageQueue;
+use tetra_entities::cmce::cmce_bs::CmceBs;
+use tetra_saps::lmm::LmmMleUnitdataInd;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use common::{ComponentTest, default_test_config};
+
+#[test]
+fn test_summary_after_scripted_run() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(default_test_config(StackMode::Bs), Some(dltime));
+    test.populate_entities(vec![TetraEntity::Mm, TetraEntity::Cmce], vec![TetraEntity::Mle, TetraEntity::Umac]);
+
+    // An MS registers: ITSI attach with group identity location demand
+    let u_location_update_demand = "0010000001100010010010100000010000000001001100000111000001110000000010010000000101000000000000000000000001101000";
+    test.submit_message(SapMsg {
+        sap: Sap::LmmSap,
+        src: TetraEntity::Mle,
+        dest: TetraEntity::Mm,
+        dltime,
+        msg: SapMsgInner::LmmMleUnitdataInd(LmmMleUnitdataInd {
+            sdu: BitBuffer::from_bitstr(u_location_update_demand),
+            handle: 0,
+            received_address: TetraAddress { encrypted: false, ssi_type: SsiType::Issi, ssi: 2040814 },
+            rx_quality: None,
+        }),
+    });
+
+    // And a group call is set up
+    let mut queue = MessageQueue::new();
+    let cmce = test.router.get_entity(TetraEntity::Cmce).unwrap().as_any_mut().downcast_mut::<CmceBs>().unwrap();
+    cmce.run_call_test(&mut queue, dltime);
+    while let Some(msg) = queue.pop_front() {
+        test.submit_message(msg);
+    }
+
+    test.run_stack(Some(10));
+    test.config.state_write().sdr_rx_overruns = 3;
+    test.config.state_write().tdma_discontinuities = 2;
+
+    let summary = test.router.summary();
+    assert_eq!(summary.ticks, 10);
+    assert_eq!(summary.calls_handled, Some(1));
+    assert_eq!(summary.ms_registered, Some(1));
+    assert_eq!(summary.registrations, Some(1));
+    assert_eq!((summary.sdr_rx_overruns, summary.sdr_tx_underruns, summary.tdma_discontinuities), (3, 0, 2));
+    assert_eq!(summary.primitive_count("LmmMleUnitdataInd"), 1);
+    assert_eq!(summary.primitive_count("LmmMleUnitdataReq"), 1);
+    assert_eq!(summary.primitive_count("CmceCallControl"), 1);
+    // D-SETUP and its repetitions for late entry, as received by the MLE
+    let d_setups = test.dump_sinks().iter().filter(|msg| matches!(msg.msg, SapMsgInner::LcmcMleUnitdataReq(_))).count();
+    assert!(d_setups >= 1);
+    assert_eq!(summary.primitive_count("LcmcMleUnitdataReq"), d_setups as u64);
+
+    let text = summary.to_string();
+    assert!(text.contains("10 ticks"), "{}", text);
+    assert!(text.contains("calls handled: 1"), "{}", text);
+    assert!(text.contains("MSs registered: 1 (1 registrations)"), "{}", text);
+    assert!(text.contains("3 RX overruns, 0 TX underruns (0 TX re-inits), 2 TDMA discontinuities"), "{}", text);
+}
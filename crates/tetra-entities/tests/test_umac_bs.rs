@@ -1,10 +1,18 @@
 mod common;
 
-use tetra_core::{BitBuffer, debug, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress};
+use tetra_core::{BitBuffer, debug, Direction, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress};
 use tetra_core::tetra_entities::TetraEntity;
-use tetra_config::StackMode;
+use tetra_config::{SecurityClass, SharedConfig, StackMode};
+use tetra_entities::umac::umac_bs::UmacBs;
+use tetra_pdus::umac::enums::sysinfo_opt_field_flag::SysinfoOptFieldFlag;
+use tetra_pdus::umac::fields::sysinfo_ext_services::{ExtServicesSection1, SysinfoExtendedServices};
+use tetra_pdus::umac::pdus::mac_resource::MacResource;
+use tetra_pdus::umac::pdus::mac_sysinfo::MacSysinfo;
+use tetra_saps::control::call_control::{CallControl, Circuit};
+use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
 use tetra_saps::lmm::LmmMleUnitdataReq;
 use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tmd::TmdCircuitDataReq;
 use tetra_saps::tmv::{TmvUnitdataInd, enums::logical_chans::LogicalChannel};
 use common::{ComponentTest, default_test_config};
 
@@ -22,7 +30,8 @@ fn test_in_fragmented_sch_hu_and_sch_f() {
         block_num: PhyBlockNum::Block1,
         logical_channel: LogicalChannel::SchHu,
         crc_pass: true,
-        scrambling_code: 864282631};
+        scrambling_code: 864282631,
+        rx_quality: None};
     let test_sapmsg1 = SapMsg {
         sap: Sap::TmvSap,
         src: TetraEntity::Lmac,
@@ -34,7 +43,8 @@ fn test_in_fragmented_sch_hu_and_sch_f() {
         block_num: PhyBlockNum::Both,
         logical_channel: LogicalChannel::SchF,
         crc_pass: true,
-        scrambling_code: 864282631};
+        scrambling_code: 864282631,
+        rx_quality: None};
     let test_sapmsg2 = SapMsg {
         sap: Sap::TmvSap,
         src: TetraEntity::Lmac,
@@ -84,7 +94,8 @@ fn test_in_fragmented_sch_hu_and_sch_hu() {
         block_num: PhyBlockNum::Block1,
         logical_channel: LogicalChannel::SchHu,
         crc_pass: true,
-        scrambling_code: 864282631};
+        scrambling_code: 864282631,
+        rx_quality: None};
     let test_sapmsg1 = SapMsg {
         sap: Sap::TmvSap,
         src: TetraEntity::Lmac,
@@ -96,7 +107,8 @@ fn test_in_fragmented_sch_hu_and_sch_hu() {
         block_num: PhyBlockNum::Block1,
         logical_channel: LogicalChannel::SchHu,
         crc_pass: true,
-        scrambling_code: 864282631};
+        scrambling_code: 864282631,
+        rx_quality: None};
     let test_sapmsg2 = SapMsg {
         sap: Sap::TmvSap,
         src: TetraEntity::Lmac,
@@ -177,3 +189,268 @@ fn test_out_fragmented_resource() {
     tracing::info!("Validation of result not implemented");
 }
 
+
+#[test]
+fn test_sysinfo_security_class_broadcast_bits() {
+    debug::setup_logging_verbose();
+
+    // (security class, sck_number, cck_id, expected 8-bit security information element)
+    let cases = [
+        (SecurityClass::Clear, None, None, "00000000"),
+        (SecurityClass::Sck, Some(3), None, "01000010"),
+        (SecurityClass::Cck, None, Some(0x1234), "01100000"),
+    ];
+    for (security_class, sck_number, cck_id, expected) in cases {
+        let mut raw_config = default_test_config(StackMode::Bs);
+        raw_config.cell.aie_service = security_class != SecurityClass::Clear;
+        raw_config.cell.security_class = security_class;
+        raw_config.cell.sck_number = sck_number;
+        raw_config.cell.cck_id = cck_id;
+        raw_config.validate().unwrap();
+
+        let precomps = UmacBs::generate_precomps(&SharedConfig::from_config(raw_config));
+        let mut buf = BitBuffer::new_autoexpand(20);
+        precomps.mac_sysinfo2.ext_services.as_ref().unwrap().to_bitbuf(&mut buf);
+        assert_eq!(&buf.to_bitstr()[..8], expected, "security class {:?}", security_class);
+        assert_eq!(precomps.mle_sysinfo.bs_service_details.aie_service, security_class != SecurityClass::Clear);
+        assert_eq!(precomps.mac_sysinfo2.cck_id, cck_id);
+        assert_eq!(precomps.mac_sysinfo2.hyperframe_number.is_some(), cck_id.is_none());
+    }
+
+    // Enabling a class without its key is rejected
+    let mut raw_config = default_test_config(StackMode::Bs);
+    raw_config.cell.aie_service = true;
+    raw_config.cell.security_class = SecurityClass::Sck;
+    assert!(raw_config.validate().is_err());
+    raw_config.cell.security_class = SecurityClass::Cck;
+    assert!(raw_config.validate().is_err());
+}
+
+
+fn umac(test: &mut ComponentTest) -> &mut UmacBs {
+    test.router.get_entity(TetraEntity::Umac).unwrap().as_any_mut().downcast_mut::<UmacBs>().unwrap()
+}
+
+fn cmce_to_umac(dltime: TdmaTime, msg: SapMsgInner) -> SapMsg {
+    SapMsg { sap: Sap::Control, src: TetraEntity::Cmce, dest: TetraEntity::Umac, dltime, msg }
+}
+
+fn circuit(call_id: u16, ssi: u32, ts: u8) -> Circuit {
+    Circuit {
+        direction: Direction::Dl,
+        ts,
+        call_id,
+        ssi,
+        usage: 4,
+        circuit_mode: CircuitModeType::TchS,
+        speech_service: Some(0),
+        etee_encrypted: false,
+    }
+}
+
+#[test]
+fn test_tch_assignment_on_call_setup_and_teardown() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(default_test_config(StackMode::Bs), Some(dltime));
+    test.populate_entities(vec![TetraEntity::Umac], vec![TetraEntity::Lmac]);
+
+    // Call setup assigns the circuit's timeslot to the call and its SSI
+    test.submit_message(cmce_to_umac(dltime, SapMsgInner::CmceCallControl(CallControl::Open(circuit(5, 26, 2)))));
+    test.deliver_all_messages();
+    let assignments = umac(&mut test).channel_scheduler.tch_assignments();
+    assert_eq!(assignments.ts_for_call(5), Some(2));
+    assert_eq!(assignments.ts_for_ssi(26), Some(2));
+
+    // U-plane frames are routed to the assigned timeslot by call id, others are dropped
+    let frame = |call_id, byte| SapMsgInner::TmdCircuitDataReq(TmdCircuitDataReq { call_id, data: vec![byte; 66] });
+    test.submit_message(SapMsg { sap: Sap::TmdSap, ..cmce_to_umac(dltime, frame(5, 0xA5)) });
+    test.submit_message(SapMsg { sap: Sap::TmdSap, ..cmce_to_umac(dltime, frame(9, 0xFF)) });
+    test.run_stack(Some(8));
+    let traffic: Vec<(u8, String)> = test.dump_sinks().into_iter().filter_map(|msg| {
+        let SapMsgInner::TmvUnitdataReq(slot) = msg.msg else { return None };
+        let blk = slot.blk1?;
+        (blk.logical_channel == LogicalChannel::TchS).then(|| (slot.ts.t, blk.mac_block.to_bitstr()[..8].to_string()))
+    }).collect();
+    assert!(traffic.iter().all(|(ts, _)| *ts == 2));
+    assert_eq!(traffic.iter().filter(|(_, bits)| bits == "10100101").count(), 1);
+    assert!(!traffic.iter().any(|(_, bits)| bits == "11111111"));
+
+    // The MCCH timeslot can't be assigned to a call
+    test.submit_message(cmce_to_umac(dltime, SapMsgInner::CmceCallControl(CallControl::Open(circuit(6, 2040001, 1)))));
+    test.deliver_all_messages();
+    assert_eq!(umac(&mut test).channel_scheduler.tch_assignments().ts_for_call(6), None);
+    assert!(!umac(&mut test).channel_scheduler.circuit_is_active(Direction::Dl, 1));
+
+    // Teardown releases the assignment
+    test.submit_message(cmce_to_umac(dltime, SapMsgInner::CmceCallControl(CallControl::Close(Direction::Dl, 2))));
+    test.deliver_all_messages();
+    let assignments = umac(&mut test).channel_scheduler.tch_assignments();
+    assert_eq!(assignments.ts_for_call(5), None);
+    assert_eq!(assignments.get(2), None);
+}
+
+
+#[test]
+fn test_uplink_traffic_passed_to_uplane_by_call_id() {
+    debug::setup_logging_verbose();
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(default_test_config(StackMode::Bs), Some(dltime));
+    test.populate_entities(vec![TetraEntity::Umac], vec![TetraEntity::Lmac, TetraEntity::User]);
+
+    test.submit_message(cmce_to_umac(dltime, SapMsgInner::CmceCallControl(CallControl::Open(circuit(5, 26, 2)))));
+    test.deliver_all_messages();
+
+    // Traffic blocks received on the assigned timeslot are tagged with its call, others dropped
+    let traffic = |ts: u8, bitstr: &str| SapMsg {
+        sap: Sap::TmvSap,
+        src: TetraEntity::Lmac,
+        dest: TetraEntity::Umac,
+        dltime: TdmaTime { t: ts, ..TdmaTime::default() },
+        msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+            pdu: BitBuffer::from_bitstr(bitstr),
+            block_num: PhyBlockNum::Both,
+            logical_channel: LogicalChannel::TchS,
+            crc_pass: true,
+            scrambling_code: 0,
+            rx_quality: None,
+        }),
+    };
+    test.submit_message(traffic(2, "1010010111110000"));
+    test.submit_message(traffic(3, "0000000011111111"));
+    test.deliver_all_messages();
+
+    let frames: Vec<_> = test.dump_sinks().into_iter().filter_map(|msg| {
+        let SapMsgInner::TmdCircuitDataInd(prim) = msg.msg else { return None };
+        assert_eq!(msg.dest, TetraEntity::User);
+        Some((prim.call_id, prim.ts, prim.data))
+    }).collect();
+    assert_eq!(frames, vec![(5, 2, vec![0xA5, 0xF0])]);
+}
+
+#[test]
+fn test_sync_and_sysinfo_cadence() {
+    debug::setup_logging_verbose();
+    let mut config = default_test_config(StackMode::Bs);
+    config.cell.sync_period_multiframes = 2;
+    config.cell.sysinfo_period_multiframes = 3;
+    config.validate().unwrap();
+    let dltime = TdmaTime::default();
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Umac], vec![TetraEntity::Lmac]);
+
+    // Step through six multiframes, noting in which ones SYNC and SYSINFO were sent
+    test.run_stack(Some(6 * 18 * 4));
+    let mut sync = [false; 6];
+    let mut sysinfo = [false; 6];
+    for msg in test.dump_sinks() {
+        let SapMsgInner::TmvUnitdataReq(slot) = msg.msg else { continue };
+        let m = slot.ts.m as usize - 1;
+        if m >= 6 {
+            continue;
+        }
+        let lchans = [slot.blk1.as_ref(), slot.blk2.as_ref()].map(|blk| blk.map(|b| b.logical_channel));
+        sync[m] |= lchans.contains(&Some(LogicalChannel::Bsch));
+        sysinfo[m] |= lchans.contains(&Some(LogicalChannel::Bnch));
+
+        // On frame 18, each is present exactly in the multiframes it is due
+        if slot.ts.f == 18 {
+            assert_eq!(lchans[0] == Some(LogicalChannel::Bsch), m.is_multiple_of(2), "SYNC at {:?}", slot.ts);
+            assert_eq!(lchans[1] == Some(LogicalChannel::Bnch), m.is_multiple_of(3), "SYSINFO at {:?}", slot.ts);
+        }
+    }
+    assert_eq!(sync, [true, false, true, false, true, false]);
+    assert_eq!(sysinfo, [true, false, false, true, false, false]);
+
+    // Periods must be within one hyperframe
+    let mut config = default_test_config(StackMode::Bs);
+    config.cell.sync_period_multiframes = 0;
+    assert!(config.validate().is_err());
+    config.cell.sync_period_multiframes = 61;
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_null_pdu_filler_on_empty_queue() {
+    debug::setup_logging_verbose();
+    let mut config = default_test_config(StackMode::Bs);
+    config.cell.sysinfo_period_multiframes = 2;
+    let mut test = ComponentTest::new(config, Some(TdmaTime::default()));
+    test.populate_entities(vec![TetraEntity::Umac], vec![TetraEntity::Lmac]);
+
+    // Nothing is queued for the downlink, yet every block of every slot is filled
+    test.run_stack(Some(18 * 4));
+    let slots: Vec<_> = test.dump_sinks().into_iter().filter_map(|msg| match msg.msg {
+        SapMsgInner::TmvUnitdataReq(slot) => Some(slot),
+        _ => None,
+    }).collect();
+    assert!(!slots.is_empty());
+    let mut nulls = 0;
+    for slot in slots {
+        let blk1 = slot.blk1.expect("blk1 left empty");
+        if blk1.logical_channel != LogicalChannel::SchF {
+            assert!(slot.blk2.is_some(), "blk2 left empty at {:?}", slot.ts);
+        }
+
+        // Without SYNC or SYSINFO, blocks on the signalling channels are filled with null PDUs
+        for mut blk in [Some(blk1), slot.blk2].into_iter().flatten() {
+            if blk.logical_channel == LogicalChannel::SchF || blk.logical_channel == LogicalChannel::SchHd {
+                blk.mac_block.seek(0);
+                let pdu = MacResource::from_bitbuf(&mut blk.mac_block).unwrap();
+                assert!(pdu.is_null_pdu(), "{:?} at {:?} is not a null PDU", blk.logical_channel, slot.ts);
+                nulls += 1;
+            }
+        }
+    }
+    assert!(nulls > 0);
+
+    let filler = UmacBs::build_null_pdu(LogicalChannel::SchHd, 0);
+    assert_eq!(filler.mac_block.get_len(), 124);
+}
+
+/// Runs one multiframe and returns the timeslots at which MAC-SYSINFO carried the
+/// extended services broadcast, along with the last broadcast sent
+fn sysinfo_ext_services(config: tetra_config::StackConfig) -> (Vec<TdmaTime>, Option<SysinfoExtendedServices>) {
+    let mut test = ComponentTest::new(config, Some(TdmaTime::default()));
+    test.populate_entities(vec![TetraEntity::Umac], vec![TetraEntity::Lmac]);
+    test.run_stack(Some(18 * 4));
+    let mut sent_at = Vec::new();
+    let mut last = None;
+    for msg in test.dump_sinks() {
+        let SapMsgInner::TmvUnitdataReq(slot) = msg.msg else { continue };
+        let Some(mut blk2) = slot.blk2.filter(|b| b.logical_channel == LogicalChannel::Bnch) else { continue };
+        blk2.mac_block.seek(0);
+        let sysinfo = MacSysinfo::from_bitbuf(&mut blk2.mac_block).unwrap();
+        if sysinfo.option_field == SysinfoOptFieldFlag::ExtServicesBroadcast {
+            sent_at.push(slot.ts);
+            last = sysinfo.ext_services;
+        }
+    }
+    (sent_at, last)
+}
+
+#[test]
+fn test_ext_services_broadcast_on_frame_18() {
+    debug::setup_logging_verbose();
+
+    // Not sent without the frame 18 extension
+    let mut config = default_test_config(StackMode::Bs);
+    config.cell.data_priority_service = true;
+    let (sent_at, _) = sysinfo_ext_services(config);
+    assert!(sent_at.is_empty(), "sent at {:?}", sent_at);
+
+    // With it, sent on frame 18 only, announcing the enabled services
+    let mut config = default_test_config(StackMode::Bs);
+    config.cell.frame_18_ext = true;
+    config.cell.data_priority_service = true;
+    config.cell.qos_negotiation_service = true;
+    let (sent_at, ext_services) = sysinfo_ext_services(config);
+    assert!(!sent_at.is_empty());
+    assert!(sent_at.iter().all(|ts| ts.f == 18), "sent at {:?}", sent_at);
+    let ext_services = ext_services.unwrap();
+    assert_eq!(ext_services.section, 0);
+    let section1 = ExtServicesSection1::from_bits(ext_services.section_data);
+    assert!(section1.data_priority_supported);
+    assert!(section1.qos_negotiation_supported);
+    assert!(!section1.ext_advanced_links_supported);
+}
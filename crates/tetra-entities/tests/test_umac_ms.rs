@@ -1,8 +1,13 @@
 mod common;
 
-use tetra_core::{BitBuffer, debug, PhyBlockNum, Sap, TdmaTime};
+use tetra_core::{BitBuffer, debug, PhyBlockNum, Sap, SsiType, TdmaTime, TetraAddress};
 use tetra_core::tetra_entities::TetraEntity;
 use tetra_config::StackMode;
+use tetra_entities::umac::subcomp::fillbits;
+use tetra_pdus::cmce::pdus::{cmce_pdu_dl::CmcePduDl, d_alert::DAlert, d_tx_ceased::DTxCeased};
+use tetra_pdus::llc::pdus::bl_udata::BlUdata;
+use tetra_pdus::mle::enums::mle_protocol_discriminator::MleProtocolDiscriminator;
+use tetra_pdus::umac::pdus::mac_resource::MacResource;
 use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
 use tetra_saps::tmv::{TmvUnitdataInd, enums::logical_chans::LogicalChannel};
 use common::{ComponentTest, default_test_config};
@@ -32,6 +37,7 @@ fn test_umac_ms() {
                 logical_channel: LogicalChannel::SchHd,
                 crc_pass: true,
                 scrambling_code: 0,
+                rx_quality: None,
             }
         )
     };
@@ -79,6 +85,7 @@ fn test_umac_frag() {
                 logical_channel: LogicalChannel::SchHd,
                 crc_pass: true,
                 scrambling_code: 0,
+                rx_quality: None,
             }
         )
     };
@@ -97,6 +104,7 @@ fn test_umac_frag() {
                 logical_channel: LogicalChannel::SchHd,
                 crc_pass: true,
                 scrambling_code: 0,
+                rx_quality: None,
             }
         )
     };
@@ -143,6 +151,7 @@ fn test_sysinfo() {
                 logical_channel: LogicalChannel::Bnch,
                 crc_pass: true,
                 scrambling_code: 0,
+                rx_quality: None,
             }
         )
     };
@@ -188,6 +197,7 @@ fn test_sync() {
                 logical_channel: LogicalChannel::Bsch,
                 crc_pass: true,
                 scrambling_code: 0,
+                rx_quality: None,
             }
         )
     };
@@ -229,6 +239,7 @@ fn test_resource() {
                 logical_channel: LogicalChannel::SchF,
                 crc_pass: true,
                 scrambling_code: 0,
+                rx_quality: None,
             }
         )
     };
@@ -240,4 +251,80 @@ fn test_resource() {
     }
 
     tracing::warn!("Validation of result not implemented");
-}
\ No newline at end of file
+}
+/// Builds a MAC-RESOURCE PDU addressed to `ssi`, carrying a CMCE PDU in a BL-UDATA.
+fn write_mac_resource_with_cmce(mac_block: &mut BitBuffer, ssi: u32, cmce_pdu: &BitBuffer) {
+    let mut sdu = BitBuffer::new_autoexpand(64);
+    BlUdata { has_fcs: false }.to_bitbuf(&mut sdu);
+    sdu.write_bits(MleProtocolDiscriminator::Cmce.into_raw(), 3);
+    sdu.copy_bits(&mut BitBuffer::from_bitbuffer(cmce_pdu), cmce_pdu.get_len());
+
+    let mut pdu = MacResource {
+        addr: Some(TetraAddress::new(ssi, SsiType::Ssi)),
+        ..MacResource::null_pdu()
+    };
+    let sdu_len = sdu.get_len();
+    let num_fill_bits = pdu.update_len_and_fill_ind(sdu_len);
+    pdu.to_bitbuf(mac_block);
+    sdu.seek(0);
+    mac_block.copy_bits(&mut sdu, sdu_len);
+    fillbits::addition::write(mac_block, Some(num_fill_bits));
+}
+
+#[test]
+/// A full slot containing two MAC-RESOURCE PDUs, each carrying a CMCE PDU, followed by fill bits.
+/// Both CMCE PDUs must be delivered, and parsing must stop where the fill bits begin.
+fn test_umac_two_pdus_in_mac_block() {
+    debug::setup_logging_verbose();
+    let config = default_test_config(StackMode::Ms);
+    let mut test = ComponentTest::new(config, None);
+
+    let components = vec![
+        TetraEntity::Umac,
+        TetraEntity::Llc,
+        TetraEntity::Mle,
+    ];
+    let sinks = vec![
+        TetraEntity::Cmce,
+    ];
+    test.populate_entities(components, sinks);
+
+    let d_alert = DAlert { call_identifier: 1234, call_time_out_set_up_phase: 3, reserved: true, ..Default::default() };
+    let d_tx_ceased = DTxCeased { call_identifier: 1234, transmission_request_permission: true, ..Default::default() };
+    let mut pdu_1 = BitBuffer::new_autoexpand(64);
+    d_alert.to_bitbuf(&mut pdu_1).unwrap();
+    let mut pdu_2 = BitBuffer::new_autoexpand(64);
+    d_tx_ceased.to_bitbuf(&mut pdu_2).unwrap();
+
+    let mut mac_block = BitBuffer::new(268);
+    write_mac_resource_with_cmce(&mut mac_block, 2040814, &pdu_1);
+    write_mac_resource_with_cmce(&mut mac_block, 2040814, &pdu_2);
+    fillbits::addition::finalize(&mut mac_block);
+    mac_block.seek(0);
+
+    let m = SapMsg {
+        sap: Sap::TmvSap,
+        src: TetraEntity::Lmac,
+        dest: TetraEntity::Umac,
+        dltime: TdmaTime::default(),
+        msg: SapMsgInner::TmvUnitdataInd(
+            TmvUnitdataInd {
+                pdu: mac_block,
+                block_num: PhyBlockNum::Both,
+                logical_channel: LogicalChannel::SchF,
+                crc_pass: true,
+                scrambling_code: 0,
+                rx_quality: None,
+            }
+        )
+    };
+    test.submit_message(m);
+    test.deliver_all_messages();
+    let msgs = test.dump_sinks();
+
+    let parsed: Vec<CmcePduDl> = msgs.into_iter().map(|msg| {
+        let SapMsgInner::LcmcMleUnitdataInd(mut prim) = msg.msg else { panic!("unexpected message {:?}", msg) };
+        CmcePduDl::from_bitbuf(&mut prim.sdu).expect("Failed parsing")
+    }).collect();
+    assert_eq!(parsed, vec![CmcePduDl::DAlert(d_alert), CmcePduDl::DTxCeased(d_tx_ceased)]);
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use tetra_core::{BitBuffer, debug, Direction, PhyBlockNum, Sap, TdmaTime};
+use tetra_core::tetra_entities::TetraEntity;
+use tetra_config::{CfgUplane, StackMode};
+use tetra_entities::{MessageQueue, TetraEntityTrait};
+use tetra_entities::uplane::frame_sink::read_recording;
+use tetra_saps::control::call_control::{CallControl, Circuit};
+use tetra_saps::control::enums::circuit_mode_type::CircuitModeType;
+use tetra_saps::sapmsg::{SapMsg, SapMsgInner};
+use tetra_saps::tmv::{TmvUnitdataInd, enums::logical_chans::LogicalChannel};
+use tetra_saps::tnmm::TnmmTestDemand;
+use common::{ComponentTest, default_test_config};
+
+fn call_control(dltime: TdmaTime, prim: CallControl) -> SapMsg {
+    SapMsg { sap: Sap::Control, src: TetraEntity::Cmce, dest: TetraEntity::Umac, dltime, msg: SapMsgInner::CmceCallControl(prim) }
+}
+
+fn circuit(direction: Direction, call_id: u16, ssi: u32, ts: u8) -> Circuit {
+    Circuit {
+        direction,
+        ts,
+        call_id,
+        ssi,
+        usage: 4,
+        circuit_mode: CircuitModeType::TchS,
+        speech_service: Some(0),
+        etee_encrypted: false,
+    }
+}
+
+/// TCH/S block as passed up by the LMAC for uplink timeslot `ts`
+fn traffic(ts: u8, byte: u8) -> SapMsg {
+    let mut pdu = BitBuffer::new(432);
+    for _ in 0..54 {
+        pdu.write_bits(byte as u64, 8);
+    }
+    pdu.seek(0);
+    SapMsg {
+        sap: Sap::TmvSap,
+        src: TetraEntity::Lmac,
+        dest: TetraEntity::Umac,
+        dltime: TdmaTime { t: ts, ..TdmaTime::default() },
+        msg: SapMsgInner::TmvUnitdataInd(TmvUnitdataInd {
+            pdu,
+            block_num: PhyBlockNum::Both,
+            logical_channel: LogicalChannel::TchS,
+            crc_pass: true,
+            scrambling_code: 0,
+            rx_quality: None,
+        }),
+    }
+}
+
+fn recordings(dir: &PathBuf) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = std::fs::read_dir(dir).unwrap().map(|e| e.unwrap().path()).collect();
+    files.sort();
+    files
+}
+
+#[test]
+fn test_call_recorded_to_file() {
+    debug::setup_logging_verbose();
+    let dir = std::env::temp_dir().join(format!("bluestation_test_uplane_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let mut config = default_test_config(StackMode::Bs);
+    config.uplane = Some(CfgUplane { frame_dir: Some(dir.to_string_lossy().into_owned()), udp_target: None });
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Umac, TetraEntity::User], vec![TetraEntity::Lmac]);
+
+    // Duplex call on ts 2; both directions belong to the same recording
+    test.submit_message(call_control(dltime, CallControl::Open(circuit(Direction::Ul, 5, 26, 2))));
+    test.submit_message(call_control(dltime, CallControl::Open(circuit(Direction::Dl, 5, 26, 2))));
+    test.deliver_all_messages();
+
+    // Frames on ts 2 are recorded, frames on an unassigned timeslot are not
+    for byte in [0x11, 0x22, 0x33] {
+        test.submit_message(traffic(2, byte));
+        test.submit_message(traffic(3, 0xFF));
+    }
+    test.deliver_all_messages();
+
+    // The recording is complete once the last circuit of the call is closed
+    test.submit_message(call_control(dltime, CallControl::Close(Direction::Ul, 2)));
+    test.submit_message(call_control(dltime, CallControl::Close(Direction::Dl, 2)));
+    test.deliver_all_messages();
+
+    let files = recordings(&dir);
+    assert_eq!(files.len(), 1);
+    let name = files[0].file_name().unwrap().to_string_lossy().into_owned();
+    assert!(name.starts_with("call_5_ssi_26_") && name.ends_with(".tch"), "unexpected file {}", name);
+    let frames = read_recording(&files[0]).unwrap();
+    assert_eq!(frames.len(), 3);
+    assert_eq!(frames[0], vec![0x11; 54]);
+    assert_eq!(frames[2], vec![0x33; 54]);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+/// U-plane entity on the TNMM SAP, standing in for the network entity
+struct TnmmStub(Arc<Mutex<Vec<SapMsg>>>);
+
+impl TetraEntityTrait for TnmmStub {
+    fn entity(&self) -> TetraEntity {
+        TetraEntity::User
+    }
+
+    fn user_saps(&self) -> &[Sap] {
+        &[Sap::TnmmSap]
+    }
+
+    fn rx_prim(&mut self, _queue: &mut MessageQueue, message: SapMsg) {
+        self.0.lock().unwrap().push(message);
+    }
+}
+
+#[test]
+fn test_user_entities_routed_by_sap() {
+    debug::setup_logging_verbose();
+    let dir = std::env::temp_dir().join(format!("bluestation_test_uplane_sap_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+
+    let mut config = default_test_config(StackMode::Bs);
+    config.uplane = Some(CfgUplane { frame_dir: Some(dir.to_string_lossy().into_owned()), udp_target: None });
+    let dltime = TdmaTime::default().add_timeslots(2);
+    let mut test = ComponentTest::new(config, Some(dltime));
+    test.populate_entities(vec![TetraEntity::Umac, TetraEntity::User], vec![TetraEntity::Lmac]);
+    let tnmm_msgs = Arc::new(Mutex::new(Vec::new()));
+    test.register_entity(TnmmStub(tnmm_msgs.clone()));
+
+    // A second entity on the same SAP is not registered
+    let duplicate_msgs = Arc::new(Mutex::new(Vec::new()));
+    test.register_entity(TnmmStub(duplicate_msgs.clone()));
+
+    test.submit_message(SapMsg {
+        sap: Sap::TnmmSap,
+        src: TetraEntity::Mm,
+        dest: TetraEntity::User,
+        dltime,
+        msg: SapMsgInner::TnmmTestDemand(TnmmTestDemand { issi: 1001 }),
+    });
+    test.deliver_all_messages();
+    assert_eq!(tnmm_msgs.lock().unwrap().len(), 1);
+    assert!(duplicate_msgs.lock().unwrap().is_empty());
+
+    // Call control and traffic still reach the U-plane entity
+    test.submit_message(call_control(dltime, CallControl::Open(circuit(Direction::Ul, 5, 26, 2))));
+    test.submit_message(traffic(2, 0x11));
+    test.submit_message(call_control(dltime, CallControl::Close(Direction::Ul, 2)));
+    test.deliver_all_messages();
+    let files = recordings(&dir);
+    assert_eq!(files.len(), 1);
+    assert_eq!(read_recording(&files[0]).unwrap(), vec![vec![0x11; 54]]);
+    assert_eq!(tnmm_msgs.lock().unwrap().len(), 1);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
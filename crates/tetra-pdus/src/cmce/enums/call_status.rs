@@ -1,6 +1,7 @@
 /// 14.8.13 Call status
 /// Bits: 3
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum CallStatus {
     Callproceeding = 0,
@@ -1,6 +1,7 @@
 /// Clause 14.8.16 Call time-out
 /// Bits: 4
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum CallTimeout {
     Infinite = 0,
@@ -68,6 +69,35 @@ impl CallTimeout {
             CallTimeout::Reserved => 15,
         }
     }
+
+    /// Duration in seconds, None if infinite or reserved
+    pub fn secs(self) -> Option<u32> {
+        match self {
+            CallTimeout::Infinite | CallTimeout::Reserved => None,
+            CallTimeout::T30s => Some(30),
+            CallTimeout::T45s => Some(45),
+            CallTimeout::T60s => Some(60),
+            CallTimeout::T2m => Some(120),
+            CallTimeout::T3m => Some(180),
+            CallTimeout::T4 => Some(240),
+            CallTimeout::T5m => Some(300),
+            CallTimeout::T6m => Some(360),
+            CallTimeout::T8m => Some(480),
+            CallTimeout::T10m => Some(600),
+            CallTimeout::T12m => Some(720),
+            CallTimeout::T15m => Some(900),
+            CallTimeout::T20m => Some(1200),
+            CallTimeout::T30m => Some(1800),
+        }
+    }
+
+    /// Shortest call time-out lasting at least `secs` seconds, Infinite if none does
+    pub fn at_least_secs(secs: u32) -> Self {
+        (1..=14)
+            .filter_map(|raw| CallTimeout::try_from(raw).ok())
+            .find(|timeout| timeout.secs().is_some_and(|s| s >= secs))
+            .unwrap_or(CallTimeout::Infinite)
+    }
 }
 
 impl From<CallTimeout> for u64 {
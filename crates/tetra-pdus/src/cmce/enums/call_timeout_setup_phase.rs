@@ -1,6 +1,7 @@
 /// Clause 14.8.17 Call time-out, set-up phase
 /// Bits: 3
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum CallTimeoutSetupPhase {
     Predefined = 0,
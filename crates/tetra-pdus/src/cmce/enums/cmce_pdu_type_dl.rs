@@ -1,6 +1,7 @@
 /// Clause 14.8.28 PDU type
 /// Bits: 5
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum CmcePduTypeDl {
     DAlert = 0,
@@ -1,6 +1,7 @@
 /// Clause 29.4.3.9 SDS Protocol identifier. Values undefined here may be user definition or reserved
 /// Bits: 8
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum SdsProtocolId {
     Otak = 1,
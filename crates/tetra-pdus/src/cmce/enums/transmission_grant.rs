@@ -1,6 +1,7 @@
 /// Clause 14.8.42 Transmission grant
 /// Bits: 2
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum TransmissionGrant {
     Granted = 0,
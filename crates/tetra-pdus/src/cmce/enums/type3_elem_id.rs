@@ -2,6 +2,7 @@
 /// 
 /// Bits: 4
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum CmceType3ElemId {
     Dtmf = 1,
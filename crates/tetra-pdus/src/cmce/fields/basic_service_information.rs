@@ -5,7 +5,8 @@ use tetra_saps::control::enums::{circuit_mode_type::CircuitModeType, communicati
 
 
 /// Clause 14.8.2 Basic service information
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BasicServiceInformation {
     // 3
     pub circuit_mode_type: CircuitModeType,
@@ -1 +1,2 @@
-pub mod basic_service_information;
\ No newline at end of file
+pub mod basic_service_information;
+pub mod party_identifier;
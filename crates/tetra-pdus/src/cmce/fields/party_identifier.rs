@@ -0,0 +1,174 @@
+use tetra_core::{BitBuffer, PduParseErr};
+
+
+/// The party a type identifier refers to. Determines the field name used in errors,
+/// and whether a short number address (type identifier 0) may be used.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PartyType {
+    /// Calling party type identifier (CPTI)
+    Calling,
+    /// Called party type identifier (CPTI)
+    Called,
+    /// Other party type identifier (OPTI)
+    Other,
+    /// Transmitting party type identifier (TPTI)
+    Transmitting,
+}
+
+impl PartyType {
+    fn field(self) -> &'static str {
+        match self {
+            PartyType::Calling => "calling_party_type_identifier",
+            PartyType::Called => "called_party_type_identifier",
+            PartyType::Other => "other_party_type_identifier",
+            PartyType::Transmitting => "transmitting_party_type_identifier",
+        }
+    }
+
+    /// Only the called and other party may be addressed by short number
+    fn allows_short_number(self) -> bool {
+        matches!(self, PartyType::Called | PartyType::Other)
+    }
+}
+
+/// Party address elements selected by a party type identifier:
+/// 0: short number address (8 bits), 1: SSI (24 bits), 2: SSI and extension (24 + 24 bits), 3: reserved
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PartyIdentifier {
+    pub short_number_address: Option<u64>,
+    pub ssi: Option<u64>,
+    pub extension: Option<u64>,
+}
+
+impl PartyIdentifier {
+    /// Reads the conditional elements selected by an already read `type_identifier`
+    pub fn parse(buffer: &mut BitBuffer, party: PartyType, type_identifier: u8) -> Result<Self, PduParseErr> {
+        match type_identifier {
+            0 if party.allows_short_number() => Ok(Self {
+                short_number_address: Some(buffer.read_field(8, "short_number_address")?),
+                ..Default::default()
+            }),
+            1 => Ok(Self {
+                ssi: Some(buffer.read_field(24, "ssi")?),
+                ..Default::default()
+            }),
+            2 => Ok(Self {
+                ssi: Some(buffer.read_field(24, "ssi")?),
+                extension: Some(buffer.read_field(24, "extension")?),
+                ..Default::default()
+            }),
+            _ => Err(PduParseErr::InvalidValue { field: party.field(), value: type_identifier as u64 }),
+        }
+    }
+
+    /// As `parse`, for PDUs where the type identifier is a type2 element. Returns no elements if it is absent.
+    pub fn parse_optional(buffer: &mut BitBuffer, party: PartyType, type_identifier: Option<u64>) -> Result<Self, PduParseErr> {
+        match type_identifier {
+            Some(type_identifier) => Self::parse(buffer, party, type_identifier as u8),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.short_number_address.is_none() && self.ssi.is_none() && self.extension.is_none()
+    }
+
+    /// Derives the type identifier from the elements present
+    pub fn type_identifier(&self, party: PartyType) -> Result<u8, PduParseErr> {
+        match (self.short_number_address, self.ssi, self.extension) {
+            (Some(_), None, None) if party.allows_short_number() => Ok(0),
+            (None, Some(_), None) => Ok(1),
+            (None, Some(_), Some(_)) => Ok(2),
+            _ => Err(PduParseErr::Inconsistency { field: party.field(), reason: "no type identifier matches the party address elements present" }),
+        }
+    }
+
+    /// Derives the type identifier from the elements present, or None if there are none
+    pub fn optional_type_identifier(&self, party: PartyType) -> Result<Option<u64>, PduParseErr> {
+        if self.is_empty() {
+            return Ok(None);
+        }
+        self.type_identifier(party).map(|ti| Some(ti as u64))
+    }
+
+    /// Writes the conditional elements following `type_identifier`, after checking they match it
+    pub fn write(&self, buffer: &mut BitBuffer, party: PartyType, type_identifier: u8) -> Result<(), PduParseErr> {
+        if self.type_identifier(party)? != type_identifier {
+            return Err(PduParseErr::Inconsistency { field: party.field(), reason: "type identifier does not match the party address elements present" });
+        }
+        if let Some(sna) = self.short_number_address {
+            buffer.write_bits(sna, 8);
+        }
+        if let Some(ssi) = self.ssi {
+            buffer.write_bits(ssi, 24);
+        }
+        if let Some(extension) = self.extension {
+            buffer.write_bits(extension, 24);
+        }
+        Ok(())
+    }
+
+    /// As `write`, for PDUs where the type identifier is a type2 element. If absent, no elements may be present.
+    pub fn write_optional(&self, buffer: &mut BitBuffer, party: PartyType, type_identifier: Option<u64>) -> Result<(), PduParseErr> {
+        match type_identifier {
+            Some(type_identifier) => self.write(buffer, party, type_identifier as u8),
+            None if self.is_empty() => Ok(()),
+            None => Err(PduParseErr::Inconsistency { field: party.field(), reason: "party address elements present without type identifier" }),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn roundtrip(party: PartyType, type_identifier: u8, bits: &str) -> PartyIdentifier {
+        let mut buffer = BitBuffer::from_bitstr(bits);
+        let parsed = PartyIdentifier::parse(&mut buffer, party, type_identifier).unwrap();
+        assert_eq!(buffer.get_len_remaining(), 0);
+        assert_eq!(parsed.type_identifier(party), Ok(type_identifier));
+
+        let mut out = BitBuffer::new_autoexpand(48);
+        parsed.write(&mut out, party, type_identifier).unwrap();
+        assert_eq!(out.to_bitstr(), bits);
+        parsed
+    }
+
+    #[test]
+    fn test_short_number_address() {
+        let parsed = roundtrip(PartyType::Called, 0, "00001100");
+        assert_eq!(parsed, PartyIdentifier { short_number_address: Some(12), ..Default::default() });
+
+        // A calling party cannot be addressed by short number
+        let mut buffer = BitBuffer::from_bitstr("00001100");
+        assert_eq!(PartyIdentifier::parse(&mut buffer, PartyType::Calling, 0),
+            Err(PduParseErr::InvalidValue { field: "calling_party_type_identifier", value: 0 }));
+    }
+
+    #[test]
+    fn test_ssi() {
+        let parsed = roundtrip(PartyType::Calling, 1, "000111110010011000101000");
+        assert_eq!(parsed, PartyIdentifier { ssi: Some(2041384), ..Default::default() });
+    }
+
+    #[test]
+    fn test_ssi_with_extension() {
+        let parsed = roundtrip(PartyType::Transmitting, 2, "000111110010011000101000001100110000010100111001");
+        assert_eq!(parsed, PartyIdentifier { ssi: Some(2041384), extension: Some((204 << 14) | 1337), ..Default::default() });
+    }
+
+    #[test]
+    fn test_inconsistent_elements_rejected() {
+        let mut out = BitBuffer::new_autoexpand(48);
+        let ssi_only = PartyIdentifier { ssi: Some(1000), ..Default::default() };
+        assert!(ssi_only.write(&mut out, PartyType::Called, 2).is_err());
+        assert!(ssi_only.write_optional(&mut out, PartyType::Called, None).is_err());
+
+        let extension_only = PartyIdentifier { extension: Some(1000), ..Default::default() };
+        assert!(extension_only.type_identifier(PartyType::Other).is_err());
+
+        let mut buffer = BitBuffer::from_bitstr("0000");
+        assert!(PartyIdentifier::parse(&mut buffer, PartyType::Other, 3).is_err());
+    }
+}
@@ -2,4 +2,3 @@ pub mod enums;
 pub mod fields;
 pub mod pdus;
 pub mod structs;
-
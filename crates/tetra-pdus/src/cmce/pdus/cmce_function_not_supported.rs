@@ -15,7 +15,8 @@ use crate::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
 // note 4: Element can have any value from 0 to 255₁₀; if non-zero, shall point to the first bit of the element in the received PDU which indicates the function that cannot be supported by the receiving entity. If zero, shall indicate that the PDU type itself (and hence the entire PDU specified by the "Not-supported PDU type" element) cannot be supported.
 // note 5: Shall be conditional on the value of Function-not-supported pointer: if Function-not-supported pointer is non-zero, this element shall be present; if Function-not-supported pointer is zero, this element shall not be present.
 // note 6: The total length of this element should be not less than the value of Function-not-supported pointer plus enough bits to identify the element in the received PDU which indicates the function that cannot be supported. This element shall not contain the PDU Type element of the received PDU because this is already specified by the "Not-supported PDU type" element (see note 2).
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CmceFunctionNotSupported {
     /// Type1, 5 bits, See note 2,
     pub not_supported_pdu_type: u8,
@@ -88,7 +89,7 @@ impl CmceFunctionNotSupported {
         buffer.write_bits(self.call_identifier_present as u64, 1);
         // Conditional
         if let Some(ref value) = self.call_identifier {
-            buffer.write_bits(*value, 14);
+            buffer.write_field(*value, 14, "call_identifier")?;
         }
         // Type1
         buffer.write_bits(self.function_not_supported_pointer as u64, 8);
@@ -0,0 +1,21 @@
+use core::fmt;
+
+use crate::cmce::pdus::{cmce_pdu_dl::CmcePduDl, cmce_pdu_ul::CmcePduUl};
+
+
+/// A CMCE PDU of either direction
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CmcePdu {
+    Ul(CmcePduUl),
+    Dl(CmcePduDl),
+}
+
+impl fmt::Display for CmcePdu {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CmcePdu::Ul(pdu) => pdu.fmt(f),
+            CmcePdu::Dl(pdu) => pdu.fmt(f),
+        }
+    }
+}
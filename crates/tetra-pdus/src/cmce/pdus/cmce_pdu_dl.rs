@@ -0,0 +1,165 @@
+use core::fmt;
+
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::cmce::enums::cmce_pdu_type_dl::CmcePduTypeDl;
+use crate::cmce::pdus::{
+    cmce_function_not_supported::CmceFunctionNotSupported, d_alert::DAlert, d_call_proceeding::DCallProceeding,
+    d_call_restore::DCallRestore, d_connect::DConnect, d_connect_acknowledge::DConnectAcknowledge,
+    d_disconnect::DDisconnect, d_facility::DFacility, d_info::DInfo, d_release::DRelease, d_sds_data::DSdsData,
+    d_setup::DSetup, d_status::DStatus, d_tx_ceased::DTxCeased, d_tx_continue::DTxContinue,
+    d_tx_granted::DTxGranted, d_tx_interrupt::DTxInterrupt, d_tx_wait::DTxWait,
+};
+use crate::parse_timing::timed_parse;
+
+
+/// Any downlink CMCE PDU, as selected by the 5-bit PDU type (Clause 14.8.28)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CmcePduDl {
+    DAlert(DAlert),
+    DCallProceeding(DCallProceeding),
+    DConnect(DConnect),
+    DConnectAcknowledge(DConnectAcknowledge),
+    DDisconnect(DDisconnect),
+    DInfo(DInfo),
+    DRelease(DRelease),
+    DSetup(DSetup),
+    DStatus(DStatus),
+    DTxCeased(DTxCeased),
+    DTxContinue(DTxContinue),
+    DTxGranted(DTxGranted),
+    DTxWait(DTxWait),
+    DTxInterrupt(DTxInterrupt),
+    DCallRestore(DCallRestore),
+    DSdsData(DSdsData),
+    DFacility(DFacility),
+    CmceFunctionNotSupported(CmceFunctionNotSupported),
+}
+
+impl CmcePduDl {
+    /// Reads the PDU type without consuming it.
+    /// Returns PduParseErr::UnknownPduType with the raw value if the type is not defined,
+    /// in which case the caller should skip this PDU but may continue with any further PDUs.
+    pub fn peek_pdu_type(buffer: &BitBuffer) -> Result<CmcePduTypeDl, PduParseErr> {
+        buffer.peek_pdu_type(5)
+    }
+
+    /// Parses any downlink CMCE PDU from the BitBuffer, dispatching on its PDU type.
+    /// On failure, the buffer is left at the start of the PDU, so the caller can skip it
+    /// or try another parse.
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = Self::peek_pdu_type(buffer)?;
+        let checkpoint = buffer.checkpoint();
+        let result = timed_parse(pdu_type, buffer, |buffer| Self::parse_pdu_type(pdu_type, buffer));
+        if result.is_err() {
+            buffer.restore(checkpoint)?;
+        }
+        result
+    }
+
+    fn parse_pdu_type(pdu_type: CmcePduTypeDl, buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu = match pdu_type {
+            CmcePduTypeDl::DAlert => CmcePduDl::DAlert(DAlert::from_bitbuf(buffer)?),
+            CmcePduTypeDl::DCallProceeding => CmcePduDl::DCallProceeding(DCallProceeding::from_bitbuf(buffer)?),
+            CmcePduTypeDl::DConnect => CmcePduDl::DConnect(DConnect::from_bitbuf(buffer)?),
+            CmcePduTypeDl::DConnectAcknowledge => CmcePduDl::DConnectAcknowledge(DConnectAcknowledge::from_bitbuf(buffer)?),
+            CmcePduTypeDl::DDisconnect => CmcePduDl::DDisconnect(DDisconnect::from_bitbuf(buffer)?),
+            CmcePduTypeDl::DInfo => CmcePduDl::DInfo(DInfo::from_bitbuf(buffer)?),
+            CmcePduTypeDl::DRelease => CmcePduDl::DRelease(DRelease::from_bitbuf(buffer)?),
+            CmcePduTypeDl::DSetup => CmcePduDl::DSetup(DSetup::from_bitbuf(buffer)?),
+            CmcePduTypeDl::DStatus => CmcePduDl::DStatus(DStatus::from_bitbuf(buffer)?),
+            CmcePduTypeDl::DTxCeased => CmcePduDl::DTxCeased(DTxCeased::from_bitbuf(buffer)?),
+            CmcePduTypeDl::DTxContinue => CmcePduDl::DTxContinue(DTxContinue::from_bitbuf(buffer)?),
+            CmcePduTypeDl::DTxGranted => CmcePduDl::DTxGranted(DTxGranted::from_bitbuf(buffer)?),
+            CmcePduTypeDl::DTxWait => CmcePduDl::DTxWait(DTxWait::from_bitbuf(buffer)?),
+            CmcePduTypeDl::DTxInterrupt => CmcePduDl::DTxInterrupt(DTxInterrupt::from_bitbuf(buffer)?),
+            CmcePduTypeDl::DCallRestore => CmcePduDl::DCallRestore(DCallRestore::from_bitbuf(buffer)?),
+            CmcePduTypeDl::DSdsData => CmcePduDl::DSdsData(DSdsData::from_bitbuf(buffer)?),
+            CmcePduTypeDl::DFacility => CmcePduDl::DFacility(DFacility::from_bitbuf(buffer)?),
+            CmcePduTypeDl::CmceFunctionNotSupported => CmcePduDl::CmceFunctionNotSupported(CmceFunctionNotSupported::from_bitbuf(buffer)?),
+        };
+        Ok(pdu)
+    }
+}
+
+impl fmt::Display for CmcePduDl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CmcePduDl::DAlert(pdu) => pdu.fmt(f),
+            CmcePduDl::DCallProceeding(pdu) => pdu.fmt(f),
+            CmcePduDl::DConnect(pdu) => pdu.fmt(f),
+            CmcePduDl::DConnectAcknowledge(pdu) => pdu.fmt(f),
+            CmcePduDl::DDisconnect(pdu) => pdu.fmt(f),
+            CmcePduDl::DInfo(pdu) => pdu.fmt(f),
+            CmcePduDl::DRelease(pdu) => pdu.fmt(f),
+            CmcePduDl::DSetup(pdu) => pdu.fmt(f),
+            CmcePduDl::DStatus(pdu) => pdu.fmt(f),
+            CmcePduDl::DTxCeased(pdu) => pdu.fmt(f),
+            CmcePduDl::DTxContinue(pdu) => pdu.fmt(f),
+            CmcePduDl::DTxGranted(pdu) => pdu.fmt(f),
+            CmcePduDl::DTxWait(pdu) => pdu.fmt(f),
+            CmcePduDl::DTxInterrupt(pdu) => pdu.fmt(f),
+            CmcePduDl::DCallRestore(pdu) => pdu.fmt(f),
+            CmcePduDl::DSdsData(pdu) => pdu.fmt(f),
+            CmcePduDl::DFacility(pdu) => pdu.fmt(f),
+            CmcePduDl::CmceFunctionNotSupported(pdu) => pdu.fmt(f),
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_unknown_pdu_type_is_skipped() {
+        debug::setup_logging_verbose();
+
+        // One MAC block carrying PDU type 17, which is reserved, with some payload, followed
+        // by a D-ALERT. The end of each SDU is known from the MAC header length indication.
+        let d_alert = DAlert { call_identifier: 1234, call_time_out_set_up_phase: 3, reserved: true, ..Default::default() };
+        let mut block = BitBuffer::new_autoexpand(64);
+        block.write_bits(0b10001_010110100, 14);
+        d_alert.to_bitbuf(&mut block).unwrap();
+        let sdu_ends = [14, block.get_raw_pos()];
+
+        block.seek(0);
+        let mut parsed = Vec::new();
+        for sdu_end in sdu_ends {
+            block.set_raw_end(sdu_end);
+            match CmcePduDl::from_bitbuf(&mut block) {
+                Ok(pdu) => parsed.push(pdu),
+                Err(e) => {
+                    assert_eq!(e, PduParseErr::UnknownPduType { found: 17 });
+                    tracing::warn!("skipping pdu: {:?}", e);
+                }
+            }
+            // Continue with the next SDU, skipping whatever is left of this one
+            block.set_raw_pos(sdu_end);
+        }
+        assert_eq!(parsed, vec![CmcePduDl::DAlert(d_alert)]);
+    }
+
+    #[test]
+    fn test_failed_parse_rewinds_to_pdu_start() {
+        let d_alert = DAlert { call_identifier: 1234, call_time_out_set_up_phase: 3, reserved: true, ..Default::default() };
+        let mut buf = BitBuffer::new_autoexpand(64);
+        buf.write_bits(0b101, 3);
+        d_alert.to_bitbuf(&mut buf).unwrap();
+        let end = buf.get_raw_end();
+
+        // Cut the D-ALERT off within its type 1 elements
+        buf.seek(3);
+        buf.set_raw_end(3 + 20);
+        assert!(matches!(CmcePduDl::from_bitbuf(&mut buf), Err(PduParseErr::BufferEnded { .. })));
+        assert_eq!(buf.get_pos(), 3);
+
+        // Parsing again from the same position succeeds once the PDU is complete
+        buf.set_raw_end(end);
+        assert_eq!(CmcePduDl::from_bitbuf(&mut buf), Ok(CmcePduDl::DAlert(d_alert)));
+    }
+}
@@ -0,0 +1,91 @@
+use core::fmt;
+
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+use crate::cmce::enums::cmce_pdu_type_ul::CmcePduTypeUl;
+use crate::cmce::pdus::{
+    cmce_function_not_supported::CmceFunctionNotSupported, u_alert::UAlert, u_call_restore::UCallRestore,
+    u_connect::UConnect, u_disconnect::UDisconnect, u_facility::UFacility, u_info::UInfo, u_release::URelease,
+    u_sds_data::USdsData, u_setup::USetup, u_status::UStatus, u_tx_ceased::UTxCeased, u_tx_demand::UTxDemand,
+};
+use crate::parse_timing::timed_parse;
+
+
+/// Any uplink CMCE PDU, as selected by the 5-bit PDU type (Clause 14.8.28)
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CmcePduUl {
+    UAlert(UAlert),
+    UConnect(UConnect),
+    UDisconnect(UDisconnect),
+    UInfo(UInfo),
+    URelease(URelease),
+    USetup(USetup),
+    UStatus(UStatus),
+    UTxCeased(UTxCeased),
+    UTxDemand(UTxDemand),
+    UCallRestore(UCallRestore),
+    USdsData(USdsData),
+    UFacility(UFacility),
+    CmceFunctionNotSupported(CmceFunctionNotSupported),
+}
+
+impl CmcePduUl {
+    /// Reads the PDU type without consuming it.
+    /// Returns PduParseErr::UnknownPduType with the raw value if the type is not defined.
+    pub fn peek_pdu_type(buffer: &BitBuffer) -> Result<CmcePduTypeUl, PduParseErr> {
+        buffer.peek_pdu_type(5)
+    }
+
+    /// Parses any uplink CMCE PDU from the BitBuffer, dispatching on its PDU type.
+    /// On failure, the buffer is left at the start of the PDU, so the caller can skip it
+    /// or try another parse.
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu_type = Self::peek_pdu_type(buffer)?;
+        let checkpoint = buffer.checkpoint();
+        let result = timed_parse(pdu_type, buffer, |buffer| Self::parse_pdu_type(pdu_type, buffer));
+        if result.is_err() {
+            buffer.restore(checkpoint)?;
+        }
+        result
+    }
+
+    fn parse_pdu_type(pdu_type: CmcePduTypeUl, buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let pdu = match pdu_type {
+            CmcePduTypeUl::UAlert => CmcePduUl::UAlert(UAlert::from_bitbuf(buffer)?),
+            CmcePduTypeUl::UConnect => CmcePduUl::UConnect(UConnect::from_bitbuf(buffer)?),
+            CmcePduTypeUl::UDisconnect => CmcePduUl::UDisconnect(UDisconnect::from_bitbuf(buffer)?),
+            CmcePduTypeUl::UInfo => CmcePduUl::UInfo(UInfo::from_bitbuf(buffer)?),
+            CmcePduTypeUl::URelease => CmcePduUl::URelease(URelease::from_bitbuf(buffer)?),
+            CmcePduTypeUl::USetup => CmcePduUl::USetup(USetup::from_bitbuf(buffer)?),
+            CmcePduTypeUl::UStatus => CmcePduUl::UStatus(UStatus::from_bitbuf(buffer)?),
+            CmcePduTypeUl::UTxCeased => CmcePduUl::UTxCeased(UTxCeased::from_bitbuf(buffer)?),
+            CmcePduTypeUl::UTxDemand => CmcePduUl::UTxDemand(UTxDemand::from_bitbuf(buffer)?),
+            CmcePduTypeUl::UCallRestore => CmcePduUl::UCallRestore(UCallRestore::from_bitbuf(buffer)?),
+            CmcePduTypeUl::USdsData => CmcePduUl::USdsData(USdsData::from_bitbuf(buffer)?),
+            CmcePduTypeUl::UFacility => CmcePduUl::UFacility(UFacility::from_bitbuf(buffer)?),
+            CmcePduTypeUl::CmceFunctionNotSupported => CmcePduUl::CmceFunctionNotSupported(CmceFunctionNotSupported::from_bitbuf(buffer)?),
+        };
+        Ok(pdu)
+    }
+}
+
+impl fmt::Display for CmcePduUl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CmcePduUl::UAlert(pdu) => pdu.fmt(f),
+            CmcePduUl::UConnect(pdu) => pdu.fmt(f),
+            CmcePduUl::UDisconnect(pdu) => pdu.fmt(f),
+            CmcePduUl::UInfo(pdu) => pdu.fmt(f),
+            CmcePduUl::URelease(pdu) => pdu.fmt(f),
+            CmcePduUl::USetup(pdu) => pdu.fmt(f),
+            CmcePduUl::UStatus(pdu) => pdu.fmt(f),
+            CmcePduUl::UTxCeased(pdu) => pdu.fmt(f),
+            CmcePduUl::UTxDemand(pdu) => pdu.fmt(f),
+            CmcePduUl::UCallRestore(pdu) => pdu.fmt(f),
+            CmcePduUl::USdsData(pdu) => pdu.fmt(f),
+            CmcePduUl::UFacility(pdu) => pdu.fmt(f),
+            CmcePduUl::CmceFunctionNotSupported(pdu) => pdu.fmt(f),
+        }
+    }
+}
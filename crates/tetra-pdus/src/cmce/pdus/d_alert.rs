@@ -1,5 +1,6 @@
 use core::fmt;
 
+use tetra_config::ProtocolEdition;
 use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 use tetra_core::typed_pdu_fields::*;
 use crate::cmce::enums::{cmce_pdu_type_dl::CmcePduTypeDl, type3_elem_id::CmceType3ElemId};
@@ -13,7 +14,8 @@ use crate::cmce::fields::basic_service_information::BasicServiceInformation;
 
 // note 1: This information element is not used in this edition of the present document and its value shall be set to "1" (equivalent to "Hook on/Hook off signalling" for backwards compatibility with edition 1 of the present document – refer to Table 14.62).
 // note 2: If different from requested.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DAlert {
     /// Type1, 14 bits, Call identifier
     pub call_identifier: u16,
@@ -35,8 +37,34 @@ pub struct DAlert {
     pub proprietary: Option<Type3FieldGeneric>,
 }
 
+impl Default for DAlert {
+    /// D-ALERT with the reserved bit set to "1" as required by note 1, without any optional elements
+    fn default() -> Self {
+        Self {
+            call_identifier: 0,
+            call_time_out_set_up_phase: 0,
+            reserved: true,
+            simplex_duplex_selection: false,
+            call_queued: false,
+            basic_service_information: None,
+            notification_indicator: None,
+            facility: None,
+            proprietary: None,
+        }
+    }
+}
+
 #[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
 impl DAlert {
+    /// D-ALERT with the reserved bit set as expected by MSs of `edition`. Edition 1 MSs
+    /// read it as the hook method selection, which is direct setup as the BS supports no other.
+    pub fn for_edition(edition: ProtocolEdition) -> Self {
+        Self {
+            reserved: edition != ProtocolEdition::Edition1,
+            ..Default::default()
+        }
+    }
+
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
         
@@ -93,7 +121,7 @@ impl DAlert {
         // PDU Type
         buffer.write_bits(CmcePduTypeDl::DAlert.into_raw(), 5);
         // Type1
-        buffer.write_bits(self.call_identifier as u64, 14);
+        buffer.write_field(self.call_identifier as u64, 14, "call_identifier")?;
         // Type1
         buffer.write_bits(self.call_time_out_set_up_phase as u64, 3);
         // Type1
@@ -143,3 +171,114 @@ impl fmt::Display for DAlert {
         )
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+
+    use tetra_core::debug;
+    use tetra_saps::control::enums::{circuit_mode_type::CircuitModeType, communication_type::CommunicationType};
+
+    use super::*;
+
+    fn build_d_alert() -> DAlert {
+        DAlert {
+            call_identifier: 1234,
+            call_time_out_set_up_phase: 3,
+            reserved: true,
+            simplex_duplex_selection: false,
+            call_queued: false,
+            basic_service_information: Some(BasicServiceInformation {
+                circuit_mode_type: CircuitModeType::TchS,
+                encryption_flag: false,
+                communication_type: CommunicationType::P2Mp,
+                slots_per_frame: None,
+                speech_service: Some(0),
+            }),
+            notification_indicator: None,
+            facility: None,
+            proprietary: None,
+        }
+    }
+
+    #[test]
+    fn test_d_alert_reserved_by_edition() {
+        debug::setup_logging_verbose();
+
+        let ed1 = DAlert { call_identifier: 1234, ..DAlert::for_edition(ProtocolEdition::Edition1) };
+        let ed2 = DAlert { call_identifier: 1234, ..DAlert::for_edition(ProtocolEdition::Edition2) };
+        assert!(!ed1.reserved);
+        assert!(ed2.reserved);
+        assert_eq!(DAlert::default(), DAlert::for_edition(ProtocolEdition::Edition2));
+
+        // Only the reserved bit, following the call identifier and call time-out, differs
+        let mut buf1 = BitBuffer::new_autoexpand(32);
+        let mut buf2 = BitBuffer::new_autoexpand(32);
+        ed1.to_bitbuf(&mut buf1).unwrap();
+        ed2.to_bitbuf(&mut buf2).unwrap();
+        let (bits1, bits2) = (buf1.dump_bin_unformatted(), buf2.dump_bin_unformatted());
+        assert_eq!(bits1.len(), bits2.len());
+        let diff: Vec<usize> = bits1.chars().zip(bits2.chars())
+            .enumerate().filter(|(_, (a, b))| a != b).map(|(i, _)| i).collect();
+        assert_eq!(diff, vec![5 + 14 + 3]);
+    }
+
+    #[test]
+    fn test_d_alert_eq() {
+        let a = build_d_alert();
+        let b = build_d_alert();
+        assert_eq!(a, b);
+        assert_eq!(a.clone(), b);
+
+        let c = DAlert { call_queued: true, ..build_d_alert() };
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_d_alert_from_json() {
+        debug::setup_logging_verbose();
+
+        let pdu = DAlert {
+            call_identifier: 1234,
+            call_time_out_set_up_phase: 3,
+            reserved: true,
+            simplex_duplex_selection: false,
+            call_queued: false,
+            basic_service_information: Some(BasicServiceInformation {
+                circuit_mode_type: CircuitModeType::TchS,
+                encryption_flag: false,
+                communication_type: CommunicationType::P2Mp,
+                slots_per_frame: None,
+                speech_service: Some(0),
+            }),
+            notification_indicator: None,
+            facility: None,
+            proprietary: None,
+        };
+        let mut expected = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut expected).unwrap();
+
+        // Absent optional elements may simply be left out of the JSON
+        let json = r#"{
+            "call_identifier": 1234,
+            "call_time_out_set_up_phase": 3,
+            "reserved": true,
+            "simplex_duplex_selection": false,
+            "call_queued": false,
+            "basic_service_information": {
+                "circuit_mode_type": "TchS",
+                "encryption_flag": false,
+                "communication_type": "P2Mp",
+                "speech_service": 0
+            }
+        }"#;
+        let mut buffer = crate::json::json_to_bitbuf(json, DAlert::to_bitbuf).unwrap();
+        assert_eq!(buffer.to_bitstr(), expected.to_bitstr());
+
+        // And parse the bits back to check we got a valid D-ALERT
+        let parsed = DAlert::from_bitbuf(&mut buffer).unwrap();
+        assert_eq!(parsed.call_identifier, 1234);
+        assert_eq!(parsed.basic_service_information.unwrap().communication_type, CommunicationType::P2Mp);
+    }
+}
@@ -14,7 +14,8 @@ use crate::cmce::fields::basic_service_information::BasicServiceInformation;
 /// Response to: U-SETUP
 
 // note 1: If different from requested.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DCallProceeding {
     /// Type1, 14 bits, Call identifier
     pub call_identifier: u16,
@@ -103,7 +104,7 @@ impl DCallProceeding {
         // PDU Type
         buffer.write_bits(CmcePduTypeDl::DCallProceeding.into_raw(), 5);
         // Type1
-        buffer.write_bits(self.call_identifier as u64, 14);
+        buffer.write_field(self.call_identifier as u64, 14, "call_identifier")?;
         // Type1
         buffer.write_bits(self.call_time_out_set_up_phase as u64, 3);
         // Type1
@@ -13,7 +13,8 @@ use crate::cmce::fields::basic_service_information::BasicServiceInformation;
 /// Response to: U-SETUP
 
 // note 1: Basic service information element: If different from requested.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DConnect {
     /// Type1, 14 bits, Call identifier
     pub call_identifier: u16,
@@ -117,7 +118,7 @@ impl DConnect {
         // PDU Type
         buffer.write_bits(CmcePduTypeDl::DConnect.into_raw(), 5);
         // Type1
-        buffer.write_bits(self.call_identifier as u64, 14);
+        buffer.write_field(self.call_identifier as u64, 14, "call_identifier")?;
         // Type1
         buffer.write_bits(self.call_time_out as u64, 4);
         // Type1
@@ -206,4 +207,15 @@ mod tests {
         assert!(d_connect.facility.is_none());
         assert!(d_connect.proprietary.is_none());
     }
+
+    #[test]
+    fn test_d_connect_call_identifier_too_wide() {
+        let mut buffer = BitBuffer::from_bitstr("000100000000000010001110000000");
+        let mut d_connect = DConnect::from_bitbuf(&mut buffer).unwrap();
+        d_connect.call_identifier = 1 << 14;
+
+        let mut buffer = BitBuffer::new_autoexpand(30);
+        let result = d_connect.to_bitbuf(&mut buffer);
+        assert_eq!(result, Err(PduParseErr::ValueTooWide { field: "call_identifier", value: 1 << 14, bits: 14 }));
+    }
 }
\ No newline at end of file
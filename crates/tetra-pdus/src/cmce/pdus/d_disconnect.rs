@@ -10,7 +10,8 @@ use crate::cmce::enums::{cmce_pdu_type_dl::CmcePduTypeDl, type3_elem_id::CmceTyp
 /// Response expected: U-RELEASE
 /// Response to: -
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DDisconnect {
     /// Type1, 14 bits, Call identifier
     pub call_identifier: u16,
@@ -70,7 +71,7 @@ impl DDisconnect {
         // PDU Type
         buffer.write_bits(CmcePduTypeDl::DDisconnect.into_raw(), 5);
         // Type1
-        buffer.write_bits(self.call_identifier as u64, 14);
+        buffer.write_field(self.call_identifier as u64, 14, "call_identifier")?;
         // Type1
         buffer.write_bits(self.disconnect_cause as u64, 5);
 
@@ -11,7 +11,8 @@ use crate::cmce::enums::{cmce_pdu_type_dl::CmcePduTypeDl};
 /// Response to: -
 
 // note 1: Contents of this PDU shall be defined by SS protocols.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DFacility {
 }
 
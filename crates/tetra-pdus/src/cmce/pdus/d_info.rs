@@ -13,7 +13,8 @@ use crate::cmce::enums::{cmce_pdu_type_dl::CmcePduTypeDl, type3_elem_id::CmceTyp
 // note 1: If the message is sent connectionless the call identifier shall be the dummy call identifier.
 // note 2: Shall be valid for acknowledged group call only. For other types of calls it shall be set = 0.
 // note 3: Shall be valid for acknowledged group call only.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DInfo {
     /// Type1, 14 bits, See note 1,
     pub call_identifier: u16,
@@ -136,7 +137,7 @@ impl DInfo {
         // PDU Type
         buffer.write_bits(CmcePduTypeDl::DInfo.into_raw(), 5);
         // Type1
-        buffer.write_bits(self.call_identifier as u64, 14);
+        buffer.write_field(self.call_identifier as u64, 14, "call_identifier")?;
         // Type1
         buffer.write_bits(self.reset_call_time_out_timer_t310_ as u64, 1);
         // Type1
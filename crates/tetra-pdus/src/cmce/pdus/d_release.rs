@@ -10,7 +10,8 @@ use crate::cmce::enums::{cmce_pdu_type_dl::CmcePduTypeDl, type3_elem_id::CmceTyp
 /// Response expected: -
 /// Response to: -/U-DISCONNECT
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DRelease {
     /// Type1, 14 bits, Call identifier
     pub call_identifier: u16,
@@ -71,7 +72,7 @@ impl DRelease {
         // PDU Type
         buffer.write_bits(CmcePduTypeDl::DRelease.into_raw(), 5);
         // Type1
-        buffer.write_bits(self.call_identifier as u64, 14);
+        buffer.write_field(self.call_identifier as u64, 14, "call_identifier")?;
         // Type1
         buffer.write_bits(self.disconnect_cause as u64, 5);
 
@@ -132,4 +133,22 @@ mod tests {
         assert_eq!(bitstr, buffer_out.to_bitstr());
         assert!(buffer.get_len_remaining() == 0);
     }
+
+    #[test]
+    fn test_d_release_default() {
+        debug::setup_logging_verbose();
+        let pdu = DRelease {
+            call_identifier: 217,
+            disconnect_cause: 13,
+            ..Default::default()
+        };
+        assert!(pdu.notification_indicator.is_none());
+        assert!(pdu.facility.is_none());
+        assert!(pdu.proprietary.is_none());
+
+        // Without optional elements, this is identical to the parse test vector
+        let mut buffer_out = BitBuffer::new_autoexpand(30);
+        pdu.to_bitbuf(&mut buffer_out).unwrap();
+        assert_eq!(buffer_out.to_bitstr(), "0011000000011011001011010");
+    }
 }
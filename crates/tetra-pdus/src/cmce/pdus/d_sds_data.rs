@@ -1,8 +1,10 @@
 use core::fmt;
 
-use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
+use tetra_core::{BitBuffer, TetraAddress, expect_pdu_type, pdu_parse_error::PduParseErr};
 use tetra_core::typed_pdu_fields::*;
+use tetra_core::pdu_log::AddressedPdu;
 use crate::cmce::enums::{cmce_pdu_type_dl::CmcePduTypeDl, type3_elem_id::CmceType3ElemId};
+use crate::cmce::fields::party_identifier::{PartyIdentifier, PartyType};
 
 
 /// Representation of the D-SDS-DATA PDU (Clause 14.7.1.10).
@@ -12,13 +14,14 @@ use crate::cmce::enums::{cmce_pdu_type_dl::CmcePduTypeDl, type3_elem_id::CmceTyp
 
 // note 1: Shall be conditional on the value of Calling Party Type Identifier (CPTI): CPTI = 1: Calling Party SSI; CPTI = 2: Calling Party SSI + Calling Party Extension.
 // note 2: Shall be conditional on the value of Short Data Type Identifier (SDTI): SDTI = 0: User Defined Data-1; SDTI = 1: User Defined Data-2; SDTI = 2: User Defined Data-3; SDTI = 3: Length Indicator + User Defined Data-4.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DSdsData {
     /// Type1, 2 bits, Calling party type identifier
     pub calling_party_type_identifier: u8,
     /// Conditional 24 bits, See note 1, condition: calling_party_type_identifier == 1 || calling_party_type_identifier == 2
     pub calling_party_address_ssi: Option<u64>,
-    /// Conditional 24 bits, See note 1, condition: calling_party_type_identifier == 1
+    /// Conditional 24 bits, See note 1, condition: calling_party_type_identifier == 2
     pub calling_party_extension: Option<u64>,
     /// Type1, 2 bits, Short data type identifier
     pub short_data_type_identifier: u8,
@@ -31,14 +34,14 @@ pub struct DSdsData {
     /// Conditional 11 bits, See note 2, condition: short_data_type_identifier == 3
     pub length_indicator: Option<u64>,
     /// Conditional See note 2, condition: short_data_type_identifier == 3
-    pub user_defined_data_4: Option<u64>,
+    /// Packed MSB-first, length_indicator bits long; the last byte is left-aligned
+    pub user_defined_data_4: Option<Vec<u8>>,
     /// Type3, External subscriber number
     pub external_subscriber_number: Option<Type3FieldGeneric>,
     /// Type3, DM-MS address
     pub dm_ms_address: Option<Type3FieldGeneric>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
 impl DSdsData {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
@@ -49,13 +52,9 @@ impl DSdsData {
         // Type1
         let calling_party_type_identifier = buffer.read_field(2, "calling_party_type_identifier")? as u8;
         // Conditional
-        let calling_party_address_ssi = if calling_party_type_identifier == 1 || calling_party_type_identifier == 2 { 
-            Some(buffer.read_field(24, "calling_party_address_ssi")?) 
-        } else { None };
-        // Conditional
-        let calling_party_extension = if calling_party_type_identifier == 1 { 
-            Some(buffer.read_field(24, "calling_party_extension")?) 
-        } else { None };
+        let calling_party = PartyIdentifier::parse(buffer, PartyType::Calling, calling_party_type_identifier)?;
+        let calling_party_address_ssi = calling_party.ssi;
+        let calling_party_extension = calling_party.extension;
         // Type1
         let short_data_type_identifier = buffer.read_field(2, "short_data_type_identifier")? as u8;
         // Conditional
@@ -75,9 +74,16 @@ impl DSdsData {
             Some(buffer.read_field(11, "length_indicator")?) 
         } else { None };
         // Conditional
-        let user_defined_data_4 = if short_data_type_identifier == 3 { 
-            unimplemented!();
-            Some(buffer.read_field(999, "user_defined_data_4")?) 
+        let user_defined_data_4 = if let Some(len) = length_indicator { 
+            let mut data = Vec::with_capacity((len as usize).div_ceil(8));
+            let mut remaining = len as usize;
+            while remaining > 0 {
+                let num_bits = remaining.min(8);
+                let byte = buffer.read_field(num_bits, "user_defined_data_4")? as u8;
+                data.push(byte << (8 - num_bits));
+                remaining -= num_bits;
+            }
+            Some(data)
         } else { None };
 
         // obit designates presence of any further type2, type3 or type4 fields
@@ -117,13 +123,12 @@ impl DSdsData {
         // Type1
         buffer.write_bits(self.calling_party_type_identifier as u64, 2);
         // Conditional
-        if let Some(ref value) = self.calling_party_address_ssi {
-            buffer.write_bits(*value, 24);
-        }
-        // Conditional
-        if let Some(ref value) = self.calling_party_extension {
-            buffer.write_bits(*value, 24);
-        }
+        let calling_party = PartyIdentifier {
+            short_number_address: None,
+            ssi: self.calling_party_address_ssi,
+            extension: self.calling_party_extension,
+        };
+        calling_party.write(buffer, PartyType::Calling, self.calling_party_type_identifier)?;
         // Type1
         buffer.write_bits(self.short_data_type_identifier as u64, 2);
         // Conditional
@@ -143,9 +148,17 @@ impl DSdsData {
             buffer.write_bits(*value, 11);
         }
         // Conditional
-        if let Some(ref _value) = self.user_defined_data_4 {
-            unimplemented!();
-            buffer.write_bits(*_value, 999);
+        if let Some(ref value) = self.user_defined_data_4 {
+            let len = self.length_indicator.unwrap_or(0) as usize;
+            if value.len() != len.div_ceil(8) {
+                return Err(PduParseErr::InconsistentLength { expected: len.div_ceil(8), found: value.len() });
+            }
+            let mut remaining = len;
+            for byte in value {
+                let num_bits = remaining.min(8);
+                buffer.write_bits((*byte >> (8 - num_bits)) as u64, num_bits);
+                remaining -= num_bits;
+            }
         }
 
         // Check if any optional field present and place o-bit
@@ -165,6 +178,12 @@ impl DSdsData {
     }
 }
 
+impl AddressedPdu for DSdsData {
+    fn calling_ssi(&self) -> Option<TetraAddress> {
+        self.calling_party_address_ssi.map(|ssi| TetraAddress::issi(ssi as u32))
+    }
+}
+
 impl fmt::Display for DSdsData {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "DSdsData {{ calling_party_type_identifier: {:?} calling_party_address_ssi: {:?} calling_party_extension: {:?} short_data_type_identifier: {:?} user_defined_data_1: {:?} user_defined_data_2: {:?} user_defined_data_3: {:?} length_indicator: {:?} user_defined_data_4: {:?} external_subscriber_number: {:?} dm_ms_address: {:?} }}",
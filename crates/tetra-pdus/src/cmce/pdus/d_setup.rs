@@ -1,11 +1,13 @@
 use core::fmt;
 
-use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
+use tetra_core::{BitBuffer, TetraAddress, expect_pdu_type, pdu_parse_error::PduParseErr};
 use tetra_core::typed_pdu_fields::*;
+use tetra_core::pdu_log::AddressedPdu;
 use crate::cmce::enums::call_timeout::CallTimeout;
 use crate::cmce::enums::transmission_grant::TransmissionGrant;
 use crate::cmce::enums::{cmce_pdu_type_dl::CmcePduTypeDl, type3_elem_id::CmceType3ElemId};
 use crate::cmce::fields::basic_service_information::BasicServiceInformation;
+use crate::cmce::fields::party_identifier::{PartyIdentifier, PartyType};
 
 
 /// Representation of the D-SETUP PDU (Clause 14.7.1.12).
@@ -16,7 +18,8 @@ use crate::cmce::fields::basic_service_information::BasicServiceInformation;
 // note 1: This information element is used by SS-PC, refer to ETSI EN 300 392-12-10 [15] and SS-PPC and ETSI EN 300 392-12-16 [16].
 // note 2: For resolution of possible Facility (Talking Party Identifier)/Calling party identifier conflicts, refer to ETSI EN 300 392-12-3 [12], clause 5.2.1.5 and ETSI EN 300 392-12-1 [11], clause 4.3.5.
 // note 3: Shall be conditional on the value of Calling Party Type Identifier (CPTI): • CPTI = 1 ⇒ Calling Party SSI; • CPTI = 2 ⇒ Calling Party SSI + Calling Party Extension.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DSetup {
     /// Type1, 14 bits, Call identifier
     pub call_identifier: u16,
@@ -93,13 +96,9 @@ impl DSetup {
         // Type2
         let calling_party_type_identifier = typed::parse_type2_generic(obit, buffer, 2, "calling_party_type_identifier")?;
         // Conditional
-        let calling_party_address_ssi = if obit && calling_party_type_identifier == Some(1) || calling_party_type_identifier == Some(2) { 
-            Some(buffer.read_field(24, "calling_party_address_ssi")? as u32) 
-        } else { None };
-        // Conditional
-        let calling_party_extension = if obit && calling_party_type_identifier == Some(2) { 
-            Some(buffer.read_field(24, "calling_party_extension")? as u32) 
-        } else { None };
+        let calling_party = PartyIdentifier::parse_optional(buffer, PartyType::Calling, calling_party_type_identifier)?;
+        let calling_party_address_ssi = calling_party.ssi.map(|ssi| ssi as u32);
+        let calling_party_extension = calling_party.extension.map(|ext| ext as u32);
 
         // Type3
         let external_subscriber_number = typed::parse_type3_generic(obit, buffer, CmceType3ElemId::ExtSubscriberNum)?;
@@ -146,7 +145,7 @@ impl DSetup {
         // PDU Type
         buffer.write_bits(CmcePduTypeDl::DSetup.into_raw(), 5);
         // Type1
-        buffer.write_bits(self.call_identifier as u64, 14);
+        buffer.write_field(self.call_identifier as u64, 14, "call_identifier")?;
         // Type1
         buffer.write_bits(self.call_time_out as u64, 4);
         // Type1
@@ -181,23 +180,17 @@ impl DSetup {
         // Type2
         typed::write_type2_generic(obit, buffer, self.temporary_address, 24);
 
-        // Type2
-        let calling_party_type_identifier = match (self.calling_party_address_ssi, self.calling_party_extension) {
-            (None, None) => None,
-            (Some(_), None) => Some(1),
-            (Some(_), Some(_)) => Some(2),
-            _ => return Err(PduParseErr::InvalidValue { field: "calling_party_type_identifier", value: 0 }),
+        // Type2, derived from the calling party address elements present
+        let calling_party = PartyIdentifier {
+            short_number_address: None,
+            ssi: self.calling_party_address_ssi.map(|ssi| ssi as u64),
+            extension: self.calling_party_extension.map(|ext| ext as u64),
         };
+        let calling_party_type_identifier = calling_party.optional_type_identifier(PartyType::Calling)?;
         typed::write_type2_generic(obit, buffer, calling_party_type_identifier, 2);
 
         // Conditional
-        if let Some(ref value) = self.calling_party_address_ssi {
-            buffer.write_bits(*value as u64, 24);
-        }
-        // Conditional
-        if let Some(ref value) = self.calling_party_extension {
-            buffer.write_bits(*value as u64, 24);
-        }
+        calling_party.write_optional(buffer, PartyType::Calling, calling_party_type_identifier)?;
         // Type3
         typed::write_type3_generic(obit, buffer, &self.external_subscriber_number, CmceType3ElemId::ExtSubscriberNum)?;
         
@@ -215,6 +208,12 @@ impl DSetup {
     }
 }
 
+impl AddressedPdu for DSetup {
+    fn calling_ssi(&self) -> Option<TetraAddress> {
+        self.calling_party_address_ssi.map(TetraAddress::issi)
+    }
+}
+
 impl fmt::Display for DSetup {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "DSetup {{ call_identifier: {:?} call_time_out: {:?} hook_method_selection: {:?} simplex_duplex_selection: {:?} basic_service_information: {:?} transmission_grant: {:?} transmission_request_permission: {:?} call_priority: {:?} notification_indicator: {:?} temporary_address: {:?} calling_party_address_ssi: {:?} calling_party_extension: {:?} external_subscriber_number: {:?} facility: {:?} dm_ms_address: {:?} proprietary: {:?} }}",
@@ -242,7 +241,7 @@ impl fmt::Display for DSetup {
 #[cfg(test)]
 mod tests {
 
-    use tetra_core::debug;
+    use tetra_core::{SsiType, debug};
     use tetra_saps::control::enums::{circuit_mode_type::CircuitModeType, communication_type::CommunicationType};
 
     use super::*;
@@ -316,6 +315,52 @@ mod tests {
         let mut new = BitBuffer::new_autoexpand(71);
         pdu.to_bitbuf(&mut new).unwrap();
         assert_eq!(new.to_bitstr(), buffer.to_bitstr());
-    }    
-}
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_d_setup_json() {
+        use crate::json::{PduFromJson, PduToJson};
+
+        debug::setup_logging_verbose();
+        let mut buffer = BitBuffer::from_bitstr("00111000000110000110000000000010011000001001010001111100100110001010000");
+        let pdu = DSetup::from_bitbuf(&mut buffer).unwrap();
+
+        let json = pdu.to_json();
+        let v: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(v["call_identifier"], 195);
+        assert_eq!(v["call_time_out"], "Infinite");
+        assert_eq!(v["basic_service_information"]["circuit_mode_type"], "TchS");
+        assert_eq!(v["basic_service_information"]["communication_type"], "P2Mp");
+        assert_eq!(v["basic_service_information"]["slots_per_frame"], serde_json::Value::Null);
+        assert_eq!(v["transmission_grant"], "GrantedToOtherUser");
+        assert_eq!(v["calling_party_address_ssi"], 2041384);
+        assert_eq!(v["temporary_address"], serde_json::Value::Null);
+        assert_eq!(v["proprietary"], serde_json::Value::Null);
+
+        // The JSON holds everything needed to rebuild the PDU
+        assert_eq!(DSetup::from_json(&json).unwrap(), pdu);
+    }
+
+    #[test]
+    fn test_d_setup_calling_ssi_with_extension() {
+        debug::setup_logging_verbose();
+        let mut buffer = BitBuffer::from_bitstr("00111000000110000110000000000010011000001001010001111100100110001010000");
+        let mut pdu = DSetup::from_bitbuf(&mut buffer).unwrap();
 
+        // Calling party type identifier 2: SSI followed by the extension holding MCC 204, MNC 1337
+        let extension = (204 << 14) | 1337;
+        pdu.calling_party_extension = Some(extension);
+        let mut buffer = BitBuffer::new_autoexpand(95);
+        pdu.to_bitbuf(&mut buffer).unwrap();
+        buffer.seek(0);
+        let pdu = DSetup::from_bitbuf(&mut buffer).unwrap();
+        assert_eq!(pdu.calling_party_extension, Some(extension));
+
+        let calling = pdu.calling_ssi().unwrap();
+        assert_eq!(calling.ssi, 2041384);
+        assert_eq!(calling.ssi_type, SsiType::Issi);
+        assert!(pdu.called_ssi().is_none());
+    }
+}
@@ -1,8 +1,10 @@
 use core::fmt;
 
-use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
+use tetra_core::{BitBuffer, TetraAddress, expect_pdu_type, pdu_parse_error::PduParseErr};
 use tetra_core::typed_pdu_fields::*;
+use tetra_core::pdu_log::AddressedPdu;
 use crate::cmce::enums::{cmce_pdu_type_dl::CmcePduTypeDl, type3_elem_id::CmceType3ElemId};
+use crate::cmce::fields::party_identifier::{PartyIdentifier, PartyType};
 
 
 /// Representation of the D-STATUS PDU (Clause 14.7.1.11).
@@ -11,7 +13,8 @@ use crate::cmce::enums::{cmce_pdu_type_dl::CmcePduTypeDl, type3_elem_id::CmceTyp
 /// Response to: None
 
 // Note 1: Shall be conditional on the value of Calling Party Type Identifier (CPTI): CPTI = 1 → include Calling Party SSI only; CPTI = 2 → include both SSI and Calling Party Extension.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DStatus {
     /// Type1, 2 bits, Calling party type identifier
     pub calling_party_type_identifier: u8,
@@ -38,13 +41,9 @@ impl DStatus {
         // Type1
         let calling_party_type_identifier = buffer.read_field(2, "calling_party_type_identifier")? as u8;
         // Conditional
-        let calling_party_address_ssi = if calling_party_type_identifier == 1 || calling_party_type_identifier == 2 { 
-            Some(buffer.read_field(24, "calling_party_address_ssi")?) 
-        } else { None };
-        // Conditional
-        let calling_party_extension = if calling_party_type_identifier == 2 { 
-            Some(buffer.read_field(24, "calling_party_extension")?) 
-        } else { None };
+        let calling_party = PartyIdentifier::parse(buffer, PartyType::Calling, calling_party_type_identifier)?;
+        let calling_party_address_ssi = calling_party.ssi;
+        let calling_party_extension = calling_party.extension;
         // Type1
         let pre_coded_status = buffer.read_field(16, "pre_coded_status")? as u16;
 
@@ -82,13 +81,12 @@ impl DStatus {
         // Type1
         buffer.write_bits(self.calling_party_type_identifier as u64, 2);
         // Conditional
-        if let Some(ref value) = self.calling_party_address_ssi {
-            buffer.write_bits(*value, 24);
-        }
-        // Conditional
-        if let Some(ref value) = self.calling_party_extension {
-            buffer.write_bits(*value, 24);
-        }
+        let calling_party = PartyIdentifier {
+            short_number_address: None,
+            ssi: self.calling_party_address_ssi,
+            extension: self.calling_party_extension,
+        };
+        calling_party.write(buffer, PartyType::Calling, self.calling_party_type_identifier)?;
         // Type1
         buffer.write_bits(self.pre_coded_status as u64, 16);
 
@@ -109,6 +107,12 @@ impl DStatus {
     }
 }
 
+impl AddressedPdu for DStatus {
+    fn calling_ssi(&self) -> Option<TetraAddress> {
+        self.calling_party_address_ssi.map(|ssi| TetraAddress::issi(ssi as u32))
+    }
+}
+
 impl fmt::Display for DStatus {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "DStatus {{ calling_party_type_identifier: {:?} calling_party_address_ssi: {:?} calling_party_extension: {:?} pre_coded_status: {:?} external_subscriber_number: {:?} dm_ms_address: {:?} }}",
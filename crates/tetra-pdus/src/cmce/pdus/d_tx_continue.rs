@@ -10,7 +10,8 @@ use crate::cmce::enums::{cmce_pdu_type_dl::CmcePduTypeDl, type3_elem_id::CmceTyp
 /// Response expected: -
 /// Response to: -
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DTxContinue {
     /// Type1, 14 bits, Call identifier
     pub call_identifier: u16,
@@ -83,7 +84,7 @@ impl DTxContinue {
         // PDU Type
         buffer.write_bits(CmcePduTypeDl::DTxContinue.into_raw(), 5);
         // Type1
-        buffer.write_bits(self.call_identifier as u64, 14);
+        buffer.write_field(self.call_identifier as u64, 14, "call_identifier")?;
         // Type1
         buffer.write_bits(self.do_continue as u64, 1);
         // Type1
@@ -1,8 +1,11 @@
 use core::fmt;
 
-use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
+use tetra_config::ProtocolEdition;
+use tetra_core::{BitBuffer, TetraAddress, expect_pdu_type, pdu_parse_error::PduParseErr};
 use tetra_core::typed_pdu_fields::*;
+use tetra_core::pdu_log::AddressedPdu;
 use crate::cmce::enums::{cmce_pdu_type_dl::CmcePduTypeDl, type3_elem_id::CmceType3ElemId};
+use crate::cmce::fields::party_identifier::{PartyIdentifier, PartyType};
 
 
 /// Representation of the D-TX GRANTED PDU (Clause 14.7.1.15).
@@ -12,7 +15,8 @@ use crate::cmce::enums::{cmce_pdu_type_dl::CmcePduTypeDl, type3_elem_id::CmceTyp
 
 // note 1: This information element is not used in this version of the present document and its value shall be set to "0."
 // note 2: Shall be conditional on the value of Transmitting Party Type Identifier (TPTI): TPTI = 1 ⇒ Transmitting Party SSI; TPTI = 2 ⇒ Transmitting Party SSI + Transmitting Party Extension.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DTxGranted {
     /// Type1, 14 bits, Call identifier
     pub call_identifier: u16,
@@ -45,6 +49,15 @@ pub struct DTxGranted {
 
 #[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
 impl DTxGranted {
+    /// D-TX GRANTED with the reserved bit set as expected by MSs of `edition`. Unlike in
+    /// D-ALERT, note 1 gives it no meaning to keep for edition 1, so it is 0 for both.
+    pub fn for_edition(_edition: ProtocolEdition) -> Self {
+        Self {
+            reserved: false,
+            ..Default::default()
+        }
+    }
+
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
         
@@ -70,13 +83,9 @@ impl DTxGranted {
         // Type2
         let transmitting_party_type_identifier = typed::parse_type2_generic(obit, buffer, 2, "transmitting_party_type_identifier")?;
         // Conditional
-        let transmitting_party_address_ssi = if obit && transmitting_party_type_identifier == Some(1) || transmitting_party_type_identifier == Some(2) { 
-            Some(buffer.read_field(24, "transmitting_party_address_ssi")?) 
-        } else { None };
-        // Conditional
-        let transmitting_party_extension = if obit && transmitting_party_type_identifier == Some(2) { 
-            Some(buffer.read_field(24, "transmitting_party_extension")?) 
-        } else { None };
+        let transmitting_party = PartyIdentifier::parse_optional(buffer, PartyType::Transmitting, transmitting_party_type_identifier)?;
+        let transmitting_party_address_ssi = transmitting_party.ssi;
+        let transmitting_party_extension = transmitting_party.extension;
 
 
         // Type3
@@ -120,7 +129,7 @@ impl DTxGranted {
         // PDU Type
         buffer.write_bits(CmcePduTypeDl::DTxGranted.into_raw(), 5);
         // Type1
-        buffer.write_bits(self.call_identifier as u64, 14);
+        buffer.write_field(self.call_identifier as u64, 14, "call_identifier")?;
         // Type1
         buffer.write_bits(self.transmission_grant as u64, 2);
         // Type1
@@ -142,13 +151,12 @@ impl DTxGranted {
         typed::write_type2_generic(obit, buffer, self.transmitting_party_type_identifier, 2);
 
         // Conditional
-        if let Some(ref value) = self.transmitting_party_address_ssi {
-            buffer.write_bits(*value, 24);
-        }
-        // Conditional
-        if let Some(ref value) = self.transmitting_party_extension {
-            buffer.write_bits(*value, 24);
-        }
+        let transmitting_party = PartyIdentifier {
+            short_number_address: None,
+            ssi: self.transmitting_party_address_ssi,
+            extension: self.transmitting_party_extension,
+        };
+        transmitting_party.write_optional(buffer, PartyType::Transmitting, self.transmitting_party_type_identifier)?;
         // Type3
         typed::write_type3_generic(obit, buffer, &self.external_subscriber_number, CmceType3ElemId::ExtSubscriberNum)?;
         
@@ -168,6 +176,13 @@ impl DTxGranted {
     }
 }
 
+impl AddressedPdu for DTxGranted {
+    /// The party granted permission to transmit
+    fn calling_ssi(&self) -> Option<TetraAddress> {
+        self.transmitting_party_address_ssi.map(|ssi| TetraAddress::issi(ssi as u32))
+    }
+}
+
 impl fmt::Display for DTxGranted {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "DTxGranted {{ call_identifier: {:?} transmission_grant: {:?} transmission_request_permission: {:?} encryption_control: {:?} reserved: {:?} notification_indicator: {:?} transmitting_party_type_identifier: {:?} transmitting_party_address_ssi: {:?} transmitting_party_extension: {:?} external_subscriber_number: {:?} facility: {:?} dm_ms_address: {:?} proprietary: {:?} }}",
@@ -187,3 +202,24 @@ impl fmt::Display for DTxGranted {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_d_tx_granted_reserved_by_edition() {
+        let encode = |edition| {
+            let pdu = DTxGranted { call_identifier: 1234, transmission_grant: 3, ..DTxGranted::for_edition(edition) };
+            let mut buf = BitBuffer::new_autoexpand(32);
+            pdu.to_bitbuf(&mut buf).unwrap();
+            buf.dump_bin_unformatted()
+        };
+        let (bits1, bits2) = (encode(ProtocolEdition::Edition1), encode(ProtocolEdition::Edition2));
+
+        // The reserved bit follows the call identifier, transmission grant, transmission
+        // request permission and encryption control, and is 0 in both editions
+        assert_eq!(bits1, bits2);
+        assert_eq!(&bits1[5 + 14 + 2 + 1 + 1..][..1], "0");
+    }
+}
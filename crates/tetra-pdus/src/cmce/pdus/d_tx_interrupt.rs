@@ -1,8 +1,10 @@
 use core::fmt;
 
-use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
+use tetra_core::{BitBuffer, TetraAddress, expect_pdu_type, pdu_parse_error::PduParseErr};
 use tetra_core::typed_pdu_fields::*;
+use tetra_core::pdu_log::AddressedPdu;
 use crate::cmce::enums::{cmce_pdu_type_dl::CmcePduTypeDl, type3_elem_id::CmceType3ElemId};
+use crate::cmce::fields::party_identifier::{PartyIdentifier, PartyType};
 
 
 /// Representation of the D-TX INTERRUPT PDU (Clause 14.7.1.16).
@@ -12,7 +14,8 @@ use crate::cmce::enums::{cmce_pdu_type_dl::CmcePduTypeDl, type3_elem_id::CmceTyp
 
 // note 1: This information element is not used in this version of the present document and its value shall be set to "0".
 // note 2: Shall be conditional on the value of Transmitting Party Type Identifier (TPTI): TPTI = 1; Transmitting Party SSI; TPTI = 2; Transmitting Party SSI + Transmitting Party Extension.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DTxInterrupt {
     /// Type1, 14 bits, Call identifier
     pub call_identifier: u16,
@@ -69,10 +72,10 @@ impl DTxInterrupt {
         let notification_indicator = typed::parse_type2_generic(obit, buffer, 6, "notification_indicator")?;
         // Type2
         let transmitting_party_type_identifier = typed::parse_type2_generic(obit, buffer, 2, "transmitting_party_type_identifier")?;
-        // Type2
-        let transmitting_party_address_ssi = typed::parse_type2_generic(obit, buffer, 24, "transmitting_party_address_ssi")?;
-        // Type2
-        let transmitting_party_extension = typed::parse_type2_generic(obit, buffer, 24, "transmitting_party_extension")?;
+        // Conditional
+        let transmitting_party = PartyIdentifier::parse_optional(buffer, PartyType::Transmitting, transmitting_party_type_identifier)?;
+        let transmitting_party_address_ssi = transmitting_party.ssi;
+        let transmitting_party_extension = transmitting_party.extension;
 
 
         // Type3
@@ -116,7 +119,7 @@ impl DTxInterrupt {
         // PDU Type
         buffer.write_bits(CmcePduTypeDl::DTxInterrupt.into_raw(), 5);
         // Type1
-        buffer.write_bits(self.call_identifier as u64, 14);
+        buffer.write_field(self.call_identifier as u64, 14, "call_identifier")?;
         // Type1
         buffer.write_bits(self.transmission_grant as u64, 2);
         // Type1
@@ -127,7 +130,7 @@ impl DTxInterrupt {
         buffer.write_bits(self.reserved as u64, 1);
 
         // Check if any optional field present and place o-bit
-        let obit = self.notification_indicator.is_some() || self.transmitting_party_type_identifier.is_some() || self.transmitting_party_address_ssi.is_some() || self.transmitting_party_extension.is_some() || self.external_subscriber_number.is_some() || self.facility.is_some() || self.dm_ms_address.is_some() || self.proprietary.is_some() ;
+        let obit = self.notification_indicator.is_some() || self.transmitting_party_type_identifier.is_some() || self.external_subscriber_number.is_some() || self.facility.is_some() || self.dm_ms_address.is_some() || self.proprietary.is_some() ;
         delimiters::write_obit(buffer, obit as u8);
         if !obit { return Ok(()); }
 
@@ -137,11 +140,13 @@ impl DTxInterrupt {
         // Type2
         typed::write_type2_generic(obit, buffer, self.transmitting_party_type_identifier, 2);
 
-        // Type2
-        typed::write_type2_generic(obit, buffer, self.transmitting_party_address_ssi, 24);
-
-        // Type2
-        typed::write_type2_generic(obit, buffer, self.transmitting_party_extension, 24);
+        // Conditional
+        let transmitting_party = PartyIdentifier {
+            short_number_address: None,
+            ssi: self.transmitting_party_address_ssi,
+            extension: self.transmitting_party_extension,
+        };
+        transmitting_party.write_optional(buffer, PartyType::Transmitting, self.transmitting_party_type_identifier)?;
 
         // Type3
         typed::write_type3_generic(obit, buffer, &self.external_subscriber_number, CmceType3ElemId::ExtSubscriberNum)?;
@@ -161,6 +166,13 @@ impl DTxInterrupt {
     }
 }
 
+impl AddressedPdu for DTxInterrupt {
+    /// The party granted permission to transmit
+    fn calling_ssi(&self) -> Option<TetraAddress> {
+        self.transmitting_party_address_ssi.map(|ssi| TetraAddress::issi(ssi as u32))
+    }
+}
+
 impl fmt::Display for DTxInterrupt {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "DTxInterrupt {{ call_identifier: {:?} transmission_grant: {:?} transmission_request_permission: {:?} encryption_control: {:?} reserved: {:?} notification_indicator: {:?} transmitting_party_type_identifier: {:?} transmitting_party_address_ssi: {:?} transmitting_party_extension: {:?} external_subscriber_number: {:?} facility: {:?} dm_ms_address: {:?} proprietary: {:?} }}",
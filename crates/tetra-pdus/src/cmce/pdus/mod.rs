@@ -28,3 +28,6 @@ pub mod u_setup;
 pub mod u_tx_ceased;
 pub mod u_tx_demand;
 pub mod cmce_function_not_supported;
+pub mod cmce_pdu_dl;
+pub mod cmce_pdu_ul;
+pub mod cmce_pdu;
@@ -12,7 +12,8 @@ use crate::cmce::fields::basic_service_information::BasicServiceInformation;
 /// Response to: D-SETUP
 
 // note 1: This information element is not used in this edition of the present document and its value shall be set to "1" (equivalent to "Hook on/Hook off signalling" for backwards compatibility with edition 1 of the present document – refer to table 14.62).
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UAlert {
     /// Type1, 14 bits, Call identifier
     pub call_identifier: u16,
@@ -76,7 +77,7 @@ impl UAlert {
         // PDU Type
         buffer.write_bits(CmcePduTypeUl::UAlert.into_raw(), 5);
         // Type1
-        buffer.write_bits(self.call_identifier as u64, 14);
+        buffer.write_field(self.call_identifier as u64, 14, "call_identifier")?;
         // Type1
         buffer.write_bits(self.reserved as u64, 1);
         // Type1
@@ -1,9 +1,11 @@
 use core::fmt;
 
-use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
+use tetra_core::{BitBuffer, SsiType, TetraAddress, expect_pdu_type, pdu_parse_error::PduParseErr};
 use tetra_core::typed_pdu_fields::*;
+use tetra_core::pdu_log::AddressedPdu;
 use crate::cmce::enums::{cmce_pdu_type_ul::CmcePduTypeUl, type3_elem_id::CmceType3ElemId};
 use crate::cmce::fields::basic_service_information::BasicServiceInformation;
+use crate::cmce::fields::party_identifier::{PartyIdentifier, PartyType};
 
 /// Representation of the U-CALL RESTORE PDU (Clause 14.7.2.2).
 /// This PDU shall be the order from the MS for restoration of a specific call after a temporary break of the call.
@@ -13,7 +15,8 @@ use crate::cmce::fields::basic_service_information::BasicServiceInformation;
 // note 1: Shall be conditional on the value of Other Party Type Identifier (OPTI): OPTI = 0; Other Party SNA; OPTI = 1; Other Party SSI; OPTI = 2; Other Party SSI + Other Party Extension.
 // note 2: A use of SNA in call restoration is strongly discouraged as SS-SNA may not be supported in all networks.
 // note 3: Although coded as a type 2 element, this information element is mandatory to inform the new cell of the basic service of the current call.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UCallRestore {
     /// Type1, 14 bits, Call identifier
     pub call_identifier: u16,
@@ -52,17 +55,10 @@ impl UCallRestore {
         // Type1
         let other_party_type_identifier = buffer.read_field(2, "other_party_type_identifier")? as u8;
         // Conditional
-        let other_party_short_number_address = if other_party_type_identifier == 0 { 
-            Some(buffer.read_field(8, "other_party_short_number_address")?) 
-        } else { None };
-        // Conditional
-        let other_party_ssi = if other_party_type_identifier == 1 || other_party_type_identifier == 2 { 
-            Some(buffer.read_field(24, "other_party_ssi")?) 
-        } else { None };
-        // Conditional
-        let other_party_extension = if other_party_type_identifier == 2 { 
-            Some(buffer.read_field(24, "other_party_extension")?) 
-        } else { None };
+        let other_party = PartyIdentifier::parse(buffer, PartyType::Other, other_party_type_identifier)?;
+        let other_party_short_number_address = other_party.short_number_address;
+        let other_party_ssi = other_party.ssi;
+        let other_party_extension = other_party.extension;
 
         // obit designates presence of any further type2, type3 or type4 fields
         let mut obit = delimiters::read_obit(buffer)?;
@@ -106,23 +102,18 @@ impl UCallRestore {
         // PDU Type
         buffer.write_bits(CmcePduTypeUl::UCallRestore.into_raw(), 5);
         // Type1
-        buffer.write_bits(self.call_identifier as u64, 14);
+        buffer.write_field(self.call_identifier as u64, 14, "call_identifier")?;
         // Type1
         buffer.write_bits(self.request_to_transmit_send_data as u64, 1);
         // Type1
         buffer.write_bits(self.other_party_type_identifier as u64, 2);
         // Conditional
-        if let Some(ref value) = self.other_party_short_number_address {
-            buffer.write_bits(*value, 8);
-        }
-        // Conditional
-        if let Some(ref value) = self.other_party_ssi {
-            buffer.write_bits(*value, 24);
-        }
-        // Conditional
-        if let Some(ref value) = self.other_party_extension {
-            buffer.write_bits(*value, 24);
-        }
+        let other_party = PartyIdentifier {
+            short_number_address: self.other_party_short_number_address,
+            ssi: self.other_party_ssi,
+            extension: self.other_party_extension,
+        };
+        other_party.write(buffer, PartyType::Other, self.other_party_type_identifier)?;
 
         // Check if any optional field present and place o-bit
         let obit = self.basic_service_information.is_some() || self.facility.is_some() || self.dm_ms_address.is_some() || self.proprietary.is_some() ;
@@ -147,6 +138,13 @@ impl UCallRestore {
     }
 }
 
+impl AddressedPdu for UCallRestore {
+    /// The other party of the call being restored
+    fn called_ssi(&self) -> Option<TetraAddress> {
+        self.other_party_ssi.map(|ssi| TetraAddress::new(ssi as u32, SsiType::Ssi))
+    }
+}
+
 impl fmt::Display for UCallRestore {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "UCallRestore {{ call_identifier: {:?} request_to_transmit_send_data: {:?} other_party_type_identifier: {:?} other_party_short_number_address: {:?} other_party_ssi: {:?} other_party_extension: {:?} basic_service_information: {:?} facility: {:?} dm_ms_address: {:?} proprietary: {:?} }}",
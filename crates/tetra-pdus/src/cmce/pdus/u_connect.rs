@@ -10,7 +10,8 @@ use crate::cmce::fields::basic_service_information::BasicServiceInformation;
 /// Response expected: D-CONNECT ACKNOWLEDGE
 /// Response to: D-SETUP
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UConnect {
     /// Type1, 14 bits, Call identifier
     pub call_identifier: u16,
@@ -75,7 +76,7 @@ impl UConnect {
         // PDU Type
         buffer.write_bits(CmcePduTypeUl::UConnect.into_raw(), 5);
         // Type1
-        buffer.write_bits(self.call_identifier as u64, 14);
+        buffer.write_field(self.call_identifier as u64, 14, "call_identifier")?;
         // Type1
         buffer.write_bits(self.hook_method_selection as u64, 1);
         // Type1
@@ -10,7 +10,8 @@ use crate::cmce::enums::{cmce_pdu_type_ul::CmcePduTypeUl};
 /// Response to: -
 
 // note 1: Contents of this PDU shall be defined by SS protocols.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UFacility {
 }
 
@@ -11,7 +11,8 @@ use crate::cmce::enums::{cmce_pdu_type_ul::CmcePduTypeUl, type3_elem_id::CmceTyp
 
 // note 1: If the message is sent connectionless then the call identifier shall be equal to the dummy call identifier.
 // note 2: Shall be valid for acknowledged group call only. For other types of call it shall be set equal to zero.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UInfo {
     /// Type1, 14 bits, See note 1,
     pub call_identifier: u16,
@@ -77,7 +78,7 @@ impl UInfo {
         // PDU Type
         buffer.write_bits(CmcePduTypeUl::UInfo.into_raw(), 5);
         // Type1
-        buffer.write_bits(self.call_identifier as u64, 14);
+        buffer.write_field(self.call_identifier as u64, 14, "call_identifier")?;
         // Type1
         buffer.write_bits(self.poll_response as u64, 1);
 
@@ -10,7 +10,8 @@ use crate::cmce::enums::{cmce_pdu_type_ul::CmcePduTypeUl, type3_elem_id::CmceTyp
 /// Response expected: -
 /// Response to: D-DISCONNECT
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct URelease {
     /// Type1, 14 bits, Call identifier
     pub call_identifier: u16,
@@ -63,7 +64,7 @@ impl URelease {
         // PDU Type
         buffer.write_bits(CmcePduTypeUl::URelease.into_raw(), 5);
         // Type1
-        buffer.write_bits(self.call_identifier as u64, 14);
+        buffer.write_field(self.call_identifier as u64, 14, "call_identifier")?;
         // Type1
         buffer.write_bits(self.disconnect_cause as u64, 5);
 
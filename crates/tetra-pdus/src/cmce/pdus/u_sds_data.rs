@@ -1,8 +1,10 @@
 use core::fmt;
 
-use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
+use tetra_core::{BitBuffer, SsiType, TetraAddress, expect_pdu_type, pdu_parse_error::PduParseErr};
 use tetra_core::typed_pdu_fields::*;
+use tetra_core::pdu_log::AddressedPdu;
 use crate::cmce::enums::{cmce_pdu_type_ul::CmcePduTypeUl, type3_elem_id::CmceType3ElemId};
+use crate::cmce::fields::party_identifier::{PartyIdentifier, PartyType};
 
 /// Representation of the U-SDS-DATA PDU (Clause 14.7.2.8).
 /// This PDU shall be for sending user defined SDS data.
@@ -14,7 +16,8 @@ use crate::cmce::enums::{cmce_pdu_type_ul::CmcePduTypeUl, type3_elem_id::CmceTyp
 // note 3: Shall be conditional on the value of Short Data Type Identifier (SDTI): SDTI=0 → User Defined Data-1; SDTI=1 → User Defined Data-2; SDTI=2 → User Defined Data-3; SDTI=3 → Length indicator + User Defined Data-4.
 // note 4: Any combination of address and user defined data type is allowed; recommended to choose the shortest appropriate user defined data type to fit one sub-slot when possible.
 // note 5: The length of User Defined Data-4 is between 0 and 2 047 bits (longest recommended: 1 017 bits on basic link with Short SSI and FCS on π/4-DQPSK).
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct USdsData {
     /// Type1, 4 bits, See note 1,
     pub area_selection: u8,
@@ -37,14 +40,14 @@ pub struct USdsData {
     /// Conditional 11 bits, See note 2, condition: short_data_type_identifier == 3
     pub length_indicator: Option<u64>,
     /// Conditional See note 2, condition: short_data_type_identifier == 3
-    pub user_defined_data_4: Option<u64>,
+    /// Packed MSB-first, length_indicator bits long; the last byte is left-aligned
+    pub user_defined_data_4: Option<Vec<u8>>,
     /// Type3, External subscriber number
     pub external_subscriber_number: Option<Type3FieldGeneric>,
     /// Type3, DM-MS address
     pub dm_ms_address: Option<Type3FieldGeneric>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
 impl USdsData {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
@@ -57,17 +60,10 @@ impl USdsData {
         // Type1
         let called_party_type_identifier = buffer.read_field(2, "called_party_type_identifier")? as u8;
         // Conditional
-        let called_party_short_number_address = if called_party_type_identifier == 0 { 
-            Some(buffer.read_field(8, "called_party_short_number_address")?) 
-        } else { None };
-        // Conditional
-        let called_party_ssi = if called_party_type_identifier == 1 || called_party_type_identifier == 2 { 
-            Some(buffer.read_field(24, "called_party_ssi")?) 
-        } else { None };
-        // Conditional
-        let called_party_extension = if called_party_type_identifier == 2 { 
-            Some(buffer.read_field(24, "called_party_extension")?) 
-        } else { None };
+        let called_party = PartyIdentifier::parse(buffer, PartyType::Called, called_party_type_identifier)?;
+        let called_party_short_number_address = called_party.short_number_address;
+        let called_party_ssi = called_party.ssi;
+        let called_party_extension = called_party.extension;
         // Type1
         let short_data_type_identifier = buffer.read_field(2, "short_data_type_identifier")? as u8;
         // Conditional
@@ -87,9 +83,16 @@ impl USdsData {
             Some(buffer.read_field(11, "length_indicator")?) 
         } else { None };
         // Conditional
-        let user_defined_data_4 = if short_data_type_identifier == 3 { 
-            unimplemented!();
-            Some(buffer.read_field(999, "user_defined_data_4")?) 
+        let user_defined_data_4 = if let Some(len) = length_indicator { 
+            let mut data = Vec::with_capacity((len as usize).div_ceil(8));
+            let mut remaining = len as usize;
+            while remaining > 0 {
+                let num_bits = remaining.min(8);
+                let byte = buffer.read_field(num_bits, "user_defined_data_4")? as u8;
+                data.push(byte << (8 - num_bits));
+                remaining -= num_bits;
+            }
+            Some(data)
         } else { None };
 
         // obit designates presence of any further type2, type3 or type4 fields
@@ -133,17 +136,12 @@ impl USdsData {
         // Type1
         buffer.write_bits(self.called_party_type_identifier as u64, 2);
         // Conditional
-        if let Some(ref value) = self.called_party_short_number_address {
-            buffer.write_bits(*value, 8);
-        }
-        // Conditional
-        if let Some(ref value) = self.called_party_ssi {
-            buffer.write_bits(*value, 24);
-        }
-        // Conditional
-        if let Some(ref value) = self.called_party_extension {
-            buffer.write_bits(*value, 24);
-        }
+        let called_party = PartyIdentifier {
+            short_number_address: self.called_party_short_number_address,
+            ssi: self.called_party_ssi,
+            extension: self.called_party_extension,
+        };
+        called_party.write(buffer, PartyType::Called, self.called_party_type_identifier)?;
         // Type1
         buffer.write_bits(self.short_data_type_identifier as u64, 2);
         // Conditional
@@ -163,9 +161,17 @@ impl USdsData {
             buffer.write_bits(*value, 11);
         }
         // Conditional
-        if let Some(ref _value) = self.user_defined_data_4 {
-            unimplemented!();
-            buffer.write_bits(*_value, 999);
+        if let Some(ref value) = self.user_defined_data_4 {
+            let len = self.length_indicator.unwrap_or(0) as usize;
+            if value.len() != len.div_ceil(8) {
+                return Err(PduParseErr::InconsistentLength { expected: len.div_ceil(8), found: value.len() });
+            }
+            let mut remaining = len;
+            for byte in value {
+                let num_bits = remaining.min(8);
+                buffer.write_bits((*byte >> (8 - num_bits)) as u64, num_bits);
+                remaining -= num_bits;
+            }
         }
 
         // Check if any optional field present and place o-bit
@@ -185,6 +191,12 @@ impl USdsData {
     }
 }
 
+impl AddressedPdu for USdsData {
+    fn called_ssi(&self) -> Option<TetraAddress> {
+        self.called_party_ssi.map(|ssi| TetraAddress::new(ssi as u32, SsiType::Ssi))
+    }
+}
+
 impl fmt::Display for USdsData {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "USdsData {{ area_selection: {:?} called_party_type_identifier: {:?} called_party_short_number_address: {:?} called_party_ssi: {:?} called_party_extension: {:?} short_data_type_identifier: {:?} user_defined_data_1: {:?} user_defined_data_2: {:?} user_defined_data_3: {:?} length_indicator: {:?} user_defined_data_4: {:?} external_subscriber_number: {:?} dm_ms_address: {:?} }}",
@@ -1,9 +1,12 @@
 use core::fmt;
 
-use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
+use tetra_core::{BitBuffer, SsiType, TetraAddress, expect_pdu_type, pdu_parse_error::PduParseErr};
 use tetra_core::typed_pdu_fields::*;
+use tetra_core::pdu_log::AddressedPdu;
 use crate::cmce::enums::{cmce_pdu_type_ul::CmcePduTypeUl, type3_elem_id::CmceType3ElemId};
 use crate::cmce::fields::basic_service_information::BasicServiceInformation;
+use crate::cmce::fields::party_identifier::{PartyIdentifier, PartyType};
+use tetra_saps::control::enums::communication_type::CommunicationType;
 
 /// Representation of the U-SETUP PDU (Clause 14.7.2.10).
 /// This PDU shall be the request for a call set-up from a MS.
@@ -14,7 +17,8 @@ use crate::cmce::fields::basic_service_information::BasicServiceInformation;
 // note 2: This information element is used by SS-PC, refer to ETSI EN 300 392-12-10 [15] and SS-PPC, refer to ETSI EN 300 392-12-16 [16].
 // note 3: Refer to ETSI EN 300 392-12-1 [11].
 // note 4: Shall be conditional on the value of Called Party Type Identifier (CPTI): CPTI = 0 → Called Party SNA (refer to ETS 300 392-12-7 [13]); CPTI = 1 → Called Party SSI; CPTI = 2 → Called Party SSI + Called Party Extension.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct USetup {
     /// Type1, 4 bits, See note 1. ETSI EN 300 392-12-8 Clause 5.2.2.3
     /// 0 = SS-AS not defined, 1-14 = SS-AS with selected area N, 15 = (usually) all areas
@@ -85,17 +89,10 @@ impl USetup {
         // Type1
         let called_party_type_identifier = buffer.read_field(2, "called_party_type_identifier")? as u8;
         // Conditional
-        let called_party_short_number_address = if called_party_type_identifier == 0 { 
-            Some(buffer.read_field(8, "called_party_short_number_address")?) 
-        } else { None };
-        // Conditional
-        let called_party_ssi = if called_party_type_identifier == 1 || called_party_type_identifier == 2 { 
-            Some(buffer.read_field(24, "called_party_ssi")?) 
-        } else { None };
-        // Conditional
-        let called_party_extension = if called_party_type_identifier == 2 { 
-            Some(buffer.read_field(24, "called_party_extension")?) 
-        } else { None };
+        let called_party = PartyIdentifier::parse(buffer, PartyType::Called, called_party_type_identifier)?;
+        let called_party_short_number_address = called_party.short_number_address;
+        let called_party_ssi = called_party.ssi;
+        let called_party_extension = called_party.extension;
 
         // obit designates presence of any further type2, type3 or type4 fields
         let mut obit = delimiters::read_obit(buffer)?;
@@ -159,17 +156,12 @@ impl USetup {
         // Type1
         buffer.write_bits(self.called_party_type_identifier as u64, 2);
         // Conditional
-        if let Some(ref value) = self.called_party_short_number_address {
-            buffer.write_bits(*value, 8);
-        }
-        // Conditional
-        if let Some(ref value) = self.called_party_ssi {
-            buffer.write_bits(*value, 24);
-        }
-        // Conditional
-        if let Some(ref value) = self.called_party_extension {
-            buffer.write_bits(*value, 24);
-        }
+        let called_party = PartyIdentifier {
+            short_number_address: self.called_party_short_number_address,
+            ssi: self.called_party_ssi,
+            extension: self.called_party_extension,
+        };
+        called_party.write(buffer, PartyType::Called, self.called_party_type_identifier)?;
 
         // Check if any optional field present and place o-bit
         let obit = self.external_subscriber_number.is_some() || self.facility.is_some() || self.dm_ms_address.is_some() || self.proprietary.is_some() ;
@@ -194,6 +186,17 @@ impl USetup {
     }
 }
 
+impl AddressedPdu for USetup {
+    /// Group address for point-to-multipoint calls, individual address otherwise
+    fn called_ssi(&self) -> Option<TetraAddress> {
+        let ssi_type = match self.basic_service_information.communication_type {
+            CommunicationType::P2p => SsiType::Issi,
+            _ => SsiType::Gssi,
+        };
+        self.called_party_ssi.map(|ssi| TetraAddress::new(ssi as u32, ssi_type))
+    }
+}
+
 impl fmt::Display for USetup {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "USetup {{ area_selection: {:?} hook_method_selection: {:?} simplex_duplex_selection: {:?} basic_service_information: {:?} request_to_transmit_send_data: {:?} call_priority: {:?} clir_control: {:?} called_party_type_identifier: {:?} called_party_short_number_address: {:?} called_party_ssi: {:?} called_party_extension: {:?} external_subscriber_number: {:?} facility: {:?} dm_ms_address: {:?} proprietary: {:?} }}",
@@ -1,8 +1,10 @@
 use core::fmt;
 
-use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
+use tetra_core::{BitBuffer, SsiType, TetraAddress, expect_pdu_type, pdu_parse_error::PduParseErr};
 use tetra_core::typed_pdu_fields::*;
+use tetra_core::pdu_log::AddressedPdu;
 use crate::cmce::enums::{cmce_pdu_type_ul::CmcePduTypeUl, type3_elem_id::CmceType3ElemId};
+use crate::cmce::fields::party_identifier::{PartyIdentifier, PartyType};
 
 /// Representation of the U-STATUS PDU (Clause 14.7.2.7).
 /// This PDU shall be used for sending a pre-coded status message.
@@ -11,7 +13,8 @@ use crate::cmce::enums::{cmce_pdu_type_ul::CmcePduTypeUl, type3_elem_id::CmceTyp
 
 // note 1: This information element is used by SS-AS, refer to ETSI EN 300 392-12-8 [14].
 // note 2: Shall be conditional on the value of Called Party Type Identifier (CPTI): CPTI = 0 → Called Party SNA (see ETS 300 392-12-7 [13]); CPTI = 1 → Called Party SSI; CPTI = 2 → Called Party SSI + Called Party Extension.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UStatus {
     /// Type1, 4 bits, See note 1,
     pub area_selection: u8,
@@ -44,17 +47,10 @@ impl UStatus {
         // Type1
         let called_party_type_identifier = buffer.read_field(2, "called_party_type_identifier")? as u8;
         // Conditional
-        let called_party_short_number_address = if called_party_type_identifier == 0 { 
-            Some(buffer.read_field(8, "called_party_short_number_address")?) 
-        } else { None };
-        // Conditional
-        let called_party_ssi = if called_party_type_identifier == 1 || called_party_type_identifier == 2 { 
-            Some(buffer.read_field(24, "called_party_ssi")?) 
-        } else { None };
-        // Conditional
-        let called_party_extension = if called_party_type_identifier == 2 { 
-            Some(buffer.read_field(24, "called_party_extension")?) 
-        } else { None };
+        let called_party = PartyIdentifier::parse(buffer, PartyType::Called, called_party_type_identifier)?;
+        let called_party_short_number_address = called_party.short_number_address;
+        let called_party_ssi = called_party.ssi;
+        let called_party_extension = called_party.extension;
         // Type1
         let pre_coded_status = buffer.read_field(16, "pre_coded_status")? as u16;
 
@@ -94,17 +90,12 @@ impl UStatus {
         // Type1
         buffer.write_bits(self.called_party_type_identifier as u64, 2);
         // Conditional
-        if let Some(ref value) = self.called_party_short_number_address {
-            buffer.write_bits(*value, 8);
-        }
-        // Conditional
-        if let Some(ref value) = self.called_party_ssi {
-            buffer.write_bits(*value, 24);
-        }
-        // Conditional
-        if let Some(ref value) = self.called_party_extension {
-            buffer.write_bits(*value, 24);
-        }
+        let called_party = PartyIdentifier {
+            short_number_address: self.called_party_short_number_address,
+            ssi: self.called_party_ssi,
+            extension: self.called_party_extension,
+        };
+        called_party.write(buffer, PartyType::Called, self.called_party_type_identifier)?;
         // Type1
         buffer.write_bits(self.pre_coded_status as u64, 16);
 
@@ -125,6 +116,12 @@ impl UStatus {
     }
 }
 
+impl AddressedPdu for UStatus {
+    fn called_ssi(&self) -> Option<TetraAddress> {
+        self.called_party_ssi.map(|ssi| TetraAddress::new(ssi as u32, SsiType::Ssi))
+    }
+}
+
 impl fmt::Display for UStatus {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "UStatus {{ area_selection: {:?} called_party_type_identifier: {:?} called_party_short_number_address: {:?} called_party_ssi: {:?} called_party_extension: {:?} pre_coded_status: {:?} external_subscriber_number: {:?} dm_ms_address: {:?} }}",
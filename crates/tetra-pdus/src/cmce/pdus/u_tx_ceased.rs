@@ -9,7 +9,8 @@ use crate::cmce::enums::{cmce_pdu_type_ul::CmcePduTypeUl, type3_elem_id::CmceTyp
 /// Response expected: D-TX CEASED/D-TX GRANTED/D-TX WAIT
 /// Response to: -
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UTxCeased {
     /// Type1, 14 bits, Call identifier
     pub call_identifier: u16,
@@ -64,7 +65,7 @@ impl UTxCeased {
         // PDU Type
         buffer.write_bits(CmcePduTypeUl::UTxCeased.into_raw(), 5);
         // Type1
-        buffer.write_bits(self.call_identifier as u64, 14);
+        buffer.write_field(self.call_identifier as u64, 14, "call_identifier")?;
 
         // Check if any optional field present and place o-bit
         let obit = self.facility.is_some() || self.dm_ms_address.is_some() || self.proprietary.is_some() ;
@@ -10,7 +10,8 @@ use crate::cmce::enums::{cmce_pdu_type_ul::CmcePduTypeUl, type3_elem_id::CmceTyp
 /// Response to: D-TX GRANTED
 
 // note 1: This information element is not used in this version of the present document and its value shall be set to "0".
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UTxDemand {
     /// Type1, 14 bits, Call identifier
     pub call_identifier: u16,
@@ -80,7 +81,7 @@ impl UTxDemand {
         // PDU Type
         buffer.write_bits(CmcePduTypeUl::UTxDemand.into_raw(), 5);
         // Type1
-        buffer.write_bits(self.call_identifier as u64, 14);
+        buffer.write_field(self.call_identifier as u64, 14, "call_identifier")?;
         // Type1
         buffer.write_bits(self.tx_demand_priority as u64, 2);
         // Type1
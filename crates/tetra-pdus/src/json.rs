@@ -0,0 +1,54 @@
+//! JSON input and output for PDUs, for integration with external tooling and for
+//! hand-authoring test vectors. Available with the `serde` feature.
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use tetra_core::{BitBuffer, PduParseErr};
+
+#[derive(Debug)]
+pub enum PduJsonErr {
+    /// The JSON could not be parsed into the requested PDU struct
+    Json(serde_json::Error),
+    /// The PDU struct was parsed, but could not be serialized to bits
+    Pdu(PduParseErr),
+}
+
+/// Convenience trait to dump any serializable PDU as JSON.
+/// Optional type2/3/4 elements that are absent serialize as `null`.
+pub trait PduToJson: Serialize {
+    fn to_json(&self) -> String {
+        // PDU structs only contain plain fields, options and vecs; serialization cannot fail
+        serde_json::to_string(self).expect("PDU serialization to JSON failed")
+    }
+
+    fn to_json_pretty(&self) -> String {
+        serde_json::to_string_pretty(self).expect("PDU serialization to JSON failed")
+    }
+}
+
+impl<T: Serialize> PduToJson for T {}
+
+/// Convenience trait to construct a PDU struct from JSON.
+/// Optional type2/3/4 elements may be omitted from the JSON, in which case they are `None`.
+pub trait PduFromJson: DeserializeOwned {
+    fn from_json(json: &str) -> Result<Self, PduJsonErr> {
+        serde_json::from_str(json).map_err(PduJsonErr::Json)
+    }
+}
+
+impl<T: DeserializeOwned> PduFromJson for T {}
+
+/// Parse a PDU from JSON and serialize it to bits using the given `to_bitbuf` function.
+/// Returns the resulting BitBuffer, positioned at the start.
+/// Example: `json_to_bitbuf(json, DAlert::to_bitbuf)`
+pub fn json_to_bitbuf<T, F>(json: &str, writer: F) -> Result<BitBuffer, PduJsonErr>
+where
+    T: DeserializeOwned,
+    F: Fn(&T, &mut BitBuffer) -> Result<(), PduParseErr>,
+{
+    let pdu = T::from_json(json)?;
+    let mut buffer = BitBuffer::new_autoexpand(64);
+    writer(&pdu, &mut buffer).map_err(PduJsonErr::Pdu)?;
+    buffer.seek(0);
+    Ok(buffer)
+}
@@ -6,3 +6,11 @@ pub mod mle;
 pub mod mm;
 pub mod phy;
 pub mod umac;
+
+#[cfg(feature = "serde")]
+pub mod json;
+
+pub mod parse_timing;
+pub mod selftest;
+#[cfg(test)]
+mod testing;
@@ -1 +1,2 @@
-pub mod bs_service_details;
\ No newline at end of file
+pub mod bs_service_details;
+pub mod neighbour_cell_information_for_ca;
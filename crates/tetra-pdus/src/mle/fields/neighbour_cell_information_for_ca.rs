@@ -0,0 +1,132 @@
+use core::fmt;
+
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+use tetra_core::typed_pdu_fields::*;
+
+
+/// Clause 18.5.17 Neighbour cell information for CA
+/// Carried in D-NWRK-BROADCAST, without a preceding P-bit, once per CA neighbour cell.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct NeighbourCellInformationForCa {
+    /// Type1, 5 bits, Cell identifier CA
+    pub cell_identifier_ca: u8,
+    /// Type1, 2 bits, Cell reselection types supported
+    pub cell_reselection_types_supported: u8,
+    /// Type1, 1 bit, Neighbour cell synchronized
+    pub neighbour_cell_synchronized: bool,
+    /// Type1, 2 bits, Cell load CA
+    pub cell_load_ca: u8,
+    /// Type1, 12 bits, Main carrier number
+    pub main_carrier_number: u16,
+    /// Type2, 10 bits, Main carrier number extension
+    pub main_carrier_number_extension: Option<u64>,
+    /// Type2, 10 bits, MCC
+    pub mcc: Option<u64>,
+    /// Type2, 14 bits, MNC
+    pub mnc: Option<u64>,
+    /// Type2, 14 bits, Location area
+    pub location_area: Option<u64>,
+    /// Type2, 3 bits, Maximum MS transmit power
+    pub maximum_ms_transmit_power: Option<u64>,
+    /// Type2, 4 bits, Minimum RX access level
+    pub minimum_rx_access_level: Option<u64>,
+    /// Type2, 16 bits, Subscriber class
+    pub subscriber_class: Option<u64>,
+    /// Type2, 12 bits, BS service details
+    pub bs_service_details: Option<u64>,
+    /// Type2, 5 bits, Timeshare cell information or security parameters
+    pub timeshare_cell_or_security_parameters: Option<u64>,
+    /// Type2, 6 bits, TDMA frame offset
+    pub tdma_frame_offset: Option<u64>,
+}
+
+impl NeighbourCellInformationForCa {
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        // Type1
+        let cell_identifier_ca = buffer.read_field(5, "cell_identifier_ca")? as u8;
+        let cell_reselection_types_supported = buffer.read_field(2, "cell_reselection_types_supported")? as u8;
+        let neighbour_cell_synchronized = buffer.read_field(1, "neighbour_cell_synchronized")? != 0;
+        let cell_load_ca = buffer.read_field(2, "cell_load_ca")? as u8;
+        let main_carrier_number = buffer.read_field(12, "main_carrier_number")? as u16;
+
+        // obit designates presence of any further type2 fields
+        let obit = delimiters::read_obit(buffer)?;
+
+        // Type2
+        let main_carrier_number_extension = typed::parse_type2_generic(obit, buffer, 10, "main_carrier_number_extension")?;
+        let mcc = typed::parse_type2_generic(obit, buffer, 10, "mcc")?;
+        let mnc = typed::parse_type2_generic(obit, buffer, 14, "mnc")?;
+        let location_area = typed::parse_type2_generic(obit, buffer, 14, "location_area")?;
+        let maximum_ms_transmit_power = typed::parse_type2_generic(obit, buffer, 3, "maximum_ms_transmit_power")?;
+        let minimum_rx_access_level = typed::parse_type2_generic(obit, buffer, 4, "minimum_rx_access_level")?;
+        let subscriber_class = typed::parse_type2_generic(obit, buffer, 16, "subscriber_class")?;
+        let bs_service_details = typed::parse_type2_generic(obit, buffer, 12, "bs_service_details")?;
+        let timeshare_cell_or_security_parameters = typed::parse_type2_generic(obit, buffer, 5, "timeshare_cell_or_security_parameters")?;
+        let tdma_frame_offset = typed::parse_type2_generic(obit, buffer, 6, "tdma_frame_offset")?;
+
+        Ok(NeighbourCellInformationForCa {
+            cell_identifier_ca,
+            cell_reselection_types_supported,
+            neighbour_cell_synchronized,
+            cell_load_ca,
+            main_carrier_number,
+            main_carrier_number_extension,
+            mcc,
+            mnc,
+            location_area,
+            maximum_ms_transmit_power,
+            minimum_rx_access_level,
+            subscriber_class,
+            bs_service_details,
+            timeshare_cell_or_security_parameters,
+            tdma_frame_offset,
+        })
+    }
+
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) {
+        // Type1
+        buffer.write_bits(self.cell_identifier_ca as u64, 5);
+        buffer.write_bits(self.cell_reselection_types_supported as u64, 2);
+        buffer.write_bits(self.neighbour_cell_synchronized as u64, 1);
+        buffer.write_bits(self.cell_load_ca as u64, 2);
+        buffer.write_bits(self.main_carrier_number as u64, 12);
+
+        // Check if any optional field present and place o-bit
+        let obit = self.main_carrier_number_extension.is_some()
+            || self.mcc.is_some()
+            || self.mnc.is_some()
+            || self.location_area.is_some()
+            || self.maximum_ms_transmit_power.is_some()
+            || self.minimum_rx_access_level.is_some()
+            || self.subscriber_class.is_some()
+            || self.bs_service_details.is_some()
+            || self.timeshare_cell_or_security_parameters.is_some()
+            || self.tdma_frame_offset.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit { return; }
+
+        // Type2
+        typed::write_type2_generic(obit, buffer, self.main_carrier_number_extension, 10);
+        typed::write_type2_generic(obit, buffer, self.mcc, 10);
+        typed::write_type2_generic(obit, buffer, self.mnc, 14);
+        typed::write_type2_generic(obit, buffer, self.location_area, 14);
+        typed::write_type2_generic(obit, buffer, self.maximum_ms_transmit_power, 3);
+        typed::write_type2_generic(obit, buffer, self.minimum_rx_access_level, 4);
+        typed::write_type2_generic(obit, buffer, self.subscriber_class, 16);
+        typed::write_type2_generic(obit, buffer, self.bs_service_details, 12);
+        typed::write_type2_generic(obit, buffer, self.timeshare_cell_or_security_parameters, 5);
+        typed::write_type2_generic(obit, buffer, self.tdma_frame_offset, 6);
+    }
+}
+
+impl fmt::Display for NeighbourCellInformationForCa {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "NeighbourCellInformationForCa {{ cell_identifier_ca: {} main_carrier_number: {} location_area: {:?} }}",
+            self.cell_identifier_ca,
+            self.main_carrier_number,
+            self.location_area,
+        )
+    }
+}
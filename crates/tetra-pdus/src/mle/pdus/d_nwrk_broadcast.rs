@@ -4,6 +4,7 @@ use tetra_core::{BitBuffer, expect_pdu_type, pdu_parse_error::PduParseErr};
 use tetra_core::typed_pdu_fields::*;
 
 use crate::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
+use crate::mle::fields::neighbour_cell_information_for_ca::NeighbourCellInformationForCa;
 
 /// Representation of the D-NWRK-BROADCAST PDU (Clause 18.4.1.4.1).
 /// Upon receipt from the SwMI, the message shall inform the MS-MLE about parameters for the CA serving cell and parameters for one or more CA neighbour cells.
@@ -13,7 +14,7 @@ use crate::mle::enums::mle_pdu_type_dl::MlePduTypeDl;
 // note 1: This element shall not be used by a DA MS.
 // note 2: If present, the element shall indicate how many “Neighbour cell information for CA” elements follow. If not present, no neighbour cell information shall follow.
 // note 3: The element definition is contained in clause 18.5 which gives the type and length for each sub-element which is included in this element. The element shall be present as many times as indicated by the “number of CA neighbour cells” element. There shall be no P-bit preceding each “neighbour cell information for CA” element which is carried by this PDU.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct DNwrkBroadcast {
     /// Type1, 16 bits, See note 1,
     pub cell_re_select_parameters: u16,
@@ -23,11 +24,10 @@ pub struct DNwrkBroadcast {
     pub tetra_network_time: Option<u64>,
     /// Type2, 3 bits, See note 2,
     pub number_of_ca_neighbour_cells: Option<u64>,
-    /// Conditional See note 3, condition: number_of_ca_neighbour_cells > Some(0)
-    pub neighbour_cell_information_for_ca: Option<u64>,
+    /// Conditional See note 3, as many elements as given by number_of_ca_neighbour_cells
+    pub neighbour_cell_information_for_ca: Vec<NeighbourCellInformationForCa>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
 impl DNwrkBroadcast {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
@@ -49,10 +49,10 @@ impl DNwrkBroadcast {
         let number_of_ca_neighbour_cells = typed::parse_type2_generic(obit, buffer, 3, "number_of_ca_neighbour_cells")?;
 
         // Conditional
-        let neighbour_cell_information_for_ca = if obit && number_of_ca_neighbour_cells > Some(0) { 
-            unimplemented!();
-            Some(buffer.read_field(999, "neighbour_cell_information_for_ca")?) 
-        } else { None };
+        let mut neighbour_cell_information_for_ca = Vec::new();
+        for _ in 0..number_of_ca_neighbour_cells.unwrap_or(0) {
+            neighbour_cell_information_for_ca.push(NeighbourCellInformationForCa::from_bitbuf(buffer)?);
+        }
 
         // Read trailing obit (if not previously encountered)
         obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
@@ -71,6 +71,11 @@ impl DNwrkBroadcast {
 
     /// Serialize this PDU into the given BitBuffer.
     pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        let num_neighbours = self.number_of_ca_neighbour_cells.unwrap_or(0) as usize;
+        if num_neighbours != self.neighbour_cell_information_for_ca.len() {
+            return Err(PduParseErr::InconsistentLength { expected: num_neighbours, found: self.neighbour_cell_information_for_ca.len() });
+        }
+
         // PDU Type
         buffer.write_bits(MlePduTypeDl::DNwrkBroadcast.into_raw(), 3);
         // Type1
@@ -90,10 +95,10 @@ impl DNwrkBroadcast {
         typed::write_type2_generic(obit, buffer, self.number_of_ca_neighbour_cells, 3);
 
         // Conditional
-        if let Some(ref _value) = self.neighbour_cell_information_for_ca {
-            unimplemented!();
-            buffer.write_bits(*_value, 999);
+        for neighbour in &self.neighbour_cell_information_for_ca {
+            neighbour.to_bitbuf(buffer);
         }
+
         // Write terminating m-bit
         delimiters::write_mbit(buffer, 0);
         Ok(())
@@ -113,3 +118,48 @@ impl fmt::Display for DNwrkBroadcast {
         )
     }
 }
+
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_d_nwrk_broadcast_with_neighbours() {
+        debug::setup_logging_verbose();
+        let pdu = DNwrkBroadcast {
+            cell_re_select_parameters: 0,
+            cell_load_ca: 1,
+            tetra_network_time: None,
+            number_of_ca_neighbour_cells: Some(2),
+            neighbour_cell_information_for_ca: vec![
+                NeighbourCellInformationForCa { cell_identifier_ca: 1, main_carrier_number: 1522, location_area: Some(3), ..Default::default() },
+                NeighbourCellInformationForCa { cell_identifier_ca: 2, main_carrier_number: 1530, ..Default::default() },
+            ],
+        };
+
+        let mut buf = BitBuffer::new_autoexpand(128);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        tracing::info!("Serialized: {}", buf.dump_bin());
+        buf.seek(0);
+
+        let parsed = DNwrkBroadcast::from_bitbuf(&mut buf).expect("Failed parsing");
+        assert!(buf.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(parsed, pdu);
+    }
+
+    #[test]
+    fn test_d_nwrk_broadcast_inconsistent_neighbour_count() {
+        let pdu = DNwrkBroadcast {
+            cell_re_select_parameters: 0,
+            cell_load_ca: 0,
+            tetra_network_time: None,
+            number_of_ca_neighbour_cells: Some(2),
+            neighbour_cell_information_for_ca: vec![NeighbourCellInformationForCa::default()],
+        };
+        let mut buf = BitBuffer::new_autoexpand(64);
+        assert_eq!(pdu.to_bitbuf(&mut buf), Err(PduParseErr::InconsistentLength { expected: 2, found: 1 }));
+    }
+}
@@ -0,0 +1,52 @@
+/// EN 300 392-7 Equipment status and Subscription status, as reported in U-DISABLE STATUS
+/// Bits: 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum DisableStatus {
+    #[default]
+    Enabled = 0,
+    TemporarilyDisabled = 1,
+    PermanentlyDisabled = 2,
+}
+
+impl std::convert::TryFrom<u64> for DisableStatus {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(DisableStatus::Enabled),
+            1 => Ok(DisableStatus::TemporarilyDisabled),
+            2 => Ok(DisableStatus::PermanentlyDisabled),
+            _ => Err(()),
+        }
+    }
+}
+
+impl DisableStatus {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            DisableStatus::Enabled => 0,
+            DisableStatus::TemporarilyDisabled => 1,
+            DisableStatus::PermanentlyDisabled => 2,
+        }
+    }
+
+    pub fn is_disabled(self) -> bool {
+        self != DisableStatus::Enabled
+    }
+}
+
+impl From<DisableStatus> for u64 {
+    fn from(e: DisableStatus) -> Self { e.into_raw() }
+}
+
+impl core::fmt::Display for DisableStatus {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DisableStatus::Enabled => write!(f, "Enabled"),
+            DisableStatus::TemporarilyDisabled => write!(f, "TemporarilyDisabled"),
+            DisableStatus::PermanentlyDisabled => write!(f, "PermanentlyDisabled"),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 /// Clause 16.10.9 Energy saving mode
 /// Bits: 3
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum EnergySavingMode {
     StayAlive = 0,
@@ -51,6 +52,26 @@ impl EnergySavingMode {
             EnergySavingMode::Eg7 => 7,
         }
     }
+
+    /// Number of TDMA frames the MS may sleep per energy economy cycle (clause 23.7.6).
+    /// Returns 0 for StayAlive.
+    pub fn sleep_frames(self) -> u16 {
+        match self {
+            EnergySavingMode::StayAlive => 0,
+            EnergySavingMode::Eg1 => 1,
+            EnergySavingMode::Eg2 => 2,
+            EnergySavingMode::Eg3 => 5,
+            EnergySavingMode::Eg4 => 8,
+            EnergySavingMode::Eg5 => 17,
+            EnergySavingMode::Eg6 => 71,
+            EnergySavingMode::Eg7 => 359,
+        }
+    }
+
+    /// Length of one energy economy cycle in TDMA frames: one awake frame followed by the sleep frames
+    pub fn cycle_frames(self) -> u16 {
+        self.sleep_frames() + 1
+    }
 }
 
 impl From<EnergySavingMode> for u64 {
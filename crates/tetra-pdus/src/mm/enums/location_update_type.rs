@@ -2,6 +2,7 @@
 /// Almost identical to MmLocationUpdateAcceptType
 /// Bits: 3
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum LocationUpdateType {
     RoamingLocationUpdating = 0,
@@ -2,9 +2,14 @@
 pub mod mm_pdu_type_dl;
 pub mod mm_pdu_type_ul;
 
+pub mod disable_status;
 pub mod energy_saving_mode;
 pub mod location_update_type;
 pub mod location_update_accept_type;
+pub mod otar_sub_type_dl;
+pub mod otar_sub_type_ul;
+pub mod otar_type3_elem_id;
+pub mod reject_cause;
 
 pub mod status_downlink;
 pub mod status_uplink;
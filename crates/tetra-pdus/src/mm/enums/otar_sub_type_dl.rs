@@ -0,0 +1,59 @@
+/// EN 300 392-7 OTAR sub-type, downlink direction (D-OTAR)
+/// Bits: 4
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum OtarSubTypeDl {
+    CckProvide = 0,
+    CckReject = 1,
+    SckProvide = 2,
+    SckReject = 3,
+    GckProvide = 4,
+    GckReject = 5,
+    KeyAssociate = 6,
+    GskoProvide = 8,
+    GskoReject = 9,
+    KeyDeleteDemand = 10,
+    KeyStatusDemand = 11,
+    CmgGtsiProvide = 12,
+    DmSckActivate = 13,
+}
+
+impl std::convert::TryFrom<u64> for OtarSubTypeDl {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(OtarSubTypeDl::CckProvide),
+            1 => Ok(OtarSubTypeDl::CckReject),
+            2 => Ok(OtarSubTypeDl::SckProvide),
+            3 => Ok(OtarSubTypeDl::SckReject),
+            4 => Ok(OtarSubTypeDl::GckProvide),
+            5 => Ok(OtarSubTypeDl::GckReject),
+            6 => Ok(OtarSubTypeDl::KeyAssociate),
+            8 => Ok(OtarSubTypeDl::GskoProvide),
+            9 => Ok(OtarSubTypeDl::GskoReject),
+            10 => Ok(OtarSubTypeDl::KeyDeleteDemand),
+            11 => Ok(OtarSubTypeDl::KeyStatusDemand),
+            12 => Ok(OtarSubTypeDl::CmgGtsiProvide),
+            13 => Ok(OtarSubTypeDl::DmSckActivate),
+            _ => Err(()),
+        }
+    }
+}
+
+impl OtarSubTypeDl {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        self as u64
+    }
+}
+
+impl From<OtarSubTypeDl> for u64 {
+    fn from(e: OtarSubTypeDl) -> Self { e.into_raw() }
+}
+
+impl core::fmt::Display for OtarSubTypeDl {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self, f)
+    }
+}
@@ -0,0 +1,55 @@
+/// EN 300 392-7 OTAR sub-type, uplink direction (U-OTAR)
+/// Bits: 4
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum OtarSubTypeUl {
+    CckDemand = 0,
+    CckResult = 1,
+    SckDemand = 2,
+    SckResult = 3,
+    GckDemand = 4,
+    GckResult = 5,
+    GskoDemand = 8,
+    GskoResult = 9,
+    KeyDeleteResult = 10,
+    KeyStatusResponse = 11,
+    CmgGtsiResult = 12,
+}
+
+impl std::convert::TryFrom<u64> for OtarSubTypeUl {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            0 => Ok(OtarSubTypeUl::CckDemand),
+            1 => Ok(OtarSubTypeUl::CckResult),
+            2 => Ok(OtarSubTypeUl::SckDemand),
+            3 => Ok(OtarSubTypeUl::SckResult),
+            4 => Ok(OtarSubTypeUl::GckDemand),
+            5 => Ok(OtarSubTypeUl::GckResult),
+            8 => Ok(OtarSubTypeUl::GskoDemand),
+            9 => Ok(OtarSubTypeUl::GskoResult),
+            10 => Ok(OtarSubTypeUl::KeyDeleteResult),
+            11 => Ok(OtarSubTypeUl::KeyStatusResponse),
+            12 => Ok(OtarSubTypeUl::CmgGtsiResult),
+            _ => Err(()),
+        }
+    }
+}
+
+impl OtarSubTypeUl {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        self as u64
+    }
+}
+
+impl From<OtarSubTypeUl> for u64 {
+    fn from(e: OtarSubTypeUl) -> Self { e.into_raw() }
+}
+
+impl core::fmt::Display for OtarSubTypeUl {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self, f)
+    }
+}
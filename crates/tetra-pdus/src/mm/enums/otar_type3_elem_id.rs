@@ -0,0 +1,37 @@
+/// EN 300 392-7 Type 3 element identifiers used within D-OTAR and U-OTAR PDUs
+/// Bits: 4
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum OtarType3ElemId {
+    SealedKey = 1,
+    Proprietary = 15,
+}
+
+impl std::convert::TryFrom<u64> for OtarType3ElemId {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            1 => Ok(OtarType3ElemId::SealedKey),
+            15 => Ok(OtarType3ElemId::Proprietary),
+            _ => Err(()),
+        }
+    }
+}
+
+impl OtarType3ElemId {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        self as u64
+    }
+}
+
+impl From<OtarType3ElemId> for u64 {
+    fn from(e: OtarType3ElemId) -> Self { e.into_raw() }
+}
+
+impl core::fmt::Display for OtarType3ElemId {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Debug::fmt(self, f)
+    }
+}
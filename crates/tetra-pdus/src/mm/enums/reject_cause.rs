@@ -0,0 +1,107 @@
+/// Clause 16.10.42 Reject cause
+/// Bits: 5
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+pub enum RejectCause {
+    ItsiAtsiUnknown = 1,
+    IllegalMs = 2,
+    LaNotAllowed = 3,
+    LaUnknown = 4,
+    NetworkFailure = 5,
+    Congestion = 6,
+    ForwardRegistrationFailure = 7,
+    ServiceNotSubscribed = 8,
+    MandatoryElementError = 9,
+    MessageConsistencyError = 10,
+    RoamingNotSupported = 11,
+    MigrationNotSupported = 12,
+    NoCipherKsg = 13,
+    IdentifiedCipherKsgNotSupported = 14,
+    RequestedCipherKeyTypeNotAvailable = 15,
+    IdentifiedCipherKeyNotAvailable = 16,
+    CipheringRequired = 18,
+    AuthenticationFailure = 19,
+}
+
+impl std::convert::TryFrom<u64> for RejectCause {
+    type Error = ();
+    fn try_from(x: u64) -> Result<Self, Self::Error> {
+        match x {
+            1 => Ok(RejectCause::ItsiAtsiUnknown),
+            2 => Ok(RejectCause::IllegalMs),
+            3 => Ok(RejectCause::LaNotAllowed),
+            4 => Ok(RejectCause::LaUnknown),
+            5 => Ok(RejectCause::NetworkFailure),
+            6 => Ok(RejectCause::Congestion),
+            7 => Ok(RejectCause::ForwardRegistrationFailure),
+            8 => Ok(RejectCause::ServiceNotSubscribed),
+            9 => Ok(RejectCause::MandatoryElementError),
+            10 => Ok(RejectCause::MessageConsistencyError),
+            11 => Ok(RejectCause::RoamingNotSupported),
+            12 => Ok(RejectCause::MigrationNotSupported),
+            13 => Ok(RejectCause::NoCipherKsg),
+            14 => Ok(RejectCause::IdentifiedCipherKsgNotSupported),
+            15 => Ok(RejectCause::RequestedCipherKeyTypeNotAvailable),
+            16 => Ok(RejectCause::IdentifiedCipherKeyNotAvailable),
+            18 => Ok(RejectCause::CipheringRequired),
+            19 => Ok(RejectCause::AuthenticationFailure),
+            _ => Err(()),
+        }
+    }
+}
+
+impl RejectCause {
+    /// Convert this enum back into the raw integer value
+    pub fn into_raw(self) -> u64 {
+        match self {
+            RejectCause::ItsiAtsiUnknown => 1,
+            RejectCause::IllegalMs => 2,
+            RejectCause::LaNotAllowed => 3,
+            RejectCause::LaUnknown => 4,
+            RejectCause::NetworkFailure => 5,
+            RejectCause::Congestion => 6,
+            RejectCause::ForwardRegistrationFailure => 7,
+            RejectCause::ServiceNotSubscribed => 8,
+            RejectCause::MandatoryElementError => 9,
+            RejectCause::MessageConsistencyError => 10,
+            RejectCause::RoamingNotSupported => 11,
+            RejectCause::MigrationNotSupported => 12,
+            RejectCause::NoCipherKsg => 13,
+            RejectCause::IdentifiedCipherKsgNotSupported => 14,
+            RejectCause::RequestedCipherKeyTypeNotAvailable => 15,
+            RejectCause::IdentifiedCipherKeyNotAvailable => 16,
+            RejectCause::CipheringRequired => 18,
+            RejectCause::AuthenticationFailure => 19,
+        }
+    }
+}
+
+impl From<RejectCause> for u64 {
+    fn from(e: RejectCause) -> Self { e.into_raw() }
+}
+
+impl core::fmt::Display for RejectCause {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RejectCause::ItsiAtsiUnknown => write!(f, "ItsiAtsiUnknown"),
+            RejectCause::IllegalMs => write!(f, "IllegalMs"),
+            RejectCause::LaNotAllowed => write!(f, "LaNotAllowed"),
+            RejectCause::LaUnknown => write!(f, "LaUnknown"),
+            RejectCause::NetworkFailure => write!(f, "NetworkFailure"),
+            RejectCause::Congestion => write!(f, "Congestion"),
+            RejectCause::ForwardRegistrationFailure => write!(f, "ForwardRegistrationFailure"),
+            RejectCause::ServiceNotSubscribed => write!(f, "ServiceNotSubscribed"),
+            RejectCause::MandatoryElementError => write!(f, "MandatoryElementError"),
+            RejectCause::MessageConsistencyError => write!(f, "MessageConsistencyError"),
+            RejectCause::RoamingNotSupported => write!(f, "RoamingNotSupported"),
+            RejectCause::MigrationNotSupported => write!(f, "MigrationNotSupported"),
+            RejectCause::NoCipherKsg => write!(f, "NoCipherKsg"),
+            RejectCause::IdentifiedCipherKsgNotSupported => write!(f, "IdentifiedCipherKsgNotSupported"),
+            RejectCause::RequestedCipherKeyTypeNotAvailable => write!(f, "RequestedCipherKeyTypeNotAvailable"),
+            RejectCause::IdentifiedCipherKeyNotAvailable => write!(f, "IdentifiedCipherKeyNotAvailable"),
+            RejectCause::CipheringRequired => write!(f, "CipheringRequired"),
+            RejectCause::AuthenticationFailure => write!(f, "AuthenticationFailure"),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 /// 16.10.48a Status uplink (also B.3.11 Status uplink in ETSI EN 300 396-5, Gateway air interface)
 /// Bits: 6
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum StatusUplink {
     ChangeOfEnergySavingModeRequest = 1,
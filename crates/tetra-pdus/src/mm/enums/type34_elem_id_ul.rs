@@ -1,6 +1,7 @@
 /// Clause 16.10.39 MM PDU types
 /// Bits: 3
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum MmType34ElemIdUl {
     GroupIdentityLocationDemand = 3,
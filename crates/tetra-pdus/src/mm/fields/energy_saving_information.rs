@@ -7,13 +7,14 @@ use crate::mm::enums::energy_saving_mode::EnergySavingMode;
 
 /// 16.10.10 Energy saving information
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EnergySavingInformation {
     // 3
     pub energy_saving_mode: EnergySavingMode,
-    // 2, when energy saving mode is "Stay alive" this field has no meaning and is set to 0
+    // 5, when energy saving mode is "Stay alive" this field has no meaning and is set to 0
     pub frame_number: Option<u8>,
-    // 2, when energy saving mode is "Stay alive" this field has no meaning and is set to 0
+    // 6, when energy saving mode is "Stay alive" this field has no meaning and is set to 0
     pub multiframe_number: Option<u8>,
 }
 
@@ -22,8 +23,8 @@ impl EnergySavingInformation {
         let val = buffer.read_field(3, "energy_saving_mode")? as u8;        
         let energy_saving_mode = EnergySavingMode::try_from(val as u64).unwrap(); // Never fails
 
-        let fn_val = buffer.read_field(2, "frame_number")? as u8;
-        let mn_val = buffer.read_field(2, "multiframe_number")? as u8;
+        let fn_val = buffer.read_field(5, "frame_number")? as u8;
+        let mn_val = buffer.read_field(6, "multiframe_number")? as u8;
 
         // Sanity check
         let (f, m) = if energy_saving_mode == EnergySavingMode::StayAlive {
@@ -33,9 +34,9 @@ impl EnergySavingInformation {
             if mn_val != 0 {
                 return Err(PduParseErr::InvalidValue{field: "multiframe_number", value: mn_val as u64});
             }
-            (Some(fn_val), Some(mn_val))
-        } else {
             (None, None)
+        } else {
+            (Some(fn_val), Some(mn_val))
         };
 
         let s = EnergySavingInformation {
@@ -58,15 +59,15 @@ impl EnergySavingInformation {
             if let Some(f) = self.multiframe_number {
                 return Err(PduParseErr::InvalidValue{field: "multiframe_number", value: f as u64});
             }
-            buf.write_bits(0, 2+2);
+            buf.write_bits(0, 5+6);
         } else {
             if let Some(f) = self.frame_number {
-                buf.write_bits(f as u64, 2);
+                buf.write_bits(f as u64, 5);
             } else {
                 return Err(PduParseErr::FieldNotPresent{field: Some("frame_number")});
             }
             if let Some(f) = self.multiframe_number {
-                buf.write_bits(f as u64, 2);
+                buf.write_bits(f as u64, 6);
             } else {
                 return Err(PduParseErr::FieldNotPresent{field: Some("multiframe_number")});  
             }
@@ -86,3 +87,30 @@ impl fmt::Display for EnergySavingInformation {
     }
 }
 
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_sleep_cycle_from_raw() {
+        // Raw 14-bit element: Eg4, starting at frame 3 of multiframe 10
+        let raw: u64 = (4 << 11) | (3 << 6) | 10;
+        let mut buf = BitBuffer::new_autoexpand(14);
+        buf.write_bits(raw, 14);
+        buf.seek(0);
+
+        let esi = EnergySavingInformation::from_bitbuf(&mut buf).unwrap();
+        assert_eq!(esi.energy_saving_mode, EnergySavingMode::Eg4);
+        assert_eq!(esi.frame_number, Some(3));
+        assert_eq!(esi.multiframe_number, Some(10));
+        assert_eq!(esi.energy_saving_mode.sleep_frames(), 8);
+        assert_eq!(esi.energy_saving_mode.cycle_frames(), 9);
+
+        // Raw 3-bit energy saving mode element
+        let esm = EnergySavingMode::try_from(7).unwrap();
+        assert_eq!(esm.cycle_frames(), 360);
+        assert_eq!(EnergySavingMode::StayAlive.sleep_frames(), 0);
+    }
+}
@@ -4,7 +4,8 @@ use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
 
 
 /// 16.10.19 Group Identity Attachment
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GroupIdentityAttachment {
     /// 2 bits. 
     /// 0: Attachment not needed
@@ -6,7 +6,8 @@ use crate::mm::fields::group_identity_attachment::GroupIdentityAttachment;
 
 
 /// 16.10.22 Group identity downlink
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GroupIdentityDownlink {
     // 1
     // pub attach_detach_type_identifier: u8,
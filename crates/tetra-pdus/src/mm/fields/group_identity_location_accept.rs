@@ -7,7 +7,8 @@ use crate::mm::{enums::type34_elem_id_dl::MmType34ElemIdDl, fields::group_identi
 
 /// Representation of the Group identity location accept PDU (Clause 16.10.23).
 /// The group identity location accept information element shall be a collection of sub elements.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GroupIdentityLocationAccept {
     /// Type1, 1 bit. 0 = accept, 1 = reject
     pub group_identity_accept_reject: u8,
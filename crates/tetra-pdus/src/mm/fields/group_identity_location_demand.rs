@@ -15,7 +15,8 @@ use crate::mm::fields::group_identity_uplink::GroupIdentityUplink;
 /// Response expected: 
 /// Response to: 
 
-#[derive(Debug)] 
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GroupIdentityLocationDemand {
     /// Type1, 1 bits, reserved
     // pub reserved: bool,
@@ -4,7 +4,8 @@ use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
 
 
 /// 16.10.27 Group identity uplink
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GroupIdentityUplink {
     // 1
     // pub attach_detach_type_identifier: bool,
@@ -0,0 +1,59 @@
+use core::fmt;
+
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+
+/// EN 300 392-7 key association, identifying the SCK or GCK an OTAR exchange refers to.
+/// A 1-bit key association type selects between a 5-bit SCK number and a 16-bit GCK number,
+/// followed by the 16-bit key version number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeyAssociation {
+    /// Static cipher key, 5 bits SCK number (SCKN)
+    Sck { sck_number: u8, key_version_number: u16 },
+    /// Group cipher key, 16 bits GCK number (GCKN)
+    Gck { gck_number: u16, key_version_number: u16 },
+}
+
+impl KeyAssociation {
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let is_gck = buffer.read_field(1, "key_association_type")? == 1;
+        if is_gck {
+            let gck_number = buffer.read_field(16, "gck_number")? as u16;
+            let key_version_number = buffer.read_field(16, "key_version_number")? as u16;
+            Ok(KeyAssociation::Gck { gck_number, key_version_number })
+        } else {
+            let sck_number = buffer.read_field(5, "sck_number")? as u8;
+            let key_version_number = buffer.read_field(16, "key_version_number")? as u16;
+            Ok(KeyAssociation::Sck { sck_number, key_version_number })
+        }
+    }
+
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        match *self {
+            KeyAssociation::Sck { sck_number, key_version_number } => {
+                if sck_number >= 32 {
+                    return Err(PduParseErr::InvalidValue { field: "sck_number", value: sck_number as u64 });
+                }
+                buffer.write_bits(0, 1);
+                buffer.write_bits(sck_number as u64, 5);
+                buffer.write_bits(key_version_number as u64, 16);
+            }
+            KeyAssociation::Gck { gck_number, key_version_number } => {
+                buffer.write_bits(1, 1);
+                buffer.write_bits(gck_number as u64, 16);
+                buffer.write_bits(key_version_number as u64, 16);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for KeyAssociation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeyAssociation::Sck { sck_number, key_version_number } => write!(f, "SCKN {} KSV {}", sck_number, key_version_number),
+            KeyAssociation::Gck { gck_number, key_version_number } => write!(f, "GCKN {} KSV {}", gck_number, key_version_number),
+        }
+    }
+}
@@ -4,3 +4,6 @@ pub mod group_identity_downlink;
 pub mod group_identity_location_accept;
 pub mod group_identity_location_demand;
 pub mod group_identity_uplink;
+pub mod key_association;
+pub mod sealed_key;
+pub mod subscriber_class;
@@ -0,0 +1,36 @@
+use core::fmt;
+
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+
+/// EN 300 392-7 sealed key, a 120-bit key encrypted for transport over the air.
+/// The contents are carried opaquely; unsealing is out of scope of the PDU layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SealedKey(pub u128);
+
+impl SealedKey {
+    pub const BITS: usize = 120;
+
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+        let hi = buffer.read_field(Self::BITS / 2, "sealed_key")? as u128;
+        let lo = buffer.read_field(Self::BITS / 2, "sealed_key")? as u128;
+        Ok(SealedKey((hi << (Self::BITS / 2)) | lo))
+    }
+
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        if self.0 >> Self::BITS != 0 {
+            return Err(PduParseErr::Inconsistency { field: "sealed_key", reason: "exceeds 120 bits" });
+        }
+        let half = Self::BITS / 2;
+        buffer.write_bits((self.0 >> half) as u64, half);
+        buffer.write_bits((self.0 & ((1u128 << half) - 1)) as u64, half);
+        Ok(())
+    }
+}
+
+impl fmt::Display for SealedKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:030x}", self.0)
+    }
+}
@@ -0,0 +1,48 @@
+use core::fmt;
+
+
+/// 16.10.45 Subscriber class (also 18.5.23 in D-MLE-SYSINFO)
+/// 16-bit bitmap, each bit representing one of the 16 subscriber classes.
+/// For an MS, a set bit means the MS is a member of that class.
+/// For a cell, a set bit means members of that class are allowed to access the cell.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SubscriberClass(pub u16);
+
+impl SubscriberClass {
+    /// Returns true if at least one of the classes of this MS is allowed by the cell bitmap `cell_classes`
+    pub fn is_allowed(&self, cell_classes: u16) -> bool {
+        self.0 & cell_classes != 0
+    }
+}
+
+impl fmt::Display for SubscriberClass {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "SubscriberClass({:#06x})", self.0)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subscriber_class_allowed() {
+        // Single overlapping class
+        assert!(SubscriberClass(0x0001).is_allowed(0x0001));
+        // MS in several classes, only one of which is allowed
+        assert!(SubscriberClass(0x8003).is_allowed(0x8000));
+        // Cell open to all classes
+        assert!(SubscriberClass(0x0400).is_allowed(0xFFFF));
+    }
+
+    #[test]
+    fn test_subscriber_class_denied() {
+        assert!(!SubscriberClass(0x0001).is_allowed(0x0002));
+        assert!(!SubscriberClass(0x00F0).is_allowed(0xFF0F));
+        // Cell closed to all classes, or MS without any class
+        assert!(!SubscriberClass(0xFFFF).is_allowed(0x0000));
+        assert!(!SubscriberClass(0x0000).is_allowed(0xFFFF));
+    }
+}
@@ -15,7 +15,8 @@ use crate::mm::fields::group_identity_downlink::GroupIdentityDownlink;
 /// Response to: -/U-ATTACH/DETACH GROUP IDENTITY (report request)
 
 // note 1: The MS shall accept the type 3/4 information elements both in the numerical order as described in annex E and in the order shown in this table.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DAttachDetachGroupIdentity {
     /// Type1, 1 bits, Group identity report
     pub group_identity_report: bool,
@@ -15,7 +15,8 @@ use crate::mm::fields::group_identity_downlink::GroupIdentityDownlink;
 /// Response to: U-ATTACH/DETACH GROUP IDENTITY
 
 // Note: The MS shall accept the type 3/4 information elements both in the numerical order as described in annex E and in the order shown in this table.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DAttachDetachGroupIdentityAcknowledgement {
     /// Type1, 1 bits, Group identity accept/reject
     pub group_identity_accept_reject: u8,
@@ -155,4 +156,14 @@ mod tests {
         tracing::info!("Serialized: {}", buf_out.dump_bin());
         assert_eq!(buf_out.to_bitstr(), test_vec);
     }
+
+    #[test]
+    fn test_num_elems_exceeding_element_length() {
+        // As above, but announcing 63 group identity downlink elements in the space of one
+        debug::setup_logging_verbose();
+        let test_vec = "10110011011100000100110111111011100000000110101000110011100000";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let result = DAttachDetachGroupIdentityAcknowledgement::from_bitbuf(&mut buf_in);
+        assert_eq!(result, Err(PduParseErr::Inconsistency { field: "type4 num_elems", reason: "more elements than fit in the element length" }));
+    }
 }
@@ -0,0 +1,133 @@
+use core::fmt;
+
+use tetra_core::{expect_pdu_type, expect_value};
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+use tetra_core::typed_pdu_fields::*;
+
+use crate::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use crate::mm::enums::status_downlink::StatusDownlink;
+use crate::mm::enums::type34_elem_id_dl::MmType34ElemIdDl;
+use crate::mm::fields::energy_saving_information::EnergySavingInformation;
+
+
+/// Representation of the D-CHANGE OF ENERGY SAVING MODE REQUEST PDU (Clause 16.9.2.5.1).
+/// This is a D-MM STATUS sub-PDU. The infrastructure sends it to the MS to command a new energy saving mode,
+/// together with the frame and multiframe at which the new energy economy cycle starts.
+/// Response expected: U-CHANGE OF ENERGY SAVING MODE RESPONSE
+/// Response to: -
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DChangeOfEnergySavingModeRequest {
+    /// Type1, 14 bits, Energy saving information
+    pub energy_saving_information: EnergySavingInformation,
+    /// Type3, Proprietary
+    pub proprietary: Option<Type3FieldGeneric>,
+}
+
+impl DChangeOfEnergySavingModeRequest {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, MmPduTypeDl::DMmStatus)?;
+
+        // Type1
+        let status_downlink = buffer.read_field(6, "status_downlink")?;
+        expect_value!(status_downlink, StatusDownlink::ChangeOfEnergySavingModeRequest.into_raw())?;
+
+        // Type1
+        let energy_saving_information = EnergySavingInformation::from_bitbuf(buffer)?;
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let mut obit = delimiters::read_obit(buffer)?;
+
+        // Type3
+        let proprietary = typed::parse_type3_generic(obit, buffer, MmType34ElemIdDl::Proprietary)?;
+
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        if obit {
+            return Err(PduParseErr::InvalidTrailingMbitValue);
+        }
+
+        Ok(DChangeOfEnergySavingModeRequest {
+            energy_saving_information,
+            proprietary,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(MmPduTypeDl::DMmStatus.into_raw(), 4);
+        // Type1
+        buffer.write_bits(StatusDownlink::ChangeOfEnergySavingModeRequest.into_raw(), 6);
+        // Type1
+        self.energy_saving_information.to_bitbuf(buffer)?;
+
+        // Check if any optional field present and place o-bit
+        let obit = self.proprietary.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit { return Ok(()); }
+
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.proprietary, MmType34ElemIdDl::Proprietary)?;
+
+        // Write terminating m-bit
+        delimiters::write_mbit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for DChangeOfEnergySavingModeRequest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DChangeOfEnergySavingModeRequest {{ energy_saving_information: {} proprietary: {:?} }}",
+            self.energy_saving_information,
+            self.proprietary,
+        )
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use crate::mm::enums::energy_saving_mode::EnergySavingMode;
+
+    use super::*;
+
+    #[test]
+    fn test_d_change_of_energy_saving_mode_request() {
+
+        // 1100 000001 011 00100 001010 0
+        // |--|                             pdu type: D-MM STATUS
+        //      |----|                      status downlink: change of energy saving mode request
+        //             |-|                  energy saving mode: Eg3
+        //                 |---|            frame number: 4
+        //                       |----|     multiframe number: 10
+        //                              |   obit
+        debug::setup_logging_verbose();
+        let test_vec = "1100000001011001000010100";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = DChangeOfEnergySavingModeRequest::from_bitbuf(&mut buf_in).expect("Failed parsing");
+
+        tracing::info!("Parsed: {:?}", pdu);
+        assert!(buf_in.get_len_remaining() == 0, "Buffer not fully consumed");
+        assert_eq!(pdu.energy_saving_information.energy_saving_mode, EnergySavingMode::Eg3);
+        assert_eq!(pdu.energy_saving_information.frame_number, Some(4));
+        assert_eq!(pdu.energy_saving_information.multiframe_number, Some(10));
+        assert_eq!(pdu.proprietary, None);
+
+        let mut buf_out = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+
+    #[test]
+    fn test_d_change_of_energy_saving_mode_request_rejects_other_status() {
+        // D-MM STATUS carrying a change of energy saving mode response instead
+        let mut buf_in = BitBuffer::from_bitstr("1100000010011001000010100");
+        assert!(DChangeOfEnergySavingModeRequest::from_bitbuf(&mut buf_in).is_err());
+    }
+}
@@ -0,0 +1,192 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+use tetra_core::typed_pdu_fields::*;
+use tetra_core::pdu_log::AddressedPdu;
+
+use crate::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use crate::mm::enums::type34_elem_id_dl::MmType34ElemIdDl;
+
+
+/// Representation of the D-ENABLE PDU (EN 300 392-7, enable and disable).
+/// The infrastructure sends this message to the MS to enable its temporarily disabled
+/// equipment, subscription, or both.
+/// Response expected: U-DISABLE STATUS
+/// Response to: -
+
+// note 1: TETRA equipment identity is present if Equipment enable is set to 1.
+// note 2: Address extension and SSI are present if Subscription enable is set to 1.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DEnable {
+    /// Type1, 1 bits, Intent/confirm. 0 = intent, 1 = confirm
+    pub intent_confirm: bool,
+    /// Type1, 1 bits, Equipment enable
+    pub equipment_enable: bool,
+    /// Conditional 60 bits, See note 1,
+    pub tetra_equipment_identity: Option<u64>,
+    /// Type1, 1 bits, Subscription enable
+    pub subscription_enable: bool,
+    /// Conditional 24 bits, See note 2,
+    pub address_extension: Option<u64>,
+    /// Conditional 24 bits, See note 2,
+    pub ssi: Option<u64>,
+    /// Type3, Authentication downlink
+    pub authentication_downlink: Option<Type3FieldGeneric>,
+    /// Type3, Proprietary
+    pub proprietary: Option<Type3FieldGeneric>,
+}
+
+impl DEnable {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, MmPduTypeDl::DEnable)?;
+
+        // Type1
+        let intent_confirm = buffer.read_field(1, "intent_confirm")? != 0;
+        // Type1
+        let equipment_enable = buffer.read_field(1, "equipment_enable")? != 0;
+        // Conditional
+        let tetra_equipment_identity = if equipment_enable {
+            Some(buffer.read_field(60, "tetra_equipment_identity")?)
+        } else { None };
+        // Type1
+        let subscription_enable = buffer.read_field(1, "subscription_enable")? != 0;
+        // Conditional
+        let address_extension = if subscription_enable {
+            Some(buffer.read_field(24, "address_extension")?)
+        } else { None };
+        // Conditional
+        let ssi = if subscription_enable {
+            Some(buffer.read_field(24, "ssi")?)
+        } else { None };
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let mut obit = delimiters::read_obit(buffer)?;
+
+        // Type3
+        let authentication_downlink = typed::parse_type3_generic(obit, buffer, MmType34ElemIdDl::AuthenticationDownlink)?;
+        // Type3
+        let proprietary = typed::parse_type3_generic(obit, buffer, MmType34ElemIdDl::Proprietary)?;
+
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        if obit {
+            return Err(PduParseErr::InvalidTrailingMbitValue);
+        }
+
+        Ok(DEnable {
+            intent_confirm,
+            equipment_enable,
+            tetra_equipment_identity,
+            subscription_enable,
+            address_extension,
+            ssi,
+            authentication_downlink,
+            proprietary,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        if self.equipment_enable != self.tetra_equipment_identity.is_some() {
+            return Err(PduParseErr::Inconsistency { field: "tetra_equipment_identity", reason: "must be present iff equipment_enable is set" });
+        }
+        if self.subscription_enable != (self.address_extension.is_some() && self.ssi.is_some()) {
+            return Err(PduParseErr::Inconsistency { field: "ssi", reason: "address_extension and ssi must be present iff subscription_enable is set" });
+        }
+
+        // PDU Type
+        buffer.write_bits(MmPduTypeDl::DEnable.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.intent_confirm as u64, 1);
+        // Type1
+        buffer.write_bits(self.equipment_enable as u64, 1);
+        // Conditional
+        if let Some(ref value) = self.tetra_equipment_identity {
+            buffer.write_bits(*value, 60);
+        }
+        // Type1
+        buffer.write_bits(self.subscription_enable as u64, 1);
+        // Conditional
+        if let Some(ref value) = self.address_extension {
+            buffer.write_bits(*value, 24);
+        }
+        // Conditional
+        if let Some(ref value) = self.ssi {
+            buffer.write_bits(*value, 24);
+        }
+
+        // Check if any optional field present and place o-bit
+        let obit = self.authentication_downlink.is_some() || self.proprietary.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit { return Ok(()); }
+
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.authentication_downlink, MmType34ElemIdDl::AuthenticationDownlink)?;
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.proprietary, MmType34ElemIdDl::Proprietary)?;
+
+        // Write terminating m-bit
+        delimiters::write_mbit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for DEnable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DEnable {{ intent_confirm: {:?} equipment_enable: {:?} tetra_equipment_identity: {:?} subscription_enable: {:?} address_extension: {:?} ssi: {:?} authentication_downlink: {:?} proprietary: {:?} }}",
+            self.intent_confirm,
+            self.equipment_enable,
+            self.tetra_equipment_identity,
+            self.subscription_enable,
+            self.address_extension,
+            self.ssi,
+            self.authentication_downlink,
+            self.proprietary,
+        )
+    }
+}
+
+impl AddressedPdu for DEnable {
+    /// SSI of the addressed subscription
+    fn addressed_ssi(&self) -> Option<u32> {
+        self.ssi.map(|ssi| ssi as u32)
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_d_enable() {
+        debug::setup_logging_verbose();
+        let pdu = DEnable {
+            intent_confirm: true,
+            subscription_enable: true,
+            address_extension: Some((204 << 14) | 1337),
+            ssi: Some(2040814),
+            authentication_downlink: Some(Type3FieldGeneric { field_id: MmType34ElemIdDl::AuthenticationDownlink.into_raw(), len: 16, data: 0xbeef }),
+            ..Default::default()
+        };
+
+        let mut buf = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        buf.seek(0);
+        let parsed = DEnable::from_bitbuf(&mut buf).expect("Failed parsing");
+        tracing::info!("Parsed: {}", parsed);
+        assert_eq!(buf.get_len_remaining(), 0);
+        assert_eq!(parsed, pdu);
+
+        // A D-DISABLE is not accepted as D-ENABLE
+        let mut buf = BitBuffer::from_bitstr("00110000");
+        assert!(DEnable::from_bitbuf(&mut buf).is_err());
+    }
+}
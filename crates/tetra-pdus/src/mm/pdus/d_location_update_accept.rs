@@ -3,6 +3,7 @@ use core::fmt;
 use tetra_core::expect_pdu_type;
 use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
 use tetra_core::typed_pdu_fields::*;
+use tetra_core::pdu_log::AddressedPdu;
 
 use crate::mm::enums::location_update_type::LocationUpdateType;
 use crate::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
@@ -17,7 +18,8 @@ use crate::mm::fields::group_identity_location_accept::GroupIdentityLocationAcce
 /// Response to: U-LOCATION UPDATE DEMAND
 
 // Note: The MS shall accept the type 3/4 information elements both in the numerical order as described in annex E and in the order shown in this table.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DLocationUpdateAccept {
     /// Type1, 3 bits, Location update accept type
     pub location_update_accept_type: LocationUpdateType,
@@ -49,6 +51,28 @@ pub struct DLocationUpdateAccept {
     pub proprietary: Option<Type3FieldGeneric>,
 }
 
+impl Default for DLocationUpdateAccept {
+    /// Plain roaming location update acceptance, without any optional elements
+    fn default() -> Self {
+        Self {
+            location_update_accept_type: LocationUpdateType::RoamingLocationUpdating,
+            ssi: None,
+            address_extension: None,
+            subscriber_class: None,
+            energy_saving_information: None,
+            scch_information_and_distribution_on_18th_frame: None,
+            new_registered_area: None,
+            security_downlink: None,
+            group_identity_location_accept: None,
+            default_group_attachment_lifetime: None,
+            authentication_downlink: None,
+            group_identity_security_related_information: None,
+            cell_type_control: None,
+            proprietary: None,
+        }
+    }
+}
+
 #[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
 impl DLocationUpdateAccept {
     /// Parse from BitBuffer
@@ -198,6 +222,13 @@ impl DLocationUpdateAccept {
     }
 }
 
+impl AddressedPdu for DLocationUpdateAccept {
+    /// (V)ASSI assigned to the MS, if any
+    fn addressed_ssi(&self) -> Option<u32> {
+        self.ssi.map(|ssi| ssi as u32)
+    }
+}
+
 impl fmt::Display for DLocationUpdateAccept {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "DLocationUpdateAccept {{ location_update_accept_type: {:?} ssi: {:?} address_extension: {:?} subscriber_class: {:?} energy_saving_information: {:?} scch_information_and_distribution_on_18th_frame: {:?} new_registered_area: {:?} security_downlink: {:?} group_identity_location_accept: {:?} default_group_attachment_lifetime: {:?} authentication_downlink: {:?} group_identity_security_related_information: {:?} cell_type_control: {:?} proprietary: {:?} }}",
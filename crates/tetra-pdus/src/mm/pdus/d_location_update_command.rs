@@ -13,7 +13,8 @@ use crate::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
 /// Response to: -
 
 // note 1: Ciphering parameters element is not present if Cipher control is set to ‘0’ and is present if set to ‘1’.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DLocationUpdateCommand {
     /// Type1, 1 bits, Group identity report
     pub group_identity_report: bool,
@@ -3,6 +3,7 @@ use core::fmt;
 use tetra_core::expect_pdu_type;
 use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
 use tetra_core::typed_pdu_fields::*;
+use tetra_core::pdu_log::AddressedPdu;
 
 use crate::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
 use crate::mm::enums::type34_elem_id_dl::MmType34ElemIdDl;
@@ -13,7 +14,8 @@ use crate::mm::enums::type34_elem_id_dl::MmType34ElemIdDl;
 /// Response expected: -
 /// Response to: U-LOCATION UPDATE DEMAND
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DLocationUpdateProceeding {
     /// Type1, 24 bits, (V)ASSI of the MS,
     pub ssi: u32,
@@ -78,6 +80,13 @@ impl DLocationUpdateProceeding {
     }
 }
 
+impl AddressedPdu for DLocationUpdateProceeding {
+    /// Assigned SSI
+    fn addressed_ssi(&self) -> Option<u32> {
+        Some(self.ssi)
+    }
+}
+
 impl fmt::Display for DLocationUpdateProceeding {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "DLocationUpdateProceeding {{ ssi: {:?} address_extension: {:?} proprietary: {:?} }}",
@@ -15,7 +15,8 @@ use crate::mm::enums::type34_elem_id_dl::MmType34ElemIdDl;
 
 // note 1: Information element "Ciphering parameters" is not present if "Cipher control" is set to "0", "ciphering off".
 // note 2: Information element "Ciphering parameters" is present if "Cipher control" is set to "1", "ciphering on".
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DLocationUpdateReject {
     /// Type1, 3 bits, Location update type
     pub location_update_type: u8,
@@ -33,8 +34,6 @@ pub struct DLocationUpdateReject {
     pub proprietary: Option<Type3FieldGeneric>,
 }
 
-#[allow(unreachable_code)] // TODO FIXME review, finalize and remove this
-#[allow(unused_variables)]
 impl DLocationUpdateReject {
     /// Parse from BitBuffer
     pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
@@ -49,7 +48,9 @@ impl DLocationUpdateReject {
         // Type1
         let cipher_control = buffer.read_field(1, "cipher_control")? != 0;
         // Conditional
-        unimplemented!(); let ciphering_parameters = if true { Some(0) } else { None };
+        let ciphering_parameters = if cipher_control { 
+            Some(buffer.read_field(10, "ciphering_parameters")?) 
+        } else { None };
 
         // obit designates presence of any further type2, type3 or type4 fields
         let mut obit = delimiters::read_obit(buffer)?;
@@ -15,7 +15,8 @@ use crate::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
 // note 1: This information element shall indicate the requested service or a response to a request and the sub-type of the D-MM STATUS PDU.
 // note 2: This information element or set of information elements shall be as defined by the status downlink information element, refer to clauses 16.9.2.5.1 to 16.9.2.5.7.
 // note 3: This Status downlink element indicates which sub-PDU this D-MM STATUS PDU contains. If the receiving party does not support the indicated function but recognizes the PDU structure, it should set the value to Not-supported sub-PDU type element.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DMmStatus {
     /// Type1, 6 bits, See notes 1 and 3,
     pub status_downlink: u8,
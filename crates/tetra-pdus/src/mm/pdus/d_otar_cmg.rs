@@ -0,0 +1,144 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+use tetra_core::typed_pdu_fields::*;
+
+use crate::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
+use crate::mm::enums::otar_sub_type_dl::OtarSubTypeDl;
+use crate::mm::enums::otar_type3_elem_id::OtarType3ElemId;
+use crate::mm::fields::key_association::KeyAssociation;
+use crate::mm::fields::sealed_key::SealedKey;
+
+
+/// Representation of the D-OTAR PDU (EN 300 392-7, over the air rekeying), as used to provide
+/// the SCK or GCK of a crypto management group. This is a skeleton covering the common
+/// key association and sealed key elements; the key itself is carried sealed and not interpreted.
+/// Response expected: U-OTAR
+/// Response to: -/U-OTAR
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DOtarCmg {
+    /// Type1, 4 bits, OTAR sub-type
+    pub otar_sub_type: OtarSubTypeDl,
+    /// Type1, 22 or 33 bits, Key association (SCK or GCK number and key version number)
+    pub key_association: KeyAssociation,
+    /// Type3, Sealed key
+    pub sealed_key: Option<SealedKey>,
+    /// Type3, Proprietary
+    pub proprietary: Option<Type3FieldGeneric>,
+}
+
+impl DOtarCmg {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, MmPduTypeDl::DOtar)?;
+
+        // Type1
+        let val = buffer.read_field(4, "otar_sub_type")?;
+        let Ok(otar_sub_type) = OtarSubTypeDl::try_from(val) else {
+            return Err(PduParseErr::InvalidValue { field: "otar_sub_type", value: val });
+        };
+        // Type1
+        let key_association = KeyAssociation::from_bitbuf(buffer)?;
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let mut obit = delimiters::read_obit(buffer)?;
+
+        // Type3
+        let sealed_key = typed::parse_type3_struct(obit, buffer, OtarType3ElemId::SealedKey, SealedKey::from_bitbuf)?;
+        // Type3
+        let proprietary = typed::parse_type3_generic(obit, buffer, OtarType3ElemId::Proprietary)?;
+
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        if obit {
+            return Err(PduParseErr::InvalidTrailingMbitValue);
+        }
+
+        Ok(DOtarCmg {
+            otar_sub_type,
+            key_association,
+            sealed_key,
+            proprietary,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(MmPduTypeDl::DOtar.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.otar_sub_type.into_raw(), 4);
+        // Type1
+        self.key_association.to_bitbuf(buffer)?;
+
+        // Check if any optional field present and place o-bit
+        let obit = self.sealed_key.is_some() || self.proprietary.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit { return Ok(()); }
+
+        // Type3
+        typed::write_type3_struct(obit, buffer, &self.sealed_key, OtarType3ElemId::SealedKey, SealedKey::to_bitbuf)?;
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.proprietary, OtarType3ElemId::Proprietary)?;
+
+        // Write terminating m-bit
+        delimiters::write_mbit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for DOtarCmg {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "DOtarCmg {{ otar_sub_type: {} key_association: {} sealed_key: {} proprietary: {:?} }}",
+            self.otar_sub_type,
+            self.key_association,
+            if self.sealed_key.is_some() { "present" } else { "none" },
+            self.proprietary,
+        )
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_d_otar_cmg_sck() {
+        debug::setup_logging_verbose();
+        // SCK provide for SCKN 3, KSV 1, without sealed key
+        let test_vec = "0000001000001100000000000000010";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = DOtarCmg::from_bitbuf(&mut buf_in).expect("Failed parsing");
+        tracing::info!("Parsed: {}", pdu);
+        assert_eq!(buf_in.get_len_remaining(), 0);
+        assert_eq!(pdu.otar_sub_type, OtarSubTypeDl::SckProvide);
+        assert_eq!(pdu.key_association, KeyAssociation::Sck { sck_number: 3, key_version_number: 1 });
+
+        let mut buf_out = BitBuffer::new_autoexpand(32);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+
+    #[test]
+    fn test_d_otar_cmg_gck_sealed_key() {
+        debug::setup_logging_verbose();
+        let pdu = DOtarCmg {
+            otar_sub_type: OtarSubTypeDl::GckProvide,
+            key_association: KeyAssociation::Gck { gck_number: 0x1234, key_version_number: 7 },
+            sealed_key: Some(SealedKey(0x00fe_dcba_9876_5432_10ff_eedd_ccbb_aa99 & ((1u128 << SealedKey::BITS) - 1))),
+            proprietary: None,
+        };
+        let mut buf = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        // Header, key association, o-bit, sealed key element with header, trailing m-bit
+        assert_eq!(buf.get_pos(), 4 + 4 + 33 + 1 + (16 + SealedKey::BITS) + 1);
+        crate::assert_pdu_roundtrip!(DOtarCmg, pdu);
+    }
+}
@@ -16,7 +16,8 @@ use crate::mm::enums::mm_pdu_type_dl::MmPduTypeDl;
 // note 2: In case the receiving party recognizes the PDU and the PDU contains a sub-PDU field (like in U/M-MM STATUS PDU, U/D-OTAR, U/D-ENABLE, etc.) this element contains the element indicating which sub-PDU this is.
 // note 3: The length of this element is indicated by the Length of the copied PDU element. This element is not present if the Length of the copied PDU element is not present.
 // note 4: This element contains the received PDU beginning from and excluding the PDU type element.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MmPduFunctionNotSupported {
     /// Type1, 4 bits, See note 1,
     pub not_supported_pdu_type: u8,
@@ -14,7 +14,8 @@ use crate::mm::fields::group_identity_uplink::GroupIdentityUplink;
 /// Response expected: D-ATTACH/DETACH GROUP IDENTITY ACKNOWLEDGEMENT
 /// Response to: -/D-ATTACH/DETACH GROUP IDENTITY (report request)
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UAttachDetachGroupIdentity {
     /// Type1, 1 bits, Group identity report
     pub group_identity_report: bool,
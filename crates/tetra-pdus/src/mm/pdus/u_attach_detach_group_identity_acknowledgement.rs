@@ -14,7 +14,8 @@ use crate::mm::fields::group_identity_uplink::GroupIdentityUplink;
 /// Response expected: -
 /// Response to: D-ATTACH/DETACH GROUP IDENTITY
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UAttachDetachGroupIdentityAcknowledgement {
     /// Type1, 1 bits, Group identity acknowledgement type
     pub group_identity_acknowledgement_type: bool,
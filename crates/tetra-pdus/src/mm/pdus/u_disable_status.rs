@@ -0,0 +1,156 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+use tetra_core::typed_pdu_fields::*;
+
+use crate::mm::enums::disable_status::DisableStatus;
+use crate::mm::enums::mm_pdu_type_ul::MmPduTypeUl;
+use crate::mm::enums::type34_elem_id_ul::MmType34ElemIdUl;
+
+
+/// Representation of the U-DISABLE STATUS PDU (EN 300 392-7, enable and disable).
+/// The MS sends this message to the infrastructure to report its enable/disable state.
+/// Response expected: -
+/// Response to: D-DISABLE / D-ENABLE
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UDisableStatus {
+    /// Type1, 2 bits, Equipment status
+    pub equipment_status: DisableStatus,
+    /// Type1, 2 bits, Subscription status
+    pub subscription_status: DisableStatus,
+    /// Type1, 3 bits, Enable/disable result. 0 = successful
+    pub enable_disable_result: u8,
+    /// Type3, Authentication uplink
+    pub authentication_uplink: Option<Type3FieldGeneric>,
+    /// Type3, Proprietary
+    pub proprietary: Option<Type3FieldGeneric>,
+}
+
+impl UDisableStatus {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, MmPduTypeUl::UDisableStatus)?;
+
+        // Type1
+        let val = buffer.read_field(2, "equipment_status")?;
+        let Ok(equipment_status) = DisableStatus::try_from(val) else {
+            return Err(PduParseErr::InvalidValue { field: "equipment_status", value: val });
+        };
+        // Type1
+        let val = buffer.read_field(2, "subscription_status")?;
+        let Ok(subscription_status) = DisableStatus::try_from(val) else {
+            return Err(PduParseErr::InvalidValue { field: "subscription_status", value: val });
+        };
+        // Type1
+        let enable_disable_result = buffer.read_field(3, "enable_disable_result")? as u8;
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let mut obit = delimiters::read_obit(buffer)?;
+
+        // Type3
+        let authentication_uplink = typed::parse_type3_generic(obit, buffer, MmType34ElemIdUl::AuthenticationUplink)?;
+        // Type3
+        let proprietary = typed::parse_type3_generic(obit, buffer, MmType34ElemIdUl::Proprietary)?;
+
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        if obit {
+            return Err(PduParseErr::InvalidTrailingMbitValue);
+        }
+
+        Ok(UDisableStatus {
+            equipment_status,
+            subscription_status,
+            enable_disable_result,
+            authentication_uplink,
+            proprietary,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(MmPduTypeUl::UDisableStatus.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.equipment_status.into_raw(), 2);
+        // Type1
+        buffer.write_bits(self.subscription_status.into_raw(), 2);
+        // Type1
+        buffer.write_bits(self.enable_disable_result as u64, 3);
+
+        // Check if any optional field present and place o-bit
+        let obit = self.authentication_uplink.is_some() || self.proprietary.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit { return Ok(()); }
+
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.authentication_uplink, MmType34ElemIdUl::AuthenticationUplink)?;
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.proprietary, MmType34ElemIdUl::Proprietary)?;
+
+        // Write terminating m-bit
+        delimiters::write_mbit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for UDisableStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "UDisableStatus {{ equipment_status: {} subscription_status: {} enable_disable_result: {} authentication_uplink: {:?} proprietary: {:?} }}",
+            self.equipment_status,
+            self.subscription_status,
+            self.enable_disable_result,
+            self.authentication_uplink,
+            self.proprietary,
+        )
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_u_disable_status() {
+        debug::setup_logging_verbose();
+        // Subscription temporarily disabled, successful, no type3 elements
+        let test_vec = "101100010000";
+        let mut buf_in = BitBuffer::from_bitstr(test_vec);
+        let pdu = UDisableStatus::from_bitbuf(&mut buf_in).expect("Failed parsing");
+        tracing::info!("Parsed: {}", pdu);
+        assert_eq!(buf_in.get_len_remaining(), 0);
+        assert_eq!(pdu.equipment_status, DisableStatus::Enabled);
+        assert_eq!(pdu.subscription_status, DisableStatus::TemporarilyDisabled);
+
+        let mut buf_out = BitBuffer::new_autoexpand(16);
+        pdu.to_bitbuf(&mut buf_out).unwrap();
+        assert_eq!(buf_out.to_bitstr(), test_vec);
+    }
+
+    #[test]
+    fn test_u_disable_status_with_authentication() {
+        let pdu = UDisableStatus {
+            equipment_status: DisableStatus::PermanentlyDisabled,
+            subscription_status: DisableStatus::PermanentlyDisabled,
+            enable_disable_result: 0,
+            authentication_uplink: Some(Type3FieldGeneric { field_id: MmType34ElemIdUl::AuthenticationUplink.into_raw(), len: 32, data: 0x12345678 }),
+            proprietary: None,
+        };
+        let mut buf = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        buf.seek(0);
+        assert_eq!(UDisableStatus::from_bitbuf(&mut buf).unwrap(), pdu);
+        assert_eq!(buf.get_len_remaining(), 0);
+
+        // Equipment status 3 is reserved
+        let mut buf = BitBuffer::from_bitstr("101111000000");
+        assert!(UDisableStatus::from_bitbuf(&mut buf).is_err());
+    }
+}
@@ -13,7 +13,8 @@ use crate::mm::enums::type34_elem_id_ul::MmType34ElemIdUl;
 /// Response expected: -/D-MM STATUS
 /// Response to: -
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UItsiDetach {
     /// Type2, 24 bits, MNI of the MS (MCC followed by MNC)
     pub address_extension: Option<u64>,
@@ -3,6 +3,7 @@ use core::fmt;
 use tetra_core::expect_pdu_type;
 use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
 use tetra_core::typed_pdu_fields::*;
+use tetra_core::pdu_log::AddressedPdu;
 
 use crate::mm::enums::energy_saving_mode::EnergySavingMode;
 use crate::mm::enums::location_update_type::LocationUpdateType;
@@ -18,7 +19,8 @@ use crate::mm::fields::group_identity_location_demand::GroupIdentityLocationDema
 
 // note 1: Information element "Ciphering parameters" is not present if "Cipher control" is set to "0" (ciphering off); present if set to "1" (ciphering on).
 // note 2: If the "class of MS" or the "extended capabilities" element is not included and the SwMI needs either, it may accept the request and then send a D-LOCATION UPDATE COMMAND PDU.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ULocationUpdateDemand {
     /// Type1, 3 bits, Location update type
     pub location_update_type: LocationUpdateType,
@@ -208,6 +210,13 @@ impl ULocationUpdateDemand {
     }
 }
 
+impl AddressedPdu for ULocationUpdateDemand {
+    /// Registering MS, if an SSI is given
+    fn addressed_ssi(&self) -> Option<u32> {
+        self.ssi.map(|ssi| ssi as u32)
+    }
+}
+
 impl fmt::Display for ULocationUpdateDemand {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "ULocationUpdateDemand {{ location_update_type: {:?} request_to_append_la: {:?} cipher_control: {:?} ciphering_parameters: {:?} class_of_ms: {:?} energy_saving_mode: {:?} la_information: {:?} ssi: {:?} address_extension: {:?} group_identity_location_demand: {:?} group_report_response: {:?} authentication_uplink: {:?} extended_capabilities: {:?} proprietary: {:?} }}",
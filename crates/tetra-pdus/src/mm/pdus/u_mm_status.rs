@@ -15,7 +15,8 @@ use crate::mm::enums::status_uplink::StatusUplink;
 // note 1: This information element shall indicate the requested service or a response to a request and the sub-type of the U-MM STATUS PDU.
 // note 2: This information element or set of information elements shall be as defined by the status uplink information element, refer to clauses 16.9.3.5.1 to 16.9.3.5.8.
 // note 3: This Status uplink element indicates which sub-PDU this U-MM STATUS PDU contains; in case the receiving party does not support indicated function but recognizes this PDU structure, it should set the received value of Status uplink element to Not-supported sub PDU type element.
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct UMmStatus {
     /// Type1, 6 bits, See notes 1 and 3,
     pub status_uplink: StatusUplink,
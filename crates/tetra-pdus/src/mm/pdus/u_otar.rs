@@ -0,0 +1,120 @@
+use core::fmt;
+
+use tetra_core::expect_pdu_type;
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+use tetra_core::typed_pdu_fields::*;
+
+use crate::mm::enums::mm_pdu_type_ul::MmPduTypeUl;
+use crate::mm::enums::otar_sub_type_ul::OtarSubTypeUl;
+use crate::mm::enums::otar_type3_elem_id::OtarType3ElemId;
+use crate::mm::fields::key_association::KeyAssociation;
+
+
+/// Representation of the U-OTAR PDU (EN 300 392-7, over the air rekeying), with which the MS
+/// demands an SCK or GCK, or reports the result of a key provision. This is a skeleton covering
+/// the common key association element.
+/// Response expected: -/D-OTAR
+/// Response to: -/D-OTAR
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UOtar {
+    /// Type1, 4 bits, OTAR sub-type
+    pub otar_sub_type: OtarSubTypeUl,
+    /// Type1, 22 or 33 bits, Key association (SCK or GCK number and key version number)
+    pub key_association: KeyAssociation,
+    /// Type3, Proprietary
+    pub proprietary: Option<Type3FieldGeneric>,
+}
+
+impl UOtar {
+    /// Parse from BitBuffer
+    pub fn from_bitbuf(buffer: &mut BitBuffer) -> Result<Self, PduParseErr> {
+
+        let pdu_type = buffer.read_field(4, "pdu_type")?;
+        expect_pdu_type!(pdu_type, MmPduTypeUl::UOtar)?;
+
+        // Type1
+        let val = buffer.read_field(4, "otar_sub_type")?;
+        let Ok(otar_sub_type) = OtarSubTypeUl::try_from(val) else {
+            return Err(PduParseErr::InvalidValue { field: "otar_sub_type", value: val });
+        };
+        // Type1
+        let key_association = KeyAssociation::from_bitbuf(buffer)?;
+
+        // obit designates presence of any further type2, type3 or type4 fields
+        let mut obit = delimiters::read_obit(buffer)?;
+
+        // Type3
+        let proprietary = typed::parse_type3_generic(obit, buffer, OtarType3ElemId::Proprietary)?;
+
+        // Read trailing mbit (if not previously encountered)
+        obit = if obit { buffer.read_field(1, "trailing_obit")? == 1 } else { obit };
+        if obit {
+            return Err(PduParseErr::InvalidTrailingMbitValue);
+        }
+
+        Ok(UOtar {
+            otar_sub_type,
+            key_association,
+            proprietary,
+        })
+    }
+
+    /// Serialize this PDU into the given BitBuffer.
+    pub fn to_bitbuf(&self, buffer: &mut BitBuffer) -> Result<(), PduParseErr> {
+        // PDU Type
+        buffer.write_bits(MmPduTypeUl::UOtar.into_raw(), 4);
+        // Type1
+        buffer.write_bits(self.otar_sub_type.into_raw(), 4);
+        // Type1
+        self.key_association.to_bitbuf(buffer)?;
+
+        // Check if any optional field present and place o-bit
+        let obit = self.proprietary.is_some();
+        delimiters::write_obit(buffer, obit as u8);
+        if !obit { return Ok(()); }
+
+        // Type3
+        typed::write_type3_generic(obit, buffer, &self.proprietary, OtarType3ElemId::Proprietary)?;
+
+        // Write terminating m-bit
+        delimiters::write_mbit(buffer, 0);
+        Ok(())
+    }
+}
+
+impl fmt::Display for UOtar {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "UOtar {{ otar_sub_type: {} key_association: {} proprietary: {:?} }}",
+            self.otar_sub_type,
+            self.key_association,
+            self.proprietary,
+        )
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use super::*;
+
+    #[test]
+    fn test_u_otar_gck_demand() {
+        debug::setup_logging_verbose();
+        let pdu = UOtar {
+            otar_sub_type: OtarSubTypeUl::GckDemand,
+            key_association: KeyAssociation::Gck { gck_number: 42, key_version_number: 0xffff },
+            proprietary: None,
+        };
+        let mut buf = BitBuffer::new_autoexpand(64);
+        pdu.to_bitbuf(&mut buf).unwrap();
+        assert_eq!(buf.get_pos(), 4 + 4 + 33 + 1);
+        crate::assert_pdu_roundtrip!(UOtar, pdu.clone());
+
+        // Out of range SCK number is refused
+        let pdu = UOtar { key_association: KeyAssociation::Sck { sck_number: 32, key_version_number: 0 }, ..pdu };
+        assert!(pdu.to_bitbuf(&mut BitBuffer::new_autoexpand(64)).is_err());
+    }
+}
@@ -0,0 +1,74 @@
+//! Optional timing of PDU parsing. With the `parse-timing` feature, parses taking
+//! longer than a threshold are logged, which points at pathological inputs such as
+//! huge length fields. Without it, parsing is passed through at no cost.
+
+use core::fmt;
+use std::time::Duration;
+
+use tetra_core::{BitBuffer, pdu_parse_error::PduParseErr};
+
+/// Parses slower than this are reported
+pub const SLOW_PARSE_THRESHOLD: Duration = Duration::from_millis(1);
+
+#[cfg(feature = "parse-timing")]
+static SLOW_PARSES: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+
+/// Number of parses that exceeded their threshold since startup
+#[cfg(feature = "parse-timing")]
+pub fn slow_parses() -> u64 {
+    SLOW_PARSES.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+/// Runs `parse` on `buffer`, warning if it takes longer than `SLOW_PARSE_THRESHOLD`
+#[inline(always)]
+pub fn timed_parse<T>(pdu: impl fmt::Display, buffer: &mut BitBuffer, parse: impl FnOnce(&mut BitBuffer) -> Result<T, PduParseErr>) -> Result<T, PduParseErr> {
+    timed_parse_with_threshold(pdu, SLOW_PARSE_THRESHOLD, buffer, parse)
+}
+
+/// As `timed_parse`, with a custom threshold
+#[cfg(feature = "parse-timing")]
+pub fn timed_parse_with_threshold<T>(pdu: impl fmt::Display, threshold: Duration, buffer: &mut BitBuffer, parse: impl FnOnce(&mut BitBuffer) -> Result<T, PduParseErr>) -> Result<T, PduParseErr> {
+    let len = buffer.get_len();
+    let start = std::time::Instant::now();
+    let result = parse(buffer);
+    let elapsed = start.elapsed();
+    if elapsed >= threshold {
+        SLOW_PARSES.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        tracing::warn!("Slow parse of {} ({} bits): {} us, {}", pdu, len, elapsed.as_micros(),
+            if result.is_ok() { "ok" } else { "failed" });
+    }
+    result
+}
+
+/// As `timed_parse`, with a custom threshold
+#[cfg(not(feature = "parse-timing"))]
+#[inline(always)]
+pub fn timed_parse_with_threshold<T>(_pdu: impl fmt::Display, _threshold: Duration, buffer: &mut BitBuffer, parse: impl FnOnce(&mut BitBuffer) -> Result<T, PduParseErr>) -> Result<T, PduParseErr> {
+    parse(buffer)
+}
+
+
+#[cfg(all(test, feature = "parse-timing"))]
+mod tests {
+    use crate::mm::pdus::u_attach_detach_group_identity::UAttachDetachGroupIdentity;
+
+    use super::*;
+
+    #[test]
+    fn test_slow_parse_reported() {
+        // U-ATTACH/DETACH GROUP IDENTITY whose group identity uplink element claims 2047 bits.
+        // The element length is bounded by the PDU, so parsing fails without reading past it
+        let bits = "0111011110001111111111100000100000000";
+        let result = timed_parse("UAttachDetachGroupIdentity", &mut BitBuffer::from_bitstr(bits), UAttachDetachGroupIdentity::from_bitbuf);
+        assert!(result.is_err());
+
+        // A parse exceeding the threshold is reported, and its result passed on
+        let before = slow_parses();
+        let result = timed_parse("UAttachDetachGroupIdentity", &mut BitBuffer::from_bitstr(bits), |buffer| {
+            std::thread::sleep(SLOW_PARSE_THRESHOLD);
+            UAttachDetachGroupIdentity::from_bitbuf(buffer)
+        });
+        assert!(result.is_err());
+        assert!(slow_parses() > before);
+    }
+}
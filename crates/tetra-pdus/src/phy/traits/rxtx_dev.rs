@@ -1,5 +1,7 @@
+use num_complex::Complex32;
+
 use tetra_core::TdmaTime;
-use tetra_core::TrainingSequence;
+use tetra_core::{RxQuality, TrainingSequence};
 
 
 #[derive(Debug, PartialEq, Clone, Copy)]
@@ -12,6 +14,8 @@ pub enum RxTxDevError {
 pub struct RxBurstBits<'a> {
     pub train_type: TrainingSequence,
     pub bits: &'a [u8],
+    /// Reception quality, meaningful only if a training sequence was found
+    pub quality: RxQuality,
 }
 
 #[derive(Debug, Default)]
@@ -38,7 +42,22 @@ pub struct TxSlotBits<'a> {
     // pub subslot2: Option<&'a [u8]>,
 }
 
+/// Receives a copy of the IQ samples passing through an RX/TX device,
+/// normalized to a full scale of 1.0
+pub trait SampleTap: Send {
+    /// Samples received by the device
+    fn rx_samples(&mut self, samples: &[Complex32]);
+    /// Samples handed to the device for transmission
+    fn tx_samples(&mut self, samples: &[Complex32]);
+}
+
 /// Trait for RX/TX devices that work with full slots.
 pub trait RxTxDev {
     fn rxtx_timeslot(&mut self, tx_slot: &[TxSlotBits]) -> Result<Vec<Option<RxSlotBits<'_>>>, RxTxDevError>;
+
+    /// Passes the samples received and transmitted from now on to `tap`.
+    /// Returns false if the device has no IQ samples to pass, e.g. as it only handles bits.
+    fn set_sample_tap(&mut self, _tap: Box<dyn SampleTap>) -> bool {
+        false
+    }
 }
@@ -0,0 +1,354 @@
+//! Startup self-test: builds instances of each CMCE and MM PDU that can be both serialized
+//! and parsed, and checks that they survive a round trip. The same fixtures are run as the
+//! round-trip unit tests of this crate.
+
+use tetra_core::typed_pdu_fields::Type3FieldGeneric;
+use tetra_saps::control::enums::{circuit_mode_type::CircuitModeType, communication_type::CommunicationType};
+
+use crate::cmce::enums::{call_status::CallStatus, call_timeout::CallTimeout, call_timeout_setup_phase::CallTimeoutSetupPhase, transmission_grant::TransmissionGrant};
+use crate::cmce::fields::basic_service_information::BasicServiceInformation;
+use crate::cmce::pdus::*;
+use crate::mm::enums::{disable_status::DisableStatus, energy_saving_mode::EnergySavingMode, location_update_type::LocationUpdateType, otar_sub_type_dl::OtarSubTypeDl, otar_sub_type_ul::OtarSubTypeUl, status_uplink::StatusUplink};
+use crate::mm::fields::{energy_saving_information::EnergySavingInformation, key_association::KeyAssociation, sealed_key::SealedKey, group_identity_attachment::GroupIdentityAttachment, group_identity_downlink::GroupIdentityDownlink, group_identity_location_accept::GroupIdentityLocationAccept, group_identity_location_demand::GroupIdentityLocationDemand, group_identity_uplink::GroupIdentityUplink};
+use crate::mm::pdus::*;
+
+/// Serialize a PDU with `to_bitbuf`, parse the result back with `from_bitbuf` and check
+/// that the parsed PDU equals the original, and that all written bits were consumed.
+/// Evaluates to a `Result<(), String>` describing the first failed check.
+/// Usage: `check_pdu_roundtrip!(DAlert, pdu)`
+#[macro_export]
+macro_rules! check_pdu_roundtrip {
+    ($pdu_type:ty, $pdu:expr) => {{
+        let pdu: $pdu_type = $pdu;
+        let mut buffer = tetra_core::BitBuffer::new_autoexpand(64);
+        match pdu.to_bitbuf(&mut buffer) {
+            Err(e) => Err(format!("{}: to_bitbuf failed: {:?}", stringify!($pdu_type), e)),
+            Ok(_) => {
+                let written = buffer.get_pos();
+                buffer.seek(0);
+                match <$pdu_type>::from_bitbuf(&mut buffer) {
+                    Err(e) => Err(format!("{}: from_bitbuf failed: {:?} on {}", stringify!($pdu_type), e, buffer.dump_bin())),
+                    Ok(parsed) if parsed != pdu => Err(format!("{}: round-trip mismatch: {:?} != {:?}", stringify!($pdu_type), pdu, parsed)),
+                    Ok(_) if buffer.get_pos() != written => Err(format!("{}: parsed length {} differs from written length {}",
+                        stringify!($pdu_type), buffer.get_pos(), written)),
+                    Ok(_) => Ok(()),
+                }
+            }
+        }
+    }};
+}
+
+/// Outcome of the round trip of one PDU
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelftestResult {
+    pub pdu: &'static str,
+    pub result: Result<(), String>,
+}
+
+/// Round-trips `$pdu` of type `$pdu_type` and records the outcome under the type name
+macro_rules! selftest_pdu {
+    ($results:expr, $pdu_type:ty, $pdu:expr) => {
+        $results.push(SelftestResult { pdu: stringify!($pdu_type), result: $crate::check_pdu_roundtrip!($pdu_type, $pdu) })
+    };
+}
+
+fn t3(field_id: u64, len: usize, data: u64) -> Option<Type3FieldGeneric> {
+    Some(Type3FieldGeneric { field_id, len, data })
+}
+
+fn bsi() -> BasicServiceInformation {
+    BasicServiceInformation {
+        circuit_mode_type: CircuitModeType::TchS,
+        encryption_flag: false,
+        communication_type: CommunicationType::P2Mp,
+        slots_per_frame: None,
+        speech_service: Some(0),
+    }
+}
+
+/// Runs the round trip for all fixtures. Does not stop at the first failure.
+pub fn run_selftest() -> Vec<SelftestResult> {
+    let mut results = Vec::new();
+    selftest_cmce_downlink(&mut results);
+    selftest_cmce_uplink(&mut results);
+    selftest_mm_downlink(&mut results);
+    selftest_mm_uplink(&mut results);
+    results
+}
+
+/// Round-trips the downlink CMCE PDUs
+pub(crate) fn selftest_cmce_downlink(results: &mut Vec<SelftestResult>) {
+    selftest_pdu!(results, d_alert::DAlert, d_alert::DAlert { call_identifier: 12, ..Default::default() });
+    selftest_pdu!(results, d_alert::DAlert, d_alert::DAlert {
+        call_identifier: 12, call_time_out_set_up_phase: 3, reserved: true, call_queued: true,
+        basic_service_information: Some(bsi()), notification_indicator: Some(5),
+        facility: t3(3, 8, 0xA5), proprietary: t3(15, 16, 0x1234),
+        ..Default::default()
+    });
+    selftest_pdu!(results, d_call_proceeding::DCallProceeding, d_call_proceeding::DCallProceeding {
+        call_identifier: 100, call_time_out_set_up_phase: CallTimeoutSetupPhase::T10s,
+        hook_method_selection: true, simplex_duplex_selection: false,
+        basic_service_information: Some(bsi()), call_status: Some(CallStatus::Callqueued),
+        notification_indicator: Some(1), facility: None, proprietary: t3(15, 12, 0xABC),
+    });
+    selftest_pdu!(results, d_call_restore::DCallRestore, d_call_restore::DCallRestore {
+        call_identifier: 8, transmission_grant: 1, transmission_request_permission: true,
+        new_call_identifier: Some(9), call_time_out: Some(4), modify: Some(0x1FF),
+        temporary_address: t3(5, 24, 0x123456),
+        ..Default::default()
+    });
+    selftest_pdu!(results, d_connect::DConnect, d_connect::DConnect {
+        call_identifier: 77, call_time_out: CallTimeout::T5m, hook_method_selection: false,
+        simplex_duplex_selection: false, transmission_grant: TransmissionGrant::Granted,
+        transmission_request_permission: true, call_ownership: true, call_priority: Some(3),
+        basic_service_information: None, temporary_address: Some(0xFFFFFE), notification_indicator: None,
+        facility: None, proprietary: None,
+    });
+    selftest_pdu!(results, d_connect_acknowledge::DConnectAcknowledge, d_connect_acknowledge::DConnectAcknowledge {
+        call_identifier: 77, call_time_out: 11, transmission_grant: 1, transmission_request_permission: true,
+        notification_indicator: Some(3), facility: None, proprietary: t3(15, 8, 0x42),
+    });
+    selftest_pdu!(results, d_disconnect::DDisconnect, d_disconnect::DDisconnect {
+        call_identifier: 5, disconnect_cause: 13, facility: t3(3, 4, 0x5), ..Default::default()
+    });
+    selftest_pdu!(results, d_facility::DFacility, d_facility::DFacility {});
+    selftest_pdu!(results, d_info::DInfo, d_info::DInfo {
+        call_identifier: 1, reset_call_time_out_timer_t310_: true, poll_request: true,
+        call_time_out: Some(7), call_status: Some(2), poll_response_percentage: Some(50),
+        dtmf: t3(1, 8, 0x12), poll_response_addresses: t3(4, 24, 0x00BEEF),
+        ..Default::default()
+    });
+    selftest_pdu!(results, d_release::DRelease, d_release::DRelease {
+        call_identifier: 217, disconnect_cause: 13, notification_indicator: Some(9), ..Default::default()
+    });
+    selftest_pdu!(results, d_sds_data::DSdsData, d_sds_data::DSdsData {
+        calling_party_type_identifier: 1, calling_party_address_ssi: Some(1000),
+        short_data_type_identifier: 1, user_defined_data_2: Some(0xDEADBEEF),
+        ..Default::default()
+    });
+    selftest_pdu!(results, d_sds_data::DSdsData, d_sds_data::DSdsData {
+        calling_party_type_identifier: 2, calling_party_address_ssi: Some(1000), calling_party_extension: Some(0x123456),
+        short_data_type_identifier: 2, user_defined_data_3: Some(0x0123_4567_89AB_CDEF),
+        ..Default::default()
+    });
+    selftest_pdu!(results, d_sds_data::DSdsData, d_sds_data::DSdsData {
+        calling_party_type_identifier: 1, calling_party_address_ssi: Some(7070000),
+        short_data_type_identifier: 3, length_indicator: Some(20), user_defined_data_4: Some(vec![0x82, 0x04, 0xA0]),
+        ..Default::default()
+    });
+    selftest_pdu!(results, d_setup::DSetup, d_setup::DSetup {
+        call_identifier: 4, call_time_out: CallTimeout::T5m, hook_method_selection: false,
+        simplex_duplex_selection: false, basic_service_information: bsi(),
+        transmission_grant: TransmissionGrant::GrantedToOtherUser, transmission_request_permission: false,
+        call_priority: 0, notification_indicator: None, temporary_address: None,
+        calling_party_address_ssi: Some(910001), calling_party_extension: None,
+        external_subscriber_number: None, facility: None, dm_ms_address: None, proprietary: None,
+    });
+    selftest_pdu!(results, d_setup::DSetup, d_setup::DSetup {
+        call_identifier: 4, call_time_out: CallTimeout::Infinite, hook_method_selection: true,
+        simplex_duplex_selection: true, basic_service_information: bsi(),
+        transmission_grant: TransmissionGrant::Granted, transmission_request_permission: true,
+        call_priority: 15, notification_indicator: Some(1), temporary_address: Some(0x111111),
+        calling_party_address_ssi: Some(910001), calling_party_extension: Some(0x204_0F1),
+        external_subscriber_number: None, facility: t3(3, 8, 0x11), dm_ms_address: None, proprietary: None,
+    });
+    selftest_pdu!(results, d_status::DStatus, d_status::DStatus {
+        calling_party_type_identifier: 1, calling_party_address_ssi: Some(2000), pre_coded_status: 0x8001,
+        ..Default::default()
+    });
+    selftest_pdu!(results, d_tx_ceased::DTxCeased, d_tx_ceased::DTxCeased {
+        call_identifier: 3, transmission_request_permission: true, dm_ms_address: t3(6, 24, 1), ..Default::default()
+    });
+    selftest_pdu!(results, d_tx_continue::DTxContinue, d_tx_continue::DTxContinue {
+        call_identifier: 3, do_continue: true, notification_indicator: Some(2), ..Default::default()
+    });
+    selftest_pdu!(results, d_tx_granted::DTxGranted, d_tx_granted::DTxGranted {
+        call_identifier: 3, transmission_grant: 0, transmission_request_permission: true,
+        transmitting_party_type_identifier: Some(1), transmitting_party_address_ssi: Some(2041384),
+        ..Default::default()
+    });
+    selftest_pdu!(results, d_tx_granted::DTxGranted, d_tx_granted::DTxGranted {
+        call_identifier: 3, transmission_grant: 0,
+        transmitting_party_type_identifier: Some(2), transmitting_party_address_ssi: Some(2041384),
+        transmitting_party_extension: Some(0x123456), proprietary: t3(15, 8, 1),
+        ..Default::default()
+    });
+    selftest_pdu!(results, d_tx_interrupt::DTxInterrupt, d_tx_interrupt::DTxInterrupt {
+        call_identifier: 3, transmission_grant: 2, encryption_control: true,
+        transmitting_party_type_identifier: Some(1), transmitting_party_address_ssi: Some(2041384),
+        ..Default::default()
+    });
+    selftest_pdu!(results, d_tx_wait::DTxWait, d_tx_wait::DTxWait {
+        call_identifier: 3, transmission_request_permission: false, ..Default::default()
+    });
+    selftest_pdu!(results, cmce_function_not_supported::CmceFunctionNotSupported, cmce_function_not_supported::CmceFunctionNotSupported {
+        not_supported_pdu_type: 7, call_identifier_present: true, call_identifier: Some(12),
+        function_not_supported_pointer: 0, ..Default::default()
+    });
+}
+
+/// Round-trips the uplink CMCE PDUs
+pub(crate) fn selftest_cmce_uplink(results: &mut Vec<SelftestResult>) {
+    selftest_pdu!(results, u_alert::UAlert, u_alert::UAlert {
+        call_identifier: 9, simplex_duplex_selection: true, basic_service_information: Some(bsi()), ..Default::default()
+    });
+    selftest_pdu!(results, u_call_restore::UCallRestore, u_call_restore::UCallRestore {
+        call_identifier: 9, other_party_type_identifier: 1, other_party_ssi: Some(1234), ..Default::default()
+    });
+    selftest_pdu!(results, u_connect::UConnect, u_connect::UConnect {
+        call_identifier: 9, hook_method_selection: true, facility: t3(3, 8, 0x77), ..Default::default()
+    });
+    selftest_pdu!(results, u_disconnect::UDisconnect, u_disconnect::UDisconnect {
+        call_identifier: 9, disconnect_cause: 1, ..Default::default()
+    });
+    selftest_pdu!(results, u_facility::UFacility, u_facility::UFacility {});
+    selftest_pdu!(results, u_info::UInfo, u_info::UInfo {
+        call_identifier: 9, poll_response: true, modify: Some(3), ..Default::default()
+    });
+    selftest_pdu!(results, u_release::URelease, u_release::URelease {
+        call_identifier: 9, disconnect_cause: 2, proprietary: t3(15, 9, 0x1AB), ..Default::default()
+    });
+    selftest_pdu!(results, u_sds_data::USdsData, u_sds_data::USdsData {
+        called_party_type_identifier: 1, called_party_ssi: Some(1001),
+        short_data_type_identifier: 0, user_defined_data_1: Some(0x8002),
+        ..Default::default()
+    });
+    selftest_pdu!(results, u_sds_data::USdsData, u_sds_data::USdsData {
+        called_party_type_identifier: 1, called_party_ssi: Some(1001),
+        short_data_type_identifier: 3, length_indicator: Some(16), user_defined_data_4: Some(vec![0x82, 0x04]),
+        ..Default::default()
+    });
+    selftest_pdu!(results, u_setup::USetup, u_setup::USetup {
+        area_selection: 0, hook_method_selection: false, simplex_duplex_selection: false,
+        basic_service_information: bsi(), request_to_transmit_send_data: true, call_priority: 0,
+        clir_control: 0, called_party_type_identifier: 1, called_party_short_number_address: None,
+        called_party_ssi: Some(26), called_party_extension: None,
+        external_subscriber_number: None, facility: None, dm_ms_address: None, proprietary: None,
+    });
+    selftest_pdu!(results, u_status::UStatus, u_status::UStatus {
+        called_party_type_identifier: 0, called_party_short_number_address: Some(12), pre_coded_status: 3,
+        ..Default::default()
+    });
+    selftest_pdu!(results, u_tx_ceased::UTxCeased, u_tx_ceased::UTxCeased {
+        call_identifier: 9, ..Default::default()
+    });
+    selftest_pdu!(results, u_tx_demand::UTxDemand, u_tx_demand::UTxDemand {
+        call_identifier: 9, tx_demand_priority: 2, encryption_control: false, reserved: false,
+        facility: None, dm_ms_address: None, proprietary: t3(15, 8, 0x10),
+    });
+}
+
+/// Round-trips the downlink MM PDUs
+pub(crate) fn selftest_mm_downlink(results: &mut Vec<SelftestResult>) {
+    let gid_dl = GroupIdentityDownlink {
+        group_identity_attachment: Some(GroupIdentityAttachment { group_identity_attachment_lifetime: 3, class_of_usage: 4 }),
+        group_identity_detachment_uplink: None,
+        gssi: Some(26),
+        address_extension: None,
+        vgssi: None,
+    };
+
+    selftest_pdu!(results, d_attach_detach_group_identity::DAttachDetachGroupIdentity, d_attach_detach_group_identity::DAttachDetachGroupIdentity {
+        group_identity_report: false, group_identity_acknowledgement_request: true,
+        group_identity_downlink: Some(vec![gid_dl.clone()]), ..Default::default()
+    });
+    selftest_pdu!(results, d_attach_detach_group_identity_acknowledgement::DAttachDetachGroupIdentityAcknowledgement, d_attach_detach_group_identity_acknowledgement::DAttachDetachGroupIdentityAcknowledgement {
+        group_identity_accept_reject: 0, group_identity_downlink: Some(vec![gid_dl.clone()]), ..Default::default()
+    });
+    selftest_pdu!(results, d_location_update_accept::DLocationUpdateAccept, d_location_update_accept::DLocationUpdateAccept {
+        location_update_accept_type: LocationUpdateType::ItsiAttach,
+        ssi: Some(2041384),
+        energy_saving_information: Some(EnergySavingInformation { energy_saving_mode: EnergySavingMode::Eg2, frame_number: Some(17), multiframe_number: Some(42) }),
+        group_identity_location_accept: Some(GroupIdentityLocationAccept { group_identity_accept_reject: 0, group_identity_downlink: Some(vec![gid_dl]) }),
+        ..Default::default()
+    });
+    selftest_pdu!(results, d_location_update_proceeding::DLocationUpdateProceeding, d_location_update_proceeding::DLocationUpdateProceeding {
+        ssi: 1234, address_extension: 0x204_0F1, proprietary: None,
+    });
+
+    selftest_pdu!(results, d_change_of_energy_saving_mode_request::DChangeOfEnergySavingModeRequest, d_change_of_energy_saving_mode_request::DChangeOfEnergySavingModeRequest {
+        energy_saving_information: EnergySavingInformation { energy_saving_mode: EnergySavingMode::Eg7, frame_number: Some(1), multiframe_number: Some(60) },
+        proprietary: t3(15, 9, 0x1AB),
+    });
+    selftest_pdu!(results, d_change_of_energy_saving_mode_request::DChangeOfEnergySavingModeRequest, d_change_of_energy_saving_mode_request::DChangeOfEnergySavingModeRequest {
+        energy_saving_information: EnergySavingInformation { energy_saving_mode: EnergySavingMode::StayAlive, frame_number: None, multiframe_number: None },
+        proprietary: None,
+    });
+
+    selftest_pdu!(results, d_location_update_reject::DLocationUpdateReject, d_location_update_reject::DLocationUpdateReject {
+        location_update_type: 3, reject_cause: 3, cipher_control: true, ciphering_parameters: Some(0x155),
+        address_extension: Some(0x204_0F1), ..Default::default()
+    });
+
+    selftest_pdu!(results, d_disable::DDisable, d_disable::DDisable {
+        intent_confirm: true, disabling_type: false, subscription_disable: true,
+        address_extension: Some(0x204_0F1), ssi: Some(2041384), authentication_downlink: t3(10, 16, 0xBEEF), ..Default::default()
+    });
+    selftest_pdu!(results, d_enable::DEnable, d_enable::DEnable {
+        intent_confirm: false, equipment_enable: true, tetra_equipment_identity: Some(0x123_4567_89AB_CDEF), ..Default::default()
+    });
+
+    selftest_pdu!(results, d_otar_cmg::DOtarCmg, d_otar_cmg::DOtarCmg {
+        otar_sub_type: OtarSubTypeDl::SckProvide, key_association: KeyAssociation::Sck { sck_number: 5, key_version_number: 2 },
+        sealed_key: Some(SealedKey(0xAB_CDEF)), proprietary: t3(15, 9, 0x1AB),
+    });
+
+    // Not covered, as parsing is not implemented yet:
+    // DLocationUpdateCommand, DMmStatus, MmPduFunctionNotSupported
+}
+
+/// Round-trips the uplink MM PDUs
+pub(crate) fn selftest_mm_uplink(results: &mut Vec<SelftestResult>) {
+    let gid_ul = GroupIdentityUplink {
+        class_of_usage: Some(4),
+        group_identity_detachment_uplink: None,
+        gssi: Some(26),
+        address_extension: None,
+        vgssi: None,
+    };
+
+    selftest_pdu!(results, u_attach_detach_group_identity::UAttachDetachGroupIdentity, u_attach_detach_group_identity::UAttachDetachGroupIdentity {
+        group_identity_report: false, group_identity_attach_detach_mode: true,
+        group_identity_uplink: Some(vec![gid_ul.clone()]), ..Default::default()
+    });
+    selftest_pdu!(results, u_attach_detach_group_identity_acknowledgement::UAttachDetachGroupIdentityAcknowledgement, u_attach_detach_group_identity_acknowledgement::UAttachDetachGroupIdentityAcknowledgement {
+        group_identity_acknowledgement_type: true, group_identity_uplink: Some(vec![gid_ul.clone()]), ..Default::default()
+    });
+    selftest_pdu!(results, u_itsi_detach::UItsiDetach, u_itsi_detach::UItsiDetach {
+        address_extension: Some(0x123456), proprietary: None,
+    });
+    selftest_pdu!(results, u_location_update_demand::ULocationUpdateDemand, u_location_update_demand::ULocationUpdateDemand {
+        location_update_type: LocationUpdateType::ItsiAttach, request_to_append_la: false, cipher_control: false,
+        ciphering_parameters: None, class_of_ms: Some(0x123456), energy_saving_mode: Some(EnergySavingMode::StayAlive),
+        la_information: None, ssi: Some(2041384), address_extension: None,
+        group_identity_location_demand: Some(GroupIdentityLocationDemand { group_identity_attach_detach_mode: 0, group_identity_uplink: Some(vec![gid_ul]) }),
+        group_report_response: None, authentication_uplink: None, extended_capabilities: None, proprietary: None,
+    });
+    selftest_pdu!(results, u_mm_status::UMmStatus, u_mm_status::UMmStatus {
+        status_uplink: StatusUplink::ChangeOfEnergySavingModeRequest,
+        status_uplink_dependent_information: Some(2),
+        status_uplink_dependent_information_len: Some(3),
+    });
+    selftest_pdu!(results, u_disable_status::UDisableStatus, u_disable_status::UDisableStatus {
+        equipment_status: DisableStatus::Enabled, subscription_status: DisableStatus::TemporarilyDisabled,
+        enable_disable_result: 0, authentication_uplink: t3(9, 32, 0x1234_5678), proprietary: None,
+    });
+    selftest_pdu!(results, u_otar::UOtar, u_otar::UOtar {
+        otar_sub_type: OtarSubTypeUl::SckDemand, key_association: KeyAssociation::Sck { sck_number: 5, key_version_number: 2 }, proprietary: None,
+    });
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_selftest_passes() {
+        let results = run_selftest();
+        assert!(!results.is_empty());
+        for r in &results {
+            assert_eq!(r.result, Ok(()), "{} failed self-test", r.pdu);
+        }
+    }
+}
@@ -0,0 +1,52 @@
+//! Test helpers for PDU (de)serialization
+
+/// Like `check_pdu_roundtrip`, but panics if any check fails.
+/// Usage: `assert_pdu_roundtrip!(DAlert, pdu)`
+#[macro_export]
+macro_rules! assert_pdu_roundtrip {
+    ($pdu_type:ty, $pdu:expr) => {{
+        if let Err(e) = $crate::check_pdu_roundtrip!($pdu_type, $pdu) {
+            panic!("{}", e);
+        }
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use tetra_core::debug;
+
+    use crate::selftest::{self, SelftestResult};
+
+    fn assert_all_pass(fixtures: fn(&mut Vec<SelftestResult>)) {
+        debug::setup_logging_verbose();
+
+        let mut results = Vec::new();
+        fixtures(&mut results);
+        assert!(!results.is_empty());
+        for r in results {
+            if let Err(e) = r.result {
+                panic!("{}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_cmce_downlink() {
+        assert_all_pass(selftest::selftest_cmce_downlink);
+    }
+
+    #[test]
+    fn test_roundtrip_cmce_uplink() {
+        assert_all_pass(selftest::selftest_cmce_uplink);
+    }
+
+    #[test]
+    fn test_roundtrip_mm_downlink() {
+        assert_all_pass(selftest::selftest_mm_downlink);
+    }
+
+    #[test]
+    fn test_roundtrip_mm_uplink() {
+        assert_all_pass(selftest::selftest_mm_uplink);
+    }
+}
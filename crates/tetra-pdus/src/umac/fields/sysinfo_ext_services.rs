@@ -119,6 +119,53 @@ impl SysinfoExtendedServices {
 }
 
 
+/// Clause 21.4.4.1 Extended services broadcast section 1, carried in section_data when section is 0
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ExtServicesSection1 {
+    // 1
+    pub data_priority_supported: bool,
+    // 1
+    pub ext_advanced_links_supported: bool,
+    // 1
+    pub qos_negotiation_supported: bool,
+    // 1
+    pub d8psk_service: bool,
+    // 1
+    pub section2_sent: bool,
+    // 1
+    pub section3_sent: bool,
+    // 1
+    pub section4_sent: bool,
+}
+
+impl ExtServicesSection1 {
+    pub fn from_bits(section_data: u8) -> Self {
+        let bit = |n: u8| (section_data >> (6 - n)) & 1 != 0;
+        Self {
+            data_priority_supported: bit(0),
+            ext_advanced_links_supported: bit(1),
+            qos_negotiation_supported: bit(2),
+            d8psk_service: bit(3),
+            section2_sent: bit(4),
+            section3_sent: bit(5),
+            section4_sent: bit(6),
+        }
+    }
+
+    pub fn to_bits(&self) -> u8 {
+        [
+            self.data_priority_supported,
+            self.ext_advanced_links_supported,
+            self.qos_negotiation_supported,
+            self.d8psk_service,
+            self.section2_sent,
+            self.section3_sent,
+            self.section4_sent,
+        ].iter().fold(0, |acc, &b| (acc << 1) | b as u8)
+    }
+}
+
+
 impl fmt::Display for SysinfoExtendedServices {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         // preserve the original assertion
@@ -1,6 +1,7 @@
 use tetra_core::Direction;
 
 use crate::control::enums::circuit_mode_type::CircuitModeType;
+use crate::lcmc::CallId;
 
 
 #[derive(Debug, Clone)]
@@ -10,6 +11,12 @@ pub struct Circuit {
     
     /// Timeslot in which this circuit exists
     pub ts: u8,
+
+    /// Call ID as allocated by CMCE
+    pub call_id: CallId,
+
+    /// Individual or group SSI the call is addressed to
+    pub ssi: u32,
    
     /// Usage number, between 4 and 63
     pub usage: u8,
@@ -32,11 +39,11 @@ pub struct Circuit {
 pub enum CallControl{
     /// Signals to set up a circuit
     /// Created by CMCE, sent to Umac
-    /// Umac forwards to Lmac
+    /// Umac forwards to Lmac, and to the U-plane
     Open(Circuit),
     /// Signals to release a circuit
     /// Created by CMCE, sent to Umac
-    /// Umac forwards to Lmac
+    /// Umac forwards to Lmac, and to the U-plane once the timeslot is released
     /// Contains (Direction, timeslot) of associated circuit
     Close(Direction, u8), 
 }
@@ -1,6 +1,7 @@
 /// 14.8.17a Circuit mode type
 /// Bits: 3
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum CircuitModeType {
     /// Tch/S
@@ -1,6 +1,7 @@
 /// 14.8.17c Communication type
 /// Bits: 2
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum CommunicationType {
     /// Point-to-point
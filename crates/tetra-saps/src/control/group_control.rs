@@ -0,0 +1,12 @@
+
+/// Group attachment changes of registered MSs
+/// Created by MM, sent to CMCE, which addresses group calls to the attached MSs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GroupControl {
+    /// ISSI attached to GSSI
+    Attach { issi: u32, gssi: u32 },
+    /// ISSI detached from GSSI
+    Detach { issi: u32, gssi: u32 },
+    /// ISSI detached from all of its groups, e.g. as it deregistered
+    DetachAll { issi: u32 },
+}
@@ -1,2 +1,4 @@
 pub mod enums;
-pub mod call_control;
\ No newline at end of file
+pub mod call_control;
+pub mod group_control;
+pub mod ms_activity;
\ No newline at end of file
@@ -0,0 +1,8 @@
+
+/// Uplink activity of an MS
+/// Created by MLE for each PDU not addressed to MM, sent to MM, which deregisters MSs not heard from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MsActivity {
+    /// SSI the PDU was received from
+    pub ssi: u32,
+}
@@ -1,4 +1,4 @@
-use tetra_core::{BitBuffer, EndpointId, LinkId, MleHandle, TetraAddress, Todo};
+use tetra_core::{BitBuffer, EndpointId, LinkId, MleHandle, RxQuality, TetraAddress, Todo};
 
 use crate::{control::enums::circuit_mode_type::CircuitModeType, lcmc::{fields::chan_alloc_req::CmceChanAllocReq}};
 
@@ -205,4 +205,6 @@ pub struct LcmcMleUnitdataInd {
     pub received_tetra_address: TetraAddress, // ITSI/GTSI
     pub chan_change_resp_req: bool,
     pub chan_change_handle: Option<Todo>,
+    /// Not in the spec: quality of the burst completing the CMCE PDU, logged by CMCE along with the calling MS
+    pub rx_quality: Option<RxQuality>,
 }
@@ -1,6 +1,6 @@
 // Clause 17.3.2 Service primitives for the LMM-SAP
 #![allow(unused)]
-use tetra_core::{BitBuffer, MleHandle, TetraAddress, Todo};
+use tetra_core::{BitBuffer, MleHandle, RxQuality, TetraAddress, Todo};
 
 
 /// This shall be used as a request to initiate the selection of a cell for communications. The
@@ -152,6 +152,8 @@ pub struct LmmMleUnitdataInd {
     pub handle: MleHandle,
     pub received_address: TetraAddress,
     // pub received_address_type: Todo,
+    /// Not in the spec: quality of the burst completing the MM PDU, logged by MM along with the sending MS
+    pub rx_quality: Option<RxQuality>,
 }
 
 #[derive(Debug)]
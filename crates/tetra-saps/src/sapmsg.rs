@@ -1,10 +1,13 @@
 use core::fmt::Display;
 
+use tetra_core::PhyBlockNum;
 use tetra_core::Sap;
 use tetra_core::TdmaTime;
 use tetra_core::tetra_entities::TetraEntity;
 
 use crate::control::call_control::CallControl;
+use crate::control::group_control::GroupControl;
+use crate::control::ms_activity::MsActivity;
 use crate::tmd::TmdCircuitDataInd;
 use crate::tmd::TmdCircuitDataReq;
 use crate::tnmm::TnmmTestDemand;
@@ -79,6 +82,12 @@ pub enum SapMsgInner {
     // CMCE -> UMAC control
     CmceCallControl(CallControl),
 
+    // MM -> CMCE control
+    MmGroupControl(GroupControl),
+
+    // MLE -> MM control
+    MleMsActivity(MsActivity),
+
     // LTPD-SAP (MLE-LTPD)
     LtpdMleUnitdataInd(LtpdMleUnitdataInd),
 
@@ -88,6 +97,43 @@ pub enum SapMsgInner {
     TnmmTestResponse(TnmmTestResponse),
 }
 
+impl SapMsgInner {
+    /// Name of the primitive, e.g. for counting primitives by type
+    pub fn name(&self) -> &'static str {
+        match self {
+            SapMsgInner::TpUnitdataInd(_) => "TpUnitdataInd",
+            SapMsgInner::TpUnitdataReq(_) => "TpUnitdataReq",
+            SapMsgInner::TmvUnitdataReq(_) => "TmvUnitdataReq",
+            SapMsgInner::TmvUnitdataInd(_) => "TmvUnitdataInd",
+            SapMsgInner::TmvConfigureReq(_) => "TmvConfigureReq",
+            SapMsgInner::TmvConfigureConf(_) => "TmvConfigureConf",
+            SapMsgInner::TmaUnitdataInd(_) => "TmaUnitdataInd",
+            SapMsgInner::TmaUnitdataReq(_) => "TmaUnitdataReq",
+            SapMsgInner::TmaReportInd(_) => "TmaReportInd",
+            SapMsgInner::TlmbSyncInd(_) => "TlmbSyncInd",
+            SapMsgInner::TlmbSysinfoInd(_) => "TlmbSysinfoInd",
+            SapMsgInner::TlmcConfigureReq(_) => "TlmcConfigureReq",
+            SapMsgInner::TmdCircuitDataReq(_) => "TmdCircuitDataReq",
+            SapMsgInner::TmdCircuitDataInd(_) => "TmdCircuitDataInd",
+            SapMsgInner::TlaTlDataIndBl(_) => "TlaTlDataIndBl",
+            SapMsgInner::TlaTlDataReqBl(_) => "TlaTlDataReqBl",
+            SapMsgInner::TlaTlReportInd(_) => "TlaTlReportInd",
+            SapMsgInner::TlaTlUnitdataIndBl(_) => "TlaTlUnitdataIndBl",
+            SapMsgInner::TlaTlUnitdataReqBl(_) => "TlaTlUnitdataReqBl",
+            SapMsgInner::LmmMleUnitdataInd(_) => "LmmMleUnitdataInd",
+            SapMsgInner::LmmMleUnitdataReq(_) => "LmmMleUnitdataReq",
+            SapMsgInner::LcmcMleUnitdataInd(_) => "LcmcMleUnitdataInd",
+            SapMsgInner::LcmcMleUnitdataReq(_) => "LcmcMleUnitdataReq",
+            SapMsgInner::CmceCallControl(_) => "CmceCallControl",
+            SapMsgInner::MmGroupControl(_) => "MmGroupControl",
+            SapMsgInner::MleMsActivity(_) => "MleMsActivity",
+            SapMsgInner::LtpdMleUnitdataInd(_) => "LtpdMleUnitdataInd",
+            SapMsgInner::TnmmTestDemand(_) => "TnmmTestDemand",
+            SapMsgInner::TnmmTestResponse(_) => "TnmmTestResponse",
+        }
+    }
+}
+
 impl Display for SapMsgInner {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
@@ -168,6 +214,37 @@ impl SapMsg {
     // pub fn get_subprim(&self) -> &SapSubPrim {
     //     &self.subprim
     // }
-    
+
+    /// Returns the subslot, 1 or 2, of a received half-slot block, for primitives carrying a block number
+    pub fn get_subslot(&self) -> Option<u8> {
+        let block_num = match &self.msg {
+            SapMsgInner::TpUnitdataInd(prim) => prim.block_num,
+            SapMsgInner::TmvUnitdataInd(prim) => prim.block_num,
+            _ => return None,
+        };
+        match block_num {
+            PhyBlockNum::Block1 => Some(1),
+            PhyBlockNum::Block2 => Some(2),
+            PhyBlockNum::Both | PhyBlockNum::Undefined => None,
+        }
+    }
+
+    /// Returns the SSI this message is addressed to or received from, for primitives carrying an address
+    pub fn get_ssi(&self) -> Option<u32> {
+        match &self.msg {
+            SapMsgInner::TmaUnitdataInd(prim) => Some(prim.main_address.ssi),
+            SapMsgInner::TmaUnitdataReq(prim) => Some(prim.main_address.ssi),
+            SapMsgInner::TlaTlDataIndBl(prim) => Some(prim.main_address.ssi),
+            SapMsgInner::TlaTlDataReqBl(prim) => Some(prim.main_address.ssi),
+            SapMsgInner::TlaTlUnitdataIndBl(prim) => Some(prim.main_address.ssi),
+            SapMsgInner::TlaTlUnitdataReqBl(prim) => Some(prim.main_address.ssi),
+            SapMsgInner::LmmMleUnitdataInd(prim) => Some(prim.received_address.ssi),
+            SapMsgInner::LmmMleUnitdataReq(prim) => Some(prim.address.ssi),
+            SapMsgInner::LcmcMleUnitdataInd(prim) => Some(prim.received_tetra_address.ssi),
+            SapMsgInner::LcmcMleUnitdataReq(prim) => Some(prim.main_address.ssi),
+            SapMsgInner::LtpdMleUnitdataInd(prim) => Some(prim.received_tetra_address.ssi),
+            _ => None,
+        }
+    }
     
 }
\ No newline at end of file
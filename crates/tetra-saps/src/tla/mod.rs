@@ -1,5 +1,5 @@
 #![allow(unused)]
-use tetra_core::{BitBuffer, EndpointId, LinkId, TetraAddress, Todo};
+use tetra_core::{BitBuffer, EndpointId, LinkId, RxQuality, TetraAddress, Todo};
 
 use crate::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
 
@@ -140,6 +140,8 @@ pub struct TlaTlDataIndBl {
     pub chan_change_handle: Option<Todo>,
     pub chan_info: Option<Todo>,
     pub req_handle: Todo,
+    /// Not in the spec: quality of the burst completing the BL-DATA or BL-ADATA PDU, as reported by the MAC
+    pub rx_quality: Option<RxQuality>,
 }
 
 /// Clause 20.3.5.1.4 
@@ -291,6 +293,8 @@ pub struct TlaTlUnitdataIndBl {
     pub chan_change_handle: Option<Todo>,
     pub chan_info: Option<Todo>,
     pub report: Option<Todo>,
+    /// Not in the spec: quality of the burst completing the BL-UDATA PDU, as reported by the MAC
+    pub rx_quality: Option<RxQuality>,
 }
 
 /// Clause 20.3.5.1.9, optional
@@ -1,4 +1,4 @@
-use tetra_core::{BitBuffer, EndpointId, TetraAddress, Todo};
+use tetra_core::{BitBuffer, EndpointId, RxQuality, TetraAddress, Todo};
 
 use crate::lcmc::fields::chan_alloc_req::CmceChanAllocReq;
 
@@ -91,4 +91,7 @@ pub struct TmaUnitdataInd {
     pub chan_change_response_req: bool,
     pub chan_change_handle: Option<Todo>,
     pub chan_info: Option<Todo>,
+    /// Not in the spec: quality of the burst carrying the MAC PDU that completed the TM-SDU,
+    /// i.e. that of the last fragment for a fragmented SDU
+    pub rx_quality: Option<RxQuality>,
 }
@@ -1,17 +1,17 @@
+use crate::lcmc::CallId;
 
-
-/// Pass TMD circuit data to UMAC for TX scheduling
+/// Pass TMD circuit data to UMAC for TX scheduling.
+/// UMAC looks up the timeslot assigned to the call.
 #[derive(Debug)]
 pub struct TmdCircuitDataReq {
-    // call_id: CallId,
-    pub ts: u8,
+    pub call_id: CallId,
     pub data: Vec<u8>,
 }
 
 /// Rx'ed traffic
 #[derive(Debug)]
 pub struct TmdCircuitDataInd {
-    // call_id: CallId,
+    pub call_id: CallId,
     pub ts: u8,
     pub data: Vec<u8>,
-}
\ No newline at end of file
+}
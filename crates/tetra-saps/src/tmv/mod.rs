@@ -1,6 +1,6 @@
 pub mod enums;
 
-use tetra_core::{BitBuffer, PhyBlockNum, PhysicalChannel, TdmaTime, Todo};
+use tetra_core::{BitBuffer, PhyBlockNum, PhysicalChannel, RxQuality, TdmaTime, Todo};
 
 use crate::tmv::enums::logical_chans::LogicalChannel;
 
@@ -52,6 +52,10 @@ pub struct TmvUnitdataInd {
     /// If no CRC is present on this message type (for example, for AACH), crc_pass is set to True
     pub crc_pass: bool,
     pub scrambling_code: u32,
+
+    /// Not in the spec: quality of the burst this block was decoded from, passed on from TP-UNITDATA.
+    /// The BS UMAC bases its uplink power control on it.
+    pub rx_quality: Option<RxQuality>,
 }
 
 
@@ -1,4 +1,4 @@
-use tetra_core::{BitBuffer, BurstType, PhyBlockNum, PhyBlockType, TrainingSequence};
+use tetra_core::{BitBuffer, BurstType, PhyBlockNum, PhyBlockType, RxQuality, TrainingSequence};
 
 
 #[derive(Debug)]
@@ -9,6 +9,9 @@ pub struct TpUnitdataInd{
     /// Undefined for BBK. For all others: [ Block1 | Block2 | Both ]
     pub block_num: PhyBlockNum, 
     pub block: BitBuffer,
+    /// Not in the spec: RSSI, BER estimate and arrival delay of the burst, as measured by the PHY.
+    /// None for PHYs that don't measure them.
+    pub rx_quality: Option<RxQuality>,
 }
 
 #[derive(Debug)]